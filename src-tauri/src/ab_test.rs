@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::services::ProviderMetrics;
+
+/// 判定胜出前每个供应商至少需要的请求数
+const MIN_SAMPLES: u64 = 20;
+
+/// 错误率相差超过该值时按错误率判定，否则按中位延迟判定
+const ERROR_RATE_MARGIN: f64 = 0.02;
+
+/// A/B 测试的分流方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AbTestMode {
+    /// 本地代理按请求交替转发给两个供应商
+    Proxy,
+    /// 按固定时长轮流切换当前供应商（指标仍来自经过本地代理的请求）
+    Rotation,
+}
+
+/// 两个供应商之间的 A/B 测试
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbTest {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub app: AppType,
+    pub provider_a: String,
+    pub provider_b: String,
+    pub mode: AbTestMode,
+    /// 轮换模式下每个供应商连续使用的小时数
+    #[serde(default = "default_rotation_hours")]
+    pub rotation_hours: u32,
+    /// 开始时间（Unix 毫秒）
+    #[serde(default)]
+    pub started_at: i64,
+    /// 结束时间（Unix 毫秒），进行中为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<i64>,
+}
+
+fn default_rotation_hours() -> u32 {
+    24
+}
+
+impl AbTest {
+    pub fn is_active(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// 轮换模式下 `now`（Unix 毫秒）时应使用的供应商
+    pub fn rotation_provider(&self, now: i64) -> &str {
+        let slot = i64::from(self.rotation_hours.max(1)) * 3600 * 1000;
+        if (now - self.started_at).max(0) / slot % 2 == 0 {
+            &self.provider_a
+        } else {
+            &self.provider_b
+        }
+    }
+
+    /// 代理模式下第 `seq` 个请求应转发的供应商
+    pub fn proxy_provider(&self, seq: u64) -> &str {
+        match seq % 2 {
+            0 => &self.provider_a,
+            _ => &self.provider_b,
+        }
+    }
+
+    /// 找到指定应用进行中的测试（同一应用同时只允许一个）
+    pub fn find_active<'a>(tests: &'a [AbTest], app_type: &AppType) -> Option<&'a AbTest> {
+        tests
+            .iter()
+            .find(|test| test.is_active() && test.app == *app_type)
+    }
+}
+
+/// 比较两个供应商的指标，样本不足或差异不明显时返回 `None`
+pub fn pick_winner<'a>(a: &'a ProviderMetrics, b: &'a ProviderMetrics) -> Option<&'a str> {
+    if a.requests < MIN_SAMPLES || b.requests < MIN_SAMPLES {
+        return None;
+    }
+    if (a.error_rate - b.error_rate).abs() > ERROR_RATE_MARGIN {
+        let winner = if a.error_rate < b.error_rate { a } else { b };
+        return Some(&winner.provider_id);
+    }
+    match (a.p50_ms, b.p50_ms) {
+        (Some(la), Some(lb)) if la < lb => Some(&a.provider_id),
+        (Some(la), Some(lb)) if lb < la => Some(&b.provider_id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(id: &str, requests: u64, errors: u64, p50: u64) -> ProviderMetrics {
+        ProviderMetrics {
+            app_type: "claude".into(),
+            provider_id: id.into(),
+            requests,
+            errors,
+            error_rate: errors as f64 / requests as f64,
+            p50_ms: Some(p50),
+            p90_ms: None,
+            p99_ms: None,
+            avg_ms: None,
+        }
+    }
+
+    #[test]
+    fn alternates_providers_and_picks_winner() {
+        let test = AbTest {
+            id: "ab-1".into(),
+            name: String::new(),
+            app: AppType::Claude,
+            provider_a: "a".into(),
+            provider_b: "b".into(),
+            mode: AbTestMode::Rotation,
+            rotation_hours: 24,
+            started_at: 0,
+            ended_at: None,
+        };
+        let hour = 3600 * 1000;
+        assert_eq!(test.rotation_provider(23 * hour), "a");
+        assert_eq!(test.rotation_provider(24 * hour), "b");
+        assert_eq!(test.rotation_provider(48 * hour), "a");
+        assert_eq!((test.proxy_provider(0), test.proxy_provider(1)), ("a", "b"));
+
+        // 错误率差异明显时错误率低者胜出，即使延迟更高
+        assert_eq!(
+            pick_winner(&metrics("a", 100, 10, 300), &metrics("b", 100, 1, 900)),
+            Some("b")
+        );
+        // 错误率接近时按中位延迟
+        assert_eq!(
+            pick_winner(&metrics("a", 100, 1, 300), &metrics("b", 100, 2, 900)),
+            Some("a")
+        );
+        assert_eq!(
+            pick_winner(&metrics("a", 5, 0, 300), &metrics("b", 100, 50, 900)),
+            None
+        );
+    }
+}
@@ -0,0 +1,392 @@
+//! 声明式应用适配器：用清单文件描述简单 CLI 的配置文件与字段映射
+//!
+//! 清单放在 `~/.cc-switch/adapters/<id>.json`，描述配置文件位置与格式（env / json / toml）、
+//! 供应商字段到文件键的映射（json / toml 用 `.` 分隔嵌套路径）以及校验规则。
+//! 适配器应用的供应商配置为扁平对象 `{ 字段 key: 字符串值 }`，由本模块的通用代码读写，
+//! 写入时只改动清单映射的键，文件中的其他内容保持不变。
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+use crate::gemini_config::{parse_env_file, serialize_env_file};
+
+/// 不能用作适配器 ID 的名称（内置应用与 config.json 顶层字段）
+const RESERVED_IDS: &[&str] = &[
+    "claude", "codex", "gemini", "version", "mcp", "prompts", "skills",
+];
+
+/// 配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdapterFileFormat {
+    Env,
+    Json,
+    Toml,
+}
+
+/// 适配器管理的配置文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterFile {
+    /// 字段通过该 ID 引用文件
+    pub id: String,
+    /// 以 `~/` 开头、相对用户主目录的路径
+    pub path: String,
+    pub format: AdapterFileFormat,
+}
+
+/// 供应商字段到配置文件键的映射及校验规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterField {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// 所在文件的 ID
+    pub file: String,
+    /// env 为变量名，json / toml 为 `.` 分隔的键路径
+    pub path: String,
+    #[serde(default)]
+    pub required: bool,
+    /// 界面中按密钥处理（遮罩显示）
+    #[serde(default)]
+    pub secret: bool,
+    /// 值需匹配的正则表达式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+/// 适配器清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppAdapter {
+    /// 小写字母、数字与 `-`，同时作为 config.json 中的应用键
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub files: Vec<AdapterFile>,
+    pub fields: Vec<AdapterField>,
+}
+
+impl AppAdapter {
+    /// 校验清单结构（ID、文件路径、字段引用与正则）
+    pub fn validate(&self) -> Result<(), AppError> {
+        let valid_id = !self.id.is_empty()
+            && self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid_id || RESERVED_IDS.contains(&self.id.as_str()) {
+            return Err(invalid_manifest(&self.id, "id"));
+        }
+        if self.fields.is_empty() {
+            return Err(invalid_manifest(&self.id, "fields"));
+        }
+        for file in &self.files {
+            relative_home_path(&file.path)
+                .ok_or_else(|| invalid_manifest(&self.id, &format!("files.{}.path", file.id)))?;
+        }
+        for field in &self.fields {
+            if !self.files.iter().any(|file| file.id == field.file) || field.path.is_empty() {
+                return Err(invalid_manifest(&self.id, &format!("fields.{}", field.key)));
+            }
+            if let Some(pattern) = &field.pattern {
+                regex::Regex::new(pattern).map_err(|_| {
+                    invalid_manifest(&self.id, &format!("fields.{}.pattern", field.key))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 按字段规则校验供应商配置
+    pub fn validate_values(&self, values: &Value) -> Result<(), AppError> {
+        for field in &self.fields {
+            let value = values.get(&field.key).and_then(Value::as_str).unwrap_or("");
+            if value.is_empty() {
+                if field.required {
+                    return Err(invalid_value(field, "必填", "is required"));
+                }
+                continue;
+            }
+            if let Some(pattern) = &field.pattern {
+                let matched = regex::Regex::new(pattern)
+                    .map(|re| re.is_match(value))
+                    .unwrap_or(false);
+                if !matched {
+                    return Err(invalid_value(field, "格式不正确", "has an invalid format"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 配置文件的绝对路径
+    pub fn file_path(&self, file: &AdapterFile) -> Result<PathBuf, AppError> {
+        let relative = relative_home_path(&file.path)
+            .ok_or_else(|| invalid_manifest(&self.id, &format!("files.{}.path", file.id)))?;
+        let home = dirs::home_dir().ok_or_else(|| {
+            AppError::localized(
+                "adapter.home_missing",
+                "无法确定用户主目录",
+                "Cannot determine the user home directory",
+            )
+        })?;
+        Ok(home.join(relative))
+    }
+
+    /// 从各配置文件读取字段值（缺失的文件或键不出现在结果中）
+    pub fn read_values(&self, contents: &HashMap<String, String>) -> Result<Value, AppError> {
+        let mut values = Map::new();
+        for field in &self.fields {
+            let Some(content) = contents.get(&field.file) else {
+                continue;
+            };
+            let format = self.format_of(&field.file);
+            if let Some(value) = read_key(format, content, &field.path).map_err(|e| {
+                AppError::localized(
+                    "adapter.parse_failed",
+                    format!("解析 {} 的配置文件 {} 失败: {e}", self.name, field.file),
+                    format!(
+                        "Failed to parse {} config file {}: {e}",
+                        self.name, field.file
+                    ),
+                )
+            })? {
+                values.insert(field.key.clone(), Value::String(value));
+            }
+        }
+        Ok(Value::Object(values))
+    }
+
+    /// 将字段值写入各配置文件的现有内容，返回需要写入的文件（文件 ID -> 新内容）
+    ///
+    /// 值为空的字段会从文件中移除对应的键
+    pub fn render(
+        &self,
+        values: &Value,
+        contents: &HashMap<String, String>,
+    ) -> Result<Vec<(String, String)>, AppError> {
+        let mut rendered = Vec::new();
+        for file in &self.files {
+            let fields: Vec<&AdapterField> =
+                self.fields.iter().filter(|f| f.file == file.id).collect();
+            if fields.is_empty() {
+                continue;
+            }
+            let existing = contents.get(&file.id).map(String::as_str).unwrap_or("");
+            let updates: Vec<(&str, Option<&str>)> = fields
+                .iter()
+                .map(|field| {
+                    let value = values
+                        .get(&field.key)
+                        .and_then(Value::as_str)
+                        .filter(|v| !v.is_empty());
+                    (field.path.as_str(), value)
+                })
+                .collect();
+            let content = write_keys(file.format, existing, &updates).map_err(|e| {
+                AppError::localized(
+                    "adapter.parse_failed",
+                    format!("解析 {} 的配置文件 {} 失败: {e}", self.name, file.id),
+                    format!("Failed to parse {} config file {}: {e}", self.name, file.id),
+                )
+            })?;
+            rendered.push((file.id.clone(), content));
+        }
+        Ok(rendered)
+    }
+
+    fn format_of(&self, file_id: &str) -> AdapterFileFormat {
+        self.files
+            .iter()
+            .find(|file| file.id == file_id)
+            .map(|file| file.format)
+            .unwrap_or(AdapterFileFormat::Env)
+    }
+}
+
+/// `~/a/b` -> `a/b`；拒绝绝对路径与 `..`
+fn relative_home_path(path: &str) -> Option<&Path> {
+    let relative = Path::new(path.strip_prefix("~/")?);
+    let normal = !relative.as_os_str().is_empty()
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    normal.then_some(relative)
+}
+
+fn read_key(
+    format: AdapterFileFormat,
+    content: &str,
+    path: &str,
+) -> Result<Option<String>, String> {
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    let scalar = |value: Option<&Value>| {
+        value.and_then(|v| match v {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(_) | Value::Bool(_) => Some(v.to_string()),
+            _ => None,
+        })
+    };
+    match format {
+        AdapterFileFormat::Env => Ok(parse_env_file(content).remove(path)),
+        AdapterFileFormat::Json => {
+            let root: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+            Ok(scalar(path.split('.').try_fold(&root, |v, k| v.get(k))))
+        }
+        AdapterFileFormat::Toml => {
+            let table: toml::Table = toml::from_str(content).map_err(|e| e.to_string())?;
+            let root = serde_json::to_value(table).map_err(|e| e.to_string())?;
+            Ok(scalar(path.split('.').try_fold(&root, |v, k| v.get(k))))
+        }
+    }
+}
+
+fn write_keys(
+    format: AdapterFileFormat,
+    existing: &str,
+    updates: &[(&str, Option<&str>)],
+) -> Result<String, String> {
+    match format {
+        AdapterFileFormat::Env => {
+            let mut env = parse_env_file(existing);
+            for (key, value) in updates {
+                match value {
+                    Some(value) => env.insert(key.to_string(), value.to_string()),
+                    None => env.remove(*key),
+                };
+            }
+            Ok(serialize_env_file(&env))
+        }
+        AdapterFileFormat::Json => {
+            let mut root: Value = if existing.trim().is_empty() {
+                Value::Object(Map::new())
+            } else {
+                serde_json::from_str(existing).map_err(|e| e.to_string())?
+            };
+            for (path, value) in updates {
+                let mut keys: Vec<&str> = path.split('.').collect();
+                let last = keys.pop().unwrap_or_default();
+                let mut node = &mut root;
+                for key in keys {
+                    let object = node.as_object_mut().ok_or("not an object")?;
+                    node = object
+                        .entry(key.to_string())
+                        .or_insert_with(|| Value::Object(Map::new()));
+                }
+                let object = node.as_object_mut().ok_or("not an object")?;
+                match value {
+                    Some(value) => {
+                        object.insert(last.to_string(), Value::String(value.to_string()))
+                    }
+                    None => object.remove(last),
+                };
+            }
+            serde_json::to_string_pretty(&root).map_err(|e| e.to_string())
+        }
+        AdapterFileFormat::Toml => {
+            let mut doc: toml_edit::DocumentMut = existing.parse().map_err(|e| format!("{e}"))?;
+            for (path, value) in updates {
+                let mut keys: Vec<&str> = path.split('.').collect();
+                let last = keys.pop().unwrap_or_default();
+                let mut table = doc.as_table_mut();
+                for key in keys {
+                    let entry = table
+                        .entry(key)
+                        .or_insert_with(|| toml_edit::Item::Table(toml_edit::Table::new()));
+                    table = entry.as_table_mut().ok_or("not a table")?;
+                }
+                match value {
+                    Some(value) => {
+                        table.insert(last, toml_edit::value(*value));
+                    }
+                    None => {
+                        table.remove(last);
+                    }
+                }
+            }
+            Ok(doc.to_string())
+        }
+    }
+}
+
+fn invalid_manifest(id: &str, field: &str) -> AppError {
+    AppError::localized(
+        "adapter.invalid_manifest",
+        format!("适配器清单 {id} 的 {field} 无效"),
+        format!("Adapter manifest {id} has an invalid {field}"),
+    )
+}
+
+fn invalid_value(field: &AdapterField, zh: &str, en: &str) -> AppError {
+    let name = field.label.as_deref().unwrap_or(&field.key);
+    AppError::localized(
+        "adapter.invalid_value",
+        format!("{name} {zh}"),
+        format!("{name} {en}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn adapter() -> AppAdapter {
+        serde_json::from_value(json!({
+            "id": "demo",
+            "name": "Demo CLI",
+            "files": [
+                { "id": "env", "path": "~/.demo/.env", "format": "env" },
+                { "id": "config", "path": "~/.demo/config.toml", "format": "toml" }
+            ],
+            "fields": [
+                { "key": "apiKey", "file": "env", "path": "DEMO_API_KEY", "required": true, "secret": true },
+                { "key": "baseUrl", "file": "config", "path": "api.base_url", "pattern": "^https?://" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_and_reads_mapped_keys() {
+        let adapter = adapter();
+        adapter.validate().unwrap();
+
+        let contents = HashMap::from([
+            ("env".to_string(), "KEEP=1\nDEMO_API_KEY=old".to_string()),
+            (
+                "config".to_string(),
+                "# comment\ntheme = \"dark\"\n".to_string(),
+            ),
+        ]);
+        let values = json!({ "apiKey": "sk-new", "baseUrl": "https://api.demo.dev" });
+        adapter.validate_values(&values).unwrap();
+        let rendered: HashMap<String, String> = adapter
+            .render(&values, &contents)
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(rendered["env"], "DEMO_API_KEY=sk-new\nKEEP=1");
+        assert!(rendered["config"].contains("theme = \"dark\""));
+        assert!(rendered["config"].contains("[api]\nbase_url = \"https://api.demo.dev\""));
+
+        assert_eq!(adapter.read_values(&rendered).unwrap(), values);
+        assert!(adapter
+            .validate_values(&json!({ "apiKey": "k", "baseUrl": "ftp://x" }))
+            .is_err());
+        assert!(adapter.validate_values(&json!({})).is_err());
+
+        let mut escaping = adapter.clone();
+        escaping.files[0].path = "~/../etc/demo".to_string();
+        assert!(escaping.validate().is_err());
+    }
+}
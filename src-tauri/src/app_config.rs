@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
 
-use crate::services::skill::SkillStore;
+use crate::services::skill::{SkillRepo, SkillStore};
 
 /// MCP 服务器应用状态（标记应用到哪些客户端）
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -70,6 +70,106 @@ pub struct McpServer {
     pub docs: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    /// 最近一次健康检查结果（由定期检查写入，编辑后清空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<McpHealth>,
+    /// 分应用覆盖（如 Codex 使用不同的 cwd 或 env），同步到对应应用时合并到 `server`
+    #[serde(default, skip_serializing_if = "McpAppOverrides::is_empty")]
+    pub overrides: McpAppOverrides,
+}
+
+impl McpServer {
+    /// 写入指定应用的连接定义：`server` 与该应用的覆盖合并
+    pub fn spec_for(&self, app: &AppType) -> serde_json::Value {
+        let mut spec = self.server.clone();
+        if let Some(patch) = self.overrides.for_app(app) {
+            merge_override(&mut spec, patch);
+        }
+        spec
+    }
+}
+
+/// MCP 服务器的分应用覆盖：对象逐键合并（`env`、`headers` 等只需写出差异），
+/// 值为 null 时删除该字段，其他值直接替换
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpAppOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gemini: Option<serde_json::Value>,
+}
+
+impl McpAppOverrides {
+    pub fn for_app(&self, app: &AppType) -> Option<&serde_json::Value> {
+        match app {
+            AppType::Claude => self.claude.as_ref(),
+            AppType::Codex => self.codex.as_ref(),
+            AppType::Gemini => self.gemini.as_ref(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.claude.is_none() && self.codex.is_none() && self.gemini.is_none()
+    }
+}
+
+fn merge_override(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let (Some(target), Some(patch)) = (target.as_object_mut(), patch.as_object()) else {
+        return;
+    };
+    for (key, value) in patch {
+        match (target.get_mut(key), value) {
+            (_, serde_json::Value::Null) => {
+                target.remove(key);
+            }
+            (Some(current), serde_json::Value::Object(_)) if current.is_object() => {
+                merge_override(current, value);
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// MCP 服务器健康状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpHealthStatus {
+    Healthy,
+    Unhealthy,
+}
+
+/// 健康检查发现的问题类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum McpHealthIssueKind {
+    /// 启动命令不存在
+    MissingCommand,
+    /// 固定的 npm/PyPI 包版本已被撤回或删除
+    YankedPackage,
+    /// 远程 URL 返回 404/410
+    UrlNotFound,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpHealthIssue {
+    pub kind: McpHealthIssueKind,
+    pub detail: String,
+}
+
+/// MCP 服务器健康检查结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpHealth {
+    pub status: McpHealthStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<McpHealthIssue>,
+    /// 检查时间（毫秒时间戳）
+    pub checked_at: i64,
 }
 
 /// MCP 配置：单客户端维度（v3.6.x 及以前，保留用于向后兼容）
@@ -226,12 +326,21 @@ pub struct MultiAppConfig {
     /// Claude Skills 配置
     #[serde(default)]
     pub skills: SkillStore,
+    /// Claude Code 自定义斜杠命令（命令名 -> 命令）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub slash_commands: HashMap<String, crate::slash_command::SlashCommand>,
+    /// 子代理仓库（复用技能仓库结构，`skillsPath` 为子代理所在子目录）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agent_repos: Vec<SkillRepo>,
     /// 通用配置片段（按应用分治）
     #[serde(default)]
     pub common_config_snippets: CommonConfigSnippets,
     /// Claude 通用配置片段（旧字段，用于向后兼容迁移）
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub claude_common_config_snippet: Option<String>,
+    /// 已生效的供应商配置键迁移记录（见 config_migrations 注册表）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub applied_config_migrations: Vec<crate::config_migrations::AppliedMigrationRecord>,
 }
 
 fn default_version() -> u32 {
@@ -251,8 +360,11 @@ impl Default for MultiAppConfig {
             mcp: McpRoot::default(),
             prompts: PromptRoot::default(),
             skills: SkillStore::default(),
+            slash_commands: HashMap::new(),
+            agent_repos: Vec::new(),
             common_config_snippets: CommonConfigSnippets::default(),
             claude_common_config_snippet: None,
+            applied_config_migrations: Vec::new(),
         }
     }
 }
@@ -279,6 +391,8 @@ impl MultiAppConfig {
         // 满足：顶层同时包含 providers(object) + current(string)，且不包含 version/apps/mcp 关键键，即视为 v1
         let value: serde_json::Value =
             serde_json::from_str(&content).map_err(|e| AppError::json(&config_path, e))?;
+        let checksum = crate::config_integrity::checksum_status(content.as_bytes());
+        crate::config_integrity::remember_content(content.as_bytes());
         let is_v1 = value.as_object().is_some_and(|map| {
             let has_providers = map.get("providers").map(|v| v.is_object()).unwrap_or(false);
             let has_current = map.get("current").map(|v| v.is_string()).unwrap_or(false);
@@ -294,6 +408,13 @@ impl MultiAppConfig {
             ));
         }
 
+        crate::config_integrity::check_schema_version(
+            value
+                .get("version")
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u32::try_from(v).ok()),
+        )?;
+
         let has_skills_in_config = value
             .as_object()
             .is_some_and(|map| map.contains_key("skills"));
@@ -355,16 +476,35 @@ impl MultiAppConfig {
             updated = true;
         }
 
+        // 应用供应商配置键迁移（上游客户端的键重命名等）
+        if config.apply_config_migrations() {
+            log::info!("已应用供应商配置键迁移");
+            updated = true;
+        }
+
+        // 文件可正常解析但校验和不一致：视为外部修改，仅记录警告供前端展示
+        if checksum == crate::config_integrity::ChecksumStatus::Mismatch {
+            log::warn!("config.json 校验和与上次写入不一致，可能已被外部修改");
+            crate::init_status::set_integrity_warning(crate::config_integrity::inspect());
+        }
+
         if updated {
             log::info!("配置结构已更新（包括 MCP 迁移或 Prompt 自动导入），保存配置...");
             config.save()?;
+        } else if checksum != crate::config_integrity::ChecksumStatus::Match {
+            crate::config_integrity::record_checksum();
         }
+        crate::config_integrity::remember_state(&config);
 
         Ok(config)
     }
 
     /// 保存配置到文件
     pub fn save(&self) -> Result<(), AppError> {
+        // 配置目录可能被其他机器（同步盘 / NFS）上的 cc-switch 同时写入
+        let _lock = crate::config_lock::acquire()?;
+        // 同步盘可能已带来其他机器写入的版本，不能直接覆盖
+        crate::config_integrity::ensure_not_changed_externally()?;
         let config_path = get_app_config_path();
         // 先备份旧版（若存在）到 ~/.cc-switch/config.json.bak，再写入新内容
         if config_path.exists() {
@@ -375,9 +515,36 @@ impl MultiAppConfig {
         }
 
         write_json_file(&config_path, self)?;
+        crate::config_integrity::record_checksum();
+        crate::config_integrity::remember_state(self);
+        crate::config_history::record(self);
+        crate::services::PermissionService::harden_if_enabled();
         Ok(())
     }
 
+    /// 对所有供应商应用配置键迁移注册表，返回是否有变化
+    pub fn apply_config_migrations(&mut self) -> bool {
+        let mut changed = false;
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            let Some(manager) = self.apps.get_mut(app.as_str()) else {
+                continue;
+            };
+            let mut applied = Vec::new();
+            for provider in manager.providers.values_mut() {
+                let ids = crate::config_migrations::apply(&app, &mut provider.settings_config);
+                if !ids.is_empty() {
+                    log::info!("供应商 {} 已应用配置迁移: {}", provider.id, ids.join(", "));
+                    applied.extend(ids);
+                }
+            }
+            if !applied.is_empty() {
+                crate::config_migrations::record(&mut self.applied_config_migrations, &applied);
+                changed = true;
+            }
+        }
+        changed
+    }
+
     /// 获取指定应用的管理器
     pub fn get_manager(&self, app: &AppType) -> Option<&ProviderManager> {
         self.apps.get(app.as_str())
@@ -620,6 +787,8 @@ impl MultiAppConfig {
                             homepage,
                             docs,
                             tags,
+                            health: None,
+                            overrides: McpAppOverrides::default(),
                         },
                     );
                 }
@@ -857,4 +1026,24 @@ mod tests {
                 .enabled
         );
     }
+
+    #[test]
+    fn mcp_overrides_merge_per_app() {
+        let server: McpServer = serde_json::from_value(serde_json::json!({
+            "id": "fs",
+            "name": "fs",
+            "server": { "command": "npx", "cwd": "/repo", "env": { "A": "1", "B": "2" } },
+            "apps": { "claude": true, "codex": true },
+            "overrides": { "codex": { "cwd": "/codex", "env": { "B": "3", "A": null } } }
+        }))
+        .unwrap();
+
+        assert_eq!(server.spec_for(&AppType::Claude), server.server);
+        assert_eq!(
+            server.spec_for(&AppType::Codex),
+            serde_json::json!({ "command": "npx", "cwd": "/codex", "env": { "B": "3" } })
+        );
+        let round_trip = serde_json::to_value(&server).unwrap();
+        assert!(round_trip["overrides"].get("claude").is_none());
+    }
 }
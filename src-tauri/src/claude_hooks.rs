@@ -0,0 +1,306 @@
+//! Claude Code 生命周期钩子（settings.json 的 `hooks`）
+//!
+//! 钩子保存在应用设置中，与供应商无关：写入 Claude live 配置时合并到 `hooks` 下，
+//! 回填供应商快照时移除，因此切换供应商不会丢失，也不会混入某个供应商的配置。
+//! 用户手动添加的钩子保持不变。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::config::{get_claude_settings_path, read_json_file, write_json_file};
+use crate::error::AppError;
+use crate::settings;
+
+/// Claude Code 支持的钩子事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HookEvent {
+    PreToolUse,
+    PostToolUse,
+    Notification,
+    UserPromptSubmit,
+    Stop,
+    SubagentStop,
+    PreCompact,
+    SessionStart,
+    SessionEnd,
+}
+
+impl HookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::PreToolUse => "PreToolUse",
+            Self::PostToolUse => "PostToolUse",
+            Self::Notification => "Notification",
+            Self::UserPromptSubmit => "UserPromptSubmit",
+            Self::Stop => "Stop",
+            Self::SubagentStop => "SubagentStop",
+            Self::PreCompact => "PreCompact",
+            Self::SessionStart => "SessionStart",
+            Self::SessionEnd => "SessionEnd",
+        }
+    }
+}
+
+/// 由 cc-switch 管理的钩子
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeHook {
+    pub id: String,
+    pub event: HookEvent,
+    /// 工具名匹配（如 `Bash`、`Edit|Write`），未设置时匹配全部
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+    pub command: String,
+    /// 超时时间（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u32>,
+}
+
+pub fn list() -> Vec<ClaudeHook> {
+    settings::get_settings().claude_hooks
+}
+
+/// 添加钩子并立即更新当前 Claude settings.json（同一事件、匹配与命令的钩子已存在时报错）
+pub fn add(
+    event: HookEvent,
+    command: &str,
+    matcher: Option<&str>,
+    timeout: Option<u32>,
+) -> Result<ClaudeHook, AppError> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err(AppError::localized(
+            "claude_hooks.empty_command",
+            "钩子命令不能为空",
+            "Hook command cannot be empty",
+        ));
+    }
+    let matcher = matcher
+        .map(str::trim)
+        .filter(|m| !m.is_empty() && *m != "*")
+        .map(str::to_string);
+
+    let mut app_settings = settings::get_settings();
+    if app_settings
+        .claude_hooks
+        .iter()
+        .any(|hook| hook.event == event && hook.matcher == matcher && hook.command == command)
+    {
+        return Err(AppError::localized(
+            "claude_hooks.duplicate",
+            format!("{} 钩子已存在: {command}", event.as_str()),
+            format!("{} hook already exists: {command}", event.as_str()),
+        ));
+    }
+    let hook = ClaudeHook {
+        id: format!("hook-{}", chrono::Utc::now().timestamp_millis()),
+        event,
+        matcher,
+        command: command.to_string(),
+        timeout,
+    };
+    app_settings.claude_hooks.push(hook.clone());
+    settings::update_settings(app_settings)?;
+
+    patch_live_settings(&[])?;
+    Ok(hook)
+}
+
+/// 移除钩子并从当前 Claude settings.json 中删除；不存在时返回 false
+pub fn remove(id: &str) -> Result<bool, AppError> {
+    let mut app_settings = settings::get_settings();
+    let Some(index) = app_settings
+        .claude_hooks
+        .iter()
+        .position(|hook| hook.id == id)
+    else {
+        return Ok(false);
+    };
+    let removed = app_settings.claude_hooks.remove(index);
+    settings::update_settings(app_settings)?;
+
+    patch_live_settings(&[removed])?;
+    Ok(true)
+}
+
+/// 写入 live 配置时合并 cc-switch 管理的钩子（重复调用结果不变）
+pub fn patch_settings(content: &mut Value) {
+    let hooks = list();
+    strip_hooks(content, &hooks);
+    for hook in &hooks {
+        insert_hook(content, hook);
+    }
+}
+
+/// 移除 cc-switch 管理的钩子（回填供应商快照时调用）
+pub fn strip_managed(content: &mut Value) {
+    strip_hooks(content, &list());
+}
+
+fn patch_live_settings(removed: &[ClaudeHook]) -> Result<(), AppError> {
+    let path = get_claude_settings_path();
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut content: Value = read_json_file(&path)?;
+    let before = content.clone();
+    strip_hooks(&mut content, removed);
+    patch_settings(&mut content);
+    if content != before {
+        write_json_file(&path, &content)?;
+    }
+    Ok(())
+}
+
+fn same_matcher(group: &Value, matcher: Option<&str>) -> bool {
+    let existing = group
+        .get("matcher")
+        .and_then(Value::as_str)
+        .filter(|m| !m.is_empty() && *m != "*");
+    existing == matcher
+}
+
+fn insert_hook(content: &mut Value, hook: &ClaudeHook) {
+    let Some(obj) = content.as_object_mut() else {
+        return;
+    };
+    let hooks = obj
+        .entry("hooks")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !hooks.is_object() {
+        *hooks = Value::Object(Map::new());
+    }
+    let Some(groups) = hooks
+        .as_object_mut()
+        .map(|hooks| {
+            hooks
+                .entry(hook.event.as_str())
+                .or_insert_with(|| Value::Array(Vec::new()))
+        })
+        .and_then(Value::as_array_mut)
+    else {
+        return;
+    };
+
+    let mut entry = json!({ "type": "command", "command": hook.command });
+    if let Some(timeout) = hook.timeout {
+        entry["timeout"] = json!(timeout);
+    }
+    let matcher = hook.matcher.as_deref();
+    if let Some(list) = groups
+        .iter_mut()
+        .find(|group| same_matcher(group, matcher))
+        .and_then(|group| group.get_mut("hooks"))
+        .and_then(Value::as_array_mut)
+    {
+        list.push(entry);
+        return;
+    }
+    let mut group = json!({ "hooks": [entry] });
+    if let Some(matcher) = matcher {
+        group["matcher"] = json!(matcher);
+    }
+    groups.push(group);
+}
+
+/// 删除与给定钩子相同（事件、匹配、命令）的条目，并清理因此变空的分组与事件
+fn strip_hooks(content: &mut Value, hooks: &[ClaudeHook]) {
+    let Some(events) = content.get_mut("hooks").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for hook in hooks {
+        let Some(groups) = events
+            .get_mut(hook.event.as_str())
+            .and_then(Value::as_array_mut)
+        else {
+            continue;
+        };
+        for group in groups.iter_mut() {
+            if !same_matcher(group, hook.matcher.as_deref()) {
+                continue;
+            }
+            if let Some(list) = group.get_mut("hooks").and_then(Value::as_array_mut) {
+                list.retain(|entry| {
+                    entry.get("command").and_then(Value::as_str) != Some(hook.command.as_str())
+                });
+            }
+        }
+        groups.retain(|group| {
+            group
+                .get("hooks")
+                .and_then(Value::as_array)
+                .is_none_or(|list| !list.is_empty())
+        });
+        if groups.is_empty() {
+            events.remove(hook.event.as_str());
+        }
+    }
+    if events.is_empty() {
+        if let Some(obj) = content.as_object_mut() {
+            obj.remove("hooks");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(event: HookEvent, matcher: Option<&str>, command: &str) -> ClaudeHook {
+        ClaudeHook {
+            id: command.to_string(),
+            event,
+            matcher: matcher.map(str::to_string),
+            command: command.to_string(),
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn merges_managed_hooks_next_to_user_hooks() {
+        let mut content = json!({
+            "env": {},
+            "hooks": {
+                "PreToolUse": [
+                    { "matcher": "Bash", "hooks": [{ "type": "command", "command": "user.sh" }] }
+                ]
+            }
+        });
+        let managed = vec![
+            hook(HookEvent::PreToolUse, Some("Bash"), "audit.sh"),
+            hook(HookEvent::Stop, None, "notify.sh"),
+        ];
+        for _ in 0..2 {
+            strip_hooks(&mut content, &managed);
+            for h in &managed {
+                insert_hook(&mut content, h);
+            }
+        }
+        assert_eq!(
+            content["hooks"]["PreToolUse"][0]["hooks"],
+            json!([
+                { "type": "command", "command": "user.sh" },
+                { "type": "command", "command": "audit.sh" }
+            ])
+        );
+        assert_eq!(
+            content["hooks"]["Stop"],
+            json!([{ "hooks": [{ "type": "command", "command": "notify.sh" }] }])
+        );
+
+        strip_hooks(&mut content, &managed);
+        assert_eq!(
+            content["hooks"],
+            json!({
+                "PreToolUse": [
+                    { "matcher": "Bash", "hooks": [{ "type": "command", "command": "user.sh" }] }
+                ]
+            })
+        );
+        strip_hooks(
+            &mut content,
+            &[hook(HookEvent::PreToolUse, Some("Bash"), "user.sh")],
+        );
+        assert!(content.get("hooks").is_none());
+    }
+}
@@ -0,0 +1,100 @@
+//! macOS 钥匙串中的 Claude Code 登录凭据
+//!
+//! macOS 上 Claude Code 不写 `~/.claude/.credentials.json`，而是把同样的 JSON 保存在
+//! 钥匙串的通用密码项 `Claude Code-credentials` 中。这里通过系统自带的 `security` 命令读写，
+//! 写入时凭据经 stdin 以十六进制传入，不会出现在进程参数中。其他平台上均为空操作。
+
+use crate::error::AppError;
+
+/// Claude Code 使用的钥匙串服务名
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const SERVICE: &str = "Claude Code-credentials";
+
+/// 读取钥匙串中的凭据原文（不存在或非 macOS 时返回 None）
+pub fn read() -> Result<Option<String>, AppError> {
+    #[cfg(target_os = "macos")]
+    {
+        let output = security(&["find-generic-password", "-s", SERVICE, "-w"], None)?;
+        // 44: errSecItemNotFound
+        if output.status.code() == Some(44) {
+            return Ok(None);
+        }
+        if !output.status.success() {
+            return Err(failed("读取", &output.stderr));
+        }
+        let content = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((!content.is_empty()).then_some(content))
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(None)
+}
+
+/// 写入（覆盖）钥匙串中的凭据；返回是否写入了钥匙串（非 macOS 为 false）
+pub fn write(content: &str) -> Result<bool, AppError> {
+    #[cfg(target_os = "macos")]
+    {
+        let account = std::env::var("USER").unwrap_or_else(|_| "claude".to_string());
+        let hex: String = content.bytes().map(|b| format!("{b:02x}")).collect();
+        let script = format!(
+            "add-generic-password -U -a \"{}\" -s \"{SERVICE}\" -X {hex}\n",
+            account.replace('"', "")
+        );
+        let output = security(&["-i"], Some(&script))?;
+        if !output.status.success() {
+            return Err(failed("写入", &output.stderr));
+        }
+        Ok(true)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = content;
+        Ok(false)
+    }
+}
+
+/// 删除钥匙串中的凭据（不存在时忽略）
+pub fn delete() -> Result<(), AppError> {
+    #[cfg(target_os = "macos")]
+    {
+        let output = security(&["delete-generic-password", "-s", SERVICE], None)?;
+        if !output.status.success() && output.status.code() != Some(44) {
+            return Err(failed("删除", &output.stderr));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn security(args: &[&str], stdin: Option<&str>) -> Result<std::process::Output, AppError> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("/usr/bin/security")
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::Message(format!("执行 security 命令失败: {e}")))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())
+            .map_err(|e| AppError::Message(format!("写入 security 命令输入失败: {e}")))?;
+    }
+    child
+        .wait_with_output()
+        .map_err(|e| AppError::Message(format!("执行 security 命令失败: {e}")))
+}
+
+#[cfg(target_os = "macos")]
+fn failed(action: &str, stderr: &[u8]) -> AppError {
+    let detail = String::from_utf8_lossy(stderr).trim().to_string();
+    AppError::localized(
+        "claude.keychain_failed",
+        format!("{action}钥匙串中的 Claude 凭据失败: {detail}"),
+        format!("Failed to access Claude credentials in the keychain: {detail}"),
+    )
+}
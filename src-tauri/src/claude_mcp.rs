@@ -147,6 +147,11 @@ pub fn upsert_mcp_server(id: &str, spec: Value) -> Result<bool, AppError> {
         }
     }
 
+    let mut spec = spec;
+    if let Some(obj) = spec.as_object_mut() {
+        crate::mcp::apply_platform_launcher(obj);
+    }
+
     let path = user_config_path();
     let mut root = if path.exists() {
         read_json_value(&path)?
@@ -289,6 +294,7 @@ pub fn set_mcp_servers_map(
         obj.remove("tags");
         obj.remove("homepage");
         obj.remove("docs");
+        crate::mcp::apply_platform_launcher(&mut obj);
 
         out.insert(id.clone(), Value::Object(obj));
     }
@@ -9,13 +9,16 @@ use serde_json::Value;
 use std::fs;
 use std::path::Path;
 
+/// Codex 配置目录中用于识别“正在使用”的文件
+pub(crate) const CODEX_DIR_MARKERS: &[&str] = &["config.toml", "auth.json"];
+
 /// 获取 Codex 配置目录路径
 pub fn get_codex_config_dir() -> PathBuf {
     if let Some(custom) = crate::settings::get_codex_override_dir() {
         return custom;
     }
 
-    dirs::home_dir().expect("无法获取用户主目录").join(".codex")
+    crate::config::resolve_cli_dir("codex", ".codex", CODEX_DIR_MARKERS)
 }
 
 /// 获取 Codex auth.json 路径
@@ -132,3 +135,432 @@ pub fn read_and_validate_codex_config_text() -> Result<String, AppError> {
     validate_config_toml(&s)?;
     Ok(s)
 }
+
+/// 将自定义请求头写入当前 `model_provider` 对应的 `[model_providers.<name>]` 表的 `http_headers`
+///
+/// - `headers` 为空时移除 `http_headers`
+/// - 未设置 `model_provider` 或找不到对应表时原样返回
+pub fn apply_http_headers(
+    text: &str,
+    headers: &std::collections::HashMap<String, String>,
+) -> Result<String, AppError> {
+    use toml_edit::{InlineTable, Item};
+
+    if text.trim().is_empty() {
+        return Ok(text.to_string());
+    }
+    let mut doc = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Config(format!("解析 config.toml 失败: {e}")))?;
+
+    let Some(provider_key) = doc
+        .get("model_provider")
+        .and_then(|item| item.as_str())
+        .map(str::to_string)
+    else {
+        log::warn!("config.toml 未设置 model_provider，跳过 http_headers 注入");
+        return Ok(text.to_string());
+    };
+
+    let Some(provider_tbl) = doc
+        .get_mut("model_providers")
+        .and_then(|item| item.get_mut(provider_key.as_str()))
+        .and_then(|item| item.as_table_like_mut())
+    else {
+        log::warn!("config.toml 中不存在 [model_providers.{provider_key}]，跳过 http_headers 注入");
+        return Ok(text.to_string());
+    };
+
+    if headers.is_empty() {
+        provider_tbl.remove("http_headers");
+    } else {
+        let mut names: Vec<_> = headers.keys().collect();
+        names.sort();
+        let mut inline = InlineTable::new();
+        for name in names {
+            inline.insert(name.as_str(), headers[name].as_str().into());
+        }
+        provider_tbl.insert(
+            "http_headers",
+            Item::Value(toml_edit::Value::InlineTable(inline)),
+        );
+    }
+
+    Ok(doc.to_string())
+}
+
+/// 设置当前 `model_provider` 对应表中的数值键（值为 None 时移除该键）
+///
+/// 未设置 `model_provider` 或找不到对应表时原样返回
+pub fn apply_provider_numbers(
+    text: &str,
+    entries: &[(&str, Option<u64>)],
+) -> Result<String, AppError> {
+    if text.trim().is_empty() {
+        return Ok(text.to_string());
+    }
+    let mut doc = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Config(format!("解析 config.toml 失败: {e}")))?;
+
+    let Some(provider_key) = doc
+        .get("model_provider")
+        .and_then(|item| item.as_str())
+        .map(str::to_string)
+    else {
+        log::warn!("config.toml 未设置 model_provider，跳过性能参数注入");
+        return Ok(text.to_string());
+    };
+    let Some(provider_tbl) = doc
+        .get_mut("model_providers")
+        .and_then(|item| item.get_mut(provider_key.as_str()))
+        .and_then(|item| item.as_table_like_mut())
+    else {
+        log::warn!("config.toml 中不存在 [model_providers.{provider_key}]，跳过性能参数注入");
+        return Ok(text.to_string());
+    };
+
+    for (key, value) in entries {
+        match value {
+            Some(value) => {
+                let value = i64::try_from(*value).unwrap_or(i64::MAX);
+                provider_tbl.insert(key, toml_edit::value(value));
+            }
+            None => {
+                provider_tbl.remove(key);
+            }
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+/// 写入 `[shell_environment_policy] set`，为 Codex 启动的命令注入额外环境变量
+///
+/// - 先移除 `remove` 中列出的旧键（上次注入、本次不再需要的变量）
+/// - `set` 为空时移除该字段，`shell_environment_policy` 为空时一并移除
+pub fn apply_shell_env(
+    text: &str,
+    env: &std::collections::HashMap<String, String>,
+    remove: &[String],
+) -> Result<String, AppError> {
+    use toml_edit::{InlineTable, Item, Table};
+
+    if env.is_empty() && remove.is_empty() {
+        return Ok(text.to_string());
+    }
+    let mut doc = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Config(format!("解析 config.toml 失败: {e}")))?;
+
+    let policy = doc
+        .entry("shell_environment_policy")
+        .or_insert_with(|| Item::Table(Table::new()))
+        .as_table_like_mut()
+        .ok_or_else(|| {
+            AppError::Config("config.toml 中 shell_environment_policy 不是表".to_string())
+        })?;
+    let mut set = policy
+        .get("set")
+        .and_then(|item| item.as_table_like())
+        .map(|table| {
+            table
+                .iter()
+                .map(|(key, item)| (key.to_string(), item.clone()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    set.retain(|(key, _)| !remove.contains(key) && !env.contains_key(key));
+
+    let mut names: Vec<_> = env.keys().collect();
+    names.sort();
+    let mut inline = InlineTable::new();
+    for (key, item) in set {
+        if let Ok(value) = item.into_value() {
+            inline.insert(key.as_str(), value);
+        }
+    }
+    for name in names {
+        inline.insert(name.as_str(), env[name].as_str().into());
+    }
+
+    if inline.is_empty() {
+        policy.remove("set");
+    } else {
+        policy.insert("set", Item::Value(toml_edit::Value::InlineTable(inline)));
+    }
+    if policy.is_empty() {
+        doc.remove("shell_environment_policy");
+    }
+
+    Ok(doc.to_string())
+}
+
+/// 读取当前 `model_provider` 对应的 `wire_api`
+pub fn get_wire_api(text: &str) -> Option<String> {
+    let table: toml::Table = toml::from_str(text).ok()?;
+    let provider_key = table.get("model_provider")?.as_str()?;
+    table
+        .get("model_providers")?
+        .get(provider_key)?
+        .get("wire_api")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// 设置当前 `model_provider` 的 `wire_api`（通过 toml_edit 修改，保留注释与格式）
+pub fn set_wire_api(text: &str, wire_api: &str) -> Result<String, AppError> {
+    let mut doc = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Config(format!("解析 config.toml 失败: {e}")))?;
+
+    let provider_key = doc
+        .get("model_provider")
+        .and_then(|item| item.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            AppError::localized(
+                "codex.wire_api.no_provider",
+                "config.toml 未设置 model_provider，无法修改 wire_api",
+                "config.toml has no model_provider; cannot set wire_api",
+            )
+        })?;
+
+    let provider_tbl = doc
+        .get_mut("model_providers")
+        .and_then(|item| item.get_mut(provider_key.as_str()))
+        .and_then(|item| item.as_table_like_mut())
+        .ok_or_else(|| {
+            AppError::localized(
+                "codex.wire_api.no_provider",
+                format!("config.toml 中不存在 [model_providers.{provider_key}]"),
+                format!("config.toml has no [model_providers.{provider_key}] table"),
+            )
+        })?;
+    provider_tbl.insert("wire_api", toml_edit::value(wire_api));
+
+    Ok(doc.to_string())
+}
+
+/// 设置顶层 `model`（通过 toml_edit 修改，保留注释与格式）；`model` 为 None 时移除该键
+pub fn set_model(text: &str, model: Option<&str>) -> Result<String, AppError> {
+    let value = model.map_or(Value::Null, |model| Value::String(model.to_string()));
+    set_config_value(text, "model", &value)
+}
+
+/// 当前 `model_provider` 对应表的路径别名，如 `@provider.base_url`
+const PROVIDER_ALIAS: &str = "@provider";
+
+fn invalid_key_path(key_path: &str, reason: &str) -> AppError {
+    AppError::localized(
+        "codex.config_path.invalid",
+        format!("无效的 config.toml 键路径 '{key_path}': {reason}"),
+        format!("Invalid config.toml key path '{key_path}': {reason}"),
+    )
+}
+
+/// 解析键路径：以 `.` 分隔，含 `.` 的键可用双引号包裹（如 `model_providers."a.b".base_url`）；
+/// 首段为 `@provider` 时展开为 `model_providers.<当前 model_provider>`
+fn parse_key_path(text: &str, key_path: &str) -> Result<Vec<String>, AppError> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut was_quoted = false;
+    for c in key_path.trim().chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                was_quoted = true;
+            }
+            '.' if !quoted => {
+                segments.push(std::mem::take(&mut current));
+                if !was_quoted && segments.last().is_some_and(String::is_empty) {
+                    return Err(invalid_key_path(key_path, "empty segment"));
+                }
+                was_quoted = false;
+            }
+            _ => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(invalid_key_path(key_path, "unterminated quote"));
+    }
+    if current.is_empty() && !was_quoted {
+        return Err(invalid_key_path(key_path, "empty segment"));
+    }
+    segments.push(current);
+
+    if segments[0] == PROVIDER_ALIAS {
+        let table: toml::Table = toml::from_str(text)
+            .map_err(|e| AppError::Config(format!("解析 config.toml 失败: {e}")))?;
+        let provider = table
+            .get("model_provider")
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| invalid_key_path(key_path, "model_provider is not set"))?;
+        segments.splice(0..1, ["model_providers".to_string(), provider.to_string()]);
+    }
+    Ok(segments)
+}
+
+/// 读取 config.toml 中指定键路径的值（表转换为 JSON 对象）；不存在时返回 None
+pub fn get_config_value(text: &str, key_path: &str) -> Result<Option<Value>, AppError> {
+    let segments = parse_key_path(text, key_path)?;
+    let table: toml::Table = toml::from_str(text)
+        .map_err(|e| AppError::Config(format!("解析 config.toml 失败: {e}")))?;
+    let mut node: Option<&toml::Value> = None;
+    for segment in &segments {
+        node = match node {
+            None => table.get(segment),
+            Some(toml::Value::Table(inner)) => inner.get(segment),
+            Some(_) => None,
+        };
+        if node.is_none() {
+            return Ok(None);
+        }
+    }
+    node.map(|value| serde_json::to_value(value).map_err(|e| AppError::JsonSerialize { source: e }))
+        .transpose()
+}
+
+/// 设置 config.toml 中指定键路径的值（通过 toml_edit 修改，保留注释与格式）
+///
+/// - `value` 为 null 时移除该键
+/// - 缺失的中间表自动创建；路径中途遇到非表的值时报错
+pub fn set_config_value(text: &str, key_path: &str, value: &Value) -> Result<String, AppError> {
+    use toml_edit::{Item, Table};
+
+    let segments = parse_key_path(text, key_path)?;
+    let mut doc = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Config(format!("解析 config.toml 失败: {e}")))?;
+
+    let (last, parents) = segments
+        .split_last()
+        .expect("key path has at least one segment");
+    let mut table: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for segment in parents {
+        if value.is_null() && table.get(segment).is_none() {
+            return Ok(text.to_string());
+        }
+        table = table
+            .entry(segment)
+            .or_insert_with(|| {
+                let mut created = Table::new();
+                created.set_implicit(true);
+                Item::Table(created)
+            })
+            .as_table_like_mut()
+            .ok_or_else(|| invalid_key_path(key_path, &format!("'{segment}' is not a table")))?;
+    }
+
+    if value.is_null() {
+        table.remove(last);
+        return Ok(doc.to_string());
+    }
+    let new_value = json_to_toml_value(value)
+        .ok_or_else(|| invalid_key_path(key_path, "null is not allowed inside arrays or tables"))?;
+    match table.get_mut(last).and_then(Item::as_value_mut) {
+        // 原地替换值，保留键前的注释与行尾注释
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = new_value;
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            table.insert(last, Item::Value(new_value));
+        }
+    }
+    Ok(doc.to_string())
+}
+
+fn json_to_toml_value(value: &Value) -> Option<toml_edit::Value> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(flag) => (*flag).into(),
+        Value::Number(number) => match number.as_i64() {
+            Some(int) => int.into(),
+            None => number.as_f64()?.into(),
+        },
+        Value::String(text) => text.as_str().into(),
+        Value::Array(items) => items
+            .iter()
+            .map(json_to_toml_value)
+            .collect::<Option<toml_edit::Array>>()?
+            .into(),
+        Value::Object(map) => {
+            let mut inline = toml_edit::InlineTable::new();
+            for (key, item) in map {
+                inline.insert(key.as_str(), json_to_toml_value(item)?);
+            }
+            inline.into()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn config_value_paths_read_and_edit_in_place() {
+        let text = r#"# managed by team
+model = "gpt-5" # default
+model_provider = "relay"
+
+[model_providers.relay]
+base_url = "https://old.example.com/v1"
+wire_api = "responses"
+"#;
+        assert_eq!(
+            get_config_value(text, "@provider.base_url").unwrap(),
+            Some(json!("https://old.example.com/v1"))
+        );
+        assert_eq!(
+            get_config_value(text, "model_providers.relay").unwrap(),
+            Some(json!({ "base_url": "https://old.example.com/v1", "wire_api": "responses" }))
+        );
+        assert_eq!(get_config_value(text, "missing.key").unwrap(), None);
+
+        let edited =
+            set_config_value(text, "@provider.base_url", &json!("https://new/v1")).unwrap();
+        let edited = set_config_value(&edited, "model_reasoning_effort", &json!("high")).unwrap();
+        let edited = set_config_value(&edited, "model", &json!("o3")).unwrap();
+        assert!(edited.starts_with("# managed by team\nmodel = \"o3\" # default\n"));
+        assert!(edited.contains("base_url = \"https://new/v1\""));
+        assert!(edited.contains("model_reasoning_effort = \"high\""));
+
+        let removed = set_config_value(&edited, "@provider.wire_api", &Value::Null).unwrap();
+        assert!(!removed.contains("wire_api"));
+        assert!(set_config_value(text, "model.name", &json!("x")).is_err());
+        assert!(parse_key_path(text, "a..b").is_err());
+        assert_eq!(
+            parse_key_path(text, r#"model_providers."a.b".base_url"#).unwrap(),
+            vec!["model_providers", "a.b", "base_url"]
+        );
+    }
+
+    #[test]
+    fn provider_numbers_set_and_remove_in_provider_table() {
+        let text = "model_provider = \"relay\"\n\n[model_providers.relay]\nbase_url = \"https://relay/v1\"\nrequest_max_retries = 2\n";
+        let edited = apply_provider_numbers(
+            text,
+            &[
+                ("max_concurrent_requests", Some(4)),
+                ("request_max_retries", None),
+            ],
+        )
+        .unwrap();
+        let table: toml::Table = toml::from_str(&edited).unwrap();
+        let relay = &table["model_providers"]["relay"];
+        assert_eq!(relay["max_concurrent_requests"].as_integer(), Some(4));
+        assert!(relay.get("request_max_retries").is_none());
+        assert_eq!(relay["base_url"].as_str(), Some("https://relay/v1"));
+
+        let no_provider = "model = \"gpt-5\"\n";
+        assert_eq!(
+            apply_provider_numbers(no_provider, &[("request_timeout_ms", Some(1))]).unwrap(),
+            no_provider
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use tauri::State;
+
+use crate::ab_test::AbTest;
+use crate::error::CommandError;
+use crate::services::{AbTestReport, AbTestService};
+use crate::store::AppState;
+
+/// 获取 A/B 测试列表（含已结束的测试）
+#[tauri::command]
+pub fn list_ab_tests() -> Result<Vec<AbTest>, CommandError> {
+    Ok(AbTestService::list())
+}
+
+/// 开始 A/B 测试（代理模式交替转发请求，轮换模式由调度器按时长切换当前供应商）
+#[tauri::command]
+pub fn start_ab_test(state: State<'_, AppState>, test: AbTest) -> Result<AbTest, CommandError> {
+    AbTestService::start(state.inner(), test).map_err(CommandError::from)
+}
+
+/// 结束 A/B 测试
+#[tauri::command]
+pub fn stop_ab_test(id: String) -> Result<AbTest, CommandError> {
+    AbTestService::stop(&id).map_err(CommandError::from)
+}
+
+/// 删除 A/B 测试记录
+#[tauri::command]
+pub fn delete_ab_test(id: String) -> Result<bool, CommandError> {
+    AbTestService::delete(&id).map_err(CommandError::from)
+}
+
+/// 获取 A/B 测试的对比报告
+#[tauri::command]
+pub fn get_ab_test_report(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<AbTestReport, CommandError> {
+    AbTestService::report(state.inner(), &id).map_err(CommandError::from)
+}
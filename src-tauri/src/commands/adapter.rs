@@ -0,0 +1,66 @@
+#![allow(non_snake_case)]
+
+use serde_json::Value;
+use tauri::State;
+
+use crate::adapter::AppAdapter;
+use crate::error::CommandError;
+use crate::provider::Provider;
+use crate::services::{AdapterProviders, AdapterService};
+use crate::store::AppState;
+
+/// 列出 `~/.cc-switch/adapters/` 中有效的应用适配器清单
+#[tauri::command]
+pub fn list_app_adapters() -> Result<Vec<AppAdapter>, CommandError> {
+    Ok(AdapterService::list())
+}
+
+/// 获取适配器应用的供应商列表与当前供应商
+#[tauri::command]
+pub fn get_adapter_providers(
+    state: State<'_, AppState>,
+    adapter: String,
+) -> Result<AdapterProviders, CommandError> {
+    AdapterService::providers(&state, &adapter).map_err(CommandError::from)
+}
+
+/// 读取适配器应用 live 配置中的字段值
+#[tauri::command]
+pub fn read_adapter_live_config(adapter: String) -> Result<Value, CommandError> {
+    AdapterService::read_live(&adapter).map_err(CommandError::from)
+}
+
+/// 新增或更新适配器应用的供应商（当前供应商会同步写入 live 配置）
+#[tauri::command]
+pub fn save_adapter_provider(
+    state: State<'_, AppState>,
+    adapter: String,
+    provider: Provider,
+) -> Result<bool, CommandError> {
+    AdapterService::save_provider(&state, &adapter, provider)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn delete_adapter_provider(
+    state: State<'_, AppState>,
+    adapter: String,
+    id: String,
+) -> Result<bool, CommandError> {
+    AdapterService::delete_provider(&state, &adapter, &id)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 切换适配器应用的供应商
+#[tauri::command]
+pub fn switch_adapter_provider(
+    state: State<'_, AppState>,
+    adapter: String,
+    id: String,
+) -> Result<bool, CommandError> {
+    AdapterService::switch(&state, &adapter, &id)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
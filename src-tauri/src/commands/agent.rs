@@ -0,0 +1,103 @@
+use tauri::State;
+
+use crate::commands::skill::SkillServiceState;
+use crate::error::CommandError;
+use crate::services::{AgentService, RepoAgent, SkillRepo, SubAgent};
+use crate::store::AppState;
+
+/// 获取所有 Claude Code 子代理（含已停用）
+#[tauri::command]
+pub async fn get_sub_agents() -> Result<Vec<SubAgent>, CommandError> {
+    AgentService::list().map_err(CommandError::from)
+}
+
+/// 新建或编辑子代理
+#[tauri::command]
+pub async fn save_sub_agent(name: String, content: String) -> Result<SubAgent, CommandError> {
+    AgentService::save(&name, &content).map_err(CommandError::from)
+}
+
+/// 删除子代理
+#[tauri::command]
+pub async fn delete_sub_agent(name: String) -> Result<bool, CommandError> {
+    AgentService::delete(&name).map_err(CommandError::from)
+}
+
+/// 启用或停用子代理
+#[tauri::command]
+pub async fn set_sub_agent_enabled(name: String, enabled: bool) -> Result<(), CommandError> {
+    AgentService::set_enabled(&name, enabled).map_err(CommandError::from)
+}
+
+/// 列出子代理仓库中可安装的定义
+#[tauri::command]
+pub async fn get_repo_agents(
+    service: State<'_, SkillServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<RepoAgent>, CommandError> {
+    let repos = {
+        let config = app_state.config.read().map_err(CommandError::from)?;
+        config.agent_repos.clone()
+    };
+
+    AgentService::list_repo_agents(&service.0, repos)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 从仓库安装子代理
+#[tauri::command]
+pub async fn install_repo_agent(
+    agent: RepoAgent,
+    service: State<'_, SkillServiceState>,
+) -> Result<SubAgent, CommandError> {
+    AgentService::install_repo_agent(&service.0, &agent)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn get_agent_repos(app_state: State<'_, AppState>) -> Result<Vec<SkillRepo>, CommandError> {
+    let config = app_state.config.read().map_err(CommandError::from)?;
+    Ok(config.agent_repos.clone())
+}
+
+#[tauri::command]
+pub fn add_agent_repo(
+    repo: SkillRepo,
+    app_state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    {
+        let mut config = app_state.config.write().map_err(CommandError::from)?;
+        // 同一仓库只保留一条，重复添加时更新
+        if let Some(pos) = config
+            .agent_repos
+            .iter()
+            .position(|r| r.owner == repo.owner && r.name == repo.name)
+        {
+            config.agent_repos[pos] = repo;
+        } else {
+            config.agent_repos.push(repo);
+        }
+    }
+
+    app_state.save().map_err(CommandError::from)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn remove_agent_repo(
+    owner: String,
+    name: String,
+    app_state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    {
+        let mut config = app_state.config.write().map_err(CommandError::from)?;
+        config
+            .agent_repos
+            .retain(|r| !(r.owner == owner && r.name == name));
+    }
+
+    app_state.save().map_err(CommandError::from)?;
+    Ok(true)
+}
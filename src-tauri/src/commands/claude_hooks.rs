@@ -0,0 +1,25 @@
+use crate::claude_hooks::{self, ClaudeHook, HookEvent};
+use crate::error::CommandError;
+
+/// 列出由 cc-switch 管理的 Claude Code 钩子
+#[tauri::command]
+pub async fn list_claude_hooks() -> Result<Vec<ClaudeHook>, CommandError> {
+    Ok(claude_hooks::list())
+}
+
+/// 添加 Claude Code 钩子并写入当前 settings.json（切换供应商时保留）
+#[tauri::command]
+pub async fn add_claude_hook(
+    event: HookEvent,
+    command: String,
+    matcher: Option<String>,
+    timeout: Option<u32>,
+) -> Result<ClaudeHook, CommandError> {
+    claude_hooks::add(event, &command, matcher.as_deref(), timeout).map_err(CommandError::from)
+}
+
+/// 移除 Claude Code 钩子
+#[tauri::command]
+pub async fn remove_claude_hook(id: String) -> Result<bool, CommandError> {
+    claude_hooks::remove(&id).map_err(CommandError::from)
+}
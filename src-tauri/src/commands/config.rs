@@ -10,15 +10,16 @@ use crate::config::{self, get_claude_settings_path, ConfigStatus};
 
 /// 获取 Claude Code 配置状态
 #[tauri::command]
-pub async fn get_claude_config_status() -> Result<ConfigStatus, String> {
+pub async fn get_claude_config_status() -> Result<ConfigStatus, CommandError> {
     Ok(config::get_claude_config_status())
 }
 
+use crate::error::{AppError, CommandError};
 use std::str::FromStr;
 
 #[tauri::command]
-pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
-    match AppType::from_str(&app).map_err(|e| e.to_string())? {
+pub async fn get_config_status(app: String) -> Result<ConfigStatus, CommandError> {
+    match AppType::from_str(&app).map_err(CommandError::from)? {
         AppType::Claude => Ok(config::get_claude_config_status()),
         AppType::Codex => {
             let auth_path = codex_config::get_codex_auth_path();
@@ -43,14 +44,14 @@ pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
 
 /// 获取 Claude Code 配置文件路径
 #[tauri::command]
-pub async fn get_claude_code_config_path() -> Result<String, String> {
+pub async fn get_claude_code_config_path() -> Result<String, CommandError> {
     Ok(get_claude_settings_path().to_string_lossy().to_string())
 }
 
 /// 获取当前生效的配置目录
 #[tauri::command]
-pub async fn get_config_dir(app: String) -> Result<String, String> {
-    let dir = match AppType::from_str(&app).map_err(|e| e.to_string())? {
+pub async fn get_config_dir(app: String) -> Result<String, CommandError> {
+    let dir = match AppType::from_str(&app).map_err(CommandError::from)? {
         AppType::Claude => config::get_claude_config_dir(),
         AppType::Codex => codex_config::get_codex_config_dir(),
         AppType::Gemini => crate::gemini_config::get_gemini_dir(),
@@ -59,23 +60,39 @@ pub async fn get_config_dir(app: String) -> Result<String, String> {
     Ok(dir.to_string_lossy().to_string())
 }
 
+/// 获取配置目录的候选位置与自动检测结果
+#[tauri::command]
+pub async fn get_config_dir_resolution(
+    app: String,
+) -> Result<config::ConfigDirResolution, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    Ok(config::get_config_dir_resolution(&app_type))
+}
+
 /// 打开配置文件夹
 #[tauri::command]
-pub async fn open_config_folder(handle: AppHandle, app: String) -> Result<bool, String> {
-    let config_dir = match AppType::from_str(&app).map_err(|e| e.to_string())? {
+pub async fn open_config_folder(handle: AppHandle, app: String) -> Result<bool, CommandError> {
+    let config_dir = match AppType::from_str(&app).map_err(CommandError::from)? {
         AppType::Claude => config::get_claude_config_dir(),
         AppType::Codex => codex_config::get_codex_config_dir(),
         AppType::Gemini => crate::gemini_config::get_gemini_dir(),
     };
 
     if !config_dir.exists() {
-        std::fs::create_dir_all(&config_dir).map_err(|e| format!("创建目录失败: {e}"))?;
+        std::fs::create_dir_all(&config_dir)
+            .map_err(|e| CommandError::from(AppError::io(&config_dir, e)))?;
     }
 
     handle
         .opener()
         .open_path(config_dir.to_string_lossy().to_string(), None::<String>)
-        .map_err(|e| format!("打开文件夹失败: {e}"))?;
+        .map_err(|e| {
+            AppError::localized(
+                "system.open_path_failed",
+                format!("打开文件夹失败: {e}"),
+                format!("Failed to open folder: {e}"),
+            )
+        })?;
 
     Ok(true)
 }
@@ -85,7 +102,7 @@ pub async fn open_config_folder(handle: AppHandle, app: String) -> Result<bool,
 pub async fn pick_directory(
     app: AppHandle,
     #[allow(non_snake_case)] defaultPath: Option<String>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CommandError> {
     let initial = defaultPath
         .map(|p| p.trim().to_string())
         .filter(|p| !p.is_empty());
@@ -98,14 +115,23 @@ pub async fn pick_directory(
         builder.blocking_pick_folder()
     })
     .await
-    .map_err(|e| format!("弹出目录选择器失败: {e}"))?;
+    .map_err(|e| {
+        AppError::localized(
+            "system.pick_directory_failed",
+            format!("弹出目录选择器失败: {e}"),
+            format!("Failed to open directory picker: {e}"),
+        )
+    })?;
 
     match result {
         Some(file_path) => {
-            let resolved = file_path
-                .simplified()
-                .into_path()
-                .map_err(|e| format!("解析选择的目录失败: {e}"))?;
+            let resolved = file_path.simplified().into_path().map_err(|e| {
+                AppError::localized(
+                    "system.pick_directory_failed",
+                    format!("解析选择的目录失败: {e}"),
+                    format!("Failed to resolve the selected directory: {e}"),
+                )
+            })?;
             Ok(Some(resolved.to_string_lossy().to_string()))
         }
         None => Ok(None),
@@ -114,24 +140,31 @@ pub async fn pick_directory(
 
 /// 获取应用配置文件路径
 #[tauri::command]
-pub async fn get_app_config_path() -> Result<String, String> {
+pub async fn get_app_config_path() -> Result<String, CommandError> {
     let config_path = config::get_app_config_path();
     Ok(config_path.to_string_lossy().to_string())
 }
 
 /// 打开应用配置文件夹
 #[tauri::command]
-pub async fn open_app_config_folder(handle: AppHandle) -> Result<bool, String> {
+pub async fn open_app_config_folder(handle: AppHandle) -> Result<bool, CommandError> {
     let config_dir = config::get_app_config_dir();
 
     if !config_dir.exists() {
-        std::fs::create_dir_all(&config_dir).map_err(|e| format!("创建目录失败: {e}"))?;
+        std::fs::create_dir_all(&config_dir)
+            .map_err(|e| CommandError::from(AppError::io(&config_dir, e)))?;
     }
 
     handle
         .opener()
         .open_path(config_dir.to_string_lossy().to_string(), None::<String>)
-        .map_err(|e| format!("打开文件夹失败: {e}"))?;
+        .map_err(|e| {
+            AppError::localized(
+                "system.open_path_failed",
+                format!("打开文件夹失败: {e}"),
+                format!("Failed to open folder: {e}"),
+            )
+        })?;
 
     Ok(true)
 }
@@ -140,11 +173,8 @@ pub async fn open_app_config_folder(handle: AppHandle) -> Result<bool, String> {
 #[tauri::command]
 pub async fn get_claude_common_config_snippet(
     state: tauri::State<'_, crate::store::AppState>,
-) -> Result<Option<String>, String> {
-    let guard = state
-        .config
-        .read()
-        .map_err(|e| format!("读取配置锁失败: {e}"))?;
+) -> Result<Option<String>, CommandError> {
+    let guard = state.config.read().map_err(CommandError::from)?;
     Ok(guard.common_config_snippets.claude.clone())
 }
 
@@ -153,16 +183,14 @@ pub async fn get_claude_common_config_snippet(
 pub async fn set_claude_common_config_snippet(
     snippet: String,
     state: tauri::State<'_, crate::store::AppState>,
-) -> Result<(), String> {
-    let mut guard = state
-        .config
-        .write()
-        .map_err(|e| format!("写入配置锁失败: {e}"))?;
+) -> Result<(), CommandError> {
+    let mut guard = state.config.write().map_err(CommandError::from)?;
 
     // 验证是否为有效的 JSON（如果不为空）
     if !snippet.trim().is_empty() {
-        serde_json::from_str::<serde_json::Value>(&snippet)
-            .map_err(|e| format!("无效的 JSON 格式: {e}"))?;
+        serde_json::from_str::<serde_json::Value>(&snippet).map_err(|e| {
+            CommandError::from(AppError::InvalidInput(format!("无效的 JSON 格式: {e}")))
+        })?;
     }
 
     guard.common_config_snippets.claude = if snippet.trim().is_empty() {
@@ -171,7 +199,9 @@ pub async fn set_claude_common_config_snippet(
         Some(snippet)
     };
 
-    guard.save().map_err(|e| e.to_string())?;
+    guard.save().map_err(CommandError::from)?;
+    drop(guard);
+    crate::config_events::notify_changed();
     Ok(())
 }
 
@@ -180,16 +210,13 @@ pub async fn set_claude_common_config_snippet(
 pub async fn get_common_config_snippet(
     app_type: String,
     state: tauri::State<'_, crate::store::AppState>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CommandError> {
     use crate::app_config::AppType;
     use std::str::FromStr;
 
-    let app = AppType::from_str(&app_type).map_err(|e| format!("无效的应用类型: {e}"))?;
+    let app = AppType::from_str(&app_type).map_err(CommandError::from)?;
 
-    let guard = state
-        .config
-        .read()
-        .map_err(|e| format!("读取配置锁失败: {e}"))?;
+    let guard = state.config.read().map_err(CommandError::from)?;
 
     Ok(guard.common_config_snippets.get(&app).cloned())
 }
@@ -200,24 +227,22 @@ pub async fn set_common_config_snippet(
     app_type: String,
     snippet: String,
     state: tauri::State<'_, crate::store::AppState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     use crate::app_config::AppType;
     use std::str::FromStr;
 
-    let app = AppType::from_str(&app_type).map_err(|e| format!("无效的应用类型: {e}"))?;
+    let app = AppType::from_str(&app_type).map_err(CommandError::from)?;
 
-    let mut guard = state
-        .config
-        .write()
-        .map_err(|e| format!("写入配置锁失败: {e}"))?;
+    let mut guard = state.config.write().map_err(CommandError::from)?;
 
     // 验证格式（根据应用类型）
     if !snippet.trim().is_empty() {
         match app {
             AppType::Claude | AppType::Gemini => {
                 // 验证 JSON 格式
-                serde_json::from_str::<serde_json::Value>(&snippet)
-                    .map_err(|e| format!("无效的 JSON 格式: {e}"))?;
+                serde_json::from_str::<serde_json::Value>(&snippet).map_err(|e| {
+                    CommandError::from(AppError::InvalidInput(format!("无效的 JSON 格式: {e}")))
+                })?;
             }
             AppType::Codex => {
                 // TOML 格式暂不验证（或可使用 toml crate）
@@ -235,6 +260,174 @@ pub async fn set_common_config_snippet(
         },
     );
 
-    guard.save().map_err(|e| e.to_string())?;
+    guard.save().map_err(CommandError::from)?;
+    drop(guard);
+    crate::config_events::notify_changed();
     Ok(())
 }
+
+/// 获取当前配置修订号（配合 `config-changed` 事件做缓存失效）
+#[tauri::command]
+pub async fn get_config_revision() -> Result<u64, CommandError> {
+    Ok(crate::config_events::current_revision())
+}
+
+/// 列出已生效的供应商配置键迁移
+#[tauri::command]
+pub async fn list_applied_config_migrations(
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<Vec<crate::config_migrations::AppliedConfigMigration>, CommandError> {
+    let guard = state.config.read().map_err(CommandError::from)?;
+    Ok(crate::config_migrations::list_applied(
+        &guard.applied_config_migrations,
+    ))
+}
+
+/// 审计并收紧受管理配置文件的权限（文件 600、目录 700，仅 Unix）；`dryRun` 时只报告不修改
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn harden_config_permissions(
+    dryRun: Option<bool>,
+) -> Result<crate::services::PermissionReport, CommandError> {
+    Ok(crate::services::PermissionService::audit(
+        !dryRun.unwrap_or(false),
+    ))
+}
+
+/// 整体配置诊断：完整性、live 一致性、MCP、备份目录、权限与凭据，返回分类报告及可用的修复动作
+#[tauri::command]
+pub async fn run_doctor(
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<crate::services::DoctorReport, CommandError> {
+    crate::services::DoctorService::run(&state).map_err(CommandError::from)
+}
+
+/// 执行诊断报告中某条检查项的自动修复，返回修复后的最新报告
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn doctor_fix(
+    findingId: String,
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<crate::services::DoctorReport, CommandError> {
+    crate::services::DoctorService::fix(&state, &findingId).map_err(CommandError::from)
+}
+
+/// 列出 config.json 的历史版本（新到旧，含每个版本变化的字段路径）
+#[tauri::command]
+pub async fn list_config_versions(
+) -> Result<Vec<crate::config_history::ConfigVersion>, CommandError> {
+    crate::config_history::list().map_err(CommandError::from)
+}
+
+/// 比较两个历史版本之间的差异（`a` → `b`）
+#[tauri::command]
+pub async fn diff_config_versions(
+    a: u64,
+    b: u64,
+) -> Result<Vec<crate::config_history::ConfigChange>, CommandError> {
+    crate::config_history::diff(a, b).map_err(CommandError::from)
+}
+
+/// 将配置恢复到指定历史版本，返回恢复前自动创建的备份 ID
+#[tauri::command]
+pub async fn restore_config_version(
+    id: u64,
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<String, CommandError> {
+    crate::services::ConfigService::restore_version(&state, id).map_err(CommandError::from)
+}
+
+/// 分页列出 config.json 备份（新到旧），含时间、大小、配置版本与各应用供应商数量
+#[tauri::command]
+pub async fn list_backups_detailed(
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<crate::pagination::Page, CommandError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::services::ConfigService::list_backups_detailed(
+            offset.unwrap_or(0),
+            limit.unwrap_or(crate::pagination::DEFAULT_PAGE_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| {
+        AppError::localized(
+            "backup.list_failed",
+            format!("读取备份列表失败: {e}"),
+            format!("Failed to list backups: {e}"),
+        )
+        .into()
+    })
+}
+
+/// 将三个应用当前的 live 配置保存为命名检查点（手动编辑前留底）
+#[tauri::command]
+pub async fn create_live_checkpoint(
+    label: String,
+) -> Result<crate::services::LiveCheckpoint, CommandError> {
+    crate::services::ConfigService::create_live_checkpoint(&label).map_err(CommandError::from)
+}
+
+/// 列出 live 检查点（新到旧）
+#[tauri::command]
+pub async fn list_live_checkpoints() -> Result<Vec<crate::services::LiveCheckpoint>, CommandError> {
+    Ok(crate::services::ConfigService::list_live_checkpoints())
+}
+
+/// 将 live 配置恢复到指定检查点（任一应用失败时整体回滚）
+#[tauri::command]
+pub async fn restore_live_checkpoint(id: String) -> Result<bool, CommandError> {
+    crate::services::ConfigService::restore_live_checkpoint(&id)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 删除 live 检查点
+#[tauri::command]
+pub async fn delete_live_checkpoint(id: String) -> Result<bool, CommandError> {
+    crate::services::ConfigService::delete_live_checkpoint(&id)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 检查磁盘上的 config.json 是否已在 cc-switch 之外被修改（如同步盘带来其他机器的版本）
+#[tauri::command]
+pub async fn check_external_config_change(
+) -> Result<Option<crate::config_integrity::ExternalConfigChange>, CommandError> {
+    Ok(crate::config_integrity::external_change())
+}
+
+/// 放弃内存中的配置，重新加载外部修改后的 config.json
+#[tauri::command]
+pub async fn reload_external_config(
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<bool, CommandError> {
+    crate::services::ConfigService::reload_external(&state)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 以内存中的配置覆盖外部修改后的 config.json，返回覆盖前的备份 ID
+#[tauri::command]
+pub async fn overwrite_external_config(
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<String, CommandError> {
+    crate::services::ConfigService::overwrite_external(&state).map_err(CommandError::from)
+}
+
+/// 检查原始配置编辑器中的内容，返回带行列位置的诊断
+#[tauri::command]
+pub async fn validate_editor_content(
+    app: String,
+    kind: crate::editor_diagnostics::EditorKind,
+    text: String,
+) -> Result<Vec<crate::editor_diagnostics::EditorDiagnostic>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    crate::editor_diagnostics::validate(&app_type, kind, &text).map_err(CommandError::from)
+}
+
+/// 列出 cc-switch 会写入的全部文件（是否存在、大小、修改时间与最后写入者）
+#[tauri::command]
+pub async fn list_managed_files() -> Result<Vec<crate::managed_files::ManagedFile>, CommandError> {
+    Ok(crate::managed_files::list())
+}
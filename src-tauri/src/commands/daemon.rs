@@ -0,0 +1,29 @@
+use std::str::FromStr;
+
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::daemon::{self, DaemonEvent, DaemonStatus};
+use crate::error::CommandError;
+use crate::store::AppState;
+
+/// 获取守护模式运行状态（巡检与故障转移事件、控制接口地址等）
+#[tauri::command]
+pub fn daemon_status() -> Result<DaemonStatus, CommandError> {
+    Ok(daemon::status())
+}
+
+/// 调试用：模拟供应商故障 `durationSecs` 秒（为 0 时取消），返回立即触发的故障转移事件
+#[tauri::command]
+pub async fn simulate_provider_outage(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    #[allow(non_snake_case)] durationSecs: u64,
+) -> Result<Vec<DaemonEvent>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    daemon::simulate_outage(&handle, state.inner(), app_type, &providerId, durationSecs)
+        .await
+        .map_err(CommandError::from)
+}
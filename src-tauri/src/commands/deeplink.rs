@@ -1,29 +1,83 @@
-use crate::deeplink::{import_provider_from_deeplink, parse_deeplink_url, DeepLinkImportRequest};
+use crate::app_config::AppType;
+use crate::deeplink::{
+    build_provider_deeplink, import_provider_from_deeplink, parse_deeplink_url,
+    DeepLinkImportRequest,
+};
+use crate::error::{AppError, CommandError};
+use crate::services::{CapabilityService, PasscodeService};
 use crate::store::AppState;
+use std::str::FromStr;
 use tauri::State;
 
 /// Parse a deep link URL and return the parsed request for frontend confirmation
 #[tauri::command]
-pub fn parse_deeplink(url: String) -> Result<DeepLinkImportRequest, String> {
+pub fn parse_deeplink(url: String) -> Result<DeepLinkImportRequest, CommandError> {
     log::info!("Parsing deep link URL: {url}");
-    parse_deeplink_url(&url).map_err(|e| e.to_string())
+    parse_deeplink_url(&url).map_err(CommandError::from)
 }
 
 /// Import a provider from a deep link request (after user confirmation)
+///
+/// For Codex links without `wireApi`, the endpoint is probed to choose between
+/// the Responses and Chat Completions wire APIs.
 #[tauri::command]
-pub fn import_from_deeplink(
-    state: State<AppState>,
-    request: DeepLinkImportRequest,
-) -> Result<String, String> {
+pub async fn import_from_deeplink(
+    state: State<'_, AppState>,
+    mut request: DeepLinkImportRequest,
+) -> Result<String, CommandError> {
     log::info!(
         "Importing provider from deep link: {} for app {}",
         request.name,
         request.app
     );
 
-    let provider_id = import_provider_from_deeplink(&state, request).map_err(|e| e.to_string())?;
+    if request.app == "codex" && request.wire_api.is_none() {
+        match CapabilityService::detect_codex_wire_api(&request.endpoint, &request.api_key).await {
+            Ok(Some(wire_api)) => request.wire_api = Some(wire_api.to_string()),
+            Ok(None) => log::warn!("Could not detect wire_api for {}", request.endpoint),
+            Err(e) => log::warn!("Failed to probe wire_api for {}: {e}", request.endpoint),
+        }
+    }
+
+    let provider_id = import_provider_from_deeplink(&state, request).map_err(CommandError::from)?;
 
     log::info!("Successfully imported provider with ID: {provider_id}");
 
     Ok(provider_id)
 }
+
+/// Generate a ccswitch:// deep link for an existing provider
+///
+/// Embedding the API key requires a valid unlock token when an app passcode is set.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub fn generate_deeplink(
+    state: State<AppState>,
+    app: String,
+    providerId: String,
+    includeApiKey: Option<bool>,
+    unlockToken: Option<String>,
+) -> Result<String, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let include_api_key = includeApiKey.unwrap_or(false);
+    if include_api_key {
+        PasscodeService::require_unlocked(unlockToken.as_deref()).map_err(CommandError::from)?;
+    }
+
+    let config = state
+        .config
+        .read()
+        .map_err(|e| CommandError::from(AppError::from(e)))?;
+    let provider = config
+        .get_manager(&app_type)
+        .and_then(|manager| manager.providers.get(&providerId))
+        .ok_or_else(|| {
+            AppError::localized(
+                "provider.not_found",
+                format!("供应商不存在: {providerId}"),
+                format!("Provider not found: {providerId}"),
+            )
+        })?;
+
+    build_provider_deeplink(&app_type, provider, include_api_key).map_err(CommandError::from)
+}
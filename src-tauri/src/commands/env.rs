@@ -1,22 +1,41 @@
-use crate::services::env_checker::{check_env_conflicts as check_conflicts, EnvConflict};
+use crate::error::{AppError, CommandError};
+use crate::services::env_checker::{
+    check_env_conflicts as check_conflicts, detect_installed_clis as detect_clis, EnvConflict,
+    InstalledCli,
+};
 use crate::services::env_manager::{
     delete_env_vars as delete_vars, restore_from_backup, BackupInfo,
 };
 
 /// Check environment variable conflicts for a specific app
 #[tauri::command]
-pub fn check_env_conflicts(app: String) -> Result<Vec<EnvConflict>, String> {
-    check_conflicts(&app)
+pub fn check_env_conflicts(app: String) -> Result<Vec<EnvConflict>, CommandError> {
+    check_conflicts(&app).map_err(CommandError::from)
 }
 
 /// Delete environment variables with backup
 #[tauri::command]
-pub fn delete_env_vars(conflicts: Vec<EnvConflict>) -> Result<BackupInfo, String> {
-    delete_vars(conflicts)
+pub fn delete_env_vars(conflicts: Vec<EnvConflict>) -> Result<BackupInfo, CommandError> {
+    delete_vars(conflicts).map_err(CommandError::from)
 }
 
 /// Restore environment variables from backup file
 #[tauri::command]
-pub fn restore_env_backup(backup_path: String) -> Result<(), String> {
-    restore_from_backup(backup_path)
+pub fn restore_env_backup(backup_path: String) -> Result<(), CommandError> {
+    restore_from_backup(backup_path).map_err(CommandError::from)
+}
+
+/// Detect installed claude / codex / gemini CLIs and their versions
+#[tauri::command]
+pub async fn detect_installed_clis() -> Result<Vec<InstalledCli>, CommandError> {
+    tauri::async_runtime::spawn_blocking(detect_clis)
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "env.detect_cli_failed",
+                format!("检测已安装的 CLI 失败: {e}"),
+                format!("Failed to detect CLIs: {e}"),
+            )
+            .into()
+        })
 }
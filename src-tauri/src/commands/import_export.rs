@@ -2,18 +2,26 @@
 
 use serde_json::{json, Value};
 use std::path::PathBuf;
+use std::str::FromStr;
 use tauri::State;
 use tauri_plugin_dialog::DialogExt;
 
-use crate::error::AppError;
-use crate::services::ConfigService;
+use crate::app_config::AppType;
+use crate::error::{AppError, CommandError};
+use crate::services::{
+    ConfigService, DiagnosticsService, ImportMergeStrategy, PasscodeService, ShareService,
+    SharedSnippet,
+};
 use crate::store::AppState;
 
 /// 导出配置文件
 #[tauri::command]
 pub async fn export_config_to_file(
     #[allow(non_snake_case)] filePath: String,
-) -> Result<Value, String> {
+    #[allow(non_snake_case)] unlockToken: Option<String>,
+) -> Result<Value, CommandError> {
+    // 导出文件包含全部供应商密钥，设置了应用口令时需先解锁
+    PasscodeService::require_unlocked(unlockToken.as_deref()).map_err(CommandError::from)?;
     tauri::async_runtime::spawn_blocking(move || {
         let target_path = PathBuf::from(&filePath);
         ConfigService::export_config_to_path(&target_path)?;
@@ -24,8 +32,99 @@ pub async fn export_config_to_file(
         }))
     })
     .await
-    .map_err(|e| format!("导出配置失败: {e}"))?
-    .map_err(|e: AppError| e.to_string())
+    .map_err(|e| {
+        AppError::localized(
+            "export.failed",
+            format!("导出配置失败: {e}"),
+            format!("Failed to export config: {e}"),
+        )
+    })?
+    .map_err(CommandError::from)
+}
+
+/// 导出去除密钥的配置模板（可提交到版本库），返回模板所需的密钥清单
+#[tauri::command]
+pub async fn export_config_template(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::config_template::TemplateSecret>, CommandError> {
+    ConfigService::export_config_template(state.inner(), &PathBuf::from(path))
+        .map_err(CommandError::from)
+}
+
+/// 用 `secrets`（占位符名称 → 密钥）填充配置模板并替换当前配置，返回备份 ID
+#[tauri::command]
+pub async fn apply_config_template(
+    path: String,
+    secrets: std::collections::HashMap<String, String>,
+    state: State<'_, AppState>,
+) -> Result<String, CommandError> {
+    ConfigService::apply_config_template(state.inner(), &PathBuf::from(path), &secrets)
+        .map_err(CommandError::from)
+}
+
+/// 加密分享单个供应商配置（上传到私有粘贴服务，或生成离线分享码）
+///
+/// `ttl` 为有效期秒数；未提供 `passphrase` 时自动生成
+#[tauri::command]
+pub async fn share_config_snippet(
+    state: State<'_, AppState>,
+    app: String,
+    providerId: String,
+    ttl: Option<u64>,
+    passphrase: Option<String>,
+    unlockToken: Option<String>,
+) -> Result<SharedSnippet, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ShareService::share(
+        state.inner(),
+        app_type,
+        &providerId,
+        ttl,
+        passphrase,
+        unlockToken.as_deref(),
+    )
+    .await
+    .map_err(CommandError::from)
+}
+
+/// 导入分享码（离线密文或粘贴服务 key），返回新供应商 ID
+#[tauri::command]
+pub async fn import_shared_snippet(
+    state: State<'_, AppState>,
+    code: String,
+    passphrase: String,
+) -> Result<String, CommandError> {
+    ShareService::import(state.inner(), &code, &passphrase)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 生成诊断包（版本、系统、脱敏设置、最近日志、配置概要、CLI 检测结果）
+#[tauri::command]
+pub async fn generate_diagnostics_bundle(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<String, CommandError> {
+    let config = state
+        .config
+        .read()
+        .map_err(|e| CommandError::from(AppError::from(e)))?
+        .clone();
+    let target_path = PathBuf::from(&path);
+    tauri::async_runtime::spawn_blocking(move || {
+        DiagnosticsService::generate_bundle(&config, &target_path)
+    })
+    .await
+    .map_err(|e| {
+        AppError::localized(
+            "diagnostics.bundle_failed",
+            format!("生成诊断包失败: {e}"),
+            format!("Failed to generate diagnostics bundle: {e}"),
+        )
+    })?
+    .map_err(CommandError::from)?;
+    Ok(path)
 }
 
 /// 从文件导入配置
@@ -33,20 +132,26 @@ pub async fn export_config_to_file(
 pub async fn import_config_from_file(
     #[allow(non_snake_case)] filePath: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CommandError> {
     let (new_config, backup_id) = tauri::async_runtime::spawn_blocking(move || {
         let path_buf = PathBuf::from(&filePath);
         ConfigService::load_config_for_import(&path_buf)
     })
     .await
-    .map_err(|e| format!("导入配置失败: {e}"))?
-    .map_err(|e: AppError| e.to_string())?;
+    .map_err(|e| {
+        AppError::localized(
+            "import.failed",
+            format!("导入配置失败: {e}"),
+            format!("Failed to import config: {e}"),
+        )
+    })?
+    .map_err(CommandError::from)?;
 
     {
         let mut guard = state
             .config
             .write()
-            .map_err(|e| AppError::from(e).to_string())?;
+            .map_err(|e| CommandError::from(AppError::from(e)))?;
         *guard = new_config;
     }
 
@@ -57,21 +162,59 @@ pub async fn import_config_from_file(
     }))
 }
 
-/// 同步当前供应商配置到对应的 live 文件
+/// 从 URL 下载配置并导入（仅 https；`mergeStrategy` 默认整体替换）
 #[tauri::command]
-pub async fn sync_current_providers_live(state: State<'_, AppState>) -> Result<Value, String> {
-    {
+pub async fn import_config_from_url(
+    url: String,
+    mergeStrategy: Option<ImportMergeStrategy>,
+    state: State<'_, AppState>,
+) -> Result<Value, CommandError> {
+    let backup_id = ConfigService::import_config_from_url(
+        state.inner(),
+        &url,
+        mergeStrategy.unwrap_or_default(),
+    )
+    .await
+    .map_err(CommandError::from)?;
+
+    Ok(json!({
+        "success": true,
+        "message": "Configuration imported successfully",
+        "backupId": backup_id
+    }))
+}
+
+/// 同步当前供应商配置到对应的 live 文件（各应用并行写入，返回每个应用的结果）
+#[tauri::command]
+pub async fn sync_current_providers_live(
+    state: State<'_, AppState>,
+) -> Result<Value, CommandError> {
+    let results = {
         let mut config_state = state
             .config
             .write()
-            .map_err(|e| AppError::from(e).to_string())?;
-        ConfigService::sync_current_providers_to_live(&mut config_state)
-            .map_err(|e| e.to_string())?;
-    }
+            .map_err(|e| CommandError::from(AppError::from(e)))?;
+        ConfigService::sync_current_providers_to_live_report(&mut config_state)
+            .map_err(CommandError::from)?
+    };
+    let failed: Vec<String> = results
+        .iter()
+        .filter_map(|result| {
+            result
+                .error
+                .as_ref()
+                .map(|error| format!("{}: {error}", result.app.as_str()))
+        })
+        .collect();
 
     Ok(json!({
-        "success": true,
-        "message": "Live configuration synchronized"
+        "success": failed.is_empty(),
+        "message": if failed.is_empty() {
+            "Live configuration synchronized".to_string()
+        } else {
+            failed.join("\n")
+        },
+        "results": results,
     }))
 }
 
@@ -80,7 +223,7 @@ pub async fn sync_current_providers_live(state: State<'_, AppState>) -> Result<V
 pub async fn save_file_dialog<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
     #[allow(non_snake_case)] defaultName: String,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CommandError> {
     let dialog = app.dialog();
     let result = dialog
         .file()
@@ -95,7 +238,7 @@ pub async fn save_file_dialog<R: tauri::Runtime>(
 #[tauri::command]
 pub async fn open_file_dialog<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CommandError> {
     let dialog = app.dialog();
     let result = dialog
         .file()
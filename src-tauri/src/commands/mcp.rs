@@ -1,43 +1,48 @@
 #![allow(non_snake_case)]
 
 use std::collections::HashMap;
+use std::path::Path;
 
 use serde::Serialize;
 use tauri::State;
 
 use crate::app_config::AppType;
 use crate::claude_mcp;
-use crate::services::McpService;
+use crate::project_mcp::ProjectMcpImport;
+use crate::services::{McpHealthService, McpService, McpUsageService, McpUsageStat};
 use crate::store::AppState;
 
 /// 获取 Claude MCP 状态
 #[tauri::command]
-pub async fn get_claude_mcp_status() -> Result<claude_mcp::McpStatus, String> {
-    claude_mcp::get_mcp_status().map_err(|e| e.to_string())
+pub async fn get_claude_mcp_status() -> Result<claude_mcp::McpStatus, CommandError> {
+    claude_mcp::get_mcp_status().map_err(CommandError::from)
 }
 
 /// 读取 mcp.json 文本内容
 #[tauri::command]
-pub async fn read_claude_mcp_config() -> Result<Option<String>, String> {
-    claude_mcp::read_mcp_json().map_err(|e| e.to_string())
+pub async fn read_claude_mcp_config() -> Result<Option<String>, CommandError> {
+    claude_mcp::read_mcp_json().map_err(CommandError::from)
 }
 
 /// 新增或更新一个 MCP 服务器条目
 #[tauri::command]
-pub async fn upsert_claude_mcp_server(id: String, spec: serde_json::Value) -> Result<bool, String> {
-    claude_mcp::upsert_mcp_server(&id, spec).map_err(|e| e.to_string())
+pub async fn upsert_claude_mcp_server(
+    id: String,
+    spec: serde_json::Value,
+) -> Result<bool, CommandError> {
+    claude_mcp::upsert_mcp_server(&id, spec).map_err(CommandError::from)
 }
 
 /// 删除一个 MCP 服务器条目
 #[tauri::command]
-pub async fn delete_claude_mcp_server(id: String) -> Result<bool, String> {
-    claude_mcp::delete_mcp_server(&id).map_err(|e| e.to_string())
+pub async fn delete_claude_mcp_server(id: String) -> Result<bool, CommandError> {
+    claude_mcp::delete_mcp_server(&id).map_err(CommandError::from)
 }
 
 /// 校验命令是否在 PATH 中可用（不执行）
 #[tauri::command]
-pub async fn validate_mcp_command(cmd: String) -> Result<bool, String> {
-    claude_mcp::validate_command_in_path(&cmd).map_err(|e| e.to_string())
+pub async fn validate_mcp_command(cmd: String) -> Result<bool, CommandError> {
+    claude_mcp::validate_command_in_path(&cmd).map_err(CommandError::from)
 }
 
 #[derive(Serialize)]
@@ -46,6 +51,7 @@ pub struct McpConfigResponse {
     pub servers: HashMap<String, serde_json::Value>,
 }
 
+use crate::error::CommandError;
 /// 获取 MCP 配置（来自 ~/.cc-switch/config.json）
 use std::str::FromStr;
 
@@ -54,12 +60,12 @@ use std::str::FromStr;
 pub async fn get_mcp_config(
     state: State<'_, AppState>,
     app: String,
-) -> Result<McpConfigResponse, String> {
+) -> Result<McpConfigResponse, CommandError> {
     let config_path = crate::config::get_app_config_path()
         .to_string_lossy()
         .to_string();
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    let servers = McpService::get_servers(&state, app_ty).map_err(|e| e.to_string())?;
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    let servers = McpService::get_servers(&state, app_ty).map_err(CommandError::from)?;
     Ok(McpConfigResponse {
         config_path,
         servers,
@@ -75,14 +81,14 @@ pub async fn upsert_mcp_server_in_config(
     id: String,
     spec: serde_json::Value,
     sync_other_side: Option<bool>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     use crate::app_config::McpServer;
 
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
 
     // 读取现有的服务器（如果存在）
     let existing_server = {
-        let cfg = state.config.read().map_err(|e| e.to_string())?;
+        let cfg = state.config.read().map_err(CommandError::from)?;
         if let Some(servers) = &cfg.mcp.servers {
             servers.get(&id).cloned()
         } else {
@@ -117,6 +123,8 @@ pub async fn upsert_mcp_server_in_config(
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            health: None,
+            overrides: Default::default(),
         }
     };
 
@@ -129,7 +137,7 @@ pub async fn upsert_mcp_server_in_config(
 
     McpService::upsert_server(&state, new_server)
         .map(|_| true)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 在 config.json 中删除一个 MCP 服务器定义
@@ -138,8 +146,8 @@ pub async fn delete_mcp_server_in_config(
     state: State<'_, AppState>,
     _app: String, // 参数保留用于向后兼容，但在统一结构中不再需要
     id: String,
-) -> Result<bool, String> {
-    McpService::delete_server(&state, &id).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    McpService::delete_server(&state, &id).map_err(CommandError::from)
 }
 
 /// 设置启用状态并同步到客户端配置
@@ -150,9 +158,9 @@ pub async fn set_mcp_enabled(
     app: String,
     id: String,
     enabled: bool,
-) -> Result<bool, String> {
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    McpService::set_enabled(&state, app_ty, &id, enabled).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    McpService::set_enabled(&state, app_ty, &id, enabled).map_err(CommandError::from)
 }
 
 // ============================================================================
@@ -160,13 +168,32 @@ pub async fn set_mcp_enabled(
 // ============================================================================
 
 use crate::app_config::McpServer;
+use crate::mcp::McpSyncPreview;
+use crate::pagination::{Page, DEFAULT_PAGE_LIMIT};
 
 /// 获取所有 MCP 服务器（统一结构）
 #[tauri::command]
 pub async fn get_mcp_servers(
     state: State<'_, AppState>,
-) -> Result<HashMap<String, McpServer>, String> {
-    McpService::get_all_servers(&state).map_err(|e| e.to_string())
+) -> Result<HashMap<String, McpServer>, CommandError> {
+    McpService::get_all_servers(&state).map_err(CommandError::from)
+}
+
+/// 分页获取 MCP 服务器（字段投影；未指定 fields 时不返回 server 定义）
+#[tauri::command]
+pub async fn get_mcp_servers_page(
+    state: State<'_, AppState>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    fields: Option<Vec<String>>,
+) -> Result<Page, CommandError> {
+    McpService::list_page(
+        &state,
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_PAGE_LIMIT),
+        fields.as_deref(),
+    )
+    .map_err(CommandError::from)
 }
 
 /// 添加或更新 MCP 服务器
@@ -174,14 +201,39 @@ pub async fn get_mcp_servers(
 pub async fn upsert_mcp_server(
     state: State<'_, AppState>,
     server: McpServer,
-) -> Result<(), String> {
-    McpService::upsert_server(&state, server).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    McpService::upsert_server(&state, server).map_err(CommandError::from)
 }
 
 /// 删除 MCP 服务器
 #[tauri::command]
-pub async fn delete_mcp_server(state: State<'_, AppState>, id: String) -> Result<bool, String> {
-    McpService::delete_server(&state, &id).map_err(|e| e.to_string())
+pub async fn delete_mcp_server(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<bool, CommandError> {
+    McpService::delete_server(&state, &id).map_err(CommandError::from)
+}
+
+/// 复制 MCP 服务器为新 ID
+#[allow(non_snake_case)]
+#[tauri::command]
+pub async fn duplicate_mcp_server(
+    state: State<'_, AppState>,
+    id: String,
+    newId: String,
+) -> Result<McpServer, CommandError> {
+    McpService::duplicate_server(&state, &id, &newId).map_err(CommandError::from)
+}
+
+/// 重命名 MCP 服务器 ID
+#[allow(non_snake_case)]
+#[tauri::command]
+pub async fn rename_mcp_server(
+    state: State<'_, AppState>,
+    id: String,
+    newId: String,
+) -> Result<McpServer, CommandError> {
+    McpService::rename_server(&state, &id, &newId).map_err(CommandError::from)
 }
 
 /// 切换 MCP 服务器在指定应用的启用状态
@@ -191,7 +243,60 @@ pub async fn toggle_mcp_app(
     server_id: String,
     app: String,
     enabled: bool,
-) -> Result<(), String> {
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    McpService::toggle_app(&state, &server_id, app_ty, enabled).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    McpService::toggle_app(&state, &server_id, app_ty, enabled).map_err(CommandError::from)
+}
+
+/// 预览 MCP 同步到指定应用将产生的变更（dry-run）
+#[tauri::command]
+pub async fn preview_mcp_sync(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<McpSyncPreview, CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    McpService::preview_sync(&state, app_ty).map_err(CommandError::from)
+}
+
+/// 统计 Claude 会话记录中各 MCP 服务器的工具调用次数
+#[tauri::command]
+pub async fn get_mcp_usage_stats(
+    state: State<'_, AppState>,
+) -> Result<Vec<McpUsageStat>, CommandError> {
+    McpUsageService::stats(&state).map_err(CommandError::from)
+}
+
+/// 获取最近一次健康检查中不健康的 MCP 服务器
+#[tauri::command]
+pub async fn get_unhealthy_mcp_servers(
+    state: State<'_, AppState>,
+) -> Result<Vec<McpServer>, CommandError> {
+    McpHealthService::unhealthy(&state).map_err(CommandError::from)
+}
+
+/// 立即检查全部 MCP 服务器的健康状态，返回不健康的服务器
+#[tauri::command]
+pub async fn check_mcp_health(state: State<'_, AppState>) -> Result<Vec<McpServer>, CommandError> {
+    McpHealthService::check_all(&state)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 从项目目录（或 `.mcp.json` 文件）导入 MCP 服务器
+#[tauri::command]
+pub async fn import_mcp_from_project(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ProjectMcpImport, CommandError> {
+    McpService::import_from_project(&state, Path::new(&path)).map_err(CommandError::from)
+}
+
+/// 将选中的 MCP 服务器导出为项目的 `.mcp.json`，返回写入的文件路径
+#[tauri::command]
+pub async fn export_mcp_to_project(
+    state: State<'_, AppState>,
+    path: String,
+    ids: Vec<String>,
+) -> Result<String, CommandError> {
+    McpService::export_to_project(&state, Path::new(&path), &ids).map_err(CommandError::from)
 }
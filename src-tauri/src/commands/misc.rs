@@ -1,43 +1,57 @@
 #![allow(non_snake_case)]
 
+use crate::error::{AppError, CommandError};
 use crate::init_status::InitErrorPayload;
 use tauri::AppHandle;
 use tauri_plugin_opener::OpenerExt;
 
 /// 打开外部链接
 #[tauri::command]
-pub async fn open_external(app: AppHandle, url: String) -> Result<bool, String> {
+pub async fn open_external(app: AppHandle, url: String) -> Result<bool, CommandError> {
     let url = if url.starts_with("http://") || url.starts_with("https://") {
         url
     } else {
         format!("https://{url}")
     };
 
-    app.opener()
-        .open_url(&url, None::<String>)
-        .map_err(|e| format!("打开链接失败: {e}"))?;
+    app.opener().open_url(&url, None::<String>).map_err(|e| {
+        AppError::localized(
+            "system.open_url_failed",
+            format!("打开链接失败: {e}"),
+            format!("Failed to open link: {e}"),
+        )
+    })?;
 
     Ok(true)
 }
 
 /// 检查更新
 #[tauri::command]
-pub async fn check_for_updates(handle: AppHandle) -> Result<bool, String> {
+pub async fn check_for_updates(handle: AppHandle) -> Result<bool, CommandError> {
     handle
         .opener()
         .open_url(
             "https://github.com/farion1231/cc-switch/releases/latest",
             None::<String>,
         )
-        .map_err(|e| format!("打开更新页面失败: {e}"))?;
+        .map_err(|e| {
+            AppError::localized(
+                "system.open_url_failed",
+                format!("打开更新页面失败: {e}"),
+                format!("Failed to open the release page: {e}"),
+            )
+        })?;
 
     Ok(true)
 }
 
 /// 判断是否为便携版（绿色版）运行
 #[tauri::command]
-pub async fn is_portable_mode() -> Result<bool, String> {
-    let exe_path = std::env::current_exe().map_err(|e| format!("获取可执行路径失败: {e}"))?;
+pub async fn is_portable_mode() -> Result<bool, CommandError> {
+    let exe_path = std::env::current_exe().map_err(|source| AppError::IoContext {
+        context: "获取可执行路径失败".to_string(),
+        source,
+    })?;
     if let Some(dir) = exe_path.parent() {
         Ok(dir.join("portable.ini").is_file())
     } else {
@@ -45,9 +59,122 @@ pub async fn is_portable_mode() -> Result<bool, String> {
     }
 }
 
+/// 列出命令错误的 `code` 及说明（`Localized` 错误以其 i18n key 作为 code）
+#[tauri::command]
+pub fn error_codes() -> Vec<crate::error::ErrorCodeInfo> {
+    crate::error::error_codes()
+}
+
 /// 获取应用启动阶段的初始化错误（若有）。
 /// 用于前端在早期主动拉取，避免事件订阅竞态导致的提示缺失。
 #[tauri::command]
-pub async fn get_init_error() -> Result<Option<InitErrorPayload>, String> {
+pub async fn get_init_error() -> Result<Option<InitErrorPayload>, CommandError> {
     Ok(crate::init_status::get_init_error())
 }
+
+/// 获取启动时检测到的配置完整性警告（如 config.json 被外部修改）
+#[tauri::command]
+pub async fn get_config_integrity_report(
+) -> Result<Option<crate::config_integrity::IntegrityReport>, CommandError> {
+    Ok(crate::init_status::get_integrity_warning())
+}
+
+/// 修复无法加载的 config.json：从备份恢复或重置（原文件另存为 config.json.broken-*）
+/// 配置加载失败时 AppState 不存在，因此该命令不依赖应用状态；修复后需重启应用
+#[tauri::command]
+pub async fn repair_config(
+    kind: crate::config_integrity::RepairKind,
+    path: Option<String>,
+) -> Result<(), CommandError> {
+    crate::config_integrity::repair(kind, path.as_deref()).map_err(CommandError::from)
+}
+
+/// 获取最近的日志（level 为最低严重级别，默认返回全部级别；limit 默认 200）
+#[tauri::command]
+pub async fn get_recent_logs(
+    level: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<crate::logging::LogEntry>, CommandError> {
+    let level = level
+        .map(|value| {
+            serde_json::from_value::<crate::settings::LogLevel>(serde_json::Value::String(
+                value.to_lowercase(),
+            ))
+            .map_err(|_| {
+                AppError::localized(
+                    "logs.invalid_level",
+                    format!("无效的日志级别: {value}"),
+                    format!("Invalid log level: {value}"),
+                )
+            })
+        })
+        .transpose()?;
+    let limit = limit.unwrap_or(200).min(5000);
+
+    tauri::async_runtime::spawn_blocking(move || crate::logging::recent_logs(level, limit))
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "logs.read_failed",
+                format!("读取日志失败: {e}"),
+                format!("Failed to read logs: {e}"),
+            )
+        })?
+        .map_err(CommandError::from)
+}
+
+/// 打开日志目录
+#[tauri::command]
+pub async fn open_log_folder(handle: AppHandle) -> Result<bool, CommandError> {
+    let log_dir = crate::logging::log_dir();
+    if !log_dir.exists() {
+        std::fs::create_dir_all(&log_dir)
+            .map_err(|e| CommandError::from(AppError::io(&log_dir, e)))?;
+    }
+
+    handle
+        .opener()
+        .open_path(log_dir.to_string_lossy().to_string(), None::<String>)
+        .map_err(|e| {
+            AppError::localized(
+                "system.open_path_failed",
+                format!("打开文件夹失败: {e}"),
+                format!("Failed to open folder: {e}"),
+            )
+        })?;
+
+    Ok(true)
+}
+
+/// 获取本地崩溃记录
+#[tauri::command]
+pub async fn list_crash_reports() -> Result<Vec<crate::crash_reporter::CrashReport>, CommandError> {
+    Ok(crate::crash_reporter::list_reports())
+}
+
+/// 清空本地崩溃记录，返回删除数量
+#[tauri::command]
+pub async fn clear_crash_reports() -> Result<usize, CommandError> {
+    crate::crash_reporter::clear_reports().map_err(CommandError::from)
+}
+
+/// 记录前端捕获的未处理命令错误
+#[tauri::command]
+pub async fn report_command_error(
+    command: String,
+    message: String,
+) -> Result<crate::crash_reporter::CrashReport, CommandError> {
+    crate::crash_reporter::record_command_error(&command, &message).map_err(CommandError::from)
+}
+
+/// 本地统计文本 token 数（OpenAI 系为精确值，Claude / Gemini 为近似值）
+#[tauri::command]
+pub async fn count_tokens(
+    modelFamily: String,
+    text: String,
+) -> Result<crate::tokens::TokenCount, CommandError> {
+    let family = modelFamily
+        .parse::<crate::tokens::ModelFamily>()
+        .map_err(CommandError::from)?;
+    Ok(crate::tokens::count_tokens(family, &text))
+}
@@ -1,25 +1,59 @@
 #![allow(non_snake_case)]
 
+mod ab_test;
+mod adapter;
+mod agent;
+mod claude_hooks;
 mod config;
+mod daemon;
 mod deeplink;
 mod env;
 mod import_export;
 mod mcp;
 mod misc;
+mod passcode;
 mod plugin;
 mod prompt;
 mod provider;
+mod proxy;
+mod registry;
+mod remote_host;
+mod search;
 mod settings;
+mod shell_wrapper;
 pub mod skill;
+mod slash_command;
+mod statusline;
+mod subscription;
+mod switch_rule;
+mod team_pack;
+mod wsl;
 
+pub use ab_test::*;
+pub use adapter::*;
+pub use agent::*;
+pub use claude_hooks::*;
 pub use config::*;
+pub use daemon::*;
 pub use deeplink::*;
 pub use env::*;
 pub use import_export::*;
 pub use mcp::*;
 pub use misc::*;
+pub use passcode::*;
 pub use plugin::*;
 pub use prompt::*;
 pub use provider::*;
+pub use proxy::*;
+pub use registry::*;
+pub use remote_host::*;
+pub use search::*;
 pub use settings::*;
+pub use shell_wrapper::*;
 pub use skill::*;
+pub use slash_command::*;
+pub use statusline::*;
+pub use subscription::*;
+pub use switch_rule::*;
+pub use team_pack::*;
+pub use wsl::*;
@@ -0,0 +1,32 @@
+use crate::error::CommandError;
+use crate::services::{PasscodeService, UnlockToken};
+
+/// 是否已设置应用口令
+#[tauri::command]
+pub fn get_passcode_status() -> Result<bool, CommandError> {
+    Ok(PasscodeService::is_enabled())
+}
+
+/// 设置、修改或清除应用口令（passcode 为空表示清除）
+#[tauri::command]
+pub fn set_passcode(
+    current: Option<String>,
+    passcode: Option<String>,
+) -> Result<bool, CommandError> {
+    PasscodeService::set(current.as_deref(), passcode.as_deref())
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 使用口令换取短时有效的解锁令牌
+#[tauri::command]
+pub fn unlock_secrets(passcode: String) -> Result<UnlockToken, CommandError> {
+    PasscodeService::unlock(&passcode).map_err(CommandError::from)
+}
+
+/// 立即作废所有解锁令牌
+#[tauri::command]
+pub fn lock_secrets() -> Result<bool, CommandError> {
+    PasscodeService::lock();
+    Ok(true)
+}
@@ -4,7 +4,9 @@ use std::str::FromStr;
 use tauri::State;
 
 use crate::app_config::AppType;
+use crate::error::CommandError;
 use crate::prompt::Prompt;
+use crate::services::prompt::GlobalInstructions;
 use crate::services::PromptService;
 use crate::store::AppState;
 
@@ -12,9 +14,9 @@ use crate::store::AppState;
 pub async fn get_prompts(
     app: String,
     state: State<'_, AppState>,
-) -> Result<HashMap<String, Prompt>, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::get_prompts(&state, app_type).map_err(|e| e.to_string())
+) -> Result<HashMap<String, Prompt>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::get_prompts(&state, app_type).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -23,9 +25,9 @@ pub async fn upsert_prompt(
     id: String,
     prompt: Prompt,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::upsert_prompt(&state, app_type, &id, prompt).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::upsert_prompt(&state, app_type, &id, prompt).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -33,9 +35,9 @@ pub async fn delete_prompt(
     app: String,
     id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::delete_prompt(&state, app_type, &id).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::delete_prompt(&state, app_type, &id).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -43,22 +45,39 @@ pub async fn enable_prompt(
     app: String,
     id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::enable_prompt(&state, app_type, &id).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::enable_prompt(&state, app_type, &id).map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn import_prompt_from_file(
     app: String,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::import_from_file(&state, app_type).map_err(|e| e.to_string())
+) -> Result<String, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::import_from_file(&state, app_type).map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_current_prompt_file_content(app: String) -> Result<Option<String>, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::get_current_file_content(app_type).map_err(|e| e.to_string())
+pub async fn get_current_prompt_file_content(app: String) -> Result<Option<String>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::get_current_file_content(app_type).map_err(CommandError::from)
+}
+
+/// 读取全局指令文件（含 cc-switch 管理区段）
+#[tauri::command]
+pub async fn read_global_instructions(app: String) -> Result<GlobalInstructions, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::read_global_instructions(app_type).map_err(CommandError::from)
+}
+
+/// 写入全局指令文件的 cc-switch 管理区段（写入前自动备份）
+#[tauri::command]
+pub async fn write_global_instructions(
+    app: String,
+    content: String,
+) -> Result<GlobalInstructions, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::write_global_instructions(app_type, &content).map_err(CommandError::from)
 }
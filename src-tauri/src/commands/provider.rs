@@ -1,28 +1,95 @@
 use std::collections::HashMap;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 use crate::app_config::AppType;
-use crate::error::AppError;
-use crate::provider::Provider;
-use crate::services::{EndpointLatency, ProviderService, ProviderSortUpdate, SpeedtestService};
+use crate::error::{AppError, CommandError};
+use crate::pagination::{Page, DEFAULT_PAGE_LIMIT};
+use crate::provider::{ClaudeSettingsOverrides, OrderedProviders, Provider, ProviderPerformance};
+use crate::scheduler::{self, TemporarySwitch};
+use crate::services::env_checker::detect_installed_clis;
+use crate::services::passcode::mask_secrets;
+use crate::services::{
+    AuthenticatedProbe, BillingService, CapabilityReport, CapabilityService, CompareService,
+    DefaultImportResult, EndpointLatency, PasscodeService, ProviderRank, ProviderSearchFilters,
+    ProviderSearchHit, ProviderService, ProviderSortUpdate, RankingService, RankingWeights,
+    SandboxResult, SandboxService, SpeedtestService, SwitchOutcome, UpcomingRenewal,
+    VerificationService, WireApiFix,
+};
 use crate::store::AppState;
 use std::str::FromStr;
 
-/// 获取所有供应商
+/// 临时切换的最长时长（分钟）
+const MAX_TEMPORARY_SWITCH_MINUTES: u32 = 24 * 60;
+
+/// 获取所有供应商（按设置中的排序方式输出）
 #[tauri::command]
 pub fn get_providers(
     state: State<'_, AppState>,
     app: String,
-) -> Result<HashMap<String, Provider>, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::list(state.inner(), app_type).map_err(|e| e.to_string())
+) -> Result<OrderedProviders, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::list_sorted(state.inner(), app_type).map_err(CommandError::from)
+}
+
+/// 分页获取供应商（字段投影；未指定 fields 时不返回 settingsConfig）
+#[tauri::command]
+pub fn get_providers_page(
+    state: State<'_, AppState>,
+    app: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    fields: Option<Vec<String>>,
+) -> Result<Page, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::list_page(
+        state.inner(),
+        app_type,
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_PAGE_LIMIT),
+        fields.as_deref(),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 搜索并筛选供应商（按匹配得分排序）
+#[tauri::command]
+pub fn search_providers(
+    state: State<'_, AppState>,
+    app: String,
+    query: Option<String>,
+    filters: Option<ProviderSearchFilters>,
+) -> Result<Vec<ProviderSearchHit>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::search(
+        state.inner(),
+        app_type,
+        query.as_deref().unwrap_or_default(),
+        &filters.unwrap_or_default(),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 对比同一应用下的两个供应商（配置、模型、端点、延迟与价格）
+#[tauri::command]
+pub fn compare_providers(
+    state: State<'_, AppState>,
+    app: String,
+    id_a: String,
+    id_b: String,
+) -> Result<crate::services::ProviderComparison, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    CompareService::compare_providers(state.inner(), app_type, &id_a, &id_b)
+        .map_err(CommandError::from)
 }
 
 /// 获取当前供应商ID
 #[tauri::command]
-pub fn get_current_provider(state: State<'_, AppState>, app: String) -> Result<String, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::current(state.inner(), app_type).map_err(|e| e.to_string())
+pub fn get_current_provider(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<String, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::current(state.inner(), app_type).map_err(CommandError::from)
 }
 
 /// 添加供应商
@@ -31,9 +98,9 @@ pub fn add_provider(
     state: State<'_, AppState>,
     app: String,
     provider: Provider,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::add(state.inner(), app_type, provider).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::add(state.inner(), app_type, provider).map_err(CommandError::from)
 }
 
 /// 更新供应商
@@ -42,9 +109,10 @@ pub fn update_provider(
     state: State<'_, AppState>,
     app: String,
     provider: Provider,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::update(state.inner(), app_type, provider).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    // 冲突时错误码为 provider_conflict，details.current 为当前保存的版本，前端据此展示合并界面
+    ProviderService::update(state.inner(), app_type, provider).map_err(CommandError::from)
 }
 
 /// 删除供应商
@@ -53,16 +121,16 @@ pub fn delete_provider(
     state: State<'_, AppState>,
     app: String,
     id: String,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::delete(state.inner(), app_type, &id)
         .map(|_| true)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 切换供应商
 fn switch_provider_internal(state: &AppState, app_type: AppType, id: &str) -> Result<(), AppError> {
-    ProviderService::switch(state, app_type, id)
+    ProviderService::switch(state, app_type, id).map(|_| ())
 }
 
 #[cfg_attr(not(feature = "test-hooks"), doc(hidden))]
@@ -74,16 +142,80 @@ pub fn switch_provider_test_hook(
     switch_provider_internal(state, app_type, id)
 }
 
+/// 切换供应商；目标最近一次测速失败或延迟过高时不切换并返回警告，`force` 为 true 时跳过预检
 #[tauri::command]
 pub fn switch_provider(
     state: State<'_, AppState>,
     app: String,
     id: String,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    switch_provider_internal(&state, app_type, &id)
-        .map(|_| true)
-        .map_err(|e| e.to_string())
+    force: Option<bool>,
+) -> Result<SwitchOutcome, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::switch_checked(&state, app_type, &id, force.unwrap_or(false))
+        .map_err(CommandError::from)
+}
+
+/// 临时切换供应商，到期后自动回退到原供应商
+#[tauri::command]
+#[allow(non_snake_case)]
+pub fn switch_temporarily(
+    handle: AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+    providerId: String,
+    durationMinutes: u32,
+) -> Result<TemporarySwitch, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    if !(1..=MAX_TEMPORARY_SWITCH_MINUTES).contains(&durationMinutes) {
+        return Err(AppError::localized(
+            "provider.temporary_switch.invalid_duration",
+            format!("临时切换时长必须在 1-{MAX_TEMPORARY_SWITCH_MINUTES} 分钟之间"),
+            format!("Duration must be between 1 and {MAX_TEMPORARY_SWITCH_MINUTES} minutes"),
+        )
+        .into());
+    }
+
+    // 若已有进行中的临时切换，回退目标仍为最初的供应商
+    let previous = match scheduler::temporary_switch(&app_type) {
+        Some(pending) => pending.previous_provider_id,
+        None => {
+            ProviderService::current(state.inner(), app_type.clone()).map_err(CommandError::from)?
+        }
+    };
+    if previous == providerId {
+        return Err(AppError::localized(
+            "provider.temporary_switch.same_provider",
+            "目标供应商已是当前供应商",
+            "Target provider is already the current provider",
+        )
+        .into());
+    }
+
+    crate::switch_provider_internal(&handle, app_type.clone(), providerId.clone())
+        .map_err(CommandError::from)?;
+
+    let switch = TemporarySwitch {
+        app_type: app_type.as_str().to_string(),
+        provider_id: providerId,
+        previous_provider_id: previous,
+        revert_at: chrono::Utc::now().timestamp_millis() + i64::from(durationMinutes) * 60_000,
+    };
+    scheduler::schedule_revert(handle, switch.clone());
+    Ok(switch)
+}
+
+/// 获取进行中的临时切换
+#[tauri::command]
+pub fn get_temporary_switch(app: String) -> Result<Option<TemporarySwitch>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    Ok(scheduler::temporary_switch(&app_type))
+}
+
+/// 取消临时切换的自动回退（保留当前供应商）
+#[tauri::command]
+pub fn cancel_temporary_switch(app: String) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    Ok(scheduler::cancel_temporary_switch(&app_type).is_some())
 }
 
 fn import_default_config_internal(state: &AppState, app_type: AppType) -> Result<(), AppError> {
@@ -100,13 +232,36 @@ pub fn import_default_config_test_hook(
 
 /// 导入当前配置为默认供应商
 #[tauri::command]
-pub fn import_default_config(state: State<'_, AppState>, app: String) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+pub fn import_default_config(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     import_default_config_internal(&state, app_type)
         .map(|_| true)
         .map_err(Into::into)
 }
 
+/// 检测已安装的 CLI，并为尚无供应商的应用导入 live 配置作为默认供应商
+#[tauri::command]
+pub async fn import_all_default_configs(
+    state: State<'_, AppState>,
+) -> Result<Vec<DefaultImportResult>, CommandError> {
+    let installed = tauri::async_runtime::spawn_blocking(detect_installed_clis)
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "provider.detect_cli_failed",
+                format!("检测已安装的 CLI 失败: {e}"),
+                format!("Failed to detect installed CLIs: {e}"),
+            )
+        })?;
+    Ok(ProviderService::import_all_default_configs(
+        state.inner(),
+        &installed,
+    ))
+}
+
 /// 查询供应商用量
 #[allow(non_snake_case)]
 #[tauri::command]
@@ -114,11 +269,53 @@ pub async fn queryProviderUsage(
     state: State<'_, AppState>,
     #[allow(non_snake_case)] providerId: String, // 使用 camelCase 匹配前端
     app: String,
-) -> Result<crate::provider::UsageResult, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<crate::provider::UsageResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::query_usage(state.inner(), app_type, &providerId)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+/// 按延迟、错误率、剩余额度、价格与置顶状态为供应商排名（未指定权重时使用设置中的权重）
+#[tauri::command]
+pub async fn rank_providers(
+    state: State<'_, AppState>,
+    app: String,
+    weights: Option<RankingWeights>,
+) -> Result<Vec<ProviderRank>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    RankingService::rank(state.inner(), &app_type, weights)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 从 CSV（电子表格导出）批量创建供应商，`columnMapping` 未指定的列按常见列名自动匹配
+#[tauri::command]
+#[allow(non_snake_case)]
+pub fn import_providers_from_csv(
+    state: State<'_, AppState>,
+    app: String,
+    path: String,
+    columnMapping: Option<crate::provider_csv::CsvColumnMapping>,
+) -> Result<crate::provider_csv::CsvImportResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::import_from_csv(
+        state.inner(),
+        app_type,
+        std::path::Path::new(&path),
+        &columnMapping.unwrap_or_default(),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 列出 `withinDays` 天内（默认 30 天）所有应用的供应商续费，按日期升序
+#[tauri::command]
+#[allow(non_snake_case)]
+pub fn get_upcoming_renewals(
+    state: State<'_, AppState>,
+    withinDays: Option<u32>,
+) -> Result<Vec<UpcomingRenewal>, CommandError> {
+    BillingService::upcoming_renewals(state.inner(), withinDays).map_err(CommandError::from)
 }
 
 /// 测试用量脚本（使用当前编辑器中的脚本，不保存）
@@ -135,8 +332,8 @@ pub async fn testUsageScript(
     #[allow(non_snake_case)] baseUrl: Option<String>,
     #[allow(non_snake_case)] accessToken: Option<String>,
     #[allow(non_snake_case)] userId: Option<String>,
-) -> Result<crate::provider::UsageResult, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<crate::provider::UsageResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::test_usage_script(
         state.inner(),
         app_type,
@@ -149,25 +346,198 @@ pub async fn testUsageScript(
         userId.as_deref(),
     )
     .await
-    .map_err(|e| e.to_string())
+    .map_err(CommandError::from)
+}
+
+/// 查询用量脚本的熔断状态（连续失败次数、是否已自动停用）
+#[tauri::command]
+pub fn get_usage_script_status(provider_id: String) -> crate::usage_script::UsageScriptStatus {
+    crate::usage_script::usage_script_status(&provider_id)
+}
+
+/// 手动解除用量脚本熔断
+#[tauri::command]
+pub fn reset_usage_script_status(provider_id: String) -> crate::usage_script::UsageScriptStatus {
+    crate::usage_script::reset_usage_script_status(&provider_id);
+    crate::usage_script::usage_script_status(&provider_id)
 }
 
 /// 读取当前生效的配置内容
+///
+/// `revealSecrets` 默认为 true；设置了应用口令时，明文读取需提供有效的解锁令牌，
+/// 传入 false 则返回掩码后的内容
 #[tauri::command]
-pub fn read_live_provider_settings(app: String) -> Result<serde_json::Value, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::read_live_settings(app_type).map_err(|e| e.to_string())
+pub fn read_live_provider_settings(
+    app: String,
+    #[allow(non_snake_case)] revealSecrets: Option<bool>,
+    #[allow(non_snake_case)] unlockToken: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let reveal = revealSecrets.unwrap_or(true);
+    if reveal {
+        PasscodeService::require_unlocked(unlockToken.as_deref()).map_err(CommandError::from)?;
+    }
+
+    let mut settings = ProviderService::read_live_settings(app_type).map_err(CommandError::from)?;
+    if !reveal {
+        mask_secrets(&mut settings);
+    }
+    Ok(settings)
 }
 
 /// 测试第三方/自定义供应商端点的网络延迟
+///
+/// 传入 `app` 与 `providerId` 时，将响应中的限流信息记录到该供应商
 #[tauri::command]
 pub async fn test_api_endpoints(
+    state: State<'_, AppState>,
     urls: Vec<String>,
     #[allow(non_snake_case)] timeoutSecs: Option<u64>,
-) -> Result<Vec<EndpointLatency>, String> {
-    SpeedtestService::test_endpoints(urls, timeoutSecs)
+    app: Option<String>,
+    #[allow(non_snake_case)] providerId: Option<String>,
+) -> Result<Vec<EndpointLatency>, CommandError> {
+    let results = SpeedtestService::test_endpoints(urls, timeoutSecs)
+        .await
+        .map_err(CommandError::from)?;
+
+    if let (Some(app), Some(provider_id)) = (app, providerId) {
+        let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+        ProviderService::record_rate_limit(state.inner(), &app_type, &provider_id, &results)
+            .map_err(CommandError::from)?;
+    }
+    Ok(results)
+}
+
+/// 使用供应商的 API Key 发送一次最小的真实请求，测量完整延迟并识别 401/403 等配置问题
+#[tauri::command]
+pub async fn probe_provider_authenticated(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    #[allow(non_snake_case)] timeoutSecs: Option<u64>,
+) -> Result<AuthenticatedProbe, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let provider = {
+        let config = state.config.read().map_err(CommandError::from)?;
+        config
+            .get_manager(&app_type)
+            .and_then(|manager| manager.providers.get(&providerId))
+            .cloned()
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {providerId}"),
+                    format!("Provider not found: {providerId}"),
+                )
+            })?
+    };
+    let (probe, sample) = SpeedtestService::probe_authenticated(&app_type, &provider, timeoutSecs)
+        .await
+        .map_err(CommandError::from)?;
+    ProviderService::record_rate_limit(state.inner(), &app_type, &providerId, &[sample])
+        .map_err(CommandError::from)?;
+    Ok(probe)
+}
+
+/// 综合验证供应商（真实请求 + 模型列表），结果保存在供应商元数据的 `verification` 中
+#[tauri::command]
+pub async fn verify_provider(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+) -> Result<crate::provider::ProviderVerification, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    VerificationService::verify(state.inner(), &app_type, &providerId)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 在临时配置目录中用 CLI 执行一次非交互调用，验证供应商可用（不修改 live 配置）
+#[tauri::command]
+pub async fn sandbox_validate(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    #[allow(non_snake_case)] timeoutSecs: Option<u64>,
+) -> Result<SandboxResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let provider = {
+        let config = state.config.read().map_err(CommandError::from)?;
+        config
+            .get_manager(&app_type)
+            .and_then(|manager| manager.providers.get(&providerId))
+            .cloned()
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {providerId}"),
+                    format!("Provider not found: {providerId}"),
+                )
+            })?
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        SandboxService::validate(&app_type, &provider, timeoutSecs)
+    })
+    .await
+    .map_err(|e| {
+        AppError::localized(
+            "sandbox.validate_failed",
+            format!("沙箱验证失败: {e}"),
+            format!("Sandbox validation failed: {e}"),
+        )
+    })?
+    .map_err(CommandError::from)
+}
+
+/// 探测未知端点支持的 API 协议（Anthropic / OpenAI Chat / OpenAI Responses），返回推荐的应用类型与配置模板
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn probe_provider_capabilities(
+    baseUrl: String,
+    apiKey: String,
+    timeoutSecs: Option<u64>,
+) -> Result<CapabilityReport, CommandError> {
+    CapabilityService::probe(&baseUrl, &apiKey, timeoutSecs)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+/// 探测 Codex 端点应使用的 wire_api（responses / chat），用于从预设新建供应商前确认
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn detect_codex_wire_api(
+    baseUrl: String,
+    apiKey: String,
+) -> Result<Option<String>, CommandError> {
+    CapabilityService::detect_codex_wire_api(&baseUrl, &apiKey)
+        .await
+        .map(|wire_api| wire_api.map(str::to_string))
+        .map_err(CommandError::from)
+}
+
+/// 按探测结果改写 Codex 供应商 config.toml 中的 wire_api
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn fix_codex_wire_api(
+    state: State<'_, AppState>,
+    providerId: String,
+) -> Result<WireApiFix, CommandError> {
+    ProviderService::fix_codex_wire_api(state.inner(), &providerId)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 从 live 配置采集 OAuth 凭据快照（Codex auth.json / Claude .credentials.json / Gemini oauth_creds.json），并将供应商标记为 OAuth
+#[tauri::command]
+#[allow(non_snake_case)]
+pub fn capture_oauth_snapshot(
+    state: State<'_, AppState>,
+    app: String,
+    providerId: String,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::capture_oauth_snapshot(state.inner(), app_type, &providerId)
+        .map_err(CommandError::from)
 }
 
 /// 获取自定义端点列表
@@ -176,10 +546,10 @@ pub fn get_custom_endpoints(
     state: State<'_, AppState>,
     app: String,
     #[allow(non_snake_case)] providerId: String,
-) -> Result<Vec<crate::settings::CustomEndpoint>, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<Vec<crate::settings::CustomEndpoint>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::get_custom_endpoints(state.inner(), app_type, &providerId)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 添加自定义端点
@@ -189,10 +559,10 @@ pub fn add_custom_endpoint(
     app: String,
     #[allow(non_snake_case)] providerId: String,
     url: String,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::add_custom_endpoint(state.inner(), app_type, &providerId, url)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 删除自定义端点
@@ -202,10 +572,10 @@ pub fn remove_custom_endpoint(
     app: String,
     #[allow(non_snake_case)] providerId: String,
     url: String,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::remove_custom_endpoint(state.inner(), app_type, &providerId, url)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 更新端点最后使用时间
@@ -215,10 +585,10 @@ pub fn update_endpoint_last_used(
     app: String,
     #[allow(non_snake_case)] providerId: String,
     url: String,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::update_endpoint_last_used(state.inner(), app_type, &providerId, url)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 更新多个供应商的排序
@@ -227,7 +597,126 @@ pub fn update_providers_sort_order(
     state: State<'_, AppState>,
     app: String,
     updates: Vec<ProviderSortUpdate>,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::update_sort_order(state.inner(), app_type, updates).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::update_sort_order(state.inner(), app_type, updates).map_err(CommandError::from)
+}
+
+/// 一次更新多个应用的供应商排序（键为应用类型），单次保存
+#[tauri::command]
+pub fn update_sort_order_multi(
+    state: State<'_, AppState>,
+    updates: HashMap<String, Vec<ProviderSortUpdate>>,
+) -> Result<bool, CommandError> {
+    let updates = updates
+        .into_iter()
+        .map(|(app, app_updates)| Ok((AppType::from_str(&app)?, app_updates)))
+        .collect::<Result<Vec<_>, AppError>>()
+        .map_err(CommandError::from)?;
+    ProviderService::update_sort_order_multi(state.inner(), updates).map_err(CommandError::from)
+}
+
+/// 设置供应商的默认模型（Codex 改写 config.toml 的 `model`，Claude / Gemini 写入模型环境变量）
+#[tauri::command]
+pub fn set_provider_model(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    model: Option<String>,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_provider_model(state.inner(), app_type, &providerId, model)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 读取 Codex 供应商 config.toml 中的单个值（键路径以 `.` 分隔，`@provider` 表示当前 model_provider 的表）
+#[tauri::command]
+pub fn get_codex_config_value(
+    state: State<'_, AppState>,
+    #[allow(non_snake_case)] providerId: String,
+    #[allow(non_snake_case)] keyPath: String,
+) -> Result<Option<serde_json::Value>, CommandError> {
+    ProviderService::get_codex_config_value(state.inner(), &providerId, &keyPath)
+        .map_err(CommandError::from)
+}
+
+/// 设置 Codex 供应商 config.toml 中的单个值（`value` 为 null 时移除），保留其余内容与注释
+#[tauri::command]
+pub fn set_codex_config_value(
+    state: State<'_, AppState>,
+    #[allow(non_snake_case)] providerId: String,
+    #[allow(non_snake_case)] keyPath: String,
+    value: serde_json::Value,
+) -> Result<bool, CommandError> {
+    ProviderService::set_codex_config_value(state.inner(), &providerId, &keyPath, value)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 置顶或取消置顶供应商（列表与托盘菜单中优先展示）
+#[tauri::command]
+pub fn set_provider_pinned(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    pinned: bool,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_pinned(state.inner(), app_type, &providerId, pinned)
+        .map_err(CommandError::from)
+}
+
+/// 设置 Codex 供应商的额外环境变量
+#[tauri::command]
+pub fn set_provider_extra_env(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    env: HashMap<String, String>,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_provider_extra_env(state.inner(), app_type, &providerId, env)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 设置供应商自定义请求头
+#[tauri::command]
+pub fn set_provider_headers(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    headers: HashMap<String, String>,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_provider_headers(state.inner(), app_type, &providerId, headers)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 设置供应商的并发、超时与重试参数（Codex 写入供应商表，Claude 写入 API_TIMEOUT_MS）
+#[tauri::command]
+pub fn set_provider_performance(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    performance: ProviderPerformance,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_provider_performance(state.inner(), app_type, &providerId, performance)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 设置 Claude 供应商的 permissions、includeCoAuthoredBy、model 与模型别名（切换时合并写入 settings.json）
+#[tauri::command]
+pub fn set_claude_provider_settings(
+    state: State<'_, AppState>,
+    #[allow(non_snake_case)] providerId: String,
+    settings: ClaudeSettingsOverrides,
+) -> Result<bool, CommandError> {
+    ProviderService::set_claude_settings(state.inner(), &providerId, settings)
+        .map(|_| true)
+        .map_err(CommandError::from)
 }
@@ -0,0 +1,38 @@
+use tauri::AppHandle;
+
+use crate::error::{AppError, CommandError};
+use crate::proxy::{self, ProxyStatus};
+use crate::services::{MetricsPeriod, ProxyMetricsReport, ProxyMetricsService};
+
+/// 启动本地反向代理，返回监听端口（未指定端口时使用设置或默认端口）
+#[tauri::command]
+pub async fn start_proxy(app: AppHandle, port: Option<u16>) -> Result<u16, CommandError> {
+    proxy::start(app, port).await.map_err(CommandError::from)
+}
+
+/// 停止本地反向代理，返回之前是否在运行
+#[tauri::command]
+pub fn stop_proxy() -> Result<bool, CommandError> {
+    Ok(proxy::stop())
+}
+
+/// 获取代理状态、各应用应配置的基础地址与最近请求
+#[tauri::command]
+pub fn get_proxy_status() -> Result<ProxyStatus, CommandError> {
+    Ok(proxy::status())
+}
+
+/// 按供应商统计代理请求数、错误率与延迟分位数
+#[tauri::command]
+pub async fn get_proxy_metrics(period: MetricsPeriod) -> Result<ProxyMetricsReport, CommandError> {
+    tauri::async_runtime::spawn_blocking(move || ProxyMetricsService::report(period))
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "proxy.metrics_failed",
+                format!("统计代理指标失败: {e}"),
+                format!("Failed to compute proxy metrics: {e}"),
+            )
+        })?
+        .map_err(CommandError::from)
+}
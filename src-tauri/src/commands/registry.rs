@@ -0,0 +1,16 @@
+use crate::error::CommandError;
+use crate::services::{Registry, RegistryKind, RegistryRefreshReport, RegistryService};
+
+/// 获取注册表（供应商预设 / MCP 目录 / 用量脚本模板）；无缓存时返回内置版本
+#[tauri::command]
+pub fn get_registry(kind: RegistryKind) -> Result<Registry, CommandError> {
+    Ok(RegistryService::get(kind))
+}
+
+/// 联网刷新全部注册表；离线或拉取失败时保留现有数据
+#[tauri::command]
+pub async fn refresh_registries() -> Result<RegistryRefreshReport, CommandError> {
+    RegistryService::refresh_all()
+        .await
+        .map_err(CommandError::from)
+}
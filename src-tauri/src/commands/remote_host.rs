@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::{AppError, CommandError};
+use crate::remote_host::RemoteHost;
+use crate::services::{RemoteHostService, RemoteSwitchResult};
+use crate::store::AppState;
+
+/// 列出已配置的 SSH 远程主机
+#[tauri::command]
+pub fn list_remote_hosts() -> Result<Vec<RemoteHost>, CommandError> {
+    Ok(RemoteHostService::list())
+}
+
+/// 新增或更新远程主机（`id` 为空时新增），返回保存后的主机
+#[tauri::command]
+pub fn save_remote_host(host: RemoteHost) -> Result<RemoteHost, CommandError> {
+    RemoteHostService::save(host).map_err(CommandError::from)
+}
+
+/// 删除远程主机
+#[tauri::command]
+pub fn remove_remote_host(id: String) -> Result<bool, CommandError> {
+    RemoteHostService::remove(&id).map_err(CommandError::from)
+}
+
+/// 测试远程主机连接，返回远程主目录
+#[tauri::command]
+pub async fn test_remote_host(id: String) -> Result<String, CommandError> {
+    tauri::async_runtime::spawn_blocking(move || RemoteHostService::test(&id))
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "remote.test_failed",
+                format!("测试远程主机失败: {e}"),
+                format!("Failed to test remote host: {e}"),
+            )
+        })?
+        .map_err(CommandError::from)
+}
+
+/// 在本机生成供应商的 live 配置并经 SFTP 写入远程主机（远程原文件先备份到本地）
+#[tauri::command]
+pub async fn switch_provider_remote(
+    state: State<'_, AppState>,
+    host: String,
+    app: String,
+    providerId: String,
+) -> Result<RemoteSwitchResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let provider = RemoteHostService::expanded_provider(state.inner(), &app_type, &providerId)
+        .map_err(CommandError::from)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        RemoteHostService::switch_provider(&host, &app_type, &provider)
+    })
+    .await
+    .map_err(|e| {
+        AppError::localized(
+            "remote.write_failed",
+            format!("写入远程主机失败: {e}"),
+            format!("Failed to write to remote host: {e}"),
+        )
+    })?
+    .map_err(CommandError::from)
+}
@@ -0,0 +1,30 @@
+use tauri::State;
+
+use crate::commands::skill::SkillServiceState;
+use crate::error::CommandError;
+use crate::services::{SearchHit, SearchService};
+use crate::store::AppState;
+
+/// 全局搜索默认返回的最大条数
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// 全局搜索：供应商、提示词、MCP 服务器与已安装技能
+#[tauri::command]
+pub fn global_search(
+    state: State<'_, AppState>,
+    skill_service: State<'_, SkillServiceState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, CommandError> {
+    let skills = skill_service.0.list_installed().unwrap_or_else(|e| {
+        log::warn!("读取已安装技能失败，全局搜索将跳过技能: {e}");
+        Vec::new()
+    });
+    SearchService::global_search(
+        state.inner(),
+        &skills,
+        &query,
+        limit.unwrap_or(DEFAULT_SEARCH_LIMIT),
+    )
+    .map_err(CommandError::from)
+}
@@ -1,29 +1,73 @@
 #![allow(non_snake_case)]
 
+use crate::error::{AppError, CommandError};
+use std::path::PathBuf;
 use tauri::AppHandle;
 
 /// 获取设置
 #[tauri::command]
-pub async fn get_settings() -> Result<crate::settings::AppSettings, String> {
-    Ok(crate::settings::get_settings())
+pub async fn get_settings() -> Result<crate::settings::AppSettings, CommandError> {
+    let mut settings = crate::settings::get_settings();
+    // 口令哈希不下发前端，状态通过 get_passcode_status 查询
+    settings.passcode_hash = None;
+    Ok(settings)
 }
 
 /// 保存设置
 #[tauri::command]
-pub async fn save_settings(settings: crate::settings::AppSettings) -> Result<bool, String> {
-    crate::settings::update_settings(settings).map_err(|e| e.to_string())?;
+pub async fn save_settings(
+    mut settings: crate::settings::AppSettings,
+) -> Result<bool, CommandError> {
+    // 口令只能通过 set_passcode 修改
+    settings.passcode_hash = crate::settings::get_settings().passcode_hash;
+    crate::settings::update_settings(settings).map_err(CommandError::from)?;
     Ok(true)
 }
 
+/// 导出应用设置（不含供应商配置与密钥）
+#[tauri::command]
+pub async fn export_settings(path: String) -> Result<bool, CommandError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::settings::export_settings_to_path(&PathBuf::from(&path))
+    })
+    .await
+    .map_err(|e| {
+        AppError::localized(
+            "settings.export_failed",
+            format!("导出设置失败: {e}"),
+            format!("Failed to export settings: {e}"),
+        )
+    })?
+    .map_err(CommandError::from)?;
+    Ok(true)
+}
+
+/// 从文件导入应用设置，返回导入后的设置
+#[tauri::command]
+pub async fn import_settings(path: String) -> Result<crate::settings::AppSettings, CommandError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::settings::import_settings_from_path(&PathBuf::from(&path))
+    })
+    .await
+    .map_err(|e| {
+        AppError::localized(
+            "settings.import_failed",
+            format!("导入设置失败: {e}"),
+            format!("Failed to import settings: {e}"),
+        )
+    })?
+    .map_err(CommandError::from)
+}
+
 /// 重启应用程序（当 app_config_dir 变更后使用）
 #[tauri::command]
-pub async fn restart_app(app: AppHandle) -> Result<bool, String> {
+pub async fn restart_app(app: AppHandle) -> Result<bool, CommandError> {
     app.restart();
 }
 
 /// 获取 app_config_dir 覆盖配置 (从 Store)
 #[tauri::command]
-pub async fn get_app_config_dir_override(app: AppHandle) -> Result<Option<String>, String> {
+pub async fn get_app_config_dir_override(app: AppHandle) -> Result<Option<String>, CommandError> {
     Ok(crate::app_store::refresh_app_config_dir_override(&app)
         .map(|p| p.to_string_lossy().to_string()))
 }
@@ -33,7 +77,7 @@ pub async fn get_app_config_dir_override(app: AppHandle) -> Result<Option<String
 pub async fn set_app_config_dir_override(
     app: AppHandle,
     path: Option<String>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     crate::app_store::set_app_config_dir_to_store(&app, path.as_deref())?;
     Ok(true)
 }
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CommandError;
+use crate::services::{EnvFileService, ShellWrapperService};
+use crate::store::AppState;
+
+/// 在指定目录生成 `claude-with <供应商>` 等包装脚本，返回生成的脚本路径
+#[tauri::command]
+pub async fn generate_shell_wrappers(
+    state: State<'_, AppState>,
+    dir: String,
+) -> Result<Vec<String>, CommandError> {
+    ShellWrapperService::generate(state.inner(), &PathBuf::from(dir))
+        .map(|paths| {
+            paths
+                .into_iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        })
+        .map_err(CommandError::from)
+}
+
+/// 获取指定应用的环境变量文件路径（`~/.cc-switch/env/<app>.env`）
+#[tauri::command]
+pub fn get_env_file_path(app: String) -> Result<String, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    Ok(EnvFileService::path(&app_type)
+        .to_string_lossy()
+        .to_string())
+}
+
+/// 生成在 shell 启动文件中载入环境变量文件的片段
+#[tauri::command]
+pub fn get_env_shell_snippet(app: String, shell: Option<String>) -> Result<String, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    EnvFileService::shell_snippet(&app_type, shell.as_deref()).map_err(CommandError::from)
+}
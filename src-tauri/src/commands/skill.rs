@@ -1,7 +1,10 @@
+use crate::app_config::AppType;
+use crate::error::{AppError, CommandError};
 use crate::services::skill::SkillState;
 use crate::services::{Skill, SkillRepo, SkillService};
 use crate::store::AppState;
 use chrono::Utc;
+use std::str::FromStr;
 use std::sync::Arc;
 use tauri::State;
 
@@ -11,9 +14,9 @@ pub struct SkillServiceState(pub Arc<SkillService>);
 pub async fn get_skills(
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<Vec<Skill>, String> {
+) -> Result<Vec<Skill>, CommandError> {
     let repos = {
-        let config = app_state.config.read().map_err(|e| e.to_string())?;
+        let config = app_state.config.read().map_err(CommandError::from)?;
         config.skills.repos.clone()
     };
 
@@ -21,18 +24,22 @@ pub async fn get_skills(
         .0
         .list_skills(repos)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
+/// 安装技能到指定应用（`app` 默认 claude；codex 写入自定义提示词，gemini 写入扩展目录）
 #[tauri::command]
 pub async fn install_skill(
     directory: String,
+    app: Option<String>,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
+    let app_type = parse_target(app.as_deref())?;
+
     // 先在不持有写锁的情况下收集仓库与技能信息
     let repos = {
-        let config = app_state.config.read().map_err(|e| e.to_string())?;
+        let config = app_state.config.read().map_err(CommandError::from)?;
         config.skills.repos.clone()
     };
 
@@ -40,83 +47,106 @@ pub async fn install_skill(
         .0
         .list_skills(repos)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(CommandError::from)?;
 
     let skill = skills
         .iter()
         .find(|s| s.directory.eq_ignore_ascii_case(&directory))
-        .ok_or_else(|| "技能不存在".to_string())?;
-
-    if !skill.installed {
-        let repo = SkillRepo {
-            owner: skill
-                .repo_owner
-                .clone()
-                .ok_or_else(|| "缺少仓库信息".to_string())?,
-            name: skill
-                .repo_name
-                .clone()
-                .ok_or_else(|| "缺少仓库信息".to_string())?,
-            branch: skill
-                .repo_branch
-                .clone()
-                .unwrap_or_else(|| "main".to_string()),
-            enabled: true,
-            skills_path: None, // 安装时使用默认路径
+        .ok_or_else(|| {
+            AppError::localized(
+                "skill.not_found",
+                format!("技能不存在: {directory}"),
+                format!("Skill not found: {directory}"),
+            )
+        })?;
+
+    if !skill.installed_apps.contains(&app_type) {
+        // 本地独有的技能没有仓库信息，只能从已安装的 Claude 副本复制
+        let repo = match (&skill.repo_owner, &skill.repo_name) {
+            (Some(owner), Some(name)) => Some(SkillRepo {
+                owner: owner.clone(),
+                name: name.clone(),
+                branch: skill
+                    .repo_branch
+                    .clone()
+                    .unwrap_or_else(|| "main".to_string()),
+                enabled: true,
+                skills_path: None, // 安装时使用默认路径
+            }),
+            _ => None,
         };
 
         service
             .0
-            .install_skill(directory.clone(), repo)
+            .install_skill_for(directory.clone(), repo, &app_type)
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(CommandError::from)?;
     }
 
     {
-        let mut config = app_state.config.write().map_err(|e| e.to_string())?;
+        let mut config = app_state.config.write().map_err(CommandError::from)?;
 
-        config.skills.skills.insert(
-            directory.clone(),
-            SkillState {
+        let state = config
+            .skills
+            .skills
+            .entry(directory.clone())
+            .or_insert_with(|| SkillState {
                 installed: true,
                 installed_at: Utc::now(),
-            },
-        );
+                apps: Vec::new(),
+            });
+        if !state.apps.contains(&app_type) {
+            state.apps.push(app_type);
+        }
     }
 
-    app_state.save().map_err(|e| e.to_string())?;
+    app_state.save().map_err(CommandError::from)?;
 
     Ok(true)
 }
 
+/// 从指定应用卸载技能（`app` 默认 claude）；所有应用都卸载后移除安装记录
 #[tauri::command]
 pub fn uninstall_skill(
     directory: String,
+    app: Option<String>,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
+    let app_type = parse_target(app.as_deref())?;
     service
         .0
-        .uninstall_skill(directory.clone())
-        .map_err(|e| e.to_string())?;
+        .uninstall_skill_for(&directory, &app_type)
+        .map_err(CommandError::from)?;
 
     {
-        let mut config = app_state.config.write().map_err(|e| e.to_string())?;
-
-        config.skills.skills.remove(&directory);
+        let mut config = app_state.config.write().map_err(CommandError::from)?;
+
+        if let Some(state) = config.skills.skills.get_mut(&directory) {
+            state.apps.retain(|app| app != &app_type);
+            if state.apps.is_empty() {
+                config.skills.skills.remove(&directory);
+            }
+        }
     }
 
-    app_state.save().map_err(|e| e.to_string())?;
+    app_state.save().map_err(CommandError::from)?;
 
     Ok(true)
 }
 
+fn parse_target(app: Option<&str>) -> Result<AppType, CommandError> {
+    app.map_or(Ok(AppType::Claude), |app| {
+        AppType::from_str(app).map_err(CommandError::from)
+    })
+}
+
 #[tauri::command]
 pub fn get_skill_repos(
     _service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<Vec<SkillRepo>, String> {
-    let config = app_state.config.read().map_err(|e| e.to_string())?;
+) -> Result<Vec<SkillRepo>, CommandError> {
+    let config = app_state.config.read().map_err(CommandError::from)?;
 
     Ok(config.skills.repos.clone())
 }
@@ -126,17 +156,17 @@ pub fn add_skill_repo(
     repo: SkillRepo,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     {
-        let mut config = app_state.config.write().map_err(|e| e.to_string())?;
+        let mut config = app_state.config.write().map_err(CommandError::from)?;
 
         service
             .0
             .add_repo(&mut config.skills, repo)
-            .map_err(|e| e.to_string())?;
+            .map_err(CommandError::from)?;
     }
 
-    app_state.save().map_err(|e| e.to_string())?;
+    app_state.save().map_err(CommandError::from)?;
 
     Ok(true)
 }
@@ -147,17 +177,17 @@ pub fn remove_skill_repo(
     name: String,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     {
-        let mut config = app_state.config.write().map_err(|e| e.to_string())?;
+        let mut config = app_state.config.write().map_err(CommandError::from)?;
 
         service
             .0
             .remove_repo(&mut config.skills, owner, name)
-            .map_err(|e| e.to_string())?;
+            .map_err(CommandError::from)?;
     }
 
-    app_state.save().map_err(|e| e.to_string())?;
+    app_state.save().map_err(CommandError::from)?;
 
     Ok(true)
 }
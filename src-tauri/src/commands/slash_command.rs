@@ -0,0 +1,48 @@
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::services::SlashCommandService;
+use crate::slash_command::SlashCommand;
+use crate::store::AppState;
+
+/// 获取所有 Claude Code 斜杠命令
+#[tauri::command]
+pub async fn get_slash_commands(
+    state: State<'_, AppState>,
+) -> Result<Vec<SlashCommand>, CommandError> {
+    SlashCommandService::list(&state).map_err(CommandError::from)
+}
+
+/// 新建或更新斜杠命令
+#[tauri::command]
+pub async fn upsert_slash_command(
+    state: State<'_, AppState>,
+    command: SlashCommand,
+) -> Result<SlashCommand, CommandError> {
+    SlashCommandService::upsert(&state, command).map_err(CommandError::from)
+}
+
+/// 删除斜杠命令
+#[tauri::command]
+pub async fn delete_slash_command(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<bool, CommandError> {
+    SlashCommandService::delete(&state, &name).map_err(CommandError::from)
+}
+
+/// 启用或停用斜杠命令
+#[tauri::command]
+pub async fn set_slash_command_enabled(
+    state: State<'_, AppState>,
+    name: String,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    SlashCommandService::set_enabled(&state, &name, enabled).map_err(CommandError::from)
+}
+
+/// 导入命令目录中已有的命令文件
+#[tauri::command]
+pub async fn import_slash_commands(state: State<'_, AppState>) -> Result<usize, CommandError> {
+    SlashCommandService::import_from_dir(&state).map_err(CommandError::from)
+}
@@ -0,0 +1,52 @@
+use std::str::FromStr;
+
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CommandError;
+use crate::settings::StatuslineVariant;
+use crate::statusline::{self, ActiveProviderInfo, StatuslineStatus};
+use crate::store::AppState;
+
+/// 获取 Claude Code 状态栏安装状态
+#[tauri::command]
+pub async fn get_statusline_status() -> Result<StatuslineStatus, CommandError> {
+    Ok(statusline::status())
+}
+
+/// 安装内置状态栏脚本并写入 Claude settings.json
+#[tauri::command]
+pub async fn install_statusline(
+    state: State<'_, AppState>,
+    variant: StatuslineVariant,
+) -> Result<StatuslineStatus, CommandError> {
+    // 先写入当前供应商标记文件，确保脚本安装后立即可显示
+    {
+        let config = state.config.read().map_err(CommandError::from)?;
+        if let Some(provider) = config
+            .get_manager(&AppType::Claude)
+            .and_then(|manager| manager.providers.get(&manager.current))
+        {
+            statusline::write_active_provider(&AppType::Claude, provider)
+                .map_err(CommandError::from)?;
+        }
+    }
+    statusline::install(variant).map_err(CommandError::from)
+}
+
+/// 移除 cc-switch 状态栏
+#[tauri::command]
+pub async fn remove_statusline() -> Result<StatuslineStatus, CommandError> {
+    statusline::remove().map_err(CommandError::from)
+}
+
+/// 获取当前供应商（供状态栏等高频调用，只读取内存配置）
+#[tauri::command]
+pub fn get_active_provider_for_statusline(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Option<ActiveProviderInfo>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let config = state.config.read().map_err(CommandError::from)?;
+    Ok(statusline::active_provider(&config, &app_type))
+}
@@ -0,0 +1,53 @@
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::services::{SubscriptionPreset, SubscriptionService};
+use crate::store::AppState;
+use crate::subscription::SubscriptionSource;
+
+/// 获取订阅源列表（含最近一次拉取的清单）
+#[tauri::command]
+pub fn list_subscriptions() -> Result<Vec<SubscriptionSource>, CommandError> {
+    Ok(crate::settings::get_settings().subscriptions)
+}
+
+/// 添加订阅源（立即拉取一次清单）
+#[tauri::command]
+pub async fn add_subscription(url: String) -> Result<SubscriptionSource, CommandError> {
+    SubscriptionService::add(&url)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 删除订阅源（已安装的供应商保留）
+#[tauri::command]
+pub fn remove_subscription(id: String) -> Result<bool, CommandError> {
+    SubscriptionService::remove(&id).map_err(CommandError::from)
+}
+
+/// 获取全部订阅预设及安装/更新状态
+#[tauri::command]
+pub fn list_subscription_presets(
+    state: State<'_, AppState>,
+) -> Result<Vec<SubscriptionPreset>, CommandError> {
+    SubscriptionService::presets(state.inner()).map_err(CommandError::from)
+}
+
+/// 重新拉取全部订阅源
+#[tauri::command]
+pub async fn refresh_subscriptions(
+    state: State<'_, AppState>,
+) -> Result<Vec<SubscriptionPreset>, CommandError> {
+    SubscriptionService::refresh(state.inner())
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 安装订阅中的供应商（已安装时按上游更新），返回本地供应商 ID
+#[tauri::command]
+pub fn install_subscribed_provider(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<String, CommandError> {
+    SubscriptionService::install(state.inner(), &id).map_err(CommandError::from)
+}
@@ -0,0 +1,83 @@
+use std::str::FromStr;
+
+use tauri::{AppHandle, State};
+
+use crate::app_config::AppType;
+use crate::error::{AppError, CommandError};
+use crate::network::{self, NetworkSnapshot};
+use crate::services::{BestProviderSwitch, RankingService, SwitchRuleService};
+use crate::store::AppState;
+use crate::switch_rule::SwitchRule;
+
+/// 获取自动切换规则列表
+#[tauri::command]
+pub fn list_switch_rules() -> Result<Vec<SwitchRule>, CommandError> {
+    Ok(SwitchRuleService::list())
+}
+
+/// 新增或更新自动切换规则
+#[tauri::command]
+pub fn upsert_switch_rule(
+    state: State<'_, AppState>,
+    rule: SwitchRule,
+) -> Result<SwitchRule, CommandError> {
+    SwitchRuleService::upsert(state.inner(), rule).map_err(CommandError::from)
+}
+
+/// 删除自动切换规则
+#[tauri::command]
+pub fn delete_switch_rule(id: String) -> Result<bool, CommandError> {
+    SwitchRuleService::delete(&id).map_err(CommandError::from)
+}
+
+/// 启用或停用自动切换规则
+#[tauri::command]
+pub fn set_switch_rules_enabled(enabled: bool) -> Result<bool, CommandError> {
+    SwitchRuleService::set_enabled(enabled)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 获取当前网络环境（用于配置网络条件规则）
+#[tauri::command]
+pub async fn get_network_status() -> Result<NetworkSnapshot, CommandError> {
+    tauri::async_runtime::spawn_blocking(network::detect)
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "network.detect_failed",
+                format!("检测网络环境失败: {e}"),
+                format!("Failed to detect network environment: {e}"),
+            )
+            .into()
+        })
+}
+
+/// 按供应商排名（设置中的评分权重）切换到推荐的可用供应商
+#[tauri::command]
+pub async fn switch_to_best_available(
+    handle: AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<BestProviderSwitch, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let ranks = RankingService::rank(state.inner(), &app_type, None)
+        .await
+        .map_err(CommandError::from)?;
+    let best = ranks
+        .iter()
+        .find(|rank| rank.recommended)
+        .map(|rank| rank.provider_id.clone())
+        .ok_or_else(|| {
+            AppError::localized(
+                "switch_rule.no_available_provider",
+                "没有额度充足且可达的供应商",
+                "No provider with remaining quota is reachable",
+            )
+        })?;
+    crate::switch_provider_internal(&handle, app_type, best.clone()).map_err(CommandError::from)?;
+    Ok(BestProviderSwitch {
+        provider_id: best,
+        ranks,
+    })
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::services::{
+    TeamPackApplyResult, TeamPackManifest, TeamPackPreview, TeamPackResolution, TeamPackSelection,
+    TeamPackService,
+};
+use crate::store::AppState;
+
+/// 将选中的供应商（不含密钥）、MCP 服务器、提示词、技能引用与推荐设置打包到 `path`
+#[tauri::command]
+pub async fn create_team_pack(
+    state: State<'_, AppState>,
+    selection: TeamPackSelection,
+    path: String,
+) -> Result<TeamPackManifest, CommandError> {
+    TeamPackService::create(&state, &selection, Path::new(&path)).map_err(CommandError::from)
+}
+
+/// 读取团队包内容并标记与本地配置冲突的项
+#[tauri::command]
+pub async fn inspect_team_pack(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<TeamPackPreview, CommandError> {
+    TeamPackService::inspect(&state, Path::new(&path)).map_err(CommandError::from)
+}
+
+/// 安装团队包；冲突项按 `resolutions` 处理，未指定的跳过并在结果中列出
+#[tauri::command]
+pub async fn apply_team_pack(
+    state: State<'_, AppState>,
+    path: String,
+    resolutions: Option<HashMap<String, TeamPackResolution>>,
+) -> Result<TeamPackApplyResult, CommandError> {
+    TeamPackService::apply(&state, Path::new(&path), &resolutions.unwrap_or_default())
+        .map_err(CommandError::from)
+}
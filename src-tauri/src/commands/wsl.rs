@@ -0,0 +1,39 @@
+use std::str::FromStr;
+
+use crate::app_config::AppType;
+use crate::error::{AppError, CommandError};
+use crate::services::{WslDistroStatus, WslLiveFile, WslService};
+
+/// 列出已安装的 WSL 发行版及是否启用切换同步（非 Windows 返回空列表）
+#[tauri::command]
+pub async fn list_wsl_distros() -> Result<Vec<WslDistroStatus>, CommandError> {
+    tauri::async_runtime::spawn_blocking(WslService::distros)
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "wsl.list_failed",
+                format!("列出 WSL 发行版失败: {e}"),
+                format!("Failed to list WSL distributions: {e}"),
+            )
+        })?
+        .map_err(CommandError::from)
+}
+
+/// 读取 WSL 发行版中 Claude / Codex 的 live 配置文件
+#[tauri::command]
+pub async fn read_wsl_live_config(
+    distro: String,
+    app: String,
+) -> Result<Vec<WslLiveFile>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    tauri::async_runtime::spawn_blocking(move || WslService::read_live(&distro, &app_type))
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "wsl.read_failed",
+                format!("读取 WSL 配置失败: {e}"),
+                format!("Failed to read WSL config: {e}"),
+            )
+        })?
+        .map_err(CommandError::from)
+}
@@ -4,6 +4,10 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use crate::error::AppError;
+use crate::settings::PathStrategy;
+
+/// Claude 配置目录中用于识别“正在使用”的文件
+pub(crate) const CLAUDE_DIR_MARKERS: &[&str] = &["settings.json", ".credentials.json"];
 
 /// 获取 Claude Code 配置目录路径
 pub fn get_claude_config_dir() -> PathBuf {
@@ -11,9 +15,126 @@ pub fn get_claude_config_dir() -> PathBuf {
         return custom;
     }
 
-    dirs::home_dir()
-        .expect("无法获取用户主目录")
-        .join(".claude")
+    resolve_cli_dir("claude", ".claude", CLAUDE_DIR_MARKERS)
+}
+
+/// 配置目录的一个候选位置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathCandidate {
+    pub strategy: PathStrategy,
+    pub path: PathBuf,
+    pub exists: bool,
+    /// 目录中存在该 CLI 的配置文件
+    pub in_use: bool,
+}
+
+/// 按策略列出 CLI 配置目录的候选位置（`Auto` 不在其中）
+///
+/// `AppData` / `LocalAppData` 下目录名不带前导点，如 `%APPDATA%\claude`
+pub fn path_candidates(dir_name: &str, markers: &[&str]) -> Vec<PathCandidate> {
+    let home = dirs::home_dir().expect("无法获取用户主目录");
+    let bare = dir_name.trim_start_matches('.');
+    [
+        (PathStrategy::Home, Some(home.join(dir_name))),
+        (
+            PathStrategy::AppData,
+            dirs::config_dir().map(|d| d.join(bare)),
+        ),
+        (
+            PathStrategy::LocalAppData,
+            dirs::data_local_dir().map(|d| d.join(bare)),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(strategy, path)| {
+        let path = path?;
+        Some(PathCandidate {
+            strategy,
+            exists: path.is_dir(),
+            in_use: markers.iter().any(|marker| path.join(marker).exists()),
+            path,
+        })
+    })
+    .collect()
+}
+
+/// 自动检测：优先含配置文件的目录，其次已存在的目录，都没有时使用主目录
+pub fn detect_path_strategy(candidates: &[PathCandidate]) -> PathStrategy {
+    candidates
+        .iter()
+        .find(|candidate| candidate.in_use)
+        .or_else(|| candidates.iter().find(|candidate| candidate.exists))
+        .map_or(PathStrategy::Home, |candidate| candidate.strategy)
+}
+
+/// 按设置中的解析方式得到 CLI 默认配置目录（`app` 为 claude / codex / gemini）
+pub(crate) fn resolve_cli_dir(app: &str, dir_name: &str, markers: &[&str]) -> PathBuf {
+    let home = dirs::home_dir().expect("无法获取用户主目录");
+    let strategy = match crate::settings::get_path_strategy(app) {
+        // 其他平台的 CLI 均使用主目录，无需检测
+        PathStrategy::Home => return home.join(dir_name),
+        PathStrategy::Auto if !cfg!(windows) => return home.join(dir_name),
+        strategy => strategy,
+    };
+    let candidates = path_candidates(dir_name, markers);
+    let strategy = match strategy {
+        PathStrategy::Auto => detect_path_strategy(&candidates),
+        strategy => strategy,
+    };
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.strategy == strategy)
+        .map_or_else(|| home.join(dir_name), |candidate| candidate.path)
+}
+
+/// 配置目录的解析情况，供设置页展示
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDirResolution {
+    /// 设置中的解析方式
+    pub strategy: PathStrategy,
+    /// 自动检测的结果（仅供参考，`strategy` 非 `Auto` 时不生效）
+    pub detected: PathStrategy,
+    /// 设置了目录覆盖（此时忽略解析方式）
+    pub overridden: bool,
+    /// 当前生效的目录
+    pub dir: PathBuf,
+    pub candidates: Vec<PathCandidate>,
+}
+
+/// 获取应用配置目录的解析情况
+pub fn get_config_dir_resolution(app: &crate::app_config::AppType) -> ConfigDirResolution {
+    use crate::app_config::AppType;
+
+    let (dir_name, markers, override_dir, dir) = match app {
+        AppType::Claude => (
+            ".claude",
+            CLAUDE_DIR_MARKERS,
+            crate::settings::get_claude_override_dir(),
+            get_claude_config_dir(),
+        ),
+        AppType::Codex => (
+            ".codex",
+            crate::codex_config::CODEX_DIR_MARKERS,
+            crate::settings::get_codex_override_dir(),
+            crate::codex_config::get_codex_config_dir(),
+        ),
+        AppType::Gemini => (
+            ".gemini",
+            crate::gemini_config::GEMINI_DIR_MARKERS,
+            crate::settings::get_gemini_override_dir(),
+            crate::gemini_config::get_gemini_dir(),
+        ),
+    };
+    let candidates = path_candidates(dir_name, markers);
+    ConfigDirResolution {
+        strategy: crate::settings::get_path_strategy(app.as_str()),
+        detected: detect_path_strategy(&candidates),
+        overridden: override_dir.is_some(),
+        dir,
+        candidates,
+    }
 }
 
 /// 默认 Claude MCP 配置文件路径 (~/.claude.json)
@@ -62,6 +183,57 @@ pub fn get_claude_settings_path() -> PathBuf {
     settings
 }
 
+/// 获取 Claude OAuth 凭据文件路径 (~/.claude/.credentials.json)
+pub fn get_claude_credentials_path() -> PathBuf {
+    get_claude_config_dir().join(".credentials.json")
+}
+
+/// 读取 Claude OAuth 凭据原文（不存在时返回 None）
+///
+/// 优先读取 `.credentials.json`；macOS 上文件不存在时读取钥匙串
+pub fn read_claude_credentials() -> Result<Option<String>, AppError> {
+    let path = get_claude_credentials_path();
+    if !path.exists() {
+        return crate::claude_keychain::read();
+    }
+    fs::read_to_string(&path)
+        .map(Some)
+        .map_err(|e| AppError::io(&path, e))
+}
+
+/// 写入 Claude OAuth 凭据
+///
+/// macOS 上写入钥匙串（凭据文件已存在时一并更新），其他平台写入 `.credentials.json`（原子操作，权限 600）
+pub fn write_claude_credentials(content: &str) -> Result<(), AppError> {
+    let path = get_claude_credentials_path();
+    if crate::claude_keychain::write(content)? && !path.exists() {
+        return Ok(());
+    }
+    write_text_file(&path, content)?;
+
+    // 设置文件权限为 600（仅所有者可读写）
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&path)
+            .map_err(|e| AppError::io(&path, e))?
+            .permissions();
+        perms.set_mode(0o600);
+        fs::set_permissions(&path, perms).map_err(|e| AppError::io(&path, e))?;
+    }
+
+    Ok(())
+}
+
+/// 删除 Claude OAuth 凭据（凭据文件及 macOS 钥匙串项）
+pub fn delete_claude_credentials() -> Result<(), AppError> {
+    let path = get_claude_credentials_path();
+    if path.exists() {
+        delete_file(&path)?;
+    }
+    crate::claude_keychain::delete()
+}
+
 /// 获取应用配置目录路径 (~/.cc-switch)
 pub fn get_app_config_dir() -> PathBuf {
     if let Some(custom) = crate::app_store::get_app_config_dir_override() {
@@ -185,13 +357,32 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
             source: e,
         })?;
     }
+    crate::managed_files::record_write(path, data);
     Ok(())
 }
 
+/// 内容的 SHA-256（小写十六进制），用于校验和与变更检测
+pub(crate) fn sha256_hex(content: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn sha256_hex_matches_known_digest() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
     #[test]
     fn derive_mcp_path_from_override_preserves_folder_name() {
         let override_dir = PathBuf::from("/tmp/profile/.claude");
@@ -221,6 +412,32 @@ mod tests {
         let override_dir = PathBuf::from("/");
         assert!(derive_mcp_path_from_override(&override_dir).is_none());
     }
+
+    #[test]
+    fn detect_path_strategy_prefers_dir_with_config_files() {
+        let candidate = |strategy, exists, in_use| PathCandidate {
+            strategy,
+            path: PathBuf::from("/tmp"),
+            exists,
+            in_use,
+        };
+        assert_eq!(detect_path_strategy(&[]), PathStrategy::Home);
+        assert_eq!(
+            detect_path_strategy(&[
+                candidate(PathStrategy::Home, false, false),
+                candidate(PathStrategy::AppData, true, false),
+            ]),
+            PathStrategy::AppData
+        );
+        assert_eq!(
+            detect_path_strategy(&[
+                candidate(PathStrategy::Home, true, false),
+                candidate(PathStrategy::AppData, true, false),
+                candidate(PathStrategy::LocalAppData, true, true),
+            ]),
+            PathStrategy::LocalAppData
+        );
+    }
 }
 
 /// 复制文件
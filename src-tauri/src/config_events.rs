@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use tauri::Emitter;
+
+/// 配置变更事件名
+pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+
+/// `config-changed` 事件负载
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChanged {
+    /// 单调递增的配置修订号（进程内有效，重启后从 0 开始）
+    pub revision: u64,
+    /// 变更时间（毫秒时间戳）
+    pub changed_at: i64,
+}
+
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn emitter() -> &'static OnceLock<tauri::AppHandle> {
+    static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+    &HANDLE
+}
+
+/// 注册事件发送句柄（setup 阶段调用一次）
+pub fn init(app: tauri::AppHandle) {
+    let _ = emitter().set(app);
+}
+
+/// 当前配置修订号
+pub fn current_revision() -> u64 {
+    REVISION.load(Ordering::SeqCst)
+}
+
+/// 配置落盘后递增修订号并通知前端（托盘切换、深链接导入、调度器等任意来源）
+pub fn notify_changed() -> u64 {
+    let revision = REVISION.fetch_add(1, Ordering::SeqCst) + 1;
+    if let Some(app) = emitter().get() {
+        let payload = ConfigChanged {
+            revision,
+            changed_at: chrono::Utc::now().timestamp_millis(),
+        };
+        if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, payload) {
+            log::warn!("发射配置变更事件失败: {e}");
+        }
+    }
+    revision
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revision_increases_monotonically() {
+        let before = current_revision();
+        let first = notify_changed();
+        let second = notify_changed();
+        assert!(first > before);
+        assert!(second > first);
+        assert!(current_revision() >= second);
+    }
+}
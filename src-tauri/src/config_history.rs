@@ -0,0 +1,345 @@
+//! config.json 版本历史
+//!
+//! 每次由 cc-switch 写入 config.json 后，记录与上一版本之间的差异（按 JSON Pointer 路径），
+//! 保存在 `~/.cc-switch/config_history.json`。历史由基线快照加逐版本的差异组成，
+//! 最旧的版本超出数量、体积或保留期限时折叠进基线，因此任一保留版本都可以完整还原。
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::app_config::MultiAppConfig;
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+use crate::error::AppError;
+
+/// 最多保留的版本数
+const MAX_VERSIONS: usize = 200;
+/// 版本保留期限（天）
+const MAX_AGE_DAYS: i64 = 90;
+/// 历史文件体积上限（字节，近似值）
+const MAX_HISTORY_BYTES: usize = 5 * 1024 * 1024;
+
+/// 串行化历史文件的读改写
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// 单个字段的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChange {
+    /// JSON Pointer，如 `/codex/providers/<id>/settingsConfig/config`
+    pub path: String,
+    /// 变化前的值（新增时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    /// 变化后的值（删除时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+/// 版本摘要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigVersion {
+    pub id: u64,
+    /// 写入时间（毫秒时间戳）
+    pub created_at: i64,
+    /// 相对上一版本变化的路径
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionEntry {
+    id: u64,
+    created_at: i64,
+    changes: Vec<ConfigChange>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryFile {
+    /// 最旧保留版本之前的完整配置
+    #[serde(default)]
+    base: Value,
+    #[serde(default)]
+    versions: Vec<VersionEntry>,
+}
+
+impl HistoryFile {
+    /// 还原指定版本的完整配置
+    fn snapshot(&self, id: u64) -> Option<Value> {
+        let index = self.versions.iter().position(|entry| entry.id == id)?;
+        let mut value = self.base.clone();
+        for entry in &self.versions[..=index] {
+            apply_changes(&mut value, &entry.changes);
+        }
+        Some(value)
+    }
+
+    fn latest(&self) -> Option<Value> {
+        self.versions
+            .last()
+            .and_then(|entry| self.snapshot(entry.id))
+    }
+
+    /// 追加新版本；与最新版本无差异时返回 false
+    fn push(&mut self, current: Value, now: i64) -> bool {
+        let Some(latest) = self.latest() else {
+            self.base = current;
+            self.versions.push(VersionEntry {
+                id: 1,
+                created_at: now,
+                changes: Vec::new(),
+            });
+            return true;
+        };
+
+        let mut changes = Vec::new();
+        diff_values("", &latest, &current, &mut changes);
+        if changes.is_empty() {
+            return false;
+        }
+        let id = self.versions.last().map_or(1, |entry| entry.id + 1);
+        self.versions.push(VersionEntry {
+            id,
+            created_at: now,
+            changes,
+        });
+        true
+    }
+
+    /// 将超出数量、期限或体积的最旧版本折叠进基线（至少保留最新版本）
+    fn prune(&mut self, now: i64) {
+        let cutoff = now - MAX_AGE_DAYS * 24 * 60 * 60 * 1000;
+        let mut total = json_len(&self.base)
+            + self
+                .versions
+                .iter()
+                .map(|entry| json_len(&entry.changes))
+                .sum::<usize>();
+
+        while self.versions.len() > 1
+            && (self.versions.len() > MAX_VERSIONS
+                || self.versions[0].created_at < cutoff
+                || total > MAX_HISTORY_BYTES)
+        {
+            let oldest = self.versions.remove(0);
+            total = total.saturating_sub(json_len(&oldest.changes) + json_len(&self.base));
+            apply_changes(&mut self.base, &oldest.changes);
+            total += json_len(&self.base);
+        }
+    }
+}
+
+/// 序列化后的体积（用于估算历史文件大小）
+fn json_len<T: Serialize>(value: &T) -> usize {
+    serde_json::to_vec(value).map_or(0, |bytes| bytes.len())
+}
+
+fn history_path() -> std::path::PathBuf {
+    get_app_config_dir().join("config_history.json")
+}
+
+fn load() -> Result<HistoryFile, AppError> {
+    let path = history_path();
+    if !path.exists() {
+        return Ok(HistoryFile::default());
+    }
+    read_json_file(&path)
+}
+
+/// 记录本次写入的配置版本（失败仅记录日志，不影响写入结果）
+pub fn record(config: &MultiAppConfig) {
+    let result = serde_json::to_value(config)
+        .map_err(|e| AppError::JsonSerialize { source: e })
+        .and_then(|current| {
+            let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let mut history = load().unwrap_or_else(|e| {
+                log::warn!("读取配置历史失败，将重新开始记录: {e}");
+                HistoryFile::default()
+            });
+            let now = chrono::Utc::now().timestamp_millis();
+            if !history.push(current, now) {
+                return Ok(());
+            }
+            history.prune(now);
+            write_json_file(&history_path(), &history)
+        });
+    if let Err(e) = result {
+        log::warn!("记录配置历史失败: {e}");
+    }
+}
+
+/// 列出保留的版本（新到旧）
+pub fn list() -> Result<Vec<ConfigVersion>, AppError> {
+    let history = load()?;
+    Ok(history
+        .versions
+        .iter()
+        .rev()
+        .map(|entry| ConfigVersion {
+            id: entry.id,
+            created_at: entry.created_at,
+            paths: entry
+                .changes
+                .iter()
+                .map(|change| change.path.clone())
+                .collect(),
+        })
+        .collect())
+}
+
+/// 比较两个版本（`from` → `to`）之间的差异
+pub fn diff(from: u64, to: u64) -> Result<Vec<ConfigChange>, AppError> {
+    let history = load()?;
+    let before = history.snapshot(from).ok_or_else(|| not_found(from))?;
+    let after = history.snapshot(to).ok_or_else(|| not_found(to))?;
+    let mut changes = Vec::new();
+    diff_values("", &before, &after, &mut changes);
+    Ok(changes)
+}
+
+/// 还原指定版本的完整配置
+pub fn snapshot(id: u64) -> Result<MultiAppConfig, AppError> {
+    let value = load()?.snapshot(id).ok_or_else(|| not_found(id))?;
+    serde_json::from_value(value).map_err(|e| {
+        AppError::localized(
+            "config_history.invalid_snapshot",
+            format!("配置历史版本 {id} 无法解析: {e}"),
+            format!("Config history version {id} cannot be parsed: {e}"),
+        )
+    })
+}
+
+fn not_found(id: u64) -> AppError {
+    AppError::localized(
+        "config_history.not_found",
+        format!("配置历史版本不存在或已被清理: {id}"),
+        format!("Config history version not found or already pruned: {id}"),
+    )
+}
+
+/// 按对象键递归比较，数组与标量整体比较
+fn diff_values(path: &str, before: &Value, after: &Value, out: &mut Vec<ConfigChange>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            for (key, old) in before {
+                let child = format!("{path}/{}", escape_token(key));
+                match after.get(key) {
+                    Some(new) => diff_values(&child, old, new, out),
+                    None => out.push(ConfigChange {
+                        path: child,
+                        before: Some(old.clone()),
+                        after: None,
+                    }),
+                }
+            }
+            for (key, new) in after {
+                if !before.contains_key(key) {
+                    out.push(ConfigChange {
+                        path: format!("{path}/{}", escape_token(key)),
+                        before: None,
+                        after: Some(new.clone()),
+                    });
+                }
+            }
+        }
+        _ if before == after => {}
+        _ => out.push(ConfigChange {
+            path: path.to_string(),
+            before: Some(before.clone()),
+            after: Some(after.clone()),
+        }),
+    }
+}
+
+fn apply_changes(root: &mut Value, changes: &[ConfigChange]) {
+    for change in changes {
+        set_pointer(root, &change.path, change.after.clone());
+    }
+}
+
+/// 按 JSON Pointer 写入（`None` 表示删除），缺失的中间对象自动创建
+fn set_pointer(root: &mut Value, path: &str, value: Option<Value>) {
+    let tokens: Vec<String> = path.split('/').skip(1).map(unescape_token).collect();
+    let Some((last, parents)) = tokens.split_last() else {
+        *root = value.unwrap_or(Value::Null);
+        return;
+    };
+    let mut node = root;
+    for token in parents {
+        node = object_mut(node)
+            .entry(token.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    let map = object_mut(node);
+    match value {
+        Some(value) => {
+            map.insert(last.clone(), value);
+        }
+        None => {
+            map.remove(last);
+        }
+    }
+}
+
+fn object_mut(node: &mut Value) -> &mut Map<String, Value> {
+    if !node.is_object() {
+        *node = Value::Object(Map::new());
+    }
+    match node {
+        Value::Object(map) => map,
+        _ => unreachable!("node was just replaced with an object"),
+    }
+}
+
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn versions_replay_deltas_and_survive_pruning() {
+        let v1 = json!({ "codex": { "providers": { "a/b": { "baseUrl": "https://one" } } } });
+        let v2 = json!({ "codex": { "providers": { "a/b": { "baseUrl": "https://two" } } } });
+        let v3 = json!({ "codex": { "providers": {} }, "claude": { "current": "x" } });
+
+        let mut history = HistoryFile::default();
+        assert!(history.push(v1.clone(), 0));
+        assert!(
+            !history.push(v1.clone(), 1),
+            "unchanged config adds no version"
+        );
+        assert!(history.push(v2.clone(), 2));
+        assert!(history.push(v3.clone(), 3));
+
+        assert_eq!(
+            history.versions[1].changes[0].path,
+            "/codex/providers/a~1b/baseUrl"
+        );
+        assert_eq!(
+            history.versions[1].changes[0].after,
+            Some(json!("https://two"))
+        );
+        assert_eq!(history.snapshot(1), Some(v1));
+        assert_eq!(history.snapshot(2), Some(v2.clone()));
+        assert_eq!(history.latest(), Some(v3.clone()));
+
+        // 超过保留期限的版本折叠进基线，保留版本仍可完整还原
+        history.prune(MAX_AGE_DAYS * 24 * 60 * 60 * 1000 + 2);
+        assert_eq!(
+            history.versions.iter().map(|v| v.id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(history.snapshot(2), Some(v2));
+        assert_eq!(history.snapshot(3), Some(v3));
+    }
+}
@@ -0,0 +1,402 @@
+//! config.json 启动完整性检查
+//!
+//! 每次由 cc-switch 写入 config.json 后在同目录记录 SHA-256 校验和（`config.json.sha256`）；
+//! 启动时比对校验和以发现外部修改或写入中断，并对 schema 版本做闸门检查。
+//! 加载失败时生成详细报告（解析位置、可用备份等）交由前端展示修复选项。
+//!
+//! 运行期间另在内存中记住最近一次加载或写入的内容哈希：配置目录位于同步盘时，
+//! 其他机器同步来的版本（连同其 `.sha256`）会在保存前被发现，而不是被直接覆盖。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{atomic_write, get_app_config_dir, get_app_config_path, sha256_hex};
+use crate::error::AppError;
+
+/// 各配置文件最近一次由本进程加载或写入时的内容哈希
+static KNOWN_HASHES: Mutex<BTreeMap<PathBuf, String>> = Mutex::new(BTreeMap::new());
+
+/// 各配置文件对应的内存配置在最近一次加载或保存时的哈希（见 [`remember_state`]）
+static STATE_HASHES: Mutex<BTreeMap<PathBuf, String>> = Mutex::new(BTreeMap::new());
+
+/// 当前支持的 config.json schema 版本
+pub const CONFIG_SCHEMA_VERSION: u32 = 2;
+
+/// 报告中最多列出的历史备份数量
+const MAX_BACKUP_OPTIONS: usize = 5;
+
+/// 校验和比对结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumStatus {
+    /// 与上次写入一致
+    Match,
+    /// 文件在 cc-switch 之外被修改，或上次写入未完成
+    Mismatch,
+    /// 尚未记录校验和（旧版本或首次启动）
+    Missing,
+}
+
+/// 磁盘上的 config.json 已被外部修改（与内存中的版本不同）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalConfigChange {
+    pub path: String,
+    /// 磁盘文件修改时间（毫秒时间戳）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_at: Option<i64>,
+}
+
+/// JSON 解析错误位置（行列号从 1 开始）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseErrorDetail {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// 修复方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairKind {
+    /// 从备份文件恢复
+    RestoreBackup,
+    /// 移走损坏的配置，下次启动时重新创建
+    Reset,
+}
+
+/// 可选的修复方案
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairOption {
+    pub kind: RepairKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 备份修改时间（毫秒时间戳）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_at: Option<i64>,
+}
+
+/// 完整性检查报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub path: String,
+    pub expected_schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    pub checksum: ChecksumStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_error: Option<ParseErrorDetail>,
+    pub repair_options: Vec<RepairOption>,
+}
+
+fn checksum_path() -> PathBuf {
+    get_app_config_dir().join("config.json.sha256")
+}
+
+/// 比对内容与记录的校验和
+pub fn checksum_status(content: &[u8]) -> ChecksumStatus {
+    match fs::read_to_string(checksum_path()) {
+        Ok(stored) if stored.trim() == sha256_hex(content) => ChecksumStatus::Match,
+        Ok(_) => ChecksumStatus::Mismatch,
+        Err(_) => ChecksumStatus::Missing,
+    }
+}
+
+/// 记录 config.json 当前内容的校验和（失败仅记录日志，不影响写入结果）
+pub fn record_checksum() {
+    let path = get_app_config_path();
+    let result = fs::read(&path)
+        .map_err(|e| AppError::io(&path, e))
+        .and_then(|content| {
+            remember_content(&content);
+            atomic_write(&checksum_path(), sha256_hex(&content).as_bytes())
+        });
+    if let Err(e) = result {
+        log::warn!("记录 config.json 校验和失败: {e}");
+    }
+}
+
+/// 记住本进程当前持有的 config.json 内容（加载或写入后调用）
+pub fn remember_content(content: &[u8]) {
+    remember_at(&get_app_config_path(), content);
+}
+
+fn remember_at(path: &Path, content: &[u8]) {
+    KNOWN_HASHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(path.to_path_buf(), sha256_hex(content));
+}
+
+/// 按 JSON 值计算内存配置的哈希：对象键有序，不受 `HashMap` 迭代顺序与磁盘格式影响
+fn state_checksum<T: Serialize>(state: &T) -> Option<String> {
+    let value = serde_json::to_value(state).ok()?;
+    Some(sha256_hex(value.to_string().as_bytes()))
+}
+
+/// 记住本进程内存中的配置（加载或保存后调用），用于判断之后是否有未保存的修改
+pub fn remember_state<T: Serialize>(state: &T) {
+    remember_state_at(&get_app_config_path(), state);
+}
+
+fn remember_state_at<T: Serialize>(path: &Path, state: &T) {
+    let Some(checksum) = state_checksum(state) else {
+        return;
+    };
+    STATE_HASHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(path.to_path_buf(), checksum);
+}
+
+/// 内存中的配置是否与最近一次加载或保存时一致（未记录时视为不一致）
+pub fn state_unchanged<T: Serialize>(state: &T) -> bool {
+    state_unchanged_at(&get_app_config_path(), state)
+}
+
+fn state_unchanged_at<T: Serialize>(path: &Path, state: &T) -> bool {
+    let Some(checksum) = state_checksum(state) else {
+        return false;
+    };
+    STATE_HASHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(path)
+        .is_some_and(|known| *known == checksum)
+}
+
+/// 磁盘上的 config.json 与本进程上次加载或写入的内容不同时返回变更信息
+pub fn external_change() -> Option<ExternalConfigChange> {
+    external_change_at(&get_app_config_path())
+}
+
+fn external_change_at(path: &Path) -> Option<ExternalConfigChange> {
+    let known = KNOWN_HASHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(path)
+        .cloned()?;
+    let content = fs::read(path).ok()?;
+    (sha256_hex(&content) != known).then(|| ExternalConfigChange {
+        path: path.display().to_string(),
+        modified_at: modified_millis(path),
+    })
+}
+
+/// 保存前检查：磁盘版本已被外部修改（如同步盘同步了其他机器的版本）时拒绝覆盖
+pub fn ensure_not_changed_externally() -> Result<(), AppError> {
+    match external_change() {
+        None => Ok(()),
+        Some(change) => Err(AppError::localized(
+            "config.external_change",
+            format!(
+                "{} 已在 cc-switch 之外被修改，请先重新加载或确认覆盖",
+                change.path
+            ),
+            format!(
+                "{} was modified outside cc-switch; reload it or confirm overwriting first",
+                change.path
+            ),
+        )),
+    }
+}
+
+/// 确认以内存中的版本覆盖外部修改：将磁盘当前内容视为已知版本
+pub fn accept_external_change() -> Result<(), AppError> {
+    let path = get_app_config_path();
+    let content = fs::read(&path).map_err(|e| AppError::io(&path, e))?;
+    remember_content(&content);
+    Ok(())
+}
+
+/// schema 版本闸门：拒绝加载由更新版本写入的配置，避免旧版本覆盖未知字段
+pub fn check_schema_version(version: Option<u32>) -> Result<(), AppError> {
+    match version {
+        Some(found) if found > CONFIG_SCHEMA_VERSION => Err(AppError::localized(
+            "config.schema_too_new",
+            format!(
+                "config.json 的 schema 版本为 {found}，当前版本仅支持 {CONFIG_SCHEMA_VERSION}，请升级 CC Switch"
+            ),
+            format!(
+                "config.json schema version {found} is newer than supported version {CONFIG_SCHEMA_VERSION}; please upgrade CC Switch"
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 读取磁盘上的 config.json 生成完整报告（用于加载失败时展示）
+pub fn inspect() -> IntegrityReport {
+    let path = get_app_config_path();
+    let mut report = IntegrityReport {
+        path: path.display().to_string(),
+        expected_schema_version: CONFIG_SCHEMA_VERSION,
+        schema_version: None,
+        checksum: ChecksumStatus::Missing,
+        parse_error: None,
+        repair_options: repair_options(),
+    };
+    let Ok(content) = fs::read(&path) else {
+        return report;
+    };
+    report.checksum = checksum_status(&content);
+    match serde_json::from_slice::<serde_json::Value>(&content) {
+        Ok(value) => {
+            report.schema_version = value
+                .get("version")
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u32::try_from(v).ok());
+        }
+        Err(e) => {
+            report.parse_error = Some(ParseErrorDetail {
+                message: e.to_string(),
+                line: e.line(),
+                column: e.column(),
+            });
+        }
+    }
+    report
+}
+
+fn modified_millis(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let millis = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_millis();
+    i64::try_from(millis).ok()
+}
+
+/// 可用的修复方案：config.json.bak、最近的历史备份，最后是重置
+fn repair_options() -> Vec<RepairOption> {
+    let dir = get_app_config_dir();
+    let mut backups: Vec<(PathBuf, Option<i64>)> = fs::read_dir(dir.join("backups"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .map(|path| {
+            let modified = modified_millis(&path);
+            (path, modified)
+        })
+        .collect();
+    backups.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    backups.truncate(MAX_BACKUP_OPTIONS);
+
+    let bak = dir.join("config.json.bak");
+    let mut candidates = Vec::new();
+    if bak.is_file() {
+        let modified = modified_millis(&bak);
+        candidates.push((bak, modified));
+    }
+    candidates.extend(backups);
+
+    let mut options: Vec<RepairOption> = candidates
+        .into_iter()
+        .filter(|(path, _)| {
+            fs::read(path).ok().is_some_and(|content| {
+                serde_json::from_slice::<serde_json::Value>(&content).is_ok()
+            })
+        })
+        .map(|(path, modified_at)| RepairOption {
+            kind: RepairKind::RestoreBackup,
+            path: Some(path.display().to_string()),
+            modified_at,
+        })
+        .collect();
+    options.push(RepairOption {
+        kind: RepairKind::Reset,
+        path: None,
+        modified_at: None,
+    });
+    options
+}
+
+/// 执行修复：先将当前 config.json 另存为 `config.json.broken-<时间戳>`，
+/// 再从备份恢复（`RestoreBackup`）或留空由下次启动重新创建（`Reset`）
+pub fn repair(kind: RepairKind, backup_path: Option<&str>) -> Result<(), AppError> {
+    let config_path = get_app_config_path();
+    let restored = match kind {
+        RepairKind::RestoreBackup => {
+            let source = backup_path.map(PathBuf::from).ok_or_else(|| {
+                AppError::InvalidInput("恢复备份需要指定备份文件路径".to_string())
+            })?;
+            // 仅允许从报告中列出的备份恢复
+            let allowed = repair_options()
+                .iter()
+                .any(|option| option.path.as_deref() == Some(&*source.to_string_lossy()));
+            if !allowed {
+                return Err(AppError::InvalidInput(format!(
+                    "不是可用的备份文件: {}",
+                    source.display()
+                )));
+            }
+            Some(fs::read(&source).map_err(|e| AppError::io(&source, e))?)
+        }
+        RepairKind::Reset => None,
+    };
+
+    if config_path.exists() {
+        let ts = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let broken = get_app_config_dir().join(format!("config.json.broken-{ts}"));
+        fs::rename(&config_path, &broken).map_err(|e| AppError::io(&config_path, e))?;
+        log::info!("已将损坏的配置另存为 {}", broken.display());
+    }
+    if let Some(content) = restored {
+        atomic_write(&config_path, &content)?;
+        record_checksum();
+    } else {
+        let _ = fs::remove_file(checksum_path());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_gate_rejects_newer_versions() {
+        assert!(check_schema_version(None).is_ok());
+        assert!(check_schema_version(Some(CONFIG_SCHEMA_VERSION)).is_ok());
+        match check_schema_version(Some(CONFIG_SCHEMA_VERSION + 1)) {
+            Err(AppError::Localized { key, .. }) => assert_eq!(key, "config.schema_too_new"),
+            other => panic!("expected schema_too_new, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn detects_external_changes_until_remembered() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, b"{}").unwrap();
+        assert!(
+            external_change_at(&path).is_none(),
+            "unknown files are never flagged"
+        );
+        remember_at(&path, b"{}");
+        assert!(external_change_at(&path).is_none());
+
+        fs::write(&path, br#"{"version":2}"#).unwrap();
+        let change = external_change_at(&path).expect("external change");
+        assert_eq!(change.path, path.display().to_string());
+        remember_at(&path, &fs::read(&path).unwrap());
+        assert!(external_change_at(&path).is_none());
+    }
+
+    #[test]
+    fn checksum_is_stable_hex_sha256() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! 配置目录的咨询锁
+//!
+//! 单实例插件只能防止同一台机器重复启动；配置目录位于同步盘或 NFS 上被多台机器共用时，
+//! 写入 config.json 与 live 配置前先创建 `~/.cc-switch/config.lock`（记录主机与进程），
+//! 已被其他主机持有时等待片刻，仍未释放则报错，避免两边的写入交错。
+//!
+//! 持有期间后台定时刷新锁文件的修改时间；判断过期时看修改时间是否长时间未刷新，
+//! 同一主机上的锁还会检查持锁进程是否仍在运行，不比较对方主机记录的时钟。
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::get_app_config_dir;
+use crate::error::AppError;
+
+const LOCK_FILE: &str = "config.lock";
+
+/// 锁文件超过该时长未刷新视为过期（持锁进程崩溃或同步盘残留）
+const STALE_AFTER_MS: i64 = 30_000;
+
+/// 持锁期间刷新锁文件修改时间的间隔
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 锁被占用时的最长等待时间
+const WAIT_TIMEOUT: Duration = Duration::from_secs(3);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 本进程持有的锁文件及嵌套层数（保存配置与写入 live 配置可能嵌套调用）
+static HELD: Mutex<BTreeMap<PathBuf, usize>> = Mutex::new(BTreeMap::new());
+
+/// 锁文件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockOwner {
+    host: String,
+    pid: u32,
+    /// 获取时间（Unix 毫秒）
+    acquired_at: i64,
+}
+
+/// 本机主机名（查询可能需要启动子进程，缓存结果）
+fn local_host() -> &'static str {
+    static HOST: OnceLock<String> = OnceLock::new();
+    HOST.get_or_init(|| crate::placeholder::hostname().unwrap_or_else(|| "unknown".to_string()))
+}
+
+impl LockOwner {
+    fn current(now: i64) -> Self {
+        Self {
+            host: local_host().to_string(),
+            pid: std::process::id(),
+            acquired_at: now,
+        }
+    }
+
+    fn is_ours(&self) -> bool {
+        self.pid == std::process::id() && self.host == local_host()
+    }
+}
+
+/// 持有期间其他主机上的 cc-switch 无法写入配置；离开作用域时释放
+pub struct ConfigLockGuard {
+    path: PathBuf,
+}
+
+impl Drop for ConfigLockGuard {
+    fn drop(&mut self) {
+        let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(count) = held.get_mut(&self.path) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            held.remove(&self.path);
+            // 锁可能已被其他进程视为过期接管，只删除仍属于本进程的锁文件
+            if !read_owner(&self.path).is_some_and(|owner| owner.is_ours()) {
+                log::warn!("配置锁已被其他进程接管，跳过释放: {}", self.path.display());
+                return;
+            }
+            if let Err(e) = std::fs::remove_file(&self.path) {
+                if e.kind() != ErrorKind::NotFound {
+                    log::warn!("释放配置锁失败: {}: {e}", self.path.display());
+                }
+            }
+        }
+    }
+}
+
+/// 获取配置目录的锁（本进程已持有时直接复用）
+pub fn acquire() -> Result<ConfigLockGuard, AppError> {
+    acquire_at(&get_app_config_dir().join(LOCK_FILE))
+}
+
+fn acquire_at(path: &Path) -> Result<ConfigLockGuard, AppError> {
+    let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+    match held.get_mut(path) {
+        Some(count) => *count += 1,
+        None => {
+            create_lock_file(path)?;
+            held.insert(path.to_path_buf(), 1);
+            start_heartbeat();
+        }
+    }
+    Ok(ConfigLockGuard {
+        path: path.to_path_buf(),
+    })
+}
+
+fn create_lock_file(path: &Path) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    let deadline = Instant::now() + WAIT_TIMEOUT;
+    loop {
+        let now = chrono::Utc::now().timestamp_millis();
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                let owner = serde_json::to_vec(&LockOwner::current(now))
+                    .map_err(|source| AppError::JsonSerialize { source })?;
+                file.write_all(&owner).map_err(|e| AppError::io(path, e))?;
+                return Ok(());
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(AppError::io(path, e)),
+        }
+
+        let owner = read_owner(path);
+        if is_stale(path, owner.as_ref(), now) {
+            log::warn!("移除过期的配置锁: {owner:?}");
+            match std::fs::remove_file(path) {
+                Ok(()) => continue,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(AppError::io(path, e)),
+            }
+        }
+
+        if Instant::now() >= deadline {
+            let (host, pid) = owner
+                .map(|owner| (owner.host, owner.pid.to_string()))
+                .unwrap_or_else(|| ("unknown".to_string(), "?".to_string()));
+            return Err(AppError::localized(
+                "config.locked",
+                format!("配置正被主机 {host} 上的 cc-switch（PID {pid}）写入，请稍后重试"),
+                format!(
+                    "Config is locked by cc-switch on host {host} (PID {pid}); try again later"
+                ),
+            ));
+        }
+        std::thread::sleep(RETRY_INTERVAL);
+    }
+}
+
+/// 后台定时刷新本进程持有的锁文件（整个进程只启动一次）
+fn start_heartbeat() {
+    static STARTED: Once = Once::new();
+    STARTED.call_once(|| {
+        let spawned = std::thread::Builder::new()
+            .name("config-lock-heartbeat".into())
+            .spawn(|| loop {
+                std::thread::sleep(HEARTBEAT_INTERVAL);
+                let held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+                for path in held.keys() {
+                    if let Err(e) = refresh(path) {
+                        log::warn!("刷新配置锁失败: {}: {e}", path.display());
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            log::warn!("启动配置锁刷新线程失败: {e}");
+        }
+    });
+}
+
+/// 刷新锁文件的修改时间（已被其他进程接管时不处理）
+fn refresh(path: &Path) -> std::io::Result<()> {
+    if !read_owner(path).is_some_and(|owner| owner.is_ours()) {
+        return Ok(());
+    }
+    OpenOptions::new()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+}
+
+/// 锁是否过期：同一主机上按持锁进程是否存在判断，否则（或无法判断时）按修改时间判断
+fn is_stale(path: &Path, owner: Option<&LockOwner>, now: i64) -> bool {
+    if let Some(owner) = owner {
+        if owner.host == local_host() {
+            // 本进程未持有却记录为本进程：上次异常退出残留，且 PID 被复用
+            if owner.pid == std::process::id() {
+                return true;
+            }
+            if let Some(alive) = process_alive(owner.pid) {
+                return !alive;
+            }
+        }
+    }
+    // 内容无法解析（对方刚创建尚未写完，或文件损坏）或其他主机的锁按修改时间判断
+    modified_ms(path).is_some_and(|modified| now - modified > STALE_AFTER_MS)
+}
+
+/// 本机进程是否存在（无法判断时返回 None）
+fn process_alive(pid: u32) -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        Some(Path::new(&format!("/proc/{pid}")).exists())
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        std::process::Command::new("ps")
+            .args(["-p", &pid.to_string(), "-o", "pid="])
+            .output()
+            .ok()
+            .map(|output| output.status.success())
+    }
+    #[cfg(windows)]
+    {
+        let output = std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/NH", "/FO", "CSV"])
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        Some(text.contains(&format!("\"{pid}\"")))
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+fn read_owner(path: &Path) -> Option<LockOwner> {
+    let content = std::fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn modified_ms(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let since_epoch = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+    i64::try_from(since_epoch.as_millis()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_owner(path: &Path, owner: &LockOwner, age: Duration) {
+        std::fs::write(path, serde_json::to_vec(owner).unwrap()).unwrap();
+        OpenOptions::new()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - age)
+            .unwrap();
+    }
+
+    #[test]
+    fn lock_is_reentrant_and_replaces_stale_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCK_FILE);
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let other = LockOwner {
+            host: "other-host".into(),
+            pid: 42,
+            // 对方时钟偏差不影响判断
+            acquired_at: now + 3_600_000,
+        };
+        write_owner(
+            &path,
+            &other,
+            Duration::from_millis(STALE_AFTER_MS as u64 + 1_000),
+        );
+
+        let outer = acquire_at(&path).unwrap();
+        let owner = read_owner(&path).unwrap();
+        assert_eq!(owner.pid, std::process::id());
+        {
+            let _inner = acquire_at(&path).unwrap();
+        }
+        assert!(path.exists(), "inner guard must not release the lock");
+        drop(outer);
+        assert!(!path.exists());
+
+        write_owner(&path, &other, Duration::ZERO);
+        assert!(!is_stale(&path, Some(&other), now));
+        let dead = LockOwner {
+            pid: u32::MAX - 1,
+            ..LockOwner::current(now)
+        };
+        write_owner(&path, &dead, Duration::ZERO);
+        assert!(is_stale(&path, Some(&dead), now));
+    }
+
+    #[test]
+    fn guard_keeps_lock_taken_over_by_another_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCK_FILE);
+
+        let guard = acquire_at(&path).unwrap();
+        let other = LockOwner {
+            host: "other-host".into(),
+            pid: 42,
+            acquired_at: 0,
+        };
+        write_owner(&path, &other, Duration::ZERO);
+        drop(guard);
+        assert_eq!(read_owner(&path), Some(other));
+    }
+}
@@ -0,0 +1,256 @@
+//! 供应商配置键迁移注册表
+//!
+//! 上游客户端重命名配置键时，只需在 [`MIGRATIONS`] 中追加一条记录，
+//! 加载 config.json 与保存供应商时会自动应用，无需再改动业务代码。
+//!
+//! 迁移作用于供应商 `settingsConfig` 中的键值表：Claude / Gemini 为 `env`，Codex 为 `auth`。
+//! 所有操作均为幂等：重复执行不会产生变化。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::app_config::AppType;
+
+/// 单个迁移操作
+#[derive(Debug, Clone, Copy)]
+pub enum MigrationOp {
+    /// 目标键缺失时，取第一个存在的来源键的值填充（来源键保留）
+    Fill {
+        to: &'static str,
+        from: &'static [&'static str],
+    },
+    /// 重命名键：目标键缺失时搬移值，随后删除来源键
+    Rename {
+        from: &'static str,
+        to: &'static str,
+    },
+    /// 删除已废弃的键
+    Remove { key: &'static str },
+}
+
+/// 一条已登记的配置迁移
+#[derive(Debug, Clone)]
+pub struct ConfigMigration {
+    /// 唯一标识（写入 config.json 的应用记录）
+    pub id: &'static str,
+    pub app: AppType,
+    /// 引入该迁移的 CC Switch 版本
+    pub version: &'static str,
+    pub description: &'static str,
+    pub ops: &'static [MigrationOp],
+}
+
+/// 迁移注册表（按登记顺序执行）
+pub const MIGRATIONS: &[ConfigMigration] = &[ConfigMigration {
+    id: "claude-small-fast-model-to-default-models",
+    app: AppType::Claude,
+    version: "3.6.0",
+    description: "ANTHROPIC_SMALL_FAST_MODEL → ANTHROPIC_DEFAULT_{HAIKU,SONNET,OPUS}_MODEL",
+    ops: &[
+        MigrationOp::Fill {
+            to: "ANTHROPIC_DEFAULT_HAIKU_MODEL",
+            from: &["ANTHROPIC_SMALL_FAST_MODEL", "ANTHROPIC_MODEL"],
+        },
+        MigrationOp::Fill {
+            to: "ANTHROPIC_DEFAULT_SONNET_MODEL",
+            from: &["ANTHROPIC_MODEL", "ANTHROPIC_SMALL_FAST_MODEL"],
+        },
+        MigrationOp::Fill {
+            to: "ANTHROPIC_DEFAULT_OPUS_MODEL",
+            from: &["ANTHROPIC_MODEL", "ANTHROPIC_SMALL_FAST_MODEL"],
+        },
+        MigrationOp::Remove {
+            key: "ANTHROPIC_SMALL_FAST_MODEL",
+        },
+    ],
+}];
+
+/// config.json 中记录的迁移应用情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigrationRecord {
+    pub id: String,
+    /// 首次生效时间（毫秒时间戳）
+    pub first_applied_at: i64,
+    /// 最近一次生效时间（毫秒时间戳）
+    pub last_applied_at: i64,
+    /// 累计改写的供应商配置次数
+    pub applied_count: u64,
+}
+
+/// 返回给前端的迁移信息（注册表元数据 + 应用记录）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedConfigMigration {
+    pub id: String,
+    pub app: AppType,
+    pub version: &'static str,
+    pub description: &'static str,
+    pub first_applied_at: i64,
+    pub last_applied_at: i64,
+    pub applied_count: u64,
+}
+
+fn target_map<'a>(app: &AppType, settings: &'a mut Value) -> Option<&'a mut Map<String, Value>> {
+    let key = match app {
+        AppType::Claude | AppType::Gemini => "env",
+        AppType::Codex => "auth",
+    };
+    settings.get_mut(key).and_then(Value::as_object_mut)
+}
+
+fn apply_op(map: &mut Map<String, Value>, op: &MigrationOp) -> bool {
+    match *op {
+        MigrationOp::Fill { to, from } => {
+            if map.contains_key(to) {
+                return false;
+            }
+            let Some(value) = from
+                .iter()
+                .find_map(|key| map.get(*key).and_then(Value::as_str))
+                .map(str::to_string)
+            else {
+                return false;
+            };
+            map.insert(to.to_string(), Value::String(value));
+            true
+        }
+        MigrationOp::Rename { from, to } => {
+            let Some(value) = map.remove(from) else {
+                return false;
+            };
+            map.entry(to.to_string()).or_insert(value);
+            true
+        }
+        MigrationOp::Remove { key } => map.remove(key).is_some(),
+    }
+}
+
+/// 对单个供应商的 settingsConfig 应用该应用类型的全部迁移，返回实际产生变化的迁移 ID
+pub fn apply(app: &AppType, settings: &mut Value) -> Vec<&'static str> {
+    let Some(map) = target_map(app, settings) else {
+        return Vec::new();
+    };
+    MIGRATIONS
+        .iter()
+        .filter(|migration| &migration.app == app)
+        .filter_map(|migration| {
+            // 逐个执行所有操作，不能短路
+            let changed = migration
+                .ops
+                .iter()
+                .fold(false, |changed, op| apply_op(map, op) | changed);
+            changed.then_some(migration.id)
+        })
+        .collect()
+}
+
+/// 将本次生效的迁移写入应用记录
+pub fn record(records: &mut Vec<AppliedMigrationRecord>, ids: &[&'static str]) {
+    if ids.is_empty() {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    for id in ids {
+        match records.iter_mut().find(|r| r.id == *id) {
+            Some(existing) => {
+                existing.last_applied_at = now;
+                existing.applied_count += 1;
+            }
+            None => records.push(AppliedMigrationRecord {
+                id: id.to_string(),
+                first_applied_at: now,
+                last_applied_at: now,
+                applied_count: 1,
+            }),
+        }
+    }
+}
+
+/// 合并应用记录与注册表元数据（已从注册表移除的迁移不再列出）
+pub fn list_applied(records: &[AppliedMigrationRecord]) -> Vec<AppliedConfigMigration> {
+    records
+        .iter()
+        .filter_map(|record| {
+            let migration = MIGRATIONS.iter().find(|m| m.id == record.id)?;
+            Some(AppliedConfigMigration {
+                id: record.id.clone(),
+                app: migration.app.clone(),
+                version: migration.version,
+                description: migration.description,
+                first_applied_at: record.first_applied_at,
+                last_applied_at: record.last_applied_at,
+                applied_count: record.applied_count,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrates_claude_small_fast_model() {
+        let mut settings = json!({
+            "env": {
+                "ANTHROPIC_MODEL": "main",
+                "ANTHROPIC_SMALL_FAST_MODEL": "fast",
+                "ANTHROPIC_DEFAULT_OPUS_MODEL": "opus"
+            }
+        });
+        let applied = apply(&AppType::Claude, &mut settings);
+        assert_eq!(applied, vec!["claude-small-fast-model-to-default-models"]);
+
+        let env = &settings["env"];
+        assert_eq!(env["ANTHROPIC_DEFAULT_HAIKU_MODEL"], "fast");
+        assert_eq!(env["ANTHROPIC_DEFAULT_SONNET_MODEL"], "main");
+        assert_eq!(env["ANTHROPIC_DEFAULT_OPUS_MODEL"], "opus");
+        assert!(env.get("ANTHROPIC_SMALL_FAST_MODEL").is_none());
+
+        // 幂等：再次执行不产生变化
+        assert!(apply(&AppType::Claude, &mut settings).is_empty());
+        // 其他应用类型不受影响
+        let mut codex = json!({ "auth": { "ANTHROPIC_SMALL_FAST_MODEL": "fast" } });
+        assert!(apply(&AppType::Codex, &mut codex).is_empty());
+    }
+
+    #[test]
+    fn rename_keeps_existing_target() {
+        let mut map = json!({ "OLD": "a", "NEW": "b" })
+            .as_object()
+            .cloned()
+            .unwrap();
+        assert!(apply_op(
+            &mut map,
+            &MigrationOp::Rename {
+                from: "OLD",
+                to: "NEW"
+            }
+        ));
+        assert_eq!(map.get("NEW"), Some(&json!("b")));
+        assert!(!map.contains_key("OLD"));
+    }
+
+    #[test]
+    fn records_and_lists_applied_migrations() {
+        let mut records = Vec::new();
+        let id = MIGRATIONS[0].id;
+        record(&mut records, &[id]);
+        record(&mut records, &[id]);
+        record(&mut records, &[]);
+        records.push(AppliedMigrationRecord {
+            id: "removed-migration".to_string(),
+            first_applied_at: 0,
+            last_applied_at: 0,
+            applied_count: 1,
+        });
+
+        let listed = list_applied(&records);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, id);
+        assert_eq!(listed[0].applied_count, 2);
+        assert_eq!(listed[0].app, AppType::Claude);
+    }
+}
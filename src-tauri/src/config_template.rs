@@ -0,0 +1,255 @@
+//! 可提交到版本库的配置模板
+//!
+//! 导出完整的 config.json 结构，所有密钥替换为 `{{CLAUDE_KEY_1}}` 形式的占位符，
+//! 并附带需要提供的密钥清单；应用模板时按清单填回密钥。同一密钥在多处使用时共用一个占位符。
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::MultiAppConfig;
+use crate::error::AppError;
+use crate::services::passcode::replace_secrets;
+
+/// 模板格式版本
+pub const TEMPLATE_VERSION: u32 = 1;
+
+/// 模板需要的密钥
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSecret {
+    /// 占位符名称（不含花括号）
+    pub name: String,
+    /// 使用该密钥的供应商 / MCP 服务器（`claude/<供应商名>`、`mcp/<服务器 ID>`）
+    #[serde(default)]
+    pub used_by: Vec<String>,
+}
+
+/// 模板文件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigTemplate {
+    pub template_version: u32,
+    pub secrets: Vec<TemplateSecret>,
+    pub config: Value,
+}
+
+/// 按值去重地分配占位符
+#[derive(Default)]
+struct Redactor {
+    secrets: Vec<TemplateSecret>,
+    by_value: HashMap<String, usize>,
+    counters: HashMap<String, usize>,
+}
+
+impl Redactor {
+    fn redact(&mut self, value: &mut Value, prefix: &str, owner: Option<&str>) {
+        let this = RefCell::new(self);
+        replace_secrets(value, &|secret| {
+            // 空值与已替换过的占位符保持不变
+            if secret.is_empty() || is_placeholder(secret) {
+                return secret.to_string();
+            }
+            let mut redactor = this.borrow_mut();
+            let index = match redactor.by_value.get(secret) {
+                Some(index) => *index,
+                None => {
+                    let counter = redactor.counters.entry(prefix.to_string()).or_default();
+                    *counter += 1;
+                    let name = format!("{prefix}_KEY_{counter}");
+                    redactor.secrets.push(TemplateSecret {
+                        name,
+                        used_by: Vec::new(),
+                    });
+                    let index = redactor.secrets.len() - 1;
+                    redactor.by_value.insert(secret.to_string(), index);
+                    index
+                }
+            };
+            let entry = &mut redactor.secrets[index];
+            if let Some(owner) = owner {
+                if !entry.used_by.iter().any(|o| o == owner) {
+                    entry.used_by.push(owner.to_string());
+                }
+            }
+            format!("{{{{{}}}}}", entry.name)
+        });
+    }
+}
+
+fn is_placeholder(text: &str) -> bool {
+    text.starts_with("{{") && text.ends_with("}}")
+}
+
+/// 生成模板：供应商密钥按应用命名（`CLAUDE_KEY_n`），MCP 密钥为 `MCP_KEY_n`，其余为 `SECRET_KEY_n`
+pub fn redact(config: &MultiAppConfig) -> Result<ConfigTemplate, AppError> {
+    let mut value =
+        serde_json::to_value(config).map_err(|source| AppError::JsonSerialize { source })?;
+    let mut redactor = Redactor::default();
+
+    let mut apps: Vec<&String> = config.apps.keys().collect();
+    apps.sort();
+    for app in apps {
+        let manager = &config.apps[app];
+        let mut ids: Vec<&String> = manager.providers.keys().collect();
+        ids.sort_by_key(|id| (manager.providers[*id].sort_index, id.as_str()));
+        for id in ids {
+            if let Some(provider) = value
+                .get_mut(app)
+                .and_then(|v| v.get_mut("providers"))
+                .and_then(|v| v.get_mut(id))
+            {
+                let owner = format!("{app}/{}", manager.providers[id].name);
+                redactor.redact(provider, &app.to_ascii_uppercase(), Some(&owner));
+            }
+        }
+    }
+    if let Some(servers) = value
+        .get_mut("mcp")
+        .and_then(|v| v.get_mut("servers"))
+        .and_then(Value::as_object_mut)
+    {
+        let mut ids: Vec<String> = servers.keys().cloned().collect();
+        ids.sort();
+        for id in ids {
+            if let Some(server) = servers.get_mut(&id) {
+                redactor.redact(server, "MCP", Some(&format!("mcp/{id}")));
+            }
+        }
+    }
+    redactor.redact(&mut value, "SECRET", None);
+
+    Ok(ConfigTemplate {
+        template_version: TEMPLATE_VERSION,
+        secrets: redactor.secrets,
+        config: value,
+    })
+}
+
+/// 按清单填回密钥并解析为配置；缺少任一密钥时报错并列出缺失项
+pub fn rehydrate(
+    template: ConfigTemplate,
+    secrets: &HashMap<String, String>,
+) -> Result<MultiAppConfig, AppError> {
+    if template.template_version > TEMPLATE_VERSION {
+        return Err(AppError::localized(
+            "config_template.version_too_new",
+            format!(
+                "模板版本 {} 过新，请升级 CC Switch",
+                template.template_version
+            ),
+            format!(
+                "Template version {} is too new; please upgrade CC Switch",
+                template.template_version
+            ),
+        ));
+    }
+    let missing: BTreeSet<&str> = template
+        .secrets
+        .iter()
+        .map(|secret| secret.name.as_str())
+        .filter(|name| secrets.get(*name).is_none_or(|value| value.is_empty()))
+        .collect();
+    if !missing.is_empty() {
+        let list = missing.into_iter().collect::<Vec<_>>().join(", ");
+        return Err(AppError::localized(
+            "config_template.missing_secrets",
+            format!("缺少模板所需的密钥: {list}"),
+            format!("Missing secrets required by the template: {list}"),
+        ));
+    }
+
+    let replacements: Vec<(String, &str)> = template
+        .secrets
+        .iter()
+        .map(|secret| {
+            (
+                format!("{{{{{}}}}}", secret.name),
+                secrets[&secret.name].as_str(),
+            )
+        })
+        .collect();
+    let mut config = template.config;
+    fill(&mut config, &replacements);
+    serde_json::from_value(config).map_err(|source| AppError::JsonSerialize { source })
+}
+
+fn fill(value: &mut Value, replacements: &[(String, &str)]) {
+    match value {
+        Value::String(text) if text.contains("{{") => {
+            for (placeholder, secret) in replacements {
+                if text.contains(placeholder.as_str()) {
+                    *text = text.replace(placeholder.as_str(), secret);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| fill(item, replacements)),
+        Value::Object(map) => map.values_mut().for_each(|child| fill(child, replacements)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_and_rehydrates_secrets() {
+        let mut config = MultiAppConfig::default();
+        let claude = config.apps.entry("claude".into()).or_default();
+        for (id, key) in [("a", "sk-shared"), ("b", "sk-shared"), ("c", "sk-other")] {
+            claude.providers.insert(
+                id.into(),
+                Provider::with_id(
+                    id.into(),
+                    id.to_uppercase(),
+                    json!({ "env": { "ANTHROPIC_AUTH_TOKEN": key, "ANTHROPIC_BASE_URL": "https://x" } }),
+                    None,
+                ),
+            );
+        }
+        let codex = config.apps.entry("codex".into()).or_default();
+        codex.providers.insert(
+            "k".into(),
+            Provider::with_id(
+                "k".into(),
+                "K".into(),
+                json!({ "auth": { "OPENAI_API_KEY": "" }, "config": "api_key = \"sk-toml\"\n" }),
+                None,
+            ),
+        );
+
+        let template = redact(&config).unwrap();
+        let names: Vec<&str> = template.secrets.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["CLAUDE_KEY_1", "CLAUDE_KEY_2", "CODEX_KEY_1"]);
+        assert_eq!(template.secrets[0].used_by, vec!["claude/A", "claude/B"]);
+        let text = template.config.to_string();
+        assert!(!text.contains("sk-shared") && !text.contains("sk-toml"));
+        assert_eq!(
+            template.config["claude"]["providers"]["b"]["settingsConfig"]["env"]
+                ["ANTHROPIC_AUTH_TOKEN"],
+            "{{CLAUDE_KEY_1}}"
+        );
+
+        let mut secrets = HashMap::from([
+            ("CLAUDE_KEY_1".to_string(), "sk-new".to_string()),
+            ("CLAUDE_KEY_2".to_string(), "sk-other".to_string()),
+        ]);
+        assert!(rehydrate(template.clone(), &secrets).is_err());
+        secrets.insert("CODEX_KEY_1".into(), "sk-toml".into());
+        let restored = rehydrate(template, &secrets).unwrap();
+        let providers = &restored.apps["claude"].providers;
+        assert_eq!(
+            providers["b"].settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "sk-new"
+        );
+        assert_eq!(
+            restored.apps["codex"].providers["k"].settings_config["config"],
+            "api_key = \"sk-toml\"\n"
+        );
+    }
+}
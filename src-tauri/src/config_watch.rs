@@ -0,0 +1,182 @@
+//! 多进程配置同步
+//!
+//! GUI、守护进程（`--daemon`）与 CLI 可能同时运行，也可能经同步盘共用配置目录。
+//! 各进程定期检查 config.json 的修改时间与大小，内容与本进程上次加载或写入的版本不同时：
+//! - 内存中没有未保存的修改：在配置锁内重新加载到 `AppState`，并刷新托盘与前端；
+//! - 否则发出 `config-external-change` 事件，由用户选择重新加载或以当前配置覆盖
+//!   （`reload_external_config` / `overwrite_external_config`），不静默丢弃内存中的状态。
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use tauri::{Emitter, Manager};
+
+use crate::config::get_app_config_path;
+use crate::error::AppError;
+use crate::services::ConfigService;
+use crate::store::AppState;
+
+/// 检查间隔（秒）
+const POLL_INTERVAL_SECS: u64 = 2;
+
+/// 外部修改与内存中未保存的修改冲突时发给前端的事件
+pub const EXTERNAL_CHANGE_EVENT: &str = "config-external-change";
+
+/// 文件的修改时间与大小，用于在计算哈希前快速判断是否可能变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl Fingerprint {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// 启动后台检查（setup 阶段调用一次）
+pub fn start(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = Fingerprint::of(&get_app_config_path());
+        let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let current = Fingerprint::of(&get_app_config_path());
+            if current == last {
+                continue;
+            }
+            last = current;
+            let handle = app.clone();
+            if let Err(e) =
+                tauri::async_runtime::spawn_blocking(move || reload_if_changed(&handle)).await
+            {
+                log::error!("检查配置文件变更失败: {e}");
+            }
+        }
+    });
+}
+
+/// 磁盘内容与本进程持有的版本不同时处理外部修改（本进程自己的写入不会触发）
+fn reload_if_changed(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    // 与保存共用配置锁，避免在其他进程写入途中读取，或与本进程的保存交错
+    let _lock = match crate::config_lock::acquire() {
+        Ok(lock) => lock,
+        Err(e) => {
+            log::warn!("获取配置锁失败，跳过本次变更检查: {e}");
+            return;
+        }
+    };
+    let Some(change) = crate::config_integrity::external_change() else {
+        return;
+    };
+    match has_unsaved_changes(state.inner()) {
+        Ok(false) => match ConfigService::reload_external(state.inner()) {
+            Ok(()) => {
+                log::info!("{} 已被其他进程修改，已重新加载", change.path);
+                crate::refresh_tray(app, state.inner());
+            }
+            Err(e) => log::warn!("重新加载被其他进程修改的配置失败: {e}"),
+        },
+        Ok(true) => {
+            log::warn!(
+                "{} 已被外部修改，且内存中有未保存的修改，等待用户选择",
+                change.path
+            );
+            if let Err(e) = app.emit(EXTERNAL_CHANGE_EVENT, &change) {
+                log::error!("发送配置外部修改事件失败: {e}");
+            }
+        }
+        Err(e) => log::warn!("检查内存中的配置是否已保存失败: {e}"),
+    }
+}
+
+/// 内存中的配置是否与本进程最近一次加载或保存时不同
+///
+/// 按 JSON 值比对（见 [`crate::config_integrity::state_unchanged`]），不受供应商等 `HashMap`
+/// 键顺序影响；无法确认一致时按有修改处理，交由用户决定
+fn has_unsaved_changes(state: &AppState) -> Result<bool, AppError> {
+    let config = state.config.read().map_err(AppError::from)?;
+    Ok(!crate::config_integrity::state_unchanged(&*config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::{AppType, MultiAppConfig};
+    use serial_test::serial;
+    use std::sync::RwLock;
+
+    #[test]
+    fn fingerprint_tracks_size_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        assert_eq!(Fingerprint::of(&path), None);
+
+        std::fs::write(&path, b"{}").unwrap();
+        let first = Fingerprint::of(&path).unwrap();
+        assert_eq!(first.len, 2);
+        std::fs::write(&path, br#"{"version":2}"#).unwrap();
+        assert_ne!(Fingerprint::of(&path), Some(first));
+    }
+
+    #[test]
+    #[serial]
+    fn loaded_config_with_many_providers_has_no_unsaved_changes() {
+        let home = tempfile::tempdir().unwrap();
+        let original_home = std::env::var_os("HOME");
+        std::env::set_var("HOME", home.path());
+
+        // 磁盘上的键顺序与内存中 HashMap 的迭代顺序无关
+        let providers: serde_json::Map<String, serde_json::Value> = (0..8)
+            .rev()
+            .map(|i| {
+                let id = format!("p{i}");
+                let provider = serde_json::json!({
+                    "settingsConfig": { "env": { "Z_KEY": "z", "A_KEY": "a" } },
+                    "name": format!("Provider {i}"),
+                    "id": id,
+                });
+                (id, provider)
+            })
+            .collect();
+        let config = serde_json::json!({
+            "version": 2,
+            "claude": { "providers": providers, "current": "p3" },
+            "codex": { "providers": {}, "current": "" },
+            "gemini": { "providers": {}, "current": "" },
+        });
+        let path = get_app_config_path();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+
+        let state = AppState {
+            config: RwLock::new(MultiAppConfig::load().expect("load config")),
+        };
+        let unchanged = has_unsaved_changes(&state);
+
+        state
+            .config
+            .write()
+            .unwrap()
+            .get_manager_mut(&AppType::Claude)
+            .and_then(|manager| manager.providers.get_mut("p5"))
+            .expect("provider p5")
+            .name = "Renamed".to_string();
+        let changed = has_unsaved_changes(&state);
+
+        match original_home {
+            Some(value) => std::env::set_var("HOME", value),
+            None => std::env::remove_var("HOME"),
+        }
+        assert!(!unchanged.unwrap());
+        assert!(changed.unwrap());
+    }
+}
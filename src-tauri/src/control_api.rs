@@ -0,0 +1,249 @@
+//! 守护模式的 HTTP 控制接口
+//!
+//! 设置了 `daemon_api_port` 与 `daemon_api_token` 时监听 `127.0.0.1:<端口>`，
+//! 所有请求都需携带 `Authorization: Bearer <令牌>`，响应均为 JSON：
+//!
+//! - `GET /status`：守护进程状态（同 `daemon_status` 命令）
+//! - `GET /providers?app=claude`：当前供应商与供应商列表
+//! - `POST /switch?app=claude&id=<供应商 ID>`：切换供应商
+//! - `POST /reconcile`：立即执行 live 配置巡检
+//!
+//! 与本地代理相同，由 axum 在 tauri 异步运行时上提供服务；同时连接数不超过
+//! [`MAX_CONNECTIONS`]，读空闲超过 [`IO_TIMEOUT_SECS`] 秒的连接会被关闭。
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::{header, HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tauri::Manager;
+use tokio::net::TcpListener;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::limited_listener::LimitedListener;
+use crate::services::ProviderService;
+use crate::store::AppState;
+
+/// 同时处理的最大连接数，超出时暂停接受新连接
+const MAX_CONNECTIONS: usize = 16;
+
+/// 连接读空闲超时（秒），超时后关闭连接
+const IO_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    Status,
+    Providers(AppType),
+    Switch(AppType, String),
+    Reconcile,
+}
+
+#[derive(Clone)]
+struct ControlContext {
+    app: tauri::AppHandle,
+    token: Arc<str>,
+}
+
+/// 启动控制接口；未配置端口或令牌时不启动并返回 None
+pub fn start(app: tauri::AppHandle) -> Result<Option<SocketAddr>, AppError> {
+    let settings = crate::settings::get_settings();
+    let Some(port) = settings.daemon_api_port else {
+        return Ok(None);
+    };
+    let Some(token) = settings
+        .daemon_api_token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+    else {
+        log::warn!("未设置 daemon_api_token，HTTP 控制接口未启动");
+        return Ok(None);
+    };
+
+    let bind_failed = |e: std::io::Error| {
+        AppError::localized(
+            "daemon.api_bind_failed",
+            format!("HTTP 控制接口无法监听端口 {port}: {e}"),
+            format!("Control API failed to listen on port {port}: {e}"),
+        )
+    };
+    // 同步绑定以便立即返回地址与错误，再交给 tokio 运行时处理连接
+    let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port)).map_err(bind_failed)?;
+    listener.set_nonblocking(true).map_err(bind_failed)?;
+    let addr = listener
+        .local_addr()
+        .map_err(|e| AppError::Message(e.to_string()))?;
+
+    let router = Router::new().fallback(handle).with_state(ControlContext {
+        app,
+        token: token.into(),
+    });
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::from_std(listener) {
+            Ok(listener) => LimitedListener::new(
+                listener,
+                MAX_CONNECTIONS,
+                Duration::from_secs(IO_TIMEOUT_SECS),
+            ),
+            Err(e) => {
+                log::error!("HTTP 控制接口启动失败: {e}");
+                return;
+            }
+        };
+        if let Err(e) = axum::serve(listener, router).await {
+            log::error!("HTTP 控制接口异常退出: {e}");
+        }
+    });
+    log::info!("HTTP 控制接口已启动: http://{addr}");
+    Ok(Some(addr))
+}
+
+async fn handle(
+    State(ctx): State<ControlContext>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let (status, body) = if !authorized(&headers, &ctx.token) {
+        (StatusCode::UNAUTHORIZED, json!({ "error": "unauthorized" }))
+    } else {
+        let target = uri.path_and_query().map_or("/", |target| target.as_str());
+        match parse_route(method.as_str(), target) {
+            // 切换供应商与巡检会读写配置文件，放到阻塞线程池执行
+            Ok(route) => tauri::async_runtime::spawn_blocking(move || respond(&ctx.app, route))
+                .await
+                .unwrap_or_else(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        json!({ "error": e.to_string() }),
+                    )
+                }),
+            Err((status, message)) => (status, json!({ "error": message })),
+        }
+    };
+    (status, Json(body)).into_response()
+}
+
+fn respond(app: &tauri::AppHandle, route: Route) -> (StatusCode, Value) {
+    match route {
+        Route::Status => (StatusCode::OK, json!(crate::daemon::status())),
+        Route::Reconcile => (
+            StatusCode::OK,
+            json!({ "events": crate::daemon::reconcile(app) }),
+        ),
+        Route::Providers(app_type) => {
+            let Some(state) = app.try_state::<AppState>() else {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    json!({ "error": "state not ready" }),
+                );
+            };
+            let result = ProviderService::list_sorted(state.inner(), app_type.clone()).and_then(
+                |providers| {
+                    let current = ProviderService::current(state.inner(), app_type)?;
+                    let list: Vec<Value> = providers
+                        .0
+                        .iter()
+                        .map(|(id, provider)| json!({ "id": id, "name": provider.name }))
+                        .collect();
+                    Ok(json!({ "current": current, "providers": list }))
+                },
+            );
+            match result {
+                Ok(body) => (StatusCode::OK, body),
+                Err(e) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    json!({ "error": e.to_string() }),
+                ),
+            }
+        }
+        Route::Switch(app_type, id) => {
+            match crate::switch_provider_internal(app, app_type.clone(), id.clone()) {
+                Ok(()) => (
+                    StatusCode::OK,
+                    json!({ "app": app_type.as_str(), "current": id }),
+                ),
+                Err(e) => (StatusCode::BAD_REQUEST, json!({ "error": e.to_string() })),
+            }
+        }
+    }
+}
+
+fn authorized(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get_all(header::AUTHORIZATION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.trim().strip_prefix("Bearer "))
+        .any(|candidate| constant_time_eq(candidate.trim().as_bytes(), token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn parse_route(method: &str, target: &str) -> Result<Route, (StatusCode, String)> {
+    let url = url::Url::parse(&format!("http://localhost{target}")).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            "invalid request target".to_string(),
+        )
+    })?;
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+            .filter(|value| !value.is_empty())
+            .ok_or((
+                StatusCode::BAD_REQUEST,
+                format!("missing query parameter: {name}"),
+            ))
+    };
+    let app_type = || {
+        let app = query("app")?;
+        AppType::from_str(&app).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+    };
+
+    match (method, url.path()) {
+        ("GET", "/status") => Ok(Route::Status),
+        ("GET", "/providers") => Ok(Route::Providers(app_type()?)),
+        ("POST", "/switch") => Ok(Route::Switch(app_type()?, query("id")?)),
+        ("POST", "/reconcile") => Ok(Route::Reconcile),
+        (_, "/status" | "/providers" | "/switch" | "/reconcile") => Err((
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method not allowed".to_string(),
+        )),
+        _ => Err((StatusCode::NOT_FOUND, "not found".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_requests_and_checks_token() {
+        assert_eq!(parse_route("GET", "/status"), Ok(Route::Status));
+        assert_eq!(
+            parse_route("POST", "/switch?app=codex&id=my%20relay"),
+            Ok(Route::Switch(AppType::Codex, "my relay".to_string()))
+        );
+        assert_eq!(
+            parse_route("GET", "/switch?app=codex&id=x").unwrap_err().0,
+            405
+        );
+        assert_eq!(parse_route("GET", "/providers").unwrap_err().0, 400);
+        assert_eq!(parse_route("GET", "/other").unwrap_err().0, 404);
+
+        let mut headers = HeaderMap::new();
+        assert!(!authorized(&headers, "s3cret"));
+        headers.insert(header::AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        assert!(authorized(&headers, "s3cret"));
+        assert!(!authorized(&headers, "other"));
+    }
+}
@@ -0,0 +1,237 @@
+//! 本地崩溃记录与可选的匿名上报
+//!
+//! - 捕获 Rust panic 与前端上报的未处理命令错误，写入 `~/.cc-switch/crashes/`
+//! - 仅当用户在设置中明确开启并配置了上报地址时，才会上传精简且脱敏的报告
+//!   （不含调用栈与本机路径），上传成功后删除本地记录
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// 本地最多保留的崩溃记录数量
+const MAX_REPORTS: usize = 50;
+/// 上报请求超时（秒）
+const UPLOAD_TIMEOUT_SECS: u64 = 10;
+
+/// 崩溃记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    /// panic / command
+    pub kind: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<String>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    /// Unix 毫秒
+    pub created_at: i64,
+}
+
+impl CrashReport {
+    fn new(kind: &str, message: &str, location: Option<String>) -> Self {
+        let created_at = chrono::Utc::now().timestamp_millis();
+        Self {
+            id: format!("{kind}-{created_at}"),
+            kind: kind.to_string(),
+            message: redact(message),
+            location,
+            backtrace: None,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            created_at,
+        }
+    }
+
+    /// 上传用的精简报告：去掉调用栈
+    fn minimal(&self) -> Self {
+        Self {
+            backtrace: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// 崩溃记录目录：`~/.cc-switch/crashes`
+pub fn crash_dir() -> PathBuf {
+    dirs::home_dir()
+        .expect("无法获取用户主目录")
+        .join(".cc-switch")
+        .join("crashes")
+}
+
+/// 安装 panic 钩子（保留默认钩子的输出）
+pub fn install() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let location = info
+            .location()
+            .map(|loc| format!("{}:{}", loc.file(), loc.line()));
+
+        let mut report = CrashReport::new("panic", &message, location);
+        report.backtrace = Some(redact(
+            &std::backtrace::Backtrace::force_capture().to_string(),
+        ));
+        if let Err(e) = save(&report) {
+            eprintln!("写入崩溃记录失败: {e}");
+        }
+
+        previous(info);
+    }));
+}
+
+/// 记录未处理的命令错误（由前端上报）
+pub fn record_command_error(command: &str, message: &str) -> Result<CrashReport, AppError> {
+    let report = CrashReport::new("command", message, Some(command.to_string()));
+    save(&report)?;
+    Ok(report)
+}
+
+/// 列出本地崩溃记录（最新在前）
+pub fn list_reports() -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(crash_dir()) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.path()).ok()?;
+            serde_json::from_str(&content).ok()
+        })
+        .collect();
+    reports.sort_by_key(|report| std::cmp::Reverse(report.created_at));
+    reports
+}
+
+/// 清空本地崩溃记录
+pub fn clear_reports() -> Result<usize, AppError> {
+    let dir = crash_dir();
+    let reports = list_reports();
+    for report in &reports {
+        let path = dir.join(format!("{}.json", report.id));
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| AppError::io(&path, e))?;
+        }
+    }
+    Ok(reports.len())
+}
+
+/// 在用户同意的前提下上传本地崩溃记录，返回成功上传的数量
+pub async fn upload_pending() -> Result<usize, AppError> {
+    let settings = crate::settings::get_settings();
+    if !settings.crash_report_upload {
+        return Ok(0);
+    }
+    let Some(endpoint) = settings
+        .crash_report_endpoint
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+        .map(str::to_string)
+    else {
+        return Ok(0);
+    };
+
+    let reports = list_reports();
+    if reports.is_empty() {
+        return Ok(0);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(UPLOAD_TIMEOUT_SECS))
+        .user_agent(format!("cc-switch/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| AppError::Message(format!("创建 HTTP 客户端失败: {e}")))?;
+
+    let dir = crash_dir();
+    let mut uploaded = 0;
+    for report in reports {
+        match client.post(&endpoint).json(&report.minimal()).send().await {
+            Ok(resp) if resp.status().is_success() => {
+                let _ = fs::remove_file(dir.join(format!("{}.json", report.id)));
+                uploaded += 1;
+            }
+            Ok(resp) => {
+                log::warn!("上传崩溃报告失败: HTTP {}", resp.status());
+                break;
+            }
+            Err(e) => {
+                log::warn!("上传崩溃报告失败: {e}");
+                break;
+            }
+        }
+    }
+    Ok(uploaded)
+}
+
+fn save(report: &CrashReport) -> Result<(), AppError> {
+    let dir = crash_dir();
+    fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+
+    let path = dir.join(format!("{}.json", report.id));
+    let json =
+        serde_json::to_string_pretty(report).map_err(|e| AppError::JsonSerialize { source: e })?;
+    fs::write(&path, json).map_err(|e| AppError::io(&path, e))?;
+
+    // 超出上限时删除最旧的记录
+    let reports = list_reports();
+    for stale in reports.iter().skip(MAX_REPORTS) {
+        let _ = fs::remove_file(dir.join(format!("{}.json", stale.id)));
+    }
+    Ok(())
+}
+
+/// 脱敏：隐藏疑似密钥的长串，并将用户主目录替换为 `~`
+fn redact(text: &str) -> String {
+    static SECRET: OnceLock<Regex> = OnceLock::new();
+    let secret = SECRET.get_or_init(|| {
+        Regex::new(r"(?:sk-[A-Za-z0-9_\-]{6,}|[A-Za-z0-9_\-]{32,})").expect("valid regex")
+    });
+    let mut redacted = secret.replace_all(text, "****").into_owned();
+    if let Some(home) = dirs::home_dir() {
+        let home = home.to_string_lossy();
+        if !home.is_empty() {
+            redacted = redacted.replace(home.as_ref(), "~");
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_hides_keys_and_minimal_drops_backtrace() {
+        let mut report = CrashReport::new(
+            "command",
+            "request failed with key sk-ant-abcdef123456 and token 0123456789abcdef0123456789abcdef",
+            None,
+        );
+        assert_eq!(
+            report.message,
+            "request failed with key **** and token ****"
+        );
+
+        report.backtrace = Some("frames".into());
+        assert!(report.minimal().backtrace.is_none());
+    }
+}
@@ -0,0 +1,499 @@
+//! 无界面守护模式（`--daemon`），面向服务器长期运行
+//!
+//! 不显示主窗口（托盘可通过 `--tray` 开启），保持调度器运行，并额外启动：
+//! - live 配置巡检：外部进程改动了当前供应商的 live 文件时重新写入；
+//! - 故障转移探测：当前供应商端点连续多次不可达时，切换到排序中下一个可达的供应商；
+//! - HTTP 控制接口（见 [`crate::control_api`]）。
+//!
+//! 调试故障转移规则时可模拟供应商故障（[`simulate_outage`]）：到期前探测与代理转发都视其为不可达，
+//! 不需要真的断开网络。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::{ProviderService, SpeedtestService};
+use crate::store::AppState;
+
+/// 以守护模式启动的命令行参数
+pub const DAEMON_FLAG: &str = "--daemon";
+
+/// 守护模式下仍显示托盘图标的命令行参数
+pub const TRAY_FLAG: &str = "--tray";
+
+/// live 配置巡检间隔（秒）
+const RECONCILE_INTERVAL_SECS: u64 = 60;
+
+/// 故障转移探测间隔（秒）
+const FAILOVER_PROBE_INTERVAL_SECS: u64 = 5 * 60;
+
+/// 连续探测失败达到该次数后执行故障转移
+const FAILOVER_THRESHOLD: u32 = 3;
+
+/// 探测请求超时（秒）
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// 保留的最近事件条数
+const MAX_EVENTS: usize = 50;
+
+/// 模拟故障的最长持续时间（秒）
+const MAX_SIMULATED_OUTAGE_SECS: u64 = 60 * 60;
+
+/// 命令行参数解析出的运行方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunMode {
+    pub daemon: bool,
+    pub tray: bool,
+}
+
+impl RunMode {
+    fn parse<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut mode = Self::default();
+        for arg in args {
+            match arg.as_ref() {
+                DAEMON_FLAG => mode.daemon = true,
+                TRAY_FLAG => mode.tray = true,
+                _ => {}
+            }
+        }
+        mode
+    }
+}
+
+/// 当前进程的运行方式（首次调用时解析命令行参数）
+pub fn run_mode() -> RunMode {
+    static MODE: OnceLock<RunMode> = OnceLock::new();
+    *MODE.get_or_init(|| RunMode::parse(std::env::args().skip(1)))
+}
+
+pub fn is_daemon() -> bool {
+    run_mode().daemon
+}
+
+/// 是否创建托盘（守护模式下需显式传入 `--tray`）
+pub fn tray_enabled() -> bool {
+    let mode = run_mode();
+    !mode.daemon || mode.tray
+}
+
+/// 守护进程事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DaemonEventKind {
+    /// live 配置被外部修改，已重新写入当前供应商
+    Reconciled,
+    ReconcileFailed,
+    /// 当前供应商不可达，已切换到其他供应商
+    Failover,
+    /// 当前供应商不可达，且没有可切换的供应商
+    FailoverFailed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonEvent {
+    /// 发生时间（Unix 毫秒）
+    pub at: i64,
+    pub app_type: String,
+    pub kind: DaemonEventKind,
+    /// 重新写入或切换到的供应商（切换失败时为原供应商）
+    pub provider_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// 进行中的模拟故障
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedOutage {
+    pub app_type: String,
+    pub provider_id: String,
+    /// 结束时间（Unix 毫秒）
+    pub until: i64,
+}
+
+/// `daemon_status` 返回的运行状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonStatus {
+    /// 是否以守护模式运行
+    pub daemon: bool,
+    pub tray: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<i64>,
+    /// HTTP 控制接口监听地址（未启用时为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_api: Option<String>,
+    /// 各应用当前供应商的连续探测失败次数
+    pub probe_failures: HashMap<String, u32>,
+    /// 最近的巡检与故障转移事件（新的在前）
+    pub events: Vec<DaemonEvent>,
+    /// 进行中的模拟故障
+    pub simulated_outages: Vec<SimulatedOutage>,
+}
+
+#[derive(Default)]
+struct Runtime {
+    started_at: Option<i64>,
+    control_api: Option<String>,
+    probe_failures: HashMap<String, u32>,
+    events: VecDeque<DaemonEvent>,
+    /// `(应用, 供应商 ID)` -> 结束时间（Unix 毫秒）
+    simulated_outages: HashMap<(String, String), i64>,
+}
+
+fn runtime() -> &'static Mutex<Runtime> {
+    static RUNTIME: OnceLock<Mutex<Runtime>> = OnceLock::new();
+    RUNTIME.get_or_init(|| Mutex::new(Runtime::default()))
+}
+
+fn record_event(
+    app_type: &AppType,
+    kind: DaemonEventKind,
+    provider_id: &str,
+    detail: Option<String>,
+) -> DaemonEvent {
+    let event = DaemonEvent {
+        at: chrono::Utc::now().timestamp_millis(),
+        app_type: app_type.as_str().to_string(),
+        kind,
+        provider_id: provider_id.to_string(),
+        detail,
+    };
+    if let Ok(mut guard) = runtime().lock() {
+        guard.events.push_front(event.clone());
+        guard.events.truncate(MAX_EVENTS);
+    }
+    event
+}
+
+/// 获取守护进程运行状态（非守护模式时仅返回 `daemon: false`）
+pub fn status() -> DaemonStatus {
+    let mode = run_mode();
+    let mut status = DaemonStatus {
+        daemon: mode.daemon,
+        tray: tray_enabled(),
+        ..DaemonStatus::default()
+    };
+    if let Ok(guard) = runtime().lock() {
+        status.started_at = guard.started_at;
+        status.control_api = guard.control_api.clone();
+        status.probe_failures = guard.probe_failures.clone();
+        status.events = guard.events.iter().cloned().collect();
+        let now = chrono::Utc::now().timestamp_millis();
+        status.simulated_outages = guard
+            .simulated_outages
+            .iter()
+            .filter(|(_, until)| **until > now)
+            .map(|((app_type, provider_id), until)| SimulatedOutage {
+                app_type: app_type.clone(),
+                provider_id: provider_id.clone(),
+                until: *until,
+            })
+            .collect();
+    }
+    status
+}
+
+/// 供应商是否处于模拟故障中（过期的记录顺带清除）
+pub(crate) fn outage_simulated(app_type: &AppType, provider_id: &str) -> bool {
+    let Ok(mut guard) = runtime().lock() else {
+        return false;
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    guard.simulated_outages.retain(|_, until| *until > now);
+    guard
+        .simulated_outages
+        .contains_key(&(app_type.as_str().to_string(), provider_id.to_string()))
+}
+
+/// 模拟供应商故障 `duration_secs` 秒（为 0 时取消），返回立即执行故障转移探测产生的事件
+///
+/// 模拟期间探测与代理转发都视该供应商为不可达。若它是当前供应商，立即连续探测到故障转移阈值，
+/// 不必等待守护进程的探测周期（非守护模式下同样生效）。
+pub async fn simulate_outage(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    app_type: AppType,
+    provider_id: &str,
+    duration_secs: u64,
+) -> Result<Vec<DaemonEvent>, AppError> {
+    let providers = ProviderService::list_sorted(state, app_type.clone())?.0;
+    if !providers.iter().any(|(id, _)| id == provider_id) {
+        return Err(AppError::localized(
+            "provider.not_found",
+            format!("供应商不存在: {provider_id}"),
+            format!("Provider not found: {provider_id}"),
+        ));
+    }
+
+    let key = (app_type.as_str().to_string(), provider_id.to_string());
+    let secs = duration_secs.min(MAX_SIMULATED_OUTAGE_SECS);
+    {
+        let mut guard = runtime().lock().map_err(AppError::from)?;
+        if duration_secs == 0 {
+            guard.simulated_outages.remove(&key);
+            log::info!("取消模拟故障 {}/{provider_id}", app_type.as_str());
+            return Ok(Vec::new());
+        }
+        let until = chrono::Utc::now().timestamp_millis() + secs as i64 * 1000;
+        guard.simulated_outages.insert(key, until);
+    }
+    log::warn!(
+        "模拟供应商故障 {}/{provider_id}，持续 {secs} 秒",
+        app_type.as_str()
+    );
+
+    if ProviderService::current(state, app_type.clone())? != provider_id {
+        return Ok(Vec::new());
+    }
+    let mut events = Vec::new();
+    for _ in 0..FAILOVER_THRESHOLD {
+        if let Some(event) = probe_app(app, state, &app_type).await {
+            events.push(event);
+            break;
+        }
+    }
+    Ok(events)
+}
+
+/// 启动守护任务（仅守护模式下生效，应在调度器启动后调用一次）
+pub fn start(app: tauri::AppHandle) {
+    if !is_daemon() {
+        return;
+    }
+    log::info!("以守护模式运行（托盘: {}）", tray_enabled());
+    let control_api = match crate::control_api::start(app.clone()) {
+        Ok(addr) => addr.map(|addr| addr.to_string()),
+        Err(e) => {
+            log::error!("启动 HTTP 控制接口失败: {e}");
+            None
+        }
+    };
+    if let Ok(mut guard) = runtime().lock() {
+        guard.started_at = Some(chrono::Utc::now().timestamp_millis());
+        guard.control_api = control_api;
+    }
+
+    let reconcile_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(RECONCILE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let handle = reconcile_app.clone();
+            if let Err(e) = tauri::async_runtime::spawn_blocking(move || reconcile(&handle)).await {
+                log::error!("执行 live 配置巡检失败: {e}");
+            }
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(FAILOVER_PROBE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            probe_and_failover(&app).await;
+        }
+    });
+}
+
+/// 重新写入被外部修改的 live 配置，返回本次产生的事件
+pub fn reconcile(app: &tauri::AppHandle) -> Vec<DaemonEvent> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Vec::new();
+    };
+    let mut events = Vec::new();
+    for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+        let provider_id = match ProviderService::live_drift(state.inner(), &app_type) {
+            Ok(Some(id)) => id,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("检查 {} 的 live 配置失败: {e}", app_type.as_str());
+                continue;
+            }
+        };
+        log::info!(
+            "{} 的 live 配置被外部修改，重新写入供应商 {provider_id}",
+            app_type.as_str()
+        );
+        let event =
+            match crate::switch_provider_internal(app, app_type.clone(), provider_id.clone()) {
+                Ok(()) => record_event(&app_type, DaemonEventKind::Reconciled, &provider_id, None),
+                Err(e) => {
+                    log::error!("重新写入 {} 的 live 配置失败: {e}", app_type.as_str());
+                    record_event(
+                        &app_type,
+                        DaemonEventKind::ReconcileFailed,
+                        &provider_id,
+                        Some(e.to_string()),
+                    )
+                }
+            };
+        events.push(event);
+    }
+    events
+}
+
+/// 探测各应用当前供应商的端点，连续失败达到阈值时切换到下一个可达的供应商
+async fn probe_and_failover(app: &tauri::AppHandle) {
+    if crate::settings::get_settings().offline_mode {
+        return;
+    }
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+        probe_app(app, state.inner(), &app_type).await;
+    }
+}
+
+/// 探测单个应用的当前供应商，返回本次触发的故障转移事件（未达到阈值时为空）
+async fn probe_app(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    app_type: &AppType,
+) -> Option<DaemonEvent> {
+    let providers = ProviderService::list_sorted(state, app_type.clone()).ok()?;
+    let current = ProviderService::current(state, app_type.clone()).ok()?;
+    // 官方登录等没有自定义端点的供应商不参与探测
+    let current_url = providers
+        .0
+        .iter()
+        .find(|(id, _)| *id == current)
+        .and_then(|(_, provider)| ProviderService::provider_base_url(app_type, provider))?;
+
+    let key = app_type.as_str().to_string();
+    if probe(app_type, &current, &current_url).await {
+        if let Ok(mut guard) = runtime().lock() {
+            guard.probe_failures.remove(&key);
+        }
+        return None;
+    }
+    let failures = {
+        let mut guard = runtime().lock().ok()?;
+        let count = guard.probe_failures.entry(key.clone()).or_insert(0);
+        *count += 1;
+        *count
+    };
+    log::warn!(
+        "{} 当前供应商 {current} 探测失败（连续 {failures} 次）",
+        app_type.as_str()
+    );
+    if failures < FAILOVER_THRESHOLD {
+        return None;
+    }
+    if let Ok(mut guard) = runtime().lock() {
+        guard.probe_failures.remove(&key);
+    }
+
+    let mut target = None;
+    for (id, provider) in providers.0.iter().filter(|(id, _)| *id != current) {
+        let Some(url) = ProviderService::provider_base_url(app_type, provider) else {
+            continue;
+        };
+        if probe(app_type, id, &url).await {
+            target = Some(id.clone());
+            break;
+        }
+    }
+    let Some(target) = target else {
+        log::error!("{} 没有可切换的可达供应商", app_type.as_str());
+        return Some(record_event(
+            app_type,
+            DaemonEventKind::FailoverFailed,
+            &current,
+            None,
+        ));
+    };
+
+    log::info!("{} 故障转移: {current} -> {target}", app_type.as_str());
+    let handle = app.clone();
+    let switch_app = app_type.clone();
+    let switch_target = target.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        crate::switch_provider_internal(&handle, switch_app, switch_target)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    let event = match result {
+        Ok(()) => record_event(
+            app_type,
+            DaemonEventKind::Failover,
+            &target,
+            Some(format!("from {current}")),
+        ),
+        Err(e) => {
+            log::error!("{} 故障转移失败: {e}", app_type.as_str());
+            record_event(app_type, DaemonEventKind::FailoverFailed, &current, Some(e))
+        }
+    };
+    Some(event)
+}
+
+/// 端点是否可达（能收到任意 HTTP 响应即视为可达；模拟故障中的供应商始终不可达）
+async fn probe(app_type: &AppType, provider_id: &str, url: &str) -> bool {
+    if outage_simulated(app_type, provider_id) {
+        return false;
+    }
+    SpeedtestService::test_endpoints(vec![url.to_string()], Some(PROBE_TIMEOUT_SECS))
+        .await
+        .ok()
+        .and_then(|results| results.into_iter().next())
+        .is_some_and(|result| result.latency.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_run_mode_flags() {
+        assert_eq!(RunMode::parse(Vec::<String>::new()), RunMode::default());
+        assert_eq!(
+            RunMode::parse(["--daemon"]),
+            RunMode {
+                daemon: true,
+                tray: false
+            }
+        );
+        assert_eq!(
+            RunMode::parse(["ccswitch://import", "--tray", "--daemon"]),
+            RunMode {
+                daemon: true,
+                tray: true
+            }
+        );
+    }
+
+    #[test]
+    fn simulated_outage_expires() {
+        let now = chrono::Utc::now().timestamp_millis();
+        {
+            let mut guard = runtime().lock().unwrap();
+            guard
+                .simulated_outages
+                .insert(("claude".into(), "down".into()), now + 60_000);
+            guard
+                .simulated_outages
+                .insert(("claude".into(), "expired".into()), now - 1);
+        }
+        assert!(outage_simulated(&AppType::Claude, "down"));
+        assert!(!outage_simulated(&AppType::Codex, "down"));
+        assert!(!outage_simulated(&AppType::Claude, "expired"));
+        assert!(!runtime()
+            .lock()
+            .unwrap()
+            .simulated_outages
+            .contains_key(&("claude".to_string(), "expired".to_string())));
+    }
+}
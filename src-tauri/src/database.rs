@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use rusqlite::Connection;
+
+use crate::config::get_app_config_dir;
+use crate::error::AppError;
+use crate::services::{mcp_usage, proxy_metrics, search};
+
+/// 本地 SQLite 数据库路径：`~/.cc-switch/cc-switch.db`
+///
+/// 代理请求指标、MCP 使用统计与全局搜索的 FTS5 索引共用此数据库
+pub fn get_database_path() -> PathBuf {
+    get_app_config_dir().join("cc-switch.db")
+}
+
+/// 在共享连接上执行操作（首次调用时打开数据库并建表）
+pub fn with_connection<T>(
+    f: impl FnOnce(&mut Connection) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    static CONNECTION: OnceLock<Mutex<Connection>> = OnceLock::new();
+    let conn = match CONNECTION.get() {
+        Some(conn) => conn,
+        None => {
+            let conn = open()?;
+            CONNECTION.get_or_init(|| Mutex::new(conn))
+        }
+    };
+    let mut conn = conn.lock().map_err(AppError::from)?;
+    f(&mut conn)
+}
+
+fn open() -> Result<Connection, AppError> {
+    let path = get_database_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    let conn = Connection::open(&path).map_err(db_error)?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+/// 创建各模块的表（均为 `IF NOT EXISTS`，可重复执行）
+pub(crate) fn init_schema(conn: &Connection) -> Result<(), AppError> {
+    proxy_metrics::init_schema(conn)?;
+    mcp_usage::init_schema(conn)?;
+    search::init_schema(conn)
+}
+
+pub(crate) fn db_error(e: rusqlite::Error) -> AppError {
+    AppError::localized(
+        "database.access_failed",
+        format!("读写本地数据库失败: {e}"),
+        format!("Failed to access the local database: {e}"),
+    )
+}
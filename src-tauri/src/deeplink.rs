@@ -37,6 +37,9 @@ pub struct DeepLinkImportRequest {
     /// Optional notes/description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    /// Optional Codex wire API ("responses" or "chat"); probed on import when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wire_api: Option<String>,
 }
 
 /// Parse a ccswitch:// URL into a DeepLinkImportRequest
@@ -132,6 +135,14 @@ pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppErr
     // Extract optional fields
     let model = params.get("model").cloned();
     let notes = params.get("notes").cloned();
+    let wire_api = params.get("wireApi").cloned();
+    if let Some(wire_api) = wire_api.as_deref() {
+        if wire_api != "responses" && wire_api != "chat" {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid wireApi: must be 'responses' or 'chat', got '{wire_api}'"
+            )));
+        }
+    }
 
     Ok(DeepLinkImportRequest {
         version,
@@ -143,9 +154,104 @@ pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppErr
         api_key,
         model,
         notes,
+        wire_api,
     })
 }
 
+/// Build a ccswitch:// import URL for an existing provider
+///
+/// The API key is only embedded when `include_api_key` is true; otherwise the
+/// `apiKey` parameter is left empty so the recipient fills in their own key.
+pub fn build_provider_deeplink(
+    app_type: &AppType,
+    provider: &Provider,
+    include_api_key: bool,
+) -> Result<String, AppError> {
+    let settings = &provider.settings_config;
+    let env_str = |key: &str| {
+        settings
+            .get("env")
+            .and_then(|env| env.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+
+    let endpoint = ProviderService::provider_base_url(app_type, provider).ok_or_else(|| {
+        AppError::InvalidInput(format!("Provider '{}' has no endpoint", provider.name))
+    })?;
+    validate_url(&endpoint, "endpoint")?;
+
+    let wire_api = match app_type {
+        AppType::Codex => settings
+            .get("config")
+            .and_then(|v| v.as_str())
+            .and_then(crate::codex_config::get_wire_api),
+        _ => None,
+    };
+
+    let (api_key, model) = match app_type {
+        AppType::Claude => (
+            env_str("ANTHROPIC_AUTH_TOKEN").or_else(|| env_str("ANTHROPIC_API_KEY")),
+            env_str("ANTHROPIC_MODEL"),
+        ),
+        AppType::Codex => (
+            settings
+                .get("auth")
+                .and_then(|auth| auth.get("OPENAI_API_KEY"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            settings
+                .get("config")
+                .and_then(|v| v.as_str())
+                .and_then(|text| toml::from_str::<toml::Table>(text).ok())
+                .and_then(|table| table.get("model")?.as_str().map(str::to_string)),
+        ),
+        AppType::Gemini => (
+            env_str("GEMINI_API_KEY"),
+            env_str("GOOGLE_GEMINI_MODEL").or_else(|| env_str("GEMINI_MODEL")),
+        ),
+    };
+
+    // homepage is required by the import side; fall back to the endpoint
+    let homepage = provider
+        .website_url
+        .as_deref()
+        .filter(|url| validate_url(url, "homepage").is_ok())
+        .unwrap_or(&endpoint)
+        .to_string();
+
+    let mut url = Url::parse("ccswitch://v1/import")
+        .map_err(|e| AppError::Message(format!("Failed to build deep link: {e}")))?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("resource", "provider")
+            .append_pair("app", app_type.as_str())
+            .append_pair("name", &provider.name)
+            .append_pair("homepage", &homepage)
+            .append_pair("endpoint", &endpoint)
+            .append_pair(
+                "apiKey",
+                if include_api_key {
+                    api_key.as_deref().unwrap_or_default()
+                } else {
+                    ""
+                },
+            );
+        if let Some(model) = model.as_deref().filter(|m| !m.is_empty()) {
+            query.append_pair("model", model);
+        }
+        if let Some(notes) = provider.notes.as_deref().filter(|n| !n.is_empty()) {
+            query.append_pair("notes", notes);
+        }
+        if let Some(wire_api) = wire_api.as_deref() {
+            query.append_pair("wireApi", wire_api);
+        }
+    }
+
+    Ok(url.to_string())
+}
+
 /// Validate that a string is a valid HTTP(S) URL
 fn validate_url(url_str: &str, field_name: &str) -> Result<(), AppError> {
     let url = Url::parse(url_str)
@@ -198,7 +304,7 @@ pub fn import_provider_from_deeplink(
 }
 
 /// Build a Provider structure from a deep link request
-fn build_provider_from_request(
+pub(crate) fn build_provider_from_request(
     app_type: &AppType,
     request: &DeepLinkImportRequest,
 ) -> Result<Provider, AppError> {
@@ -225,63 +331,11 @@ fn build_provider_from_request(
             // 这里尽量与前端 `getCodexCustomTemplate` 的默认模板保持一致，
             // 再根据深链接参数注入 base_url / model，避免出现“只有 base_url 行”的极简配置，
             // 让通过 UI 新建和通过深链接导入的 Codex 自定义供应商行为一致。
-
-            // 1. 生成一个适合作为 model_provider 名的安全标识
-            //    规则尽量与前端 codexProviderPresets.generateThirdPartyConfig 保持一致：
-            //    - 转小写
-            //    - 非 [a-z0-9_] 统一替换为下划线
-            //    - 去掉首尾下划线
-            //    - 若结果为空，则使用 "custom"
-            let clean_provider_name = {
-                let raw: String = request.name.chars().filter(|c| !c.is_control()).collect();
-                let lower = raw.to_lowercase();
-                let mut key: String = lower
-                    .chars()
-                    .map(|c| match c {
-                        'a'..='z' | '0'..='9' | '_' => c,
-                        _ => '_',
-                    })
-                    .collect();
-
-                // 去掉首尾下划线
-                while key.starts_with('_') {
-                    key.remove(0);
-                }
-                while key.ends_with('_') {
-                    key.pop();
-                }
-
-                if key.is_empty() {
-                    "custom".to_string()
-                } else {
-                    key
-                }
-            };
-
-            // 2. 模型名称：优先使用 deeplink 中的 model，否则退回到 Codex 默认模型
-            let model_name = request
-                .model
-                .as_deref()
-                .unwrap_or("gpt-5-codex")
-                .to_string();
-
-            // 3. 端点：与 UI 中 Base URL 处理方式保持一致，去掉结尾多余的斜杠
-            let endpoint = request.endpoint.trim().trim_end_matches('/').to_string();
-
-            // 4. 组装 config.toml 内容
-            // 使用 Rust 1.58+ 的内联格式化语法，避免 clippy::uninlined_format_args 警告
-            let config_toml = format!(
-                r#"model_provider = "{clean_provider_name}"
-model = "{model_name}"
-model_reasoning_effort = "high"
-disable_response_storage = true
-
-[model_providers.{clean_provider_name}]
-name = "{clean_provider_name}"
-base_url = "{endpoint}"
-wire_api = "responses"
-requires_openai_auth = true
-"#
+            let config_toml = codex_config_toml(
+                &request.name,
+                request.model.as_deref(),
+                &request.endpoint,
+                request.wire_api.as_deref().unwrap_or("responses"),
             );
 
             json!({
@@ -319,11 +373,78 @@ requires_openai_auth = true
         sort_index: None,
         notes: request.notes.clone(),
         meta: None,
+        auth_kind: None,
+        updated_at: None,
+        pinned: false,
+        icon: None,
+        icon_color: None,
     };
 
     Ok(provider)
 }
 
+/// 生成 Codex 自定义供应商的 config.toml（与前端 `getCodexCustomTemplate` 默认模板保持一致）
+pub(crate) fn codex_config_toml(
+    name: &str,
+    model: Option<&str>,
+    endpoint: &str,
+    wire_api: &str,
+) -> String {
+    // 1. 生成一个适合作为 model_provider 名的安全标识
+    //    规则尽量与前端 codexProviderPresets.generateThirdPartyConfig 保持一致：
+    //    - 转小写
+    //    - 非 [a-z0-9_] 统一替换为下划线
+    //    - 去掉首尾下划线
+    //    - 若结果为空，则使用 "custom"
+    let clean_provider_name = {
+        let raw: String = name.chars().filter(|c| !c.is_control()).collect();
+        let lower = raw.to_lowercase();
+        let mut key: String = lower
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | '0'..='9' | '_' => c,
+                _ => '_',
+            })
+            .collect();
+
+        // 去掉首尾下划线
+        while key.starts_with('_') {
+            key.remove(0);
+        }
+        while key.ends_with('_') {
+            key.pop();
+        }
+
+        if key.is_empty() {
+            "custom".to_string()
+        } else {
+            key
+        }
+    };
+
+    // 2. 模型名称：优先使用传入的 model，否则退回到 Codex 默认模型
+    let model_name = model.unwrap_or("gpt-5-codex");
+
+    // 3. 端点：与 UI 中 Base URL 处理方式保持一致，去掉结尾多余的斜杠
+    let endpoint = endpoint.trim().trim_end_matches('/');
+
+    // 4. 组装 config.toml 内容
+    // 使用 Rust 1.58+ 的内联格式化语法，避免 clippy::uninlined_format_args 警告
+    format!(
+        r#"model_provider = "{clean_provider_name}"
+model = "{model_name}"
+model_reasoning_effort = "high"
+disable_response_storage = true
+
+[model_providers.{clean_provider_name}]
+name = "{clean_provider_name}"
+base_url = "{endpoint}"
+wire_api = "{wire_api}"
+requires_openai_auth = true
+"#
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -385,6 +506,51 @@ mod tests {
             .contains("Missing 'homepage' parameter"));
     }
 
+    #[test]
+    fn test_build_deeplink_round_trips_and_hides_key() {
+        let request = parse_deeplink_url("ccswitch://v1/import?resource=provider&app=claude&name=Relay&homepage=https%3A%2F%2Frelay.example&endpoint=https%3A%2F%2Fapi.relay.example&apiKey=sk-secret&model=claude-sonnet").unwrap();
+        let provider = build_provider_from_request(&AppType::Claude, &request).unwrap();
+
+        let with_key = build_provider_deeplink(&AppType::Claude, &provider, true).unwrap();
+        let parsed = parse_deeplink_url(&with_key).unwrap();
+        assert_eq!(parsed.name, "Relay");
+        assert_eq!(parsed.endpoint, "https://api.relay.example");
+        assert_eq!(parsed.api_key, "sk-secret");
+        assert_eq!(parsed.model.as_deref(), Some("claude-sonnet"));
+
+        let without_key = build_provider_deeplink(&AppType::Claude, &provider, false).unwrap();
+        assert!(!without_key.contains("sk-secret"));
+        assert_eq!(parse_deeplink_url(&without_key).unwrap().api_key, "");
+    }
+
+    #[test]
+    fn test_codex_wire_api_from_deeplink_and_rewrite() {
+        let request = parse_deeplink_url("ccswitch://v1/import?resource=provider&app=codex&name=Relay&homepage=https%3A%2F%2Frelay.example&endpoint=https%3A%2F%2Fapi.relay.example%2Fv1&apiKey=sk&wireApi=chat").unwrap();
+        assert_eq!(request.wire_api.as_deref(), Some("chat"));
+        let provider = build_provider_from_request(&AppType::Codex, &request).unwrap();
+        let config = provider.settings_config["config"].as_str().unwrap();
+        assert_eq!(
+            crate::codex_config::get_wire_api(config).as_deref(),
+            Some("chat")
+        );
+
+        let link = build_provider_deeplink(&AppType::Codex, &provider, false).unwrap();
+        assert_eq!(
+            parse_deeplink_url(&link).unwrap().wire_api.as_deref(),
+            Some("chat")
+        );
+
+        let commented = format!("# relay config\n{config}");
+        let rewritten = crate::codex_config::set_wire_api(&commented, "responses").unwrap();
+        assert!(rewritten.starts_with("# relay config\n"));
+        assert_eq!(
+            crate::codex_config::get_wire_api(&rewritten).as_deref(),
+            Some("responses")
+        );
+
+        assert!(parse_deeplink_url("ccswitch://v1/import?resource=provider&app=codex&name=R&homepage=https%3A%2F%2Fr.example&endpoint=https%3A%2F%2Fr.example&apiKey=sk&wireApi=grpc").is_err());
+    }
+
     #[test]
     fn test_validate_invalid_url() {
         let result = validate_url("not-a-url", "test");
@@ -0,0 +1,408 @@
+//! 原始配置编辑器的实时诊断
+//!
+//! 对编辑器中的 JSON / TOML / .env 文本做语法与结构检查，返回带行列位置的诊断，
+//! 便于编辑器在对应位置标注，而不是等到保存时才得到一条没有位置的错误。
+//! 行号与列号从 1 开始，列按字符计数。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::report_format::ReportLocale;
+
+/// 编辑器内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EditorKind {
+    /// Claude / Gemini 供应商的 settings JSON
+    Settings,
+    /// Codex auth.json
+    Auth,
+    /// Codex config.toml
+    Config,
+    /// Gemini .env
+    Env,
+    /// 单个 MCP 服务器定义（JSON）
+    Mcp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorDiagnostic {
+    pub line: usize,
+    pub column: usize,
+    /// 范围结束位置（不含），无法确定范围时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+/// 诊断收集器：负责字节偏移到行列的换算与消息本地化
+struct Collector<'a> {
+    text: &'a str,
+    locale: ReportLocale,
+    diagnostics: Vec<EditorDiagnostic>,
+}
+
+impl<'a> Collector<'a> {
+    fn new(text: &'a str, locale: ReportLocale) -> Self {
+        Self {
+            text,
+            locale,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn text(&self, zh: &str, en: &str) -> String {
+        match self.locale {
+            ReportLocale::Zh => zh.to_string(),
+            ReportLocale::En => en.to_string(),
+        }
+    }
+
+    /// 字节偏移换算为 `(行, 列)`
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &self.text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+
+    fn push_span(
+        &mut self,
+        span: Option<std::ops::Range<usize>>,
+        severity: DiagnosticSeverity,
+        message: String,
+    ) {
+        let span = span.unwrap_or(0..0);
+        let (line, column) = self.position(span.start);
+        let end = (span.end > span.start).then(|| self.position(span.end));
+        self.diagnostics.push(EditorDiagnostic {
+            line,
+            column,
+            end_line: end.map(|(line, _)| line),
+            end_column: end.map(|(_, column)| column),
+            severity,
+            message,
+        });
+    }
+
+    /// 标注 JSON 中第一个 `"key"` 出现的位置（找不到时标注在开头）
+    fn push_json_key(&mut self, key: &str, severity: DiagnosticSeverity, message: String) {
+        let quoted = format!("\"{key}\"");
+        let span = self
+            .text
+            .find(&quoted)
+            .map(|start| start..start + quoted.len());
+        self.push_span(span, severity, message);
+    }
+
+    /// 解析 JSON，语法错误时记录位置并返回 None
+    fn parse_json(&mut self) -> Option<Value> {
+        match serde_json::from_str::<Value>(self.text) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                // serde_json 的列按字节计数且可能为 0，先还原为字节偏移再换算
+                let line_start: usize = self
+                    .text
+                    .split_inclusive('\n')
+                    .take(e.line().saturating_sub(1))
+                    .map(str::len)
+                    .sum();
+                let offset = line_start + e.column().saturating_sub(1);
+                let message = e.to_string();
+                let message = message
+                    .rsplit_once(" at line ")
+                    .map_or(message.as_str(), |(message, _)| message)
+                    .to_string();
+                self.push_span(Some(offset..offset), DiagnosticSeverity::Error, message);
+                None
+            }
+        }
+    }
+
+    /// 要求根节点为对象
+    fn require_object<'v>(
+        &mut self,
+        value: &'v Value,
+    ) -> Option<&'v serde_json::Map<String, Value>> {
+        let object = value.as_object();
+        if object.is_none() {
+            let message = self.text("根节点必须是 JSON 对象", "Root must be a JSON object");
+            self.push_span(None, DiagnosticSeverity::Error, message);
+        }
+        object
+    }
+
+    /// `env` 必须是对象，且值应为字符串
+    fn check_env_object(&mut self, root: &serde_json::Map<String, Value>) {
+        let Some(env) = root.get("env") else {
+            return;
+        };
+        let Some(env) = env.as_object() else {
+            let message = self.text("env 必须是对象", "env must be an object");
+            self.push_json_key("env", DiagnosticSeverity::Error, message);
+            return;
+        };
+        for (key, value) in env {
+            if !value.is_string() {
+                let message = match self.locale {
+                    ReportLocale::Zh => format!("环境变量 {key} 的值应为字符串"),
+                    ReportLocale::En => format!("Value of {key} should be a string"),
+                };
+                self.push_json_key(key, DiagnosticSeverity::Warning, message);
+            }
+        }
+        for key in ["ANTHROPIC_BASE_URL", "GOOGLE_GEMINI_BASE_URL"] {
+            if let Some(url) = env.get(key).and_then(Value::as_str) {
+                if !is_http_url(url) {
+                    let message = match self.locale {
+                        ReportLocale::Zh => format!("{key} 应以 http:// 或 https:// 开头"),
+                        ReportLocale::En => format!("{key} should start with http:// or https://"),
+                    };
+                    self.push_json_key(key, DiagnosticSeverity::Warning, message);
+                }
+            }
+        }
+    }
+
+    fn check_settings(&mut self) {
+        let Some(value) = self.parse_json() else {
+            return;
+        };
+        if let Some(root) = self.require_object(&value) {
+            self.check_env_object(root);
+        }
+    }
+
+    fn check_auth(&mut self) {
+        let Some(value) = self.parse_json() else {
+            return;
+        };
+        let Some(root) = self.require_object(&value) else {
+            return;
+        };
+        if root
+            .get("OPENAI_API_KEY")
+            .is_some_and(|key| !key.is_string() && !key.is_null())
+        {
+            let message = self.text(
+                "OPENAI_API_KEY 应为字符串",
+                "OPENAI_API_KEY should be a string",
+            );
+            self.push_json_key("OPENAI_API_KEY", DiagnosticSeverity::Warning, message);
+        }
+    }
+
+    fn check_config(&mut self) {
+        if self.text.trim().is_empty() {
+            return;
+        }
+        let doc = match toml_edit::ImDocument::parse(self.text) {
+            Ok(doc) => doc,
+            Err(e) => {
+                let message = e.message().trim().to_string();
+                self.push_span(e.span(), DiagnosticSeverity::Error, message);
+                return;
+            }
+        };
+        let Some((_, provider)) = doc.get_key_value("model_provider") else {
+            return;
+        };
+        let Some(name) = provider.as_str() else {
+            let message = self.text(
+                "model_provider 应为字符串",
+                "model_provider should be a string",
+            );
+            self.push_span(provider.span(), DiagnosticSeverity::Error, message);
+            return;
+        };
+        let table = doc
+            .get("model_providers")
+            .and_then(|providers| providers.get(name));
+        let Some(table) = table else {
+            let message = match self.locale {
+                ReportLocale::Zh => format!("未找到 [model_providers.{name}]"),
+                ReportLocale::En => format!("[model_providers.{name}] is not defined"),
+            };
+            self.push_span(provider.span(), DiagnosticSeverity::Warning, message);
+            return;
+        };
+        if let Some(base_url) = table.get("base_url") {
+            if !base_url.as_str().is_some_and(is_http_url) {
+                let message = self.text(
+                    "base_url 应以 http:// 或 https:// 开头",
+                    "base_url should start with http:// or https://",
+                );
+                self.push_span(base_url.span(), DiagnosticSeverity::Warning, message);
+            }
+        }
+    }
+
+    /// 与 `gemini_config::parse_env_file_strict` 的规则一致，重复的变量给出警告
+    fn check_env(&mut self) {
+        let mut seen = std::collections::HashSet::new();
+        let mut offset = 0;
+        for raw in self.text.split_inclusive('\n') {
+            let line_start = offset;
+            offset += raw.len();
+            let line = raw.trim_end_matches(['\r', '\n']);
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let indent = line.len() - line.trim_start().len();
+            let start = line_start + indent;
+            let Some((key, _)) = trimmed.split_once('=') else {
+                let message = self.text("缺少 '=' 分隔符", "Missing '=' separator");
+                self.push_span(
+                    Some(start..start + trimmed.len()),
+                    DiagnosticSeverity::Error,
+                    message,
+                );
+                continue;
+            };
+            let key_span = Some(start..start + key.len());
+            let key = key.trim();
+            if key.is_empty() {
+                let message = self.text("环境变量名不能为空", "Variable name cannot be empty");
+                self.push_span(key_span, DiagnosticSeverity::Error, message);
+            } else if !key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                let message = self.text(
+                    "环境变量名只能包含字母、数字和下划线",
+                    "Variable name can only contain letters, numbers, and underscores",
+                );
+                self.push_span(key_span, DiagnosticSeverity::Error, message);
+            } else if !seen.insert(key.to_string()) {
+                let message = match self.locale {
+                    ReportLocale::Zh => format!("{key} 重复定义，以最后一次为准"),
+                    ReportLocale::En => format!("{key} is defined more than once; the last wins"),
+                };
+                self.push_span(key_span, DiagnosticSeverity::Warning, message);
+            }
+        }
+    }
+
+    fn check_mcp(&mut self) {
+        let Some(value) = self.parse_json() else {
+            return;
+        };
+        if self.require_object(&value).is_none() {
+            return;
+        }
+        if let Err(e) = crate::mcp::validate_server_spec(&value) {
+            self.push_span(None, DiagnosticSeverity::Error, e.to_string());
+        }
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    let url = url.trim();
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// 检查编辑器内容，按位置顺序返回诊断；内容类型与应用不匹配时报错
+pub fn validate(
+    app_type: &AppType,
+    kind: EditorKind,
+    text: &str,
+) -> Result<Vec<EditorDiagnostic>, AppError> {
+    validate_with_locale(app_type, kind, text, ReportLocale::current())
+}
+
+fn validate_with_locale(
+    app_type: &AppType,
+    kind: EditorKind,
+    text: &str,
+    locale: ReportLocale,
+) -> Result<Vec<EditorDiagnostic>, AppError> {
+    let mut collector = Collector::new(text, locale);
+    match (app_type, kind) {
+        (AppType::Claude | AppType::Gemini, EditorKind::Settings) => collector.check_settings(),
+        (AppType::Codex, EditorKind::Auth) => collector.check_auth(),
+        (AppType::Codex, EditorKind::Config) => collector.check_config(),
+        (AppType::Gemini, EditorKind::Env) => collector.check_env(),
+        (_, EditorKind::Mcp) => collector.check_mcp(),
+        _ => {
+            return Err(AppError::localized(
+                "editor.kind_unsupported",
+                format!("{} 没有 {kind:?} 类型的编辑器内容", app_type.as_str()),
+                format!("{} has no {kind:?} editor content", app_type.as_str()),
+            ))
+        }
+    }
+    let mut diagnostics = collector.diagnostics;
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(
+        app_type: AppType,
+        kind: EditorKind,
+        text: &str,
+    ) -> Vec<(usize, usize, DiagnosticSeverity)> {
+        validate_with_locale(&app_type, kind, text, ReportLocale::En)
+            .unwrap()
+            .into_iter()
+            .map(|d| (d.line, d.column, d.severity))
+            .collect()
+    }
+
+    #[test]
+    fn reports_positions_for_each_format() {
+        use DiagnosticSeverity::{Error, Warning};
+
+        let json = "{\n  \"env\": {\n    \"A\": 1,\n  }\n}";
+        assert_eq!(
+            check(AppType::Claude, EditorKind::Settings, json),
+            vec![(4, 3, Error)]
+        );
+        let json = "{\n  \"env\": { \"ANTHROPIC_BASE_URL\": \"relay\", \"N\": 1 }\n}";
+        assert_eq!(
+            check(AppType::Claude, EditorKind::Settings, json),
+            vec![(2, 12, Warning), (2, 43, Warning)]
+        );
+
+        let toml =
+            "model_provider = \"relay\"\n[model_providers.relay]\nbase_url = \"x\"\nwire_api = \n";
+        assert_eq!(
+            check(AppType::Codex, EditorKind::Config, toml),
+            vec![(4, 12, Error)]
+        );
+        let toml = "model_provider = \"relay\"\n";
+        assert_eq!(
+            check(AppType::Codex, EditorKind::Config, toml),
+            vec![(1, 18, Warning)]
+        );
+
+        let env = "GEMINI_API_KEY=a\nbad line\n  GEMINI_API_KEY=b\n";
+        assert_eq!(
+            check(AppType::Gemini, EditorKind::Env, env),
+            vec![(2, 1, Error), (3, 3, Warning)]
+        );
+
+        assert!(
+            validate_with_locale(&AppType::Claude, EditorKind::Config, "", ReportLocale::En)
+                .is_err()
+        );
+    }
+}
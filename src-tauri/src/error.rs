@@ -1,6 +1,8 @@
 use std::path::Path;
 use std::sync::PoisonError;
 
+use serde::Serialize;
+use serde_json::{json, Value};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -44,6 +46,14 @@ pub enum AppError {
     McpValidation(String),
     #[error("{0}")]
     Message(String),
+    /// 供应商已被其他窗口修改（携带当前保存的版本供前端合并）
+    #[error(
+        "供应商 {id} 已被修改，请合并最新内容后重试 (Provider {id} was modified elsewhere; merge the latest copy and retry)",
+        id = .current.id
+    )]
+    ProviderConflict {
+        current: Box<crate::provider::Provider>,
+    },
     #[error("{zh} ({en})")]
     Localized {
         key: &'static str,
@@ -81,6 +91,486 @@ impl AppError {
             en: en.into(),
         }
     }
+
+    /// 稳定的机器可读错误码（见 [`ERROR_CODES`]；`Localized` 错误使用其 i18n key）
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config",
+            Self::InvalidInput(_) => "invalid_input",
+            Self::Io { source, .. } | Self::IoContext { source, .. } => io_code(source),
+            Self::Json { .. } => "json_parse",
+            Self::JsonSerialize { .. } => "json_serialize",
+            Self::Toml { .. } => "toml_parse",
+            Self::Lock(_) => "lock",
+            Self::McpValidation(_) => "mcp_validation",
+            Self::Message(_) => "unknown",
+            Self::ProviderConflict { .. } => "provider_conflict",
+            Self::Localized { key, .. } => key,
+        }
+    }
+
+    /// 供前端使用的附加信息（文件路径、冲突时的当前版本等）
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            Self::Io { path, source } => {
+                Some(json!({ "path": path, "kind": source.kind().to_string() }))
+            }
+            Self::IoContext { context, source } => {
+                Some(json!({ "context": context, "kind": source.kind().to_string() }))
+            }
+            Self::Json { path, .. } | Self::Toml { path, .. } => Some(json!({ "path": path })),
+            Self::ProviderConflict { current } => Some(json!({ "current": current })),
+            Self::Localized { zh, en, .. } => Some(json!({ "zh": zh, "en": en })),
+            _ => None,
+        }
+    }
+}
+
+fn io_code(source: &std::io::Error) -> &'static str {
+    match source.kind() {
+        std::io::ErrorKind::NotFound => "file_not_found",
+        std::io::ErrorKind::PermissionDenied => "permission_denied",
+        _ => "io",
+    }
+}
+
+/// 通用错误码及说明；`Localized` 错误以其 i18n key 作为错误码，见 [`LOCALIZED_CODES`]
+pub const ERROR_CODES: &[(&str, &str)] = &[
+    ("config", "配置内容无效"),
+    ("invalid_input", "参数无效"),
+    ("file_not_found", "文件或目录不存在（details.path）"),
+    ("permission_denied", "没有读写权限（details.path）"),
+    ("io", "其他文件读写错误（details.path / details.kind）"),
+    ("json_parse", "JSON 文件解析失败（details.path）"),
+    ("json_serialize", "JSON 序列化失败"),
+    ("toml_parse", "TOML 文件解析失败（details.path）"),
+    ("lock", "内部锁获取失败"),
+    ("mcp_validation", "MCP 服务器定义校验失败"),
+    (
+        "provider_conflict",
+        "供应商已被其他窗口修改（details.current 为当前版本）",
+    ),
+    ("unknown", "未分类的错误，仅有 message"),
+];
+
+/// 全部 `Localized` 错误的 i18n key 及说明（按 key 排序；新增 key 时须同步登记，由测试检查）
+pub const LOCALIZED_CODES: &[(&str, &str)] = &[
+    ("ab_test.already_running", "该应用已有进行中的 A/B 测试"),
+    ("ab_test.duplicate_id", "A/B 测试 ID 已存在"),
+    ("ab_test.not_found", "A/B 测试不存在"),
+    ("ab_test.same_provider", "A/B 测试需要选择两个不同的供应商"),
+    ("adapter.home_missing", "无法确定用户主目录"),
+    ("adapter.invalid_manifest", "适配器清单字段无效"),
+    ("adapter.invalid_value", "适配器配置值无效"),
+    ("adapter.not_found", "适配器不存在"),
+    ("adapter.parse_failed", "解析适配器的配置文件失败"),
+    ("agent.invalid_name", "无效的子代理名称"),
+    ("agent.not_found", "子代理不存在"),
+    ("agent.repo_file_missing", "仓库中不存在子代理文件"),
+    ("backup.list_failed", "读取备份列表失败"),
+    ("capability.client_create_failed", "创建 HTTP 客户端失败"),
+    ("capability.invalid_url", "URL 无效"),
+    ("checkpoint.not_found", "live 检查点不存在"),
+    ("claude.keychain_failed", "读写钥匙串中的 Claude 凭据失败"),
+    ("claude.live.missing", "Claude 设置文件不存在，无法刷新快照"),
+    ("claude_hooks.duplicate", "Claude 钩子已存在"),
+    ("claude_hooks.empty_command", "钩子命令不能为空"),
+    ("codex.auth.missing", "Codex 配置文件不存在：缺少 auth.json"),
+    ("codex.config_path.invalid", "无效的 config.toml 键路径"),
+    ("codex.live.missing", "Codex auth.json 不存在，无法刷新快照"),
+    (
+        "codex.wire_api.no_base_url",
+        "供应商未配置 base_url，无法探测 wire_api",
+    ),
+    (
+        "codex.wire_api.no_provider",
+        "config.toml 未设置 model_provider，无法修改 wire_api",
+    ),
+    (
+        "codex.wire_api.undetected",
+        "端点既不支持 /responses 也不支持 /chat/completions",
+    ),
+    (
+        "config.external_change",
+        "config.json 已在 cc-switch 之外被修改，需重新加载或确认覆盖",
+    ),
+    ("config.locked", "配置正被其他 cc-switch 进程写入"),
+    ("config.save.rollback_failed", "保存配置失败且回滚失败"),
+    (
+        "config.schema_too_new",
+        "config.json 的 schema 版本过新，需升级",
+    ),
+    ("config.unsupported_v1", "检测到不再支持的 v1 配置格式"),
+    ("config_history.invalid_snapshot", "配置历史版本无法解析"),
+    ("config_history.not_found", "配置历史版本不存在或已被清理"),
+    ("config_template.missing_secrets", "缺少模板所需的密钥"),
+    ("config_template.version_too_new", "模板版本过新，需升级"),
+    ("daemon.api_bind_failed", "HTTP 控制接口无法监听端口"),
+    ("database.access_failed", "读写本地数据库失败"),
+    ("diagnostics.bundle_failed", "生成诊断包失败"),
+    ("doctor.finding_not_found", "检查项不存在或已修复"),
+    ("doctor.no_fix", "该检查项没有可自动执行的修复"),
+    ("editor.kind_unsupported", "该应用没有此类型的编辑器内容"),
+    ("env.detect_cli_failed", "检测已安装的 CLI 失败"),
+    ("env_file.unsupported_shell", "不支持的 shell"),
+    ("export.failed", "导出配置失败"),
+    ("gemini.env.missing", "Gemini .env 文件不存在"),
+    (
+        "gemini.env.parse_error.empty_key",
+        "Gemini .env 文件格式错误：环境变量名为空",
+    ),
+    (
+        "gemini.env.parse_error.invalid_key",
+        "Gemini .env 文件格式错误：环境变量名含非法字符",
+    ),
+    (
+        "gemini.env.parse_error.no_equals",
+        "Gemini .env 文件格式错误：缺少 '=' 分隔符",
+    ),
+    (
+        "gemini.live.missing",
+        "Gemini .env 文件不存在，无法刷新快照",
+    ),
+    ("gemini.missing_api_key", "缺少 GEMINI_API_KEY"),
+    (
+        "gemini.validation.invalid_env",
+        "Gemini 配置格式错误: env 必须是对象",
+    ),
+    (
+        "gemini.validation.missing_api_key",
+        "Gemini 配置缺少必需字段: GEMINI_API_KEY",
+    ),
+    ("home_dir_not_found", "用户主目录不存在，无法确定配置目录"),
+    ("import.failed", "导入配置失败"),
+    ("import.url_fetch_failed", "下载配置失败"),
+    ("import.url_not_https", "配置地址必须以 https:// 开头"),
+    (
+        "live.render_unsupported",
+        "Gemini 暂不支持写入到本机以外的位置",
+    ),
+    ("logs.invalid_level", "无效的日志级别"),
+    ("logs.read_failed", "读取日志失败"),
+    ("mcp.empty_id", "MCP 服务器 ID 不能为空"),
+    ("mcp.health_client_failed", "创建 HTTP 客户端失败"),
+    ("mcp.id_exists", "MCP 服务器 ID 已存在"),
+    ("mcp.not_found", "MCP 服务器不存在"),
+    (
+        "mcp.old_structure",
+        "检测到旧版 MCP 结构，请重启应用完成迁移",
+    ),
+    ("network.detect_failed", "检测网络环境失败"),
+    (
+        "oauth.credentials_missing",
+        "未找到登录凭据文件，请先完成官方登录",
+    ),
+    ("passcode.invalid", "口令错误"),
+    ("passcode.locked", "该操作需要先输入应用口令解锁"),
+    ("passcode.not_set", "尚未设置应用口令"),
+    ("passcode.too_short", "口令长度不足"),
+    ("placeholder.env_missing", "配置中引用的环境变量未设置"),
+    ("placeholder.unresolved", "无法解析配置占位符"),
+    ("post_commit.rollback_failed", "后置操作失败且回滚失败"),
+    ("provider.app_not_found", "应用类型不存在"),
+    ("provider.claude.api_key.missing", "缺少 API Key"),
+    (
+        "provider.claude.base_url.missing",
+        "缺少 ANTHROPIC_BASE_URL 配置",
+    ),
+    ("provider.claude.env.missing", "配置格式错误: 缺少 env"),
+    (
+        "provider.claude.settings.not_object",
+        "Claude 配置必须是 JSON 对象",
+    ),
+    ("provider.codex.api_key.missing", "缺少 API Key"),
+    ("provider.codex.auth.missing", "Codex 供应商缺少 auth 配置"),
+    (
+        "provider.codex.auth.not_object",
+        "Codex 供应商的 auth 配置必须是 JSON 对象",
+    ),
+    (
+        "provider.codex.base_url.invalid",
+        "config.toml 中 base_url 格式错误",
+    ),
+    (
+        "provider.codex.base_url.missing",
+        "config.toml 中缺少 base_url 配置",
+    ),
+    (
+        "provider.codex.config.invalid_type",
+        "Codex config 字段必须是字符串",
+    ),
+    (
+        "provider.codex.settings.not_object",
+        "Codex 配置必须是 JSON 对象",
+    ),
+    ("provider.csv.column_missing", "CSV 表头中找不到列"),
+    ("provider.csv.empty", "CSV 文件为空"),
+    (
+        "provider.csv.required_columns",
+        "CSV 需要包含名称、端点与密钥三列（可通过列映射指定）",
+    ),
+    ("provider.delete.current", "不能删除当前正在使用的供应商"),
+    ("provider.detect_cli_failed", "检测已安装的 CLI 失败"),
+    ("provider.endpoint.url_required", "URL 不能为空"),
+    ("provider.extra_env.invalid_name", "无效的环境变量名"),
+    (
+        "provider.extra_env.invalid_value",
+        "环境变量的值不能包含换行符或空字符",
+    ),
+    (
+        "provider.extra_env.reserved",
+        "环境变量由 CC Switch 或系统管理，不能自定义",
+    ),
+    (
+        "provider.extra_env.unsupported_app",
+        "仅 Codex 支持额外环境变量",
+    ),
+    ("provider.headers.invalid_name", "无效的请求头名称"),
+    ("provider.headers.invalid_value", "请求头的值不能包含换行符"),
+    (
+        "provider.headers.unsupported_app",
+        "Gemini 暂不支持自定义请求头",
+    ),
+    ("provider.model.invalid", "模型名称不能包含换行符或空字符"),
+    ("provider.not_found", "供应商不存在"),
+    ("provider.performance.invalid", "性能参数必须大于 0"),
+    (
+        "provider.performance.unsupported_app",
+        "Gemini 暂不支持性能参数",
+    ),
+    (
+        "provider.performance.unsupported_field",
+        "Claude 仅支持设置请求超时",
+    ),
+    ("provider.regex_init_failed", "正则初始化失败"),
+    (
+        "provider.temporary_switch.invalid_duration",
+        "临时切换时长超出允许范围",
+    ),
+    (
+        "provider.temporary_switch.same_provider",
+        "目标供应商已是当前供应商",
+    ),
+    ("provider.usage.disabled", "用量查询未启用"),
+    ("provider.usage.script.missing", "未配置用量查询脚本"),
+    (
+        "proxy.api_key_missing",
+        "供应商未配置 API Key，无法通过代理转发",
+    ),
+    ("proxy.bind_failed", "本地代理无法监听端口"),
+    ("proxy.metrics_failed", "统计代理指标失败"),
+    (
+        "ranking.invalid_weights",
+        "评分权重必须为非负数且不能全为 0",
+    ),
+    ("registry.client_create_failed", "创建 HTTP 客户端失败"),
+    ("registry.fetch_failed", "拉取注册表失败"),
+    ("registry.invalid", "注册表内容无效"),
+    ("remote.host_not_found", "远程主机不存在"),
+    ("remote.invalid_host", "远程主机字段无效"),
+    ("remote.sftp_failed", "sftp 执行失败"),
+    ("remote.test_failed", "测试远程主机失败"),
+    ("remote.write_failed", "写入远程主机失败"),
+    ("sandbox.validate_failed", "沙箱验证失败"),
+    ("settings.export_failed", "导出设置失败"),
+    ("settings.import_failed", "导入设置失败"),
+    (
+        "share.endpoint_missing",
+        "未配置分享服务地址，无法获取分享内容",
+    ),
+    ("share.expired", "分享内容已过期"),
+    ("share.invalid_snippet", "分享码无效或已损坏"),
+    ("share.request_failed", "访问分享服务失败"),
+    ("share.wrong_passphrase", "口令错误或分享内容被篡改"),
+    ("shell_wrapper.unsupported_value", "值包含 cmd 脚本无法表示的字符"),
+    ("skill.not_found", "技能不存在"),
+    ("slash_command.invalid_name", "无效的斜杠命令名称"),
+    ("slash_command.not_found", "斜杠命令不存在"),
+    (
+        "speedtest.api_key_missing",
+        "供应商未配置 API Key，无法发送真实请求",
+    ),
+    ("speedtest.client_create_failed", "创建 HTTP 客户端失败"),
+    ("speedtest.invalid_url", "URL 无效"),
+    ("subscription.exists", "订阅已存在"),
+    ("subscription.fetch_failed", "拉取订阅清单失败"),
+    ("subscription.invalid_manifest", "订阅清单格式无效"),
+    (
+        "subscription.invalid_url",
+        "订阅地址必须以 https:// 开头（仅本机地址可使用 http://）",
+    ),
+    ("subscription.provider_not_found", "订阅中不存在该供应商"),
+    ("switch.verify_failed", "live 配置写入校验失败，已回滚"),
+    (
+        "switch_rule.empty_network",
+        "网络条件至少需要填写 SSID、网关、接口或 VPN 状态中的一项",
+    ),
+    ("switch_rule.empty_window", "开始时间与结束时间不能相同"),
+    ("switch_rule.invalid_day", "无效的星期编号（应为 1-7）"),
+    ("switch_rule.invalid_quota", "无效的额度阈值"),
+    (
+        "switch_rule.invalid_quota_resume",
+        "恢复阈值不能低于触发阈值",
+    ),
+    ("switch_rule.invalid_time", "无效的时间格式（应为 HH:MM）"),
+    (
+        "switch_rule.no_available_provider",
+        "没有额度充足且可达的供应商",
+    ),
+    ("system.open_path_failed", "打开文件夹失败"),
+    ("system.open_url_failed", "打开链接失败"),
+    ("system.pick_directory_failed", "弹出目录选择器失败"),
+    ("team_pack.invalid_archive", "读写团队包失败"),
+    ("team_pack.invalid_settings", "团队包中的推荐设置无效"),
+    ("team_pack.item_not_found", "要打包的项不存在"),
+    (
+        "team_pack.unsupported_version",
+        "团队包版本过新，需升级应用后再导入",
+    ),
+    ("tokens.unknown_family", "未知的模型家族"),
+    ("tray.icon_invalid", "托盘图标不是有效的 PNG 文件"),
+    ("tray.update_failed", "更新托盘菜单失败"),
+    (
+        "unsupported_app",
+        "不支持的应用标识（可选 claude / codex / gemini）",
+    ),
+    (
+        "usage_script.array_validation_failed",
+        "脚本返回的数组元素验证失败",
+    ),
+    (
+        "usage_script.circuit_open",
+        "用量脚本连续失败已自动停用，冷却后重试",
+    ),
+    ("usage_script.client_create_failed", "创建客户端失败"),
+    ("usage_script.config_parse_failed", "解析配置失败"),
+    ("usage_script.config_reparse_failed", "重新解析配置失败"),
+    ("usage_script.context_create_failed", "创建 JS 上下文失败"),
+    ("usage_script.data_format_error", "数据格式错误"),
+    ("usage_script.empty_array", "脚本返回的数组不能为空"),
+    ("usage_script.extra_type_error", "extra 必须是字符串或 null"),
+    ("usage_script.extractor_exec_failed", "执行 extractor 失败"),
+    ("usage_script.extractor_missing", "缺少 extractor 函数"),
+    ("usage_script.get_string_failed", "获取字符串失败"),
+    ("usage_script.http_error", "用量查询请求返回 HTTP 错误"),
+    (
+        "usage_script.interval_too_large",
+        "自动查询间隔不能超过 1440 分钟",
+    ),
+    ("usage_script.invalid_http_method", "不支持的 HTTP 方法"),
+    (
+        "usage_script.invalidmessage_type_error",
+        "invalidMessage 必须是字符串或 null",
+    ),
+    (
+        "usage_script.isvalid_type_error",
+        "isValid 必须是布尔值或 null",
+    ),
+    ("usage_script.json_parse_failed", "JSON 解析失败"),
+    (
+        "usage_script.must_return_object",
+        "脚本必须返回对象或对象数组",
+    ),
+    (
+        "usage_script.planname_type_error",
+        "planName 必须是字符串或 null",
+    ),
+    ("usage_script.read_response_failed", "读取响应失败"),
+    (
+        "usage_script.remaining_type_error",
+        "remaining 必须是数字或 null",
+    ),
+    ("usage_script.request_failed", "请求失败"),
+    (
+        "usage_script.request_format_invalid",
+        "request 配置格式错误",
+    ),
+    ("usage_script.request_missing", "缺少 request 配置"),
+    (
+        "usage_script.request_serialize_failed",
+        "序列化 request 失败",
+    ),
+    ("usage_script.response_parse_failed", "解析响应 JSON 失败"),
+    ("usage_script.response_too_large", "响应体超过大小上限"),
+    ("usage_script.result_serialize_failed", "序列化结果失败"),
+    ("usage_script.runtime_create_failed", "创建 JS 运行时失败"),
+    ("usage_script.serialize_none", "序列化返回 None"),
+    ("usage_script.total_type_error", "total 必须是数字或 null"),
+    ("usage_script.unit_type_error", "unit 必须是字符串或 null"),
+    ("usage_script.used_type_error", "used 必须是数字或 null"),
+    ("wsl.home_unavailable", "无法获取 WSL 发行版的主目录"),
+    ("wsl.list_failed", "列出 WSL 发行版失败"),
+    ("wsl.read_failed", "读取 WSL 配置失败"),
+    ("wsl.sync_failed", "写入 WSL 发行版失败，已回滚"),
+    ("wsl.unavailable", "执行 wsl.exe 失败"),
+];
+
+/// `error_codes` 命令返回的错误码说明
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorCodeInfo {
+    pub code: &'static str,
+    pub description: &'static str,
+}
+
+pub fn error_codes() -> Vec<ErrorCodeInfo> {
+    ERROR_CODES
+        .iter()
+        .chain(LOCALIZED_CODES)
+        .map(|&(code, description)| ErrorCodeInfo { code, description })
+        .collect()
+}
+
+/// 命令返回给前端的错误：`{ code, message, details? }`
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandError {
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<AppError> for CommandError {
+    fn from(err: AppError) -> Self {
+        Self {
+            code: err.code().to_string(),
+            message: err.to_string(),
+            details: err.details(),
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self {
+            code: "unknown".to_string(),
+            message,
+            details: None,
+        }
+    }
+}
+
+impl<T> From<PoisonError<T>> for CommandError {
+    fn from(err: PoisonError<T>) -> Self {
+        AppError::from(err).into()
+    }
+}
+
+impl From<anyhow::Error> for CommandError {
+    fn from(err: anyhow::Error) -> Self {
+        err.to_string().into()
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
 }
 
 impl<T> From<PoisonError<T>> for AppError {
@@ -94,3 +584,103 @@ impl From<AppError> for String {
         err.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_errors_carry_stable_codes() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        let err = CommandError::from(AppError::io("/tmp/settings.json", missing));
+        assert_eq!(err.code, "file_not_found");
+        assert_eq!(err.details.unwrap()["path"], "/tmp/settings.json");
+
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(AppError::io("/tmp/x", denied).code(), "permission_denied");
+
+        let err = CommandError::from(AppError::localized(
+            "provider.not_found",
+            "不存在",
+            "missing",
+        ));
+        assert_eq!(err.code, "provider.not_found");
+        assert_eq!(err.details.unwrap()["en"], "missing");
+
+        let err = CommandError::from("plain".to_string());
+        assert_eq!(err.code, "unknown");
+        assert!(err.details.is_none());
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "code": "unknown", "message": "plain" })
+        );
+
+        let documented: Vec<&str> = ERROR_CODES.iter().map(|(code, _)| *code).collect();
+        for err in [
+            AppError::Config(String::new()),
+            AppError::InvalidInput(String::new()),
+            AppError::Lock(String::new()),
+            AppError::McpValidation(String::new()),
+            AppError::Message(String::new()),
+        ] {
+            assert!(
+                documented.contains(&err.code()),
+                "{} is undocumented",
+                err.code()
+            );
+        }
+    }
+
+    /// 扫描源码中所有 `AppError::localized("<key>", ...)`，确保 key 均已登记且登记表无过期项
+    #[test]
+    fn localized_keys_are_registered() {
+        fn collect(dir: &Path, keys: &mut std::collections::BTreeSet<String>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    collect(&path, keys);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    let source = std::fs::read_to_string(&path).unwrap();
+                    for rest in source
+                        .match_indices("localized(")
+                        .map(|(i, m)| source[i + m.len()..].trim_start())
+                    {
+                        let Some(rest) = rest.strip_prefix('"') else {
+                            continue;
+                        };
+                        let key = &rest[..rest.find('"').unwrap()];
+                        let is_key = !key.is_empty()
+                            && key.chars().all(|c| {
+                                c.is_ascii_lowercase() || c.is_ascii_digit() || "._".contains(c)
+                            });
+                        if is_key {
+                            keys.insert(key.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut used = std::collections::BTreeSet::new();
+        // cargo test 以包根目录为工作目录，file!() 即 src/error.rs
+        collect(Path::new(file!()).parent().unwrap(), &mut used);
+        // 测试中的示例 key 以外，源码里至少应有一批已登记的 key
+        assert!(used.len() > 100, "found only {} keys", used.len());
+
+        let registered: std::collections::BTreeSet<String> = LOCALIZED_CODES
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .collect();
+        assert_eq!(registered.len(), LOCALIZED_CODES.len(), "duplicate keys");
+        let missing: Vec<_> = used.difference(&registered).collect();
+        assert!(
+            missing.is_empty(),
+            "unregistered localized keys: {missing:?}"
+        );
+        let stale: Vec<_> = registered.difference(&used).collect();
+        assert!(stale.is_empty(), "registered but unused keys: {stale:?}");
+        assert!(ERROR_CODES
+            .iter()
+            .all(|(code, _)| !registered.contains(*code)));
+    }
+}
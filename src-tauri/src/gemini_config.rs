@@ -1,19 +1,20 @@
 use crate::config::write_text_file;
 use crate::error::AppError;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
+/// Gemini 配置目录中用于识别“正在使用”的文件
+pub(crate) const GEMINI_DIR_MARKERS: &[&str] = &[".env", "settings.json", "oauth_creds.json"];
+
 /// 获取 Gemini 配置目录路径（支持设置覆盖）
 pub fn get_gemini_dir() -> PathBuf {
     if let Some(custom) = crate::settings::get_gemini_override_dir() {
         return custom;
     }
 
-    dirs::home_dir()
-        .expect("无法获取用户主目录")
-        .join(".gemini")
+    crate::config::resolve_cli_dir("gemini", ".gemini", GEMINI_DIR_MARKERS)
 }
 
 /// 获取 Gemini .env 文件路径
@@ -191,6 +192,50 @@ pub fn write_gemini_env_atomic(map: &HashMap<String, String>) -> Result<(), AppE
     Ok(())
 }
 
+/// 始终视为由 cc-switch 管理的 Gemini 环境变量
+pub const GEMINI_MANAGED_ENV_KEYS: &[&str] =
+    &["GEMINI_API_KEY", "GOOGLE_GEMINI_BASE_URL", "GEMINI_MODEL"];
+
+/// 合并 .env：保留现有文件中的非托管键，托管键以 `overlay` 为准（`overlay` 中没有的托管键会被移除）
+pub fn merge_env(
+    existing: &HashMap<String, String>,
+    managed: &HashSet<String>,
+    overlay: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut merged: HashMap<String, String> = existing
+        .iter()
+        .filter(|(key, _)| !managed.contains(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    merged.extend(overlay.iter().map(|(k, v)| (k.clone(), v.clone())));
+    merged
+}
+
+/// 写入 Gemini .env：默认只覆盖托管键并保留用户自行添加的变量（如 `NO_BROWSER`），
+/// 开启 `gemini_env_replace` 设置时整体替换
+pub fn write_gemini_env_managed(
+    map: &HashMap<String, String>,
+    managed: &HashSet<String>,
+) -> Result<(), AppError> {
+    if crate::settings::get_settings().gemini_env_replace {
+        return write_gemini_env_atomic(map);
+    }
+    let existing = read_gemini_env()?;
+    write_gemini_env_atomic(&merge_env(&existing, managed, map))
+}
+
+/// 读取 Gemini .env 中属于供应商的部分（回填快照用）；合并模式下忽略非托管键，
+/// 避免用户自行添加的变量被并入供应商配置
+pub fn read_gemini_managed_env(
+    managed: &HashSet<String>,
+) -> Result<HashMap<String, String>, AppError> {
+    let mut env = read_gemini_env()?;
+    if !crate::settings::get_settings().gemini_env_replace {
+        env.retain(|key, _| managed.contains(key));
+    }
+    Ok(env)
+}
+
 /// 从 .env 格式转换为 Provider.settings_config (JSON Value)
 pub fn env_to_json(env_map: &HashMap<String, String>) -> Value {
     let mut json_map = serde_json::Map::new();
@@ -270,6 +315,38 @@ pub fn get_gemini_settings_path() -> PathBuf {
     get_gemini_dir().join("settings.json")
 }
 
+/// 获取 Gemini CLI OAuth 凭据缓存路径 (`~/.gemini/oauth_creds.json`)
+pub fn get_gemini_oauth_creds_path() -> PathBuf {
+    get_gemini_dir().join("oauth_creds.json")
+}
+
+/// 读取 Gemini CLI 的 OAuth 凭据缓存（不存在时返回 None）
+pub fn read_gemini_oauth_creds() -> Result<Option<Value>, AppError> {
+    let path = get_gemini_oauth_creds_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    crate::config::read_json_file(&path).map(Some)
+}
+
+/// 写入 Gemini CLI 的 OAuth 凭据缓存（原子操作，权限 600）
+pub fn write_gemini_oauth_creds(creds: &Value) -> Result<(), AppError> {
+    let path = get_gemini_oauth_creds_path();
+    crate::config::write_json_file(&path, creds)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&path)
+            .map_err(|e| AppError::io(&path, e))?
+            .permissions();
+        perms.set_mode(0o600);
+        fs::set_permissions(&path, perms).map_err(|e| AppError::io(&path, e))?;
+    }
+
+    Ok(())
+}
+
 /// 更新 Gemini 目录 settings.json 中的 security.auth.selectedType 字段
 ///
 /// 此函数会：
@@ -358,10 +435,143 @@ pub fn write_google_oauth_settings() -> Result<(), AppError> {
     update_selected_type("oauth-personal")
 }
 
+/// 将默认模型写入 settings.json 的 `model.name`（Gemini CLI 启动时使用的模型）
+///
+/// `model` 为 None 时只移除由供应商写入过的值（`managed_models`），保留用户自行选择的模型；
+/// 返回是否有修改
+pub fn apply_model_setting(
+    settings: &mut Value,
+    model: Option<&str>,
+    managed_models: &HashSet<String>,
+) -> bool {
+    let current = match settings.get("model") {
+        Some(Value::String(name)) => Some(name.as_str()),
+        Some(model) => model.get("name").and_then(Value::as_str),
+        None => None,
+    }
+    .map(str::to_string);
+    if current.as_deref() == model {
+        return false;
+    }
+    let Some(obj) = settings.as_object_mut() else {
+        return false;
+    };
+
+    match model {
+        Some(model) => {
+            // 兼容旧版 `"model": "<name>"` 写法，统一改为对象形式
+            let entry = obj.entry("model").or_insert_with(|| serde_json::json!({}));
+            if !entry.is_object() {
+                *entry = serde_json::json!({});
+            }
+            if let Some(model_obj) = entry.as_object_mut() {
+                model_obj.insert("name".to_string(), Value::String(model.to_string()));
+            }
+            true
+        }
+        None => {
+            if !current.is_some_and(|name| managed_models.contains(&name)) {
+                return false;
+            }
+            match obj.get_mut("model") {
+                Some(Value::Object(model_obj)) => {
+                    model_obj.remove("name");
+                    if model_obj.is_empty() {
+                        obj.remove("model");
+                    }
+                }
+                _ => {
+                    obj.remove("model");
+                }
+            }
+            true
+        }
+    }
+}
+
+/// 按供应商的 `GEMINI_MODEL` 更新 `~/.gemini/settings.json` 中的默认模型（无变化时不写入）
+pub fn write_model_setting(
+    model: Option<&str>,
+    managed_models: &HashSet<String>,
+) -> Result<(), AppError> {
+    let settings_path = get_gemini_settings_path();
+    let mut settings = if settings_path.exists() {
+        let content =
+            fs::read_to_string(&settings_path).map_err(|e| AppError::io(&settings_path, e))?;
+        match serde_json::from_str::<Value>(&content) {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("Gemini settings.json 无法解析，跳过默认模型写入: {e}");
+                return Ok(());
+            }
+        }
+    } else if model.is_some() {
+        serde_json::json!({})
+    } else {
+        return Ok(());
+    };
+
+    if apply_model_setting(&mut settings, model, managed_models) {
+        if let Some(parent) = settings_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        crate::config::write_json_file(&settings_path, &settings)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn model_setting_writes_name_and_keeps_user_choice() {
+        let managed: HashSet<String> = ["gemini-2.5-flash".to_string()].into();
+
+        let mut settings = serde_json::json!({ "model": "gemini-1.5-pro", "theme": "Dracula" });
+        assert!(apply_model_setting(
+            &mut settings,
+            Some("gemini-2.5-flash"),
+            &managed
+        ));
+        assert_eq!(settings["model"]["name"], "gemini-2.5-flash");
+        assert_eq!(settings["theme"], "Dracula");
+        assert!(!apply_model_setting(
+            &mut settings,
+            Some("gemini-2.5-flash"),
+            &managed
+        ));
+
+        // 供应商未设置模型：移除供应商写入的值，保留用户自选的模型
+        assert!(apply_model_setting(&mut settings, None, &managed));
+        assert!(settings.get("model").is_none());
+        let mut user = serde_json::json!({ "model": { "name": "gemini-exp" } });
+        assert!(!apply_model_setting(&mut user, None, &managed));
+        assert_eq!(user["model"]["name"], "gemini-exp");
+    }
+
+    #[test]
+    fn test_merge_env_keeps_unmanaged_keys() {
+        let existing: HashMap<String, String> = [
+            ("GEMINI_API_KEY", "old"),
+            ("OLD_PROVIDER_ONLY", "x"),
+            ("NO_BROWSER", "1"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let managed: HashSet<String> = ["GEMINI_API_KEY", "OLD_PROVIDER_ONLY"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let overlay = HashMap::from([("GEMINI_API_KEY".to_string(), "new".to_string())]);
+
+        let merged = merge_env(&existing, &managed, &overlay);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged.get("GEMINI_API_KEY"), Some(&"new".to_string()));
+        assert_eq!(merged.get("NO_BROWSER"), Some(&"1".to_string()));
+    }
+
     #[test]
     fn test_parse_env_file() {
         let content = r#"
@@ -105,6 +105,7 @@ pub fn set_mcp_servers_map(
         obj.remove("tags");
         obj.remove("homepage");
         obj.remove("docs");
+        crate::mcp::apply_platform_launcher(&mut obj);
 
         out.insert(id.clone(), Value::Object(obj));
     }
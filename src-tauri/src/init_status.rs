@@ -1,13 +1,19 @@
 use serde::Serialize;
 use std::sync::{OnceLock, RwLock};
 
+use crate::config_integrity::IntegrityReport;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct InitErrorPayload {
     pub path: String,
     pub error: String,
+    /// 配置完整性报告（解析位置、schema 版本、可用的修复方案）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<IntegrityReport>,
 }
 
 static INIT_ERROR: OnceLock<RwLock<Option<InitErrorPayload>>> = OnceLock::new();
+static INTEGRITY_WARNING: OnceLock<RwLock<Option<IntegrityReport>>> = OnceLock::new();
 
 fn cell() -> &'static RwLock<Option<InitErrorPayload>> {
     INIT_ERROR.get_or_init(|| RwLock::new(None))
@@ -23,6 +29,21 @@ pub fn get_init_error() -> Option<InitErrorPayload> {
     cell().read().ok()?.clone()
 }
 
+fn warning_cell() -> &'static RwLock<Option<IntegrityReport>> {
+    INTEGRITY_WARNING.get_or_init(|| RwLock::new(None))
+}
+
+/// 记录启动时检测到的非致命完整性问题（如校验和不一致但配置仍可加载）
+pub fn set_integrity_warning(report: IntegrityReport) {
+    if let Ok(mut guard) = warning_cell().write() {
+        *guard = Some(report);
+    }
+}
+
+pub fn get_integrity_warning() -> Option<IntegrityReport> {
+    warning_cell().read().ok()?.clone()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,6 +53,7 @@ mod tests {
         let payload = InitErrorPayload {
             path: "/tmp/config.json".into(),
             error: "broken json".into(),
+            report: None,
         };
         set_init_error(payload.clone());
         let got = get_init_error().expect("should get payload back");
@@ -1,47 +1,85 @@
+mod ab_test;
+mod adapter;
 mod app_config;
 mod app_store;
+mod claude_hooks;
+mod claude_keychain;
 mod claude_mcp;
 mod claude_plugin;
 mod codex_config;
 mod commands;
 mod config;
+mod config_events;
+mod config_history;
+mod config_integrity;
+mod config_lock;
+mod config_migrations;
+mod config_template;
+mod config_watch;
+mod control_api;
+mod crash_reporter;
+mod daemon;
+mod database;
 mod deeplink;
+mod editor_diagnostics;
 mod error;
 mod gemini_config; // 新增
 mod gemini_mcp;
 mod init_status;
+mod limited_listener;
+mod logging;
+mod managed_files;
 mod mcp;
+mod network;
+mod pagination;
+mod placeholder;
+mod project_mcp;
 mod prompt;
 mod prompt_files;
 mod provider;
+mod provider_csv;
+mod proxy;
+mod remote_host;
+mod report_format;
+mod scheduler;
 mod services;
 mod settings;
+mod slash_command;
+mod statusline;
 mod store;
+mod subscription;
+mod switch_rule;
+mod tokens;
+mod tray_icon;
+mod usage_normalize;
 mod usage_script;
+mod wsl;
 
 pub use app_config::{AppType, McpApps, McpServer, MultiAppConfig};
 pub use codex_config::{get_codex_auth_path, get_codex_config_path, write_codex_live_atomic};
 pub use commands::*;
 pub use config::{get_claude_mcp_path, get_claude_settings_path, read_json_file};
 pub use deeplink::{import_provider_from_deeplink, parse_deeplink_url, DeepLinkImportRequest};
-pub use error::AppError;
+pub use error::{AppError, CommandError};
 pub use mcp::{
-    import_from_claude, import_from_codex, import_from_gemini, remove_server_from_claude,
-    remove_server_from_codex, remove_server_from_gemini, sync_enabled_to_claude,
-    sync_enabled_to_codex, sync_enabled_to_gemini, sync_single_server_to_claude,
-    sync_single_server_to_codex, sync_single_server_to_gemini,
+    import_from_claude, import_from_codex, import_from_gemini, preview_sync_for,
+    remove_server_from_claude, remove_server_from_codex, remove_server_from_gemini,
+    sync_enabled_to_claude, sync_enabled_to_codex, sync_enabled_to_gemini,
+    sync_single_server_to_claude, sync_single_server_to_codex, sync_single_server_to_gemini,
+    McpSyncPreview,
 };
 pub use provider::{Provider, ProviderMeta};
 pub use services::{
-    ConfigService, EndpointLatency, McpService, PromptService, ProviderService, SkillService,
-    SpeedtestService,
+    ConfigService, EndpointLatency, ImportMergeStrategy, McpService, PromptService,
+    ProviderService, RegistryKind, RegistryService, SkillService, SpeedtestService,
 };
-pub use settings::{update_settings, AppSettings};
+pub use settings::{update_settings, AppSettings, McpSwitchSync, PathStrategy};
 pub use store::AppState;
 use tauri_plugin_deep_link::DeepLinkExt;
 
 use std::sync::Arc;
 use tauri::{
+    image::Image,
     menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem},
     tray::{TrayIconBuilder, TrayIconEvent},
 };
@@ -142,26 +180,19 @@ fn append_provider_section<'a>(
         return Ok(menu_builder.item(&empty_hint));
     }
 
-    let mut sorted_providers: Vec<_> = manager.providers.iter().collect();
-    sorted_providers.sort_by(|(_, a), (_, b)| {
-        match (a.sort_index, b.sort_index) {
-            (Some(idx_a), Some(idx_b)) => return idx_a.cmp(&idx_b),
-            (Some(_), None) => return std::cmp::Ordering::Less,
-            (None, Some(_)) => return std::cmp::Ordering::Greater,
-            _ => {}
-        }
-
-        match (a.created_at, b.created_at) {
-            (Some(time_a), Some(time_b)) => return time_a.cmp(&time_b),
-            (Some(_), None) => return std::cmp::Ordering::Greater,
-            (None, Some(_)) => return std::cmp::Ordering::Less,
-            _ => {}
-        }
-
-        a.name.cmp(&b.name)
-    });
+    let sorted_providers = ProviderService::sort_providers(
+        &section.app_type,
+        &manager.providers,
+        crate::settings::get_settings().tray_sort,
+    );
 
+    let mut previous_pinned = false;
     for (id, provider) in sorted_providers {
+        // 置顶分组与其余供应商之间加分隔线
+        if previous_pinned && !provider.pinned {
+            menu_builder = menu_builder.separator();
+        }
+        previous_pinned = provider.pinned;
         let is_current = manager.current == *id;
         let item = CheckMenuItem::with_id(
             app,
@@ -354,45 +385,83 @@ fn switch_provider_internal(
     provider_id: String,
 ) -> Result<(), AppError> {
     if let Some(app_state) = app.try_state::<AppState>() {
-        // 在使用前先保存需要的值
-        let app_type_str = app_type.as_str().to_string();
-        let provider_id_clone = provider_id.clone();
-
-        crate::commands::switch_provider(app_state.clone(), app_type_str.clone(), provider_id)
-            .map_err(AppError::Message)?;
+        // 托盘、临时切换与自动规则均为明确的切换意图，跳过健康预检；
+        // 目标已是当前供应商且 live 配置未变化时不重写文件，也无需刷新托盘
+        let details = ProviderService::switch(app_state.inner(), app_type, &provider_id)?;
+        if details.no_change {
+            return Ok(());
+        }
 
         // 切换成功后重新创建托盘菜单
-        if let Ok(new_menu) = create_tray_menu(app, app_state.inner()) {
-            if let Some(tray) = app.tray_by_id("main") {
-                if let Err(e) = tray.set_menu(Some(new_menu)) {
-                    log::error!("更新托盘菜单失败: {e}");
-                }
-            }
-        }
+        refresh_tray(app, app_state.inner());
 
-        // 发射事件到前端，通知供应商已切换
-        let event_data = serde_json::json!({
-            "appType": app_type_str,
-            "providerId": provider_id_clone
-        });
-        if let Err(e) = app.emit("provider-switched", event_data) {
+        // 发射事件到前端，通知供应商已切换（附带上一个供应商、名称、写入耗时等详情）
+        if let Err(e) = app.emit("provider-switched", details) {
             log::error!("发射供应商切换事件失败: {e}");
         }
     }
     Ok(())
 }
 
+/// 按最新配置重建托盘菜单并更新托盘图标（未创建托盘时无操作）
+fn refresh_tray(app: &tauri::AppHandle, app_state: &AppState) {
+    if let Ok(new_menu) = create_tray_menu(app, app_state) {
+        if let Some(tray) = app.tray_by_id("main") {
+            if let Err(e) = tray.set_menu(Some(new_menu)) {
+                log::error!("更新托盘菜单失败: {e}");
+            }
+        }
+    }
+    refresh_tray_icon(app, app_state);
+}
+
+/// 按当前供应商的 icon / iconColor 更新托盘图标（均未设置时恢复默认图标）
+fn refresh_tray_icon(app: &tauri::AppHandle, app_state: &AppState) {
+    let (Some(tray), Some(default_icon)) = (app.tray_by_id("main"), app.default_window_icon())
+    else {
+        return;
+    };
+    let custom = match app_state.config.read() {
+        Ok(config) => tray_icon::active_provider(&config).and_then(|provider| {
+            tray_icon::render(
+                provider,
+                default_icon.rgba(),
+                default_icon.width(),
+                default_icon.height(),
+            )
+        }),
+        Err(e) => {
+            log::error!("读取配置锁失败，跳过托盘图标更新: {e}");
+            return;
+        }
+    };
+    let icon = match custom {
+        Some(icon) => Image::new(&icon.rgba, icon.width, icon.height).to_owned(),
+        None => default_icon.clone(),
+    };
+    if let Err(e) = tray.set_icon(Some(icon)) {
+        log::error!("更新托盘图标失败: {e}");
+    }
+}
+
 /// 更新托盘菜单的Tauri命令
 #[tauri::command]
 async fn update_tray_menu(
     app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
+    // 供应商的图标设置可能已修改，一并刷新托盘图标
+    refresh_tray_icon(&app, state.inner());
     match create_tray_menu(&app, state.inner()) {
         Ok(new_menu) => {
             if let Some(tray) = app.tray_by_id("main") {
-                tray.set_menu(Some(new_menu))
-                    .map_err(|e| format!("更新托盘菜单失败: {e}"))?;
+                tray.set_menu(Some(new_menu)).map_err(|e| {
+                    AppError::localized(
+                        "tray.update_failed",
+                        format!("更新托盘菜单失败: {e}"),
+                        format!("Failed to update tray menu: {e}"),
+                    )
+                })?;
                 return Ok(true);
             }
             Ok(false)
@@ -406,6 +475,8 @@ async fn update_tray_menu(
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    crash_reporter::install();
+
     let mut builder = tauri::Builder::default();
 
     #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
@@ -430,7 +501,10 @@ pub fn run() {
                 log::info!("ℹ No deep link URL found in args (this is expected on macOS when launched via system)");
             }
 
-            // Show and focus window regardless
+            // 再次以守护模式启动时保持无界面，其余情况显示并聚焦主窗口
+            if args.iter().any(|arg| arg == daemon::DAEMON_FLAG) {
+                return;
+            }
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.unminimize();
                 let _ = window.show();
@@ -447,7 +521,8 @@ pub fn run() {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let settings = crate::settings::get_settings();
 
-                if settings.minimize_to_tray_on_close {
+                // 守护模式下关闭窗口只隐藏，后台任务继续运行
+                if settings.minimize_to_tray_on_close || daemon::is_daemon() {
                     api.prevent_close();
                     let _ = window.hide();
                     #[cfg(target_os = "windows")]
@@ -509,14 +584,18 @@ pub fn run() {
                 }
             }
 
-            // 初始化日志
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
+            // 初始化日志（写入 ~/.cc-switch/logs，级别取自设置）
+            app.handle().plugin(logging::plugin())?;
+            logging::apply_level(crate::settings::get_settings().log_level);
+
+            // 用户同意时上传本地崩溃记录
+            tauri::async_runtime::spawn(async {
+                match crash_reporter::upload_pending().await {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("已上传 {count} 条崩溃报告"),
+                    Err(e) => log::warn!("上传崩溃报告失败: {e}"),
+                }
+            });
 
             // 预先刷新 Store 覆盖配置，确保 AppState 初始化时可读取到最新路径
             app_store::refresh_app_config_dir_override(app.handle());
@@ -527,19 +606,17 @@ pub fn run() {
                 Ok(state) => state,
                 Err(err) => {
                     let path = crate::config::get_app_config_path();
-                    let payload_json = serde_json::json!({
-                        "path": path.display().to_string(),
-                        "error": err.to_string(),
-                    });
+                    let payload = crate::init_status::InitErrorPayload {
+                        path: path.display().to_string(),
+                        error: err.to_string(),
+                        report: Some(crate::config_integrity::inspect()),
+                    };
                     // 事件通知（可能早于前端订阅，不保证送达）
-                    if let Err(e) = app.emit("configLoadError", payload_json) {
+                    if let Err(e) = app.emit("configLoadError", payload.clone()) {
                         log::error!("发射配置加载错误事件失败: {e}");
                     }
                     // 同时缓存错误，供前端启动阶段主动拉取
-                    crate::init_status::set_init_error(crate::init_status::InitErrorPayload {
-                        path: path.display().to_string(),
-                        error: err.to_string(),
-                    });
+                    crate::init_status::set_init_error(payload);
                     // 不再继续构建托盘/命令依赖的状态，交由前端提示后退出。
                     return Ok(());
                 }
@@ -592,29 +669,63 @@ pub fn run() {
             });
             log::info!("✓ Deep-link URL handler registered");
 
-            // 创建动态托盘菜单
-            let menu = create_tray_menu(app.handle(), &app_state)?;
-
-            // 构建托盘
-            let mut tray_builder = TrayIconBuilder::with_id("main")
-                .on_tray_icon_event(|_tray, event| match event {
-                    // 左键点击已通过 show_menu_on_left_click(true) 打开菜单，这里不再额外处理
-                    TrayIconEvent::Click { .. } => {}
-                    _ => log::debug!("unhandled event {event:?}"),
-                })
-                .menu(&menu)
-                .on_menu_event(|app, event| {
-                    handle_tray_menu_event(app, &event.id.0);
-                })
-                .show_menu_on_left_click(true);
-
-            // 统一使用应用默认图标；待托盘模板图标就绪后再启用
-            tray_builder = tray_builder.icon(app.default_window_icon().unwrap().clone());
-
-            let _tray = tray_builder.build(app)?;
+            // 创建动态托盘菜单（守护模式下仅在传入 --tray 时创建）
+            if daemon::tray_enabled() {
+                let menu = create_tray_menu(app.handle(), &app_state)?;
+
+                // 构建托盘
+                let mut tray_builder = TrayIconBuilder::with_id("main")
+                    .on_tray_icon_event(|_tray, event| match event {
+                        // 左键点击已通过 show_menu_on_left_click(true) 打开菜单，这里不再额外处理
+                        TrayIconEvent::Click { .. } => {}
+                        _ => log::debug!("unhandled event {event:?}"),
+                    })
+                    .menu(&menu)
+                    .on_menu_event(|app, event| {
+                        handle_tray_menu_event(app, &event.id.0);
+                    })
+                    .show_menu_on_left_click(true);
+
+                // 统一使用应用默认图标；待托盘模板图标就绪后再启用
+                tray_builder = tray_builder.icon(app.default_window_icon().unwrap().clone());
+
+                let _tray = tray_builder.build(app)?;
+                refresh_tray_icon(app.handle(), &app_state);
+            }
             // 将同一个实例注入到全局状态，避免重复创建导致的不一致
             app.manage(app_state);
 
+            // 注册配置变更事件句柄，此后任意来源的配置保存都会通知前端
+            config_events::init(app.handle().clone());
+
+            // 监听其他 cc-switch 进程（守护进程、CLI、其他机器经同步盘）对 config.json 的修改
+            config_watch::start(app.handle().clone());
+
+            // 启动后台调度器（自动切换规则等）
+            scheduler::start(app.handle().clone());
+
+            // 守护模式：隐藏主窗口，启动 live 配置巡检、故障转移探测与 HTTP 控制接口
+            if daemon::is_daemon() {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+                #[cfg(target_os = "macos")]
+                {
+                    apply_tray_policy(app.handle(), false);
+                }
+            }
+            daemon::start(app.handle().clone());
+
+            // 按设置启动本地反向代理
+            if crate::settings::get_settings().proxy_enabled {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = proxy::start(handle, None).await {
+                        log::error!("启动本地代理失败: {e}");
+                    }
+                });
+            }
+
             // 初始化 SkillService
             match SkillService::new() {
                 Ok(skill_service) => {
@@ -629,20 +740,60 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_providers,
+            commands::get_providers_page,
+            commands::search_providers,
+            commands::compare_providers,
+            commands::global_search,
             commands::get_current_provider,
             commands::add_provider,
             commands::update_provider,
             commands::delete_provider,
             commands::switch_provider,
+            commands::switch_temporarily,
+            commands::get_temporary_switch,
+            commands::cancel_temporary_switch,
             commands::import_default_config,
+            commands::import_all_default_configs,
             commands::get_claude_config_status,
             commands::get_config_status,
             commands::get_claude_code_config_path,
             commands::get_config_dir,
+            commands::get_config_dir_resolution,
+            commands::list_wsl_distros,
+            commands::read_wsl_live_config,
+            commands::list_remote_hosts,
+            commands::save_remote_host,
+            commands::remove_remote_host,
+            commands::test_remote_host,
+            commands::switch_provider_remote,
+            commands::daemon_status,
+            commands::simulate_provider_outage,
+            commands::list_app_adapters,
+            commands::get_adapter_providers,
+            commands::read_adapter_live_config,
+            commands::save_adapter_provider,
+            commands::delete_adapter_provider,
+            commands::switch_adapter_provider,
+            commands::start_proxy,
+            commands::stop_proxy,
+            commands::get_proxy_status,
+            commands::get_proxy_metrics,
+            commands::list_ab_tests,
+            commands::start_ab_test,
+            commands::stop_ab_test,
+            commands::delete_ab_test,
+            commands::get_ab_test_report,
             commands::open_config_folder,
             commands::pick_directory,
             commands::open_external,
             commands::get_init_error,
+            commands::get_config_integrity_report,
+            commands::repair_config,
+            commands::get_recent_logs,
+            commands::open_log_folder,
+            commands::list_crash_reports,
+            commands::clear_crash_reports,
+            commands::report_command_error,
             commands::get_app_config_path,
             commands::open_app_config_folder,
             commands::get_claude_common_config_snippet,
@@ -652,9 +803,12 @@ pub fn run() {
             commands::read_live_provider_settings,
             commands::get_settings,
             commands::save_settings,
+            commands::export_settings,
+            commands::import_settings,
             commands::restart_app,
             commands::check_for_updates,
             commands::is_portable_mode,
+            commands::error_codes,
             commands::get_claude_plugin_status,
             commands::read_claude_plugin_config,
             commands::apply_claude_plugin_config,
@@ -668,6 +822,8 @@ pub fn run() {
             // usage query
             commands::queryProviderUsage,
             commands::testUsageScript,
+            commands::get_usage_script_status,
+            commands::reset_usage_script_status,
             // New MCP via config.json (SSOT)
             commands::get_mcp_config,
             commands::upsert_mcp_server_in_config,
@@ -675,9 +831,18 @@ pub fn run() {
             commands::set_mcp_enabled,
             // v3.7.0: Unified MCP management
             commands::get_mcp_servers,
+            commands::get_mcp_servers_page,
             commands::upsert_mcp_server,
             commands::delete_mcp_server,
+            commands::duplicate_mcp_server,
+            commands::rename_mcp_server,
             commands::toggle_mcp_app,
+            commands::preview_mcp_sync,
+            commands::get_mcp_usage_stats,
+            commands::get_unhealthy_mcp_servers,
+            commands::check_mcp_health,
+            commands::import_mcp_from_project,
+            commands::export_mcp_to_project,
             // Prompt management
             commands::get_prompts,
             commands::upsert_prompt,
@@ -685,8 +850,32 @@ pub fn run() {
             commands::enable_prompt,
             commands::import_prompt_from_file,
             commands::get_current_prompt_file_content,
+            commands::read_global_instructions,
+            commands::write_global_instructions,
+            // Claude Code statusline
+            commands::get_statusline_status,
+            commands::install_statusline,
+            commands::remove_statusline,
+            commands::get_active_provider_for_statusline,
+            // Claude Code hooks
+            commands::list_claude_hooks,
+            commands::add_claude_hook,
+            commands::remove_claude_hook,
+            // Claude Code slash commands
+            commands::get_slash_commands,
+            commands::upsert_slash_command,
+            commands::delete_slash_command,
+            commands::set_slash_command_enabled,
+            commands::import_slash_commands,
             // ours: endpoint speed test + custom endpoint management
             commands::test_api_endpoints,
+            commands::probe_provider_authenticated,
+            commands::verify_provider,
+            commands::sandbox_validate,
+            commands::probe_provider_capabilities,
+            commands::detect_codex_wire_api,
+            commands::fix_codex_wire_api,
+            commands::capture_oauth_snapshot,
             commands::get_custom_endpoints,
             commands::add_custom_endpoint,
             commands::remove_custom_endpoint,
@@ -696,20 +885,38 @@ pub fn run() {
             commands::set_app_config_dir_override,
             // provider sort order management
             commands::update_providers_sort_order,
+            commands::update_sort_order_multi,
+            commands::set_provider_pinned,
+            commands::set_provider_model,
+            commands::get_codex_config_value,
+            commands::set_codex_config_value,
+            // provider custom headers
+            commands::set_provider_headers,
+            commands::set_provider_performance,
+            commands::set_claude_provider_settings,
+            commands::set_provider_extra_env,
             // theirs: config import/export and dialogs
             commands::export_config_to_file,
+            commands::export_config_template,
+            commands::apply_config_template,
             commands::import_config_from_file,
+            commands::import_config_from_url,
+            commands::generate_diagnostics_bundle,
+            commands::share_config_snippet,
+            commands::import_shared_snippet,
             commands::save_file_dialog,
             commands::open_file_dialog,
             commands::sync_current_providers_live,
             // Deep link import
             commands::parse_deeplink,
             commands::import_from_deeplink,
+            commands::generate_deeplink,
             update_tray_menu,
             // Environment variable management
             commands::check_env_conflicts,
             commands::delete_env_vars,
             commands::restore_env_backup,
+            commands::detect_installed_clis,
             // Skill management
             commands::get_skills,
             commands::install_skill,
@@ -717,6 +924,66 @@ pub fn run() {
             commands::get_skill_repos,
             commands::add_skill_repo,
             commands::remove_skill_repo,
+            // Claude Code sub-agents
+            commands::get_sub_agents,
+            commands::save_sub_agent,
+            commands::delete_sub_agent,
+            commands::set_sub_agent_enabled,
+            commands::get_repo_agents,
+            commands::install_repo_agent,
+            commands::get_agent_repos,
+            commands::add_agent_repo,
+            commands::remove_agent_repo,
+            // Auto switch rules
+            commands::list_switch_rules,
+            commands::upsert_switch_rule,
+            commands::delete_switch_rule,
+            commands::set_switch_rules_enabled,
+            commands::switch_to_best_available,
+            commands::rank_providers,
+            commands::get_upcoming_renewals,
+            commands::import_providers_from_csv,
+            commands::list_subscriptions,
+            commands::add_subscription,
+            commands::remove_subscription,
+            commands::list_subscription_presets,
+            commands::refresh_subscriptions,
+            commands::install_subscribed_provider,
+            commands::get_registry,
+            commands::refresh_registries,
+            commands::get_network_status,
+            // App passcode
+            commands::get_passcode_status,
+            commands::set_passcode,
+            commands::unlock_secrets,
+            commands::lock_secrets,
+            // Shell wrappers
+            commands::generate_shell_wrappers,
+            commands::get_env_file_path,
+            commands::get_env_shell_snippet,
+            commands::count_tokens,
+            commands::list_applied_config_migrations,
+            commands::get_config_revision,
+            commands::harden_config_permissions,
+            commands::run_doctor,
+            commands::doctor_fix,
+            commands::list_config_versions,
+            commands::diff_config_versions,
+            commands::restore_config_version,
+            commands::list_backups_detailed,
+            commands::create_live_checkpoint,
+            commands::list_live_checkpoints,
+            commands::restore_live_checkpoint,
+            commands::delete_live_checkpoint,
+            commands::check_external_config_change,
+            commands::reload_external_config,
+            commands::overwrite_external_config,
+            commands::list_managed_files,
+            commands::validate_editor_content,
+            // Team packs
+            commands::create_team_pack,
+            commands::inspect_team_pack,
+            commands::apply_team_pack,
         ]);
 
     let app = builder
@@ -0,0 +1,177 @@
+//! 应用日志：写入 `~/.cc-switch/logs/`，按大小轮转，并提供最近日志读取
+//!
+//! 日志目录固定在用户主目录下，不受 app_config_dir 覆盖影响，保证启动早期即可写入。
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::plugin::TauriPlugin;
+use tauri::Runtime;
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+
+use crate::error::AppError;
+use crate::settings::LogLevel;
+
+/// 日志文件名（不含扩展名），轮转后为 `cc-switch_<时间>.log`
+const LOG_FILE_NAME: &str = "cc-switch";
+/// 单个日志文件最大字节数
+const MAX_LOG_FILE_SIZE: u128 = 5 * 1024 * 1024;
+/// 保留的轮转日志数量
+const MAX_ROTATED_FILES: usize = 5;
+
+/// 单条日志记录
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// 日志目录：`~/.cc-switch/logs`
+pub fn log_dir() -> PathBuf {
+    dirs::home_dir()
+        .expect("无法获取用户主目录")
+        .join(".cc-switch")
+        .join("logs")
+}
+
+/// 构建日志插件（同时输出到终端与日志目录）
+///
+/// 插件以 Trace 级别构建，实际级别通过 `log::set_max_level` 控制，
+/// 以便修改设置后无需重启即可生效。
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_log::Builder::default()
+        .targets([
+            Target::new(TargetKind::Stdout),
+            Target::new(TargetKind::Folder {
+                path: log_dir(),
+                file_name: Some(LOG_FILE_NAME.to_string()),
+            }),
+        ])
+        .level(log::LevelFilter::Trace)
+        .max_file_size(MAX_LOG_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(MAX_ROTATED_FILES))
+        .timezone_strategy(TimezoneStrategy::UseLocal)
+        .build()
+}
+
+/// 应用设置中的日志级别
+pub fn apply_level(level: LogLevel) {
+    log::set_max_level(level.to_level_filter());
+}
+
+/// 读取最近的日志（按时间顺序，最多 `limit` 条），仅返回不低于 `min_level` 严重程度的记录
+pub fn recent_logs(min_level: Option<LogLevel>, limit: usize) -> Result<Vec<LogEntry>, AppError> {
+    let dir = log_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    // 轮转文件名包含时间戳，字典序即时间序；当前文件最新
+    let mut rotated: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| AppError::io(&dir, e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(&format!("{LOG_FILE_NAME}_")) && name.ends_with(".log")
+                })
+        })
+        .collect();
+    rotated.sort();
+    rotated.push(dir.join(format!("{LOG_FILE_NAME}.log")));
+
+    let mut entries: Vec<LogEntry> = Vec::new();
+    // 从最新文件向前读取，够数即停
+    for path in rotated.iter().rev() {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let mut parsed: Vec<LogEntry> = parse_log_lines(&content)
+            .into_iter()
+            .filter(|entry| {
+                min_level.is_none_or(|min| parse_level(&entry.level).is_some_and(|l| l <= min))
+            })
+            .collect();
+        parsed.append(&mut entries);
+        entries = parsed;
+        if entries.len() >= limit {
+            break;
+        }
+    }
+
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.split_off(skip))
+}
+
+fn parse_level(value: &str) -> Option<LogLevel> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "ERROR" => Some(LogLevel::Error),
+        "WARN" => Some(LogLevel::Warn),
+        "INFO" => Some(LogLevel::Info),
+        "DEBUG" => Some(LogLevel::Debug),
+        "TRACE" => Some(LogLevel::Trace),
+        _ => None,
+    }
+}
+
+/// 解析日志插件输出的 `[日期][时间][target][LEVEL] message` 格式，
+/// 不符合格式的行视为上一条记录的续行
+fn parse_log_lines(content: &str) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for line in content.lines() {
+        match parse_log_line(line) {
+            Some(entry) => entries.push(entry),
+            None => {
+                if let Some(last) = entries.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+            }
+        }
+    }
+    entries
+}
+
+fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let mut rest = line;
+    let mut fields = Vec::with_capacity(4);
+    for _ in 0..4 {
+        let inner = rest.strip_prefix('[')?;
+        let end = inner.find(']')?;
+        fields.push(&inner[..end]);
+        rest = &inner[end + 1..];
+    }
+    parse_level(fields[3])?;
+
+    Some(LogEntry {
+        timestamp: format!("{} {}", fields[0], fields[1]),
+        level: fields[3].to_string(),
+        target: fields[2].to_string(),
+        message: rest.strip_prefix(' ').unwrap_or(rest).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plugin_format_with_continuation_lines() {
+        let content = "[2025-01-06][10:00:00][cc_switch_lib][INFO] started\n\
+                       [2025-01-06][10:00:01][cc_switch_lib::services][ERROR] failed: boom\n\
+                       caused by: io\n";
+        let entries = parse_log_lines(content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].timestamp, "2025-01-06 10:00:00");
+        assert_eq!(entries[0].message, "started");
+        assert_eq!(entries[1].level, "ERROR");
+        assert_eq!(entries[1].target, "cc_switch_lib::services");
+        assert_eq!(entries[1].message, "failed: boom\ncaused by: io");
+    }
+}
@@ -0,0 +1,265 @@
+//! cc-switch 会写入的文件清单
+//!
+//! 列出 cc-switch 自身的配置、备份与环境变量文件，以及各应用的 live 配置、凭据与 MCP 配置，
+//! 附带是否存在、大小、修改时间与最后写入者。cc-switch 每次写入这些文件时在
+//! `~/.cc-switch/managed-files.json` 记录内容哈希，内容与记录不同说明之后被其他程序修改过。
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::config::{
+    atomic_write, get_app_config_dir, get_app_config_path, get_claude_credentials_path,
+    get_claude_mcp_path, get_claude_settings_path, sha256_hex,
+};
+use crate::services::{ConfigService, EnvFileService};
+use crate::settings::AppSettings;
+
+/// 串行化本进程内对写入记录的读改写
+static LEDGER_LOCK: Mutex<()> = Mutex::new(());
+
+/// 文件所属
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ManagedFileOwner {
+    CcSwitch,
+    Claude,
+    Codex,
+    Gemini,
+}
+
+/// 文件用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ManagedFileKind {
+    /// cc-switch 的 config.json
+    Config,
+    /// cc-switch 的 settings.json
+    Settings,
+    /// 应用的 live 配置（settings.json、config.toml、.env）
+    Live,
+    /// 登录凭据或 API Key（.credentials.json、auth.json）
+    Credentials,
+    /// MCP 服务器配置
+    Mcp,
+    /// 供终端 `source` 的环境变量文件
+    Env,
+    /// config.json 备份目录
+    Backups,
+}
+
+/// 最后写入者
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LastWriter {
+    /// 内容与 cc-switch 最近一次写入的一致
+    CcSwitch,
+    /// cc-switch 写入后被其他程序修改
+    External,
+    /// 没有 cc-switch 的写入记录（从未写入或记录功能启用之前写入）
+    Unknown,
+}
+
+/// cc-switch 写入文件时记录的内容哈希
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WriteRecord {
+    sha256: String,
+    /// 写入时间（毫秒时间戳）
+    written_at: i64,
+}
+
+/// 清单中的单个文件或目录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedFile {
+    pub path: String,
+    pub owner: ManagedFileOwner,
+    pub kind: ManagedFileKind,
+    pub is_dir: bool,
+    pub exists: bool,
+    /// 字节数（目录为其中文件的总和）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// 修改时间（毫秒时间戳）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_at: Option<i64>,
+    /// 目录不记录写入者
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_writer: Option<LastWriter>,
+    /// cc-switch 最近一次写入时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub written_at: Option<i64>,
+}
+
+fn ledger_path() -> PathBuf {
+    get_app_config_dir().join("managed-files.json")
+}
+
+fn key(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// cc-switch 会写入的文件：`(路径, 所属, 用途, 是否目录)`
+fn targets() -> Vec<(PathBuf, ManagedFileOwner, ManagedFileKind, bool)> {
+    use ManagedFileKind as Kind;
+    use ManagedFileOwner as Owner;
+
+    let app_dir = get_app_config_dir();
+    let mut targets = vec![
+        (get_app_config_path(), Owner::CcSwitch, Kind::Config, false),
+        (
+            AppSettings::settings_path(),
+            Owner::CcSwitch,
+            Kind::Settings,
+            false,
+        ),
+        (
+            app_dir.join("backups"),
+            Owner::CcSwitch,
+            Kind::Backups,
+            true,
+        ),
+    ];
+    for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+        targets.push((
+            EnvFileService::path(&app_type),
+            Owner::CcSwitch,
+            Kind::Env,
+            false,
+        ));
+    }
+    targets.extend([
+        (get_claude_settings_path(), Owner::Claude, Kind::Live, false),
+        (
+            get_claude_credentials_path(),
+            Owner::Claude,
+            Kind::Credentials,
+            false,
+        ),
+        (get_claude_mcp_path(), Owner::Claude, Kind::Mcp, false),
+        (
+            crate::codex_config::get_codex_config_path(),
+            Owner::Codex,
+            Kind::Live,
+            false,
+        ),
+        (
+            crate::codex_config::get_codex_auth_path(),
+            Owner::Codex,
+            Kind::Credentials,
+            false,
+        ),
+        (
+            crate::gemini_config::get_gemini_env_path(),
+            Owner::Gemini,
+            Kind::Live,
+            false,
+        ),
+        (
+            crate::gemini_config::get_gemini_settings_path(),
+            Owner::Gemini,
+            Kind::Mcp,
+            false,
+        ),
+    ]);
+    targets
+}
+
+/// 读取写入记录（每次从磁盘读取，以包含守护进程等其他进程的写入）
+fn read_ledger() -> BTreeMap<String, WriteRecord> {
+    let path = ledger_path();
+    if !path.exists() {
+        return BTreeMap::new();
+    }
+    crate::config::read_json_file(&path).unwrap_or_default()
+}
+
+/// 记录 cc-switch 写入的内容（由原子写入调用，非清单中的文件忽略）
+pub(crate) fn record_write(path: &Path, content: &[u8]) {
+    let is_target = targets()
+        .iter()
+        .any(|(target, _, _, is_dir)| !is_dir && target == path);
+    if !is_target {
+        return;
+    }
+    let record = WriteRecord {
+        sha256: sha256_hex(content),
+        written_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let _guard = LEDGER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut ledger = read_ledger();
+    ledger.insert(key(path), record);
+    let result = serde_json::to_vec_pretty(&ledger)
+        .map_err(|source| crate::error::AppError::JsonSerialize { source })
+        .and_then(|json| atomic_write(&ledger_path(), &json));
+    if let Err(e) = result {
+        log::warn!("记录文件写入失败 {}: {e}", path.display());
+    }
+}
+
+fn last_writer(path: &Path, record: Option<&WriteRecord>) -> LastWriter {
+    let Some(record) = record else {
+        return LastWriter::Unknown;
+    };
+    match std::fs::read(path) {
+        Ok(content) if sha256_hex(&content) == record.sha256 => LastWriter::CcSwitch,
+        _ => LastWriter::External,
+    }
+}
+
+/// 列出 cc-switch 会写入的全部文件
+pub fn list() -> Vec<ManagedFile> {
+    let records = read_ledger();
+    targets()
+        .into_iter()
+        .map(|(path, owner, kind, is_dir)| {
+            let metadata = std::fs::metadata(&path).ok();
+            let modified_at = metadata
+                .as_ref()
+                .and_then(|meta| meta.modified().ok())
+                .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp_millis());
+            let size = match (&metadata, is_dir) {
+                (None, _) => None,
+                (Some(_), true) => Some(ConfigService::backup_usage().1),
+                (Some(meta), false) => Some(meta.len()),
+            };
+            let record = records.get(&key(&path));
+            ManagedFile {
+                path: key(&path),
+                owner,
+                kind,
+                is_dir,
+                exists: metadata.is_some(),
+                size,
+                modified_at,
+                last_writer: (!is_dir && metadata.is_some()).then(|| last_writer(&path, record)),
+                written_at: record.map(|record| record.written_at),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_writer_compares_recorded_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, b"{\"env\":{}}").unwrap();
+        let record = WriteRecord {
+            sha256: sha256_hex(b"{\"env\":{}}"),
+            written_at: 1,
+        };
+
+        assert_eq!(last_writer(&path, None), LastWriter::Unknown);
+        assert_eq!(last_writer(&path, Some(&record)), LastWriter::CcSwitch);
+        std::fs::write(&path, b"{\"env\":{\"A\":\"1\"}}").unwrap();
+        assert_eq!(last_writer(&path, Some(&record)), LastWriter::External);
+    }
+}
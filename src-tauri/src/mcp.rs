@@ -1,11 +1,12 @@
+use serde::Serialize;
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::app_config::{AppType, McpConfig, MultiAppConfig};
 use crate::error::AppError;
 
 /// 基础校验：允许 stdio/http/sse；或省略 type（视为 stdio）。对应必填字段存在
-fn validate_server_spec(spec: &Value) -> Result<(), AppError> {
+pub(crate) fn validate_server_spec(spec: &Value) -> Result<(), AppError> {
     if !spec.is_object() {
         return Err(AppError::McpValidation(
             "MCP 服务器连接定义必须为 JSON 对象".into(),
@@ -47,9 +48,85 @@ fn validate_server_spec(spec: &Value) -> Result<(), AppError> {
             ));
         }
     }
+    if spec
+        .get(PLATFORM_LAUNCHER_KEY)
+        .is_some_and(|value| !value.is_boolean())
+    {
+        return Err(AppError::McpValidation(format!(
+            "MCP 服务器 {PLATFORM_LAUNCHER_KEY} 必须为布尔值"
+        )));
+    }
     Ok(())
 }
 
+/// 服务器定义中的平台启动开关：为 true 时按当前系统调整 stdio 启动命令，写入 live 配置前移除
+pub(crate) const PLATFORM_LAUNCHER_KEY: &str = "platformLauncher";
+
+/// Windows 上以 .cmd 脚本形式安装、需经 `cmd /c` 启动的命令
+const WINDOWS_CMD_SHIMS: &[&str] = &["npx", "npm", "pnpm", "pnpx", "yarn"];
+
+/// 写入 live 配置前按平台调整启动命令（仅对开启 `platformLauncher` 的 stdio 服务器生效）：
+/// Windows 上将 `npx ...` 改写为 `cmd /c npx ...`，其他系统去掉 `cmd /c` 包装，
+/// 使同一份定义在 macOS 与 Windows 上都能启动
+pub(crate) fn apply_platform_launcher(spec: &mut serde_json::Map<String, Value>) {
+    apply_launcher_for(spec, cfg!(windows));
+}
+
+fn apply_launcher_for(spec: &mut serde_json::Map<String, Value>, windows: bool) {
+    let enabled = spec
+        .remove(PLATFORM_LAUNCHER_KEY)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    let is_stdio = spec
+        .get("type")
+        .and_then(Value::as_str)
+        .is_none_or(|t| t == "stdio");
+    if !enabled || !is_stdio {
+        return;
+    }
+    let Some(command) = spec.get("command").and_then(Value::as_str) else {
+        return;
+    };
+    let command = command.trim().to_string();
+    let args = spec
+        .get("args")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let is_cmd = matches!(command.to_ascii_lowercase().as_str(), "cmd" | "cmd.exe");
+    let wrapped = is_cmd
+        && args
+            .first()
+            .and_then(Value::as_str)
+            .is_some_and(|arg| arg.eq_ignore_ascii_case("/c"));
+
+    let (command, args) = if windows {
+        let lower = command.to_ascii_lowercase();
+        let needs_cmd = WINDOWS_CMD_SHIMS.contains(&lower.as_str())
+            || lower.ends_with(".cmd")
+            || lower.ends_with(".bat");
+        if is_cmd || !needs_cmd {
+            return;
+        }
+        let mut wrapped_args = vec![json!("/c"), json!(command)];
+        wrapped_args.extend(args);
+        ("cmd".to_string(), wrapped_args)
+    } else if wrapped {
+        let Some(inner) = args.get(1).and_then(Value::as_str) else {
+            return;
+        };
+        (inner.to_string(), args[2..].to_vec())
+    } else {
+        return;
+    };
+    spec.insert("command".into(), json!(command));
+    if args.is_empty() {
+        spec.remove("args");
+    } else {
+        spec.insert("args".into(), Value::Array(args));
+    }
+}
+
 #[allow(dead_code)] // v3.7.0: 旧的验证逻辑，保留用于未来可能的迁移
 fn validate_mcp_entry(entry: &Value) -> Result<(), AppError> {
     let obj = entry
@@ -388,6 +465,8 @@ pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, AppError
                     homepage: None,
                     docs: None,
                     tags: Vec::new(),
+                    health: None,
+                    overrides: Default::default(),
                 },
             );
             changed += 1;
@@ -599,6 +678,8 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
                         homepage: None,
                         docs: None,
                         tags: Vec::new(),
+                        health: None,
+                        overrides: Default::default(),
                     },
                 );
                 changed += 1;
@@ -636,26 +717,93 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
 /// - 自动清理错误格式：[mcp.servers]（如果存在）
 /// - 读取现有 config.toml；若语法无效则报错，不尝试覆盖
 /// - 仅更新 `mcp_servers` 表，保留其它键
-/// - 仅写入启用项；无启用项时清理 mcp_servers 表
+/// - 仅写入启用项；定义未变化的表原样保留（含用户注释）
+/// - 未启用项默认删除；开启 `codex_mcp_comment_disabled` 后改为注释保留，重新启用时恢复
 pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), AppError> {
-    use toml_edit::{Item, Table};
-
     // 1) 收集启用项（Codex 维度）
     let enabled = collect_enabled_servers(&config.mcp.codex);
 
     // 2) 读取现有 config.toml 文本；保持无效 TOML 的错误返回（不覆盖文件）
     let base_text = crate::codex_config::read_and_validate_codex_config_text()?;
+    let comment_disabled = crate::settings::get_settings().codex_mcp_comment_disabled;
 
-    // 3) 使用 toml_edit 解析（允许空文件）
-    let mut doc = if base_text.trim().is_empty() {
+    // 3) 计算新文本（仅改 TOML，不触碰 auth.json）
+    let new_text = apply_codex_mcp_servers(&base_text, &enabled, comment_disabled)?;
+    let path = crate::codex_config::get_codex_config_path();
+    crate::config::write_text_file(&path, &new_text)?;
+    Ok(())
+}
+
+/// 被注释保留的 MCP 服务器块起止标记（后接服务器 ID）
+const DISABLED_MCP_BEGIN: &str = "# cc-switch:disabled-mcp-begin ";
+const DISABLED_MCP_END: &str = "# cc-switch:disabled-mcp-end ";
+
+/// 将目标服务器集合应用到 Codex config.toml 文本
+fn apply_codex_mcp_servers(
+    text: &str,
+    enabled: &HashMap<String, Value>,
+    comment_disabled: bool,
+) -> Result<String, AppError> {
+    use toml_edit::{Item, Table};
+
+    let (mut doc, mut disabled) = parse_codex_doc(text)?;
+
+    // 不再启用的项：注释保留或直接删除
+    let mut servers_tbl = match doc.as_table_mut().remove("mcp_servers") {
+        Some(Item::Table(table)) => table,
+        _ => Table::new(),
+    };
+    let stale: Vec<String> = servers_tbl
+        .iter()
+        .map(|(id, _)| id.to_string())
+        .filter(|id| !enabled.contains_key(id))
+        .collect();
+    for id in stale {
+        if let Some(item) = servers_tbl.remove(&id) {
+            if comment_disabled {
+                disabled.insert(id.clone(), codex_server_fragment(&id, &item));
+            }
+        }
+    }
+
+    // 启用项：优先沿用现有表或被注释的原文，定义变化时才重新生成
+    let mut ids: Vec<_> = enabled.keys().cloned().collect();
+    ids.sort();
+    for id in ids {
+        let spec = enabled.get(&id).expect("spec must exist");
+        let existing = servers_tbl
+            .remove(&id)
+            .or_else(|| restore_disabled_server(&mut disabled, &id));
+        match choose_codex_server_item(&id, spec, existing) {
+            Ok(item) => {
+                servers_tbl[&id[..]] = item;
+            }
+            Err(err) => {
+                log::error!("跳过无效的 MCP 服务器 '{id}': {err}");
+            }
+        }
+    }
+
+    if !servers_tbl.is_empty() {
+        // 使用唯一正确的格式：[mcp_servers]
+        doc["mcp_servers"] = Item::Table(servers_tbl);
+    }
+
+    Ok(render_codex_doc(&doc, &disabled))
+}
+
+/// 解析 Codex config.toml：分离出被注释的服务器块，并清理错误格式 [mcp.servers]
+fn parse_codex_doc(
+    text: &str,
+) -> Result<(toml_edit::DocumentMut, BTreeMap<String, String>), AppError> {
+    let (rest, disabled) = split_disabled_codex_blocks(text);
+    let mut doc = if rest.trim().is_empty() {
         toml_edit::DocumentMut::default()
     } else {
-        base_text
-            .parse::<toml_edit::DocumentMut>()
-            .map_err(|e| AppError::McpValidation(format!("解析 config.toml 失败: {e}")))?
+        rest.parse::<toml_edit::DocumentMut>()
+            .map_err(|e| AppError::McpValidation(format!("解析 Codex config.toml 失败: {e}")))?
     };
 
-    // 4) 清理可能存在的错误格式 [mcp.servers]
     if let Some(mcp_item) = doc.get_mut("mcp") {
         if let Some(tbl) = mcp_item.as_table_like_mut() {
             if tbl.contains_key("servers") {
@@ -665,36 +813,149 @@ pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), AppError> {
         }
     }
 
-    // 5) 构造目标 servers 表（稳定的键顺序）
-    if enabled.is_empty() {
-        // 无启用项：移除 mcp_servers 表
-        doc.as_table_mut().remove("mcp_servers");
-    } else {
-        // 构建 servers 表
-        let mut servers_tbl = Table::new();
-        let mut ids: Vec<_> = enabled.keys().cloned().collect();
-        ids.sort();
-        for id in ids {
-            let spec = enabled.get(&id).expect("spec must exist");
-            // 复用通用转换函数（已包含扩展字段支持）
-            match json_server_to_toml_table(spec) {
-                Ok(table) => {
-                    servers_tbl[&id[..]] = Item::Table(table);
-                }
-                Err(err) => {
-                    log::error!("跳过无效的 MCP 服务器 '{id}': {err}");
-                }
+    Ok((doc, disabled))
+}
+
+/// 输出文档，并将被注释的服务器块统一追加在文件末尾
+fn render_codex_doc(doc: &toml_edit::DocumentMut, disabled: &BTreeMap<String, String>) -> String {
+    let mut out = doc.to_string();
+    if disabled.is_empty() {
+        return out;
+    }
+
+    let trimmed = out.trim_end().len();
+    out.truncate(trimmed);
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    for (id, fragment) in disabled {
+        out.push_str(&format!("{DISABLED_MCP_BEGIN}{id}\n"));
+        for line in fragment.trim_matches('\n').lines() {
+            if line.is_empty() {
+                out.push_str("#\n");
+            } else {
+                out.push_str(&format!("# {line}\n"));
             }
         }
-        // 使用唯一正确的格式：[mcp_servers]
-        doc["mcp_servers"] = Item::Table(servers_tbl);
+        out.push_str(&format!("{DISABLED_MCP_END}{id}\n"));
+    }
+    out
+}
+
+/// 从文本中分离出被注释的服务器块，返回（剩余文本, ID -> 原始 TOML 片段）
+///
+/// 缺少结束标记的块视为普通注释保留在原处
+fn split_disabled_codex_blocks(text: &str) -> (String, BTreeMap<String, String>) {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut rest: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut disabled = BTreeMap::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        if let Some(id) = lines[i].trim().strip_prefix(DISABLED_MCP_BEGIN.trim_end()) {
+            let id = id.trim();
+            let end_marker = format!("{DISABLED_MCP_END}{id}");
+            if let Some(offset) = lines[i + 1..]
+                .iter()
+                .position(|line| line.trim() == end_marker)
+            {
+                let fragment: Vec<&str> = lines[i + 1..i + 1 + offset]
+                    .iter()
+                    .map(|line| {
+                        let line = line.trim_start();
+                        line.strip_prefix("# ")
+                            .or_else(|| line.strip_prefix('#'))
+                            .unwrap_or(line)
+                    })
+                    .collect();
+                disabled.insert(id.to_string(), fragment.join("\n") + "\n");
+                i += offset + 2;
+                continue;
+            }
+        }
+        rest.push(lines[i]);
+        i += 1;
     }
 
-    // 6) 写回（仅改 TOML，不触碰 auth.json）；toml_edit 会尽量保留未改区域的注释/空白/顺序
-    let new_text = doc.to_string();
-    let path = crate::codex_config::get_codex_config_path();
-    crate::config::write_text_file(&path, &new_text)?;
-    Ok(())
+    let mut rest = rest.join("\n");
+    if disabled.is_empty() {
+        // 没有注释块时保持原文不变
+        return (text.to_string(), disabled);
+    }
+    let trimmed = rest.trim_end().len();
+    rest.truncate(trimmed);
+    if !rest.is_empty() {
+        rest.push('\n');
+    }
+    (rest, disabled)
+}
+
+/// 将单个服务器条目渲染为独立的 `[mcp_servers.<id>]` 片段（保留注释与子表）
+fn codex_server_fragment(id: &str, item: &toml_edit::Item) -> String {
+    let mut parent = toml_edit::Table::new();
+    parent.set_implicit(true);
+    parent.insert(id, item.clone());
+    let mut doc = toml_edit::DocumentMut::new();
+    doc.insert("mcp_servers", toml_edit::Item::Table(parent));
+    doc.to_string()
+}
+
+/// 取出并解析被注释保留的服务器块；片段无法解析时丢弃
+fn restore_disabled_server(
+    disabled: &mut BTreeMap<String, String>,
+    id: &str,
+) -> Option<toml_edit::Item> {
+    let fragment = disabled.remove(id)?;
+    let parsed = fragment
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| log::warn!("无法恢复被注释的 MCP 服务器 '{id}': {e}"))
+        .ok()?;
+    parsed
+        .get("mcp_servers")
+        .and_then(|servers| servers.get(id))
+        .cloned()
+}
+
+/// 现有条目与目标定义一致时原样沿用（保留用户注释），否则按定义重新生成
+fn choose_codex_server_item(
+    id: &str,
+    spec: &Value,
+    existing: Option<toml_edit::Item>,
+) -> Result<toml_edit::Item, AppError> {
+    let rendered = toml_edit::Item::Table(json_server_to_toml_table(spec)?);
+    if let Some(existing) = existing {
+        let as_json = |item: &toml_edit::Item| {
+            codex_servers_as_json(&codex_server_fragment(id, item))
+                .ok()
+                .and_then(|mut servers| servers.remove(id))
+        };
+        if let Some(current) = as_json(&existing) {
+            if Some(current) == as_json(&rendered) {
+                return Ok(existing);
+            }
+        }
+    }
+    Ok(rendered)
+}
+
+/// 按稳定的键顺序构建 Codex `[mcp_servers]` 表，跳过无法转换的条目
+fn build_codex_servers_table(servers: &HashMap<String, Value>) -> toml_edit::Table {
+    let mut servers_tbl = toml_edit::Table::new();
+    let mut ids: Vec<_> = servers.keys().cloned().collect();
+    ids.sort();
+    for id in ids {
+        let spec = servers.get(&id).expect("spec must exist");
+        // 复用通用转换函数（已包含扩展字段支持）
+        match json_server_to_toml_table(spec) {
+            Ok(table) => {
+                servers_tbl[&id[..]] = toml_edit::Item::Table(table);
+            }
+            Err(err) => {
+                log::error!("跳过无效的 MCP 服务器 '{id}': {err}");
+            }
+        }
+    }
+    servers_tbl
 }
 
 /// 将 config.json 中 enabled==true 的项投影写入 ~/.gemini/settings.json
@@ -758,6 +1019,8 @@ pub fn import_from_gemini(config: &mut MultiAppConfig) -> Result<usize, AppError
                     homepage: None,
                     docs: None,
                     tags: Vec::new(),
+                    health: None,
+                    overrides: Default::default(),
                 },
             );
             changed += 1;
@@ -903,6 +1166,12 @@ fn json_value_to_toml_item(value: &Value, field_name: &str) -> Option<toml_edit:
 fn json_server_to_toml_table(spec: &Value) -> Result<toml_edit::Table, AppError> {
     use toml_edit::{Array, Item, Table};
 
+    let mut spec = spec.clone();
+    if let Some(obj) = spec.as_object_mut() {
+        apply_platform_launcher(obj);
+    }
+    let spec = &spec;
+
     let mut t = Table::new();
     let typ = spec.get("type").and_then(|v| v.as_str()).unwrap_or("stdio");
     t["type"] = toml_edit::value(typ);
@@ -1022,56 +1291,55 @@ fn json_server_to_toml_table(spec: &Value) -> Result<toml_edit::Table, AppError>
 
 /// 将单个 MCP 服务器同步到 Codex live 配置
 /// 始终使用 Codex 官方格式 [mcp_servers]，并清理可能存在的错误格式 [mcp.servers]
+/// 若该服务器此前被注释保留，则优先恢复原文（保留用户注释）
 pub fn sync_single_server_to_codex(
     _config: &MultiAppConfig,
     id: &str,
     server_spec: &Value,
 ) -> Result<(), AppError> {
-    use toml_edit::Item;
-
     // 读取现有的 config.toml
     let config_path = crate::codex_config::get_codex_config_path();
-
-    let mut doc = if config_path.exists() {
-        let content =
-            std::fs::read_to_string(&config_path).map_err(|e| AppError::io(&config_path, e))?;
-        content
-            .parse::<toml_edit::DocumentMut>()
-            .map_err(|e| AppError::McpValidation(format!("解析 Codex config.toml 失败: {e}")))?
+    let content = if config_path.exists() {
+        std::fs::read_to_string(&config_path).map_err(|e| AppError::io(&config_path, e))?
     } else {
-        toml_edit::DocumentMut::new()
+        String::new()
     };
-
-    // 清理可能存在的错误格式 [mcp.servers]
-    if let Some(mcp_item) = doc.get_mut("mcp") {
-        if let Some(tbl) = mcp_item.as_table_like_mut() {
-            if tbl.contains_key("servers") {
-                log::warn!("检测到错误的 MCP 格式 [mcp.servers]，正在清理并迁移到 [mcp_servers]");
-                tbl.remove("servers");
-            }
-        }
-    }
+    let (mut doc, mut disabled) = parse_codex_doc(&content)?;
 
     // 确保 [mcp_servers] 表存在
     if !doc.contains_key("mcp_servers") {
         doc["mcp_servers"] = toml_edit::table();
     }
 
-    // 将 JSON 服务器规范转换为 TOML 表
-    let toml_table = json_server_to_toml_table(server_spec)?;
+    let existing = doc["mcp_servers"]
+        .as_table_like_mut()
+        .and_then(|servers| servers.remove(id))
+        .or_else(|| restore_disabled_server(&mut disabled, id));
 
     // 使用唯一正确的格式：[mcp_servers]
-    doc["mcp_servers"][id] = Item::Table(toml_table);
+    doc["mcp_servers"][id] = choose_codex_server_item(id, server_spec, existing)?;
 
     // 写回文件
-    std::fs::write(&config_path, doc.to_string()).map_err(|e| AppError::io(&config_path, e))?;
+    crate::config::write_text_file(&config_path, &render_codex_doc(&doc, &disabled))?;
 
     Ok(())
 }
 
 /// 从 Codex live 配置中移除单个 MCP 服务器
 /// 从正确的 [mcp_servers] 表中删除，同时清理可能存在于错误位置 [mcp.servers] 的数据
+/// 以及被注释保留的同名服务器块
 pub fn remove_server_from_codex(id: &str) -> Result<(), AppError> {
+    update_codex_server_removal(id, false)
+}
+
+/// 在 Codex 中停用单个 MCP 服务器
+/// 开启 `codex_mcp_comment_disabled` 时将其表注释保留，否则直接删除
+pub fn disable_server_in_codex(id: &str) -> Result<(), AppError> {
+    let comment_disabled = crate::settings::get_settings().codex_mcp_comment_disabled;
+    update_codex_server_removal(id, comment_disabled)
+}
+
+fn update_codex_server_removal(id: &str, comment_out: bool) -> Result<(), AppError> {
     let config_path = crate::codex_config::get_codex_config_path();
 
     if !config_path.exists() {
@@ -1081,13 +1349,24 @@ pub fn remove_server_from_codex(id: &str) -> Result<(), AppError> {
     let content =
         std::fs::read_to_string(&config_path).map_err(|e| AppError::io(&config_path, e))?;
 
-    let mut doc = content
+    let (rest, mut disabled) = split_disabled_codex_blocks(&content);
+    let mut doc = rest
         .parse::<toml_edit::DocumentMut>()
         .map_err(|e| AppError::McpValidation(format!("解析 Codex config.toml 失败: {e}")))?;
 
     // 从正确的位置删除：[mcp_servers]
-    if let Some(mcp_servers) = doc.get_mut("mcp_servers").and_then(|s| s.as_table_mut()) {
-        mcp_servers.remove(id);
+    let removed = doc
+        .get_mut("mcp_servers")
+        .and_then(|s| s.as_table_like_mut())
+        .and_then(|servers| servers.remove(id));
+    match removed {
+        Some(item) if comment_out => {
+            disabled.insert(id.to_string(), codex_server_fragment(id, &item));
+        }
+        _ if !comment_out => {
+            disabled.remove(id);
+        }
+        _ => {}
     }
 
     // 同时清理可能存在于错误位置的数据：[mcp.servers]（如果存在）
@@ -1100,7 +1379,7 @@ pub fn remove_server_from_codex(id: &str) -> Result<(), AppError> {
     }
 
     // 写回文件
-    std::fs::write(&config_path, doc.to_string()).map_err(|e| AppError::io(&config_path, e))?;
+    crate::config::write_text_file(&config_path, &render_codex_doc(&doc, &disabled))?;
 
     Ok(())
 }
@@ -1133,3 +1412,214 @@ pub fn remove_server_from_gemini(id: &str) -> Result<(), AppError> {
     // 写回
     crate::gemini_mcp::set_mcp_servers_map(&current)
 }
+
+/// MCP 同步预览：对比目标服务器集合与 live 配置，不写入任何文件
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSyncPreview {
+    pub app: String,
+    /// 将新增的服务器 ID
+    pub added: Vec<String>,
+    /// 将从 live 配置中移除的服务器 ID
+    pub removed: Vec<String>,
+    /// 定义将被覆盖的服务器 ID
+    pub modified: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// 同步后写入的片段（Claude/Gemini 为 `mcpServers` JSON，Codex 为 `[mcp_servers]` TOML）
+    pub snippet: String,
+}
+
+/// 收集指定应用的目标 MCP 服务器（统一结构优先，兼容旧的分应用结构）
+fn target_servers_for(config: &MultiAppConfig, app: &AppType) -> HashMap<String, Value> {
+    match &config.mcp.servers {
+        Some(servers) => servers
+            .values()
+            .filter(|server| server.apps.is_enabled_for(app))
+            .map(|server| (server.id.clone(), server.spec_for(app)))
+            .collect(),
+        None => collect_enabled_servers(config.mcp_for(app)),
+    }
+}
+
+/// 将 TOML `[mcp_servers]` 表转换为 JSON 映射，便于与目标集合按值比较
+fn codex_servers_as_json(text: &str) -> Result<HashMap<String, Value>, AppError> {
+    if text.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+    let root: toml::Table = toml::from_str(text)
+        .map_err(|e| AppError::McpValidation(format!("解析 ~/.codex/config.toml 失败: {e}")))?;
+    let Some(servers) = root.get("mcp_servers").and_then(|v| v.as_table()) else {
+        return Ok(HashMap::new());
+    };
+    servers
+        .iter()
+        .map(|(id, entry)| {
+            serde_json::to_value(entry)
+                .map(|value| (id.clone(), value))
+                .map_err(|e| AppError::JsonSerialize { source: e })
+        })
+        .collect()
+}
+
+/// 预览 `sync_enabled_to_claude/codex/gemini` 将对 live 配置造成的变更（dry-run）
+pub fn preview_sync_for(
+    config: &MultiAppConfig,
+    app: &AppType,
+) -> Result<McpSyncPreview, AppError> {
+    let target = target_servers_for(config, app);
+
+    let (live, expected, snippet) = match app {
+        AppType::Claude | AppType::Gemini => {
+            let live = match app {
+                AppType::Claude => crate::claude_mcp::read_mcp_servers_map()?,
+                _ => crate::gemini_mcp::read_mcp_servers_map()?,
+            };
+            // 与写入时一致地按平台调整启动命令，避免误报为已修改
+            let mut target = target.clone();
+            for spec in target.values_mut() {
+                if let Some(obj) = spec.as_object_mut() {
+                    apply_platform_launcher(obj);
+                }
+            }
+            let sorted: BTreeMap<&String, &Value> = target.iter().collect();
+            let snippet = serde_json::to_string_pretty(&json!({ "mcpServers": sorted }))
+                .map_err(|e| AppError::JsonSerialize { source: e })?;
+            (live, target, snippet)
+        }
+        AppType::Codex => {
+            let live_text = crate::codex_config::read_and_validate_codex_config_text()?;
+            let live = codex_servers_as_json(&live_text)?;
+
+            let mut doc = toml_edit::DocumentMut::new();
+            if !target.is_empty() {
+                doc["mcp_servers"] = toml_edit::Item::Table(build_codex_servers_table(&target));
+            }
+            let snippet = doc.to_string();
+            // 以渲染后的 TOML 作为比较基准，避免 JSON/TOML 表示差异造成误报
+            let expected = codex_servers_as_json(&snippet)?;
+            (live, expected, snippet)
+        }
+    };
+
+    let mut preview = McpSyncPreview {
+        app: app.as_str().to_string(),
+        snippet,
+        ..Default::default()
+    };
+    for (id, spec) in &expected {
+        match live.get(id) {
+            None => preview.added.push(id.clone()),
+            Some(current) if current != spec => preview.modified.push(id.clone()),
+            Some(_) => preview.unchanged.push(id.clone()),
+        }
+    }
+    preview.removed = live
+        .keys()
+        .filter(|id| !expected.contains_key(*id))
+        .cloned()
+        .collect();
+
+    preview.added.sort();
+    preview.removed.sort();
+    preview.modified.sort();
+    preview.unchanged.sort();
+    Ok(preview)
+}
+
+/// 按目标服务器集合整体重写指定应用的 live MCP 配置（移除未由 CC Switch 管理的条目）
+pub fn sync_target_to_live(config: &MultiAppConfig, app: &AppType) -> Result<(), AppError> {
+    let target = target_servers_for(config, app);
+    match app {
+        AppType::Claude => crate::claude_mcp::set_mcp_servers_map(&target),
+        AppType::Gemini => crate::gemini_mcp::set_mcp_servers_map(&target),
+        AppType::Codex => {
+            let base_text = crate::codex_config::read_and_validate_codex_config_text()?;
+            let comment_disabled = crate::settings::get_settings().codex_mcp_comment_disabled;
+            let new_text = apply_codex_mcp_servers(&base_text, &target, comment_disabled)?;
+            let path = crate::codex_config::get_codex_config_path();
+            crate::config::write_text_file(&path, &new_text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_codex_servers_are_commented_and_restored_with_comments() {
+        let text = "model = \"gpt-5\"\n\n\
+                    # my notes about echo\n\
+                    [mcp_servers.echo]\n\
+                    type = \"stdio\"\n\
+                    command = \"echo\" # keep this inline comment\n";
+        let echo = json!({ "type": "stdio", "command": "echo" });
+
+        let disabled = apply_codex_mcp_servers(text, &HashMap::new(), true).expect("disable");
+        assert!(disabled.starts_with("model = \"gpt-5\"\n"));
+        assert!(disabled.contains(&format!("{DISABLED_MCP_BEGIN}echo")));
+        assert!(disabled.contains("# command = \"echo\" # keep this inline comment"));
+        let parsed: toml::Table = toml::from_str(&disabled).expect("still valid toml");
+        assert!(!parsed.contains_key("mcp_servers"));
+
+        let enabled = HashMap::from([("echo".to_string(), echo)]);
+        let restored = apply_codex_mcp_servers(&disabled, &enabled, true).expect("re-enable");
+        assert!(!restored.contains(DISABLED_MCP_BEGIN.trim_end()));
+        assert!(restored.contains("# my notes about echo"));
+        assert!(restored.contains("command = \"echo\" # keep this inline comment"));
+    }
+
+    #[test]
+    fn remove_mode_drops_disabled_servers_and_changed_specs_are_regenerated() {
+        let text = "[mcp_servers.echo]\ncommand = \"echo\" # old\n";
+
+        let removed = apply_codex_mcp_servers(text, &HashMap::new(), false).expect("remove");
+        assert!(!removed.contains("echo"));
+
+        let enabled = HashMap::from([(
+            "echo".to_string(),
+            json!({ "type": "stdio", "command": "echo-v2" }),
+        )]);
+        let updated = apply_codex_mcp_servers(text, &enabled, false).expect("update");
+        assert!(updated.contains("command = \"echo-v2\""));
+        assert!(!updated.contains("# old"));
+    }
+
+    #[test]
+    fn platform_launcher_wraps_npx_on_windows_only_when_enabled() {
+        let spec = json!({
+            "command": "npx",
+            "args": ["-y", "@modelcontextprotocol/server-filesystem"],
+            "platformLauncher": true,
+        });
+
+        let mut windows = spec.as_object().cloned().unwrap();
+        apply_launcher_for(&mut windows, true);
+        assert_eq!(windows["command"], "cmd");
+        assert_eq!(
+            windows["args"],
+            json!(["/c", "npx", "-y", "@modelcontextprotocol/server-filesystem"])
+        );
+        assert!(!windows.contains_key(PLATFORM_LAUNCHER_KEY));
+
+        // 已包装的定义在 macOS / Linux 上还原为直接启动
+        let mut unix = windows.clone();
+        unix.insert(PLATFORM_LAUNCHER_KEY.into(), json!(true));
+        apply_launcher_for(&mut unix, false);
+        assert_eq!(Value::Object(unix), {
+            let mut expected = spec.clone();
+            expected
+                .as_object_mut()
+                .unwrap()
+                .remove(PLATFORM_LAUNCHER_KEY);
+            expected
+        });
+
+        let mut untouched = json!({ "command": "npx", "args": ["-y", "pkg"] })
+            .as_object()
+            .cloned()
+            .unwrap();
+        apply_launcher_for(&mut untouched, true);
+        assert_eq!(untouched["command"], "npx");
+    }
+}
@@ -0,0 +1,269 @@
+//! 网络环境检测（Wi-Fi SSID / 默认网关 / 活动网络接口）
+//!
+//! 供自动切换规则使用：例如连接公司 VPN 时切换到内部代理供应商。
+//! 检测失败时对应字段为空，不会返回错误。
+
+use serde::Serialize;
+use std::process::Command;
+
+/// 常见 VPN 隧道接口名前缀
+const VPN_INTERFACE_PREFIXES: [&str; 7] = ["tun", "tap", "wg", "ppp", "ipsec", "utun", "zt"];
+
+/// 当前网络环境快照
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSnapshot {
+    /// 当前连接的 Wi-Fi 名称
+    pub ssid: Option<String>,
+    /// 默认网关地址
+    pub gateway: Option<String>,
+    /// 处于启用状态的网络接口名称
+    pub interfaces: Vec<String>,
+    /// 是否检测到 VPN 隧道接口（启发式）
+    pub vpn_active: bool,
+}
+
+impl NetworkSnapshot {
+    /// 是否存在以指定前缀开头的活动接口（大小写不敏感）
+    pub fn has_interface(&self, prefix: &str) -> bool {
+        let prefix = prefix.trim().to_lowercase();
+        !prefix.is_empty()
+            && self
+                .interfaces
+                .iter()
+                .any(|name| name.to_lowercase().starts_with(&prefix))
+    }
+}
+
+/// 检测当前网络环境
+pub fn detect() -> NetworkSnapshot {
+    let interfaces = detect_interfaces();
+    let vpn_active = interfaces.iter().any(|name| is_vpn_interface(name));
+    NetworkSnapshot {
+        ssid: detect_ssid(),
+        gateway: detect_gateway(),
+        interfaces,
+        vpn_active,
+    }
+}
+
+fn is_vpn_interface(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    // macOS 系统自带 utun0-utun3（iCloud 私有中继等），不视为 VPN
+    if let Some(index) = lower.strip_prefix("utun") {
+        return index.parse::<u32>().map(|i| i >= 4).unwrap_or(false);
+    }
+    VPN_INTERFACE_PREFIXES
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+        || lower.contains("vpn")
+        || lower.contains("wireguard")
+}
+
+fn run_command(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn detect_ssid() -> Option<String> {
+    let output = run_command("networksetup", &["-getairportnetwork", "en0"])?;
+    output
+        .lines()
+        .find_map(|line| line.split_once("Current Wi-Fi Network:"))
+        .and_then(|(_, ssid)| non_empty(ssid))
+}
+
+#[cfg(target_os = "linux")]
+fn detect_ssid() -> Option<String> {
+    if let Some(output) = run_command("iwgetid", &["-r"]) {
+        if let Some(ssid) = non_empty(&output) {
+            return Some(ssid);
+        }
+    }
+    let output = run_command("nmcli", &["-t", "-f", "active,ssid", "dev", "wifi"])?;
+    parse_nmcli_active_ssid(&output)
+}
+
+#[cfg(target_os = "windows")]
+fn detect_ssid() -> Option<String> {
+    let output = run_command("netsh", &["wlan", "show", "interfaces"])?;
+    parse_netsh_ssid(&output)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn detect_ssid() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn detect_gateway() -> Option<String> {
+    let output = run_command("route", &["-n", "get", "default"])?;
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("gateway:"))
+        .and_then(non_empty)
+}
+
+#[cfg(target_os = "linux")]
+fn detect_gateway() -> Option<String> {
+    let content = std::fs::read_to_string("/proc/net/route").ok()?;
+    parse_proc_net_route_gateway(&content)
+}
+
+#[cfg(target_os = "windows")]
+fn detect_gateway() -> Option<String> {
+    let output = run_command("ipconfig", &[])?;
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(" : ")?;
+        if key.trim_start().starts_with("Default Gateway") {
+            non_empty(value)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn detect_gateway() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn detect_interfaces() -> Vec<String> {
+    run_command("ifconfig", &["-lu"])
+        .map(|output| output.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+fn detect_interfaces() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| {
+            // tun/wg 等虚拟接口的 operstate 通常为 unknown
+            let state = std::fs::read_to_string(entry.path().join("operstate")).unwrap_or_default();
+            matches!(state.trim(), "up" | "unknown")
+        })
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name != "lo")
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(target_os = "windows")]
+fn detect_interfaces() -> Vec<String> {
+    // 输出格式：Admin State  State  Type  Interface Name
+    run_command("netsh", &["interface", "show", "interface"])
+        .map(|output| {
+            output
+                .lines()
+                .filter_map(|line| {
+                    let columns: Vec<&str> = line.split_whitespace().collect();
+                    if columns.len() >= 4 && columns[1].eq_ignore_ascii_case("Connected") {
+                        Some(columns[3..].join(" "))
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn detect_interfaces() -> Vec<String> {
+    Vec::new()
+}
+
+/// 解析 `nmcli -t -f active,ssid dev wifi` 输出中处于活动状态的 SSID
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nmcli_active_ssid(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("yes:"))
+        .map(|ssid| ssid.replace("\\:", ":"))
+        .and_then(|ssid| non_empty(&ssid))
+}
+
+/// 解析 `netsh wlan show interfaces` 输出中的 SSID（忽略 BSSID 行）
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_netsh_ssid(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if key.trim() == "SSID" {
+            non_empty(value)
+        } else {
+            None
+        }
+    })
+}
+
+/// 解析 `/proc/net/route` 中默认路由的网关（小端十六进制）
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_net_route_gateway(content: &str) -> Option<String> {
+    content.lines().skip(1).find_map(|line| {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() < 3 || columns[1] != "00000000" {
+            return None;
+        }
+        let raw = u32::from_str_radix(columns[2], 16).ok()?;
+        let [a, b, c, d] = raw.to_le_bytes();
+        Some(format!("{a}.{b}.{c}.{d}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_net_route_default_gateway() {
+        let content = "Iface\tDestination\tGateway \tFlags\n\
+                       eth0\t0000A8C0\t00000000\t0001\n\
+                       eth0\t00000000\t0101A8C0\t0003\n";
+        assert_eq!(
+            parse_proc_net_route_gateway(content).as_deref(),
+            Some("192.168.1.1")
+        );
+    }
+
+    #[test]
+    fn parses_ssid_outputs() {
+        assert_eq!(
+            parse_nmcli_active_ssid("no:Guest\nyes:Corp\\:5G\n").as_deref(),
+            Some("Corp:5G")
+        );
+        let netsh = "    Name                   : Wi-Fi\n    SSID                   : Office\n    BSSID                  : aa:bb\n";
+        assert_eq!(parse_netsh_ssid(netsh).as_deref(), Some("Office"));
+    }
+
+    #[test]
+    fn detects_vpn_interfaces_heuristically() {
+        assert!(is_vpn_interface("wg0"));
+        assert!(is_vpn_interface("utun5"));
+        assert!(!is_vpn_interface("utun1"));
+        assert!(!is_vpn_interface("en0"));
+    }
+}
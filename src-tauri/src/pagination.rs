@@ -0,0 +1,117 @@
+//! 列表分页与字段投影
+//!
+//! 列表视图只需要名称、分类等少量字段；完整的 `settingsConfig` / MCP `server`
+//! 定义体积大且包含密钥，默认不返回，需要时通过 `fields` 显式请求。
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+
+/// 未指定 `limit` 时的默认条数
+pub const DEFAULT_PAGE_LIMIT: usize = 50;
+
+/// 单页最大条数
+pub const MAX_PAGE_LIMIT: usize = 200;
+
+/// 分页结果（`items` 为按字段投影后的 JSON 对象，始终包含 `id`）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page {
+    pub items: Vec<Value>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub has_more: bool,
+}
+
+/// 对已排序的条目分页并投影字段
+///
+/// - `fields` 为空时返回除 `default_excluded` 之外的全部字段
+/// - `limit` 会被限制在 1..=[`MAX_PAGE_LIMIT`]
+pub fn paginate<'a, T: Serialize + 'a>(
+    items: impl ExactSizeIterator<Item = &'a T>,
+    offset: usize,
+    limit: usize,
+    fields: Option<&[String]>,
+    default_excluded: &[&str],
+) -> Result<Page, AppError> {
+    let total = items.len();
+    let limit = limit.clamp(1, MAX_PAGE_LIMIT);
+    let items = items
+        .skip(offset)
+        .take(limit)
+        .map(|item| {
+            let value =
+                serde_json::to_value(item).map_err(|source| AppError::JsonSerialize { source })?;
+            Ok(project(value, fields, default_excluded))
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    Ok(Page {
+        items,
+        total,
+        offset,
+        limit,
+        has_more: offset.saturating_add(limit) < total,
+    })
+}
+
+fn project(value: Value, fields: Option<&[String]>, default_excluded: &[&str]) -> Value {
+    let Value::Object(map) = value else {
+        return value;
+    };
+    let projected: Map<String, Value> = match fields {
+        Some(fields) if !fields.is_empty() => map
+            .into_iter()
+            .filter(|(key, _)| key == "id" || fields.iter().any(|field| field == key))
+            .collect(),
+        _ => map
+            .into_iter()
+            .filter(|(key, _)| !default_excluded.contains(&key.as_str()))
+            .collect(),
+    };
+    Value::Object(projected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn paginates_and_projects_fields() {
+        let items: Vec<Value> = (0..5)
+            .map(|i| {
+                json!({
+                    "id": format!("p{i}"),
+                    "name": format!("P{i}"),
+                    "settingsConfig": { "secret": i }
+                })
+            })
+            .collect();
+
+        let page = paginate(items.iter(), 1, 2, None, &["settingsConfig"]).unwrap();
+        assert_eq!(page.total, 5);
+        assert!(page.has_more);
+        assert_eq!(
+            page.items,
+            vec![
+                json!({ "id": "p1", "name": "P1" }),
+                json!({ "id": "p2", "name": "P2" })
+            ]
+        );
+
+        let fields = vec!["settingsConfig".to_string()];
+        let last = paginate(items.iter(), 4, 10, Some(&fields), &["settingsConfig"]).unwrap();
+        assert!(!last.has_more);
+        assert_eq!(
+            last.items,
+            vec![json!({ "id": "p4", "settingsConfig": { "secret": 4 } })]
+        );
+
+        let empty = paginate(items.iter(), 10, 0, None, &[]).unwrap();
+        assert!(empty.items.is_empty());
+        assert_eq!(empty.limit, 1);
+    }
+}
@@ -0,0 +1,213 @@
+//! 供应商配置中的占位符
+//!
+//! `settings_config` 中的字符串可以包含 `${HOME}`、`${USER}`、`${hostname}`、`${env:NAME}`，
+//! 仅在写入 live 配置时展开，使同一份导出的配置可以在用户名、路径不同的机器上复用。
+//! 未识别的 `${...}` 原样保留（如 statusLine 命令中的 shell 变量），`$${` 表示字面量 `${`。
+//! 回填 live 配置到供应商快照时，再把展开后的值还原为占位符。
+
+use serde_json::Value;
+
+use crate::error::AppError;
+
+/// 展开 JSON 中所有字符串值里的占位符
+pub fn expand_value(value: &Value) -> Result<Value, AppError> {
+    map_strings(value, &|text| expand_str(text, &resolve))
+}
+
+/// 回填快照时保留模板中的占位符：展开结果与 live 值一致的字符串（或多行文本中的行）还原为模板写法
+pub fn restore_value(template: &Value, live: &mut Value) {
+    restore_with(template, live, &|text| expand_str(text, &resolve).ok())
+}
+
+fn restore_with(template: &Value, live: &mut Value, expand: &dyn Fn(&str) -> Option<String>) {
+    match (template, live) {
+        (Value::Object(template), Value::Object(live)) => {
+            for (key, child) in live.iter_mut() {
+                if let Some(original) = template.get(key) {
+                    restore_with(original, child, expand);
+                }
+            }
+        }
+        (Value::Array(template), Value::Array(live)) => {
+            for (original, child) in template.iter().zip(live.iter_mut()) {
+                restore_with(original, child, expand);
+            }
+        }
+        (Value::String(template), Value::String(live)) if template.contains("${") => {
+            if expand(template).as_deref() == Some(live.as_str()) {
+                *live = template.clone();
+                return;
+            }
+            // 多行文本（如 Codex config.toml）写入时可能追加了其他内容，按行还原
+            if !template.contains('\n') {
+                return;
+            }
+            let lines: Vec<(String, &str)> = template
+                .lines()
+                .filter(|line| line.contains("${"))
+                .filter_map(|line| Some((expand(line)?, line)))
+                .collect();
+            let restored: Vec<&str> = live
+                .split('\n')
+                .map(|line| {
+                    lines
+                        .iter()
+                        .find(|(expanded, _)| expanded == line)
+                        .map_or(line, |(_, original)| original)
+                })
+                .collect();
+            *live = restored.join("\n");
+        }
+        _ => {}
+    }
+}
+
+fn map_strings(
+    value: &Value,
+    f: &dyn Fn(&str) -> Result<String, AppError>,
+) -> Result<Value, AppError> {
+    Ok(match value {
+        Value::String(text) => Value::String(f(text)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| map_strings(item, f))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, child)| Ok((key.clone(), map_strings(child, f)?)))
+                .collect::<Result<_, AppError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// 展开单个字符串；`resolve` 返回 `Ok(None)` 表示未识别的占位符（原样保留）
+fn expand_str(
+    text: &str,
+    resolve: &dyn Fn(&str) -> Result<Option<String>, AppError>,
+) -> Result<String, AppError> {
+    if !text.contains("${") {
+        return Ok(text.to_string());
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(escaped) = tail.strip_prefix("$${") {
+            out.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let Some(body) = tail.strip_prefix("${") else {
+            out.push('$');
+            rest = &tail[1..];
+            continue;
+        };
+        let Some(end) = body.find('}') else {
+            out.push_str(tail);
+            rest = "";
+            break;
+        };
+        match resolve(&body[..end])? {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&tail[..end + 3]),
+        }
+        rest = &body[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn resolve(name: &str) -> Result<Option<String>, AppError> {
+    if let Some(var) = name.strip_prefix("env:") {
+        return std::env::var(var).map(Some).map_err(|_| {
+            AppError::localized(
+                "placeholder.env_missing",
+                format!("配置中引用的环境变量未设置: {var}"),
+                format!("Environment variable referenced in config is not set: {var}"),
+            )
+        });
+    }
+    let value = match name {
+        "HOME" => dirs::home_dir().map(|home| home.to_string_lossy().to_string()),
+        "USER" => std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok(),
+        "hostname" => hostname(),
+        _ => return Ok(None),
+    };
+    value.map(Some).ok_or_else(|| {
+        AppError::localized(
+            "placeholder.unresolved",
+            format!("无法解析配置占位符: ${{{name}}}"),
+            format!("Unable to resolve config placeholder: ${{{name}}}"),
+        )
+    })
+}
+
+pub(crate) fn hostname() -> Option<String> {
+    let from_env = std::env::var("COMPUTERNAME").ok();
+    let from_file = || {
+        std::fs::read_to_string("/etc/hostname")
+            .ok()
+            .map(|text| text.trim().to_string())
+    };
+    let from_command = || {
+        let output = std::process::Command::new("hostname").output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    from_env
+        .or_else(from_file)
+        .or_else(from_command)
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fake(name: &str) -> Result<Option<String>, AppError> {
+        Ok(match name {
+            "HOME" => Some("/home/alice".to_string()),
+            "env:TEAM" => Some("infra".to_string()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn expands_known_placeholders_and_keeps_others() {
+        assert_eq!(
+            expand_str("${HOME}/bin:${PATH} $${HOME} ${env:TEAM} $5", &fake).unwrap(),
+            "/home/alice/bin:${PATH} ${HOME} infra $5"
+        );
+        assert_eq!(expand_str("${HOME", &fake).unwrap(), "${HOME");
+    }
+
+    #[test]
+    fn restore_keeps_template_for_unchanged_values() {
+        let expand = |text: &str| expand_str(text, &fake).ok();
+        let template = json!({
+            "env": { "NODE_EXTRA_CA_CERTS": "${HOME}/ca.pem" },
+            "config": "model = \"o3\"\ncwd = \"${HOME}/work\""
+        });
+        let mut live = json!({
+            "env": { "NODE_EXTRA_CA_CERTS": "/home/alice/ca.pem", "NEW": "1" },
+            "config": "model = \"o3\"\ncwd = \"/home/alice/work\"\n\n[extra]\nx = 1"
+        });
+        restore_with(&template, &mut live, &expand);
+        assert_eq!(live["env"]["NODE_EXTRA_CA_CERTS"], "${HOME}/ca.pem");
+        assert_eq!(live["env"]["NEW"], "1");
+        assert_eq!(
+            live["config"],
+            "model = \"o3\"\ncwd = \"${HOME}/work\"\n\n[extra]\nx = 1"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! 项目级 `.mcp.json`（Claude Code 在项目根目录读取）的导入与导出
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::app_config::{AppType, McpApps, McpServer};
+use crate::error::AppError;
+use crate::services::passcode::is_secret_key;
+
+/// 项目级 MCP 配置文件名
+pub const PROJECT_MCP_FILE: &str = ".mcp.json";
+
+/// 统一结构中仅供界面使用、不属于连接定义的字段
+const UI_FIELDS: [&str; 8] = [
+    "enabled",
+    "source",
+    "id",
+    "name",
+    "description",
+    "tags",
+    "homepage",
+    "docs",
+];
+
+/// 从项目导入 MCP 服务器的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectMcpImport {
+    pub file: String,
+    /// 添加到导入服务器上的标签（`project:<目录名>`）
+    pub tag: String,
+    /// 新增的服务器
+    pub imported: Vec<String>,
+    /// 已存在且定义相同、仅追加了项目标签的服务器
+    pub tagged: Vec<String>,
+    /// 已存在但定义不同的服务器（保留本地定义，未导入）
+    pub conflicts: Vec<String>,
+    /// 定义无效而跳过的服务器
+    pub invalid: Vec<String>,
+}
+
+/// `path` 可以是项目目录，也可以是 `.mcp.json` 文件本身
+pub fn project_file(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join(PROJECT_MCP_FILE)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// 项目标签：`project:<项目目录名>`
+pub fn project_tag(file: &Path) -> String {
+    let name = file
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    format!("project:{name}")
+}
+
+/// 将 `.mcp.json` 内容中的服务器合并到统一结构（新服务器默认不启用任何应用）
+pub fn merge_into(
+    servers: &mut HashMap<String, McpServer>,
+    content: &str,
+    tag: &str,
+) -> Result<ProjectMcpImport, AppError> {
+    let root: Value = serde_json::from_str(content)
+        .map_err(|e| AppError::McpValidation(format!("解析 {PROJECT_MCP_FILE} 失败: {e}")))?;
+    let Some(map) = root.get("mcpServers").and_then(Value::as_object) else {
+        return Err(AppError::McpValidation(format!(
+            "{PROJECT_MCP_FILE} 缺少 mcpServers 对象"
+        )));
+    };
+
+    let mut result = ProjectMcpImport {
+        tag: tag.to_string(),
+        ..Default::default()
+    };
+    for (id, spec) in map {
+        if let Err(e) = crate::mcp::validate_server_spec(spec) {
+            log::warn!("跳过无效的项目 MCP 服务器 '{id}': {e}");
+            result.invalid.push(id.clone());
+            continue;
+        }
+        match servers.get_mut(id) {
+            Some(existing) if existing.server == *spec => {
+                if !existing.tags.iter().any(|t| t == tag) {
+                    existing.tags.push(tag.to_string());
+                }
+                result.tagged.push(id.clone());
+            }
+            Some(_) => result.conflicts.push(id.clone()),
+            None => {
+                servers.insert(
+                    id.clone(),
+                    McpServer {
+                        id: id.clone(),
+                        name: id.clone(),
+                        server: spec.clone(),
+                        apps: McpApps::default(),
+                        description: None,
+                        homepage: None,
+                        docs: None,
+                        tags: vec![tag.to_string()],
+                        health: None,
+                        overrides: Default::default(),
+                    },
+                );
+                result.imported.push(id.clone());
+            }
+        }
+    }
+    for list in [
+        &mut result.imported,
+        &mut result.tagged,
+        &mut result.conflicts,
+        &mut result.invalid,
+    ] {
+        list.sort();
+    }
+    Ok(result)
+}
+
+/// 生成可提交到仓库的 `.mcp.json`：去掉界面字段，密钥类环境变量改为 `${变量名}` 引用
+pub fn render(servers: &[&McpServer]) -> Value {
+    let mut out = Map::new();
+    for server in servers {
+        let mut spec = server
+            .spec_for(&AppType::Claude)
+            .as_object()
+            .cloned()
+            .unwrap_or_default();
+        for field in UI_FIELDS {
+            spec.remove(field);
+        }
+        // 仓库内的 .mcp.json 跨平台共享，保持定义原样，只去掉开关
+        spec.remove(crate::mcp::PLATFORM_LAUNCHER_KEY);
+        if let Some(env) = spec.get_mut("env").and_then(Value::as_object_mut) {
+            for (key, value) in env.iter_mut() {
+                if is_secret_key(key) && value.is_string() {
+                    *value = Value::String(format!("${{{key}}}"));
+                }
+            }
+        }
+        out.insert(server.id.clone(), Value::Object(spec));
+    }
+    json!({ "mcpServers": out })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, spec: Value) -> McpServer {
+        McpServer {
+            id: id.into(),
+            name: id.into(),
+            server: spec,
+            apps: McpApps::default(),
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+            health: None,
+            overrides: Default::default(),
+        }
+    }
+
+    #[test]
+    fn merges_and_renders_project_servers() {
+        let mut servers = HashMap::new();
+        servers.insert("same".into(), server("same", json!({ "command": "npx" })));
+        servers.insert("local".into(), server("local", json!({ "command": "uvx" })));
+        let content = json!({
+            "mcpServers": {
+                "same": { "command": "npx" },
+                "local": { "command": "node" },
+                "fresh": { "type": "http", "url": "https://example.com/mcp" },
+                "broken": { "type": "stdio" }
+            }
+        })
+        .to_string();
+
+        let result = merge_into(&mut servers, &content, "project:demo").unwrap();
+        assert_eq!(result.imported, vec!["fresh"]);
+        assert_eq!(result.tagged, vec!["same"]);
+        assert_eq!(result.conflicts, vec!["local"]);
+        assert_eq!(result.invalid, vec!["broken"]);
+        assert_eq!(servers["same"].tags, vec!["project:demo"]);
+        assert_eq!(servers["local"].server, json!({ "command": "uvx" }));
+        assert!(!servers["fresh"].apps.claude);
+
+        let exported = server(
+            "api",
+            json!({
+                "command": "npx",
+                "enabled": true,
+                "env": { "API_KEY": "sk-secret", "MAX_TOKENS": "100" }
+            }),
+        );
+        assert_eq!(
+            render(&[&exported]),
+            json!({
+                "mcpServers": {
+                    "api": {
+                        "command": "npx",
+                        "env": { "API_KEY": "${API_KEY}", "MAX_TOKENS": "100" }
+                    }
+                }
+            })
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // SSOT 模式：不再写供应商副本文件
 
@@ -28,6 +28,21 @@ pub struct Provider {
     /// 供应商元数据（不写入 live 配置，仅存于 ~/.cc-switch/config.json）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta: Option<ProviderMeta>,
+    /// 认证方式（Claude / Codex；未设置视为 API Key）
+    #[serde(rename = "authKind", skip_serializing_if = "Option::is_none")]
+    pub auth_kind: Option<AuthKind>,
+    /// 最近一次保存时间（毫秒时间戳），更新时用于检测并发修改
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    /// 置顶：在供应商列表与托盘菜单中优先展示，不受排序方式与 sortIndex 影响
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// 托盘图标文件（PNG），该供应商为当前供应商时替换托盘图标
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// 托盘图标颜色（`#RRGGBB`），在默认托盘图标上叠加该颜色的角标
+    #[serde(rename = "iconColor", skip_serializing_if = "Option::is_none")]
+    pub icon_color: Option<String>,
 }
 
 impl Provider {
@@ -48,7 +63,48 @@ impl Provider {
             sort_index: None,
             notes: None,
             meta: None,
+            auth_kind: None,
+            updated_at: None,
+            pinned: false,
+            icon: None,
+            icon_color: None,
+        }
+    }
+
+    /// 是否为官方账号 OAuth 登录的供应商
+    pub fn is_oauth(&self) -> bool {
+        self.auth_kind == Some(AuthKind::Oauth)
+    }
+}
+
+/// 供应商认证方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthKind {
+    /// API Key / Token（写入 env 或 auth.json 的 OPENAI_API_KEY）
+    ApiKey,
+    /// 官方账号 OAuth 登录：切换时恢复保存的凭据快照（Codex 为 auth.json，Claude 为 .credentials.json 或 macOS 钥匙串）
+    Oauth,
+}
+
+/// 有序的供应商集合
+///
+/// 序列化为 JSON 对象（与 `HashMap<String, Provider>` 结构一致），但保持排序后的键顺序
+#[derive(Debug, Clone, Default)]
+pub struct OrderedProviders(pub Vec<(String, Provider)>);
+
+impl Serialize for OrderedProviders {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (id, provider) in &self.0 {
+            map.serialize_entry(id, provider)?;
         }
+        map.end()
     }
 }
 
@@ -87,6 +143,26 @@ pub struct UsageScript {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "autoQueryInterval")]
     pub auto_query_interval: Option<u64>,
+    /// 请求失败（网络错误、HTTP 429/5xx）时的最大重试次数（默认 0，不重试）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "maxRetries")]
+    pub max_retries: Option<u32>,
+    /// 首次重试前的等待时间（毫秒），之后每次翻倍
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "retryBackoffMs")]
+    pub retry_backoff_ms: Option<u64>,
+    /// 响应体大小上限（字节，默认 1 MiB）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "maxResponseBytes")]
+    pub max_response_bytes: Option<usize>,
+    /// 连续失败多少次后自动停用脚本（默认 5，0 表示不启用熔断）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "failureThreshold")]
+    pub failure_threshold: Option<u32>,
+    /// 熔断后自动恢复前的冷却时间（分钟，默认 30）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "cooldownMinutes")]
+    pub cooldown_minutes: Option<u64>,
 }
 
 /// 用量数据
@@ -111,6 +187,71 @@ pub struct UsageData {
     pub remaining: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unit: Option<String>,
+    /// 额度单位类型（脚本未返回时由 `unit` 推断）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "unitKind")]
+    pub unit_kind: Option<UsageUnitKind>,
+    /// 货币代码（ISO 4217，仅货币类额度；脚本未返回时由 `unit` 推断）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// 按设置中的展示货币换算后的额度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub converted: Option<ConvertedUsage>,
+}
+
+/// 用量额度的单位类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageUnitKind {
+    Currency,
+    Credits,
+    Tokens,
+}
+
+/// 换算为展示货币后的额度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertedUsage {
+    pub currency: String,
+    /// 换算比例（1 单位原货币 = rate 单位展示货币）
+    pub rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<f64>,
+}
+
+/// 测速时从响应头中采集的限流信息（`x-ratelimit-*`、`retry-after` 及中转站配额头）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_remaining: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_remaining: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<String>,
+    /// 采集到的原始响应头（小写名称）
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// 采集时间（毫秒时间戳）
+    pub captured_at: i64,
+}
+
+/// Codex 供应商的登录方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodexLoginType {
+    /// 使用 `OPENAI_API_KEY`
+    ApiKey,
+    /// ChatGPT 账号登录（auth.json 中的 OAuth tokens）
+    Chatgpt,
 }
 
 /// 用量查询结果（支持多套餐）
@@ -141,6 +282,370 @@ pub struct ProviderMeta {
         skip_serializing_if = "Option::is_none"
     )]
     pub partner_promotion_key: Option<String>,
+    /// 自定义 HTTP 请求头（切换时写入 live 配置：Claude 使用 ANTHROPIC_CUSTOM_HEADERS，Codex 使用 http_headers）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// 最近一次切换到该供应商的时间（毫秒时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+    /// 切换到该供应商时启用的斜杠命令集合（未设置时保持现状）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slash_commands: Option<Vec<String>>,
+    /// 最近一次测速采集到的限流信息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitInfo>,
+    /// 最近一次写入 live 配置时由 cc-switch 管理的环境变量键（Gemini .env 合并写入时用于清理旧键）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub managed_env_keys: Vec<String>,
+    /// Codex 登录方式（未设置时按 auth 中是否有 OPENAI_API_KEY 推断）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex_login_type: Option<CodexLoginType>,
+    /// 切换离开时暂存的 Codex OAuth 字段（`tokens`、`last_refresh`），切回时写回 auth.json
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex_oauth: Option<serde_json::Map<String, Value>>,
+    /// OAuth 供应商的凭据快照（Codex 为完整 auth.json，Claude 为 .credentials.json 或 macOS 钥匙串中的同名 JSON，Gemini 为 oauth_creds.json）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth_snapshot: Option<Value>,
+    /// 用户自定义标签（用于搜索与筛选）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 最近一次切换到该供应商后 live 配置文件的内容哈希（用于识别重复切换）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_hash: Option<String>,
+    /// Codex 额外环境变量（写入 config.toml 的 `shell_environment_policy.set`，并导出到环境变量文件与包装脚本）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_env: HashMap<String, String>,
+    /// 价格信息（用户填写，用于供应商对比）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ProviderPricing>,
+    /// 来自订阅源时记录来源与安装时的上游修订号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<SubscriptionOrigin>,
+    /// Claude settings.json 中以结构化字段管理的设置（切换时合并写入）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_settings: Option<ClaudeSettingsOverrides>,
+    /// 付费周期与续费日期（用于续费提醒与费用汇总）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub billing: Option<ProviderBilling>,
+    /// 最近一次综合验证（端点可达 + Key 有效 + 模型列表）的结果
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<ProviderVerification>,
+    /// 并发、超时与重试等性能参数（切换时写入 Codex 供应商表与 Claude env）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performance: Option<ProviderPerformance>,
+}
+
+/// 订阅安装来源
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionOrigin {
+    pub source_id: String,
+    pub entry_id: String,
+    pub revision: String,
+}
+
+/// 供应商价格信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderPricing {
+    /// 计价货币（ISO 4217）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// 每百万输入 tokens 价格
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_per_million: Option<f64>,
+    /// 每百万输出 tokens 价格
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_per_million: Option<f64>,
+    /// 中转站倍率（相对官方价格）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// 付费周期
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BillingCycle {
+    #[default]
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl BillingCycle {
+    pub fn months(self) -> u32 {
+        match self {
+            Self::Monthly => 1,
+            Self::Quarterly => 3,
+            Self::Yearly => 12,
+        }
+    }
+}
+
+/// 供应商付费信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderBilling {
+    #[serde(default)]
+    pub cycle: BillingCycle,
+    /// 续费日期（`YYYY-MM-DD`）；已过去时按周期顺延到下一次
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renewal_date: Option<String>,
+    /// 折算到每月的费用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_cost: Option<f64>,
+    /// 计价货币（ISO 4217）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// 提前多少天提醒续费（未设置时为 3 天）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remind_days_before: Option<u32>,
+}
+
+/// 供应商验证结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationStatus {
+    /// 全部检查通过
+    Verified,
+    /// 可以使用，但模型列表不可用（部分中转站未实现）
+    Degraded,
+    /// 端点不可达或 Key 无效
+    Failed,
+}
+
+/// 供应商验证结果（显示信任标记，长期未验证时提示）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderVerification {
+    pub status: VerificationStatus,
+    /// 最近一次检查时间（毫秒时间戳）
+    pub checked_at: i64,
+    /// 最近一次全部检查通过的时间（毫秒时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<i64>,
+    pub endpoint_reachable: bool,
+    pub key_valid: bool,
+    pub models_ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 供应商级的 Claude settings.json 设置，切换时覆盖供应商配置中的同名字段
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeSettingsOverrides {
+    /// `permissions.allow` / `permissions.deny`（`permissions` 下的其他字段保持不变）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<ClaudePermissions>,
+    /// `includeCoAuthoredBy`：提交信息是否附带 Co-Authored-By
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_co_authored_by: Option<bool>,
+    /// `model`：默认模型，可为 opus / sonnet / haiku 等别名或完整模型名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 模型别名实际指向的模型
+    #[serde(default, skip_serializing_if = "ClaudeModelAliases::is_empty")]
+    pub model_aliases: ClaudeModelAliases,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudePermissions {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+/// 写入 `ANTHROPIC_DEFAULT_{OPUS,SONNET,HAIKU}_MODEL`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeModelAliases {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opus: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sonnet: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub haiku: Option<String>,
+}
+
+impl ClaudeModelAliases {
+    pub fn is_empty(&self) -> bool {
+        self.entries().all(|(_, model)| model.is_none())
+    }
+
+    /// (环境变量名, 模型)
+    fn entries(&self) -> impl Iterator<Item = (&'static str, Option<&String>)> {
+        [
+            ("ANTHROPIC_DEFAULT_OPUS_MODEL", self.opus.as_ref()),
+            ("ANTHROPIC_DEFAULT_SONNET_MODEL", self.sonnet.as_ref()),
+            ("ANTHROPIC_DEFAULT_HAIKU_MODEL", self.haiku.as_ref()),
+        ]
+        .into_iter()
+    }
+}
+
+impl ClaudeSettingsOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 接管的顶层键（`managed-keys` 模式下与设置中的托管键一起改写）
+    pub fn managed_keys(&self) -> Vec<String> {
+        let mut keys = Vec::new();
+        if self.permissions.is_some() {
+            keys.push("permissions".to_string());
+        }
+        if self.include_co_authored_by.is_some() {
+            keys.push("includeCoAuthoredBy".to_string());
+        }
+        if self.model.is_some() {
+            keys.push("model".to_string());
+        }
+        keys
+    }
+
+    /// 将设置合并到 settings.json 内容中
+    pub fn apply(&self, content: &mut Value) {
+        let Some(obj) = content.as_object_mut() else {
+            return;
+        };
+        if let Some(permissions) = &self.permissions {
+            let target = obj
+                .entry("permissions")
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if !target.is_object() {
+                *target = Value::Object(serde_json::Map::new());
+            }
+            if let Some(target) = target.as_object_mut() {
+                target.insert("allow".to_string(), Value::from(permissions.allow.clone()));
+                target.insert("deny".to_string(), Value::from(permissions.deny.clone()));
+            }
+        }
+        if let Some(include) = self.include_co_authored_by {
+            obj.insert("includeCoAuthoredBy".to_string(), Value::Bool(include));
+        }
+        if let Some(model) = &self.model {
+            obj.insert("model".to_string(), Value::String(model.clone()));
+        }
+        if !self.model_aliases.is_empty() {
+            let env = obj
+                .entry("env")
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if let Some(env) = env.as_object_mut() {
+                for (key, model) in self.model_aliases.entries() {
+                    if let Some(model) = model {
+                        env.insert(key.to_string(), Value::String(model.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// 从内容中移除由本设置写入的字段（切换后回填到供应商配置的副本在设置变更时清理）
+    pub fn strip(&self, content: &mut Value) {
+        let Some(obj) = content.as_object_mut() else {
+            return;
+        };
+        if self.permissions.is_some() {
+            if let Some(permissions) = obj.get_mut("permissions").and_then(Value::as_object_mut) {
+                permissions.remove("allow");
+                permissions.remove("deny");
+                if permissions.is_empty() {
+                    obj.remove("permissions");
+                }
+            }
+        }
+        if self.include_co_authored_by.is_some() {
+            obj.remove("includeCoAuthoredBy");
+        }
+        if self.model.is_some() {
+            obj.remove("model");
+        }
+        if let Some(env) = obj.get_mut("env").and_then(Value::as_object_mut) {
+            for (key, model) in self.model_aliases.entries() {
+                if model.is_some() {
+                    env.remove(key);
+                }
+            }
+        }
+    }
+}
+
+/// 供应商级性能参数
+///
+/// Codex 写入当前 `[model_providers.<name>]` 表的同名键；Claude 仅支持请求超时，写入 `env.API_TIMEOUT_MS`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderPerformance {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_requests: Option<u32>,
+    /// 请求超时（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_timeout_ms: Option<u64>,
+    /// 请求失败后的重试次数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_max_retries: Option<u32>,
+    /// 流式响应无数据的超时（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_idle_timeout_ms: Option<u64>,
+}
+
+impl ProviderPerformance {
+    /// Claude 请求超时的环境变量
+    pub const CLAUDE_TIMEOUT_ENV: &'static str = "API_TIMEOUT_MS";
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Codex 供应商表中的键与值（未设置的为 None）
+    pub fn codex_entries(&self) -> [(&'static str, Option<u64>); 4] {
+        [
+            (
+                "max_concurrent_requests",
+                self.max_concurrent_requests.map(u64::from),
+            ),
+            ("request_timeout_ms", self.request_timeout_ms),
+            (
+                "request_max_retries",
+                self.request_max_retries.map(u64::from),
+            ),
+            ("stream_idle_timeout_ms", self.stream_idle_timeout_ms),
+        ]
+    }
+
+    /// 将请求超时写入 Claude settings.json 的 env
+    pub fn apply_claude(&self, content: &mut Value) {
+        let Some(timeout) = self.request_timeout_ms else {
+            return;
+        };
+        let Some(obj) = content.as_object_mut() else {
+            return;
+        };
+        let env = obj
+            .entry("env")
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+        if let Some(env) = env.as_object_mut() {
+            env.insert(
+                Self::CLAUDE_TIMEOUT_ENV.to_string(),
+                Value::String(timeout.to_string()),
+            );
+        }
+    }
+
+    /// 移除 [`Self::apply_claude`] 写入的字段
+    pub fn strip_claude(&self, content: &mut Value) {
+        if self.request_timeout_ms.is_none() {
+            return;
+        }
+        if let Some(env) = content.get_mut("env").and_then(Value::as_object_mut) {
+            env.remove(Self::CLAUDE_TIMEOUT_ENV);
+        }
+    }
 }
 
 impl ProviderManager {
@@ -0,0 +1,291 @@
+//! 从 CSV（电子表格导出）批量创建供应商
+//!
+//! 支持逗号、分号与制表符分隔（按表头自动识别）、双引号转义与 UTF-8 BOM。
+//! 首行为表头，列名通过 [`CsvColumnMapping`] 指定，未指定的列按常见列名自动匹配。
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::deeplink::DeepLinkImportRequest;
+use crate::error::AppError;
+use crate::provider::Provider;
+
+/// 各字段对应的表头列名（不区分大小写）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvColumnMapping {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+}
+
+/// 未指定映射时自动匹配的列名
+const NAME_HEADERS: &[&str] = &["name", "provider", "名称", "供应商"];
+const ENDPOINT_HEADERS: &[&str] = &["endpoint", "base_url", "baseurl", "url", "地址", "端点"];
+const API_KEY_HEADERS: &[&str] = &["key", "api_key", "apikey", "token", "密钥"];
+const NOTES_HEADERS: &[&str] = &["notes", "note", "remark", "备注"];
+const MODEL_HEADERS: &[&str] = &["model", "模型"];
+const HOMEPAGE_HEADERS: &[&str] = &["homepage", "website", "官网"];
+
+/// 单行导入失败的原因
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvRowError {
+    /// 文件中的行号（表头为第 1 行）
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub message: String,
+}
+
+/// CSV 导入结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportResult {
+    /// 新建的供应商 ID
+    pub imported: Vec<String>,
+    pub errors: Vec<CsvRowError>,
+}
+
+/// 解析出的一行供应商数据
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvProviderRow {
+    pub row: usize,
+    pub name: String,
+    pub endpoint: String,
+    pub api_key: String,
+    pub notes: Option<String>,
+    pub model: Option<String>,
+    pub homepage: Option<String>,
+}
+
+/// 解析 CSV 内容，返回 `(行号, 字段)` 列表（含表头，跳过空行）
+fn parse_records(content: &str) -> Vec<(usize, Vec<String>)> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let first_line = content.lines().next().unwrap_or_default();
+    let delimiter = [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| first_line.matches(*d).count())
+        .unwrap_or(',');
+
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+                line += 1;
+                record_line = line;
+            }
+            c if c == delimiter => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+    records.retain(|(_, fields)| fields.iter().any(|f| !f.trim().is_empty()));
+    records
+}
+
+/// 按映射（或常见列名）查找列序号
+fn find_column(headers: &[String], mapped: Option<&str>, candidates: &[&str]) -> Option<usize> {
+    let matches = |header: &str, name: &str| header.trim().eq_ignore_ascii_case(name.trim());
+    match mapped {
+        Some(name) => headers.iter().position(|h| matches(h, name)),
+        None => candidates
+            .iter()
+            .find_map(|name| headers.iter().position(|h| matches(h, name))),
+    }
+}
+
+/// 解析 CSV 为供应商行；缺少必需字段的行记入错误列表
+pub fn parse_rows(
+    content: &str,
+    mapping: &CsvColumnMapping,
+) -> Result<(Vec<CsvProviderRow>, Vec<CsvRowError>), AppError> {
+    let mut records = parse_records(content).into_iter();
+    let Some((_, headers)) = records.next() else {
+        return Err(AppError::localized(
+            "provider.csv.empty",
+            "CSV 文件为空",
+            "The CSV file is empty",
+        ));
+    };
+
+    let column = |mapped: &Option<String>, candidates: &[&str], label: &str| {
+        let index = find_column(&headers, mapped.as_deref(), candidates);
+        if index.is_none() && mapped.is_some() {
+            return Err(AppError::localized(
+                "provider.csv.column_missing",
+                format!(
+                    "CSV 表头中找不到{label}列: {}",
+                    mapped.as_deref().unwrap_or("")
+                ),
+                format!(
+                    "Column for {label} not found in CSV header: {}",
+                    mapped.as_deref().unwrap_or("")
+                ),
+            ));
+        }
+        Ok(index)
+    };
+    let name_col = column(&mapping.name, NAME_HEADERS, "name")?;
+    let endpoint_col = column(&mapping.endpoint, ENDPOINT_HEADERS, "endpoint")?;
+    let key_col = column(&mapping.api_key, API_KEY_HEADERS, "key")?;
+    let notes_col = column(&mapping.notes, NOTES_HEADERS, "notes")?;
+    let model_col = column(&mapping.model, MODEL_HEADERS, "model")?;
+    let homepage_col = column(&mapping.homepage, HOMEPAGE_HEADERS, "homepage")?;
+    let (Some(name_col), Some(endpoint_col), Some(key_col)) = (name_col, endpoint_col, key_col)
+    else {
+        return Err(AppError::localized(
+            "provider.csv.required_columns",
+            "CSV 需要包含名称、端点与密钥三列（可通过列映射指定）",
+            "The CSV must contain name, endpoint and key columns (use the column mapping to specify them)",
+        ));
+    };
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for (row, fields) in records {
+        let get = |index: Option<usize>| {
+            index
+                .and_then(|i| fields.get(i))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let (name, endpoint, api_key) = match (
+            get(Some(name_col)),
+            get(Some(endpoint_col)),
+            get(Some(key_col)),
+        ) {
+            (Some(name), Some(endpoint), Some(api_key)) => (name, endpoint, api_key),
+            (name, endpoint, api_key) => {
+                let missing: Vec<&str> = [
+                    ("name", name.is_none()),
+                    ("endpoint", endpoint.is_none()),
+                    ("key", api_key.is_none()),
+                ]
+                .into_iter()
+                .filter_map(|(label, missing)| missing.then_some(label))
+                .collect();
+                errors.push(CsvRowError {
+                    row,
+                    name,
+                    message: format!("missing {}", missing.join(", ")),
+                });
+                continue;
+            }
+        };
+        rows.push(CsvProviderRow {
+            row,
+            name,
+            endpoint,
+            api_key,
+            notes: get(notes_col),
+            model: get(model_col),
+            homepage: get(homepage_col),
+        });
+    }
+    Ok((rows, errors))
+}
+
+/// 按深链接导入相同的模板生成供应商（ID 由调用方分配）
+pub fn build_provider(app_type: &AppType, row: &CsvProviderRow) -> Result<Provider, AppError> {
+    let url = url::Url::parse(&row.endpoint)
+        .map_err(|e| AppError::InvalidInput(format!("无效的端点 {}: {e}", row.endpoint)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::InvalidInput(format!(
+            "端点必须以 http:// 或 https:// 开头: {}",
+            row.endpoint
+        )));
+    }
+    let request = DeepLinkImportRequest {
+        version: "v1".to_string(),
+        resource: "provider".to_string(),
+        app: app_type.as_str().to_string(),
+        name: row.name.clone(),
+        homepage: row.homepage.clone().unwrap_or_default(),
+        endpoint: row.endpoint.clone(),
+        api_key: row.api_key.clone(),
+        model: row.model.clone(),
+        notes: row.notes.clone(),
+        wire_api: None,
+    };
+    let mut provider = crate::deeplink::build_provider_from_request(app_type, &request)?;
+    if row.homepage.is_none() {
+        provider.website_url = None;
+    }
+    Ok(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rows_with_quotes_and_auto_detected_columns() {
+        let content = "\u{feff}Name;Base_URL;API_Key;Notes\r\n\
+             Relay A;https://a.example.com;sk-a;\"team; shared\"\r\n\
+             \r\n\
+             \"Relay \"\"B\"\"\";https://b.example.com;;\r\n\
+             Relay C;https://c.example.com;sk-c;\"line1\nline2\"\n";
+        let (rows, errors) = parse_rows(content, &CsvColumnMapping::default()).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].row, 2);
+        assert_eq!(rows[0].notes.as_deref(), Some("team; shared"));
+        assert_eq!(rows[1].row, 5);
+        assert_eq!(rows[1].notes.as_deref(), Some("line1\nline2"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].row, 4);
+        assert_eq!(errors[0].name.as_deref(), Some("Relay \"B\""));
+        assert_eq!(errors[0].message, "missing key");
+
+        let mapping = CsvColumnMapping {
+            api_key: Some("secret".into()),
+            ..Default::default()
+        };
+        assert!(parse_rows(content, &mapping).is_err());
+
+        let provider = build_provider(&AppType::Claude, &rows[0]).unwrap();
+        assert_eq!(
+            provider.settings_config["env"]["ANTHROPIC_BASE_URL"],
+            "https://a.example.com"
+        );
+        assert!(provider.website_url.is_none());
+    }
+}
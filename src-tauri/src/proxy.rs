@@ -0,0 +1,593 @@
+//! 本地反向代理：在 127.0.0.1 上提供 Anthropic / OpenAI / Gemini 兼容端点
+//!
+//! CLI 将基础地址指向 `http://127.0.0.1:<端口>/<应用>`（如 Claude 的 `ANTHROPIC_BASE_URL`
+//! 设为 `http://127.0.0.1:15721/claude`）后，每个请求在转发时才读取当前供应商，
+//! 因此切换即时生效，无需改写 CLI 配置文件。请求的鉴权头会替换为供应商的 API Key；
+//! 携带 `x-cc-switch-provider: <供应商 ID>` 时该请求改用指定供应商。
+//! 开启 `proxy_failover` 后，连接失败、5xx 或 429 时按排序依次尝试其他供应商
+//! （处于模拟故障中的供应商按连接失败处理）。
+//! 代理模式的 A/B 测试进行中时，未指定供应商的请求在两个供应商之间交替转发。
+//!
+//! 代理会附带真实的 API Key，因此只接受 `Host` 为 `127.0.0.1:<端口>` / `localhost:<端口>`
+//! 且不带 `Origin` 的请求，防止网页通过跨域请求或 DNS 重绑定消耗用户的 Key。
+//! 同时连接数不超过 [`MAX_CONNECTIONS`]，读空闲超过 [`IDLE_TIMEOUT_SECS`] 秒的连接会被关闭。
+
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use axum::body::{Body, Bytes};
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri};
+use axum::response::Response;
+use axum::routing::any;
+use axum::Router;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::Manager;
+
+use crate::ab_test::AbTestMode;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::limited_listener::LimitedListener;
+use crate::provider::Provider;
+use crate::services::{AbTestService, ProviderService, ProxyMetricsService, ProxySample};
+use crate::store::AppState;
+
+/// 默认监听端口
+pub const DEFAULT_PROXY_PORT: u16 = 15721;
+
+/// 同时处理的最大连接数，超出时暂停接受新连接（CLI 的并行子任务各占一个连接）
+const MAX_CONNECTIONS: usize = 64;
+
+/// 连接读空闲超时（秒）：需长于上游非流式请求的最长耗时，避免等待响应期间连接被关闭
+const IDLE_TIMEOUT_SECS: u64 = 15 * 60;
+
+/// 按请求指定供应商的请求头
+pub const PROVIDER_HEADER: &str = "x-cc-switch-provider";
+
+/// 请求体上限
+const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// 连接上游的超时（秒）；流式响应本身不设总超时
+const CONNECT_TIMEOUT_SECS: u64 = 15;
+
+/// 保留的最近请求记录条数
+const MAX_RECENT_REQUESTS: usize = 200;
+
+/// 不转发的请求头（逐跳头与客户端携带的鉴权头）
+const SKIPPED_REQUEST_HEADERS: &[&str] = &[
+    "host",
+    "connection",
+    "content-length",
+    "transfer-encoding",
+    "authorization",
+    "x-api-key",
+    "x-goog-api-key",
+    "cookie",
+    PROVIDER_HEADER,
+];
+
+/// 不回传的响应头（响应体以流式重新分块）
+const SKIPPED_RESPONSE_HEADERS: &[&str] = &["connection", "content-length", "transfer-encoding"];
+
+/// 一次代理请求的记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyRequestLog {
+    /// 请求时间（Unix 毫秒）
+    pub at: i64,
+    pub app_type: String,
+    /// 最终处理请求的供应商
+    pub provider_id: String,
+    pub method: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// 收到上游响应头的耗时（毫秒）
+    pub latency_ms: u64,
+    /// 尝试的供应商数量（故障转移时大于 1）
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 代理运行状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyStatus {
+    pub running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// 各应用应配置的基础地址
+    pub endpoints: HashMap<String, String>,
+    pub failover: bool,
+    /// 最近的请求（新的在前）
+    pub recent: Vec<ProxyRequestLog>,
+}
+
+/// 转发目标
+#[derive(Debug, Clone, PartialEq)]
+struct Upstream {
+    provider_id: String,
+    base_url: String,
+    headers: Vec<(String, String)>,
+}
+
+#[derive(Clone)]
+struct ProxyContext {
+    app: tauri::AppHandle,
+    client: reqwest::Client,
+    port: u16,
+}
+
+struct RunningProxy {
+    port: u16,
+    shutdown: tokio::sync::oneshot::Sender<()>,
+}
+
+fn running() -> &'static Mutex<Option<RunningProxy>> {
+    static RUNNING: OnceLock<Mutex<Option<RunningProxy>>> = OnceLock::new();
+    RUNNING.get_or_init(|| Mutex::new(None))
+}
+
+fn recent_requests() -> &'static Mutex<VecDeque<ProxyRequestLog>> {
+    static RECENT: OnceLock<Mutex<VecDeque<ProxyRequestLog>>> = OnceLock::new();
+    RECENT.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+/// 启动代理（已在运行时直接返回当前端口）
+pub async fn start(app: tauri::AppHandle, port: Option<u16>) -> Result<u16, AppError> {
+    if let Some(current) = running()
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|p| p.port))
+    {
+        return Ok(current);
+    }
+
+    let port = port
+        .or(crate::settings::get_settings().proxy_port)
+        .unwrap_or(DEFAULT_PROXY_PORT);
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "proxy.bind_failed",
+                format!("本地代理无法监听端口 {port}: {e}"),
+                format!("Local proxy failed to listen on port {port}: {e}"),
+            )
+        })?;
+    let listener = LimitedListener::new(
+        listener,
+        MAX_CONNECTIONS,
+        Duration::from_secs(IDLE_TIMEOUT_SECS),
+    );
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| AppError::Message(format!("创建代理 HTTP 客户端失败: {e}")))?;
+
+    let router = Router::new()
+        .route("/{app}/{*path}", any(forward))
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .with_state(ProxyContext { app, client, port });
+    let (shutdown, signal) = tokio::sync::oneshot::channel::<()>();
+    if let Ok(mut guard) = running().lock() {
+        *guard = Some(RunningProxy { port, shutdown });
+    }
+    tauri::async_runtime::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = signal.await;
+        });
+        if let Err(e) = server.await {
+            log::error!("本地代理异常退出: {e}");
+        }
+        if let Ok(mut guard) = running().lock() {
+            if guard.as_ref().is_some_and(|p| p.port == port) {
+                *guard = None;
+            }
+        }
+    });
+    log::info!("本地代理已启动: http://127.0.0.1:{port}");
+    Ok(port)
+}
+
+/// 停止代理，返回之前是否在运行
+pub fn stop() -> bool {
+    let Some(proxy) = running().lock().ok().and_then(|mut guard| guard.take()) else {
+        return false;
+    };
+    let _ = proxy.shutdown.send(());
+    log::info!("本地代理已停止");
+    true
+}
+
+pub fn status() -> ProxyStatus {
+    let port = running()
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|p| p.port));
+    let endpoint_port = port
+        .or(crate::settings::get_settings().proxy_port)
+        .unwrap_or(DEFAULT_PROXY_PORT);
+    let endpoints = [AppType::Claude, AppType::Codex, AppType::Gemini]
+        .iter()
+        .map(|app| {
+            (
+                app.as_str().to_string(),
+                format!("http://127.0.0.1:{endpoint_port}/{}", app.as_str()),
+            )
+        })
+        .collect();
+    ProxyStatus {
+        running: port.is_some(),
+        port,
+        endpoints,
+        failover: crate::settings::get_settings().proxy_failover,
+        recent: recent_requests()
+            .lock()
+            .map(|guard| guard.iter().cloned().collect())
+            .unwrap_or_default(),
+    }
+}
+
+async fn forward(
+    State(ctx): State<ProxyContext>,
+    Path((app, path)): Path<(String, String)>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let started = Instant::now();
+    if let Err(reason) = check_local_request(&headers, ctx.port) {
+        log::warn!("拒绝代理请求 /{app}/{path}: {reason}");
+        return error_response(StatusCode::FORBIDDEN, reason);
+    }
+    let Ok(app_type) = AppType::from_str(&app) else {
+        return error_response(StatusCode::NOT_FOUND, "unknown app");
+    };
+    let pinned = headers
+        .get(PROVIDER_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| ab_test_provider(&app_type));
+    let failover = crate::settings::get_settings().proxy_failover;
+    let upstreams = match ctx.app.try_state::<AppState>() {
+        Some(state) => resolve_upstreams(state.inner(), &app_type, pinned.as_deref(), failover),
+        None => return error_response(StatusCode::SERVICE_UNAVAILABLE, "state not ready"),
+    };
+    let upstreams = match upstreams {
+        Ok(upstreams) => upstreams,
+        Err(e) => return error_response(StatusCode::BAD_GATEWAY, &e.to_string()),
+    };
+
+    let mut log_entry = ProxyRequestLog {
+        at: chrono::Utc::now().timestamp_millis(),
+        app_type: app_type.as_str().to_string(),
+        provider_id: String::new(),
+        method: method.to_string(),
+        path: format!("/{path}"),
+        status: None,
+        latency_ms: 0,
+        attempts: 0,
+        error: None,
+    };
+    let total = upstreams.len();
+    for (index, upstream) in upstreams.into_iter().enumerate() {
+        log_entry.attempts += 1;
+        log_entry.provider_id = upstream.provider_id.clone();
+        let last = index + 1 == total;
+        if crate::daemon::outage_simulated(&app_type, &upstream.provider_id) {
+            log::warn!("代理请求 {} 处于模拟故障，跳过", upstream.provider_id);
+            if !last {
+                continue;
+            }
+            log_entry.error = Some("simulated outage".to_string());
+            log_entry.latency_ms = started.elapsed().as_millis() as u64;
+            record(log_entry);
+            return error_response(StatusCode::BAD_GATEWAY, "simulated outage");
+        }
+        let attempt_started = Instant::now();
+        let mut request = ctx
+            .client
+            .request(
+                method.clone(),
+                target_url(&upstream.base_url, &path, uri.query()),
+            )
+            .body(body.clone());
+        for (name, value) in headers.iter() {
+            if !SKIPPED_REQUEST_HEADERS.contains(&name.as_str()) {
+                request = request.header(name, value);
+            }
+        }
+        for (name, value) in &upstream.headers {
+            request = request.header(name, value);
+        }
+
+        match request.send().await {
+            Ok(resp) => {
+                let status = resp.status();
+                record_metric(&log_entry, Some(status.as_u16()), attempt_started.elapsed());
+                if !last && (status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS) {
+                    log::warn!(
+                        "代理请求 {} 返回 {status}，尝试下一个供应商",
+                        upstream.provider_id
+                    );
+                    continue;
+                }
+                log_entry.status = Some(status.as_u16());
+                log_entry.latency_ms = started.elapsed().as_millis() as u64;
+                record(log_entry);
+                return stream_response(resp);
+            }
+            Err(e) => {
+                log::warn!("代理请求 {} 失败: {e}", upstream.provider_id);
+                record_metric(&log_entry, None, attempt_started.elapsed());
+                if !last {
+                    continue;
+                }
+                log_entry.error = Some(e.to_string());
+                log_entry.latency_ms = started.elapsed().as_millis() as u64;
+                record(log_entry);
+                return error_response(StatusCode::BAD_GATEWAY, &e.to_string());
+            }
+        }
+    }
+    error_response(StatusCode::BAD_GATEWAY, "no upstream available")
+}
+
+fn record(entry: ProxyRequestLog) {
+    log::info!(
+        "代理 {} {} {} -> {} ({:?}, {}ms, {} 次尝试)",
+        entry.app_type,
+        entry.method,
+        entry.path,
+        entry.provider_id,
+        entry.status,
+        entry.latency_ms,
+        entry.attempts
+    );
+    if let Ok(mut guard) = recent_requests().lock() {
+        guard.push_front(entry);
+        guard.truncate(MAX_RECENT_REQUESTS);
+    }
+}
+
+/// 代理模式 A/B 测试进行中时，按请求交替选择供应商
+fn ab_test_provider(app_type: &AppType) -> Option<String> {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let test = AbTestService::active_for(app_type)?;
+    if test.mode != AbTestMode::Proxy {
+        return None;
+    }
+    let seq = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    Some(test.proxy_provider(seq).to_string())
+}
+
+/// 将单次尝试写入指标库（不阻塞代理请求）
+fn record_metric(entry: &ProxyRequestLog, status: Option<u16>, latency: Duration) {
+    let sample = ProxySample {
+        at: chrono::Utc::now().timestamp_millis(),
+        app_type: entry.app_type.clone(),
+        provider_id: entry.provider_id.clone(),
+        status,
+        latency_ms: latency.as_millis() as u64,
+    };
+    tauri::async_runtime::spawn_blocking(move || ProxyMetricsService::record(&sample));
+}
+
+fn stream_response(resp: reqwest::Response) -> Response {
+    let mut builder = Response::builder().status(resp.status());
+    for (name, value) in resp.headers() {
+        if !SKIPPED_RESPONSE_HEADERS.contains(&name.as_str()) {
+            builder = builder.header(name, value);
+        }
+    }
+    builder
+        .body(Body::from_stream(resp.bytes_stream()))
+        .unwrap_or_else(|e| error_response(StatusCode::BAD_GATEWAY, &e.to_string()))
+}
+
+/// 只接受本机 CLI 的请求：`Host` 必须是本代理的回环地址，且浏览器发起的请求（带 `Origin`）一律拒绝
+fn check_local_request(headers: &HeaderMap, port: u16) -> Result<(), &'static str> {
+    if headers.contains_key("origin") {
+        return Err("browser requests are not allowed");
+    }
+    let host = headers
+        .get("host")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let allowed = [format!("127.0.0.1:{port}"), format!("localhost:{port}")];
+    if allowed.contains(&host) {
+        Ok(())
+    } else {
+        Err("invalid host")
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    let mut response = Response::new(Body::from(
+        json!({ "error": { "type": "cc_switch_proxy_error", "message": message } }).to_string(),
+    ));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        HeaderName::from_static("content-type"),
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+fn target_url(base_url: &str, path: &str, query: Option<&str>) -> String {
+    let mut url = format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    );
+    if let Some(query) = query.filter(|q| !q.is_empty()) {
+        url.push('?');
+        url.push_str(query);
+    }
+    url
+}
+
+/// 按顺序列出可转发的供应商：指定供应商或当前供应商在前，开启故障转移时追加其余供应商
+fn resolve_upstreams(
+    state: &AppState,
+    app_type: &AppType,
+    pinned: Option<&str>,
+    failover: bool,
+) -> Result<Vec<Upstream>, AppError> {
+    let providers = ProviderService::list_sorted(state, app_type.clone())?.0;
+    let primary = match pinned {
+        Some(id) => id.to_string(),
+        None => ProviderService::current(state, app_type.clone())?,
+    };
+    let provider = providers
+        .iter()
+        .find(|(id, _)| *id == primary)
+        .map(|(_, provider)| provider)
+        .ok_or_else(|| {
+            AppError::localized(
+                "provider.not_found",
+                format!("供应商不存在: {primary}"),
+                format!("Provider not found: {primary}"),
+            )
+        })?;
+
+    let mut upstreams = vec![upstream_for(app_type, provider)?];
+    if failover {
+        upstreams.extend(
+            providers
+                .iter()
+                .filter(|(id, _)| *id != primary)
+                .filter_map(|(_, provider)| upstream_for(app_type, provider).ok()),
+        );
+    }
+    Ok(upstreams)
+}
+
+/// 供应商的上游地址与鉴权头（没有 API Key 的供应商无法代理）
+fn upstream_for(app_type: &AppType, provider: &Provider) -> Result<Upstream, AppError> {
+    let settings = crate::placeholder::expand_value(&provider.settings_config)?;
+    let mut expanded = provider.clone();
+    expanded.settings_config = settings.clone();
+    let text = |pointer: &str| {
+        settings
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+
+    let base_url = ProviderService::provider_base_url(app_type, &expanded);
+    let (base_url, auth) = match app_type {
+        AppType::Claude => (
+            base_url.unwrap_or_else(|| "https://api.anthropic.com".to_string()),
+            text("/env/ANTHROPIC_AUTH_TOKEN")
+                .map(|token| ("authorization", format!("Bearer {token}")))
+                .or_else(|| text("/env/ANTHROPIC_API_KEY").map(|key| ("x-api-key", key))),
+        ),
+        AppType::Codex => (
+            base_url.unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            text("/auth/OPENAI_API_KEY").map(|key| ("authorization", format!("Bearer {key}"))),
+        ),
+        AppType::Gemini => (
+            base_url.unwrap_or_else(|| "https://generativelanguage.googleapis.com".to_string()),
+            text("/env/GEMINI_API_KEY").map(|key| ("x-goog-api-key", key)),
+        ),
+    };
+    let (auth_name, auth_value) = auth.ok_or_else(|| {
+        AppError::localized(
+            "proxy.api_key_missing",
+            format!("供应商 {} 未配置 API Key，无法通过代理转发", provider.name),
+            format!(
+                "Provider {} has no API key and cannot be proxied",
+                provider.name
+            ),
+        )
+    })?;
+
+    let mut headers: Vec<(String, String)> = provider
+        .meta
+        .as_ref()
+        .map(|meta| {
+            meta.headers
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    headers.push((auth_name.to_string(), auth_value));
+    Ok(Upstream {
+        provider_id: provider.id.clone(),
+        base_url,
+        headers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_local_cli_requests() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in pairs {
+                map.insert(*name, HeaderValue::from_static(value));
+            }
+            map
+        };
+        assert!(check_local_request(&headers(&[("host", "127.0.0.1:15721")]), 15721).is_ok());
+        assert!(check_local_request(&headers(&[("host", "LocalHost:15721")]), 15721).is_ok());
+        assert!(check_local_request(&headers(&[("host", "evil.example:15721")]), 15721).is_err());
+        assert!(check_local_request(&headers(&[("host", "127.0.0.1:80")]), 15721).is_err());
+        assert!(check_local_request(&headers(&[]), 15721).is_err());
+        assert!(check_local_request(
+            &headers(&[
+                ("host", "127.0.0.1:15721"),
+                ("origin", "https://evil.example")
+            ]),
+            15721
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn builds_upstream_targets() {
+        assert_eq!(
+            target_url("https://relay.example.com/v1/", "responses", Some("a=1")),
+            "https://relay.example.com/v1/responses?a=1"
+        );
+        assert_eq!(
+            target_url("https://api.anthropic.com", "v1/messages", None),
+            "https://api.anthropic.com/v1/messages"
+        );
+
+        let provider = Provider::with_id(
+            "relay".into(),
+            "Relay".into(),
+            json!({ "env": {
+                "ANTHROPIC_BASE_URL": "https://relay.example.com",
+                "ANTHROPIC_AUTH_TOKEN": "sk-relay"
+            } }),
+            None,
+        );
+        let upstream = upstream_for(&AppType::Claude, &provider).unwrap();
+        assert_eq!(upstream.base_url, "https://relay.example.com");
+        assert_eq!(
+            upstream.headers,
+            vec![("authorization".to_string(), "Bearer sk-relay".to_string())]
+        );
+
+        let official = Provider::with_id("o".into(), "Official".into(), json!({ "env": {} }), None);
+        assert!(upstream_for(&AppType::Claude, &official).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! SSH 远程主机：配置定义与 `sftp` 批处理调用
+//!
+//! 通过系统自带的 `sftp`（OpenSSH）以批处理模式传输文件，使用 `BatchMode=yes` 禁止交互，
+//! 因此需要密钥认证（`identity_file` 或 ssh-agent / `~/.ssh/config` 中的配置）。
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// 连接超时（秒）
+const CONNECT_TIMEOUT_SECS: u64 = 15;
+
+/// 远程主机（SSH 目标）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteHost {
+    pub id: String,
+    pub name: String,
+    /// 主机名或 `~/.ssh/config` 中的 Host 别名
+    pub host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// 私钥路径（未设置时使用 ssh-agent / ssh 配置）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_file: Option<String>,
+}
+
+impl RemoteHost {
+    fn destination(&self) -> String {
+        match self
+            .user
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+        {
+            Some(user) => format!("{user}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    /// 校验字段，避免把选项注入到 sftp 命令行
+    pub fn validate(&self) -> Result<(), AppError> {
+        let invalid = |field: &str| {
+            AppError::localized(
+                "remote.invalid_host",
+                format!("远程主机的 {field} 无效"),
+                format!("Remote host has an invalid {field}"),
+            )
+        };
+        let host = self.host.trim();
+        if host.is_empty() || host.starts_with('-') || host.contains(char::is_whitespace) {
+            return Err(invalid("host"));
+        }
+        if let Some(user) = self.user.as_deref() {
+            if user.starts_with('-') || user.contains(['@', ' ']) {
+                return Err(invalid("user"));
+            }
+        }
+        if self.name.trim().is_empty() {
+            return Err(invalid("name"));
+        }
+        Ok(())
+    }
+}
+
+/// 以批处理模式执行 sftp 命令（远程路径相对于登录用户的主目录）
+///
+/// 以 `-` 开头的命令失败时不中止（如下载不存在的文件）
+pub fn sftp(host: &RemoteHost, batch: &str) -> Result<String, AppError> {
+    let mut command = Command::new("sftp");
+    command
+        .arg("-b")
+        .arg("-")
+        .arg("-o")
+        .arg("BatchMode=yes")
+        .arg("-o")
+        .arg(format!("ConnectTimeout={CONNECT_TIMEOUT_SECS}"));
+    if let Some(port) = host.port {
+        command.arg("-P").arg(port.to_string());
+    }
+    if let Some(identity) = host
+        .identity_file
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        command.arg("-i").arg(identity);
+    }
+    command
+        .arg("--")
+        .arg(host.destination())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let failed = |detail: String| {
+        AppError::localized(
+            "remote.sftp_failed",
+            format!("sftp 执行失败（{}）: {detail}", host.name),
+            format!("sftp failed ({}): {detail}", host.name),
+        )
+    };
+    let mut child = command.spawn().map_err(|e| failed(e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(batch.as_bytes())
+            .map_err(|e| failed(e.to_string()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .unwrap_or("unknown error");
+        return Err(failed(detail.to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 为 sftp 批处理命令的参数加引号
+pub fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_hosts_and_quotes_paths() {
+        let mut host = RemoteHost {
+            id: "h".into(),
+            name: "dev".into(),
+            host: "dev.example.com".into(),
+            port: Some(2222),
+            user: Some("me".into()),
+            identity_file: None,
+        };
+        assert!(host.validate().is_ok());
+        assert_eq!(host.destination(), "me@dev.example.com");
+
+        host.host = "-oProxyCommand=evil".into();
+        assert!(host.validate().is_err());
+
+        assert_eq!(
+            quote(r#"C:\Temp\my "dir"\settings.json"#),
+            r#""C:\\Temp\\my \"dir\"\\settings.json""#
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! 报告与通知中的本地化格式
+//!
+//! 按 `AppSettings.language` 格式化日期、金额、字节数与剩余天数，
+//! 供续费提醒、备份列表与诊断报告等后端生成的文本统一使用。
+
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+
+use crate::settings;
+
+/// 报告使用的语言环境
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportLocale {
+    Zh,
+    En,
+}
+
+const MONTHS_EN: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const BYTE_UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+impl ReportLocale {
+    /// 与托盘菜单一致：仅 `en` 使用英文，其余（含未设置）使用中文
+    pub fn from_language(language: Option<&str>) -> Self {
+        match language {
+            Some("en") => Self::En,
+            _ => Self::Zh,
+        }
+    }
+
+    /// 当前设置中的语言
+    pub fn current() -> Self {
+        Self::from_language(settings::get_settings().language.as_deref())
+    }
+
+    /// `2024年1月31日` / `Jan 31, 2024`
+    pub fn date(self, date: NaiveDate) -> String {
+        match self {
+            Self::Zh => format!("{}年{}月{}日", date.year(), date.month(), date.day()),
+            Self::En => format!(
+                "{} {}, {}",
+                MONTHS_EN[date.month0() as usize],
+                date.day(),
+                date.year()
+            ),
+        }
+    }
+
+    /// 毫秒时间戳按本地时区格式化为日期与时间
+    pub fn datetime_millis(self, millis: i64) -> Option<String> {
+        let time = Local.timestamp_millis_opt(millis).single()?;
+        Some(format!(
+            "{} {}",
+            self.date(time.date_naive()),
+            time.format("%H:%M")
+        ))
+    }
+
+    /// 金额与币种符号（`currency` 为 ISO 4217 代码，未设置时只输出数字）
+    pub fn currency(self, amount: f64, currency: Option<&str>) -> String {
+        let code = currency.map(|c| c.trim().to_ascii_uppercase());
+        let decimals = match code.as_deref() {
+            Some("JPY" | "KRW") => 0,
+            _ => 2,
+        };
+        let number = group_thousands(amount.abs(), decimals);
+        let sign = if amount < 0.0 && number.chars().any(|c| matches!(c, '1'..='9')) {
+            "-"
+        } else {
+            ""
+        };
+        let symbol = match (code.as_deref(), self) {
+            (None, _) => return format!("{sign}{number}"),
+            (Some("USD"), Self::Zh) => "US$",
+            (Some("USD"), Self::En) => "$",
+            (Some("CNY"), Self::Zh) => "¥",
+            (Some("CNY"), Self::En) => "CN¥",
+            (Some("JPY"), Self::Zh) => "JP¥",
+            (Some("JPY"), Self::En) => "¥",
+            (Some("EUR"), _) => "€",
+            (Some("GBP"), _) => "£",
+            (Some("HKD"), _) => "HK$",
+            (Some(other), _) => return format!("{sign}{other} {number}"),
+        };
+        format!("{sign}{symbol}{number}")
+    }
+
+    /// 字节数（1024 进制，保留一位小数）
+    pub fn bytes(self, bytes: u64) -> String {
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < BYTE_UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{bytes} B")
+        } else {
+            format!("{value:.1} {}", BYTE_UNITS[unit])
+        }
+    }
+
+    /// 距某日期的天数：`今天` / `明天` / `3 天后`
+    pub fn days_left(self, days: i64) -> String {
+        match (self, days) {
+            (Self::Zh, 0) => "今天".to_string(),
+            (Self::Zh, 1) => "明天".to_string(),
+            (Self::Zh, n) => format!("{n} 天后"),
+            (Self::En, 0) => "today".to_string(),
+            (Self::En, 1) => "tomorrow".to_string(),
+            (Self::En, n) => format!("in {n} days"),
+        }
+    }
+}
+
+/// 按千位分组（`1,234.50`）
+fn group_thousands(value: f64, decimals: usize) -> String {
+    let text = format!("{value:.decimals$}");
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text.as_str(), None),
+    };
+    let mut grouped = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    match fraction {
+        Some(fraction) => format!("{grouped}.{fraction}"),
+        None => grouped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_by_locale() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        assert_eq!(ReportLocale::Zh.date(date), "2024年1月31日");
+        assert_eq!(ReportLocale::En.date(date), "Jan 31, 2024");
+
+        assert_eq!(ReportLocale::En.currency(1234.5, Some("usd")), "$1,234.50");
+        assert_eq!(ReportLocale::Zh.currency(20.0, Some("CNY")), "¥20.00");
+        assert_eq!(ReportLocale::En.currency(20.0, Some("CNY")), "CN¥20.00");
+        assert_eq!(ReportLocale::En.currency(1500.0, Some("JPY")), "¥1,500");
+        assert_eq!(ReportLocale::En.currency(-3.0, Some("CHF")), "-CHF 3.00");
+        assert_eq!(ReportLocale::Zh.currency(0.001, None), "0.00");
+
+        assert_eq!(ReportLocale::En.bytes(512), "512 B");
+        assert_eq!(ReportLocale::En.bytes(3 * 1024 * 1024 / 2), "1.5 MB");
+
+        assert_eq!(ReportLocale::Zh.days_left(3), "3 天后");
+        assert_eq!(ReportLocale::En.days_left(1), "tomorrow");
+        assert_eq!(ReportLocale::from_language(Some("fr")), ReportLocale::Zh);
+    }
+}
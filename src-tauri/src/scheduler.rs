@@ -0,0 +1,534 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::ab_test::{AbTest, AbTestMode};
+use crate::app_config::AppType;
+use crate::services::{
+    subscription, BillingService, McpHealthService, ProviderService, SubscriptionService,
+    VerificationService,
+};
+use crate::store::AppState;
+use crate::switch_rule::SwitchRule;
+use crate::usage_script;
+
+/// 调度器轮询间隔（秒）
+const TICK_INTERVAL_SECS: u64 = 30;
+
+/// MCP 健康检查间隔（秒）
+const MCP_HEALTH_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// 启动后首次执行后台检查（MCP 健康检查、订阅拉取）的延迟（秒），避免与启动流程争抢资源
+const BACKGROUND_TASK_INITIAL_DELAY_SECS: u64 = 120;
+
+/// 订阅源拉取间隔（秒）
+const SUBSCRIPTION_REFRESH_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// 续费提醒检查间隔（秒）
+const RENEWAL_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+
+/// 供应商综合验证间隔（秒）
+const VERIFICATION_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// 临时切换倒计时事件的发送间隔（秒）
+const COUNTDOWN_INTERVAL_SECS: u64 = 1;
+
+/// 临时切换记录（仅保存在内存中，应用重启后不会自动回退）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemporarySwitch {
+    pub app_type: String,
+    pub provider_id: String,
+    pub previous_provider_id: String,
+    /// 回退时间（Unix 毫秒）
+    pub revert_at: i64,
+}
+
+/// 临时切换倒计时事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TemporarySwitchCountdown<'a> {
+    #[serde(flatten)]
+    switch: &'a TemporarySwitch,
+    remaining_secs: i64,
+}
+
+fn temporary_switches() -> &'static Mutex<HashMap<String, TemporarySwitch>> {
+    static SWITCHES: OnceLock<Mutex<HashMap<String, TemporarySwitch>>> = OnceLock::new();
+    SWITCHES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 获取指定应用进行中的临时切换
+pub fn temporary_switch(app_type: &AppType) -> Option<TemporarySwitch> {
+    let guard = temporary_switches().lock().ok()?;
+    guard.get(app_type.as_str()).cloned()
+}
+
+/// 取消指定应用的自动回退（保留当前供应商），返回被取消的记录
+pub fn cancel_temporary_switch(app_type: &AppType) -> Option<TemporarySwitch> {
+    let mut guard = temporary_switches().lock().ok()?;
+    guard.remove(app_type.as_str())
+}
+
+/// 登记临时切换并在到期后自动回退到原供应商
+///
+/// 期间每秒发送 `temporary-switch-countdown` 事件，回退后发送 `temporary-switch-reverted`。
+/// 同一应用再次临时切换会替换之前的记录，旧的倒计时随之结束。
+pub fn schedule_revert(app: tauri::AppHandle, switch: TemporarySwitch) {
+    if let Ok(mut guard) = temporary_switches().lock() {
+        guard.insert(switch.app_type.clone(), switch.clone());
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(COUNTDOWN_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+
+            let still_pending = temporary_switches()
+                .lock()
+                .map(|guard| guard.get(&switch.app_type) == Some(&switch))
+                .unwrap_or(false);
+            if !still_pending {
+                return;
+            }
+
+            let remaining_ms = switch.revert_at - chrono::Utc::now().timestamp_millis();
+            if remaining_ms > 0 {
+                let payload = TemporarySwitchCountdown {
+                    switch: &switch,
+                    remaining_secs: (remaining_ms + 999) / 1000,
+                };
+                if let Err(e) = app.emit("temporary-switch-countdown", payload) {
+                    log::error!("发射临时切换倒计时事件失败: {e}");
+                }
+                continue;
+            }
+
+            if let Ok(mut guard) = temporary_switches().lock() {
+                if guard.get(&switch.app_type) == Some(&switch) {
+                    guard.remove(&switch.app_type);
+                }
+            }
+            let handle = app.clone();
+            let pending = switch.clone();
+            if let Err(e) = tauri::async_runtime::spawn_blocking(move || {
+                revert_temporary_switch(&handle, &pending)
+            })
+            .await
+            {
+                log::error!("执行临时切换回退失败: {e}");
+            }
+            return;
+        }
+    });
+}
+
+fn revert_temporary_switch(app: &tauri::AppHandle, switch: &TemporarySwitch) {
+    let Ok(app_type) = switch.app_type.parse::<AppType>() else {
+        return;
+    };
+
+    // 期间用户已手动切换到其他供应商时不再回退
+    if let Some(state) = app.try_state::<AppState>() {
+        let current = ProviderService::current(state.inner(), app_type.clone()).ok();
+        if current.as_deref() != Some(switch.provider_id.as_str()) {
+            log::info!("{} 当前供应商已变更，跳过临时切换回退", switch.app_type);
+            return;
+        }
+    }
+
+    log::info!(
+        "临时切换到期: {} {} -> {}",
+        switch.app_type,
+        switch.provider_id,
+        switch.previous_provider_id
+    );
+    if let Err(e) =
+        crate::switch_provider_internal(app, app_type, switch.previous_provider_id.clone())
+    {
+        log::error!("临时切换回退失败: {e}");
+        return;
+    }
+    if let Err(e) = app.emit("temporary-switch-reverted", switch) {
+        log::error!("发射临时切换回退事件失败: {e}");
+    }
+}
+
+/// 每个应用最近一次生效的规则 ID，仅在生效规则变化时才触发切换，
+/// 避免覆盖用户在规则窗口内的手动切换
+fn active_rules() -> &'static Mutex<HashMap<String, String>> {
+    static ACTIVE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 已触发的额度规则及触发时被监测的原供应商（规则 ID -> 供应商 ID），
+/// 原供应商额度回升到恢复阈值前不会再次触发
+fn quota_latches() -> &'static Mutex<HashMap<String, String>> {
+    static LATCHES: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    LATCHES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 额度规则触发切换后发送给前端的通知
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QuotaSwitchEvent<'a> {
+    rule_id: &'a str,
+    app_type: &'a str,
+    from_provider_id: &'a str,
+    to_provider_id: &'a str,
+    remaining: f64,
+}
+
+/// 每个轮换模式 A/B 测试最近一次切换到的供应商，仅在轮换到下一时段时才切换，
+/// 避免覆盖用户在时段内的手动切换
+fn ab_rotations() -> &'static Mutex<HashMap<String, String>> {
+    static ROTATIONS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    ROTATIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 本次运行中已提醒过的续费（`应用:供应商:续费日期`），每次续费只提醒一次
+fn reminded_renewals() -> &'static Mutex<HashSet<String>> {
+    static REMINDED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    REMINDED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// 启动后台调度器（应用生命周期内只应调用一次）
+pub fn start(app: tauri::AppHandle) {
+    let health_app = app.clone();
+    let subscription_app = app.clone();
+    let renewal_app = app.clone();
+    let verification_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(TICK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let handle = app.clone();
+            if let Err(e) = tauri::async_runtime::spawn_blocking(move || {
+                evaluate_switch_rules(&handle);
+                evaluate_quota_rules(&handle);
+                evaluate_ab_rotations(&handle);
+            })
+            .await
+            {
+                log::error!("执行调度任务失败: {e}");
+            }
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + Duration::from_secs(BACKGROUND_TASK_INITIAL_DELAY_SECS),
+            Duration::from_secs(MCP_HEALTH_INTERVAL_SECS),
+        );
+        loop {
+            interval.tick().await;
+            check_mcp_health(&health_app).await;
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + Duration::from_secs(BACKGROUND_TASK_INITIAL_DELAY_SECS),
+            Duration::from_secs(SUBSCRIPTION_REFRESH_INTERVAL_SECS),
+        );
+        loop {
+            interval.tick().await;
+            refresh_subscriptions(&subscription_app).await;
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + Duration::from_secs(BACKGROUND_TASK_INITIAL_DELAY_SECS),
+            Duration::from_secs(RENEWAL_CHECK_INTERVAL_SECS),
+        );
+        loop {
+            interval.tick().await;
+            remind_renewals(&renewal_app);
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + Duration::from_secs(BACKGROUND_TASK_INITIAL_DELAY_SECS),
+            Duration::from_secs(VERIFICATION_INTERVAL_SECS),
+        );
+        loop {
+            interval.tick().await;
+            verify_providers(&verification_app).await;
+        }
+    });
+}
+
+/// 按设置定期验证所有供应商（离线模式下跳过），结果写入供应商元数据
+async fn verify_providers(app: &tauri::AppHandle) {
+    let settings = crate::settings::get_settings();
+    if !settings.provider_verification || settings.offline_mode {
+        return;
+    }
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    match VerificationService::verify_all(state.inner()).await {
+        Ok(count) => log::info!("已完成 {count} 个供应商的定期验证"),
+        Err(e) => log::error!("供应商定期验证失败: {e}"),
+    }
+}
+
+/// 供应商续费进入提醒时间时通知前端（每次续费只提醒一次）
+fn remind_renewals(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let renewals = match BillingService::upcoming_renewals(state.inner(), None) {
+        Ok(renewals) => renewals,
+        Err(e) => {
+            log::error!("检查供应商续费失败: {e}");
+            return;
+        }
+    };
+    let due: Vec<_> = {
+        let mut reminded = reminded_renewals()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        renewals
+            .into_iter()
+            .filter(|renewal| renewal.remind)
+            .filter(|renewal| {
+                reminded.insert(format!(
+                    "{}:{}:{}",
+                    renewal.app_type.as_str(),
+                    renewal.provider_id,
+                    renewal.renewal_date
+                ))
+            })
+            .collect()
+    };
+    if due.is_empty() {
+        return;
+    }
+    log::info!("{} 个供应商即将续费", due.len());
+    if let Err(e) = app.emit("billing-renewal-reminder", due) {
+        log::error!("发射续费提醒事件失败: {e}");
+    }
+}
+
+/// 定期拉取订阅源，上游有更新时通知前端
+async fn refresh_subscriptions(app: &tauri::AppHandle) {
+    if !subscription::has_subscriptions() {
+        return;
+    }
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    match SubscriptionService::refresh(state.inner()).await {
+        Ok(presets) => {
+            let updates: Vec<&str> = presets
+                .iter()
+                .filter(|preset| preset.update_available)
+                .map(|preset| preset.id.as_str())
+                .collect();
+            if !updates.is_empty() {
+                if let Err(e) = app.emit("subscription-updates-available", updates) {
+                    log::error!("发射订阅更新事件失败: {e}");
+                }
+            }
+        }
+        Err(e) => log::error!("拉取订阅源失败: {e}"),
+    }
+}
+
+/// 定期检查 MCP 服务器健康状态，并通知前端不健康的服务器
+async fn check_mcp_health(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    match McpHealthService::check_all(state.inner()).await {
+        Ok(unhealthy) if !unhealthy.is_empty() => {
+            let ids: Vec<&str> = unhealthy.iter().map(|server| server.id.as_str()).collect();
+            if let Err(e) = app.emit("mcp-health-warning", ids) {
+                log::error!("发射 MCP 健康检查事件失败: {e}");
+            }
+        }
+        Ok(_) => {}
+        Err(e) => log::error!("MCP 健康检查失败: {e}"),
+    }
+}
+
+/// 评估自动切换规则，在生效规则变化时切换供应商
+fn evaluate_switch_rules(app: &tauri::AppHandle) {
+    let settings = crate::settings::get_settings();
+    if !settings.switch_rules_enabled {
+        if let Ok(mut guard) = active_rules().lock() {
+            guard.clear();
+        }
+        return;
+    }
+
+    let now = chrono::Local::now();
+    // 仅在存在启用的网络条件规则时才检测网络（需调用系统命令）
+    let network = settings
+        .switch_rules
+        .iter()
+        .any(|rule| rule.enabled && rule.condition.uses_network())
+        .then(crate::network::detect);
+    for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+        let active =
+            SwitchRule::find_active(&settings.switch_rules, &app_type, &now, network.as_ref());
+        let key = app_type.as_str().to_string();
+
+        {
+            let Ok(mut guard) = active_rules().lock() else {
+                return;
+            };
+            match active {
+                None => {
+                    guard.remove(&key);
+                    continue;
+                }
+                Some(rule) if guard.get(&key) == Some(&rule.id) => continue,
+                Some(rule) => {
+                    guard.insert(key, rule.id.clone());
+                }
+            }
+        }
+
+        if let Some(rule) = active {
+            apply_rule(app, &app_type, rule);
+        }
+    }
+}
+
+/// 评估额度规则：当前供应商剩余额度低于阈值时切换到目标供应商并通知前端
+///
+/// 额度来自用量查询（前端自动查询或手动查询）的最近结果，没有查询数据的供应商不会触发
+fn evaluate_quota_rules(app: &tauri::AppHandle) {
+    let settings = crate::settings::get_settings();
+    let rules: Vec<&SwitchRule> = if settings.switch_rules_enabled {
+        settings
+            .switch_rules
+            .iter()
+            .filter(|rule| rule.enabled && rule.condition.uses_quota())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let Ok(mut latches) = quota_latches().lock() else {
+        return;
+    };
+    latches.retain(|id, _| rules.iter().any(|rule| &rule.id == id));
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    for rule in rules {
+        if let Some(watched) = latches.get(&rule.id) {
+            let recovered = usage_script::latest_quota(&rule.app, watched, now)
+                .is_some_and(|quota| !rule.condition.quota_triggered(quota.remaining, true));
+            if recovered {
+                log::info!("额度规则 '{}' 的供应商 {watched} 额度已恢复", rule.id);
+                latches.remove(&rule.id);
+            }
+            continue;
+        }
+
+        let Ok(current) = ProviderService::current(state.inner(), rule.app.clone()) else {
+            continue;
+        };
+        if current == rule.provider_id {
+            continue;
+        }
+        let Some(quota) = usage_script::latest_quota(&rule.app, &current, now) else {
+            continue;
+        };
+        if !rule.condition.quota_triggered(quota.remaining, false) {
+            continue;
+        }
+
+        latches.insert(rule.id.clone(), current.clone());
+        log::info!(
+            "额度规则 '{}' 生效: {} {current} 剩余 {} -> {}",
+            rule.id,
+            rule.app.as_str(),
+            quota.remaining,
+            rule.provider_id
+        );
+        if let Err(e) =
+            crate::switch_provider_internal(app, rule.app.clone(), rule.provider_id.clone())
+        {
+            log::error!("按额度规则 '{}' 自动切换供应商失败: {e}", rule.id);
+            continue;
+        }
+        let event = QuotaSwitchEvent {
+            rule_id: &rule.id,
+            app_type: rule.app.as_str(),
+            from_provider_id: &current,
+            to_provider_id: &rule.provider_id,
+            remaining: quota.remaining,
+        };
+        if let Err(e) = app.emit("quota-auto-switch", event) {
+            log::error!("发射额度自动切换事件失败: {e}");
+        }
+    }
+}
+
+fn apply_rule(app: &tauri::AppHandle, app_type: &AppType, rule: &SwitchRule) {
+    if let Some(state) = app.try_state::<AppState>() {
+        let current = ProviderService::current(state.inner(), app_type.clone()).ok();
+        if current.as_deref() == Some(rule.provider_id.as_str()) {
+            return;
+        }
+    }
+
+    log::info!(
+        "自动切换规则 '{}' 生效: {} -> {}",
+        rule.id,
+        app_type.as_str(),
+        rule.provider_id
+    );
+    if let Err(e) = crate::switch_provider_internal(app, app_type.clone(), rule.provider_id.clone())
+    {
+        log::error!("按规则 '{}' 自动切换供应商失败: {e}", rule.id);
+    }
+}
+
+/// 按轮换模式 A/B 测试的时段切换当前供应商
+fn evaluate_ab_rotations(app: &tauri::AppHandle) {
+    let tests: Vec<AbTest> = crate::settings::get_settings()
+        .ab_tests
+        .into_iter()
+        .filter(|test| test.is_active() && test.mode == AbTestMode::Rotation)
+        .collect();
+    let now = chrono::Utc::now().timestamp_millis();
+    for test in tests {
+        let expected = test.rotation_provider(now).to_string();
+        {
+            let Ok(mut guard) = ab_rotations().lock() else {
+                return;
+            };
+            if guard.get(&test.id) == Some(&expected) {
+                continue;
+            }
+            guard.insert(test.id.clone(), expected.clone());
+        }
+
+        if let Some(state) = app.try_state::<AppState>() {
+            let current = ProviderService::current(state.inner(), test.app.clone()).ok();
+            if current.as_deref() == Some(expected.as_str()) {
+                continue;
+            }
+        }
+        log::info!(
+            "A/B 测试 '{}' 轮换: {} -> {expected}",
+            test.id,
+            test.app.as_str()
+        );
+        if let Err(e) = crate::switch_provider_internal(app, test.app.clone(), expected) {
+            log::error!("A/B 测试 '{}' 轮换供应商失败: {e}", test.id);
+        }
+    }
+}
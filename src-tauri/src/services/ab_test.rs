@@ -0,0 +1,195 @@
+use serde::Serialize;
+
+use crate::ab_test::{pick_winner, AbTest};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::{ProviderMetrics, ProxyMetricsService};
+use crate::settings;
+use crate::store::AppState;
+
+/// A/B 测试中单个供应商的统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbTestArm {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub metrics: ProviderMetrics,
+    /// 占两者请求总数的比例（0~1）
+    pub share: f64,
+}
+
+/// `get_ab_test_report` 的返回值
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbTestReport {
+    pub test: AbTest,
+    /// 统计截止时间（已结束的测试为结束时间，否则为当前时间）
+    pub until: i64,
+    pub arms: Vec<AbTestArm>,
+    /// 错误率更低（相差明显时）或中位延迟更低的供应商；样本不足时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner: Option<String>,
+}
+
+/// 两个供应商之间的 A/B 测试（保存在设置中，指标来自本地代理）
+pub struct AbTestService;
+
+impl AbTestService {
+    pub fn list() -> Vec<AbTest> {
+        settings::get_settings().ab_tests
+    }
+
+    /// 指定应用进行中的测试
+    pub fn active_for(app_type: &AppType) -> Option<AbTest> {
+        AbTest::find_active(&settings::get_settings().ab_tests, app_type).cloned()
+    }
+
+    /// 开始新的测试（同一应用已有进行中的测试时报错）
+    pub fn start(state: &AppState, test: AbTest) -> Result<AbTest, AppError> {
+        let mut test = test;
+        let now = chrono::Utc::now().timestamp_millis();
+        test.id = test.id.trim().to_string();
+        if test.id.is_empty() {
+            test.id = format!("ab-{now}");
+        }
+        test.started_at = now;
+        test.ended_at = None;
+        Self::validate(state, &test)?;
+
+        let mut app_settings = settings::get_settings();
+        if AbTest::find_active(&app_settings.ab_tests, &test.app).is_some() {
+            return Err(AppError::localized(
+                "ab_test.already_running",
+                format!("{} 已有进行中的 A/B 测试", test.app.as_str()),
+                format!("An A/B test is already running for {}", test.app.as_str()),
+            ));
+        }
+        if app_settings.ab_tests.iter().any(|t| t.id == test.id) {
+            return Err(AppError::localized(
+                "ab_test.duplicate_id",
+                format!("A/B 测试 ID 已存在: {}", test.id),
+                format!("A/B test ID already exists: {}", test.id),
+            ));
+        }
+        app_settings.ab_tests.push(test.clone());
+        settings::update_settings(app_settings)?;
+        Ok(test)
+    }
+
+    /// 结束测试（保留记录以便查看报告）
+    pub fn stop(id: &str) -> Result<AbTest, AppError> {
+        let mut app_settings = settings::get_settings();
+        let test = app_settings
+            .ab_tests
+            .iter_mut()
+            .find(|test| test.id == id)
+            .ok_or_else(|| Self::not_found(id))?;
+        if test.ended_at.is_none() {
+            test.ended_at = Some(chrono::Utc::now().timestamp_millis());
+        }
+        let test = test.clone();
+        settings::update_settings(app_settings)?;
+        Ok(test)
+    }
+
+    pub fn delete(id: &str) -> Result<bool, AppError> {
+        let mut app_settings = settings::get_settings();
+        let before = app_settings.ab_tests.len();
+        app_settings.ab_tests.retain(|test| test.id != id);
+        if app_settings.ab_tests.len() == before {
+            return Ok(false);
+        }
+        settings::update_settings(app_settings)?;
+        Ok(true)
+    }
+
+    /// 汇总测试期间两个供应商的请求数、错误率与延迟
+    pub fn report(state: &AppState, id: &str) -> Result<AbTestReport, AppError> {
+        let test = Self::list()
+            .into_iter()
+            .find(|test| test.id == id)
+            .ok_or_else(|| Self::not_found(id))?;
+        let until = test
+            .ended_at
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+        let app = test.app.as_str();
+        let a = ProxyMetricsService::provider_metrics(
+            app,
+            &test.provider_a,
+            test.started_at,
+            test.ended_at,
+        )?;
+        let b = ProxyMetricsService::provider_metrics(
+            app,
+            &test.provider_b,
+            test.started_at,
+            test.ended_at,
+        )?;
+        let winner = pick_winner(&a, &b).map(str::to_string);
+
+        let total = (a.requests + b.requests).max(1) as f64;
+        let names = {
+            let config = state.config.read().map_err(AppError::from)?;
+            let manager = config.get_manager(&test.app);
+            let name = |id: &str| {
+                manager
+                    .and_then(|manager| manager.providers.get(id))
+                    .map(|provider| provider.name.clone())
+                    .unwrap_or_else(|| id.to_string())
+            };
+            (name(&test.provider_a), name(&test.provider_b))
+        };
+        let arms = vec![
+            AbTestArm {
+                provider_id: test.provider_a.clone(),
+                provider_name: names.0,
+                share: a.requests as f64 / total,
+                metrics: a,
+            },
+            AbTestArm {
+                provider_id: test.provider_b.clone(),
+                provider_name: names.1,
+                share: b.requests as f64 / total,
+                metrics: b,
+            },
+        ];
+        Ok(AbTestReport {
+            test,
+            until,
+            arms,
+            winner,
+        })
+    }
+
+    fn validate(state: &AppState, test: &AbTest) -> Result<(), AppError> {
+        if test.provider_a == test.provider_b {
+            return Err(AppError::localized(
+                "ab_test.same_provider",
+                "A/B 测试需要选择两个不同的供应商",
+                "An A/B test requires two different providers",
+            ));
+        }
+        let config = state.config.read().map_err(AppError::from)?;
+        for provider_id in [&test.provider_a, &test.provider_b] {
+            let exists = config
+                .get_manager(&test.app)
+                .is_some_and(|manager| manager.providers.contains_key(provider_id));
+            if !exists {
+                return Err(AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {provider_id}"),
+                    format!("Provider not found: {provider_id}"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn not_found(id: &str) -> AppError {
+        AppError::localized(
+            "ab_test.not_found",
+            format!("A/B 测试不存在: {id}"),
+            format!("A/B test not found: {id}"),
+        )
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::adapter::AppAdapter;
+use crate::config::{delete_file, get_app_config_dir, write_text_file};
+use crate::error::AppError;
+use crate::provider::{Provider, ProviderManager};
+use crate::store::AppState;
+
+/// 适配器应用的供应商列表
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterProviders {
+    pub current: String,
+    pub providers: HashMap<String, Provider>,
+}
+
+/// 写入前的文件内容，用于回滚
+struct FileBackup {
+    path: PathBuf,
+    content: Option<String>,
+}
+
+/// 清单驱动的适配器应用：加载 `~/.cc-switch/adapters/*.json`，并用通用代码读写其配置文件
+///
+/// 供应商保存在 config.json 中以适配器 ID 为键的应用下，与内置应用的结构一致。
+pub struct AdapterService;
+
+impl AdapterService {
+    pub fn adapters_dir() -> PathBuf {
+        get_app_config_dir().join("adapters")
+    }
+
+    /// 加载全部有效的清单（无效清单记录日志后跳过）
+    pub fn list() -> Vec<AppAdapter> {
+        let Ok(entries) = std::fs::read_dir(Self::adapters_dir()) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        let mut adapters: Vec<AppAdapter> = Vec::new();
+        for path in paths {
+            let loaded = std::fs::read_to_string(&path)
+                .map_err(|e| AppError::io(&path, e))
+                .and_then(|content| {
+                    serde_json::from_str::<AppAdapter>(&content)
+                        .map_err(|e| AppError::json(&path, e))
+                })
+                .and_then(|adapter| adapter.validate().map(|_| adapter));
+            match loaded {
+                Ok(adapter) if adapters.iter().any(|a| a.id == adapter.id) => {
+                    log::warn!("适配器 ID 重复，已忽略 {}", path.display());
+                }
+                Ok(adapter) => adapters.push(adapter),
+                Err(e) => log::warn!("加载适配器清单 {} 失败: {e}", path.display()),
+            }
+        }
+        adapters
+    }
+
+    pub fn get(id: &str) -> Result<AppAdapter, AppError> {
+        Self::list()
+            .into_iter()
+            .find(|adapter| adapter.id == id)
+            .ok_or_else(|| {
+                AppError::localized(
+                    "adapter.not_found",
+                    format!("适配器不存在: {id}"),
+                    format!("Adapter not found: {id}"),
+                )
+            })
+    }
+
+    pub fn providers(state: &AppState, adapter_id: &str) -> Result<AdapterProviders, AppError> {
+        Self::get(adapter_id)?;
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config.apps.get(adapter_id).cloned().unwrap_or_default();
+        Ok(AdapterProviders {
+            current: manager.current,
+            providers: manager.providers,
+        })
+    }
+
+    /// 读取当前 live 配置中的字段值
+    pub fn read_live(adapter_id: &str) -> Result<Value, AppError> {
+        let adapter = Self::get(adapter_id)?;
+        adapter.read_values(&read_files(&adapter)?)
+    }
+
+    /// 新增或更新供应商（按清单校验字段）
+    pub fn save_provider(
+        state: &AppState,
+        adapter_id: &str,
+        provider: Provider,
+    ) -> Result<(), AppError> {
+        let adapter = Self::get(adapter_id)?;
+        adapter.validate_values(&provider.settings_config)?;
+        let is_current = {
+            let mut config = state.config.write().map_err(AppError::from)?;
+            let manager = config.apps.entry(adapter.id.clone()).or_default();
+            let is_current = manager.current == provider.id;
+            manager
+                .providers
+                .insert(provider.id.clone(), provider.clone());
+            is_current
+        };
+        if is_current {
+            write_live(&adapter, &provider.settings_config)?;
+        }
+        state.save()
+    }
+
+    pub fn delete_provider(
+        state: &AppState,
+        adapter_id: &str,
+        provider_id: &str,
+    ) -> Result<(), AppError> {
+        {
+            let mut config = state.config.write().map_err(AppError::from)?;
+            let Some(manager) = config.apps.get_mut(adapter_id) else {
+                return Ok(());
+            };
+            if manager.current == provider_id {
+                return Err(AppError::localized(
+                    "provider.delete.current",
+                    "不能删除当前正在使用的供应商",
+                    "Cannot delete the provider currently in use",
+                ));
+            }
+            manager.providers.remove(provider_id);
+        }
+        state.save()
+    }
+
+    /// 切换供应商：先将 live 配置回填到当前供应商，再写入目标供应商；保存失败时恢复文件
+    pub fn switch(state: &AppState, adapter_id: &str, provider_id: &str) -> Result<(), AppError> {
+        let adapter = Self::get(adapter_id)?;
+        let live = adapter.read_values(&read_files(&adapter)?)?;
+        let target = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .apps
+                .get(&adapter.id)
+                .and_then(|manager| manager.providers.get(provider_id))
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?
+        };
+        adapter.validate_values(&target.settings_config)?;
+
+        let backups = write_live(&adapter, &target.settings_config)?;
+        {
+            let mut config = state.config.write().map_err(AppError::from)?;
+            let manager = config.apps.entry(adapter.id.clone()).or_default();
+            backfill_current(manager, provider_id, live);
+            manager.current = provider_id.to_string();
+        }
+        state.save().inspect_err(|_| restore(&backups))
+    }
+}
+
+fn backfill_current(manager: &mut ProviderManager, next: &str, live: Value) {
+    if manager.current.is_empty() || manager.current == next {
+        return;
+    }
+    if let Some(current) = manager.providers.get_mut(&manager.current) {
+        if let (Some(snapshot), Value::Object(live)) =
+            (current.settings_config.as_object_mut(), live)
+        {
+            snapshot.extend(live);
+        }
+    }
+}
+
+/// 读取清单中存在的配置文件（文件 ID -> 内容）
+fn read_files(adapter: &AppAdapter) -> Result<HashMap<String, String>, AppError> {
+    let mut contents = HashMap::new();
+    for file in &adapter.files {
+        let path = adapter.file_path(file)?;
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                contents.insert(file.id.clone(), content);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(AppError::io(&path, e)),
+        }
+    }
+    Ok(contents)
+}
+
+/// 写入字段值，任一文件失败时恢复已写入的文件；成功时返回备份供后续回滚
+fn write_live(adapter: &AppAdapter, values: &Value) -> Result<Vec<FileBackup>, AppError> {
+    let contents = read_files(adapter)?;
+    let rendered = adapter.render(values, &contents)?;
+    let mut backups = Vec::new();
+    for (file_id, content) in rendered {
+        let Some(file) = adapter.files.iter().find(|file| file.id == file_id) else {
+            continue;
+        };
+        let path = adapter.file_path(file)?;
+        backups.push(FileBackup {
+            content: contents.get(&file_id).cloned(),
+            path: path.clone(),
+        });
+        if let Err(e) = write_text_file(&path, &content) {
+            restore(&backups);
+            return Err(e);
+        }
+    }
+    Ok(backups)
+}
+
+fn restore(backups: &[FileBackup]) {
+    for backup in backups.iter().rev() {
+        let result = match &backup.content {
+            Some(content) => write_text_file(&backup.path, content),
+            None => delete_file(&backup.path),
+        };
+        if let Err(e) = result {
+            log::warn!("恢复适配器文件 {} 失败: {e}", backup.path.display());
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{delete_file, get_claude_config_dir, write_text_file};
+use crate::error::AppError;
+use crate::services::skill::{SkillRepo, SkillService};
+
+/// 下载仓库的总超时（秒）
+const REPO_DOWNLOAD_TIMEOUT_SECS: u64 = 15;
+
+/// Claude Code 子代理定义（`~/.claude/agents/<name>.md`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAgent {
+    /// 文件名（不含 `.md`）
+    pub name: String,
+    /// front matter 中的描述
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub content: String,
+    pub enabled: bool,
+}
+
+/// 仓库中可安装的子代理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoAgent {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 仓库内的相对路径
+    pub path: String,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub repo_branch: String,
+    pub installed: bool,
+}
+
+/// 子代理 front matter（仅读取展示所需字段）
+#[derive(Debug, Default, Deserialize)]
+struct AgentMetadata {
+    name: Option<String>,
+    description: Option<String>,
+}
+
+/// Claude Code 子代理管理：停用的定义移动到 `agents-disabled` 目录
+pub struct AgentService;
+
+impl AgentService {
+    /// 启用的子代理目录：`<Claude 配置目录>/agents`
+    pub fn agents_dir() -> PathBuf {
+        get_claude_config_dir().join("agents")
+    }
+
+    /// 停用的子代理目录（与 agents 同级，避免被 Claude Code 加载）
+    pub fn disabled_dir() -> PathBuf {
+        get_claude_config_dir().join("agents-disabled")
+    }
+
+    /// 列出所有子代理（含已停用），按名称排序
+    pub fn list() -> Result<Vec<SubAgent>, AppError> {
+        let mut agents = Vec::new();
+        for (dir, enabled) in [(Self::agents_dir(), true), (Self::disabled_dir(), false)] {
+            for path in list_md_files(&dir)? {
+                let content = fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+                let name = file_stem(&path);
+                // 同名文件同时存在时以启用目录为准
+                if agents.iter().any(|agent: &SubAgent| agent.name == name) {
+                    continue;
+                }
+                agents.push(SubAgent {
+                    description: parse_metadata(&content).description,
+                    name,
+                    content,
+                    enabled,
+                });
+            }
+        }
+        agents.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(agents)
+    }
+
+    /// 新建或编辑子代理；已停用的定义在停用目录中原地更新
+    pub fn save(name: &str, content: &str) -> Result<SubAgent, AppError> {
+        let name = validate_name(name)?;
+        let disabled_path = Self::disabled_dir().join(format!("{name}.md"));
+        let enabled = !disabled_path.exists();
+        let path = if enabled {
+            Self::agents_dir().join(format!("{name}.md"))
+        } else {
+            disabled_path
+        };
+        write_text_file(&path, content)?;
+
+        Ok(SubAgent {
+            description: parse_metadata(content).description,
+            name,
+            content: content.to_string(),
+            enabled,
+        })
+    }
+
+    /// 删除子代理（启用与停用目录中的同名文件均删除）
+    pub fn delete(name: &str) -> Result<bool, AppError> {
+        let name = validate_name(name)?;
+        let mut existed = false;
+        for dir in [Self::agents_dir(), Self::disabled_dir()] {
+            let path = dir.join(format!("{name}.md"));
+            if path.exists() {
+                delete_file(&path)?;
+                existed = true;
+            }
+        }
+        Ok(existed)
+    }
+
+    /// 启用或停用子代理：在 agents 与 agents-disabled 目录之间移动文件
+    pub fn set_enabled(name: &str, enabled: bool) -> Result<(), AppError> {
+        let name = validate_name(name)?;
+        let (from_dir, to_dir) = if enabled {
+            (Self::disabled_dir(), Self::agents_dir())
+        } else {
+            (Self::agents_dir(), Self::disabled_dir())
+        };
+        let from = from_dir.join(format!("{name}.md"));
+        let to = to_dir.join(format!("{name}.md"));
+
+        if !from.exists() {
+            if to.exists() {
+                return Ok(());
+            }
+            return Err(AppError::localized(
+                "agent.not_found",
+                format!("子代理不存在: {name}"),
+                format!("Sub-agent not found: {name}"),
+            ));
+        }
+        fs::create_dir_all(&to_dir).map_err(|e| AppError::io(&to_dir, e))?;
+        fs::rename(&from, &to).map_err(|e| AppError::io(&from, e))
+    }
+
+    /// 将启用与停用的子代理复制到备份目录（无子代理时不创建目录）
+    pub fn backup_to(target: &Path) -> Result<(), AppError> {
+        for (dir, sub) in [
+            (Self::agents_dir(), "agents"),
+            (Self::disabled_dir(), "disabled"),
+        ] {
+            let files = list_md_files(&dir)?;
+            if files.is_empty() {
+                continue;
+            }
+            let dest_dir = target.join(sub);
+            fs::create_dir_all(&dest_dir).map_err(|e| AppError::io(&dest_dir, e))?;
+            for path in files {
+                let dest = dest_dir.join(path.file_name().unwrap_or_default());
+                fs::copy(&path, &dest).map_err(|e| AppError::io(&path, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 扫描已启用仓库中的子代理定义（需带 `name` front matter 的 `.md` 文件）
+    pub async fn list_repo_agents(
+        skill_service: &SkillService,
+        repos: Vec<SkillRepo>,
+    ) -> Result<Vec<RepoAgent>, AppError> {
+        let installed: Vec<String> = Self::list()?.into_iter().map(|a| a.name).collect();
+        let mut agents = Vec::new();
+
+        for repo in repos.into_iter().filter(|repo| repo.enabled) {
+            let temp_dir = match download_repo(skill_service, &repo).await {
+                Ok(dir) => dir,
+                Err(e) => {
+                    log::warn!("获取仓库 {}/{} 子代理失败: {}", repo.owner, repo.name, e);
+                    continue;
+                }
+            };
+            let scan_dir = match &repo.skills_path {
+                Some(sub) => temp_dir.join(sub.trim_matches('/')),
+                None => temp_dir.clone(),
+            };
+
+            let mut files = Vec::new();
+            collect_md_files_recursive(&scan_dir, &mut files);
+            for path in files {
+                let Ok(content) = fs::read_to_string(&path) else {
+                    continue;
+                };
+                let meta = parse_metadata(&content);
+                if meta.name.is_none() {
+                    continue;
+                }
+                let name = file_stem(&path);
+                let relative = path
+                    .strip_prefix(&temp_dir)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                agents.push(RepoAgent {
+                    installed: installed.contains(&name),
+                    name,
+                    description: meta.description,
+                    path: relative,
+                    repo_owner: repo.owner.clone(),
+                    repo_name: repo.name.clone(),
+                    repo_branch: repo.branch.clone(),
+                });
+            }
+
+            let _ = fs::remove_dir_all(&temp_dir);
+        }
+
+        agents.sort_by_key(|a| a.name.to_lowercase());
+        Ok(agents)
+    }
+
+    /// 从仓库安装单个子代理到 agents 目录（已存在同名定义时覆盖）
+    pub async fn install_repo_agent(
+        skill_service: &SkillService,
+        agent: &RepoAgent,
+    ) -> Result<SubAgent, AppError> {
+        let repo = SkillRepo {
+            owner: agent.repo_owner.clone(),
+            name: agent.repo_name.clone(),
+            branch: agent.repo_branch.clone(),
+            enabled: true,
+            skills_path: None,
+        };
+        let temp_dir = download_repo(skill_service, &repo).await?;
+
+        let relative = Path::new(agent.path.trim_start_matches('/'));
+        let source = temp_dir.join(relative);
+        let safe = relative
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+        let result = if safe && source.is_file() {
+            fs::read_to_string(&source)
+                .map_err(|e| AppError::io(&source, e))
+                .and_then(|content| {
+                    let _ = Self::delete(&agent.name);
+                    Self::save(&agent.name, &content)
+                })
+        } else {
+            Err(AppError::localized(
+                "agent.repo_file_missing",
+                format!("仓库中不存在子代理文件: {}", agent.path),
+                format!("Sub-agent file not found in repository: {}", agent.path),
+            ))
+        };
+
+        let _ = fs::remove_dir_all(&temp_dir);
+        result
+    }
+}
+
+/// 下载仓库（带总超时，与技能仓库一致）
+async fn download_repo(
+    skill_service: &SkillService,
+    repo: &SkillRepo,
+) -> Result<PathBuf, AppError> {
+    tokio::time::timeout(
+        std::time::Duration::from_secs(REPO_DOWNLOAD_TIMEOUT_SECS),
+        skill_service.download_repo(repo),
+    )
+    .await
+    .map_err(|_| AppError::Message(format!("下载仓库 {}/{} 超时", repo.owner, repo.name)))?
+    .map_err(|e| AppError::Message(format!("下载仓库 {}/{} 失败: {e}", repo.owner, repo.name)))
+}
+
+/// 校验子代理名称（即文件名）：仅允许字母、数字、`-`、`_`、`.`，且不能以 `.` 开头
+fn validate_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(AppError::localized(
+            "agent.invalid_name",
+            format!("无效的子代理名称: {name}"),
+            format!("Invalid sub-agent name: {name}"),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// 列出目录下（不递归）的 `.md` 文件
+fn list_md_files(dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| AppError::io(dir, e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn collect_md_files_recursive(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_md_files_recursive(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            out.push(path);
+        }
+    }
+}
+
+/// 解析 YAML front matter 中的 name / description
+fn parse_metadata(content: &str) -> AgentMetadata {
+    let content = content.trim_start_matches('\u{feff}');
+    content
+        .strip_prefix("---")
+        .and_then(|rest| rest.split_once("\n---"))
+        .and_then(|(front_matter, _)| serde_yaml::from_str(front_matter).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_agent_front_matter_and_validates_names() {
+        let content = "---\nname: code-reviewer\ndescription: Reviews code\ntools: Read, Grep\n---\nYou are a reviewer.";
+        let meta = parse_metadata(content);
+        assert_eq!(meta.name.as_deref(), Some("code-reviewer"));
+        assert_eq!(meta.description.as_deref(), Some("Reviews code"));
+        assert!(parse_metadata("plain text").name.is_none());
+
+        assert!(validate_name("code-reviewer").is_ok());
+        assert!(validate_name("../x").is_err());
+        assert!(validate_name("a/b").is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::{Months, NaiveDate};
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::{BillingCycle, ProviderBilling};
+use crate::report_format::ReportLocale;
+use crate::store::AppState;
+
+/// 未设置提前天数时的续费提醒时间
+const DEFAULT_REMIND_DAYS: u32 = 3;
+
+/// 未指定范围时列出的续费天数
+const DEFAULT_WITHIN_DAYS: u32 = 30;
+
+/// 续费日期已过去时最多顺延的周期数
+const MAX_CYCLES: u32 = 1200;
+
+/// 即将到来的续费
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpcomingRenewal {
+    pub app_type: AppType,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub cycle: BillingCycle,
+    /// 下一次续费日期（`YYYY-MM-DD`）
+    pub renewal_date: String,
+    /// 距续费的天数（当天为 0）
+    pub days_left: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_cost: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// 已进入提醒时间
+    pub remind: bool,
+    /// 按界面语言格式化的提醒文本
+    pub summary: String,
+}
+
+/// 供应商付费周期与续费提醒
+pub struct BillingService;
+
+impl BillingService {
+    /// 列出 `within_days` 天内（默认 30 天）所有应用的续费，按日期升序
+    pub fn upcoming_renewals(
+        state: &AppState,
+        within_days: Option<u32>,
+    ) -> Result<Vec<UpcomingRenewal>, AppError> {
+        let within = i64::from(within_days.unwrap_or(DEFAULT_WITHIN_DAYS));
+        let today = chrono::Local::now().date_naive();
+        let locale = ReportLocale::current();
+        let config = state.config.read().map_err(AppError::from)?;
+        let mut renewals = Vec::new();
+        for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            let Some(manager) = config.get_manager(&app_type) else {
+                continue;
+            };
+            for (id, provider) in &manager.providers {
+                let Some(billing) = provider
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.billing.as_ref())
+                else {
+                    continue;
+                };
+                let Some(date) = next_renewal(billing, today) else {
+                    continue;
+                };
+                let days_left = (date - today).num_days();
+                if days_left > within {
+                    continue;
+                }
+                let remind_days = billing.remind_days_before.unwrap_or(DEFAULT_REMIND_DAYS);
+                renewals.push(UpcomingRenewal {
+                    app_type: app_type.clone(),
+                    provider_id: id.clone(),
+                    provider_name: provider.name.clone(),
+                    cycle: billing.cycle,
+                    renewal_date: date.format("%Y-%m-%d").to_string(),
+                    days_left,
+                    monthly_cost: billing.monthly_cost,
+                    currency: billing.currency.clone(),
+                    remind: days_left <= i64::from(remind_days),
+                    summary: renewal_summary(locale, &provider.name, billing, date, days_left),
+                });
+            }
+        }
+        renewals.sort_by(|a, b| {
+            a.days_left
+                .cmp(&b.days_left)
+                .then_with(|| a.provider_name.cmp(&b.provider_name))
+        });
+        Ok(renewals)
+    }
+}
+
+/// `Relay A 将于 2024年1月31日（3 天后）续费，每月 ¥20.00`
+fn renewal_summary(
+    locale: ReportLocale,
+    name: &str,
+    billing: &ProviderBilling,
+    date: NaiveDate,
+    days_left: i64,
+) -> String {
+    let when = locale.date(date);
+    let days = locale.days_left(days_left);
+    let cost = billing
+        .monthly_cost
+        .map(|cost| locale.currency(cost, billing.currency.as_deref()));
+    match (locale, cost) {
+        (ReportLocale::Zh, Some(cost)) => format!("{name} 将于 {when}（{days}）续费，每月 {cost}"),
+        (ReportLocale::Zh, None) => format!("{name} 将于 {when}（{days}）续费"),
+        (ReportLocale::En, Some(cost)) => {
+            format!("{name} renews on {when} ({days}), {cost}/month")
+        }
+        (ReportLocale::En, None) => format!("{name} renews on {when} ({days})"),
+    }
+}
+
+/// `today` 当天或之后的下一次续费日期（按周期从设定的续费日期顺延，月末日期按当月最后一天计）
+pub fn next_renewal(billing: &ProviderBilling, today: NaiveDate) -> Option<NaiveDate> {
+    let raw = billing.renewal_date.as_deref()?.trim();
+    let anchor = match NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(e) => {
+            log::warn!("无效的续费日期 '{raw}': {e}");
+            return None;
+        }
+    };
+    let step = billing.cycle.months();
+    (0..=MAX_CYCLES)
+        .map_while(|n| anchor.checked_add_months(Months::new(n * step)))
+        .find(|date| *date >= today)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn rolls_renewal_forward_by_cycle() {
+        let mut billing = ProviderBilling {
+            renewal_date: Some("2024-01-31".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            next_renewal(&billing, date("2024-01-15")),
+            Some(date("2024-01-31"))
+        );
+        // 月末日期不会因经过二月而永久变为 28/29 日
+        assert_eq!(
+            next_renewal(&billing, date("2024-02-10")),
+            Some(date("2024-02-29"))
+        );
+        assert_eq!(
+            next_renewal(&billing, date("2024-03-01")),
+            Some(date("2024-03-31"))
+        );
+
+        billing.cycle = BillingCycle::Yearly;
+        assert_eq!(
+            next_renewal(&billing, date("2025-06-01")),
+            Some(date("2026-01-31"))
+        );
+
+        billing.renewal_date = Some("31/01/2024".into());
+        assert_eq!(next_renewal(&billing, date("2024-01-01")), None);
+    }
+}
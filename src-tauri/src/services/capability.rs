@@ -0,0 +1,306 @@
+use std::time::Duration;
+
+use futures::future::join_all;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const MAX_TIMEOUT_SECS: u64 = 30;
+const MIN_TIMEOUT_SECS: u64 = 2;
+
+/// 可探测的 API 协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiProtocol {
+    /// Anthropic Messages API（`/v1/messages`）
+    Anthropic,
+    /// OpenAI Chat Completions（`/v1/chat/completions`）
+    OpenaiChat,
+    /// OpenAI Responses（`/v1/responses`）
+    OpenaiResponses,
+}
+
+/// 单个路由的探测结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteProbe {
+    pub protocol: ApiProtocol,
+    pub url: String,
+    /// 路由存在（返回 JSON 且不是 404/405）
+    pub supported: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 推荐的供应商配置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilitySuggestion {
+    /// 推荐的应用类型（claude / codex）
+    pub app: String,
+    /// Codex 的 `wire_api`（responses / chat）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wire_api: Option<String>,
+    /// 可直接用于新建供应商的 settingsConfig
+    pub settings_config: Value,
+}
+
+/// 端点能力探测报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilityReport {
+    pub base_url: String,
+    pub probes: Vec<RouteProbe>,
+    /// 按推荐程度排序（Anthropic 优先，其次 Responses，最后 Chat）
+    pub suggestions: Vec<CapabilitySuggestion>,
+}
+
+/// 探测未知中转站支持的 API 协议，生成对应的供应商配置模板
+///
+/// 探测请求使用空消息等无效参数，只用于判断路由是否存在，不会产生计费调用
+pub struct CapabilityService;
+
+impl CapabilityService {
+    pub async fn probe(
+        base_url: &str,
+        api_key: &str,
+        timeout_secs: Option<u64>,
+    ) -> Result<CapabilityReport, AppError> {
+        let root = api_root(base_url)?;
+        let client = build_client(timeout_secs)?;
+
+        let protocols = [
+            ApiProtocol::Anthropic,
+            ApiProtocol::OpenaiResponses,
+            ApiProtocol::OpenaiChat,
+        ];
+        let probes =
+            join_all(protocols.iter().map(|protocol| {
+                probe_route(&client, route_url(&root, *protocol), api_key, *protocol)
+            }))
+            .await;
+
+        let supported = |protocol| {
+            probes
+                .iter()
+                .any(|probe| probe.protocol == protocol && probe.supported)
+        };
+        let mut suggestions = Vec::new();
+        if supported(ApiProtocol::Anthropic) {
+            suggestions.push(CapabilitySuggestion {
+                app: "claude".to_string(),
+                wire_api: None,
+                settings_config: json!({
+                    "env": {
+                        "ANTHROPIC_BASE_URL": root,
+                        "ANTHROPIC_AUTH_TOKEN": api_key,
+                    }
+                }),
+            });
+        }
+        for (protocol, wire_api) in [
+            (ApiProtocol::OpenaiResponses, "responses"),
+            (ApiProtocol::OpenaiChat, "chat"),
+        ] {
+            if supported(protocol) {
+                suggestions.push(codex_suggestion(&root, api_key, wire_api));
+            }
+        }
+
+        Ok(CapabilityReport {
+            base_url: root,
+            probes,
+            suggestions,
+        })
+    }
+
+    /// 判断 Codex 端点应使用的 `wire_api`：支持 Responses 时为 `responses`，仅支持 Chat 时为 `chat`
+    ///
+    /// `base_url` 为 Codex config.toml 中的 base_url，与 Codex 一致直接拼接 `/responses`、`/chat/completions`；
+    /// 两者均不支持时返回 None
+    pub async fn detect_codex_wire_api(
+        base_url: &str,
+        api_key: &str,
+    ) -> Result<Option<&'static str>, AppError> {
+        // 复用根地址校验，实际探测使用原始 base_url
+        api_root(base_url)?;
+        let base = base_url.trim().trim_end_matches('/');
+        let client = build_client(None)?;
+        let (responses, chat) = futures::join!(
+            probe_route(
+                &client,
+                format!("{base}/responses"),
+                api_key,
+                ApiProtocol::OpenaiResponses
+            ),
+            probe_route(
+                &client,
+                format!("{base}/chat/completions"),
+                api_key,
+                ApiProtocol::OpenaiChat
+            ),
+        );
+        Ok(if responses.supported {
+            Some("responses")
+        } else if chat.supported {
+            Some("chat")
+        } else {
+            None
+        })
+    }
+}
+
+fn codex_suggestion(root: &str, api_key: &str, wire_api: &str) -> CapabilitySuggestion {
+    let config =
+        crate::deeplink::codex_config_toml("custom", None, &format!("{root}/v1"), wire_api);
+    CapabilitySuggestion {
+        app: "codex".to_string(),
+        wire_api: Some(wire_api.to_string()),
+        settings_config: json!({
+            "auth": { "OPENAI_API_KEY": api_key },
+            "config": config,
+        }),
+    }
+}
+
+/// 规范化为不含 `/v1` 的根地址（Claude 使用根地址，Codex 使用 `<根地址>/v1`）
+fn api_root(base_url: &str) -> Result<String, AppError> {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    let url = reqwest::Url::parse(trimmed).map_err(|e| {
+        AppError::localized(
+            "capability.invalid_url",
+            format!("URL 无效: {e}"),
+            format!("Invalid URL: {e}"),
+        )
+    })?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::localized(
+            "capability.invalid_url",
+            format!("URL 必须以 http:// 或 https:// 开头: {trimmed}"),
+            format!("URL must start with http:// or https://: {trimmed}"),
+        ));
+    }
+    Ok(trimmed.strip_suffix("/v1").unwrap_or(trimmed).to_string())
+}
+
+fn route_url(root: &str, protocol: ApiProtocol) -> String {
+    match protocol {
+        ApiProtocol::Anthropic => format!("{root}/v1/messages"),
+        ApiProtocol::OpenaiChat => format!("{root}/v1/chat/completions"),
+        ApiProtocol::OpenaiResponses => format!("{root}/v1/responses"),
+    }
+}
+
+async fn probe_route(
+    client: &Client,
+    url: String,
+    api_key: &str,
+    protocol: ApiProtocol,
+) -> RouteProbe {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&format!("Bearer {api_key}")) {
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+    // 空消息 / 空输入均为无效请求，支持该协议的服务会返回 400 而不会实际调用模型
+    let body = match protocol {
+        ApiProtocol::Anthropic => {
+            if let Ok(value) = HeaderValue::from_str(api_key) {
+                headers.insert("x-api-key", value);
+            }
+            headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
+            json!({ "model": "claude-3-5-haiku-latest", "max_tokens": 1, "messages": [] })
+        }
+        ApiProtocol::OpenaiChat => {
+            json!({ "model": "gpt-4o-mini", "max_tokens": 1, "messages": [] })
+        }
+        ApiProtocol::OpenaiResponses => {
+            json!({ "model": "gpt-4o-mini", "max_output_tokens": 1, "input": [] })
+        }
+    };
+
+    match client.post(&url).headers(headers).json(&body).send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            let is_json = resp
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.contains("json"));
+            RouteProbe {
+                protocol,
+                url,
+                supported: route_supported(status, is_json),
+                status: Some(status.as_u16()),
+                error: None,
+            }
+        }
+        Err(err) => RouteProbe {
+            protocol,
+            url,
+            supported: false,
+            status: None,
+            error: Some(if err.is_timeout() {
+                "请求超时".to_string()
+            } else if err.is_connect() {
+                "连接失败".to_string()
+            } else {
+                err.to_string()
+            }),
+        },
+    }
+}
+
+/// 路由存在的判断：返回 JSON 且不是 404/405（中转站的兜底页面通常返回 HTML）
+fn route_supported(status: StatusCode, is_json: bool) -> bool {
+    is_json
+        && status != StatusCode::NOT_FOUND
+        && status != StatusCode::METHOD_NOT_ALLOWED
+        && !status.is_server_error()
+}
+
+fn build_client(timeout_secs: Option<u64>) -> Result<Client, AppError> {
+    let secs = timeout_secs
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS);
+    Client::builder()
+        .timeout(Duration::from_secs(secs))
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .user_agent("cc-switch-probe/1.0")
+        .build()
+        .map_err(|e| {
+            AppError::localized(
+                "capability.client_create_failed",
+                format!("创建 HTTP 客户端失败: {e}"),
+                format!("Failed to create HTTP client: {e}"),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_root_and_judges_routes() {
+        assert_eq!(
+            api_root("https://relay.example.com/v1/").unwrap(),
+            "https://relay.example.com"
+        );
+        assert_eq!(
+            api_root("https://relay.example.com/api").unwrap(),
+            "https://relay.example.com/api"
+        );
+        assert!(api_root("ftp://relay.example.com").is_err());
+
+        assert!(route_supported(StatusCode::BAD_REQUEST, true));
+        assert!(route_supported(StatusCode::UNAUTHORIZED, true));
+        assert!(!route_supported(StatusCode::NOT_FOUND, true));
+        assert!(!route_supported(StatusCode::OK, false));
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::{Provider, ProviderBilling, ProviderPricing};
+use crate::services::passcode::mask_secrets;
+use crate::services::{ProviderService, SpeedtestService};
+use crate::settings;
+use crate::store::AppState;
+
+/// 各应用需要对比的模型字段：（角色，环境变量 / 配置键）
+const CLAUDE_MODEL_KEYS: &[(&str, &str)] = &[
+    ("main", "ANTHROPIC_MODEL"),
+    ("haiku", "ANTHROPIC_DEFAULT_HAIKU_MODEL"),
+    ("sonnet", "ANTHROPIC_DEFAULT_SONNET_MODEL"),
+    ("opus", "ANTHROPIC_DEFAULT_OPUS_MODEL"),
+    ("small_fast", "ANTHROPIC_SMALL_FAST_MODEL"),
+];
+const CODEX_MODEL_KEYS: &[(&str, &str)] = &[
+    ("main", "model"),
+    ("reasoning_effort", "model_reasoning_effort"),
+];
+const GEMINI_MODEL_KEYS: &[(&str, &str)] = &[("main", "GEMINI_MODEL")];
+
+/// 字段差异类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    /// 仅 B 存在
+    Added,
+    /// 仅 A 存在
+    Removed,
+    Changed,
+    Same,
+}
+
+/// 单个字段的对比（密钥类字段已掩码）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDiff {
+    /// 字段路径（如 `env.ANTHROPIC_BASE_URL`、`config.model_providers.x.base_url`）
+    pub path: String,
+    pub kind: DiffKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b: Option<Value>,
+}
+
+/// 对比视图中的供应商概要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparedProvider {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// 自定义端点（不含主端点）
+    pub custom_endpoints: Vec<String>,
+    /// 最近一次成功测速的延迟（毫秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ProviderPricing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing: Option<ProviderBilling>,
+}
+
+/// 两个供应商的结构化对比结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderComparison {
+    pub app_type: AppType,
+    pub a: ComparedProvider,
+    pub b: ComparedProvider,
+    /// settingsConfig 中取值不同的字段
+    pub settings: Vec<FieldDiff>,
+    /// 模型配置（按角色列出，包括相同项）
+    pub models: Vec<FieldDiff>,
+    /// 主端点与自定义端点对比
+    pub endpoints: Vec<FieldDiff>,
+    /// 延迟更低的一方（`a` / `b`；任一未测速时为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub faster: Option<&'static str>,
+    /// 价格信息对比（包括相同项）
+    pub pricing: Vec<FieldDiff>,
+    /// 按输出价格（换算为同一货币后）更便宜的一方
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cheaper: Option<&'static str>,
+}
+
+/// 供应商对比（同一应用下的两个供应商）
+pub struct CompareService;
+
+impl CompareService {
+    pub fn compare_providers(
+        state: &AppState,
+        app_type: AppType,
+        id_a: &str,
+        id_b: &str,
+    ) -> Result<ProviderComparison, AppError> {
+        let (provider_a, provider_b) = {
+            let config = state.config.read().map_err(AppError::from)?;
+            let manager = config
+                .get_manager(&app_type)
+                .ok_or_else(|| ProviderService::app_not_found(&app_type))?;
+            let find = |id: &str| {
+                manager.providers.get(id).cloned().ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {id}"),
+                        format!("Provider not found: {id}"),
+                    )
+                })
+            };
+            (find(id_a)?, find(id_b)?)
+        };
+
+        let a = Self::summary(&app_type, &provider_a);
+        let b = Self::summary(&app_type, &provider_b);
+
+        let settings = diff_maps(
+            &flatten_settings(&app_type, &provider_a),
+            &flatten_settings(&app_type, &provider_b),
+        )
+        .into_iter()
+        .filter(|diff| diff.kind != DiffKind::Same)
+        .collect();
+
+        let models = model_keys(&app_type)
+            .iter()
+            .filter_map(|(role, key)| {
+                let diff = field_diff(
+                    role,
+                    model_value(&app_type, &provider_a, key),
+                    model_value(&app_type, &provider_b, key),
+                );
+                (diff.a.is_some() || diff.b.is_some()).then_some(diff)
+            })
+            .collect();
+
+        let mut endpoints = vec![field_diff(
+            "base_url",
+            a.base_url.clone().map(Value::String),
+            b.base_url.clone().map(Value::String),
+        )];
+        endpoints.push(field_diff(
+            "custom_endpoints",
+            Some(Value::from(a.custom_endpoints.clone())),
+            Some(Value::from(b.custom_endpoints.clone())),
+        ));
+
+        let faster = match (a.latency, b.latency) {
+            (Some(la), Some(lb)) if la < lb => Some("a"),
+            (Some(la), Some(lb)) if lb < la => Some("b"),
+            _ => None,
+        };
+
+        let pricing = diff_maps(&flatten_pricing(&a.pricing), &flatten_pricing(&b.pricing));
+        let cheaper = cheaper_side(a.pricing.as_ref(), b.pricing.as_ref());
+
+        Ok(ProviderComparison {
+            app_type,
+            a,
+            b,
+            settings,
+            models,
+            endpoints,
+            faster,
+            pricing,
+            cheaper,
+        })
+    }
+
+    fn summary(app_type: &AppType, provider: &Provider) -> ComparedProvider {
+        let base_url = ProviderService::provider_base_url(app_type, provider);
+        let latency = base_url
+            .as_deref()
+            .and_then(SpeedtestService::latest_latency);
+        let mut custom_endpoints: Vec<String> = provider
+            .meta
+            .as_ref()
+            .map(|meta| meta.custom_endpoints.keys().cloned().collect())
+            .unwrap_or_default();
+        custom_endpoints.sort();
+        ComparedProvider {
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            category: provider.category.clone(),
+            base_url,
+            custom_endpoints,
+            latency,
+            pricing: provider.meta.as_ref().and_then(|meta| meta.pricing.clone()),
+            billing: provider.meta.as_ref().and_then(|meta| meta.billing.clone()),
+        }
+    }
+}
+
+fn model_keys(app_type: &AppType) -> &'static [(&'static str, &'static str)] {
+    match app_type {
+        AppType::Claude => CLAUDE_MODEL_KEYS,
+        AppType::Codex => CODEX_MODEL_KEYS,
+        AppType::Gemini => GEMINI_MODEL_KEYS,
+    }
+}
+
+fn model_value(app_type: &AppType, provider: &Provider, key: &str) -> Option<Value> {
+    let settings = &provider.settings_config;
+    match app_type {
+        AppType::Codex => {
+            let text = settings.get("config")?.as_str()?;
+            let table: toml::Table = toml::from_str(text).ok()?;
+            table.get(key)?.as_str().map(Value::from)
+        }
+        AppType::Gemini => {
+            let env = settings.get("env")?;
+            env.get(key)
+                .or_else(|| env.get("GOOGLE_GEMINI_MODEL"))
+                .cloned()
+        }
+        AppType::Claude => settings.get("env")?.get(key).cloned(),
+    }
+}
+
+/// 展开 settingsConfig 为「路径 → 值」（Codex 的 config.toml 解析后按键展开），密钥字段掩码
+fn flatten_settings(app_type: &AppType, provider: &Provider) -> BTreeMap<String, (Value, Value)> {
+    let mut raw = provider.settings_config.clone();
+    if matches!(app_type, AppType::Codex) {
+        if let Some(text) = raw.get("config").and_then(Value::as_str) {
+            if let Ok(table) = toml::from_str::<toml::Table>(text) {
+                if let Ok(parsed) = serde_json::to_value(table) {
+                    raw["config"] = parsed;
+                }
+            }
+        }
+    }
+    let mut masked = raw.clone();
+    mask_secrets(&mut masked);
+
+    let mut raw_flat = BTreeMap::new();
+    flatten("", &raw, &mut raw_flat);
+    let mut masked_flat = BTreeMap::new();
+    flatten("", &masked, &mut masked_flat);
+    raw_flat
+        .into_iter()
+        .map(|(path, value)| {
+            let shown = masked_flat.remove(&path).unwrap_or(Value::Null);
+            (path, (value, shown))
+        })
+        .collect()
+}
+
+fn flatten_pricing(pricing: &Option<ProviderPricing>) -> BTreeMap<String, (Value, Value)> {
+    let mut flat = BTreeMap::new();
+    if let Some(value) = pricing.as_ref().and_then(|p| serde_json::to_value(p).ok()) {
+        flatten("", &value, &mut flat);
+    }
+    flat.into_iter()
+        .map(|(path, value)| (path, (value.clone(), value)))
+        .collect()
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&path, child, out);
+            }
+        }
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+/// 按原始值比较、按展示值（掩码后）输出
+fn diff_maps(
+    a: &BTreeMap<String, (Value, Value)>,
+    b: &BTreeMap<String, (Value, Value)>,
+) -> Vec<FieldDiff> {
+    let mut paths: Vec<&String> = a.keys().chain(b.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .map(|path| {
+            let left = a.get(path);
+            let right = b.get(path);
+            let kind = match (left, right) {
+                (Some((ra, _)), Some((rb, _))) if ra == rb => DiffKind::Same,
+                (Some(_), Some(_)) => DiffKind::Changed,
+                (Some(_), None) => DiffKind::Removed,
+                _ => DiffKind::Added,
+            };
+            FieldDiff {
+                path: path.clone(),
+                kind,
+                a: left.map(|(_, shown)| shown.clone()),
+                b: right.map(|(_, shown)| shown.clone()),
+            }
+        })
+        .collect()
+}
+
+fn field_diff(path: &str, a: Option<Value>, b: Option<Value>) -> FieldDiff {
+    let kind = match (&a, &b) {
+        (Some(va), Some(vb)) if va == vb => DiffKind::Same,
+        (Some(_), Some(_)) => DiffKind::Changed,
+        (Some(_), None) => DiffKind::Removed,
+        (None, Some(_)) => DiffKind::Added,
+        (None, None) => DiffKind::Same,
+    };
+    FieldDiff {
+        path: path.to_string(),
+        kind,
+        a,
+        b,
+    }
+}
+
+/// 比较输出价格；货币不同时按设置中的汇率换算，无法换算则不给出结论
+fn cheaper_side(a: Option<&ProviderPricing>, b: Option<&ProviderPricing>) -> Option<&'static str> {
+    let (a, b) = (a?, b?);
+    let (price_a, price_b) = (a.output_per_million?, b.output_per_million?);
+    let price_b = match (a.currency.as_deref(), b.currency.as_deref()) {
+        (Some(ca), Some(cb)) => {
+            price_b * crate::usage_normalize::conversion_rate(cb, ca, &settings::get_settings())?
+        }
+        _ => price_b,
+    };
+    if price_a < price_b {
+        Some("a")
+    } else if price_b < price_a {
+        Some("b")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn codex(id: &str, config: &str, key: &str) -> Provider {
+        Provider::with_id(
+            id.to_string(),
+            id.to_string(),
+            json!({ "auth": { "OPENAI_API_KEY": key }, "config": config }),
+            None,
+        )
+    }
+
+    #[test]
+    fn settings_diff_expands_codex_toml_and_masks_secrets() {
+        let a = codex(
+            "a",
+            "model = \"gpt-5\"\nbase_url = \"https://a\"",
+            "sk-aaaaaaaaaaaa1111",
+        );
+        let b = codex(
+            "b",
+            "model = \"gpt-5\"\nbase_url = \"https://b\"",
+            "sk-bbbbbbbbbbbb2222",
+        );
+
+        let diffs: Vec<FieldDiff> = diff_maps(
+            &flatten_settings(&AppType::Codex, &a),
+            &flatten_settings(&AppType::Codex, &b),
+        )
+        .into_iter()
+        .filter(|diff| diff.kind != DiffKind::Same)
+        .collect();
+        let paths: Vec<&str> = diffs.iter().map(|diff| diff.path.as_str()).collect();
+        assert_eq!(paths, vec!["auth.OPENAI_API_KEY", "config.base_url"]);
+
+        let key_diff = &diffs[0];
+        assert_eq!(key_diff.kind, DiffKind::Changed);
+        let shown = key_diff.a.as_ref().and_then(Value::as_str).unwrap();
+        assert!(shown.contains("****") && !shown.contains("aaaaaaaaaaaa"));
+
+        assert_eq!(
+            model_value(&AppType::Codex, &a, "model"),
+            Some(Value::from("gpt-5"))
+        );
+    }
+
+    #[test]
+    fn cheaper_side_compares_output_price() {
+        let pricing = |price: f64| ProviderPricing {
+            output_per_million: Some(price),
+            ..Default::default()
+        };
+        assert_eq!(
+            cheaper_side(Some(&pricing(3.0)), Some(&pricing(5.0))),
+            Some("a")
+        );
+        assert_eq!(cheaper_side(Some(&pricing(3.0)), None), None);
+    }
+}
@@ -1,14 +1,98 @@
-use super::provider::ProviderService;
+use super::provider::{LiveSnapshot, ProviderService};
 use crate::app_config::{AppType, MultiAppConfig};
 use crate::error::AppError;
 use crate::provider::Provider;
+use crate::report_format::ReportLocale;
 use crate::store::AppState;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+pub(crate) const MAX_BACKUPS: usize = 10;
+/// 备份目录总体积上限（含子代理快照）
+pub(crate) const MAX_BACKUP_DIR_BYTES: u64 = 100 * 1024 * 1024;
+
+/// live 同步涉及的应用（各应用写入互不相关的文件，可并行执行）
+const LIVE_SYNC_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+/// 单个应用的 live 同步结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSyncResult {
+    pub app: AppType,
+    /// 已同步的当前供应商（未设置当前供应商时为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 从 URL 下载的配置文件大小上限
+const MAX_REMOTE_CONFIG_BYTES: usize = 5 * 1024 * 1024;
+/// 下载配置文件的超时时间
+const REMOTE_CONFIG_TIMEOUT_SECS: u64 = 30;
+
+/// 导入配置时与当前配置的合并方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportMergeStrategy {
+    /// 以导入的配置整体替换当前配置
+    #[default]
+    Replace,
+    /// 合并供应商、MCP 服务器与斜杠命令，ID 相同时以导入的为准
+    Merge,
+    /// 合并供应商、MCP 服务器与斜杠命令，仅添加本地不存在的 ID
+    KeepExisting,
+}
+
+/// config.json 备份的摘要（解析自备份文件）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupDetail {
+    /// 备份 ID（文件名，不含扩展名）
+    pub id: String,
+    /// 备份时间（文件修改时间，毫秒时间戳）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    /// 占用字节数（含子代理快照）
+    pub size: u64,
+    /// 按界面语言格式化的大小与备份时间
+    pub size_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at_text: Option<String>,
+    /// 备份中的配置版本（`version` 字段）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_version: Option<u64>,
+    /// 各应用的供应商数量
+    pub provider_counts: std::collections::BTreeMap<String, usize>,
+    /// 各应用的当前供应商
+    pub current_providers: std::collections::BTreeMap<String, String>,
+    pub mcp_server_count: usize,
+    /// 备份文件无法解析时的错误
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
 
-const MAX_BACKUPS: usize = 10;
+/// live 检查点摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveCheckpoint {
+    pub id: String,
+    pub label: String,
+    /// 创建时间（毫秒时间戳）
+    pub created_at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LiveCheckpointFile {
+    #[serde(flatten)]
+    info: LiveCheckpoint,
+    snapshots: Vec<LiveSnapshot>,
+}
 
 /// 配置导入导出相关业务逻辑
 pub struct ConfigService;
@@ -34,6 +118,12 @@ impl ConfigService {
         let contents = fs::read(config_path).map_err(|e| AppError::io(config_path, e))?;
         fs::write(&backup_path, contents).map_err(|e| AppError::io(&backup_path, e))?;
 
+        // 子代理定义不在 config.json 中，单独快照到同名目录
+        let agents_backup = backup_dir.join(format!("{backup_id}_agents"));
+        if let Err(err) = super::AgentService::backup_to(&agents_backup) {
+            log::warn!("备份子代理失败: {err}");
+        }
+
         Self::cleanup_old_backups(&backup_dir, MAX_BACKUPS)?;
 
         Ok(backup_id)
@@ -72,6 +162,12 @@ impl ConfigService {
         });
 
         for entry in sorted.into_iter().take(remove_count) {
+            if let Some(stem) = entry.path().file_stem() {
+                let agents_dir = backup_dir.join(format!("{}_agents", stem.to_string_lossy()));
+                if agents_dir.is_dir() {
+                    let _ = fs::remove_dir_all(&agents_dir);
+                }
+            }
             if let Err(err) = fs::remove_file(entry.path()) {
                 log::warn!(
                     "Failed to remove old backup {}: {}",
@@ -84,6 +180,193 @@ impl ConfigService {
         Ok(())
     }
 
+    /// 备份目录（`~/.cc-switch/backups`）
+    pub fn backup_dir() -> PathBuf {
+        crate::config::get_app_config_dir().join("backups")
+    }
+
+    /// 创建缺失的备份目录
+    pub fn ensure_backup_dir() -> Result<PathBuf, AppError> {
+        let dir = Self::backup_dir();
+        fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+        Ok(dir)
+    }
+
+    /// 备份数量及总字节数（含子代理快照）
+    pub fn backup_usage() -> (usize, u64) {
+        let backups = Self::list_backups(&Self::backup_dir());
+        (backups.len(), backups.iter().map(|(_, size)| size).sum())
+    }
+
+    /// 备份数量或体积超出上限时删除最旧的备份（至少保留最新一份），返回删除数量
+    pub fn trim_backups() -> Result<usize, AppError> {
+        let dir = Self::backup_dir();
+        let backups = Self::list_backups(&dir);
+
+        let mut retain = 0;
+        let mut total = 0;
+        for (_, size) in &backups {
+            if retain >= MAX_BACKUPS || (retain > 0 && total + size > MAX_BACKUP_DIR_BYTES) {
+                break;
+            }
+            retain += 1;
+            total += size;
+        }
+
+        Self::cleanup_old_backups(&dir, retain)?;
+        Ok(backups.len() - Self::list_backups(&dir).len())
+    }
+
+    /// 分页列出备份（新到旧）及其摘要；只读取并解析当前页的备份文件
+    pub fn list_backups_detailed(offset: usize, limit: usize) -> crate::pagination::Page {
+        let backups = Self::list_backups(&Self::backup_dir());
+        let total = backups.len();
+        let limit = limit.clamp(1, crate::pagination::MAX_PAGE_LIMIT);
+        let locale = ReportLocale::current();
+        let items = backups
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(path, size)| backup_detail(path, *size, locale))
+            .filter_map(|detail| serde_json::to_value(detail).ok())
+            .collect();
+        crate::pagination::Page {
+            items,
+            total,
+            offset,
+            limit,
+            has_more: offset.saturating_add(limit) < total,
+        }
+    }
+
+    /// 列出备份（新到旧）及其占用字节数
+    fn list_backups(backup_dir: &Path) -> Vec<(PathBuf, u64)> {
+        let Ok(entries) = fs::read_dir(backup_dir) else {
+            return Vec::new();
+        };
+        let mut backups: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok()?;
+                let agents_dir = path
+                    .file_stem()
+                    .map(|stem| backup_dir.join(format!("{}_agents", stem.to_string_lossy())));
+                let size = metadata.len() + agents_dir.map_or(0, |dir| dir_size(&dir));
+                Some((path, metadata.modified().ok(), size))
+            })
+            .collect();
+        backups.sort_by_key(|(_, modified, _)| std::cmp::Reverse(*modified));
+        backups
+            .into_iter()
+            .map(|(path, _, size)| (path, size))
+            .collect()
+    }
+
+    /// live 检查点目录（`~/.cc-switch/backups/checkpoints`，不参与 config.json 备份轮转）
+    fn checkpoint_dir() -> PathBuf {
+        Self::backup_dir().join("checkpoints")
+    }
+
+    fn checkpoint_path(id: &str) -> Result<PathBuf, AppError> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        let path = Self::checkpoint_dir().join(format!("{id}.json"));
+        if !valid || !path.exists() {
+            return Err(AppError::localized(
+                "checkpoint.not_found",
+                format!("live 检查点不存在: {id}"),
+                format!("Live checkpoint not found: {id}"),
+            ));
+        }
+        Ok(path)
+    }
+
+    /// 将三个应用当前的 live 配置文件（含登录凭据）保存为命名检查点，与供应商无关
+    pub fn create_live_checkpoint(label: &str) -> Result<LiveCheckpoint, AppError> {
+        let snapshots = LIVE_SYNC_APPS
+            .iter()
+            .map(ProviderService::capture_live_snapshot)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let dir = Self::checkpoint_dir();
+        fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+        let base_id = format!("checkpoint_{}", Utc::now().format("%Y%m%d_%H%M%S"));
+        let mut id = base_id.clone();
+        let mut suffix = 1;
+        while dir.join(format!("{id}.json")).exists() {
+            suffix += 1;
+            id = format!("{base_id}_{suffix}");
+        }
+
+        let info = LiveCheckpoint {
+            id,
+            label: label.trim().to_string(),
+            created_at: Utc::now().timestamp_millis(),
+        };
+        let path = dir.join(format!("{}.json", info.id));
+        crate::config::write_json_file(
+            &path,
+            &LiveCheckpointFile {
+                info: info.clone(),
+                snapshots,
+            },
+        )?;
+        // 检查点包含登录凭据
+        super::shell_wrapper::restrict_permissions(&path, 0o600);
+        Ok(info)
+    }
+
+    /// 列出 live 检查点（新到旧）
+    pub fn list_live_checkpoints() -> Vec<LiveCheckpoint> {
+        let Ok(entries) = fs::read_dir(Self::checkpoint_dir()) else {
+            return Vec::new();
+        };
+        let mut checkpoints: Vec<LiveCheckpoint> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                crate::config::read_json_file::<LiveCheckpointFile>(&path)
+                    .map_err(|e| log::warn!("读取 live 检查点失败: {e}"))
+                    .ok()
+            })
+            .map(|file| file.info)
+            .collect();
+        checkpoints.sort_by_key(|checkpoint| std::cmp::Reverse(checkpoint.created_at));
+        checkpoints
+    }
+
+    /// 将检查点中的 live 配置写回；任一应用写入失败时恢复所有应用到写回前的状态
+    pub fn restore_live_checkpoint(id: &str) -> Result<(), AppError> {
+        let file: LiveCheckpointFile = crate::config::read_json_file(&Self::checkpoint_path(id)?)?;
+        let current = LIVE_SYNC_APPS
+            .iter()
+            .map(ProviderService::capture_live_snapshot)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for snapshot in &file.snapshots {
+            if let Err(err) = snapshot.restore() {
+                for previous in &current {
+                    if let Err(rollback_err) = previous.restore() {
+                        log::error!("回滚 live 配置失败: {rollback_err}");
+                    }
+                }
+                return Err(err);
+            }
+        }
+        log::info!("已恢复 live 检查点 {id}");
+        Ok(())
+    }
+
+    /// 删除 live 检查点
+    pub fn delete_live_checkpoint(id: &str) -> Result<(), AppError> {
+        crate::config::delete_file(&Self::checkpoint_path(id)?)
+    }
+
     /// 将当前 config.json 拷贝到目标路径。
     pub fn export_config_to_path(target_path: &Path) -> Result<(), AppError> {
         let config_path = crate::config::get_app_config_path();
@@ -92,22 +375,154 @@ impl ConfigService {
         fs::write(target_path, config_content).map_err(|e| AppError::io(target_path, e))
     }
 
+    /// 导出去除密钥的配置模板（密钥替换为 `{{CLAUDE_KEY_1}}` 等占位符），返回模板所需的密钥清单
+    pub fn export_config_template(
+        state: &AppState,
+        target_path: &Path,
+    ) -> Result<Vec<crate::config_template::TemplateSecret>, AppError> {
+        let template = {
+            let config = state.config.read().map_err(AppError::from)?;
+            crate::config_template::redact(&config)?
+        };
+        crate::config::write_json_file(target_path, &template)?;
+        Ok(template.secrets)
+    }
+
+    /// 用提供的密钥填充配置模板并替换当前配置（先备份），返回备份 ID
+    ///
+    /// 与导入配置相同，应用后由调用方决定是否同步 live 配置
+    pub fn apply_config_template(
+        state: &AppState,
+        template_path: &Path,
+        secrets: &HashMap<String, String>,
+    ) -> Result<String, AppError> {
+        let template: crate::config_template::ConfigTemplate =
+            crate::config::read_json_file(template_path)?;
+        let config = crate::config_template::rehydrate(template, secrets)?;
+        let backup_id = Self::create_backup(&crate::config::get_app_config_path())?;
+        {
+            let mut guard = state.config.write().map_err(AppError::from)?;
+            *guard = config;
+        }
+        state.save()?;
+        log::info!(
+            "已应用配置模板 {}（备份 {backup_id}）",
+            template_path.display()
+        );
+        Ok(backup_id)
+    }
+
     /// 从磁盘文件加载配置并写回 config.json，返回备份 ID 及新配置。
     pub fn load_config_for_import(file_path: &Path) -> Result<(MultiAppConfig, String), AppError> {
         let import_content =
             fs::read_to_string(file_path).map_err(|e| AppError::io(file_path, e))?;
+        Self::load_content_for_import(&import_content, file_path)
+    }
 
+    /// 校验配置内容并写回 config.json（写入前备份），返回新配置及备份 ID
+    ///
+    /// `source` 仅用于错误信息（文件路径或 URL）
+    fn load_content_for_import(
+        import_content: &str,
+        source: impl AsRef<Path>,
+    ) -> Result<(MultiAppConfig, String), AppError> {
         let new_config: MultiAppConfig =
-            serde_json::from_str(&import_content).map_err(|e| AppError::json(file_path, e))?;
+            serde_json::from_str(import_content).map_err(|e| AppError::json(source, e))?;
 
         let config_path = crate::config::get_app_config_path();
         let backup_id = Self::create_backup(&config_path)?;
 
-        fs::write(&config_path, &import_content).map_err(|e| AppError::io(&config_path, e))?;
+        fs::write(&config_path, import_content).map_err(|e| AppError::io(&config_path, e))?;
+        crate::config_integrity::record_checksum();
 
         Ok((new_config, backup_id))
     }
 
+    /// 从 URL 下载配置（仅 https，大小受限）并按 `strategy` 导入，返回备份 ID
+    ///
+    /// 与从文件导入相同，导入后由调用方决定是否同步 live 配置
+    pub async fn import_config_from_url(
+        state: &AppState,
+        url: &str,
+        strategy: ImportMergeStrategy,
+    ) -> Result<String, AppError> {
+        let url = url.trim();
+        if !url.starts_with("https://") {
+            return Err(AppError::localized(
+                "import.url_not_https",
+                format!("配置地址必须以 https:// 开头: {url}"),
+                format!("Config URL must start with https://: {url}"),
+            ));
+        }
+        let content = fetch_remote_config(url).await?;
+
+        if strategy == ImportMergeStrategy::Replace {
+            let (new_config, backup_id) = Self::load_content_for_import(&content, url)?;
+            let mut guard = state.config.write().map_err(AppError::from)?;
+            *guard = new_config;
+            return Ok(backup_id);
+        }
+
+        let imported: MultiAppConfig =
+            serde_json::from_str(&content).map_err(|e| AppError::json(url, e))?;
+        let backup_id = Self::create_backup(&crate::config::get_app_config_path())?;
+        {
+            let mut guard = state.config.write().map_err(AppError::from)?;
+            Self::merge_imported_config(&mut guard, imported, strategy);
+        }
+        state.save()?;
+        Ok(backup_id)
+    }
+
+    /// 将导入的配置合并到当前配置（`Replace` 时整体替换）
+    ///
+    /// 合并时只处理供应商、MCP 服务器与斜杠命令；当前供应商仅在本地未设置时采用导入的值
+    pub fn merge_imported_config(
+        config: &mut MultiAppConfig,
+        imported: MultiAppConfig,
+        strategy: ImportMergeStrategy,
+    ) {
+        let overwrite = match strategy {
+            ImportMergeStrategy::Replace => {
+                *config = imported;
+                return;
+            }
+            ImportMergeStrategy::Merge => true,
+            ImportMergeStrategy::KeepExisting => false,
+        };
+        fn merge_map<V>(
+            target: &mut std::collections::HashMap<String, V>,
+            source: std::collections::HashMap<String, V>,
+            overwrite: bool,
+        ) {
+            for (id, value) in source {
+                if overwrite || !target.contains_key(&id) {
+                    target.insert(id, value);
+                }
+            }
+        }
+
+        for (app, manager) in imported.apps {
+            let local = config.apps.entry(app).or_default();
+            if local.current.is_empty() || !local.providers.contains_key(&local.current) {
+                local.current = manager.current.clone();
+            }
+            merge_map(&mut local.providers, manager.providers, overwrite);
+        }
+        if let Some(servers) = imported.mcp.servers {
+            merge_map(
+                config.mcp.servers.get_or_insert_with(Default::default),
+                servers,
+                overwrite,
+            );
+        }
+        merge_map(
+            &mut config.slash_commands,
+            imported.slash_commands,
+            overwrite,
+        );
+    }
+
     /// 将外部配置文件内容加载并写入应用状态。
     pub fn import_config_from_path(file_path: &Path, state: &AppState) -> Result<String, AppError> {
         let (new_config, backup_id) = Self::load_config_for_import(file_path)?;
@@ -120,26 +535,155 @@ impl ConfigService {
         Ok(backup_id)
     }
 
+    /// 将配置恢复到历史版本（先备份当前 config.json），返回备份 ID
+    ///
+    /// 与导入配置相同，恢复后由调用方决定是否同步 live 配置
+    pub fn restore_version(state: &AppState, version_id: u64) -> Result<String, AppError> {
+        let restored = crate::config_history::snapshot(version_id)?;
+        let backup_id = Self::create_backup(&crate::config::get_app_config_path())?;
+        {
+            let mut guard = state.config.write().map_err(AppError::from)?;
+            *guard = restored;
+        }
+        state.save()?;
+        log::info!("已恢复配置历史版本 {version_id}（备份 {backup_id}）");
+        Ok(backup_id)
+    }
+
+    /// 放弃内存中的配置，重新加载磁盘上被外部修改的 config.json
+    pub fn reload_external(state: &AppState) -> Result<(), AppError> {
+        let _lock = crate::config_lock::acquire()?;
+        let reloaded = MultiAppConfig::load()?;
+        {
+            let mut guard = state.config.write().map_err(AppError::from)?;
+            *guard = reloaded;
+        }
+        crate::config_events::notify_changed();
+        log::info!("已重新加载外部修改的 config.json");
+        Ok(())
+    }
+
+    /// 以内存中的配置覆盖磁盘上被外部修改的 config.json（覆盖前备份外部版本），返回备份 ID
+    pub fn overwrite_external(state: &AppState) -> Result<String, AppError> {
+        let backup_id = Self::create_backup(&crate::config::get_app_config_path())?;
+        crate::config_integrity::accept_external_change()?;
+        state.save()?;
+        log::info!("已覆盖外部修改的 config.json（备份 {backup_id}）");
+        Ok(backup_id)
+    }
+
     /// 同步当前供应商到对应的 live 配置。
+    /// 任一应用同步失败时返回第一个错误（其他应用的同步结果仍会保留）
     pub fn sync_current_providers_to_live(config: &mut MultiAppConfig) -> Result<(), AppError> {
-        Self::sync_current_provider_for_app(config, &AppType::Claude)?;
-        Self::sync_current_provider_for_app(config, &AppType::Codex)?;
-        Self::sync_current_provider_for_app(config, &AppType::Gemini)?;
+        let _lock = crate::config_lock::acquire()?;
+        if let Some((_, err)) = Self::sync_live_concurrently(config).1.into_iter().next() {
+            return Err(err);
+        }
+        // 斜杠命令随配置导入，一并写回命令目录
+        super::SlashCommandService::sync_to_live(config)?;
         Ok(())
     }
 
+    /// 同 [`Self::sync_current_providers_to_live`]，但返回各应用的同步结果而非在首个错误处失败
+    pub fn sync_current_providers_to_live_report(
+        config: &mut MultiAppConfig,
+    ) -> Result<Vec<LiveSyncResult>, AppError> {
+        let _lock = crate::config_lock::acquire()?;
+        let (results, _) = Self::sync_live_concurrently(config);
+        super::SlashCommandService::sync_to_live(config)?;
+        Ok(results)
+    }
+
+    /// 每个应用在独立线程中基于配置副本写入 live 文件；成功的应用将快照合并回配置，
+    /// 失败的应用仅回滚自己的 live 文件，不影响其他应用
+    fn sync_live_concurrently(
+        config: &mut MultiAppConfig,
+    ) -> (Vec<LiveSyncResult>, Vec<(AppType, AppError)>) {
+        let outcomes: Vec<Result<(Option<String>, MultiAppConfig), AppError>> =
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = LIVE_SYNC_APPS
+                    .iter()
+                    .map(|app_type| {
+                        let mut local = config.clone();
+                        scope.spawn(move || {
+                            Self::sync_app_isolated(&mut local, app_type)
+                                .map(|provider_id| (provider_id, local))
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle.join().unwrap_or_else(|_| {
+                            Err(AppError::Message("live 同步线程异常退出".to_string()))
+                        })
+                    })
+                    .collect()
+            });
+
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+        for (app_type, outcome) in LIVE_SYNC_APPS.iter().zip(outcomes) {
+            match outcome {
+                Ok((provider_id, mut local)) => {
+                    if let Some(manager) = local.apps.remove(app_type.as_str()) {
+                        config.apps.insert(app_type.as_str().to_string(), manager);
+                    }
+                    results.push(LiveSyncResult {
+                        app: app_type.clone(),
+                        provider_id,
+                        success: true,
+                        error: None,
+                    });
+                }
+                Err(err) => {
+                    log::warn!("同步 {} 的 live 配置失败: {err}", app_type.as_str());
+                    let provider_id = config
+                        .get_manager(app_type)
+                        .map(|manager| manager.current.clone())
+                        .filter(|current| !current.is_empty());
+                    results.push(LiveSyncResult {
+                        app: app_type.clone(),
+                        provider_id,
+                        success: false,
+                        error: Some(err.to_string()),
+                    });
+                    errors.push((app_type.clone(), err));
+                }
+            }
+        }
+        (results, errors)
+    }
+
+    /// 同步单个应用；失败时恢复该应用同步前的 live 文件
+    fn sync_app_isolated(
+        config: &mut MultiAppConfig,
+        app_type: &AppType,
+    ) -> Result<Option<String>, AppError> {
+        let backup = ProviderService::capture_live_snapshot(app_type)?;
+        Self::sync_current_provider_for_app(config, app_type).inspect_err(|_| {
+            if let Err(rollback_err) = backup.restore() {
+                log::error!(
+                    "回滚 {} 的 live 配置失败: {rollback_err}",
+                    app_type.as_str()
+                );
+            }
+        })
+    }
+
+    /// 同步单个应用的当前供应商，返回已同步的供应商 ID（无当前供应商时为 None）
     fn sync_current_provider_for_app(
         config: &mut MultiAppConfig,
         app_type: &AppType,
-    ) -> Result<(), AppError> {
+    ) -> Result<Option<String>, AppError> {
         let (current_id, provider) = {
             let manager = match config.get_manager(app_type) {
                 Some(manager) => manager,
-                None => return Ok(()),
+                None => return Ok(None),
             };
 
             if manager.current.is_empty() {
-                return Ok(());
+                return Ok(None);
             }
 
             let current_id = manager.current.clone();
@@ -149,7 +693,7 @@ impl ConfigService {
                     log::warn!(
                         "当前应用 {app_type:?} 的供应商 {current_id} 不存在，跳过 live 同步"
                     );
-                    return Ok(());
+                    return Ok(None);
                 }
             };
             (current_id, provider)
@@ -161,7 +705,7 @@ impl ConfigService {
             AppType::Gemini => Self::sync_gemini_live(config, &current_id, &provider)?,
         }
 
-        Ok(())
+        Ok(Some(current_id))
     }
 
     fn sync_codex_live(
@@ -180,9 +724,12 @@ impl ConfigService {
                 "供应商 {provider_id} 的 Codex auth 配置必须是 JSON 对象"
             )));
         }
-        let cfg_text = settings.get("config").and_then(Value::as_str);
+        let cfg_text = ProviderService::codex_live_config_text(provider)?;
 
-        crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
+        crate::codex_config::write_codex_live_atomic(
+            &ProviderService::codex_live_auth(provider),
+            cfg_text.as_deref(),
+        )?;
         crate::mcp::sync_enabled_to_codex(config)?;
 
         let cfg_text_after = crate::codex_config::read_and_validate_codex_config_text()?;
@@ -212,9 +759,12 @@ impl ConfigService {
             fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
         }
 
-        write_json_file(&settings_path, &provider.settings_config)?;
+        let content = ProviderService::merge_claude_live_settings(provider)?;
+        write_json_file(&settings_path, &content)?;
 
-        let live_after = read_json_file::<serde_json::Value>(&settings_path)?;
+        let live_after = ProviderService::claude_snapshot_from_live(read_json_file::<
+            serde_json::Value,
+        >(&settings_path)?);
         if let Some(manager) = config.get_manager_mut(&AppType::Claude) {
             if let Some(target) = manager.providers.get_mut(provider_id) {
                 target.settings_config = live_after;
@@ -230,7 +780,7 @@ impl ConfigService {
         provider: &Provider,
     ) -> Result<(), AppError> {
         use crate::gemini_config::{
-            env_to_json, json_to_env, read_gemini_env, write_gemini_env_atomic,
+            env_to_json, json_to_env, read_gemini_managed_env, write_gemini_env_managed,
         };
 
         let env_path = crate::gemini_config::get_gemini_env_path();
@@ -240,38 +790,195 @@ impl ConfigService {
 
         // 转换 JSON 配置为 .env 格式
         let env_map = json_to_env(&provider.settings_config)?;
+        let managed = ProviderService::gemini_managed_env_keys(config);
 
-        // Google 官方（OAuth）: env 为空，写入空文件并设置安全标志后返回
+        // 合并写入：只覆盖托管键；Google 官方（OAuth）的 env 为空，仅清理托管键
+        write_gemini_env_managed(&env_map, &managed)?;
         if env_map.is_empty() {
-            write_gemini_env_atomic(&env_map)?;
             ProviderService::ensure_google_oauth_security_flag(provider)?;
+        } else {
+            // 非 OAuth：在必要时设置 Packycode 安全标志
+            ProviderService::ensure_packycode_security_flag(provider)?;
+        }
 
-            let live_after_env = read_gemini_env()?;
-            let live_after = env_to_json(&live_after_env);
+        // 读回实际写入的托管内容并更新到配置中
+        let live_after = env_to_json(&read_gemini_managed_env(&managed)?);
+        let mut keys: Vec<String> = env_map.keys().cloned().collect();
+        keys.sort();
 
-            if let Some(manager) = config.get_manager_mut(&AppType::Gemini) {
-                if let Some(target) = manager.providers.get_mut(provider_id) {
-                    target.settings_config = live_after;
-                }
+        if let Some(manager) = config.get_manager_mut(&AppType::Gemini) {
+            if let Some(target) = manager.providers.get_mut(provider_id) {
+                target.settings_config = live_after;
+                target
+                    .meta
+                    .get_or_insert_with(Default::default)
+                    .managed_env_keys = keys;
             }
+        }
 
-            return Ok(());
+        Ok(())
+    }
+}
+
+/// 目录（递归）占用的字节数；不存在时为 0
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn backup_detail(path: &Path, size: u64, locale: ReportLocale) -> BackupDetail {
+    let created_at = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .map(|time| chrono::DateTime::<Utc>::from(time).timestamp_millis());
+    let mut detail = BackupDetail {
+        id: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        created_at,
+        size,
+        size_text: locale.bytes(size),
+        created_at_text: created_at.and_then(|millis| locale.datetime_millis(millis)),
+        config_version: None,
+        provider_counts: Default::default(),
+        current_providers: Default::default(),
+        mcp_server_count: 0,
+        error: None,
+    };
+    // 按 JSON 宽松解析，旧版本或部分损坏的备份也能展示可读部分
+    let value = match crate::config::read_json_file::<Value>(path) {
+        Ok(value) => value,
+        Err(err) => {
+            detail.error = Some(err.to_string());
+            return detail;
         }
+    };
+    detail.config_version = value.get("version").and_then(Value::as_u64);
+    if let Some(root) = value.as_object() {
+        for (app, manager) in root {
+            let Some(providers) = manager.get("providers").and_then(Value::as_object) else {
+                continue;
+            };
+            detail.provider_counts.insert(app.clone(), providers.len());
+            if let Some(current) = manager
+                .get("current")
+                .and_then(Value::as_str)
+                .filter(|current| !current.is_empty())
+            {
+                detail
+                    .current_providers
+                    .insert(app.clone(), current.to_string());
+            }
+        }
+    }
+    detail.mcp_server_count = value
+        .pointer("/mcp/servers")
+        .and_then(Value::as_object)
+        .map_or(0, |servers| servers.len());
+    detail
+}
 
-        // 非 OAuth：按常规写入，并在必要时设置 Packycode 安全标志
-        write_gemini_env_atomic(&env_map)?;
-        ProviderService::ensure_packycode_security_flag(provider)?;
+/// 下载远程配置，超过大小上限时中止
+async fn fetch_remote_config(url: &str) -> Result<String, AppError> {
+    fetch_text_capped(
+        url,
+        MAX_REMOTE_CONFIG_BYTES,
+        std::time::Duration::from_secs(REMOTE_CONFIG_TIMEOUT_SECS),
+    )
+    .await
+    .map_err(|detail| {
+        AppError::localized(
+            "import.url_fetch_failed",
+            format!("下载配置失败: {detail}"),
+            format!("Failed to download config: {detail}"),
+        )
+    })
+}
 
-        // 读回实际写入的内容并更新到配置中
-        let live_after_env = read_gemini_env()?;
-        let live_after = env_to_json(&live_after_env);
+/// 是否为 `http://` 本机回环地址（localhost / 127.0.0.0/8 / ::1）
+pub(crate) fn is_loopback_http_url(url: &str) -> bool {
+    let Ok(parsed) = url::Url::parse(url) else {
+        return false;
+    };
+    if parsed.scheme() != "http" {
+        return false;
+    }
+    match parsed.host() {
+        Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
 
-        if let Some(manager) = config.get_manager_mut(&AppType::Gemini) {
-            if let Some(target) = manager.providers.get_mut(provider_id) {
-                target.settings_config = live_after;
+/// 下载远程文本（导入配置、订阅清单共用），超过 `max_bytes` 时中止；错误返回失败详情
+///
+/// 重定向只允许 https；起始地址为本机回环 http 时，也允许重定向到本机回环 http
+pub(crate) async fn fetch_text_capped(
+    url: &str,
+    max_bytes: usize,
+    timeout: std::time::Duration,
+) -> Result<String, String> {
+    let allow_loopback_http = is_loopback_http_url(url);
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            let target = attempt.url();
+            if target.scheme() != "https"
+                && !(allow_loopback_http && is_loopback_http_url(target.as_str()))
+            {
+                attempt.error("redirect to non-https URL")
+            } else if attempt.previous().len() >= 5 {
+                attempt.stop()
+            } else {
+                attempt.follow()
             }
+        }))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut resp = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}", resp.status()));
+    }
+    let too_large = || format!("超过 {max_bytes} 字节");
+    if resp
+        .content_length()
+        .is_some_and(|len| len > max_bytes as u64)
+    {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(|e| e.to_string())? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large());
         }
+        body.extend_from_slice(&chunk);
+    }
+    String::from_utf8(body).map_err(|e| e.to_string())
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_loopback_hosts_are_allowed_over_http() {
+        assert!(is_loopback_http_url("http://localhost:8080/list.json"));
+        assert!(is_loopback_http_url("http://127.0.0.1/list.json"));
+        assert!(is_loopback_http_url("http://[::1]:3000/"));
+        assert!(!is_loopback_http_url("https://localhost/list.json"));
+        assert!(!is_loopback_http_url("http://example.com/list.json"));
+        assert!(!is_loopback_http_url("http://localhost.example.com/"));
+        assert!(!is_loopback_http_url("not a url"));
     }
 }
@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use serde_json::{json, Value};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::error::AppError;
+use crate::services::env_checker::detect_installed_clis;
+use crate::services::passcode::mask_secrets;
+use crate::settings;
+
+/// 诊断包中包含的最近日志条数
+const DIAGNOSTICS_LOG_LIMIT: usize = 2000;
+
+/// 生成用于问题反馈的诊断包
+pub struct DiagnosticsService;
+
+impl DiagnosticsService {
+    /// 收集版本、系统、设置（已脱敏）、最近日志、配置结构概要与 CLI 检测结果，打包为 zip
+    ///
+    /// 不包含任何供应商配置内容，仅统计数量
+    pub fn generate_bundle(config: &MultiAppConfig, target_path: &Path) -> Result<(), AppError> {
+        let summary = config_summary(config);
+
+        let mut entries: Vec<(&str, String)> = vec![
+            ("system.json", to_pretty_json(&system_info())?),
+            ("settings.json", to_pretty_json(&redacted_settings()?)?),
+            ("config-summary.json", to_pretty_json(&summary)?),
+            ("clis.json", to_pretty_json(&detect_installed_clis())?),
+        ];
+
+        let logs = crate::logging::recent_logs(None, DIAGNOSTICS_LOG_LIMIT).unwrap_or_default();
+        let log_text: String = logs
+            .iter()
+            .map(|entry| {
+                format!(
+                    "[{}][{}][{}] {}\n",
+                    entry.timestamp, entry.target, entry.level, entry.message
+                )
+            })
+            .collect();
+        entries.push(("logs.txt", log_text));
+
+        if let Some(parent) = target_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        let file = File::create(target_path).map_err(|e| AppError::io(target_path, e))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+        for (name, content) in entries {
+            zip.start_file(name, options)
+                .map_err(|e| AppError::Message(format!("写入诊断包失败: {e}")))?;
+            zip.write_all(content.as_bytes())
+                .map_err(|e| AppError::io(target_path, e))?;
+        }
+        zip.finish()
+            .map_err(|e| AppError::Message(format!("写入诊断包失败: {e}")))?;
+        Ok(())
+    }
+}
+
+fn to_pretty_json<T: serde::Serialize>(value: &T) -> Result<String, AppError> {
+    serde_json::to_string_pretty(value).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+fn system_info() -> Value {
+    json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "osFamily": std::env::consts::FAMILY,
+        "arch": std::env::consts::ARCH,
+        "generatedAt": chrono::Local::now().to_rfc3339(),
+    })
+}
+
+fn redacted_settings() -> Result<Value, AppError> {
+    let mut app_settings = settings::get_settings();
+    app_settings.passcode_hash = None;
+    let mut value =
+        serde_json::to_value(&app_settings).map_err(|e| AppError::JsonSerialize { source: e })?;
+    mask_secrets(&mut value);
+    Ok(value)
+}
+
+/// 配置结构概要：各应用供应商数量与 MCP 服务器数量
+fn config_summary(config: &MultiAppConfig) -> Value {
+    let mut apps = serde_json::Map::new();
+    for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+        let (providers, has_current) = config
+            .get_manager(&app_type)
+            .map(|manager| (manager.providers.len(), !manager.current.is_empty()))
+            .unwrap_or((0, false));
+        let mcp_enabled = config
+            .mcp
+            .servers
+            .as_ref()
+            .map(|servers| {
+                servers
+                    .values()
+                    .filter(|server| server.apps.is_enabled_for(&app_type))
+                    .count()
+            })
+            .unwrap_or(0);
+        apps.insert(
+            app_type.as_str().to_string(),
+            json!({
+                "providers": providers,
+                "hasCurrent": has_current,
+                "mcpEnabled": mcp_enabled,
+            }),
+        );
+    }
+
+    json!({
+        "version": config.version,
+        "apps": apps,
+        "mcpServers": config.mcp.servers.as_ref().map(|s| s.len()).unwrap_or(0),
+        "prompts": {
+            "claude": config.prompts.claude.prompts.len(),
+            "codex": config.prompts.codex.prompts.len(),
+            "gemini": config.prompts.gemini.prompts.len(),
+        },
+    })
+}
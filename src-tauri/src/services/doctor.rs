@@ -0,0 +1,590 @@
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::claude_mcp;
+use crate::config::get_claude_settings_path;
+use crate::config_integrity::{self, ChecksumStatus};
+use crate::error::AppError;
+use crate::managed_files::{self, LastWriter};
+use crate::mcp;
+use crate::report_format::ReportLocale;
+use crate::services::config::{MAX_BACKUPS, MAX_BACKUP_DIR_BYTES};
+use crate::services::{ConfigService, McpService, PermissionService, ProviderService};
+use crate::store::AppState;
+
+/// 检查项分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DoctorCategory {
+    Config,
+    Live,
+    Mcp,
+    Backup,
+    Permissions,
+    Credentials,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DoctorSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// 可自动执行的修复动作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DoctorFix {
+    /// 将当前供应商重新写入 live 配置（覆盖外部修改）
+    SyncLive { app: AppType },
+    /// 按 cc-switch 中的 MCP 配置重写该应用的 live MCP 配置
+    SyncMcp { app: AppType },
+    /// 收紧配置文件与目录权限
+    HardenPermissions,
+    /// 创建缺失的备份目录
+    CreateBackupDir,
+    /// 接受当前 config.json 内容并重新记录校验和
+    AcceptConfigChecksum,
+    /// 删除超出数量或体积上限的旧备份
+    TrimBackups,
+    /// 修正映射键与服务器 ID 不一致的 MCP 条目
+    NormalizeMcpKeys,
+}
+
+/// 单条检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorFinding {
+    /// 稳定 ID（`<code>` 或 `<code>:<对象>`），供修复命令引用
+    pub id: String,
+    /// 问题类型代码（前端据此做本地化）
+    pub code: String,
+    pub category: DoctorCategory,
+    pub severity: DoctorSeverity,
+    pub message: String,
+    pub message_en: String,
+    /// 相关路径、服务器 ID 等
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<DoctorFix>,
+}
+
+/// 诊断报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    /// 按严重程度降序排列
+    pub findings: Vec<DoctorFinding>,
+    /// 没有警告或错误
+    pub healthy: bool,
+    pub checked_at: i64,
+}
+
+impl DoctorFinding {
+    fn new(
+        code: &str,
+        category: DoctorCategory,
+        severity: DoctorSeverity,
+        message: impl Into<String>,
+        message_en: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: code.to_string(),
+            code: code.to_string(),
+            category,
+            severity,
+            message: message.into(),
+            message_en: message_en.into(),
+            detail: None,
+            fix: None,
+        }
+    }
+
+    /// 针对具体对象的检查项（ID 追加对象名，保证同类问题可分别修复）
+    fn subject(mut self, subject: &str) -> Self {
+        self.id = format!("{}:{subject}", self.code);
+        self
+    }
+
+    fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    fn fix(mut self, fix: DoctorFix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// 整体配置诊断（doctor）：config.json 完整性、live 配置一致性、MCP 残留与命令缺失、
+/// 备份目录、文件权限以及已过期的登录凭据
+pub struct DoctorService;
+
+impl DoctorService {
+    pub fn run(state: &AppState) -> Result<DoctorReport, AppError> {
+        let mut findings = Vec::new();
+        Self::check_config(&mut findings);
+        {
+            let config = state.config.read()?;
+            Self::check_live(&config, &mut findings);
+            Self::check_mcp(&config, &mut findings);
+            Self::check_credentials(&config, &mut findings);
+        }
+        Self::check_backups(&mut findings);
+        Self::check_permissions(&mut findings);
+        Self::check_managed_files(&mut findings);
+
+        findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.id.cmp(&b.id)));
+        Ok(DoctorReport {
+            healthy: findings
+                .iter()
+                .all(|finding| finding.severity == DoctorSeverity::Info),
+            findings,
+            checked_at: chrono::Utc::now().timestamp_millis(),
+        })
+    }
+
+    /// 执行报告中某条检查项的修复动作，返回修复后重新生成的报告
+    pub fn fix(state: &AppState, finding_id: &str) -> Result<DoctorReport, AppError> {
+        let report = Self::run(state)?;
+        let finding = report
+            .findings
+            .into_iter()
+            .find(|finding| finding.id == finding_id)
+            .ok_or_else(|| {
+                AppError::localized(
+                    "doctor.finding_not_found",
+                    format!("检查项不存在或已修复: {finding_id}"),
+                    format!("Finding not found or already resolved: {finding_id}"),
+                )
+            })?;
+        let fix = finding.fix.ok_or_else(|| {
+            AppError::localized(
+                "doctor.no_fix",
+                format!("该检查项没有可自动执行的修复: {finding_id}"),
+                format!("No automatic fix is available for: {finding_id}"),
+            )
+        })?;
+
+        log::info!("执行诊断修复 {finding_id}: {fix:?}");
+        match fix {
+            DoctorFix::SyncLive { app } => {
+                let current = ProviderService::current(state, app.clone())?;
+                ProviderService::switch_with_rewrite(state, app, &current, true)?;
+            }
+            DoctorFix::SyncMcp { app } => McpService::resync_app(state, app)?,
+            DoctorFix::HardenPermissions => {
+                PermissionService::audit(true);
+            }
+            DoctorFix::CreateBackupDir => {
+                ConfigService::ensure_backup_dir()?;
+            }
+            DoctorFix::AcceptConfigChecksum => config_integrity::record_checksum(),
+            DoctorFix::TrimBackups => {
+                ConfigService::trim_backups()?;
+            }
+            DoctorFix::NormalizeMcpKeys => {
+                McpService::normalize_keys(state)?;
+            }
+        }
+
+        Self::run(state)
+    }
+
+    fn check_config(findings: &mut Vec<DoctorFinding>) {
+        let report = config_integrity::inspect();
+        if let Some(error) = report.parse_error {
+            findings.push(
+                DoctorFinding::new(
+                    "config.parse_error",
+                    DoctorCategory::Config,
+                    DoctorSeverity::Error,
+                    format!("config.json 无法解析（第 {} 行）", error.line),
+                    format!("config.json cannot be parsed (line {})", error.line),
+                )
+                .detail(error.message),
+            );
+        }
+        if report
+            .schema_version
+            .is_some_and(|version| version > report.expected_schema_version)
+        {
+            findings.push(DoctorFinding::new(
+                "config.schema_too_new",
+                DoctorCategory::Config,
+                DoctorSeverity::Error,
+                "config.json 由更新版本的 CC Switch 写入",
+                "config.json was written by a newer CC Switch version",
+            ));
+        }
+        if report.checksum == ChecksumStatus::Mismatch {
+            findings.push(
+                DoctorFinding::new(
+                    "config.checksum_mismatch",
+                    DoctorCategory::Config,
+                    DoctorSeverity::Warning,
+                    "config.json 在 CC Switch 之外被修改，或上次写入未完成",
+                    "config.json was modified outside CC Switch or the last write was interrupted",
+                )
+                .detail(report.path)
+                .fix(DoctorFix::AcceptConfigChecksum),
+            );
+        }
+    }
+
+    /// 当前供应商与 live 配置是否一致（以切换时记录的 live 文件哈希为准）
+    fn check_live(config: &MultiAppConfig, findings: &mut Vec<DoctorFinding>) {
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            let Some(manager) = config.get_manager(&app) else {
+                continue;
+            };
+            if manager.current.is_empty() {
+                continue;
+            }
+            let Some(provider) = manager.providers.get(&manager.current) else {
+                findings.push(
+                    DoctorFinding::new(
+                        "live.current_missing",
+                        DoctorCategory::Live,
+                        DoctorSeverity::Error,
+                        format!("{} 的当前供应商已不存在", app.as_str()),
+                        format!("Current {} provider no longer exists", app.as_str()),
+                    )
+                    .subject(app.as_str())
+                    .detail(manager.current.clone()),
+                );
+                continue;
+            };
+
+            let live_missing = match app {
+                AppType::Claude => !get_claude_settings_path().exists(),
+                AppType::Codex => !crate::codex_config::get_codex_auth_path().exists(),
+                AppType::Gemini => !crate::gemini_config::get_gemini_env_path().exists(),
+            };
+            if live_missing {
+                findings.push(
+                    DoctorFinding::new(
+                        "live.missing",
+                        DoctorCategory::Live,
+                        DoctorSeverity::Warning,
+                        format!("{} 的 live 配置文件不存在", app.as_str()),
+                        format!("{} live config file is missing", app.as_str()),
+                    )
+                    .subject(app.as_str())
+                    .detail(provider.name.clone())
+                    .fix(DoctorFix::SyncLive { app: app.clone() }),
+                );
+                continue;
+            }
+
+            let recorded = provider
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.live_hash.as_deref());
+            if let Some(recorded) = recorded {
+                if recorded != ProviderService::live_files_hash(&app) {
+                    findings.push(
+                        DoctorFinding::new(
+                            "live.drift",
+                            DoctorCategory::Live,
+                            DoctorSeverity::Warning,
+                            format!(
+                                "{} 的 live 配置在切换后被外部修改，与当前供应商不一致",
+                                app.as_str()
+                            ),
+                            format!(
+                                "{} live config was modified after switching and no longer matches the current provider",
+                                app.as_str()
+                            ),
+                        )
+                        .subject(app.as_str())
+                        .detail(provider.name.clone())
+                        .fix(DoctorFix::SyncLive { app: app.clone() }),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_mcp(config: &MultiAppConfig, findings: &mut Vec<DoctorFinding>) {
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            let preview = match mcp::preview_sync_for(config, &app) {
+                Ok(preview) => preview,
+                Err(e) => {
+                    findings.push(
+                        DoctorFinding::new(
+                            "mcp.live_unreadable",
+                            DoctorCategory::Mcp,
+                            DoctorSeverity::Error,
+                            format!("无法读取 {} 的 MCP 配置", app.as_str()),
+                            format!("Unable to read {} MCP config", app.as_str()),
+                        )
+                        .subject(app.as_str())
+                        .detail(e.to_string()),
+                    );
+                    continue;
+                }
+            };
+            if !preview.removed.is_empty() {
+                findings.push(
+                    DoctorFinding::new(
+                        "mcp.orphaned",
+                        DoctorCategory::Mcp,
+                        DoctorSeverity::Warning,
+                        format!(
+                            "{} 的 live 配置中存在未由 CC Switch 管理的 MCP 服务器",
+                            app.as_str()
+                        ),
+                        format!(
+                            "{} live config contains MCP servers not managed by CC Switch",
+                            app.as_str()
+                        ),
+                    )
+                    .subject(app.as_str())
+                    .detail(preview.removed.join(", "))
+                    .fix(DoctorFix::SyncMcp { app: app.clone() }),
+                );
+            }
+            let mut stale = preview.added;
+            stale.extend(preview.modified);
+            if !stale.is_empty() {
+                findings.push(
+                    DoctorFinding::new(
+                        "mcp.out_of_sync",
+                        DoctorCategory::Mcp,
+                        DoctorSeverity::Warning,
+                        format!("{} 的 live MCP 配置与 CC Switch 不一致", app.as_str()),
+                        format!("{} live MCP config differs from CC Switch", app.as_str()),
+                    )
+                    .subject(app.as_str())
+                    .detail(stale.join(", "))
+                    .fix(DoctorFix::SyncMcp { app: app.clone() }),
+                );
+            }
+        }
+
+        let mut mismatched: Vec<String> = config
+            .mcp
+            .servers
+            .iter()
+            .flatten()
+            .filter(|(key, server)| *key != &server.id)
+            .map(|(key, server)| format!("{key} → {}", server.id))
+            .collect();
+        if !mismatched.is_empty() {
+            mismatched.sort();
+            findings.push(
+                DoctorFinding::new(
+                    "mcp.key_mismatch",
+                    DoctorCategory::Mcp,
+                    DoctorSeverity::Warning,
+                    format!("{} 个 MCP 服务器的键与其 ID 不一致", mismatched.len()),
+                    format!(
+                        "{} MCP servers are stored under a key that differs from their ID",
+                        mismatched.len()
+                    ),
+                )
+                .detail(mismatched.join("\n"))
+                .fix(DoctorFix::NormalizeMcpKeys),
+            );
+        }
+
+        for server in config
+            .mcp
+            .servers
+            .iter()
+            .flat_map(|servers| servers.values())
+        {
+            if server.apps.is_empty() {
+                continue;
+            }
+            let Some(command) = server.server.get("command").and_then(Value::as_str) else {
+                continue;
+            };
+            if !claude_mcp::validate_command_in_path(command).unwrap_or(true) {
+                findings.push(
+                    DoctorFinding::new(
+                        "mcp.missing_command",
+                        DoctorCategory::Mcp,
+                        DoctorSeverity::Warning,
+                        format!("MCP 服务器 {} 的启动命令不存在", server.name),
+                        format!("Command for MCP server {} was not found", server.name),
+                    )
+                    .subject(&server.id)
+                    .detail(command),
+                );
+            }
+        }
+    }
+
+    /// OAuth 供应商保存的登录凭据是否已过期（Claude `claudeAiOauth.expiresAt`）
+    fn check_credentials(config: &MultiAppConfig, findings: &mut Vec<DoctorFinding>) {
+        let now = chrono::Utc::now().timestamp_millis();
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            let Some(manager) = config.get_manager(&app) else {
+                continue;
+            };
+            for provider in manager.providers.values() {
+                let expires_at = provider
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.oauth_snapshot.as_ref())
+                    .and_then(|snapshot| snapshot.pointer("/claudeAiOauth/expiresAt"))
+                    .and_then(Value::as_i64);
+                if expires_at.is_some_and(|expires_at| expires_at < now) {
+                    findings.push(
+                        DoctorFinding::new(
+                            "credentials.expired",
+                            DoctorCategory::Credentials,
+                            DoctorSeverity::Warning,
+                            format!("供应商 {} 保存的登录凭据已过期，请重新登录", provider.name),
+                            format!(
+                                "Saved login for provider {} has expired; please sign in again",
+                                provider.name
+                            ),
+                        )
+                        .subject(&format!(
+                            "{}/{}",
+                            app.as_str(),
+                            provider.id
+                        )),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_backups(findings: &mut Vec<DoctorFinding>) {
+        let dir = ConfigService::backup_dir();
+        if !dir.exists() {
+            findings.push(
+                DoctorFinding::new(
+                    "backup.missing_dir",
+                    DoctorCategory::Backup,
+                    DoctorSeverity::Info,
+                    "备份目录不存在",
+                    "Backup directory does not exist",
+                )
+                .detail(dir.display().to_string())
+                .fix(DoctorFix::CreateBackupDir),
+            );
+            return;
+        }
+        if let Err(e) = fs::read_dir(&dir) {
+            findings.push(
+                DoctorFinding::new(
+                    "backup.unreadable",
+                    DoctorCategory::Backup,
+                    DoctorSeverity::Error,
+                    format!("备份目录无法读取: {e}"),
+                    format!("Backup directory is not readable: {e}"),
+                )
+                .detail(dir.display().to_string())
+                .fix(DoctorFix::HardenPermissions),
+            );
+            return;
+        }
+
+        let (count, bytes) = ConfigService::backup_usage();
+        if count > MAX_BACKUPS || bytes > MAX_BACKUP_DIR_BYTES {
+            let size = ReportLocale::Zh.bytes(bytes);
+            let size_en = ReportLocale::En.bytes(bytes);
+            findings.push(
+                DoctorFinding::new(
+                    "backup.oversized",
+                    DoctorCategory::Backup,
+                    DoctorSeverity::Warning,
+                    format!("备份目录过大：{count} 份备份，共 {size}"),
+                    format!("Backup directory is too large: {count} backups, {size_en}"),
+                )
+                .detail(dir.display().to_string())
+                .fix(DoctorFix::TrimBackups),
+            );
+        }
+    }
+
+    fn check_permissions(findings: &mut Vec<DoctorFinding>) {
+        let report = PermissionService::audit(false);
+        let loose: Vec<String> = report
+            .entries
+            .into_iter()
+            .filter(|entry| entry.too_permissive)
+            .map(|entry| entry.path)
+            .collect();
+        if !loose.is_empty() {
+            findings.push(
+                DoctorFinding::new(
+                    "permissions.too_permissive",
+                    DoctorCategory::Permissions,
+                    DoctorSeverity::Warning,
+                    format!("{} 个配置文件或目录的权限过宽", loose.len()),
+                    format!(
+                        "{} config files or directories are too permissive",
+                        loose.len()
+                    ),
+                )
+                .detail(loose.join("\n"))
+                .fix(DoctorFix::HardenPermissions),
+            );
+        }
+    }
+
+    /// cc-switch 写入后又被其他程序修改过的文件（仅提示）
+    fn check_managed_files(findings: &mut Vec<DoctorFinding>) {
+        let edited: Vec<String> = managed_files::list()
+            .into_iter()
+            .filter(|file| file.last_writer == Some(LastWriter::External))
+            .map(|file| file.path)
+            .collect();
+        if !edited.is_empty() {
+            findings.push(
+                DoctorFinding::new(
+                    "files.external_write",
+                    DoctorCategory::Config,
+                    DoctorSeverity::Info,
+                    format!(
+                        "{} 个由 cc-switch 写入的文件之后被其他程序修改",
+                        edited.len()
+                    ),
+                    format!(
+                        "{} files written by cc-switch were later modified by another program",
+                        edited.len()
+                    ),
+                )
+                .detail(edited.join("\n")),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finding_ids_include_subject() {
+        let finding = DoctorFinding::new(
+            "live.drift",
+            DoctorCategory::Live,
+            DoctorSeverity::Warning,
+            "",
+            "",
+        )
+        .subject("codex")
+        .fix(DoctorFix::SyncLive {
+            app: AppType::Codex,
+        });
+        assert_eq!(finding.id, "live.drift:codex");
+
+        let value = serde_json::to_value(&finding).unwrap();
+        assert_eq!(value["fix"]["kind"], "syncLive");
+        assert_eq!(value["fix"]["app"], "codex");
+        assert_eq!(value["severity"], "warning");
+    }
+}
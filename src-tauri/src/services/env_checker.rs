@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +13,21 @@ pub struct EnvConflict {
     pub source_path: String, // Registry path or file path
 }
 
+/// Installation status of a supported CLI tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledCli {
+    pub name: String,
+    pub installed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Timeout for `<cli> --version`
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[cfg(target_os = "windows")]
 use winreg::enums::*;
 #[cfg(target_os = "windows")]
@@ -153,6 +171,83 @@ fn check_shell_configs(keywords: &[&str]) -> Result<Vec<EnvConflict>, String> {
     Ok(conflicts)
 }
 
+/// Detect whether claude / codex / gemini CLIs are installed (PATH lookup + `--version`)
+pub fn detect_installed_clis() -> Vec<InstalledCli> {
+    ["claude", "codex", "gemini"]
+        .iter()
+        .map(|name| {
+            let path = find_in_path(name);
+            let version = path.as_deref().and_then(read_cli_version);
+            InstalledCli {
+                name: name.to_string(),
+                installed: path.is_some(),
+                path: path.map(|p| p.to_string_lossy().to_string()),
+                version,
+            }
+        })
+        .collect()
+}
+
+/// Locate an executable in PATH (honours PATHEXT on Windows)
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    let extensions: Vec<String> = if cfg!(target_os = "windows") {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .map(|ext| ext.to_lowercase())
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+
+    std::env::split_paths(&paths).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{name}{ext}")))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Run `<cli> --version`, killing the process if it does not exit in time
+fn read_cli_version(path: &Path) -> Option<String> {
+    let mut command = Command::new(path);
+    command
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = command.spawn().ok()?;
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < VERSION_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(50))
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    let output = child.wait_with_output().ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
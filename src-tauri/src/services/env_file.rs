@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+
+use crate::app_config::AppType;
+use crate::config::{get_app_config_dir, write_text_file};
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::shell_wrapper::{env_pairs, restrict_permissions, sh_quote};
+use crate::services::ProviderService;
+
+/// 当前供应商的环境变量文件：`~/.cc-switch/env/<app>.env`
+///
+/// 文件为 POSIX `export` 语句，开启 `write_env_files` 后每次切换供应商时更新，
+/// 终端用户可通过 `source` 载入当前供应商的密钥与地址
+pub struct EnvFileService;
+
+impl EnvFileService {
+    pub fn path(app_type: &AppType) -> PathBuf {
+        get_app_config_dir()
+            .join("env")
+            .join(format!("{}.env", app_type.as_str()))
+    }
+
+    /// 写入指定供应商的环境变量文件（包含密钥，Unix 下仅当前用户可读写）
+    pub fn write(app_type: &AppType, provider: &Provider) -> Result<PathBuf, AppError> {
+        let path = Self::path(app_type);
+        write_text_file(
+            &path,
+            &render_env_file(provider, &Self::env_for(app_type, provider)),
+        )?;
+        restrict_permissions(&path, 0o600);
+        Ok(path)
+    }
+
+    /// 生成在 shell 启动文件中载入环境变量文件的片段
+    ///
+    /// `shell` 支持 `sh`/`bash`/`zsh`（默认）、`fish` 与 `powershell`
+    pub fn shell_snippet(app_type: &AppType, shell: Option<&str>) -> Result<String, AppError> {
+        let path = Self::path(app_type).to_string_lossy().to_string();
+        let snippet = match shell.unwrap_or("sh").to_ascii_lowercase().as_str() {
+            "sh" | "bash" | "zsh" => {
+                let quoted = sh_quote(&path);
+                format!("[ -f {quoted} ] && . {quoted}")
+            }
+            "fish" => {
+                let quoted = sh_quote(&path);
+                format!("test -f {quoted}; and source {quoted}")
+            }
+            "powershell" | "pwsh" => format!(
+                "if (Test-Path '{path}') {{ Get-Content '{path}' | ForEach-Object {{ if ($_ -match \"^export ([A-Za-z_][A-Za-z0-9_]*)='(.*)'$\") {{ Set-Item \"env:$($Matches[1])\" ($Matches[2] -replace \"'\\\\''\", \"'\") }} }} }}",
+                path = path.replace('\'', "''")
+            ),
+            other => {
+                return Err(AppError::localized(
+                    "env_file.unsupported_shell",
+                    format!("不支持的 shell: {other}"),
+                    format!("Unsupported shell: {other}"),
+                ))
+            }
+        };
+        Ok(snippet)
+    }
+
+    /// 提取供应商对应的环境变量
+    fn env_for(app_type: &AppType, provider: &Provider) -> Vec<(String, String)> {
+        match app_type {
+            AppType::Claude => {
+                let settings = ProviderService::claude_live_settings(provider);
+                env_pairs(settings.get("env").unwrap_or(&Value::Null))
+            }
+            AppType::Codex => {
+                // Codex 的地址写在 config.toml 中，这里导出 OpenAI 兼容的变量名
+                let mut env = json!({});
+                if let Some(key) = provider
+                    .settings_config
+                    .get("auth")
+                    .and_then(|auth| auth.get("OPENAI_API_KEY"))
+                {
+                    env["OPENAI_API_KEY"] = key.clone();
+                }
+                if let Some(base_url) = ProviderService::provider_base_url(app_type, provider) {
+                    env["OPENAI_BASE_URL"] = Value::String(base_url);
+                }
+                if let Some(meta) = provider.meta.as_ref() {
+                    for (key, value) in &meta.extra_env {
+                        env[key.as_str()] = Value::String(value.clone());
+                    }
+                }
+                env_pairs(&env)
+            }
+            AppType::Gemini => {
+                env_pairs(provider.settings_config.get("env").unwrap_or(&Value::Null))
+            }
+        }
+    }
+}
+
+fn render_env_file(provider: &Provider, env: &[(String, String)]) -> String {
+    let mut out = String::new();
+    out.push_str("# Generated by CC Switch on every provider switch. Do not edit.\n");
+    out.push_str(&format!(
+        "# Provider: {}\n",
+        provider.name.lines().next().unwrap_or_default()
+    ));
+    for (key, value) in env {
+        out.push_str(&format!("export {key}={}\n", sh_quote(value)));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codex_env_exports_key_and_base_url() {
+        let provider = Provider::with_id(
+            "relay".into(),
+            "Relay".into(),
+            json!({
+                "auth": { "OPENAI_API_KEY": "sk-'x" },
+                "config": "model_provider = \"relay\"\n[model_providers.relay]\nbase_url = \"https://relay.example.com/v1\"\n"
+            }),
+            None,
+        );
+        let env = EnvFileService::env_for(&AppType::Codex, &provider);
+        let text = render_env_file(&provider, &env);
+        assert!(text.contains("# Provider: Relay\n"));
+        assert!(text.contains("export OPENAI_API_KEY='sk-'\\''x'\n"));
+        assert!(text.contains("export OPENAI_BASE_URL='https://relay.example.com/v1'\n"));
+    }
+}
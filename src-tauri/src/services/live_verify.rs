@@ -0,0 +1,289 @@
+//! 切换后的 live 写入校验
+//!
+//! 开启 `verify_live_writes` 后，切换写入 live 文件后立即重新读取并与期望内容比对；
+//! 若为该应用配置了 CLI 校验命令（如 codex: `codex config validate`），在后置操作完成后执行。
+//! 任一检查失败时切换返回错误，并由事务通过 `LiveSnapshot` 回滚。
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::provider::{GeminiAuthType, ProviderService};
+use crate::app_config::AppType;
+use crate::codex_config::{get_codex_auth_path, get_codex_config_path};
+use crate::config::{get_claude_settings_path, read_claude_credentials, read_json_file};
+use crate::error::AppError;
+use crate::provider::Provider;
+
+/// CLI 校验命令的超时时间
+const CLI_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveVerifyCheck {
+    /// 检查项，如 `claude.settings`、`codex.config`、`cli`
+    pub name: String,
+    pub passed: bool,
+    /// 未执行（如 CLI 未安装）
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl LiveVerifyCheck {
+    fn pass(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: true,
+            skipped: false,
+            detail: None,
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: false,
+            skipped: false,
+            detail: Some(detail.into()),
+        }
+    }
+
+    fn skip(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: true,
+            skipped: true,
+            detail: Some(detail.into()),
+        }
+    }
+
+    fn compare(name: &str, expected: &Value, actual: Result<Value, AppError>) -> Self {
+        match actual {
+            Ok(actual) if &actual == expected => Self::pass(name),
+            Ok(_) => Self::fail(name, "写入后的内容与期望不一致"),
+            Err(e) => Self::fail(name, e.to_string()),
+        }
+    }
+}
+
+/// 切换后的校验结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveVerification {
+    pub passed: bool,
+    pub checks: Vec<LiveVerifyCheck>,
+}
+
+impl LiveVerification {
+    pub(crate) fn push(&mut self, check: LiveVerifyCheck) {
+        self.checks.push(check);
+        self.passed = self.checks.iter().all(|check| check.passed);
+    }
+
+    /// 校验未通过时转换为错误（用于触发回滚）
+    pub(crate) fn into_result(self) -> Result<Self, AppError> {
+        if self.passed {
+            return Ok(self);
+        }
+        let failed = self
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| match &check.detail {
+                Some(detail) => format!("{}: {detail}", check.name),
+                None => check.name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(AppError::localized(
+            "switch.verify_failed",
+            format!("live 配置写入校验失败，已回滚: {failed}"),
+            format!("Live config verification failed and was rolled back: {failed}"),
+        ))
+    }
+}
+
+/// 重新读取刚写入的 live 文件并与供应商生成的期望内容比对（`provider` 为已展开占位符的副本）
+pub(crate) fn verify_files(app_type: &AppType, provider: &Provider) -> LiveVerification {
+    let mut result = LiveVerification {
+        passed: true,
+        checks: Vec::new(),
+    };
+    match app_type {
+        AppType::Claude => {
+            let expected = ProviderService::claude_live_settings(provider);
+            let actual = read_json_file::<Value>(&get_claude_settings_path());
+            let check = match ProviderService::claude_managed_keys() {
+                None => LiveVerifyCheck::compare("claude.settings", &expected, actual),
+                // 合并模式下只比对由供应商接管的键
+                Some(managed) => {
+                    let pick = |value: &Value| {
+                        Value::Object(
+                            managed
+                                .iter()
+                                .filter_map(|key| Some((key.clone(), value.get(key)?.clone())))
+                                .collect(),
+                        )
+                    };
+                    LiveVerifyCheck::compare(
+                        "claude.settings",
+                        &pick(&expected),
+                        actual.map(|live| pick(&live)),
+                    )
+                }
+            };
+            result.push(check);
+            if let Some(snapshot) = ProviderService::oauth_snapshot(provider) {
+                let actual = read_claude_credentials().and_then(|content| {
+                    let content = content.unwrap_or_default();
+                    serde_json::from_str::<Value>(&content).map_err(|e| {
+                        AppError::json(crate::config::get_claude_credentials_path(), e)
+                    })
+                });
+                result.push(LiveVerifyCheck::compare(
+                    "claude.credentials",
+                    snapshot,
+                    actual,
+                ));
+            }
+        }
+        AppType::Codex => {
+            result.push(LiveVerifyCheck::compare(
+                "codex.auth",
+                &ProviderService::codex_live_auth(provider),
+                read_json_file::<Value>(&get_codex_auth_path()),
+            ));
+            let check = match ProviderService::codex_live_config_text(provider) {
+                Ok(expected) => {
+                    let path = get_codex_config_path();
+                    match std::fs::read_to_string(&path) {
+                        Ok(actual) if actual == expected.unwrap_or_default() => {
+                            LiveVerifyCheck::pass("codex.config")
+                        }
+                        Ok(_) => LiveVerifyCheck::fail("codex.config", "写入后的内容与期望不一致"),
+                        Err(e) => LiveVerifyCheck::fail(
+                            "codex.config",
+                            AppError::io(&path, e).to_string(),
+                        ),
+                    }
+                }
+                Err(e) => LiveVerifyCheck::fail("codex.config", e.to_string()),
+            };
+            result.push(check);
+        }
+        AppType::Gemini => {
+            use crate::gemini_config::{json_to_env, read_gemini_env, read_gemini_oauth_creds};
+
+            // Google 官方（OAuth）供应商写入时清空托管变量，不比对 env
+            let expected = if ProviderService::detect_gemini_auth_type(provider)
+                == GeminiAuthType::GoogleOfficial
+            {
+                Ok(Default::default())
+            } else {
+                json_to_env(&provider.settings_config)
+            };
+            let check = match (expected, read_gemini_env()) {
+                (Ok(expected), Ok(live)) => {
+                    let mismatched: Vec<&str> = expected
+                        .iter()
+                        .filter(|(key, value)| live.get(*key) != Some(*value))
+                        .map(|(key, _)| key.as_str())
+                        .collect();
+                    if mismatched.is_empty() {
+                        LiveVerifyCheck::pass("gemini.env")
+                    } else {
+                        LiveVerifyCheck::fail(
+                            "gemini.env",
+                            format!("变量不一致: {}", mismatched.join(", ")),
+                        )
+                    }
+                }
+                (Err(e), _) | (_, Err(e)) => LiveVerifyCheck::fail("gemini.env", e.to_string()),
+            };
+            result.push(check);
+            if let Some(snapshot) = ProviderService::oauth_snapshot(provider) {
+                let actual = read_gemini_oauth_creds().map(Option::unwrap_or_default);
+                result.push(LiveVerifyCheck::compare(
+                    "gemini.oauth_creds",
+                    snapshot,
+                    actual,
+                ));
+            }
+        }
+    }
+    result
+}
+
+/// 执行该应用配置的 CLI 校验命令；未配置时返回 None，命令不存在时记为跳过
+pub(crate) fn run_cli_check(app_type: &AppType) -> Option<LiveVerifyCheck> {
+    let command = crate::settings::get_settings()
+        .live_verify_commands
+        .get(app_type.as_str())
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty())?;
+    let mut parts = command.split_whitespace();
+    let program = parts.next()?;
+
+    let mut cmd = Command::new(program);
+    cmd.args(parts)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Some(LiveVerifyCheck::skip(
+                "cli",
+                format!("未找到命令: {program}"),
+            ));
+        }
+        Err(e) => return Some(LiveVerifyCheck::fail("cli", format!("{command}: {e}"))),
+    };
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() < CLI_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(50))
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Some(LiveVerifyCheck::fail(
+                    "cli",
+                    format!("{command}: 超时（{}s）", CLI_TIMEOUT.as_secs()),
+                ));
+            }
+            Err(e) => return Some(LiveVerifyCheck::fail("cli", format!("{command}: {e}"))),
+        }
+    };
+
+    if status.success() {
+        return Some(LiveVerifyCheck::pass("cli"));
+    }
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let _ = pipe.read_to_string(&mut stderr);
+    }
+    let reason = stderr
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| status.to_string());
+    Some(LiveVerifyCheck::fail("cli", format!("{command}: {reason}")))
+}
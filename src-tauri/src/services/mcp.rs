@@ -1,14 +1,36 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use crate::app_config::{AppType, McpServer, MultiAppConfig};
+use crate::config::write_json_file;
 use crate::error::AppError;
-use crate::mcp;
+use crate::mcp::{self, McpSyncPreview};
+use crate::pagination::{self, Page};
+use crate::project_mcp::{self, ProjectMcpImport};
 use crate::store::AppState;
 
 /// MCP 相关业务逻辑（v3.7.0 统一结构）
 pub struct McpService;
 
 impl McpService {
+    /// 分页列出 MCP 服务器（按名称排序），默认不返回包含环境变量等敏感信息的 `server` 定义
+    pub fn list_page(
+        state: &AppState,
+        offset: usize,
+        limit: usize,
+        fields: Option<&[String]>,
+    ) -> Result<Page, AppError> {
+        let cfg = state.config.read()?;
+        let mut servers: Vec<&McpServer> = cfg
+            .mcp
+            .servers
+            .iter()
+            .flat_map(|servers| servers.values())
+            .collect();
+        servers.sort_by_cached_key(|server| (server.name.to_lowercase(), server.id.clone()));
+        pagination::paginate(servers.into_iter(), offset, limit, fields, &["server"])
+    }
+
     /// 获取所有 MCP 服务器（统一结构）
     pub fn get_all_servers(state: &AppState) -> Result<HashMap<String, McpServer>, AppError> {
         let cfg = state.config.read()?;
@@ -74,6 +96,88 @@ impl McpService {
         }
     }
 
+    /// 复制 MCP 服务器为新 ID（保留 apps、描述与标签），并同步到启用的应用
+    pub fn duplicate_server(
+        state: &AppState,
+        id: &str,
+        new_id: &str,
+    ) -> Result<McpServer, AppError> {
+        let server = {
+            let mut cfg = state.config.write()?;
+            let servers = cfg.mcp.servers.get_or_insert_with(HashMap::new);
+            let new_id = Self::validate_new_id(servers, id, new_id)?;
+
+            let mut server = servers[id].clone();
+            if server.name == server.id {
+                server.name = new_id.clone();
+            }
+            server.id = new_id.clone();
+            servers.insert(new_id, server.clone());
+            server
+        };
+
+        state.save()?;
+        Self::sync_server_to_apps(state, &server)?;
+
+        Ok(server)
+    }
+
+    /// 重命名 MCP 服务器 ID（保留 apps、描述与标签），同步更新各应用的 live 配置
+    pub fn rename_server(state: &AppState, id: &str, new_id: &str) -> Result<McpServer, AppError> {
+        let server = {
+            let mut cfg = state.config.write()?;
+            let servers = cfg.mcp.servers.get_or_insert_with(HashMap::new);
+            let new_id = Self::validate_new_id(servers, id, new_id)?;
+
+            let mut server = servers.remove(id).expect("validated above");
+            if server.name == server.id {
+                server.name = new_id.clone();
+            }
+            server.id = new_id.clone();
+            servers.insert(new_id, server.clone());
+            server
+        };
+
+        state.save()?;
+
+        // 先移除旧 ID，再写入新 ID
+        Self::remove_server_from_all_apps(state, id, &server)?;
+        Self::sync_server_to_apps(state, &server)?;
+
+        Ok(server)
+    }
+
+    /// 校验复制/重命名的目标 ID：源存在、新 ID 非空且未被占用
+    fn validate_new_id(
+        servers: &HashMap<String, McpServer>,
+        id: &str,
+        new_id: &str,
+    ) -> Result<String, AppError> {
+        if !servers.contains_key(id) {
+            return Err(AppError::localized(
+                "mcp.not_found",
+                format!("MCP 服务器不存在: {id}"),
+                format!("MCP server not found: {id}"),
+            ));
+        }
+        let new_id = new_id.trim();
+        if new_id.is_empty() {
+            return Err(AppError::localized(
+                "mcp.empty_id",
+                "MCP 服务器 ID 不能为空",
+                "MCP server ID cannot be empty",
+            ));
+        }
+        if servers.contains_key(new_id) {
+            return Err(AppError::localized(
+                "mcp.id_exists",
+                format!("MCP 服务器 ID 已存在: {new_id}"),
+                format!("MCP server ID already exists: {new_id}"),
+            ));
+        }
+        Ok(new_id.to_string())
+    }
+
     /// 切换指定应用的启用状态
     pub fn toggle_app(
         state: &AppState,
@@ -103,7 +207,7 @@ impl McpService {
             if enabled {
                 Self::sync_server_to_app(state, &server, &app)?;
             } else {
-                Self::remove_server_from_app(state, server_id, &app)?;
+                Self::disable_server_in_app(state, server_id, &app)?;
             }
         }
 
@@ -138,13 +242,13 @@ impl McpService {
     ) -> Result<(), AppError> {
         match app {
             AppType::Claude => {
-                mcp::sync_single_server_to_claude(cfg, &server.id, &server.server)?;
+                mcp::sync_single_server_to_claude(cfg, &server.id, &server.spec_for(app))?;
             }
             AppType::Codex => {
-                mcp::sync_single_server_to_codex(cfg, &server.id, &server.server)?;
+                mcp::sync_single_server_to_codex(cfg, &server.id, &server.spec_for(app))?;
             }
             AppType::Gemini => {
-                mcp::sync_single_server_to_gemini(cfg, &server.id, &server.server)?;
+                mcp::sync_single_server_to_gemini(cfg, &server.id, &server.spec_for(app))?;
             }
         }
         Ok(())
@@ -163,6 +267,14 @@ impl McpService {
         Ok(())
     }
 
+    /// 在指定应用中停用服务器（Codex 可按设置注释保留）
+    fn disable_server_in_app(state: &AppState, id: &str, app: &AppType) -> Result<(), AppError> {
+        match app {
+            AppType::Codex => mcp::disable_server_in_codex(id),
+            _ => Self::remove_server_from_app(state, id, app),
+        }
+    }
+
     fn remove_server_from_app(_state: &AppState, id: &str, app: &AppType) -> Result<(), AppError> {
         match app {
             AppType::Claude => mcp::remove_server_from_claude(id)?,
@@ -173,14 +285,95 @@ impl McpService {
     }
 
     /// 手动同步所有启用的 MCP 服务器到对应的应用
-    pub fn sync_all_enabled(state: &AppState) -> Result<(), AppError> {
+    ///
+    /// 返回同步的服务器数量
+    pub fn sync_all_enabled(state: &AppState) -> Result<usize, AppError> {
         let servers = Self::get_all_servers(state)?;
 
         for server in servers.values() {
             Self::sync_server_to_apps(state, server)?;
         }
 
-        Ok(())
+        Ok(servers.len())
+    }
+
+    /// 仅将为指定应用启用的服务器同步到该应用，返回同步的服务器数量
+    pub fn sync_enabled_for_app(state: &AppState, app: &AppType) -> Result<usize, AppError> {
+        let servers = Self::get_all_servers(state)?;
+
+        let mut synced = 0;
+        for server in servers.values() {
+            if server.apps.is_enabled_for(app) {
+                Self::sync_server_to_app(state, server, app)?;
+                synced += 1;
+            }
+        }
+
+        Ok(synced)
+    }
+
+    /// 预览同步到指定应用将产生的变更（不写入任何文件）
+    pub fn preview_sync(state: &AppState, app: AppType) -> Result<McpSyncPreview, AppError> {
+        let cfg = state.config.read()?;
+        mcp::preview_sync_for(&cfg, &app)
+    }
+
+    /// 按 cc-switch 中的配置整体重写指定应用的 live MCP 配置
+    pub fn resync_app(state: &AppState, app: AppType) -> Result<(), AppError> {
+        let cfg = state.config.read()?;
+        mcp::sync_target_to_live(&cfg, &app)
+    }
+
+    /// 修正映射键与 `server.id` 不一致的服务器（以 `id` 为准；目标键已被占用时保留原键）
+    ///
+    /// 返回修正的服务器数量
+    pub fn normalize_keys(state: &AppState) -> Result<usize, AppError> {
+        let normalized = {
+            let mut cfg = state.config.write()?;
+            let Some(servers) = cfg.mcp.servers.as_mut() else {
+                return Ok(0);
+            };
+            let mut mismatched: Vec<String> = servers
+                .iter()
+                .filter(|(key, server)| *key != &server.id)
+                .map(|(key, _)| key.clone())
+                .collect();
+            mismatched.sort();
+
+            let mut normalized = Vec::new();
+            for key in mismatched {
+                let id = servers[&key].id.trim().to_string();
+                if id.is_empty() || servers.contains_key(&id) {
+                    // 无法使用 id 作为键时，改为让 id 跟随键
+                    if let Some(server) = servers.get_mut(&key) {
+                        server.id = key.clone();
+                        normalized.push(server.clone());
+                    }
+                    continue;
+                }
+                let mut server = servers.remove(&key).expect("key collected above");
+                server.id = id.clone();
+                servers.insert(id, server.clone());
+                normalized.push(server);
+            }
+            normalized
+        };
+
+        if normalized.is_empty() {
+            return Ok(0);
+        }
+        state.save()?;
+
+        let cfg = state.config.read()?;
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            if normalized
+                .iter()
+                .any(|server| server.apps.is_enabled_for(&app))
+            {
+                mcp::sync_target_to_live(&cfg, &app)?;
+            }
+        }
+        Ok(normalized.len())
     }
 
     // ========================================================================
@@ -220,15 +413,7 @@ impl McpService {
     /// [已废弃] 同步启用的 MCP 到指定应用（兼容旧 API）
     #[deprecated(since = "3.7.0", note = "Use sync_all_enabled instead")]
     pub fn sync_enabled(state: &AppState, app: AppType) -> Result<(), AppError> {
-        let servers = Self::get_all_servers(state)?;
-
-        for server in servers.values() {
-            if server.apps.is_enabled_for(&app) {
-                Self::sync_server_to_app(state, server, &app)?;
-            }
-        }
-
-        Ok(())
+        Self::sync_enabled_for_app(state, &app).map(|_| ())
     }
 
     /// 从 Claude 导入 MCP（v3.7.0 已更新为统一结构）
@@ -257,4 +442,53 @@ impl McpService {
         state.save()?;
         Ok(count)
     }
+
+    /// 从项目的 `.mcp.json` 导入 MCP（新服务器带项目标签，默认不启用任何应用）
+    pub fn import_from_project(
+        state: &AppState,
+        path: &Path,
+    ) -> Result<ProjectMcpImport, AppError> {
+        let file = project_mcp::project_file(path);
+        let content = std::fs::read_to_string(&file).map_err(|e| AppError::io(&file, e))?;
+        let tag = project_mcp::project_tag(&file);
+        let result = {
+            let mut cfg = state.config.write()?;
+            let servers = cfg.mcp.servers.get_or_insert_with(HashMap::new);
+            project_mcp::merge_into(servers, &content, &tag)?
+        };
+        if !result.imported.is_empty() || !result.tagged.is_empty() {
+            state.save()?;
+        }
+        Ok(ProjectMcpImport {
+            file: file.display().to_string(),
+            ..result
+        })
+    }
+
+    /// 将选中的 MCP 服务器写入项目的 `.mcp.json`（覆盖原文件），返回写入的文件路径
+    pub fn export_to_project(
+        state: &AppState,
+        path: &Path,
+        ids: &[String],
+    ) -> Result<String, AppError> {
+        let file = project_mcp::project_file(path);
+        let value = {
+            let cfg = state.config.read()?;
+            let all = cfg.mcp.servers.as_ref();
+            let mut selected = Vec::with_capacity(ids.len());
+            for id in ids {
+                let server = all.and_then(|servers| servers.get(id)).ok_or_else(|| {
+                    AppError::localized(
+                        "mcp.not_found",
+                        format!("MCP 服务器不存在: {id}"),
+                        format!("MCP server not found: {id}"),
+                    )
+                })?;
+                selected.push(server);
+            }
+            project_mcp::render(&selected)
+        };
+        write_json_file(&file, &value)?;
+        Ok(file.display().to_string())
+    }
 }
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+
+use crate::app_config::{
+    McpHealth, McpHealthIssue, McpHealthIssueKind, McpHealthStatus, McpServer,
+};
+use crate::claude_mcp;
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// 单次网络检查超时（秒）
+const CHECK_TIMEOUT_SECS: u64 = 10;
+
+/// 固定版本的包引用
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PinnedPackage {
+    Npm { name: String, version: String },
+    PyPi { name: String, version: String },
+}
+
+/// MCP 服务器健康检查：启动命令是否存在、固定的 npm/PyPI 版本是否被撤回、远程 URL 是否 404
+///
+/// 网络错误（离线、超时等）不视为异常，避免断网时把所有服务器标记为不健康；
+/// 开启离线模式时只检查启动命令
+pub struct McpHealthService;
+
+impl McpHealthService {
+    /// 检查全部服务器并写回 `health`，返回不健康的服务器
+    pub async fn check_all(state: &AppState) -> Result<Vec<McpServer>, AppError> {
+        let specs: Vec<(String, Value)> = {
+            let cfg = state.config.read()?;
+            cfg.mcp
+                .servers
+                .iter()
+                .flat_map(|servers| servers.values())
+                .map(|server| (server.id.clone(), server.server.clone()))
+                .collect()
+        };
+        if specs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let client = build_client()?;
+        let offline = crate::settings::get_settings().offline_mode;
+        let mut results = HashMap::with_capacity(specs.len());
+        for (id, spec) in specs {
+            let issues = check_server(&client, &spec, offline).await;
+            let status = if issues.is_empty() {
+                McpHealthStatus::Healthy
+            } else {
+                log::warn!("MCP 服务器 '{id}' 健康检查未通过: {issues:?}");
+                McpHealthStatus::Unhealthy
+            };
+            results.insert(
+                id,
+                McpHealth {
+                    status,
+                    issues,
+                    checked_at: chrono::Utc::now().timestamp_millis(),
+                },
+            );
+        }
+
+        {
+            let mut cfg = state.config.write()?;
+            if let Some(servers) = cfg.mcp.servers.as_mut() {
+                for (id, health) in results {
+                    // 检查期间被删除的服务器直接忽略
+                    if let Some(server) = servers.get_mut(&id) {
+                        server.health = Some(health);
+                    }
+                }
+            }
+        }
+        state.save()?;
+
+        Self::unhealthy(state)
+    }
+
+    /// 最近一次检查中不健康的服务器（按名称排序）
+    pub fn unhealthy(state: &AppState) -> Result<Vec<McpServer>, AppError> {
+        let cfg = state.config.read()?;
+        let mut servers: Vec<McpServer> = cfg
+            .mcp
+            .servers
+            .iter()
+            .flat_map(|servers| servers.values())
+            .filter(|server| {
+                server
+                    .health
+                    .as_ref()
+                    .is_some_and(|health| health.status == McpHealthStatus::Unhealthy)
+            })
+            .cloned()
+            .collect();
+        servers.sort_by_cached_key(|server| (server.name.to_lowercase(), server.id.clone()));
+        Ok(servers)
+    }
+}
+
+async fn check_server(client: &Client, spec: &Value, offline: bool) -> Vec<McpHealthIssue> {
+    let mut issues = Vec::new();
+
+    if let Some(url) = spec.get("url").and_then(Value::as_str) {
+        if offline {
+            return issues;
+        }
+        if let Some(status) = url_not_found(client, url).await {
+            issues.push(McpHealthIssue {
+                kind: McpHealthIssueKind::UrlNotFound,
+                detail: format!("{url} 返回 {status}"),
+            });
+        }
+        return issues;
+    }
+
+    let Some(command) = spec.get("command").and_then(Value::as_str) else {
+        return issues;
+    };
+    if !claude_mcp::validate_command_in_path(command).unwrap_or(true) {
+        issues.push(McpHealthIssue {
+            kind: McpHealthIssueKind::MissingCommand,
+            detail: command.to_string(),
+        });
+    }
+
+    let args: Vec<&str> = spec
+        .get("args")
+        .and_then(Value::as_array)
+        .map(|args| args.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if let Some(package) = pinned_package(command, &args).filter(|_| !offline) {
+        if package_yanked(client, &package).await {
+            let (name, version) = match &package {
+                PinnedPackage::Npm { name, version } | PinnedPackage::PyPi { name, version } => {
+                    (name, version)
+                }
+            };
+            issues.push(McpHealthIssue {
+                kind: McpHealthIssueKind::YankedPackage,
+                detail: format!("{name}@{version}"),
+            });
+        }
+    }
+    issues
+}
+
+/// URL 返回 404/410 时给出状态码
+async fn url_not_found(client: &Client, url: &str) -> Option<StatusCode> {
+    // 只读取响应头：SSE 端点会保持连接，丢弃响应即可断开
+    let status = client.get(url).send().await.ok()?.status();
+    matches!(status, StatusCode::NOT_FOUND | StatusCode::GONE).then_some(status)
+}
+
+async fn package_yanked(client: &Client, package: &PinnedPackage) -> bool {
+    match package {
+        PinnedPackage::Npm { name, version } => {
+            let url = format!("https://registry.npmjs.org/{}", name.replace('/', "%2F"));
+            let Ok(resp) = client.get(url).send().await else {
+                return false;
+            };
+            match resp.status() {
+                StatusCode::NOT_FOUND => true,
+                status if status.is_success() => resp
+                    .json::<Value>()
+                    .await
+                    .ok()
+                    .and_then(|doc| doc.get("versions").cloned())
+                    .is_some_and(|versions| versions.get(version).is_none()),
+                _ => false,
+            }
+        }
+        PinnedPackage::PyPi { name, version } => {
+            let url = format!("https://pypi.org/pypi/{name}/{version}/json");
+            let Ok(resp) = client.get(url).send().await else {
+                return false;
+            };
+            match resp.status() {
+                StatusCode::NOT_FOUND => true,
+                status if status.is_success() => resp
+                    .json::<Value>()
+                    .await
+                    .ok()
+                    .and_then(|doc| doc.pointer("/info/yanked").and_then(Value::as_bool))
+                    .unwrap_or(false),
+                _ => false,
+            }
+        }
+    }
+}
+
+/// 从启动命令中识别固定版本的包（未固定版本时始终解析到最新版，无需检查）
+fn pinned_package(command: &str, args: &[&str]) -> Option<PinnedPackage> {
+    let program = Path::new(command)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(command)
+        .to_ascii_lowercase();
+
+    match program.as_str() {
+        "npx" | "bunx" | "pnpx" => first_positional(args).and_then(parse_npm_spec),
+        "pnpm" | "yarn" if args.first() == Some(&"dlx") => {
+            first_positional(&args[1..]).and_then(parse_npm_spec)
+        }
+        "uvx" => {
+            let spec = args
+                .iter()
+                .position(|arg| *arg == "--from")
+                .and_then(|i| args.get(i + 1).copied())
+                .or_else(|| first_positional(args));
+            spec.and_then(parse_pypi_spec)
+        }
+        "pipx" if args.first() == Some(&"run") => {
+            first_positional(&args[1..]).and_then(parse_pypi_spec)
+        }
+        _ => None,
+    }
+}
+
+fn first_positional<'a>(args: &[&'a str]) -> Option<&'a str> {
+    args.iter().copied().find(|arg| !arg.starts_with('-'))
+}
+
+/// 解析 `name@1.2.3` / `@scope/name@1.2.3`
+fn parse_npm_spec(spec: &str) -> Option<PinnedPackage> {
+    let at = spec.get(1..)?.rfind('@')? + 1;
+    let (name, version) = (&spec[..at], &spec[at + 1..]);
+    is_exact_version(version).then(|| PinnedPackage::Npm {
+        name: name.to_string(),
+        version: version.to_string(),
+    })
+}
+
+/// 解析 `name==1.2.3` / `name@1.2.3`
+fn parse_pypi_spec(spec: &str) -> Option<PinnedPackage> {
+    let (name, version) = spec.split_once("==").or_else(|| spec.split_once('@'))?;
+    (!name.is_empty() && is_exact_version(version)).then(|| PinnedPackage::PyPi {
+        name: name.to_string(),
+        version: version.to_string(),
+    })
+}
+
+fn is_exact_version(version: &str) -> bool {
+    version.starts_with(|c: char| c.is_ascii_digit())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+}
+
+fn build_client() -> Result<Client, AppError> {
+    Client::builder()
+        .timeout(Duration::from_secs(CHECK_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .user_agent("cc-switch-mcp-health/1.0")
+        .build()
+        .map_err(|e| {
+            AppError::localized(
+                "mcp.health_client_failed",
+                format!("创建 HTTP 客户端失败: {e}"),
+                format!("Failed to create HTTP client: {e}"),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_pinned_packages() {
+        assert_eq!(
+            pinned_package("npx", &["-y", "@scope/server@1.2.3"]),
+            Some(PinnedPackage::Npm {
+                name: "@scope/server".to_string(),
+                version: "1.2.3".to_string(),
+            })
+        );
+        assert_eq!(pinned_package("npx", &["-y", "@scope/server"]), None);
+        assert_eq!(pinned_package("npx.cmd", &["server@latest"]), None);
+        assert_eq!(pinned_package("npx", &["server@^1.0.0"]), None);
+        assert_eq!(
+            pinned_package(
+                "uvx",
+                &["--from", "mcp-server-git==0.6.2", "mcp-server-git"]
+            ),
+            Some(PinnedPackage::PyPi {
+                name: "mcp-server-git".to_string(),
+                version: "0.6.2".to_string(),
+            })
+        );
+        assert_eq!(
+            pinned_package("pipx", &["run", "mcp-fetch@1.0.0rc1"]),
+            Some(PinnedPackage::PyPi {
+                name: "mcp-fetch".to_string(),
+                version: "1.0.0rc1".to_string(),
+            })
+        );
+        assert_eq!(pinned_package("node", &["server.js"]), None);
+    }
+}
@@ -0,0 +1,398 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::get_claude_config_dir;
+use crate::database::{self, db_error};
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// Claude Code 中 MCP 工具名的前缀：`mcp__<服务器>__<工具>`
+const MCP_TOOL_PREFIX: &str = "mcp__";
+
+/// 单个工具的调用统计
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ToolCount {
+    calls: u64,
+    last_used_at: Option<i64>,
+}
+
+/// 重新解析过的会话记录文件
+struct ParsedFile {
+    path: String,
+    size: u64,
+    modified: i64,
+    /// 键为完整工具名（`mcp__server__tool`）
+    tools: HashMap<String, ToolCount>,
+}
+
+/// 工具调用次数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolUsage {
+    pub tool: String,
+    pub calls: u64,
+}
+
+/// MCP 服务器调用统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpUsageStat {
+    pub server_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 是否为 cc-switch 管理的服务器
+    pub configured: bool,
+    /// 是否已对 Claude 启用
+    pub enabled_for_claude: bool,
+    pub calls: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+    /// 各工具调用次数（降序）
+    pub tools: Vec<McpToolUsage>,
+}
+
+/// MCP 服务器使用统计：解析 Claude Code 会话记录（`~/.claude/projects/**/*.jsonl`）中的
+/// `tool_use`，按服务器统计调用次数，便于清理从未被调用、却持续占用上下文的服务器
+///
+/// 各文件的解析结果保存在本地数据库（见 [`database`]），仅重新解析大小或修改时间有变化的文件
+pub struct McpUsageService;
+
+impl McpUsageService {
+    pub fn stats(state: &AppState) -> Result<Vec<McpUsageStat>, AppError> {
+        let usage = Self::scan_transcripts(&get_claude_config_dir().join("projects"))?;
+        let config = state.config.read()?;
+        let servers = config.mcp.servers.clone().unwrap_or_default();
+        drop(config);
+
+        let mut by_server: HashMap<String, McpUsageStat> = servers
+            .values()
+            .map(|server| {
+                (
+                    server.id.clone(),
+                    McpUsageStat {
+                        server_id: server.id.clone(),
+                        name: Some(server.name.clone()),
+                        configured: true,
+                        enabled_for_claude: server.apps.claude,
+                        calls: 0,
+                        last_used_at: None,
+                        tools: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        for (full_name, count) in usage {
+            let Some((server_id, tool)) = split_tool_name(&full_name) else {
+                continue;
+            };
+            let stat = by_server
+                .entry(server_id.to_string())
+                .or_insert_with(|| McpUsageStat {
+                    server_id: server_id.to_string(),
+                    name: None,
+                    configured: false,
+                    enabled_for_claude: false,
+                    calls: 0,
+                    last_used_at: None,
+                    tools: Vec::new(),
+                });
+            stat.calls += count.calls;
+            stat.last_used_at = stat.last_used_at.max(count.last_used_at);
+            stat.tools.push(McpToolUsage {
+                tool: tool.to_string(),
+                calls: count.calls,
+            });
+        }
+
+        let mut stats: Vec<McpUsageStat> = by_server.into_values().collect();
+        for stat in &mut stats {
+            stat.tools
+                .sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.tool.cmp(&b.tool)));
+        }
+        stats.sort_by(|a, b| {
+            b.calls
+                .cmp(&a.calls)
+                .then_with(|| a.server_id.cmp(&b.server_id))
+        });
+        Ok(stats)
+    }
+
+    /// 汇总目录下所有会话记录中的 MCP 工具调用（键为完整工具名）
+    fn scan_transcripts(root: &Path) -> Result<HashMap<String, ToolCount>, AppError> {
+        let mut files = Vec::new();
+        collect_jsonl(root, &mut files);
+
+        let cached = database::with_connection(|conn| cached_files(conn))?;
+        let mut present = HashSet::with_capacity(files.len());
+        let mut parsed = Vec::new();
+        for path in files {
+            let Ok(meta) = fs::metadata(&path) else {
+                continue;
+            };
+            let size = meta.len();
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or_default();
+            let key = path.to_string_lossy().to_string();
+            // 解析在数据库锁外进行，避免阻塞代理指标写入
+            if cached.get(&key) != Some(&(size, modified)) {
+                parsed.push(ParsedFile {
+                    path: key.clone(),
+                    size,
+                    modified,
+                    tools: parse_transcript(&path),
+                });
+            }
+            present.insert(key);
+        }
+        let removed: Vec<&String> = cached
+            .keys()
+            .filter(|path| !present.contains(*path))
+            .collect();
+
+        database::with_connection(|conn| {
+            let tx = conn.transaction().map_err(db_error)?;
+            store_files(&tx, &removed, &parsed)?;
+            let totals = tool_totals(&tx)?;
+            tx.commit().map_err(db_error)?;
+            Ok(totals)
+        })
+    }
+}
+
+pub(crate) fn init_schema(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS mcp_usage_files (
+            path TEXT PRIMARY KEY,
+            size INTEGER NOT NULL,
+            modified INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS mcp_tool_calls (
+            path TEXT NOT NULL,
+            tool TEXT NOT NULL,
+            calls INTEGER NOT NULL,
+            last_used_at INTEGER,
+            PRIMARY KEY (path, tool)
+        );",
+    )
+    .map_err(db_error)
+}
+
+/// 已解析文件的 (大小, 修改时间)
+fn cached_files(conn: &Connection) -> Result<HashMap<String, (u64, i64)>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT path, size, modified FROM mcp_usage_files")
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                (row.get::<_, i64>(1)? as u64, row.get::<_, i64>(2)?),
+            ))
+        })
+        .map_err(db_error)?;
+    rows.collect::<Result<_, _>>().map_err(db_error)
+}
+
+/// 删除已不存在文件的记录，并覆盖重新解析过的文件
+fn store_files(
+    conn: &Connection,
+    removed: &[&String],
+    parsed: &[ParsedFile],
+) -> Result<(), AppError> {
+    for path in removed
+        .iter()
+        .copied()
+        .chain(parsed.iter().map(|file| &file.path))
+    {
+        conn.execute("DELETE FROM mcp_tool_calls WHERE path = ?1", params![path])
+            .map_err(db_error)?;
+        conn.execute("DELETE FROM mcp_usage_files WHERE path = ?1", params![path])
+            .map_err(db_error)?;
+    }
+    for file in parsed {
+        conn.execute(
+            "INSERT INTO mcp_usage_files (path, size, modified) VALUES (?1, ?2, ?3)",
+            params![file.path, file.size as i64, file.modified],
+        )
+        .map_err(db_error)?;
+        for (tool, count) in &file.tools {
+            conn.execute(
+                "INSERT INTO mcp_tool_calls (path, tool, calls, last_used_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![file.path, tool, count.calls as i64, count.last_used_at],
+            )
+            .map_err(db_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// 按工具汇总所有文件的调用次数
+fn tool_totals(conn: &Connection) -> Result<HashMap<String, ToolCount>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT tool, SUM(calls), MAX(last_used_at) FROM mcp_tool_calls GROUP BY tool")
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                ToolCount {
+                    calls: row.get::<_, i64>(1)? as u64,
+                    last_used_at: row.get(2)?,
+                },
+            ))
+        })
+        .map_err(db_error)?;
+    rows.collect::<Result<_, _>>().map_err(db_error)
+}
+
+fn collect_jsonl(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_jsonl(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            out.push(path);
+        }
+    }
+}
+
+fn parse_transcript(path: &Path) -> HashMap<String, ToolCount> {
+    let mut tools = HashMap::new();
+    let Ok(file) = fs::File::open(path) else {
+        return tools;
+    };
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        // 快速跳过不含 MCP 调用的行，避免逐行完整解析
+        if !line.contains(MCP_TOOL_PREFIX) {
+            continue;
+        }
+        record_line(&line, &mut tools);
+    }
+    tools
+}
+
+/// 统计单条记录中 assistant 消息里的 MCP `tool_use`
+fn record_line(line: &str, tools: &mut HashMap<String, ToolCount>) {
+    let Ok(record) = serde_json::from_str::<Value>(line) else {
+        return;
+    };
+    let Some(content) = record
+        .get("message")
+        .and_then(|message| message.get("content"))
+        .and_then(Value::as_array)
+    else {
+        return;
+    };
+    let timestamp = record
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ts.timestamp_millis());
+
+    for block in content {
+        if block.get("type").and_then(Value::as_str) != Some("tool_use") {
+            continue;
+        }
+        let Some(name) = block.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        if split_tool_name(name).is_none() {
+            continue;
+        }
+        let count = tools.entry(name.to_string()).or_default();
+        count.calls += 1;
+        count.last_used_at = count.last_used_at.max(timestamp);
+    }
+}
+
+/// 拆分 `mcp__<服务器>__<工具>`
+fn split_tool_name(name: &str) -> Option<(&str, &str)> {
+    let rest = name.strip_prefix(MCP_TOOL_PREFIX)?;
+    let (server, tool) = rest.split_once("__")?;
+    (!server.is_empty() && !tool.is_empty()).then_some((server, tool))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_mcp_tool_use_blocks() {
+        let mut tools = HashMap::new();
+        let line = r#"{"type":"assistant","timestamp":"2025-01-02T03:04:05Z","message":{"content":[
+            {"type":"text","text":"mcp__github__create_issue"},
+            {"type":"tool_use","name":"mcp__github__create_issue"},
+            {"type":"tool_use","name":"mcp__github__list_prs"},
+            {"type":"tool_use","name":"Bash"}
+        ]}}"#
+            .replace('\n', "");
+        record_line(&line, &mut tools);
+        record_line(&line, &mut tools);
+
+        assert_eq!(tools.len(), 2);
+        let create = &tools["mcp__github__create_issue"];
+        assert_eq!(create.calls, 2);
+        assert_eq!(create.last_used_at, Some(1_735_787_045_000));
+        assert_eq!(
+            split_tool_name("mcp__my_server__do__thing"),
+            Some(("my_server", "do__thing"))
+        );
+        assert_eq!(split_tool_name("mcp__broken"), None);
+    }
+
+    #[test]
+    fn stored_counts_are_replaced_per_file_and_summed() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        let file = |path: &str, calls: u64, last_used_at: i64| ParsedFile {
+            path: path.into(),
+            size: 1,
+            modified: 1,
+            tools: HashMap::from([(
+                "mcp__github__list_prs".to_string(),
+                ToolCount {
+                    calls,
+                    last_used_at: Some(last_used_at),
+                },
+            )]),
+        };
+
+        store_files(
+            &conn,
+            &[],
+            &[file("a.jsonl", 2, 10), file("b.jsonl", 3, 30)],
+        )
+        .unwrap();
+        store_files(&conn, &[], &[file("a.jsonl", 5, 20)]).unwrap();
+        let totals = tool_totals(&conn).unwrap();
+        assert_eq!(
+            totals["mcp__github__list_prs"],
+            ToolCount {
+                calls: 8,
+                last_used_at: Some(30)
+            }
+        );
+
+        store_files(&conn, &[&"b.jsonl".to_string()], &[]).unwrap();
+        assert_eq!(
+            tool_totals(&conn).unwrap()["mcp__github__list_prs"].calls,
+            5
+        );
+        assert_eq!(cached_files(&conn).unwrap().len(), 1);
+    }
+}
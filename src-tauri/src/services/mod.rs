@@ -1,15 +1,89 @@
+pub mod ab_test;
+pub mod adapter;
+pub mod agent;
+pub mod billing;
+pub mod capability;
+pub mod compare;
 pub mod config;
+pub mod diagnostics;
+pub mod doctor;
 pub mod env_checker;
+pub mod env_file;
 pub mod env_manager;
+pub mod live_verify;
 pub mod mcp;
+pub mod mcp_health;
+pub mod mcp_usage;
+pub mod passcode;
+pub mod permissions;
 pub mod prompt;
 pub mod provider;
+pub mod proxy_metrics;
+pub mod ranking;
+pub mod registry;
+pub mod remote_host;
+pub mod sandbox;
+pub mod search;
+pub mod share;
+pub mod shell_wrapper;
 pub mod skill;
+pub mod slash_command;
 pub mod speedtest;
+pub mod subscription;
+pub mod switch_rule;
+pub mod team_pack;
+pub mod verification;
+pub mod wsl;
 
-pub use config::ConfigService;
+pub use ab_test::{AbTestArm, AbTestReport, AbTestService};
+pub use adapter::{AdapterProviders, AdapterService};
+pub use agent::{AgentService, RepoAgent, SubAgent};
+pub use billing::{BillingService, UpcomingRenewal};
+pub use capability::{CapabilityReport, CapabilityService};
+pub use compare::{CompareService, ProviderComparison};
+pub use config::{
+    BackupDetail, ConfigService, ImportMergeStrategy, LiveCheckpoint, LiveSyncResult,
+};
+pub use diagnostics::DiagnosticsService;
+pub use doctor::{DoctorReport, DoctorService};
+pub use env_file::EnvFileService;
+pub use live_verify::{LiveVerification, LiveVerifyCheck};
 pub use mcp::McpService;
+pub use mcp_health::McpHealthService;
+pub use mcp_usage::{McpUsageService, McpUsageStat};
+pub use passcode::{PasscodeService, UnlockToken};
+pub use permissions::{PermissionReport, PermissionService};
 pub use prompt::PromptService;
-pub use provider::{ProviderService, ProviderSortUpdate};
+pub use provider::{
+    DefaultImportResult, DefaultImportStatus, ProviderSearchFilters, ProviderSearchHit,
+    ProviderService, ProviderSortUpdate, SwitchDetails, SwitchOutcome, SwitchWarning,
+    SwitchWarningKind, WireApiFix,
+};
+pub use proxy_metrics::{
+    MetricsPeriod, ProviderMetrics, ProxyMetricsReport, ProxyMetricsService, ProxySample,
+};
+pub use ranking::{
+    BestProviderSwitch, ProviderRank, RankComponents, RankingService, RankingWeights,
+};
+pub use registry::{
+    Registry, RegistryKind, RegistryRefresh, RegistryRefreshReport, RegistryService, RegistrySource,
+};
+pub use remote_host::{RemoteHostService, RemoteSwitchResult};
+pub use sandbox::{SandboxResult, SandboxService};
+pub use search::{SearchHit, SearchService};
+pub use share::{ShareService, SharedSnippet};
+pub use shell_wrapper::ShellWrapperService;
 pub use skill::{Skill, SkillRepo, SkillService};
-pub use speedtest::{EndpointLatency, SpeedtestService};
+pub use slash_command::SlashCommandService;
+pub use speedtest::{
+    AuthProbeStatus, AuthenticatedProbe, EndpointLatency, LatencySample, LatestProbe,
+    SpeedtestService,
+};
+pub use subscription::{SubscriptionPreset, SubscriptionService};
+pub use switch_rule::SwitchRuleService;
+pub use team_pack::{
+    TeamPackApplyResult, TeamPackItem, TeamPackItemKind, TeamPackItemRef, TeamPackManifest,
+    TeamPackPreview, TeamPackResolution, TeamPackSelection, TeamPackService, TeamPackSkill,
+};
+pub use verification::VerificationService;
+pub use wsl::{WslDistroStatus, WslLiveFile, WslService};
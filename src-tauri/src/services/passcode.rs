@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::settings;
+
+/// 解锁令牌有效期（秒）
+const UNLOCK_TOKEN_TTL_SECS: i64 = 5 * 60;
+/// 口令最短长度
+const MIN_PASSCODE_LEN: usize = 4;
+
+/// 解锁令牌：查看或导出密钥前需先用口令换取
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockToken {
+    pub token: String,
+    /// 过期时间（Unix 毫秒）
+    pub expires_at: i64,
+}
+
+/// 已签发的解锁令牌（令牌 -> 过期时间，仅保存在内存中）
+fn unlock_tokens() -> &'static Mutex<HashMap<String, i64>> {
+    static TOKENS: OnceLock<Mutex<HashMap<String, i64>>> = OnceLock::new();
+    TOKENS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 应用口令：保护密钥查看、配置导出与携带密钥的深链接生成
+pub struct PasscodeService;
+
+impl PasscodeService {
+    /// 是否已设置口令
+    pub fn is_enabled() -> bool {
+        settings::get_settings().passcode_hash.is_some()
+    }
+
+    /// 设置、修改或清除口令（已设置时需提供当前口令）
+    pub fn set(current: Option<&str>, passcode: Option<&str>) -> Result<(), AppError> {
+        let mut app_settings = settings::get_settings();
+        if let Some(hash) = app_settings.passcode_hash.as_deref() {
+            if !verify(hash, current.unwrap_or_default()) {
+                return Err(invalid_passcode());
+            }
+        }
+
+        app_settings.passcode_hash = match passcode {
+            Some(passcode) => {
+                if passcode.chars().count() < MIN_PASSCODE_LEN {
+                    return Err(AppError::localized(
+                        "passcode.too_short",
+                        format!("口令长度至少为 {MIN_PASSCODE_LEN} 位"),
+                        format!("Passcode must be at least {MIN_PASSCODE_LEN} characters"),
+                    ));
+                }
+                Some(hash_passcode(passcode)?)
+            }
+            None => None,
+        };
+        settings::update_settings(app_settings)?;
+        Self::lock();
+        Ok(())
+    }
+
+    /// 校验口令并签发短时有效的解锁令牌
+    pub fn unlock(passcode: &str) -> Result<UnlockToken, AppError> {
+        let app_settings = settings::get_settings();
+        let Some(hash) = app_settings.passcode_hash.as_deref() else {
+            return Err(AppError::localized(
+                "passcode.not_set",
+                "尚未设置应用口令",
+                "App passcode is not set",
+            ));
+        };
+        if !verify(hash, passcode) {
+            return Err(invalid_passcode());
+        }
+
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        let expires_at = chrono::Utc::now().timestamp_millis() + UNLOCK_TOKEN_TTL_SECS * 1000;
+
+        let mut tokens = unlock_tokens().lock().map_err(AppError::from)?;
+        let now = chrono::Utc::now().timestamp_millis();
+        tokens.retain(|_, expiry| *expiry > now);
+        tokens.insert(token.clone(), expires_at);
+        Ok(UnlockToken { token, expires_at })
+    }
+
+    /// 作废所有解锁令牌
+    pub fn lock() {
+        if let Ok(mut tokens) = unlock_tokens().lock() {
+            tokens.clear();
+        }
+    }
+
+    /// 命令层调用：未设置口令时直接放行，否则要求有效的解锁令牌
+    pub fn require_unlocked(token: Option<&str>) -> Result<(), AppError> {
+        if !Self::is_enabled() {
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let valid = token.is_some_and(|token| {
+            unlock_tokens()
+                .lock()
+                .map(|tokens| tokens.get(token).is_some_and(|expiry| *expiry > now))
+                .unwrap_or(false)
+        });
+        if valid {
+            Ok(())
+        } else {
+            Err(AppError::localized(
+                "passcode.locked",
+                "该操作需要先输入应用口令解锁",
+                "This action requires unlocking with the app passcode",
+            ))
+        }
+    }
+}
+
+/// 判断字段名是否表示密钥（API Key / Token / Secret / Password）
+pub(crate) fn is_secret_key(key: &str) -> bool {
+    let upper = key.to_ascii_uppercase();
+    // 排除 MAX_OUTPUT_TOKENS 等数量配置
+    !upper.contains("TOKENS")
+        && ["KEY", "TOKEN", "SECRET", "PASSWORD"]
+            .iter()
+            .any(|marker| upper.contains(marker))
+}
+
+/// 掩码单个密钥值，仅保留首尾少量字符便于辨认
+pub fn mask_secret(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 12 {
+        return "****".to_string();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{head}****{tail}")
+}
+
+/// 递归掩码 JSON 中的密钥字段；Codex 的 `config`（TOML 文本）按行掩码
+pub fn mask_secrets(value: &mut Value) {
+    replace_secrets(value, &mask_secret);
+}
+
+/// 递归清空 JSON 中的密钥字段（导出不含密钥的配置时使用）
+pub fn clear_secrets(value: &mut Value) {
+    replace_secrets(value, &|_| String::new());
+}
+
+pub(crate) fn replace_secrets(value: &mut Value, replace: &dyn Fn(&str) -> String) {
+    static TOML_SECRET: OnceLock<Regex> = OnceLock::new();
+    let toml_secret = TOML_SECRET.get_or_init(|| {
+        Regex::new(
+            r#"(?mi)^(\s*[a-z0-9_]*(?:key|token|secret|password)[a-z0-9_]*\s*=\s*)"([^"]*)""#,
+        )
+        .expect("valid regex")
+    });
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::String(text) if is_secret_key(key) => *text = replace(text),
+                    Value::String(text) if key == "config" => {
+                        *text = toml_secret
+                            .replace_all(text, |caps: &regex::Captures| {
+                                format!("{}\"{}\"", &caps[1], replace(&caps[2]))
+                            })
+                            .into_owned();
+                    }
+                    _ => replace_secrets(child, replace),
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| replace_secrets(item, replace)),
+        _ => {}
+    }
+}
+
+fn invalid_passcode() -> AppError {
+    AppError::localized("passcode.invalid", "口令错误", "Incorrect passcode")
+}
+
+fn hash_passcode(passcode: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passcode.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Message(format!("生成口令哈希失败: {e}")))
+}
+
+fn verify(hash: &str, passcode: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(passcode.as_bytes(), &parsed)
+            .is_ok(),
+        Err(e) => {
+            log::warn!("口令哈希格式无效: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashed_passcode_verifies_only_original_input() {
+        let hash = hash_passcode("1234").expect("hash passcode");
+        assert!(hash.starts_with("$argon2"));
+        assert!(verify(&hash, "1234"));
+        assert!(!verify(&hash, "4321"));
+        assert!(!verify("not-a-phc-string", "1234"));
+    }
+
+    #[test]
+    fn mask_secrets_covers_env_auth_and_codex_toml() {
+        let mut value = serde_json::json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-ant-1234567890abcdef",
+                "ANTHROPIC_BASE_URL": "https://api.example.com"
+            },
+            "auth": { "OPENAI_API_KEY": "short" },
+            "config": "model = \"gpt-5\"\nexperimental_bearer_token = \"tok-1234567890abcdef\"\n"
+        });
+        mask_secrets(&mut value);
+
+        assert_eq!(value["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-a****cdef");
+        assert_eq!(
+            value["env"]["ANTHROPIC_BASE_URL"],
+            "https://api.example.com"
+        );
+        assert_eq!(value["auth"]["OPENAI_API_KEY"], "****");
+        let config = value["config"].as_str().unwrap();
+        assert!(config.contains("model = \"gpt-5\""));
+        assert!(config.contains("experimental_bearer_token = \"tok-****cdef\""));
+    }
+}
@@ -0,0 +1,208 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::{
+    get_app_config_dir, get_app_config_path, get_claude_credentials_path, get_claude_settings_path,
+};
+use crate::settings::{self, AppSettings};
+
+const DIR_MODE: u32 = 0o700;
+const FILE_MODE: u32 = 0o600;
+
+/// 权限审计中的单个文件 / 目录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionEntry {
+    pub path: String,
+    pub is_dir: bool,
+    /// 审计时的权限（八进制，如 `644`）
+    pub mode: String,
+    pub expected: String,
+    /// 其他用户可读
+    pub world_readable: bool,
+    /// 权限宽于预期
+    pub too_permissive: bool,
+    /// 本次已修复
+    pub fixed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 权限审计报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionReport {
+    /// 当前平台是否支持权限审计（仅 Unix）
+    pub supported: bool,
+    pub entries: Vec<PermissionEntry>,
+    /// 审计时对其他用户可读的路径
+    pub world_readable: Vec<String>,
+}
+
+/// 配置文件权限审计与加固
+///
+/// cc-switch 自身目录收紧为 700，config.json、备份、环境变量文件以及各应用的
+/// 凭据文件（settings.json、.credentials.json、auth.json、config.toml、.env）收紧为 600
+pub struct PermissionService;
+
+impl PermissionService {
+    /// 审计受管理的路径；`fix` 为 true 时将权限过宽的路径改为预期值
+    pub fn audit(fix: bool) -> PermissionReport {
+        if !cfg!(unix) {
+            return PermissionReport {
+                supported: false,
+                entries: Vec::new(),
+                world_readable: Vec::new(),
+            };
+        }
+
+        let entries: Vec<PermissionEntry> = Self::targets()
+            .into_iter()
+            .filter(|(path, _)| path.exists())
+            .map(|(path, is_dir)| {
+                let expected = if is_dir { DIR_MODE } else { FILE_MODE };
+                inspect(&path, is_dir, expected, fix)
+            })
+            .collect();
+        let world_readable = entries
+            .iter()
+            .filter(|entry| entry.world_readable)
+            .map(|entry| entry.path.clone())
+            .collect();
+
+        PermissionReport {
+            supported: true,
+            entries,
+            world_readable,
+        }
+    }
+
+    /// 开启 `strict_permissions` 时静默加固（写入配置后调用，失败仅记录日志）
+    pub fn harden_if_enabled() {
+        if !settings::get_settings().strict_permissions {
+            return;
+        }
+        for entry in Self::audit(true).entries {
+            if let Some(error) = entry.error {
+                log::warn!("收紧权限失败 {}: {error}", entry.path);
+            }
+        }
+    }
+
+    fn targets() -> Vec<(PathBuf, bool)> {
+        let app_dir = get_app_config_dir();
+        let mut targets = vec![
+            (app_dir.clone(), true),
+            (app_dir.join("backups"), true),
+            (app_dir.join("env"), true),
+            (get_app_config_path(), false),
+            (app_dir.join("config.json.bak"), false),
+            (AppSettings::settings_path(), false),
+            (get_claude_settings_path(), false),
+            (get_claude_credentials_path(), false),
+            (crate::codex_config::get_codex_auth_path(), false),
+            (crate::codex_config::get_codex_config_path(), false),
+            (crate::gemini_config::get_gemini_env_path(), false),
+        ];
+        for (dir, extension) in [
+            (app_dir.join("backups"), "json"),
+            (app_dir.join("env"), "env"),
+        ] {
+            targets.extend(files_with_extension(&dir, extension).map(|path| (path, false)));
+        }
+        targets.sort();
+        targets.dedup();
+        targets
+    }
+}
+
+fn files_with_extension(dir: &Path, extension: &str) -> impl Iterator<Item = PathBuf> {
+    let extension = extension.to_string();
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(move |path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == extension.as_str())
+        })
+}
+
+#[cfg(unix)]
+fn inspect(path: &Path, is_dir: bool, expected: u32, fix: bool) -> PermissionEntry {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut entry = PermissionEntry {
+        path: path.to_string_lossy().to_string(),
+        is_dir,
+        mode: String::new(),
+        expected: format!("{expected:o}"),
+        world_readable: false,
+        too_permissive: false,
+        fixed: false,
+        error: None,
+    };
+    let mode = match std::fs::metadata(path) {
+        Ok(meta) => meta.permissions().mode() & 0o777,
+        Err(e) => {
+            entry.error = Some(e.to_string());
+            return entry;
+        }
+    };
+    entry.mode = format!("{mode:o}");
+    entry.world_readable = mode & 0o004 != 0;
+    entry.too_permissive = mode & !expected != 0;
+
+    if fix && entry.too_permissive {
+        match std::fs::set_permissions(path, std::fs::Permissions::from_mode(expected)) {
+            Ok(()) => entry.fixed = true,
+            Err(e) => entry.error = Some(e.to_string()),
+        }
+    }
+    entry
+}
+
+#[cfg(not(unix))]
+fn inspect(path: &Path, is_dir: bool, expected: u32, _fix: bool) -> PermissionEntry {
+    PermissionEntry {
+        path: path.to_string_lossy().to_string(),
+        is_dir,
+        mode: String::new(),
+        expected: format!("{expected:o}"),
+        world_readable: false,
+        too_permissive: false,
+        fixed: false,
+        error: None,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn inspect_reports_and_fixes_world_readable_file() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, "{}").expect("write file");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).expect("chmod 644");
+
+        let report = inspect(&path, false, FILE_MODE, false);
+        assert_eq!(report.mode, "644");
+        assert!(report.world_readable && report.too_permissive && !report.fixed);
+
+        let fixed = inspect(&path, false, FILE_MODE, true);
+        assert!(fixed.fixed);
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+
+        // 比预期更严格的权限不做改动
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o400)).unwrap();
+        assert!(!inspect(&path, false, FILE_MODE, true).fixed);
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
 use crate::app_config::AppType;
-use crate::config::write_text_file;
+use serde::Serialize;
+
+use crate::config::{get_app_config_dir, write_text_file};
 use crate::error::AppError;
 use crate::prompt::Prompt;
 use crate::prompt_files::prompt_file_path;
@@ -200,4 +202,146 @@ impl PromptService {
             std::fs::read_to_string(&file_path).map_err(|e| AppError::io(&file_path, e))?;
         Ok(Some(content))
     }
+
+    /// 读取全局指令文件（CLAUDE.md / AGENTS.md / GEMINI.md），并拆出 cc-switch 管理的区段
+    pub fn read_global_instructions(app: AppType) -> Result<GlobalInstructions, AppError> {
+        let file_path = prompt_file_path(&app)?;
+        let content = if file_path.exists() {
+            std::fs::read_to_string(&file_path).map_err(|e| AppError::io(&file_path, e))?
+        } else {
+            String::new()
+        };
+
+        Ok(GlobalInstructions {
+            path: file_path.to_string_lossy().to_string(),
+            exists: file_path.exists(),
+            managed: extract_managed_section(&content).map(str::to_string),
+            content,
+        })
+    }
+
+    /// 写入 cc-switch 管理的区段（区段外的用户内容保持不变），写入前自动备份原文件
+    pub fn write_global_instructions(
+        app: AppType,
+        managed: &str,
+    ) -> Result<GlobalInstructions, AppError> {
+        let file_path = prompt_file_path(&app)?;
+        let existing = if file_path.exists() {
+            let content =
+                std::fs::read_to_string(&file_path).map_err(|e| AppError::io(&file_path, e))?;
+            backup_instructions(&app, &content)?;
+            content
+        } else {
+            String::new()
+        };
+
+        let merged = merge_managed_section(&existing, managed);
+        write_text_file(&file_path, &merged)?;
+        Self::read_global_instructions(app)
+    }
+}
+
+/// 全局指令文件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalInstructions {
+    pub path: String,
+    pub exists: bool,
+    /// 完整文件内容
+    pub content: String,
+    /// cc-switch 管理区段内的内容（无区段时为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub managed: Option<String>,
+}
+
+const MANAGED_BEGIN: &str = "<!-- cc-switch:begin -->";
+const MANAGED_END: &str = "<!-- cc-switch:end -->";
+/// 每个应用保留的指令文件备份数量
+const MAX_INSTRUCTION_BACKUPS: usize = 10;
+
+/// 提取管理区段内容（不含标记行）
+fn extract_managed_section(content: &str) -> Option<&str> {
+    let start = content.find(MANAGED_BEGIN)? + MANAGED_BEGIN.len();
+    let end = start + content[start..].find(MANAGED_END)?;
+    Some(content[start..end].trim_matches('\n'))
+}
+
+/// 用新内容替换管理区段；文件中没有区段时追加到末尾，管理内容为空时移除区段
+fn merge_managed_section(existing: &str, managed: &str) -> String {
+    let managed = managed.trim_matches('\n');
+    let section = format!("{MANAGED_BEGIN}\n{managed}\n{MANAGED_END}");
+
+    let range = existing.find(MANAGED_BEGIN).and_then(|start| {
+        existing[start..]
+            .find(MANAGED_END)
+            .map(|offset| (start, start + offset + MANAGED_END.len()))
+    });
+
+    match range {
+        Some((start, end)) => {
+            let (before, after) = (&existing[..start], &existing[end..]);
+            if managed.trim().is_empty() {
+                let before = before.trim_end_matches('\n');
+                let after = after.trim_start_matches('\n');
+                match (before.is_empty(), after.is_empty()) {
+                    (true, _) => after.to_string(),
+                    (false, true) => format!("{before}\n"),
+                    (false, false) => format!("{before}\n\n{after}"),
+                }
+            } else {
+                format!("{before}{section}{after}")
+            }
+        }
+        None if managed.trim().is_empty() => existing.to_string(),
+        None if existing.trim().is_empty() => format!("{section}\n"),
+        None => format!("{}\n\n{section}\n", existing.trim_end_matches('\n')),
+    }
+}
+
+/// 写入前备份原指令文件到 `~/.cc-switch/backups/instructions/`
+fn backup_instructions(app: &AppType, content: &str) -> Result<(), AppError> {
+    let dir = get_app_config_dir().join("backups").join("instructions");
+    let prefix = format!("{}-", app.as_str());
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S%3f");
+    write_text_file(&dir.join(format!("{prefix}{timestamp}.md")), content)?;
+
+    // 仅保留最近的若干份（文件名含时间戳，字典序即时间序）
+    let mut backups: Vec<_> = std::fs::read_dir(&dir)
+        .map_err(|e| AppError::io(&dir, e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&prefix))
+        })
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(MAX_INSTRUCTION_BACKUPS);
+    for path in backups.into_iter().take(excess) {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn managed_section_merges_without_touching_user_content() {
+        let user = "# My rules\nBe concise.\n";
+        let merged = merge_managed_section(user, "Use pnpm.");
+        assert_eq!(
+            merged,
+            format!("# My rules\nBe concise.\n\n{MANAGED_BEGIN}\nUse pnpm.\n{MANAGED_END}\n")
+        );
+        assert_eq!(extract_managed_section(&merged), Some("Use pnpm."));
+
+        let updated = merge_managed_section(&merged, "Use bun.");
+        assert!(updated.starts_with("# My rules\nBe concise.\n\n"));
+        assert_eq!(extract_managed_section(&updated), Some("Use bun."));
+
+        assert_eq!(merge_managed_section(&updated, ""), user);
+    }
 }
@@ -1,28 +1,42 @@
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::app_config::{AppType, MultiAppConfig};
 use crate::codex_config::{get_codex_auth_path, get_codex_config_path, write_codex_live_atomic};
 use crate::config::{
-    delete_file, get_claude_settings_path, get_provider_config_path, read_json_file,
-    write_json_file, write_text_file,
+    delete_claude_credentials, delete_file, get_claude_credentials_path, get_claude_settings_path,
+    get_provider_config_path, read_claude_credentials, read_json_file, sha256_hex,
+    write_claude_credentials, write_json_file, write_text_file,
 };
 use crate::error::AppError;
-use crate::provider::{Provider, ProviderMeta, UsageData, UsageResult};
-use crate::settings::{self, CustomEndpoint};
+use crate::pagination::{self, Page};
+use crate::placeholder;
+use crate::provider::{
+    AuthKind, ClaudeSettingsOverrides, CodexLoginType, OrderedProviders, Provider, ProviderMeta,
+    ProviderPerformance, RateLimitInfo, UsageData, UsageResult,
+};
+use crate::provider_csv::{self, CsvColumnMapping, CsvImportResult, CsvRowError};
+use crate::services::env_checker::InstalledCli;
+use crate::services::live_verify::{self, LiveVerification};
+use crate::services::{CapabilityService, EndpointLatency, SpeedtestService, WslService};
+use crate::settings::{self, ClaudeSettingsMerge, CustomEndpoint, TraySort};
 use crate::store::AppState;
 use crate::usage_script;
 
 /// 供应商相关业务逻辑
 pub struct ProviderService;
 
-#[derive(Clone)]
-enum LiveSnapshot {
+/// 应用 live 配置文件的快照（切换失败回滚、live 检查点均使用）
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "app", rename_all = "lowercase")]
+pub(crate) enum LiveSnapshot {
     Claude {
         settings: Option<Value>,
+        /// `~/.claude/.credentials.json`（macOS 为钥匙串）原文（OAuth 登录凭据）
+        credentials: Option<String>,
     },
     Codex {
         auth: Option<Value>,
@@ -30,9 +44,21 @@ enum LiveSnapshot {
     },
     Gemini {
         env: Option<HashMap<String, String>>, // 新增
+        /// `~/.gemini/oauth_creds.json`（Google 账号 OAuth 凭据缓存）
+        oauth_creds: Option<Value>,
     },
 }
 
+/// 后置操作的执行情况
+struct PostCommitReport {
+    /// 写入 live 配置耗时（毫秒）
+    live_write_ms: u64,
+    /// 是否同步了 MCP 服务器（无服务器时为 false）
+    mcp_synced: bool,
+    /// 写入校验结果（未开启 `verify_live_writes` 时为 None）
+    verification: Option<LiveVerification>,
+}
+
 #[derive(Clone)]
 struct PostCommitAction {
     app_type: AppType,
@@ -43,15 +69,23 @@ struct PostCommitAction {
 }
 
 impl LiveSnapshot {
-    fn restore(&self) -> Result<(), AppError> {
+    pub(crate) fn restore(&self) -> Result<(), AppError> {
         match self {
-            LiveSnapshot::Claude { settings } => {
+            LiveSnapshot::Claude {
+                settings,
+                credentials,
+            } => {
                 let path = get_claude_settings_path();
                 if let Some(value) = settings {
                     write_json_file(&path, value)?;
                 } else if path.exists() {
                     delete_file(&path)?;
                 }
+                if let Some(content) = credentials {
+                    write_claude_credentials(content)?;
+                } else {
+                    delete_claude_credentials()?;
+                }
             }
             LiveSnapshot::Codex { auth, config } => {
                 let auth_path = get_codex_auth_path();
@@ -68,15 +102,24 @@ impl LiveSnapshot {
                     delete_file(&config_path)?;
                 }
             }
-            LiveSnapshot::Gemini { env } => {
+            LiveSnapshot::Gemini { env, oauth_creds } => {
                 // 新增
-                use crate::gemini_config::{get_gemini_env_path, write_gemini_env_atomic};
+                use crate::gemini_config::{
+                    get_gemini_env_path, get_gemini_oauth_creds_path, write_gemini_env_atomic,
+                    write_gemini_oauth_creds,
+                };
                 let path = get_gemini_env_path();
                 if let Some(env_map) = env {
                     write_gemini_env_atomic(env_map)?;
                 } else if path.exists() {
                     delete_file(&path)?;
                 }
+                let creds_path = get_gemini_oauth_creds_path();
+                if let Some(creds) = oauth_creds {
+                    write_gemini_oauth_creds(creds)?;
+                } else if creds_path.exists() {
+                    delete_file(&creds_path)?;
+                }
             }
         }
         Ok(())
@@ -86,6 +129,47 @@ impl LiveSnapshot {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::provider::{ClaudeModelAliases, ClaudePermissions};
+
+    #[test]
+    fn search_score_ranks_name_above_notes_and_filters_apply() {
+        let mut provider = Provider::with_id(
+            "p1".into(),
+            "Packy".into(),
+            json!({ "env": { "ANTHROPIC_BASE_URL": "https://api.packy.example" } }),
+            None,
+        );
+        provider.notes = Some("backup for packy outages".into());
+        provider.category = Some("third_party".into());
+        provider.meta = Some(ProviderMeta {
+            tags: vec!["Cheap".into()],
+            ..Default::default()
+        });
+
+        let (name_score, fields) =
+            ProviderService::search_score(&provider, Some("https://api.packy.example"), "packy");
+        assert_eq!(fields, vec!["name", "endpoint", "notes"]);
+        let (notes_score, _) = ProviderService::search_score(&provider, None, "outages");
+        assert!(name_score > notes_score && notes_score > 0);
+        assert_eq!(
+            ProviderService::search_score(&provider, None, "cheap").1,
+            vec!["tags"]
+        );
+
+        let filters = ProviderSearchFilters {
+            categories: vec!["third_party".into()],
+            tags: vec!["cheap".into()],
+            max_latency_ms: Some(500),
+        };
+        assert!(filters.matches(&provider, Some(300)));
+        assert!(!filters.matches(&provider, Some(800)));
+        assert!(!filters.matches(&provider, None));
+        let official = ProviderSearchFilters {
+            categories: vec!["official".into()],
+            ..Default::default()
+        };
+        assert!(!official.matches(&provider, None));
+    }
 
     #[test]
     fn validate_provider_settings_rejects_missing_auth() {
@@ -121,13 +205,237 @@ mod tests {
         assert_eq!(api_key, "token");
         assert_eq!(base_url, "https://claude.example");
     }
+
+    #[test]
+    fn claude_live_settings_injects_custom_headers() {
+        let mut provider = Provider::with_id(
+            "claude".into(),
+            "Claude".into(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token" } }),
+            None,
+        );
+        let mut meta = ProviderMeta::default();
+        meta.headers.insert("X-Team".into(), "core".into());
+        meta.headers.insert("X-Api-Version".into(), "2".into());
+        provider.meta = Some(meta);
+
+        let live = ProviderService::claude_live_settings(&provider);
+        assert_eq!(
+            live["env"]["ANTHROPIC_CUSTOM_HEADERS"],
+            json!("X-Api-Version: 2\nX-Team: core")
+        );
+        assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], json!("token"));
+    }
+
+    #[test]
+    fn claude_live_settings_applies_structured_settings() {
+        let mut provider = Provider::with_id(
+            "claude".into(),
+            "Claude".into(),
+            json!({
+                "env": { "ANTHROPIC_AUTH_TOKEN": "token" },
+                "permissions": { "allow": ["Bash(ls)"], "defaultMode": "plan" },
+                "model": "sonnet"
+            }),
+            None,
+        );
+        let overrides = ProviderService::normalize_claude_settings(ClaudeSettingsOverrides {
+            permissions: Some(ClaudePermissions {
+                allow: vec![" Bash(git:*) ".into(), "Bash(git:*)".into(), "".into()],
+                deny: vec!["Read(.env)".into()],
+            }),
+            include_co_authored_by: Some(false),
+            model: Some("opus".into()),
+            model_aliases: ClaudeModelAliases {
+                opus: Some("glm-4.6".into()),
+                ..Default::default()
+            },
+        })
+        .unwrap();
+        provider.meta = Some(ProviderMeta {
+            claude_settings: Some(overrides.clone()),
+            ..Default::default()
+        });
+
+        let live = ProviderService::claude_live_settings(&provider);
+        assert_eq!(
+            live["permissions"],
+            json!({ "allow": ["Bash(git:*)"], "deny": ["Read(.env)"], "defaultMode": "plan" })
+        );
+        assert_eq!(live["includeCoAuthoredBy"], json!(false));
+        assert_eq!(live["model"], json!("opus"));
+        assert_eq!(
+            live["env"]["ANTHROPIC_DEFAULT_OPUS_MODEL"],
+            json!("glm-4.6")
+        );
+        assert_eq!(
+            overrides.managed_keys(),
+            vec!["permissions", "includeCoAuthoredBy", "model"]
+        );
+
+        let mut backfilled = live.clone();
+        overrides.strip(&mut backfilled);
+        assert_eq!(
+            backfilled,
+            json!({
+                "env": { "ANTHROPIC_AUTH_TOKEN": "token" },
+                "permissions": { "defaultMode": "plan" }
+            })
+        );
+    }
+
+    #[test]
+    fn codex_live_config_injects_extra_env_and_rejects_reserved_keys() {
+        let mut provider = Provider::with_id(
+            "codex".into(),
+            "Codex".into(),
+            json!({
+                "auth": {},
+                "config": "model = \"gpt-5\"\n[shell_environment_policy]\nset = { KEEP = \"1\", OLD = \"x\" }\n"
+            }),
+            None,
+        );
+        let mut meta = ProviderMeta::default();
+        meta.extra_env
+            .insert("OPENAI_ORG_ID".into(), "org-1".into());
+        provider.meta = Some(meta);
+
+        let text = ProviderService::codex_live_config_text(&provider)
+            .unwrap()
+            .unwrap();
+        let table: toml::Table = toml::from_str(&text).unwrap();
+        let set = &table["shell_environment_policy"]["set"];
+        assert_eq!(set["OPENAI_ORG_ID"].as_str(), Some("org-1"));
+        assert_eq!(set["KEEP"].as_str(), Some("1"));
+
+        let cleaned =
+            crate::codex_config::apply_shell_env(&text, &HashMap::new(), &["OLD".into()]).unwrap();
+        assert!(!cleaned.contains("OLD"));
+
+        let mut reserved = HashMap::new();
+        reserved.insert("CODEX_HOME".to_string(), "/tmp".to_string());
+        assert!(ProviderService::validate_extra_env(&reserved).is_err());
+        let mut invalid = HashMap::new();
+        invalid.insert("1BAD".to_string(), "x".to_string());
+        assert!(ProviderService::validate_extra_env(&invalid).is_err());
+    }
+
+    #[test]
+    fn codex_oauth_tokens_survive_api_key_switch() {
+        let mut official = Provider::with_id(
+            "official".into(),
+            "OpenAI".into(),
+            json!({ "auth": {}, "config": "" }),
+            None,
+        );
+        let live_auth = json!({
+            "OPENAI_API_KEY": null,
+            "tokens": { "access_token": "at", "refresh_token": "rt" },
+            "last_refresh": "2025-01-01T00:00:00Z"
+        });
+        ProviderService::stash_codex_oauth(&mut official, &live_auth);
+        assert_eq!(
+            ProviderService::codex_login_type(&official),
+            CodexLoginType::Chatgpt
+        );
+
+        let restored = ProviderService::codex_live_auth(&official);
+        assert_eq!(restored["tokens"]["refresh_token"], json!("rt"));
+        assert_eq!(restored["last_refresh"], json!("2025-01-01T00:00:00Z"));
+
+        // API Key 供应商不写入 OAuth 字段
+        let mut relay = Provider::with_id(
+            "relay".into(),
+            "Relay".into(),
+            json!({ "auth": { "OPENAI_API_KEY": "sk-1" } }),
+            None,
+        );
+        relay.meta = official.meta.clone();
+        assert_eq!(
+            ProviderService::codex_login_type(&relay),
+            CodexLoginType::ApiKey
+        );
+        assert!(ProviderService::codex_live_auth(&relay)
+            .get("tokens")
+            .is_none());
+    }
+
+    #[test]
+    fn merge_managed_keys_preserves_local_settings() {
+        let existing = json!({
+            "env": { "ANTHROPIC_AUTH_TOKEN": "old", "STALE": "1" },
+            "model": "opus",
+            "permissions": { "allow": ["Bash(ls:*)"] },
+            "hooks": {}
+        });
+        let content = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "new" } });
+        let managed = vec!["env".to_string(), "model".to_string()];
+
+        let merged = ProviderService::merge_managed_keys(existing, &content, &managed);
+        assert_eq!(merged["env"], json!({ "ANTHROPIC_AUTH_TOKEN": "new" }));
+        assert!(merged.get("model").is_none());
+        assert_eq!(merged["permissions"]["allow"][0], json!("Bash(ls:*)"));
+        assert!(merged.get("hooks").is_some());
+    }
+
+    #[test]
+    fn sort_providers_supports_name_and_last_used() {
+        let mut providers = HashMap::new();
+        for (id, name, sort_index, last_used) in [
+            ("a", "Zeta", 0, Some(10)),
+            ("b", "alpha", 1, None),
+            ("c", "Beta", 2, Some(20)),
+        ] {
+            let mut provider = Provider::with_id(id.into(), name.into(), json!({}), None);
+            provider.sort_index = Some(sort_index);
+            provider.meta = Some(ProviderMeta {
+                last_used_at: last_used,
+                ..ProviderMeta::default()
+            });
+            providers.insert(id.to_string(), provider);
+        }
+
+        let ids = |strategy| {
+            ProviderService::sort_providers(&AppType::Claude, &providers, strategy)
+                .into_iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(TraySort::Manual), vec!["a", "b", "c"]);
+        assert_eq!(ids(TraySort::Name), vec!["b", "c", "a"]);
+        assert_eq!(ids(TraySort::LastUsed), vec!["c", "a", "b"]);
+        // 无测速数据时回退到手动排序
+        assert_eq!(ids(TraySort::Latency), vec!["a", "b", "c"]);
+
+        // 置顶优先，且不受排序方式影响
+        providers.get_mut("c").unwrap().pinned = true;
+        let ids = |strategy| {
+            ProviderService::sort_providers(&AppType::Claude, &providers, strategy)
+                .into_iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(TraySort::Manual), vec!["c", "a", "b"]);
+        assert_eq!(ids(TraySort::Name), vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn validate_custom_headers_rejects_invalid_names() {
+        let mut headers = HashMap::new();
+        headers.insert("Bad Header".to_string(), "value".to_string());
+        assert!(ProviderService::validate_custom_headers(&headers).is_err());
+
+        let mut headers = HashMap::new();
+        headers.insert("X-Ok".to_string(), "line\nbreak".to_string());
+        assert!(ProviderService::validate_custom_headers(&headers).is_err());
+    }
 }
 
 /// Gemini 认证类型枚举
 ///
 /// 用于优化性能，避免重复检测供应商类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum GeminiAuthType {
+pub(crate) enum GeminiAuthType {
     /// PackyCode 供应商（使用 API Key）
     Packycode,
     /// Google 官方（使用 OAuth）
@@ -148,6 +456,18 @@ impl ProviderService {
     // PackyCode 关键词常量
     const PACKYCODE_KEYWORDS: [&'static str; 3] = ["packycode", "packyapi", "packy"];
 
+    // Claude Code 读取自定义请求头的环境变量
+    const CLAUDE_CUSTOM_HEADERS_ENV: &'static str = "ANTHROPIC_CUSTOM_HEADERS";
+
+    // Codex 额外环境变量中不允许覆盖的变量（由 auth.json / 包装脚本 / 系统管理）
+    const RESERVED_EXTRA_ENV_KEYS: &'static [&'static str] = &[
+        "OPENAI_API_KEY",
+        "OPENAI_BASE_URL",
+        "CODEX_HOME",
+        "HOME",
+        "PATH",
+    ];
+
     /// 检测 Gemini 供应商的认证类型
     ///
     /// 一次性检测，避免在多个地方重复调用 `is_packycode_gemini` 和 `is_google_official_gemini`
@@ -157,7 +477,12 @@ impl ProviderService {
     /// - `GeminiAuthType::GoogleOfficial`: Google 官方，使用 OAuth
     /// - `GeminiAuthType::Packycode`: PackyCode 供应商，使用 API Key
     /// - `GeminiAuthType::Generic`: 其他通用供应商，使用 API Key
-    fn detect_gemini_auth_type(provider: &Provider) -> GeminiAuthType {
+    pub(crate) fn detect_gemini_auth_type(provider: &Provider) -> GeminiAuthType {
+        // 已采集 Google 账号凭据的 OAuth 供应商
+        if provider.is_oauth() {
+            return GeminiAuthType::GoogleOfficial;
+        }
+
         // 优先检查 partner_promotion_key（最可靠）
         if let Some(key) = provider
             .meta
@@ -403,86 +728,29 @@ impl ProviderService {
     }
 
     /// 归一化 Claude 模型键：读旧键(ANTHROPIC_SMALL_FAST_MODEL)，写新键(DEFAULT_*), 并删除旧键
+    ///
+    /// 具体规则登记在 config_migrations 注册表中
     fn normalize_claude_models_in_value(settings: &mut Value) -> bool {
-        let mut changed = false;
-        let env = match settings.get_mut("env") {
-            Some(v) if v.is_object() => v.as_object_mut().unwrap(),
-            _ => return changed,
-        };
-
-        let model = env
-            .get("ANTHROPIC_MODEL")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-        let small_fast = env
-            .get("ANTHROPIC_SMALL_FAST_MODEL")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-
-        let current_haiku = env
-            .get("ANTHROPIC_DEFAULT_HAIKU_MODEL")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-        let current_sonnet = env
-            .get("ANTHROPIC_DEFAULT_SONNET_MODEL")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-        let current_opus = env
-            .get("ANTHROPIC_DEFAULT_OPUS_MODEL")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-
-        let target_haiku = current_haiku
-            .or_else(|| small_fast.clone())
-            .or_else(|| model.clone());
-        let target_sonnet = current_sonnet
-            .or_else(|| model.clone())
-            .or_else(|| small_fast.clone());
-        let target_opus = current_opus
-            .or_else(|| model.clone())
-            .or_else(|| small_fast.clone());
-
-        if env.get("ANTHROPIC_DEFAULT_HAIKU_MODEL").is_none() {
-            if let Some(v) = target_haiku {
-                env.insert(
-                    "ANTHROPIC_DEFAULT_HAIKU_MODEL".to_string(),
-                    Value::String(v),
-                );
-                changed = true;
-            }
-        }
-        if env.get("ANTHROPIC_DEFAULT_SONNET_MODEL").is_none() {
-            if let Some(v) = target_sonnet {
-                env.insert(
-                    "ANTHROPIC_DEFAULT_SONNET_MODEL".to_string(),
-                    Value::String(v),
-                );
-                changed = true;
-            }
-        }
-        if env.get("ANTHROPIC_DEFAULT_OPUS_MODEL").is_none() {
-            if let Some(v) = target_opus {
-                env.insert("ANTHROPIC_DEFAULT_OPUS_MODEL".to_string(), Value::String(v));
-                changed = true;
-            }
-        }
-
-        if env.remove("ANTHROPIC_SMALL_FAST_MODEL").is_some() {
-            changed = true;
-        }
-
-        changed
+        !crate::config_migrations::apply(&AppType::Claude, settings).is_empty()
     }
 
-    fn normalize_provider_if_claude(app_type: &AppType, provider: &mut Provider) {
-        if matches!(app_type, AppType::Claude) {
-            let mut v = provider.settings_config.clone();
-            if Self::normalize_claude_models_in_value(&mut v) {
-                provider.settings_config = v;
-            }
-        }
+    /// 对待保存的供应商应用配置键迁移，返回生效的迁移 ID
+    fn migrate_provider_settings(app_type: &AppType, provider: &mut Provider) -> Vec<&'static str> {
+        crate::config_migrations::apply(app_type, &mut provider.settings_config)
     }
+
     fn run_transaction<R, F>(state: &AppState, f: F) -> Result<R, AppError>
+    where
+        F: FnOnce(&mut MultiAppConfig) -> Result<(R, Option<PostCommitAction>), AppError>,
+    {
+        Self::run_transaction_with_report(state, f).map(|(result, _)| result)
+    }
+
+    /// 同 [`Self::run_transaction`]，并返回后置操作的执行情况（无后置操作时为 None）
+    fn run_transaction_with_report<R, F>(
+        state: &AppState,
+        f: F,
+    ) -> Result<(R, Option<PostCommitReport>), AppError>
     where
         F: FnOnce(&mut MultiAppConfig) -> Result<(R, Option<PostCommitAction>), AppError>,
     {
@@ -498,6 +766,11 @@ impl ProviderService {
         drop(guard);
 
         if let Err(save_err) = state.save() {
+            // 磁盘版本被外部修改时回滚保存同样会被拒绝：只恢复内存状态，原样返回冲突错误
+            if save_err.code() == "config.external_change" {
+                *state.config.write().map_err(AppError::from)? = original;
+                return Err(save_err);
+            }
             if let Err(rollback_err) = Self::restore_config_only(state, original.clone()) {
                 return Err(AppError::localized(
                     "config.save.rollback_failed",
@@ -508,22 +781,28 @@ impl ProviderService {
             return Err(save_err);
         }
 
+        let mut report = None;
         if let Some(action) = action {
-            if let Err(err) = Self::apply_post_commit(state, &action) {
-                if let Err(rollback_err) =
-                    Self::rollback_after_failure(state, original.clone(), action.backup.clone())
-                {
-                    return Err(AppError::localized(
-                        "post_commit.rollback_failed",
-                        format!("后置操作失败: {err}；回滚失败: {rollback_err}"),
-                        format!("Post-commit step failed: {err}; rollback failed: {rollback_err}"),
-                    ));
+            match Self::apply_post_commit(state, &action) {
+                Ok(done) => report = Some(done),
+                Err(err) => {
+                    if let Err(rollback_err) =
+                        Self::rollback_after_failure(state, original.clone(), action.backup.clone())
+                    {
+                        return Err(AppError::localized(
+                            "post_commit.rollback_failed",
+                            format!("后置操作失败: {err}；回滚失败: {rollback_err}"),
+                            format!(
+                                "Post-commit step failed: {err}; rollback failed: {rollback_err}"
+                            ),
+                        ));
+                    }
+                    return Err(err);
                 }
-                return Err(err);
             }
         }
 
-        Ok(result)
+        Ok((result, report))
     }
 
     fn restore_config_only(state: &AppState, snapshot: MultiAppConfig) -> Result<(), AppError> {
@@ -543,24 +822,121 @@ impl ProviderService {
         backup.restore()
     }
 
-    fn apply_post_commit(state: &AppState, action: &PostCommitAction) -> Result<(), AppError> {
-        Self::write_live_snapshot(&action.app_type, &action.provider)?;
+    fn apply_post_commit(
+        state: &AppState,
+        action: &PostCommitAction,
+    ) -> Result<PostCommitReport, AppError> {
+        let started = std::time::Instant::now();
+        let wsl_backups = {
+            let _lock = crate::config_lock::acquire()?;
+            Self::write_live_snapshot(state, &action.app_type, &action.provider)?;
+            // 启用同步的 WSL 发行版与 Windows 侧在同一事务中写入，后续步骤失败时一并恢复
+            let mut expanded = action.provider.clone();
+            expanded.settings_config = placeholder::expand_value(&action.provider.settings_config)?;
+            WslService::write_live(&action.app_type, &expanded)?
+        };
+        let live_write_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        Self::finish_post_commit(state, action, live_write_ms)
+            .inspect_err(|_| WslService::restore(&wsl_backups))
+    }
+
+    /// live 配置写入之后的步骤：校验、MCP 同步、快照回填等
+    fn finish_post_commit(
+        state: &AppState,
+        action: &PostCommitAction,
+        live_write_ms: u64,
+    ) -> Result<PostCommitReport, AppError> {
+        // 在 MCP 同步等后续改动之前比对刚写入的文件
+        let mut verification = if crate::settings::get_settings().verify_live_writes {
+            let mut expanded = action.provider.clone();
+            expanded.settings_config = placeholder::expand_value(&action.provider.settings_config)?;
+            Some(live_verify::verify_files(&action.app_type, &expanded))
+        } else {
+            None
+        };
+        // 状态栏标记文件写入失败不影响切换
+        if let Err(e) = crate::statusline::write_active_provider(&action.app_type, &action.provider)
+        {
+            log::warn!("更新当前供应商标记文件失败: {e}");
+        }
+        if crate::settings::get_settings().write_env_files {
+            if let Err(e) =
+                crate::services::EnvFileService::write(&action.app_type, &action.provider)
+            {
+                log::warn!("写入环境变量文件失败: {e}");
+            }
+        }
+        let mut mcp_synced = false;
         if action.sync_mcp {
-            // 使用 v3.7.0 统一的 MCP 同步机制，支持所有应用
+            // 使用 v3.7.0 统一的 MCP 同步机制，同步范围由 `mcp_switch_sync` 按应用配置
             use crate::services::mcp::McpService;
-            McpService::sync_all_enabled(state)?;
+            use crate::settings::McpSwitchSync;
+            let policy = crate::settings::get_settings()
+                .mcp_switch_sync
+                .get(action.app_type.as_str())
+                .copied()
+                .unwrap_or_default();
+            mcp_synced = match policy {
+                McpSwitchSync::All => McpService::sync_all_enabled(state)? > 0,
+                McpSwitchSync::App => {
+                    McpService::sync_enabled_for_app(state, &action.app_type)? > 0
+                }
+                McpSwitchSync::Off => false,
+            };
+        }
+        if action.app_type == AppType::Claude {
+            if let Some(names) = action
+                .provider
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.slash_commands.as_ref())
+            {
+                crate::services::SlashCommandService::apply_enabled_set(state, names)?;
+            }
+        }
+        if let Some(verification) = verification.as_mut() {
+            if let Some(check) = live_verify::run_cli_check(&action.app_type) {
+                verification.push(check);
+            }
         }
+        // 校验失败时返回错误，由事务回滚 live 配置
+        let verification = verification
+            .map(LiveVerification::into_result)
+            .transpose()?;
         if action.refresh_snapshot {
             Self::refresh_provider_snapshot(state, &action.app_type, &action.provider.id)?;
         }
-        Ok(())
+        crate::services::PermissionService::harden_if_enabled();
+        Ok(PostCommitReport {
+            live_write_ms,
+            mcp_synced,
+            verification,
+        })
     }
 
+    /// 将写入后的 live 配置回填到供应商快照
+    ///
+    /// live 文件哈希与上次回填时一致（快照已是最新）时跳过读取与 config.json 写入
     fn refresh_provider_snapshot(
         state: &AppState,
         app_type: &AppType,
         provider_id: &str,
     ) -> Result<(), AppError> {
+        let live_hash = Self::live_files_hash(app_type);
+        {
+            let config = state.config.read().map_err(AppError::from)?;
+            let unchanged = config
+                .get_manager(app_type)
+                .and_then(|manager| manager.providers.get(provider_id))
+                .and_then(|provider| provider.meta.as_ref())
+                .and_then(|meta| meta.live_hash.as_deref())
+                .is_some_and(|recorded| recorded == live_hash);
+            if unchanged {
+                log::debug!("供应商 {provider_id} 的 live 配置未变化，跳过快照刷新");
+                return Ok(());
+            }
+        }
+
         match app_type {
             AppType::Claude => {
                 let settings_path = get_claude_settings_path();
@@ -571,13 +947,16 @@ impl ProviderService {
                         "Claude settings file missing; cannot refresh snapshot",
                     ));
                 }
-                let mut live_after = read_json_file::<Value>(&settings_path)?;
+                let mut live_after =
+                    Self::claude_snapshot_from_live(read_json_file::<Value>(&settings_path)?);
                 let _ = Self::normalize_claude_models_in_value(&mut live_after);
                 {
                     let mut guard = state.config.write().map_err(AppError::from)?;
                     if let Some(manager) = guard.get_manager_mut(app_type) {
                         if let Some(target) = manager.providers.get_mut(provider_id) {
+                            placeholder::restore_value(&target.settings_config, &mut live_after);
                             target.settings_config = live_after;
+                            Self::record_live_hash(target, &live_hash);
                         }
                     }
                 }
@@ -599,20 +978,25 @@ impl ProviderService {
                     let mut guard = state.config.write().map_err(AppError::from)?;
                     if let Some(manager) = guard.get_manager_mut(app_type) {
                         if let Some(target) = manager.providers.get_mut(provider_id) {
+                            let mut live_after = json!({ "auth": auth, "config": cfg_text });
+                            placeholder::restore_value(&target.settings_config, &mut live_after);
                             let obj = target.settings_config.as_object_mut().ok_or_else(|| {
                                 AppError::Config(format!(
                                     "供应商 {provider_id} 的 Codex 配置必须是 JSON 对象"
                                 ))
                             })?;
-                            obj.insert("auth".to_string(), auth.clone());
-                            obj.insert("config".to_string(), Value::String(cfg_text.clone()));
+                            obj.insert("auth".to_string(), live_after["auth"].take());
+                            obj.insert("config".to_string(), live_after["config"].take());
+                            Self::record_live_hash(target, &live_hash);
                         }
                     }
                 }
                 state.save()?;
             }
             AppType::Gemini => {
-                use crate::gemini_config::{env_to_json, get_gemini_env_path, read_gemini_env};
+                use crate::gemini_config::{
+                    env_to_json, get_gemini_env_path, read_gemini_managed_env,
+                };
 
                 let env_path = get_gemini_env_path();
                 if !env_path.exists() {
@@ -622,14 +1006,16 @@ impl ProviderService {
                         "Gemini .env file missing; cannot refresh snapshot",
                     ));
                 }
-                let env_map = read_gemini_env()?;
-                let live_after = env_to_json(&env_map);
 
                 {
                     let mut guard = state.config.write().map_err(AppError::from)?;
+                    let managed = Self::gemini_managed_env_keys(&guard);
+                    let mut live_after = env_to_json(&read_gemini_managed_env(&managed)?);
                     if let Some(manager) = guard.get_manager_mut(app_type) {
                         if let Some(target) = manager.providers.get_mut(provider_id) {
+                            placeholder::restore_value(&target.settings_config, &mut live_after);
                             target.settings_config = live_after;
+                            Self::record_live_hash(target, &live_hash);
                         }
                     }
                 }
@@ -639,7 +1025,77 @@ impl ProviderService {
         Ok(())
     }
 
-    fn capture_live_snapshot(app_type: &AppType) -> Result<LiveSnapshot, AppError> {
+    /// 该应用 live 配置文件的内容哈希（缺失的文件也参与计算）
+    pub(crate) fn live_files_hash(app_type: &AppType) -> String {
+        let paths = match app_type {
+            AppType::Claude => vec![get_claude_settings_path(), get_claude_credentials_path()],
+            AppType::Codex => vec![get_codex_auth_path(), get_codex_config_path()],
+            AppType::Gemini => vec![
+                crate::gemini_config::get_gemini_env_path(),
+                crate::gemini_config::get_gemini_oauth_creds_path(),
+            ],
+        };
+        let mut content = Vec::new();
+        for path in paths {
+            match std::fs::read(&path) {
+                Ok(file) => {
+                    content.extend((file.len() as u64).to_le_bytes());
+                    content.extend(file);
+                }
+                Err(_) => content.extend(u64::MAX.to_le_bytes()),
+            }
+        }
+        sha256_hex(&content)
+    }
+
+    fn record_live_hash(provider: &mut Provider, live_hash: &str) {
+        provider
+            .meta
+            .get_or_insert_with(ProviderMeta::default)
+            .live_hash = Some(live_hash.to_string());
+    }
+
+    /// 目标已是当前供应商且 live 文件与上次写入一致时，切换无需重写
+    fn is_noop_switch(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Result<bool, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let Some(manager) = config.get_manager(app_type) else {
+            return Ok(false);
+        };
+        if manager.current != provider_id {
+            return Ok(false);
+        }
+        let recorded = manager
+            .providers
+            .get(provider_id)
+            .and_then(|provider| provider.meta.as_ref())
+            .and_then(|meta| meta.live_hash.as_deref());
+        Ok(recorded.is_some_and(|hash| hash == Self::live_files_hash(app_type)))
+    }
+
+    /// 当前供应商的 live 文件在上次写入后被外部修改时返回其 ID（从未记录写入哈希时不判定）
+    pub(crate) fn live_drift(
+        state: &AppState,
+        app_type: &AppType,
+    ) -> Result<Option<String>, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let Some(manager) = config.get_manager(app_type) else {
+            return Ok(None);
+        };
+        let recorded = manager
+            .providers
+            .get(&manager.current)
+            .and_then(|provider| provider.meta.as_ref())
+            .and_then(|meta| meta.live_hash.as_deref());
+        Ok(recorded
+            .filter(|hash| *hash != Self::live_files_hash(app_type))
+            .map(|_| manager.current.clone()))
+    }
+
+    pub(crate) fn capture_live_snapshot(app_type: &AppType) -> Result<LiveSnapshot, AppError> {
         match app_type {
             AppType::Claude => {
                 let path = get_claude_settings_path();
@@ -648,7 +1104,11 @@ impl ProviderService {
                 } else {
                     None
                 };
-                Ok(LiveSnapshot::Claude { settings })
+                let credentials = read_claude_credentials()?;
+                Ok(LiveSnapshot::Claude {
+                    settings,
+                    credentials,
+                })
             }
             AppType::Codex => {
                 let auth_path = get_codex_auth_path();
@@ -670,14 +1130,17 @@ impl ProviderService {
             }
             AppType::Gemini => {
                 // 新增
-                use crate::gemini_config::{get_gemini_env_path, read_gemini_env};
+                use crate::gemini_config::{
+                    get_gemini_env_path, read_gemini_env, read_gemini_oauth_creds,
+                };
                 let path = get_gemini_env_path();
                 let env = if path.exists() {
                     Some(read_gemini_env()?)
                 } else {
                     None
                 };
-                Ok(LiveSnapshot::Gemini { env })
+                let oauth_creds = read_gemini_oauth_creds()?;
+                Ok(LiveSnapshot::Gemini { env, oauth_creds })
             }
         }
     }
@@ -694,68 +1157,329 @@ impl ProviderService {
         Ok(manager.get_all_providers().clone())
     }
 
-    /// 获取当前供应商 ID
-    pub fn current(state: &AppState, app_type: AppType) -> Result<String, AppError> {
+    /// 按设置中的排序方式列出供应商（保持顺序）
+    pub fn list_sorted(state: &AppState, app_type: AppType) -> Result<OrderedProviders, AppError> {
         let config = state.config.read().map_err(AppError::from)?;
         let manager = config
             .get_manager(&app_type)
             .ok_or_else(|| Self::app_not_found(&app_type))?;
-        Ok(manager.current.clone())
+        let strategy = settings::get_settings().tray_sort;
+        let sorted = Self::sort_providers(&app_type, &manager.providers, strategy)
+            .into_iter()
+            .map(|(id, provider)| (id.clone(), provider.clone()))
+            .collect();
+        Ok(OrderedProviders(sorted))
     }
 
-    /// 新增供应商
-    pub fn add(state: &AppState, app_type: AppType, provider: Provider) -> Result<bool, AppError> {
-        let mut provider = provider;
-        // 归一化 Claude 模型键
-        Self::normalize_provider_if_claude(&app_type, &mut provider);
-        Self::validate_provider_settings(&app_type, &provider)?;
-
-        let app_type_clone = app_type.clone();
-        let provider_clone = provider.clone();
-
-        Self::run_transaction(state, move |config| {
-            config.ensure_app(&app_type_clone);
-            let manager = config
-                .get_manager_mut(&app_type_clone)
-                .ok_or_else(|| Self::app_not_found(&app_type_clone))?;
-
-            let is_current = manager.current == provider_clone.id;
-            manager
-                .providers
-                .insert(provider_clone.id.clone(), provider_clone.clone());
+    /// 分页列出供应商（排序同 [`Self::list_sorted`]），默认不返回 `settingsConfig`
+    pub fn list_page(
+        state: &AppState,
+        app_type: AppType,
+        offset: usize,
+        limit: usize,
+        fields: Option<&[String]>,
+    ) -> Result<Page, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| Self::app_not_found(&app_type))?;
+        let strategy = settings::get_settings().tray_sort;
+        let sorted = Self::sort_providers(&app_type, &manager.providers, strategy);
+        pagination::paginate(
+            sorted.into_iter().map(|(_, provider)| provider),
+            offset,
+            limit,
+            fields,
+            &["settingsConfig"],
+        )
+    }
 
-            let action = if is_current {
-                let backup = Self::capture_live_snapshot(&app_type_clone)?;
-                Some(PostCommitAction {
-                    app_type: app_type_clone.clone(),
-                    provider: provider_clone.clone(),
-                    backup,
-                    sync_mcp: false,
-                    refresh_snapshot: false,
-                })
-            } else {
-                None
-            };
+    /// 按指定策略排序供应商（托盘菜单与供应商列表共用）
+    ///
+    /// 置顶的供应商始终排在前面；各策略在无法比较时（如缺少测速数据）回退到手动排序
+    pub fn sort_providers<'a>(
+        app_type: &AppType,
+        providers: &'a HashMap<String, Provider>,
+        strategy: TraySort,
+    ) -> Vec<(&'a String, &'a Provider)> {
+        let mut sorted: Vec<_> = providers.iter().collect();
+        sorted.sort_by(|(_, a), (_, b)| Self::compare_manual_order(a, b));
+
+        match strategy {
+            TraySort::Manual => {}
+            TraySort::Name => {
+                sorted.sort_by_cached_key(|(_, provider)| provider.name.to_lowercase());
+            }
+            TraySort::Latency => {
+                sorted.sort_by_cached_key(|(_, provider)| {
+                    match Self::provider_base_url(app_type, provider)
+                        .and_then(|url| SpeedtestService::latest_latency(&url))
+                    {
+                        Some(latency) => (0, latency),
+                        None => (1, 0),
+                    }
+                });
+            }
+            TraySort::LastUsed => {
+                sorted.sort_by_key(|(_, provider)| {
+                    std::cmp::Reverse(provider.meta.as_ref().and_then(|m| m.last_used_at))
+                });
+            }
+        }
+        // 稳定排序：置顶分组内保持上面的顺序
+        sorted.sort_by_key(|(_, provider)| !provider.pinned);
 
-            Ok((true, action))
-        })
+        sorted
     }
 
-    /// 更新供应商
-    pub fn update(
+    /// 搜索并筛选供应商，按匹配得分降序返回（得分相同保持手动排序）
+    ///
+    /// 关键字（不区分大小写）匹配名称、标签、端点地址与备注；空关键字仅应用筛选条件
+    pub fn search(
         state: &AppState,
         app_type: AppType,
-        provider: Provider,
-    ) -> Result<bool, AppError> {
-        let mut provider = provider;
-        // 归一化 Claude 模型键
-        Self::normalize_provider_if_claude(&app_type, &mut provider);
-        Self::validate_provider_settings(&app_type, &provider)?;
-        let provider_id = provider.id.clone();
-        let app_type_clone = app_type.clone();
-        let provider_clone = provider.clone();
+        query: &str,
+        filters: &ProviderSearchFilters,
+    ) -> Result<Vec<ProviderSearchHit>, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| Self::app_not_found(&app_type))?;
+        let query = query.trim().to_lowercase();
+
+        let mut hits: Vec<ProviderSearchHit> =
+            Self::sort_providers(&app_type, &manager.providers, TraySort::Manual)
+                .into_iter()
+                .filter_map(|(id, provider)| {
+                    let base_url = Self::provider_base_url(&app_type, provider);
+                    let latency = base_url
+                        .as_deref()
+                        .and_then(SpeedtestService::latest_latency);
+                    if !filters.matches(provider, latency) {
+                        return None;
+                    }
+                    let (score, matched_fields) =
+                        Self::search_score(provider, base_url.as_deref(), &query);
+                    if !query.is_empty() && score == 0 {
+                        return None;
+                    }
+                    Some(ProviderSearchHit {
+                        id: id.clone(),
+                        provider: provider.clone(),
+                        score,
+                        matched_fields,
+                        latency,
+                    })
+                })
+                .collect();
+        // 稳定排序，得分相同时保留手动排序
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+        Ok(hits)
+    }
 
-        Self::run_transaction(state, move |config| {
+    /// 计算单个供应商的匹配得分：名称 > 标签 > 端点 > 备注，各字段得分累加
+    fn search_score(
+        provider: &Provider,
+        base_url: Option<&str>,
+        query: &str,
+    ) -> (u32, Vec<&'static str>) {
+        if query.is_empty() {
+            return (0, Vec::new());
+        }
+        let contains = |text: &str| text.to_lowercase().contains(query);
+        let mut score = 0;
+        let mut matched = Vec::new();
+
+        let name = provider.name.to_lowercase();
+        let name_score = if name == query {
+            100
+        } else if name.starts_with(query) {
+            80
+        } else if name.contains(query) {
+            60
+        } else {
+            0
+        };
+        if name_score > 0 {
+            score += name_score;
+            matched.push("name");
+        }
+
+        let meta = provider.meta.as_ref();
+        if meta.is_some_and(|m| m.tags.iter().any(|tag| contains(tag))) {
+            score += 40;
+            matched.push("tags");
+        }
+
+        let endpoint_match = base_url.is_some_and(contains)
+            || provider.website_url.as_deref().is_some_and(contains)
+            || meta.is_some_and(|m| m.custom_endpoints.keys().any(|url| contains(url)));
+        if endpoint_match {
+            score += 30;
+            matched.push("endpoint");
+        }
+
+        if provider.notes.as_deref().is_some_and(contains) {
+            score += 20;
+            matched.push("notes");
+        }
+
+        (score, matched)
+    }
+
+    /// 手动排序：sortIndex → 创建时间 → 名称
+    fn compare_manual_order(a: &Provider, b: &Provider) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        match (a.sort_index, b.sort_index) {
+            (Some(idx_a), Some(idx_b)) => return idx_a.cmp(&idx_b),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            _ => {}
+        }
+
+        match (a.created_at, b.created_at) {
+            (Some(time_a), Some(time_b)) => return time_a.cmp(&time_b),
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            _ => {}
+        }
+
+        a.name.cmp(&b.name)
+    }
+
+    /// 根据测速结果记录供应商的最新限流信息（优先使用与基础地址一致的端点）
+    pub fn record_rate_limit(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+        results: &[EndpointLatency],
+    ) -> Result<Option<RateLimitInfo>, AppError> {
+        let normalize = |url: &str| url.trim().trim_end_matches('/').to_lowercase();
+        let info = {
+            let mut config = state.config.write().map_err(AppError::from)?;
+            let Some(provider) = config
+                .get_manager_mut(app_type)
+                .and_then(|manager| manager.providers.get_mut(provider_id))
+            else {
+                return Ok(None);
+            };
+
+            let base_url = Self::provider_base_url(app_type, provider).map(|url| normalize(&url));
+            let info = results
+                .iter()
+                .filter(|result| result.rate_limit.is_some())
+                .max_by_key(|result| Some(normalize(&result.url)) == base_url)
+                .and_then(|result| result.rate_limit.clone());
+            let Some(info) = info else {
+                return Ok(None);
+            };
+            provider
+                .meta
+                .get_or_insert_with(ProviderMeta::default)
+                .rate_limit = Some(info.clone());
+            info
+        };
+        state.save()?;
+        Ok(Some(info))
+    }
+
+    /// 提取供应商配置中的 API 基础地址
+    pub(crate) fn provider_base_url(app_type: &AppType, provider: &Provider) -> Option<String> {
+        let settings = &provider.settings_config;
+        match app_type {
+            AppType::Claude => settings
+                .get("env")?
+                .get("ANTHROPIC_BASE_URL")?
+                .as_str()
+                .map(str::to_string),
+            AppType::Codex => {
+                let text = settings.get("config")?.as_str()?;
+                let table: toml::Table = toml::from_str(text).ok()?;
+                let from_provider = table
+                    .get("model_provider")
+                    .and_then(|v| v.as_str())
+                    .and_then(|name| table.get("model_providers")?.get(name)?.get("base_url"))
+                    .and_then(|v| v.as_str());
+                from_provider
+                    .or_else(|| table.get("base_url").and_then(|v| v.as_str()))
+                    .map(str::to_string)
+            }
+            AppType::Gemini => settings
+                .get("env")?
+                .get("GOOGLE_GEMINI_BASE_URL")?
+                .as_str()
+                .map(str::to_string),
+        }
+    }
+
+    /// 获取当前供应商 ID
+    pub fn current(state: &AppState, app_type: AppType) -> Result<String, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| Self::app_not_found(&app_type))?;
+        Ok(manager.current.clone())
+    }
+
+    /// 新增供应商
+    pub fn add(state: &AppState, app_type: AppType, provider: Provider) -> Result<bool, AppError> {
+        let mut provider = provider;
+        // 应用配置键迁移（如 Claude 模型键归一化）
+        let migrations = Self::migrate_provider_settings(&app_type, &mut provider);
+        Self::validate_provider_settings(&app_type, &provider)?;
+
+        provider.updated_at = Some(chrono::Utc::now().timestamp_millis());
+        let app_type_clone = app_type.clone();
+        let provider_clone = provider.clone();
+
+        Self::run_transaction(state, move |config| {
+            crate::config_migrations::record(&mut config.applied_config_migrations, &migrations);
+            config.ensure_app(&app_type_clone);
+            let manager = config
+                .get_manager_mut(&app_type_clone)
+                .ok_or_else(|| Self::app_not_found(&app_type_clone))?;
+
+            let is_current = manager.current == provider_clone.id;
+            manager
+                .providers
+                .insert(provider_clone.id.clone(), provider_clone.clone());
+
+            let action = if is_current {
+                let backup = Self::capture_live_snapshot(&app_type_clone)?;
+                Some(PostCommitAction {
+                    app_type: app_type_clone.clone(),
+                    provider: provider_clone.clone(),
+                    backup,
+                    sync_mcp: false,
+                    refresh_snapshot: false,
+                })
+            } else {
+                None
+            };
+
+            Ok((true, action))
+        })
+    }
+
+    /// 更新供应商
+    ///
+    /// 传入的 `updatedAt` 与已保存的不一致时（期间被其他窗口或托盘修改）返回
+    /// [`AppError::ProviderConflict`]；未携带 `updatedAt` 的调用方不做检查
+    pub fn update(
+        state: &AppState,
+        app_type: AppType,
+        provider: Provider,
+    ) -> Result<bool, AppError> {
+        let mut provider = provider;
+        // 应用配置键迁移（如 Claude 模型键归一化）
+        let migrations = Self::migrate_provider_settings(&app_type, &mut provider);
+        Self::validate_provider_settings(&app_type, &provider)?;
+        let provider_id = provider.id.clone();
+        let app_type_clone = app_type.clone();
+        let provider_clone = provider.clone();
+
+        Self::run_transaction(state, move |config| {
             let manager = config
                 .get_manager_mut(&app_type_clone)
                 .ok_or_else(|| Self::app_not_found(&app_type_clone))?;
@@ -770,7 +1494,20 @@ impl ProviderService {
 
             let is_current = manager.current == provider_id;
             let merged = if let Some(existing) = manager.providers.get(&provider_id) {
+                if let (Some(expected), Some(saved)) =
+                    (provider_clone.updated_at, existing.updated_at)
+                {
+                    if expected != saved {
+                        return Err(AppError::ProviderConflict {
+                            current: Box::new(existing.clone()),
+                        });
+                    }
+                }
                 let mut updated = provider_clone.clone();
+                // 保证修订时间严格递增，避免同一毫秒内的两次保存无法区分
+                let now = chrono::Utc::now().timestamp_millis();
+                updated.updated_at =
+                    Some(existing.updated_at.map_or(now, |saved| now.max(saved + 1)));
                 match (existing.meta.as_ref(), updated.meta.take()) {
                     // 前端未提供 meta，表示不修改，沿用旧值
                     (Some(old_meta), None) => {
@@ -790,6 +1527,7 @@ impl ProviderService {
             };
 
             manager.providers.insert(provider_id.clone(), merged);
+            crate::config_migrations::record(&mut config.applied_config_migrations, &migrations);
 
             let action = if is_current {
                 let backup = Self::capture_live_snapshot(&app_type_clone)?;
@@ -886,6 +1624,109 @@ impl ProviderService {
         Ok(())
     }
 
+    /// 为所有已安装的 CLI 导入 live 配置作为默认供应商（已有供应商的应用跳过），返回各应用的结果
+    pub fn import_all_default_configs(
+        state: &AppState,
+        installed: &[InstalledCli],
+    ) -> Vec<DefaultImportResult> {
+        [AppType::Claude, AppType::Codex, AppType::Gemini]
+            .into_iter()
+            .map(|app_type| {
+                let is_installed = installed
+                    .iter()
+                    .any(|cli| cli.installed && cli.name == app_type.as_str());
+                let has_providers = state.config.read().ok().is_some_and(|config| {
+                    config
+                        .get_manager(&app_type)
+                        .is_some_and(|manager| !manager.providers.is_empty())
+                });
+                let (status, error) = if !is_installed {
+                    (DefaultImportStatus::NotInstalled, None)
+                } else if has_providers {
+                    (DefaultImportStatus::AlreadyConfigured, None)
+                } else {
+                    match Self::import_default_config(state, app_type.clone()) {
+                        Ok(()) => (DefaultImportStatus::Imported, None),
+                        Err(e) => (DefaultImportStatus::Failed, Some(e.to_string())),
+                    }
+                };
+                DefaultImportResult {
+                    app_type,
+                    status,
+                    error,
+                }
+            })
+            .collect()
+    }
+
+    /// 从 CSV（电子表格导出）批量创建供应商：逐行校验，有效行在同一事务中写入，返回新建 ID 与各行错误
+    pub fn import_from_csv(
+        state: &AppState,
+        app_type: AppType,
+        path: &std::path::Path,
+        mapping: &CsvColumnMapping,
+    ) -> Result<CsvImportResult, AppError> {
+        let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let (rows, errors) = provider_csv::parse_rows(&content, mapping)?;
+        let mut result = CsvImportResult {
+            errors,
+            ..Default::default()
+        };
+
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let mut providers = Vec::new();
+        let mut migrations = Vec::new();
+        for row in &rows {
+            let built = provider_csv::build_provider(&app_type, row).and_then(|mut provider| {
+                let sanitized: String = row
+                    .name
+                    .chars()
+                    .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                    .collect::<String>()
+                    .to_lowercase();
+                provider.id = format!("{sanitized}-{timestamp}-{}", row.row);
+                provider.created_at = Some(timestamp);
+                provider.updated_at = Some(timestamp);
+                migrations.extend(Self::migrate_provider_settings(&app_type, &mut provider));
+                Self::validate_provider_settings(&app_type, &provider)?;
+                Ok(provider)
+            });
+            match built {
+                Ok(provider) => providers.push(provider),
+                Err(e) => result.errors.push(CsvRowError {
+                    row: row.row,
+                    name: Some(row.name.clone()),
+                    message: e.to_string(),
+                }),
+            }
+        }
+        result.errors.sort_by_key(|error| error.row);
+        if providers.is_empty() {
+            return Ok(result);
+        }
+
+        result.imported = providers.iter().map(|p| p.id.clone()).collect();
+        let app_type_clone = app_type.clone();
+        Self::run_transaction(state, move |config| {
+            crate::config_migrations::record(&mut config.applied_config_migrations, &migrations);
+            config.ensure_app(&app_type_clone);
+            let manager = config
+                .get_manager_mut(&app_type_clone)
+                .ok_or_else(|| Self::app_not_found(&app_type_clone))?;
+            for provider in providers {
+                manager.providers.insert(provider.id.clone(), provider);
+            }
+            Ok(((), None))
+        })?;
+        log::info!(
+            "已从 CSV 导入 {} 个 {} 供应商（{} 行失败）",
+            result.imported.len(),
+            app_type.as_str(),
+            result.errors.len()
+        );
+        Ok(result)
+    }
+
     /// 读取当前 live 配置
     pub fn read_live_settings(app_type: AppType) -> Result<Value, AppError> {
         match app_type {
@@ -1055,16 +1896,31 @@ impl ProviderService {
         state: &AppState,
         app_type: AppType,
         updates: Vec<ProviderSortUpdate>,
+    ) -> Result<bool, AppError> {
+        Self::update_sort_order_multi(state, vec![(app_type, updates)])
+    }
+
+    /// 一次更新多个应用的供应商排序（同一把写锁内完成并只保存一次，任一应用不存在时整体不生效）
+    pub fn update_sort_order_multi(
+        state: &AppState,
+        updates: Vec<(AppType, Vec<ProviderSortUpdate>)>,
     ) -> Result<bool, AppError> {
         {
             let mut cfg = state.config.write().map_err(AppError::from)?;
-            let manager = cfg
-                .get_manager_mut(&app_type)
-                .ok_or_else(|| Self::app_not_found(&app_type))?;
+            for (app_type, _) in &updates {
+                if cfg.get_manager(app_type).is_none() {
+                    return Err(Self::app_not_found(app_type));
+                }
+            }
 
-            for update in updates {
-                if let Some(provider) = manager.providers.get_mut(&update.id) {
-                    provider.sort_index = Some(update.sort_index);
+            for (app_type, app_updates) in updates {
+                let Some(manager) = cfg.get_manager_mut(&app_type) else {
+                    continue;
+                };
+                for update in app_updates {
+                    if let Some(provider) = manager.providers.get_mut(&update.id) {
+                        provider.sort_index = Some(update.sort_index);
+                    }
                 }
             }
         }
@@ -1073,12 +1929,43 @@ impl ProviderService {
         Ok(true)
     }
 
+    /// 设置供应商置顶状态，返回是否发生变化
+    pub fn set_pinned(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        pinned: bool,
+    ) -> Result<bool, AppError> {
+        {
+            let mut cfg = state.config.write().map_err(AppError::from)?;
+            let provider = cfg
+                .get_manager_mut(&app_type)
+                .ok_or_else(|| Self::app_not_found(&app_type))?
+                .providers
+                .get_mut(provider_id)
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?;
+            if provider.pinned == pinned {
+                return Ok(false);
+            }
+            provider.pinned = pinned;
+        }
+
+        state.save()?;
+        Ok(true)
+    }
+
     /// 执行用量脚本并格式化结果（私有辅助方法）
     async fn execute_and_format_usage_result(
         script_code: &str,
         api_key: &str,
         base_url: &str,
-        timeout: u64,
+        policy: &usage_script::ExecutionPolicy,
         access_token: Option<&str>,
         user_id: Option<&str>,
     ) -> Result<UsageResult, AppError> {
@@ -1086,14 +1973,14 @@ impl ProviderService {
             script_code,
             api_key,
             base_url,
-            timeout,
+            policy,
             access_token,
             user_id,
         )
         .await
         {
             Ok(data) => {
-                let usage_list: Vec<UsageData> = if data.is_array() {
+                let mut usage_list: Vec<UsageData> = if data.is_array() {
                     serde_json::from_value(data).map_err(|e| {
                         AppError::localized(
                             "usage_script.data_format_error",
@@ -1111,6 +1998,10 @@ impl ProviderService {
                     })?;
                     vec![single]
                 };
+                let settings = settings::get_settings();
+                for usage in &mut usage_list {
+                    crate::usage_normalize::normalize(usage, &settings);
+                }
 
                 Ok(UsageResult {
                     success: true,
@@ -1143,13 +2034,90 @@ impl ProviderService {
         }
     }
 
+    /// 探测 Codex 供应商端点支持的 wire_api，并据此改写 config.toml（当前供应商同时更新 live 配置）
+    pub async fn fix_codex_wire_api(
+        state: &AppState,
+        provider_id: &str,
+    ) -> Result<WireApiFix, AppError> {
+        let provider = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .get_manager(&AppType::Codex)
+                .and_then(|manager| manager.providers.get(provider_id))
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?
+        };
+
+        let config_text = provider
+            .settings_config
+            .get("config")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let base_url = Self::provider_base_url(&AppType::Codex, &provider).ok_or_else(|| {
+            AppError::localized(
+                "codex.wire_api.no_base_url",
+                "供应商未配置 base_url，无法探测 wire_api",
+                "Provider has no base_url; cannot detect wire_api",
+            )
+        })?;
+        let api_key = provider
+            .settings_config
+            .get("auth")
+            .and_then(|auth| auth.get("OPENAI_API_KEY"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        let detected = CapabilityService::detect_codex_wire_api(&base_url, api_key)
+            .await?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "codex.wire_api.undetected",
+                    format!("端点既不支持 /responses 也不支持 /chat/completions: {base_url}"),
+                    format!(
+                        "Endpoint supports neither /responses nor /chat/completions: {base_url}"
+                    ),
+                )
+            })?;
+
+        let previous = crate::codex_config::get_wire_api(&config_text);
+        if previous.as_deref() == Some(detected) {
+            return Ok(WireApiFix {
+                wire_api: detected.to_string(),
+                previous,
+                changed: false,
+            });
+        }
+
+        let updated_text = crate::codex_config::set_wire_api(&config_text, detected)?;
+        let mut provider = provider;
+        if let Some(settings) = provider.settings_config.as_object_mut() {
+            settings.insert("config".to_string(), Value::String(updated_text));
+        }
+        Self::update(state, AppType::Codex, provider)?;
+
+        Ok(WireApiFix {
+            wire_api: detected.to_string(),
+            previous,
+            changed: true,
+        })
+    }
+
     /// 查询供应商用量（使用已保存的脚本配置）
+    ///
+    /// 按脚本配置重试；连续失败达到阈值后熔断，冷却期内直接拒绝执行
     pub async fn query_usage(
         state: &AppState,
         app_type: AppType,
         provider_id: &str,
     ) -> Result<UsageResult, AppError> {
-        let (script_code, timeout, api_key, base_url, access_token, user_id) = {
+        let (script_code, policy, breaker, api_key, base_url, access_token, user_id) = {
             let config = state.config.read().map_err(AppError::from)?;
             let manager = config
                 .get_manager(&app_type)
@@ -1184,7 +2152,8 @@ impl ProviderService {
             // 直接从 UsageScript 中获取凭证，不再从供应商配置提取
             (
                 usage_script.code.clone(),
-                usage_script.timeout.unwrap_or(10),
+                usage_script::ExecutionPolicy::from_script(usage_script),
+                usage_script::BreakerPolicy::from_script(usage_script),
                 usage_script.api_key.clone().unwrap_or_default(),
                 usage_script.base_url.clone().unwrap_or_default(),
                 usage_script.access_token.clone(),
@@ -1192,15 +2161,39 @@ impl ProviderService {
             )
         };
 
-        Self::execute_and_format_usage_result(
+        if breaker.failure_threshold > 0 {
+            usage_script::ensure_breaker_closed(
+                provider_id,
+                chrono::Utc::now().timestamp_millis(),
+            )?;
+        }
+
+        let result = Self::execute_and_format_usage_result(
             &script_code,
             &api_key,
             &base_url,
-            timeout,
+            &policy,
             access_token.as_deref(),
             user_id.as_deref(),
         )
-        .await
+        .await;
+
+        let outcome = match &result {
+            Ok(usage) if usage.success => Ok(()),
+            Ok(usage) => Err(usage.error.clone().unwrap_or_default()),
+            Err(err) => Err(err.to_string()),
+        };
+        let now = chrono::Utc::now().timestamp_millis();
+        usage_script::record_outcome(provider_id, outcome, breaker, now);
+        if let Ok(UsageResult {
+            success: true,
+            data: Some(data),
+            ..
+        }) = &result
+        {
+            usage_script::record_quota(&app_type, provider_id, data, now);
+        }
+        result
     }
 
     /// 测试用量脚本（使用临时脚本内容，不保存）
@@ -1221,7 +2214,7 @@ impl ProviderService {
             script_code,
             api_key.unwrap_or(""),
             base_url.unwrap_or(""),
-            timeout,
+            &usage_script::ExecutionPolicy::with_timeout(timeout),
             access_token,
             user_id,
         )
@@ -1229,30 +2222,174 @@ impl ProviderService {
     }
 
     /// 切换指定应用的供应商
-    pub fn switch(state: &AppState, app_type: AppType, provider_id: &str) -> Result<(), AppError> {
+    ///
+    /// 目标已是当前供应商且 live 文件未被改动时不做任何写入，返回 `no_change`
+    pub fn switch(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+    ) -> Result<SwitchDetails, AppError> {
+        Self::switch_with_rewrite(state, app_type, provider_id, false)
+    }
+
+    /// 同 [`Self::switch`]；`rewrite` 为 true 时即使无变化也重写 live 配置
+    pub fn switch_with_rewrite(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        rewrite: bool,
+    ) -> Result<SwitchDetails, AppError> {
+        if !rewrite && Self::is_noop_switch(state, &app_type, provider_id)? {
+            let provider_name = {
+                let config = state.config.read().map_err(AppError::from)?;
+                config
+                    .get_manager(&app_type)
+                    .and_then(|manager| manager.providers.get(provider_id))
+                    .map(|provider| provider.name.clone())
+                    .unwrap_or_default()
+            };
+            return Ok(SwitchDetails {
+                app_type,
+                provider_id: provider_id.to_string(),
+                previous_provider_id: Some(provider_id.to_string()),
+                provider_name,
+                live_write_ms: 0,
+                mcp_synced: false,
+                no_change: true,
+                verification: None,
+            });
+        }
+
         let app_type_clone = app_type.clone();
         let provider_id_owned = provider_id.to_string();
 
-        Self::run_transaction(state, move |config| {
-            let backup = Self::capture_live_snapshot(&app_type_clone)?;
-            let provider = match app_type_clone {
-                AppType::Codex => Self::prepare_switch_codex(config, &provider_id_owned)?,
-                AppType::Claude => Self::prepare_switch_claude(config, &provider_id_owned)?,
-                AppType::Gemini => Self::prepare_switch_gemini(config, &provider_id_owned)?,
-            };
+        let ((previous, provider_name), report) =
+            Self::run_transaction_with_report(state, move |config| {
+                let backup = Self::capture_live_snapshot(&app_type_clone)?;
+                let previous = config
+                    .get_manager(&app_type_clone)
+                    .map(|manager| manager.current.clone())
+                    .filter(|current| !current.is_empty());
+                let provider = match app_type_clone {
+                    AppType::Codex => Self::prepare_switch_codex(config, &provider_id_owned)?,
+                    AppType::Claude => Self::prepare_switch_claude(config, &provider_id_owned)?,
+                    AppType::Gemini => Self::prepare_switch_gemini(config, &provider_id_owned)?,
+                };
 
-            let action = PostCommitAction {
-                app_type: app_type_clone.clone(),
-                provider,
-                backup,
-                sync_mcp: true, // v3.7.0: 所有应用切换时都同步 MCP，防止配置丢失
-                refresh_snapshot: true,
-            };
+                Self::mark_last_used(config, &app_type_clone, &provider_id_owned);
+                let provider_name = provider.name.clone();
+
+                let action = PostCommitAction {
+                    app_type: app_type_clone.clone(),
+                    provider,
+                    backup,
+                    sync_mcp: true, // 按 mcp_switch_sync 设置同步 MCP，防止配置丢失
+                    refresh_snapshot: true,
+                };
+
+                Ok(((previous, provider_name), Some(action)))
+            })?;
+
+        Ok(SwitchDetails {
+            app_type,
+            provider_id: provider_id.to_string(),
+            previous_provider_id: previous,
+            provider_name,
+            live_write_ms: report.as_ref().map_or(0, |r| r.live_write_ms),
+            mcp_synced: report.as_ref().is_some_and(|r| r.mcp_synced),
+            no_change: false,
+            verification: report.and_then(|r| r.verification),
+        })
+    }
 
-            Ok(((), Some(action)))
+    /// 切换前的健康预检：最近一次测速失败或延迟超过阈值时返回警告
+    ///
+    /// `force` 为 true 时跳过预检，并在目标已是当前供应商时仍重写 live 配置
+    pub fn switch_checked(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        force: bool,
+    ) -> Result<SwitchOutcome, AppError> {
+        // 无变化时直接返回，无需预检
+        if !force && Self::is_noop_switch(state, &app_type, provider_id)? {
+            let details = Self::switch(state, app_type, provider_id)?;
+            return Ok(SwitchOutcome {
+                switched: false,
+                warning: None,
+                details: Some(details),
+            });
+        }
+        if !force {
+            if let Some(warning) = Self::switch_precheck(state, &app_type, provider_id)? {
+                return Ok(SwitchOutcome {
+                    switched: false,
+                    warning: Some(warning),
+                    details: None,
+                });
+            }
+        }
+        let details = Self::switch_with_rewrite(state, app_type, provider_id, force)?;
+        Ok(SwitchOutcome {
+            switched: !details.no_change,
+            warning: None,
+            details: Some(details),
         })
     }
 
+    /// 读取测速记录判断目标供应商是否需要确认（无测速记录时不提示）
+    pub fn switch_precheck(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Result<Option<SwitchWarning>, AppError> {
+        let base_url = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .get_manager(app_type)
+                .and_then(|manager| manager.providers.get(provider_id))
+                .and_then(|provider| Self::provider_base_url(app_type, provider))
+        };
+        let Some(base_url) = base_url else {
+            return Ok(None);
+        };
+        let Some(probe) = SpeedtestService::latest_probe(&base_url) else {
+            return Ok(None);
+        };
+
+        let threshold_ms = settings::get_settings().switch_latency_warning_ms;
+        let kind = match probe.latency {
+            None => SwitchWarningKind::ProbeFailed,
+            Some(latency) if threshold_ms > 0 && latency > u128::from(threshold_ms) => {
+                SwitchWarningKind::HighLatency
+            }
+            Some(_) => return Ok(None),
+        };
+
+        Ok(Some(SwitchWarning {
+            kind,
+            provider_id: provider_id.to_string(),
+            base_url,
+            latency: probe.latency,
+            threshold_ms,
+            error: probe.error,
+            tested_at: probe.tested_at,
+        }))
+    }
+
+    /// 记录供应商最近使用时间（用于按最近使用排序）
+    fn mark_last_used(config: &mut MultiAppConfig, app_type: &AppType, provider_id: &str) {
+        if let Some(provider) = config
+            .get_manager_mut(app_type)
+            .and_then(|manager| manager.providers.get_mut(provider_id))
+        {
+            provider
+                .meta
+                .get_or_insert_with(ProviderMeta::default)
+                .last_used_at = Some(Self::now_millis());
+        }
+    }
+
     fn prepare_switch_codex(
         config: &mut MultiAppConfig,
         provider_id: &str,
@@ -1306,13 +2443,15 @@ impl ProviderService {
             String::new()
         };
 
-        let live = json!({
-            "auth": auth,
-            "config": config_text,
-        });
-
         if let Some(manager) = config.get_manager_mut(&AppType::Codex) {
             if let Some(current) = manager.providers.get_mut(&current_id) {
+                Self::stash_codex_oauth(current, &auth);
+                Self::refresh_oauth_snapshot(current, auth.clone());
+                let mut live = json!({
+                    "auth": auth,
+                    "config": config_text,
+                });
+                placeholder::restore_value(&current.settings_config, &mut live);
                 current.settings_config = live;
             }
         }
@@ -1334,14 +2473,768 @@ impl ProviderService {
                 provider.id
             )));
         }
-        let cfg_text = settings.get("config").and_then(Value::as_str);
+        let cfg_text = Self::codex_live_config_text(provider)?;
 
-        write_codex_live_atomic(auth, cfg_text)?;
+        write_codex_live_atomic(&Self::codex_live_auth(provider), cfg_text.as_deref())?;
         Ok(())
     }
 
-    fn prepare_switch_claude(
-        config: &mut MultiAppConfig,
+    /// auth.json 中属于 ChatGPT 登录的 OAuth 字段
+    const CODEX_OAUTH_FIELDS: [&'static str; 2] = ["tokens", "last_refresh"];
+
+    /// Codex 供应商的登录方式：优先使用显式标记，否则有 OPENAI_API_KEY 即视为 API Key 登录
+    pub(crate) fn codex_login_type(provider: &Provider) -> CodexLoginType {
+        match provider.auth_kind {
+            Some(AuthKind::Oauth) => return CodexLoginType::Chatgpt,
+            Some(AuthKind::ApiKey) => return CodexLoginType::ApiKey,
+            None => {}
+        }
+        if let Some(login_type) = provider.meta.as_ref().and_then(|m| m.codex_login_type) {
+            return login_type;
+        }
+        let has_api_key = provider
+            .settings_config
+            .pointer("/auth/OPENAI_API_KEY")
+            .and_then(Value::as_str)
+            .is_some_and(|key| !key.trim().is_empty());
+        if has_api_key {
+            CodexLoginType::ApiKey
+        } else {
+            CodexLoginType::Chatgpt
+        }
+    }
+
+    /// 将 live auth.json 中的 OAuth 字段暂存到切出供应商的 meta，防止被 API Key 供应商覆盖后丢失
+    fn stash_codex_oauth(provider: &mut Provider, live_auth: &Value) {
+        let oauth: serde_json::Map<String, Value> = Self::CODEX_OAUTH_FIELDS
+            .iter()
+            .filter_map(|field| {
+                live_auth
+                    .get(*field)
+                    .filter(|value| !value.is_null())
+                    .map(|value| (field.to_string(), value.clone()))
+            })
+            .collect();
+        if oauth.is_empty() {
+            return;
+        }
+        provider
+            .meta
+            .get_or_insert_with(ProviderMeta::default)
+            .codex_oauth = Some(oauth);
+    }
+
+    /// 生成写入 live 的 Codex auth.json：ChatGPT 登录的供应商缺少 tokens 时写回暂存的 OAuth 字段
+    pub(crate) fn codex_live_auth(provider: &Provider) -> Value {
+        // OAuth 供应商直接恢复保存的 auth.json 快照
+        if let Some(snapshot) = Self::oauth_snapshot(provider) {
+            return snapshot.clone();
+        }
+        let mut auth = provider
+            .settings_config
+            .get("auth")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        if Self::codex_login_type(provider) != CodexLoginType::Chatgpt {
+            return auth;
+        }
+        let Some(stash) = provider.meta.as_ref().and_then(|m| m.codex_oauth.as_ref()) else {
+            return auth;
+        };
+        if let Some(obj) = auth.as_object_mut() {
+            for (field, value) in stash {
+                if obj.get(field).is_none_or(Value::is_null) {
+                    obj.insert(field.clone(), value.clone());
+                }
+            }
+        }
+        auth
+    }
+
+    /// 生成写入 live 的 Codex config.toml 文本（注入供应商自定义请求头）
+    pub(crate) fn codex_live_config_text(provider: &Provider) -> Result<Option<String>, AppError> {
+        let Some(mut text) = provider
+            .settings_config
+            .get("config")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+        else {
+            return Ok(None);
+        };
+        if let Some(meta) = provider.meta.as_ref() {
+            if !meta.headers.is_empty() {
+                text = crate::codex_config::apply_http_headers(&text, &meta.headers)?;
+            }
+            if !meta.extra_env.is_empty() {
+                text = crate::codex_config::apply_shell_env(&text, &meta.extra_env, &[])?;
+            }
+            if let Some(performance) = meta.performance.as_ref() {
+                text = crate::codex_config::apply_provider_numbers(
+                    &text,
+                    &performance.codex_entries(),
+                )?;
+            }
+        }
+        Ok(Some(text))
+    }
+
+    /// [`Self::render_live_files`] 可能写入的文件（相对主目录）
+    pub(crate) fn live_file_paths(app_type: &AppType) -> &'static [&'static str] {
+        match app_type {
+            AppType::Claude => &[".claude/settings.json", ".claude/.credentials.json"],
+            AppType::Codex => &[".codex/auth.json", ".codex/config.toml"],
+            AppType::Gemini => &[],
+        }
+    }
+
+    /// 生成 Claude / Codex 的 live 文件（相对主目录的路径与内容），用于写入 WSL 发行版、远程主机等非本机位置
+    ///
+    /// `existing_claude_settings` 为目标位置现有的 settings.json，`managed-keys` 模式下以其为底合并；
+    /// `provider` 为已展开占位符的副本
+    pub(crate) fn render_live_files(
+        app_type: &AppType,
+        provider: &Provider,
+        existing_claude_settings: Option<&str>,
+    ) -> Result<Vec<(&'static str, String)>, AppError> {
+        let to_json = |value: &Value| {
+            serde_json::to_string_pretty(value).map_err(|e| AppError::JsonSerialize { source: e })
+        };
+        match app_type {
+            AppType::Claude => {
+                let mut content = Self::claude_live_settings(provider);
+                if let (Some(managed), Some(existing)) = (
+                    Self::claude_managed_keys_for(provider),
+                    existing_claude_settings,
+                ) {
+                    let existing = serde_json::from_str(existing).map_err(|e| {
+                        AppError::json(std::path::Path::new(".claude/settings.json"), e)
+                    })?;
+                    content = Self::merge_managed_keys(existing, &content, &managed);
+                }
+                let mut files = vec![(".claude/settings.json", to_json(&content)?)];
+                if let Some(snapshot) = Self::oauth_snapshot(provider) {
+                    files.push((".claude/.credentials.json", to_json(snapshot)?));
+                }
+                Ok(files)
+            }
+            AppType::Codex => Ok(vec![
+                (
+                    ".codex/auth.json",
+                    to_json(&Self::codex_live_auth(provider))?,
+                ),
+                (
+                    ".codex/config.toml",
+                    Self::codex_live_config_text(provider)?.unwrap_or_default(),
+                ),
+            ]),
+            AppType::Gemini => Err(AppError::localized(
+                "live.render_unsupported",
+                "Gemini 暂不支持写入到本机以外的位置",
+                "Writing Gemini config outside this machine is not supported yet",
+            )),
+        }
+    }
+
+    /// 生成写入 live 的 Claude settings.json（注入供应商自定义请求头）
+    pub(crate) fn claude_live_settings(provider: &Provider) -> Value {
+        let mut content = provider.settings_config.clone();
+        let _ = Self::normalize_claude_models_in_value(&mut content);
+        crate::statusline::patch_settings(&mut content);
+        crate::claude_hooks::patch_settings(&mut content);
+        if let Some(overrides) = Self::claude_settings_overrides(provider) {
+            overrides.apply(&mut content);
+        }
+        if let Some(performance) = provider.meta.as_ref().and_then(|m| m.performance.as_ref()) {
+            performance.apply_claude(&mut content);
+        }
+
+        let Some(headers) = provider.meta.as_ref().map(|m| &m.headers) else {
+            return content;
+        };
+        if headers.is_empty() {
+            return content;
+        }
+
+        if let Some(obj) = content.as_object_mut() {
+            let env = obj
+                .entry("env")
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if let Some(env_obj) = env.as_object_mut() {
+                env_obj.insert(
+                    Self::CLAUDE_CUSTOM_HEADERS_ENV.to_string(),
+                    Value::String(Self::format_claude_custom_headers(headers)),
+                );
+            }
+        }
+        content
+    }
+
+    /// 按 `Name: value` 每行一个的格式序列化请求头（按名称排序，保证输出稳定）
+    fn format_claude_custom_headers(headers: &HashMap<String, String>) -> String {
+        let mut entries: Vec<_> = headers.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries
+            .into_iter()
+            .map(|(name, value)| format!("{}: {}", name.trim(), value.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 校验自定义请求头：名称需符合 RFC 7230 token 规则，值不能包含换行
+    fn validate_custom_headers(headers: &HashMap<String, String>) -> Result<(), AppError> {
+        for (name, value) in headers {
+            let trimmed = name.trim();
+            let valid_name = !trimmed.is_empty()
+                && trimmed
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c));
+            if !valid_name {
+                return Err(AppError::localized(
+                    "provider.headers.invalid_name",
+                    format!("无效的请求头名称: {name}"),
+                    format!("Invalid header name: {name}"),
+                ));
+            }
+            if value.contains('\r') || value.contains('\n') {
+                return Err(AppError::localized(
+                    "provider.headers.invalid_value",
+                    format!("请求头 {trimmed} 的值不能包含换行符"),
+                    format!("Value of header {trimmed} must not contain line breaks"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 校验 Codex 额外环境变量：变量名需合法，且不能覆盖由 cc-switch 或系统管理的变量
+    fn validate_extra_env(env: &HashMap<String, String>) -> Result<(), AppError> {
+        for (name, value) in env {
+            if !crate::services::shell_wrapper::is_valid_env_name(name) {
+                return Err(AppError::localized(
+                    "provider.extra_env.invalid_name",
+                    format!("无效的环境变量名: {name}"),
+                    format!("Invalid environment variable name: {name}"),
+                ));
+            }
+            if Self::RESERVED_EXTRA_ENV_KEYS
+                .iter()
+                .any(|reserved| reserved.eq_ignore_ascii_case(name))
+            {
+                return Err(AppError::localized(
+                    "provider.extra_env.reserved",
+                    format!("环境变量 {name} 由 CC Switch 或系统管理，不能自定义"),
+                    format!("Environment variable {name} is managed by CC Switch or the system"),
+                ));
+            }
+            if value.contains(['\r', '\n', '\0']) {
+                return Err(AppError::localized(
+                    "provider.extra_env.invalid_value",
+                    format!("环境变量 {name} 的值不能包含换行符或空字符"),
+                    format!(
+                        "Value of environment variable {name} must not contain line breaks or NUL"
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 设置供应商的默认模型（`None` 或空字符串表示移除），若为当前供应商则立即写入 live 配置
+    ///
+    /// Claude 写入 `ANTHROPIC_MODEL`，Gemini 写入 `GEMINI_MODEL`（切换时同步到 settings.json），
+    /// Codex 通过 toml_edit 改写 config.toml 中的顶层 `model`，保留其余内容与注释
+    pub fn set_provider_model(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        model: Option<String>,
+    ) -> Result<(), AppError> {
+        let model = model
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty());
+        if model
+            .as_deref()
+            .is_some_and(|model| model.contains(['\r', '\n', '\0']))
+        {
+            return Err(AppError::localized(
+                "provider.model.invalid",
+                "模型名称不能包含换行符或空字符",
+                "Model name must not contain line breaks or NUL",
+            ));
+        }
+
+        let mut provider = Self::provider_snapshot(state, &app_type, provider_id)?;
+
+        match app_type {
+            AppType::Codex => {
+                let text = provider
+                    .settings_config
+                    .get("config")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let updated = crate::codex_config::set_model(text, model.as_deref())?;
+                let settings = provider.settings_config.as_object_mut().ok_or_else(|| {
+                    AppError::Config(format!("供应商 {provider_id} 的 Codex 配置必须是对象"))
+                })?;
+                settings.insert("config".to_string(), Value::String(updated));
+            }
+            AppType::Claude | AppType::Gemini => {
+                let key = match app_type {
+                    AppType::Claude => "ANTHROPIC_MODEL",
+                    _ => "GEMINI_MODEL",
+                };
+                let settings = provider.settings_config.as_object_mut().ok_or_else(|| {
+                    AppError::Config(format!("供应商 {provider_id} 的配置必须是对象"))
+                })?;
+                let env = settings
+                    .entry("env")
+                    .or_insert_with(|| json!({}))
+                    .as_object_mut()
+                    .ok_or_else(|| {
+                        AppError::Config(format!("供应商 {provider_id} 的 env 必须是对象"))
+                    })?;
+                match model {
+                    Some(model) => {
+                        env.insert(key.to_string(), Value::String(model));
+                    }
+                    None => {
+                        env.remove(key);
+                    }
+                }
+            }
+        }
+
+        Self::update(state, app_type, provider)?;
+        Ok(())
+    }
+
+    /// 读取 Codex 供应商 config.toml 中指定键路径的值（支持 `@provider.base_url` 等别名）
+    pub fn get_codex_config_value(
+        state: &AppState,
+        provider_id: &str,
+        key_path: &str,
+    ) -> Result<Option<Value>, AppError> {
+        let provider = Self::provider_snapshot(state, &AppType::Codex, provider_id)?;
+        let text = provider
+            .settings_config
+            .get("config")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        crate::codex_config::get_config_value(text, key_path)
+    }
+
+    /// 设置 Codex 供应商 config.toml 中指定键路径的值（null 表示移除），
+    /// 若为当前供应商则立即写入 live 配置
+    pub fn set_codex_config_value(
+        state: &AppState,
+        provider_id: &str,
+        key_path: &str,
+        value: Value,
+    ) -> Result<(), AppError> {
+        let mut provider = Self::provider_snapshot(state, &AppType::Codex, provider_id)?;
+        let text = provider
+            .settings_config
+            .get("config")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let updated = crate::codex_config::set_config_value(text, key_path, &value)?;
+        let settings = provider.settings_config.as_object_mut().ok_or_else(|| {
+            AppError::Config(format!("供应商 {provider_id} 的 Codex 配置必须是对象"))
+        })?;
+        settings.insert("config".to_string(), Value::String(updated));
+
+        Self::update(state, AppType::Codex, provider)?;
+        Ok(())
+    }
+
+    /// 读取供应商的当前副本（用于局部修改后经 [`Self::update`] 保存）
+    fn provider_snapshot(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Result<Provider, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        config
+            .get_manager(app_type)
+            .ok_or_else(|| Self::app_not_found(app_type))?
+            .providers
+            .get(provider_id)
+            .cloned()
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {provider_id}"),
+                    format!("Provider not found: {provider_id}"),
+                )
+            })
+    }
+
+    /// 设置 Codex 供应商的额外环境变量（若为当前供应商则立即写入 live 配置）
+    pub fn set_provider_extra_env(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        env: HashMap<String, String>,
+    ) -> Result<(), AppError> {
+        if !matches!(app_type, AppType::Codex) {
+            return Err(AppError::localized(
+                "provider.extra_env.unsupported_app",
+                "仅 Codex 支持额外环境变量",
+                "Extra environment variables are only supported for Codex",
+            ));
+        }
+        let env: HashMap<String, String> = env
+            .into_iter()
+            .map(|(name, value)| (name.trim().to_string(), value))
+            .collect();
+        Self::validate_extra_env(&env)?;
+        let provider_id_owned = provider_id.to_string();
+
+        Self::run_transaction(state, move |config| {
+            let manager = config
+                .get_manager_mut(&AppType::Codex)
+                .ok_or_else(|| Self::app_not_found(&AppType::Codex))?;
+            let is_current = manager.current == provider_id_owned;
+            let provider = manager
+                .providers
+                .get_mut(&provider_id_owned)
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id_owned}"),
+                        format!("Provider not found: {provider_id_owned}"),
+                    )
+                })?;
+
+            // 移除之前注入（可能已回填到供应商配置中）但本次不再保留的变量
+            let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
+            let stale: Vec<String> = meta
+                .extra_env
+                .keys()
+                .filter(|key| !env.contains_key(*key))
+                .cloned()
+                .collect();
+            meta.extra_env = env;
+            if !stale.is_empty() {
+                if let Some(text) = provider
+                    .settings_config
+                    .get("config")
+                    .and_then(Value::as_str)
+                {
+                    let cleaned =
+                        crate::codex_config::apply_shell_env(text, &HashMap::new(), &stale)?;
+                    if let Some(obj) = provider.settings_config.as_object_mut() {
+                        obj.insert("config".to_string(), Value::String(cleaned));
+                    }
+                }
+            }
+
+            let action = if is_current {
+                let backup = Self::capture_live_snapshot(&AppType::Codex)?;
+                Some(PostCommitAction {
+                    app_type: AppType::Codex,
+                    provider: provider.clone(),
+                    backup,
+                    sync_mcp: false,
+                    refresh_snapshot: false,
+                })
+            } else {
+                None
+            };
+
+            Ok(((), action))
+        })
+    }
+
+    /// 设置供应商自定义请求头（若为当前供应商则立即写入 live 配置）
+    pub fn set_provider_headers(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        headers: HashMap<String, String>,
+    ) -> Result<(), AppError> {
+        if matches!(app_type, AppType::Gemini) {
+            return Err(AppError::localized(
+                "provider.headers.unsupported_app",
+                "Gemini 暂不支持自定义请求头",
+                "Custom headers are not supported for Gemini",
+            ));
+        }
+        Self::validate_custom_headers(&headers)?;
+
+        let headers: HashMap<String, String> = headers
+            .into_iter()
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+        let app_type_clone = app_type.clone();
+        let provider_id_owned = provider_id.to_string();
+
+        Self::run_transaction(state, move |config| {
+            let manager = config
+                .get_manager_mut(&app_type_clone)
+                .ok_or_else(|| Self::app_not_found(&app_type_clone))?;
+            let is_current = manager.current == provider_id_owned;
+            let provider = manager
+                .providers
+                .get_mut(&provider_id_owned)
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id_owned}"),
+                        format!("Provider not found: {provider_id_owned}"),
+                    )
+                })?;
+
+            // 清空请求头时，同时移除之前注入到配置中的对应字段
+            if headers.is_empty() {
+                Self::strip_injected_headers(&app_type_clone, provider)?;
+            }
+            provider
+                .meta
+                .get_or_insert_with(ProviderMeta::default)
+                .headers = headers;
+
+            let action = if is_current {
+                let backup = Self::capture_live_snapshot(&app_type_clone)?;
+                Some(PostCommitAction {
+                    app_type: app_type_clone.clone(),
+                    provider: provider.clone(),
+                    backup,
+                    sync_mcp: false,
+                    refresh_snapshot: false,
+                })
+            } else {
+                None
+            };
+
+            Ok(((), action))
+        })
+    }
+
+    /// 设置 Claude 供应商的结构化设置（permissions、includeCoAuthoredBy、model 及模型别名），
+    /// 若为当前供应商则立即写入 settings.json
+    pub fn set_claude_settings(
+        state: &AppState,
+        provider_id: &str,
+        overrides: ClaudeSettingsOverrides,
+    ) -> Result<(), AppError> {
+        let overrides = Self::normalize_claude_settings(overrides)?;
+        let provider_id_owned = provider_id.to_string();
+
+        Self::run_transaction(state, move |config| {
+            let manager = config
+                .get_manager_mut(&AppType::Claude)
+                .ok_or_else(|| Self::app_not_found(&AppType::Claude))?;
+            let is_current = manager.current == provider_id_owned;
+            let provider = manager
+                .providers
+                .get_mut(&provider_id_owned)
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id_owned}"),
+                        format!("Provider not found: {provider_id_owned}"),
+                    )
+                })?;
+
+            // 切换离开时回填到配置中的旧字段需先移除，否则取消设置后仍会写入
+            let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
+            if let Some(previous) = meta.claude_settings.take() {
+                previous.strip(&mut provider.settings_config);
+            }
+            meta.claude_settings = (!overrides.is_empty()).then_some(overrides);
+
+            let action = if is_current {
+                let backup = Self::capture_live_snapshot(&AppType::Claude)?;
+                Some(PostCommitAction {
+                    app_type: AppType::Claude,
+                    provider: provider.clone(),
+                    backup,
+                    sync_mcp: false,
+                    refresh_snapshot: false,
+                })
+            } else {
+                None
+            };
+
+            Ok(((), action))
+        })
+    }
+
+    /// 设置供应商的并发、超时与重试参数（若为当前供应商则立即写入 live 配置）
+    pub fn set_provider_performance(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        performance: ProviderPerformance,
+    ) -> Result<(), AppError> {
+        match app_type {
+            AppType::Gemini => {
+                return Err(AppError::localized(
+                    "provider.performance.unsupported_app",
+                    "Gemini 暂不支持性能参数",
+                    "Performance settings are not supported for Gemini",
+                ))
+            }
+            AppType::Claude
+                if performance
+                    != (ProviderPerformance {
+                        request_timeout_ms: performance.request_timeout_ms,
+                        ..Default::default()
+                    }) =>
+            {
+                return Err(AppError::localized(
+                    "provider.performance.unsupported_field",
+                    "Claude 仅支持设置请求超时",
+                    "Only the request timeout can be set for Claude",
+                ))
+            }
+            _ => {}
+        }
+        let zero = performance
+            .codex_entries()
+            .into_iter()
+            .find(|(_, value)| *value == Some(0));
+        if let Some((key, _)) = zero {
+            return Err(AppError::localized(
+                "provider.performance.invalid",
+                format!("{key} 必须大于 0"),
+                format!("{key} must be greater than 0"),
+            ));
+        }
+        let app_type_clone = app_type.clone();
+        let provider_id_owned = provider_id.to_string();
+
+        Self::run_transaction(state, move |config| {
+            let manager = config
+                .get_manager_mut(&app_type_clone)
+                .ok_or_else(|| Self::app_not_found(&app_type_clone))?;
+            let is_current = manager.current == provider_id_owned;
+            let provider = manager
+                .providers
+                .get_mut(&provider_id_owned)
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id_owned}"),
+                        format!("Provider not found: {provider_id_owned}"),
+                    )
+                })?;
+
+            // 切换离开时回填到配置中的旧值需先移除，否则取消设置后仍会写入
+            let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
+            if let Some(previous) = meta.performance.take() {
+                match app_type_clone {
+                    AppType::Claude => previous.strip_claude(&mut provider.settings_config),
+                    _ => {
+                        let cleared: Vec<(&str, Option<u64>)> = previous
+                            .codex_entries()
+                            .into_iter()
+                            .filter(|(_, value)| value.is_some())
+                            .map(|(key, _)| (key, None))
+                            .collect();
+                        if let Some(text) = provider
+                            .settings_config
+                            .get("config")
+                            .and_then(Value::as_str)
+                        {
+                            let cleaned =
+                                crate::codex_config::apply_provider_numbers(text, &cleared)?;
+                            if let Some(obj) = provider.settings_config.as_object_mut() {
+                                obj.insert("config".to_string(), Value::String(cleaned));
+                            }
+                        }
+                    }
+                }
+            }
+            meta.performance = (!performance.is_empty()).then_some(performance);
+
+            let action = if is_current {
+                let backup = Self::capture_live_snapshot(&app_type_clone)?;
+                Some(PostCommitAction {
+                    app_type: app_type_clone.clone(),
+                    provider: provider.clone(),
+                    backup,
+                    sync_mcp: false,
+                    refresh_snapshot: false,
+                })
+            } else {
+                None
+            };
+
+            Ok(((), action))
+        })
+    }
+
+    /// 去除空白与重复的权限规则，校验模型名
+    fn normalize_claude_settings(
+        mut overrides: ClaudeSettingsOverrides,
+    ) -> Result<ClaudeSettingsOverrides, AppError> {
+        fn clean(value: Option<String>) -> Result<Option<String>, AppError> {
+            let value = value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+            if value
+                .as_deref()
+                .is_some_and(|value| value.contains(['\r', '\n', '\0']))
+            {
+                return Err(AppError::localized(
+                    "provider.model.invalid",
+                    "模型名称不能包含换行符或空字符",
+                    "Model name must not contain line breaks or NUL",
+                ));
+            }
+            Ok(value)
+        }
+
+        if let Some(permissions) = overrides.permissions.as_mut() {
+            for rules in [&mut permissions.allow, &mut permissions.deny] {
+                let mut seen = HashSet::new();
+                rules.retain_mut(|rule| {
+                    *rule = rule.trim().to_string();
+                    !rule.is_empty() && seen.insert(rule.clone())
+                });
+            }
+        }
+        overrides.model = clean(overrides.model)?;
+        let aliases = &mut overrides.model_aliases;
+        aliases.opus = clean(aliases.opus.take())?;
+        aliases.sonnet = clean(aliases.sonnet.take())?;
+        aliases.haiku = clean(aliases.haiku.take())?;
+        Ok(overrides)
+    }
+
+    fn strip_injected_headers(app_type: &AppType, provider: &mut Provider) -> Result<(), AppError> {
+        match app_type {
+            AppType::Claude => {
+                if let Some(env) = provider
+                    .settings_config
+                    .get_mut("env")
+                    .and_then(Value::as_object_mut)
+                {
+                    env.remove(Self::CLAUDE_CUSTOM_HEADERS_ENV);
+                }
+            }
+            AppType::Codex => {
+                let cfg_text = provider
+                    .settings_config
+                    .get("config")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                if let Some(text) = cfg_text {
+                    let cleaned = crate::codex_config::apply_http_headers(&text, &HashMap::new())?;
+                    if let Some(obj) = provider.settings_config.as_object_mut() {
+                        obj.insert("config".to_string(), Value::String(cleaned));
+                    }
+                }
+            }
+            AppType::Gemini => {}
+        }
+        Ok(())
+    }
+
+    fn prepare_switch_claude(
+        config: &mut MultiAppConfig,
         provider_id: &str,
     ) -> Result<Provider, AppError> {
         let provider = config
@@ -1411,10 +3304,21 @@ impl ProviderService {
             return Ok(());
         }
 
-        let mut live = read_json_file::<Value>(&settings_path)?;
+        let mut live = Self::claude_snapshot_from_live(read_json_file::<Value>(&settings_path)?);
         let _ = Self::normalize_claude_models_in_value(&mut live);
+        // cc-switch 管理的钩子与供应商无关，不写入快照
+        crate::claude_hooks::strip_managed(&mut live);
+        // 凭据损坏或钥匙串不可读时不阻断切换，仅跳过快照刷新
+        let credentials = read_claude_credentials()
+            .ok()
+            .flatten()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok());
         if let Some(manager) = config.get_manager_mut(&AppType::Claude) {
             if let Some(current) = manager.providers.get_mut(&current_id) {
+                if let Some(credentials) = credentials {
+                    Self::refresh_oauth_snapshot(current, credentials);
+                }
+                placeholder::restore_value(&current.settings_config, &mut live);
                 current.settings_config = live;
             }
         }
@@ -1426,12 +3330,9 @@ impl ProviderService {
         config: &mut MultiAppConfig,
         next_provider: &str,
     ) -> Result<(), AppError> {
-        use crate::gemini_config::{env_to_json, get_gemini_env_path, read_gemini_env};
-
-        let env_path = get_gemini_env_path();
-        if !env_path.exists() {
-            return Ok(());
-        }
+        use crate::gemini_config::{
+            env_to_json, get_gemini_env_path, read_gemini_managed_env, read_gemini_oauth_creds,
+        };
 
         let current_id = config
             .get_manager(&AppType::Gemini)
@@ -1441,10 +3342,26 @@ impl ProviderService {
             return Ok(());
         }
 
-        let env_map = read_gemini_env()?;
-        let live = env_to_json(&env_map);
+        // Gemini CLI 会自动刷新 token，切出前用 live 凭据刷新 OAuth 供应商的快照（读取失败不阻断切换）
+        if let Some(creds) = read_gemini_oauth_creds().ok().flatten() {
+            if let Some(current) = config
+                .get_manager_mut(&AppType::Gemini)
+                .and_then(|manager| manager.providers.get_mut(&current_id))
+            {
+                Self::refresh_oauth_snapshot(current, creds);
+            }
+        }
+
+        let env_path = get_gemini_env_path();
+        if !env_path.exists() {
+            return Ok(());
+        }
+
+        let managed = Self::gemini_managed_env_keys(config);
+        let mut live = env_to_json(&read_gemini_managed_env(&managed)?);
         if let Some(manager) = config.get_manager_mut(&AppType::Gemini) {
             if let Some(current) = manager.providers.get_mut(&current_id) {
+                placeholder::restore_value(&current.settings_config, &mut live);
                 current.settings_config = live;
             }
         }
@@ -1454,15 +3371,245 @@ impl ProviderService {
 
     fn write_claude_live(provider: &Provider) -> Result<(), AppError> {
         let settings_path = get_claude_settings_path();
-        let mut content = provider.settings_config.clone();
-        let _ = Self::normalize_claude_models_in_value(&mut content);
+        let content = Self::merge_claude_live_settings(provider)?;
         write_json_file(&settings_path, &content)?;
+        // OAuth 供应商同时恢复 .credentials.json（macOS 为钥匙串）
+        if let Some(snapshot) = Self::oauth_snapshot(provider) {
+            let content = serde_json::to_string_pretty(snapshot)
+                .map_err(|e| AppError::JsonSerialize { source: e })?;
+            write_claude_credentials(&content)?;
+        }
         Ok(())
     }
 
-    fn write_gemini_live(provider: &Provider) -> Result<(), AppError> {
+    /// OAuth 供应商保存的凭据快照（API Key 供应商或尚未采集时返回 None）
+    pub(crate) fn oauth_snapshot(provider: &Provider) -> Option<&Value> {
+        if !provider.is_oauth() {
+            return None;
+        }
+        provider
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.oauth_snapshot.as_ref())
+            .filter(|snapshot| snapshot.is_object())
+    }
+
+    /// OAuth 凭据的 live 文件路径：Codex 为 auth.json，Claude 为 .credentials.json，Gemini 为 oauth_creds.json
+    fn oauth_credentials_path(app_type: &AppType) -> std::path::PathBuf {
+        match app_type {
+            AppType::Claude => get_claude_credentials_path(),
+            AppType::Codex => get_codex_auth_path(),
+            AppType::Gemini => crate::gemini_config::get_gemini_oauth_creds_path(),
+        }
+    }
+
+    /// 从 live 配置采集 OAuth 凭据快照并保存到供应商，同时将其标记为 OAuth 供应商
+    pub fn capture_oauth_snapshot(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+    ) -> Result<bool, AppError> {
+        let path = Self::oauth_credentials_path(&app_type);
+        let missing = || {
+            AppError::localized(
+                "oauth.credentials_missing",
+                format!("未找到登录凭据文件，请先完成官方登录: {}", path.display()),
+                format!(
+                    "Credential file not found; log in first: {}",
+                    path.display()
+                ),
+            )
+        };
+        let snapshot = match app_type {
+            // Claude 在 macOS 上凭据保存在钥匙串中
+            AppType::Claude => {
+                let content = read_claude_credentials()?.ok_or_else(missing)?;
+                serde_json::from_str::<Value>(&content).map_err(|e| AppError::json(&path, e))?
+            }
+            _ => {
+                if !path.exists() {
+                    return Err(missing());
+                }
+                read_json_file::<Value>(&path)?
+            }
+        };
+
+        {
+            let mut config = state.config.write().map_err(AppError::from)?;
+            let provider = config
+                .get_manager_mut(&app_type)
+                .and_then(|manager| manager.providers.get_mut(provider_id))
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?;
+            provider.auth_kind = Some(AuthKind::Oauth);
+            provider
+                .meta
+                .get_or_insert_with(ProviderMeta::default)
+                .oauth_snapshot = Some(snapshot);
+        }
+        state.save()?;
+        Ok(true)
+    }
+
+    /// 切出 OAuth 供应商时用 live 凭据刷新其快照（官方客户端会自动刷新 token）
+    fn refresh_oauth_snapshot(provider: &mut Provider, live: Value) {
+        if provider.is_oauth() && live.is_object() {
+            provider
+                .meta
+                .get_or_insert_with(ProviderMeta::default)
+                .oauth_snapshot = Some(live);
+        }
+    }
+
+    /// Claude 切换时由供应商接管的顶层键；`Replace` 模式返回 None（整体替换）
+    pub(crate) fn claude_managed_keys() -> Option<Vec<String>> {
+        let settings = settings::get_settings();
+        match settings.claude_settings_merge {
+            ClaudeSettingsMerge::Replace => None,
+            ClaudeSettingsMerge::ManagedKeys => {
+                let mut keys = vec!["env".to_string()];
+                keys.extend(settings.claude_managed_keys);
+                Some(keys)
+            }
+        }
+    }
+
+    /// 供应商的 Claude 结构化设置（未设置或为空时返回 None）
+    fn claude_settings_overrides(provider: &Provider) -> Option<&ClaudeSettingsOverrides> {
+        provider
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.claude_settings.as_ref())
+            .filter(|overrides| !overrides.is_empty())
+    }
+
+    /// 写入该供应商时改写的顶层键：在 [`Self::claude_managed_keys`] 基础上加入其结构化设置接管的键
+    fn claude_managed_keys_for(provider: &Provider) -> Option<Vec<String>> {
+        let mut keys = Self::claude_managed_keys()?;
+        if let Some(overrides) = Self::claude_settings_overrides(provider) {
+            for key in overrides.managed_keys() {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        Some(keys)
+    }
+
+    /// 按 `claude_settings_merge` 设置生成最终写入的 settings.json：
+    /// `managed-keys` 模式下以现有文件为底，仅改写托管键（供应商未提供的托管键会被移除）
+    pub(crate) fn merge_claude_live_settings(provider: &Provider) -> Result<Value, AppError> {
+        let content = Self::claude_live_settings(provider);
+        let Some(managed) = Self::claude_managed_keys_for(provider) else {
+            return Ok(content);
+        };
+        let settings_path = get_claude_settings_path();
+        if !settings_path.exists() {
+            return Ok(content);
+        }
+        let existing = read_json_file::<Value>(&settings_path)?;
+        Ok(Self::merge_managed_keys(existing, &content, &managed))
+    }
+
+    pub(crate) fn merge_managed_keys(
+        existing: Value,
+        content: &Value,
+        managed: &[String],
+    ) -> Value {
+        let (Value::Object(mut merged), Some(content_obj)) = (existing, content.as_object()) else {
+            return content.clone();
+        };
+        for key in managed {
+            match content_obj.get(key) {
+                Some(value) => {
+                    merged.insert(key.clone(), value.clone());
+                }
+                None => {
+                    merged.remove(key);
+                }
+            }
+        }
+        Value::Object(merged)
+    }
+
+    /// 回填快照时只保留托管键（`managed-keys` 模式），避免 permissions、hooks 等本地配置并入供应商
+    pub(crate) fn claude_snapshot_from_live(live: Value) -> Value {
+        let Some(managed) = Self::claude_managed_keys() else {
+            return live;
+        };
+        match live {
+            Value::Object(mut obj) => {
+                obj.retain(|key, _| managed.contains(key));
+                Value::Object(obj)
+            }
+            other => other,
+        }
+    }
+
+    /// Gemini .env 中由 cc-switch 管理的键：内置键、各供应商配置中的键以及上次写入时记录的键
+    pub(crate) fn gemini_managed_env_keys(config: &MultiAppConfig) -> HashSet<String> {
+        let mut keys: HashSet<String> = crate::gemini_config::GEMINI_MANAGED_ENV_KEYS
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+        if let Some(manager) = config.get_manager(&AppType::Gemini) {
+            for provider in manager.providers.values() {
+                if let Some(env) = provider
+                    .settings_config
+                    .get("env")
+                    .and_then(Value::as_object)
+                {
+                    keys.extend(env.keys().cloned());
+                }
+                if let Some(meta) = provider.meta.as_ref() {
+                    keys.extend(meta.managed_env_keys.iter().cloned());
+                }
+            }
+        }
+        keys
+    }
+
+    /// 记录本次写入 .env 的托管键，供下次合并写入时清理（无变化时不落盘）
+    fn record_gemini_env_keys(
+        state: &AppState,
+        provider_id: &str,
+        env_map: &HashMap<String, String>,
+    ) -> Result<(), AppError> {
+        let mut keys: Vec<String> = env_map.keys().cloned().collect();
+        keys.sort();
+        {
+            let mut guard = state.config.write().map_err(AppError::from)?;
+            let Some(provider) = guard
+                .get_manager_mut(&AppType::Gemini)
+                .and_then(|manager| manager.providers.get_mut(provider_id))
+            else {
+                return Ok(());
+            };
+            let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
+            if meta.managed_env_keys == keys {
+                return Ok(());
+            }
+            meta.managed_env_keys = keys;
+        }
+        state.save()
+    }
+
+    fn write_gemini_live(state: &AppState, provider: &Provider) -> Result<(), AppError> {
         use crate::gemini_config::{
-            json_to_env, validate_gemini_settings_strict, write_gemini_env_atomic,
+            json_to_env, validate_gemini_settings_strict, write_gemini_env_managed,
+        };
+
+        let (managed, managed_models) = {
+            let config = state.config.read().map_err(AppError::from)?;
+            (
+                Self::gemini_managed_env_keys(&config),
+                Self::gemini_provider_models(&config),
+            )
         };
 
         // 一次性检测认证类型，避免重复检测
@@ -1470,34 +3617,75 @@ impl ProviderService {
 
         match auth_type {
             GeminiAuthType::GoogleOfficial => {
-                // Google 官方使用 OAuth，清空 env
-                let empty_env = std::collections::HashMap::new();
-                write_gemini_env_atomic(&empty_env)?;
+                // Google 官方使用 OAuth，清空托管的 env
+                let empty_env = HashMap::new();
+                write_gemini_env_managed(&empty_env, &managed)?;
+                Self::record_gemini_env_keys(state, &provider.id, &empty_env)?;
                 Self::ensure_google_oauth_security_flag(provider)?;
+                // 多个 Google 账号：恢复该供应商保存的 OAuth 凭据缓存
+                if let Some(snapshot) = Self::oauth_snapshot(provider) {
+                    crate::gemini_config::write_gemini_oauth_creds(snapshot)?;
+                }
             }
             GeminiAuthType::Packycode => {
                 // PackyCode 供应商，使用 API Key（切换时严格验证）
                 validate_gemini_settings_strict(&provider.settings_config)?;
                 let env_map = json_to_env(&provider.settings_config)?;
-                write_gemini_env_atomic(&env_map)?;
+                write_gemini_env_managed(&env_map, &managed)?;
+                Self::record_gemini_env_keys(state, &provider.id, &env_map)?;
                 Self::ensure_packycode_security_flag(provider)?;
             }
             GeminiAuthType::Generic => {
                 // 通用供应商，使用 API Key（切换时严格验证）
                 validate_gemini_settings_strict(&provider.settings_config)?;
                 let env_map = json_to_env(&provider.settings_config)?;
-                write_gemini_env_atomic(&env_map)?;
+                write_gemini_env_managed(&env_map, &managed)?;
+                Self::record_gemini_env_keys(state, &provider.id, &env_map)?;
             }
         }
 
+        // 默认模型同时写入 settings.json，.env 之外启动的 Gemini CLI 也能使用
+        let model = json_to_env(&provider.settings_config)?
+            .remove("GEMINI_MODEL")
+            .filter(|model| !model.trim().is_empty());
+        crate::gemini_config::write_model_setting(model.as_deref(), &managed_models)?;
+
         Ok(())
     }
 
-    fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Result<(), AppError> {
+    /// 各 Gemini 供应商配置的 `GEMINI_MODEL`（写入 settings.json 时据此识别由供应商写入的模型）
+    fn gemini_provider_models(config: &MultiAppConfig) -> HashSet<String> {
+        config
+            .get_manager(&AppType::Gemini)
+            .map(|manager| {
+                manager
+                    .providers
+                    .values()
+                    .filter_map(|provider| {
+                        provider
+                            .settings_config
+                            .pointer("/env/GEMINI_MODEL")
+                            .and_then(Value::as_str)
+                            .map(str::to_string)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn write_live_snapshot(
+        state: &AppState,
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<(), AppError> {
+        // 占位符（`${HOME}`、`${env:NAME}` 等）只在写入 live 配置时展开
+        let mut expanded = provider.clone();
+        expanded.settings_config = placeholder::expand_value(&provider.settings_config)?;
+        let provider = &expanded;
         match app_type {
             AppType::Codex => Self::write_codex_live(provider),
             AppType::Claude => Self::write_claude_live(provider),
-            AppType::Gemini => Self::write_gemini_live(provider), // 新增
+            AppType::Gemini => Self::write_gemini_live(state, provider), // 新增
         }
     }
 
@@ -1564,6 +3752,17 @@ impl ProviderService {
             if let Some(usage_script) = &meta.usage_script {
                 Self::validate_usage_script(usage_script)?;
             }
+            Self::validate_custom_headers(&meta.headers)?;
+            if !meta.extra_env.is_empty() {
+                if !matches!(app_type, AppType::Codex) {
+                    return Err(AppError::localized(
+                        "provider.extra_env.unsupported_app",
+                        "仅 Codex 支持额外环境变量",
+                        "Extra environment variables are only supported for Codex",
+                    ));
+                }
+                Self::validate_extra_env(&meta.extra_env)?;
+            }
         }
 
         Ok(())
@@ -1718,7 +3917,7 @@ impl ProviderService {
         }
     }
 
-    fn app_not_found(app_type: &AppType) -> AppError {
+    pub(crate) fn app_not_found(app_type: &AppType) -> AppError {
         AppError::localized(
             "provider.app_not_found",
             format!("应用类型不存在: {app_type:?}"),
@@ -1798,6 +3997,148 @@ impl ProviderService {
     }
 }
 
+/// `fix_codex_wire_api` 的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireApiFix {
+    /// 探测得到并写入的 wire_api
+    pub wire_api: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultImportStatus {
+    /// 已导入 live 配置为 default 供应商
+    Imported,
+    /// 已有供应商，未导入
+    AlreadyConfigured,
+    /// 未检测到该 CLI
+    NotInstalled,
+    /// live 配置缺失或无效
+    Failed,
+}
+
+/// 批量导入默认配置时单个应用的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultImportResult {
+    pub app_type: AppType,
+    pub status: DefaultImportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 切换结果：预检未通过时 `switched` 为 false 并附带警告，确认后以 `force` 重试
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchOutcome {
+    pub switched: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<SwitchWarning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<SwitchDetails>,
+}
+
+/// 切换完成后的详情（同时作为 `provider-switched` 事件载荷）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchDetails {
+    pub app_type: AppType,
+    pub provider_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_provider_id: Option<String>,
+    pub provider_name: String,
+    /// 写入 live 配置耗时（毫秒）
+    pub live_write_ms: u64,
+    /// 本次切换是否同步了 MCP 服务器
+    pub mcp_synced: bool,
+    /// 目标已是当前供应商且 live 配置未变化，未做任何写入
+    pub no_change: bool,
+    /// 写入校验结果（开启 `verify_live_writes` 时）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<LiveVerification>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwitchWarningKind {
+    /// 最近一次测速延迟超过阈值
+    HighLatency,
+    /// 最近一次测速失败
+    ProbeFailed,
+}
+
+/// 切换前健康预检警告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchWarning {
+    pub kind: SwitchWarningKind,
+    pub provider_id: String,
+    pub base_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<u128>,
+    pub threshold_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub tested_at: i64,
+}
+
+/// 供应商搜索筛选条件（各条件同时满足）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderSearchFilters {
+    /// 允许的分类（为空表示不限）
+    pub categories: Vec<String>,
+    /// 必须包含的标签（不区分大小写）
+    pub tags: Vec<String>,
+    /// 最近一次测速延迟上限（毫秒）；设置后未测速或测速失败的供应商将被排除
+    pub max_latency_ms: Option<u64>,
+}
+
+impl ProviderSearchFilters {
+    fn matches(&self, provider: &Provider, latency: Option<u128>) -> bool {
+        if !self.categories.is_empty()
+            && !provider
+                .category
+                .as_ref()
+                .is_some_and(|category| self.categories.contains(category))
+        {
+            return false;
+        }
+        let provider_tags = provider.meta.as_ref().map(|m| m.tags.as_slice());
+        let has_tags = self.tags.iter().all(|wanted| {
+            provider_tags
+                .unwrap_or_default()
+                .iter()
+                .any(|tag| tag.eq_ignore_ascii_case(wanted))
+        });
+        if !has_tags {
+            return false;
+        }
+        match self.max_latency_ms {
+            Some(max) => latency.is_some_and(|value| value <= u128::from(max)),
+            None => true,
+        }
+    }
+}
+
+/// 供应商搜索结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSearchHit {
+    pub id: String,
+    pub provider: Provider,
+    /// 匹配得分（空关键字时为 0）
+    pub score: u32,
+    /// 命中的字段：name / tags / endpoint / notes
+    pub matched_fields: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<u128>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ProviderSortUpdate {
     pub id: String,
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::database::{self, db_error};
+use crate::error::AppError;
+
+/// 指标保留天数
+const RETENTION_DAYS: i64 = 90;
+
+/// 统计时间范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsPeriod {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl MetricsPeriod {
+    fn millis(self) -> i64 {
+        const HOUR: i64 = 60 * 60 * 1000;
+        match self {
+            Self::Hour => HOUR,
+            Self::Day => 24 * HOUR,
+            Self::Week => 7 * 24 * HOUR,
+            Self::Month => 30 * 24 * HOUR,
+        }
+    }
+}
+
+/// 代理转发给某个供应商的一次请求（故障转移时每次尝试各记一条）
+#[derive(Debug, Clone)]
+pub struct ProxySample {
+    pub at: i64,
+    pub app_type: String,
+    pub provider_id: String,
+    /// 上游状态码，连接失败时为空
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+}
+
+impl ProxySample {
+    /// 无响应或状态码不在 2xx / 3xx 时记为错误
+    fn is_error(&self) -> bool {
+        !self
+            .status
+            .is_some_and(|status| (200..400).contains(&status))
+    }
+}
+
+/// 单个供应商在统计范围内的指标
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderMetrics {
+    pub app_type: String,
+    pub provider_id: String,
+    pub requests: u64,
+    pub errors: u64,
+    /// 错误率（0~1）
+    pub error_rate: f64,
+    /// 成功请求的延迟分位数（毫秒），没有成功请求时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p50_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p90_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p99_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_ms: Option<u64>,
+}
+
+/// `get_proxy_metrics` 的返回值
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyMetricsReport {
+    pub period: MetricsPeriod,
+    /// 统计起点（Unix 毫秒）
+    pub since: i64,
+    pub providers: Vec<ProviderMetrics>,
+}
+
+/// 本地代理的请求指标，保存在本地数据库（见 [`database`]）的 `proxy_requests` 表
+pub struct ProxyMetricsService;
+
+impl ProxyMetricsService {
+    /// 记录一次转发（失败只记录日志，不影响代理请求）
+    pub fn record(sample: &ProxySample) {
+        if let Err(e) = database::with_connection(|conn| insert(conn, sample)) {
+            log::warn!("记录代理指标失败: {e}");
+        }
+    }
+
+    pub fn report(period: MetricsPeriod) -> Result<ProxyMetricsReport, AppError> {
+        let since = chrono::Utc::now().timestamp_millis() - period.millis();
+        Ok(ProxyMetricsReport {
+            period,
+            since,
+            providers: database::with_connection(|conn| aggregate(conn, since, None, None))?,
+        })
+    }
+
+    /// 指定供应商在 `[since, until)` 内的指标（`until` 为空表示至今，A/B 测试按时间窗口统计时使用）
+    pub fn provider_metrics(
+        app_type: &str,
+        provider_id: &str,
+        since: i64,
+        until: Option<i64>,
+    ) -> Result<ProviderMetrics, AppError> {
+        let metrics = database::with_connection(|conn| {
+            aggregate(conn, since, until, Some((app_type, provider_id)))
+        })?;
+        Ok(metrics
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| ProviderMetrics::empty(app_type, provider_id)))
+    }
+}
+
+impl ProviderMetrics {
+    fn empty(app_type: &str, provider_id: &str) -> Self {
+        Self {
+            app_type: app_type.to_string(),
+            provider_id: provider_id.to_string(),
+            requests: 0,
+            errors: 0,
+            error_rate: 0.0,
+            p50_ms: None,
+            p90_ms: None,
+            p99_ms: None,
+            avg_ms: None,
+        }
+    }
+}
+
+/// 建表并清理超过保留天数的记录
+pub(crate) fn init_schema(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS proxy_requests (
+            at INTEGER NOT NULL,
+            app_type TEXT NOT NULL,
+            provider_id TEXT NOT NULL,
+            status INTEGER,
+            latency_ms INTEGER NOT NULL,
+            is_error INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_proxy_requests_at ON proxy_requests (at);",
+    )
+    .map_err(db_error)?;
+    let cutoff = chrono::Utc::now().timestamp_millis() - RETENTION_DAYS * 24 * 3600 * 1000;
+    conn.execute("DELETE FROM proxy_requests WHERE at < ?1", params![cutoff])
+        .map_err(db_error)?;
+    Ok(())
+}
+
+fn insert(conn: &Connection, sample: &ProxySample) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO proxy_requests (at, app_type, provider_id, status, latency_ms, is_error)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            sample.at,
+            sample.app_type,
+            sample.provider_id,
+            sample.status,
+            sample.latency_ms as i64,
+            sample.is_error(),
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// 按供应商汇总请求数、错误率与成功请求的延迟分位数
+fn aggregate(
+    conn: &Connection,
+    since: i64,
+    until: Option<i64>,
+    only: Option<(&str, &str)>,
+) -> Result<Vec<ProviderMetrics>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT app_type, provider_id, latency_ms, is_error FROM proxy_requests
+             WHERE at >= ?1 AND (?2 IS NULL OR at < ?2)
+               AND (?3 IS NULL OR (app_type = ?3 AND provider_id = ?4))",
+        )
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map(
+            params![
+                since,
+                until,
+                only.map(|(app, _)| app),
+                only.map(|(_, id)| id)
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, bool>(3)?,
+                ))
+            },
+        )
+        .map_err(db_error)?;
+
+    // (应用, 供应商) -> (请求数, 错误数, 成功请求延迟)
+    let mut grouped: BTreeMap<(String, String), (u64, u64, Vec<u64>)> = BTreeMap::new();
+    for row in rows {
+        let (app_type, provider_id, latency, is_error) = row.map_err(db_error)?;
+        let entry = grouped.entry((app_type, provider_id)).or_default();
+        entry.0 += 1;
+        if is_error {
+            entry.1 += 1;
+        } else {
+            entry.2.push(latency.max(0) as u64);
+        }
+    }
+
+    Ok(grouped
+        .into_iter()
+        .map(
+            |((app_type, provider_id), (requests, errors, mut latencies))| {
+                latencies.sort_unstable();
+                let avg = (!latencies.is_empty())
+                    .then(|| latencies.iter().sum::<u64>() / latencies.len() as u64);
+                ProviderMetrics {
+                    error_rate: errors as f64 / requests as f64,
+                    p50_ms: percentile(&latencies, 50),
+                    p90_ms: percentile(&latencies, 90),
+                    p99_ms: percentile(&latencies, 99),
+                    avg_ms: avg,
+                    requests,
+                    errors,
+                    app_type,
+                    provider_id,
+                }
+            },
+        )
+        .collect())
+}
+
+/// 最近秩法分位数（`sorted` 需已升序）
+fn percentile(sorted: &[u64], p: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_error_rates_and_percentiles() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        let sample = |provider: &str, status: Option<u16>, latency: u64| ProxySample {
+            at: 1_000,
+            app_type: "claude".into(),
+            provider_id: provider.into(),
+            status,
+            latency_ms: latency,
+        };
+        for latency in 1..=100 {
+            insert(&conn, &sample("a", Some(200), latency)).unwrap();
+        }
+        insert(&conn, &sample("b", Some(200), 40)).unwrap();
+        insert(&conn, &sample("b", Some(529), 10)).unwrap();
+        insert(&conn, &sample("b", None, 15_000)).unwrap();
+
+        let metrics = aggregate(&conn, 0, None, None).unwrap();
+        assert_eq!(metrics.len(), 2);
+        let a = &metrics[0];
+        assert_eq!((a.requests, a.errors), (100, 0));
+        assert_eq!(
+            (a.p50_ms, a.p90_ms, a.p99_ms),
+            (Some(50), Some(90), Some(99))
+        );
+        let b = &metrics[1];
+        assert_eq!((b.requests, b.errors), (3, 2));
+        assert!((b.error_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(b.p50_ms, Some(40));
+
+        assert_eq!(aggregate(&conn, 2_000, None, None).unwrap(), Vec::new());
+        assert_eq!(aggregate(&conn, 0, Some(1_000), None).unwrap(), Vec::new());
+        assert_eq!(
+            aggregate(&conn, 0, Some(1_001), Some(("claude", "b")))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
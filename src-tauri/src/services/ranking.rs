@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::{ProviderService, ProxyMetricsService, SpeedtestService};
+use crate::settings::{self, AppSettings};
+use crate::store::AppState;
+use crate::usage_script::{self, QuotaSnapshot};
+
+/// 没有对应数据时使用的中性分
+const UNKNOWN_SCORE: f64 = 0.5;
+
+/// 测速超时（秒）
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// 统计错误率的时间窗口（毫秒）
+const ERROR_RATE_WINDOW_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// 请求数少于该值时不采信错误率
+const MIN_ERROR_RATE_SAMPLES: u64 = 5;
+
+/// 各评分维度的权重（按总和归一化，0 表示忽略该维度）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankingWeights {
+    #[serde(default)]
+    pub latency: f64,
+    #[serde(default)]
+    pub error_rate: f64,
+    #[serde(default)]
+    pub quota: f64,
+    #[serde(default)]
+    pub price: f64,
+    #[serde(default)]
+    pub pinned: f64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            latency: 0.3,
+            error_rate: 0.25,
+            quota: 0.25,
+            price: 0.1,
+            pinned: 0.1,
+        }
+    }
+}
+
+impl RankingWeights {
+    fn validate(&self) -> Result<(), AppError> {
+        let weights = [
+            self.latency,
+            self.error_rate,
+            self.quota,
+            self.price,
+            self.pinned,
+        ];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) || weights.iter().sum::<f64>() <= 0.0
+        {
+            return Err(AppError::localized(
+                "ranking.invalid_weights",
+                "评分权重必须为非负数且不能全为 0",
+                "Ranking weights must be non-negative and not all zero",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 各维度的得分（0~1，越高越好）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankComponents {
+    pub latency: f64,
+    pub error_rate: f64,
+    pub quota: f64,
+    pub price: f64,
+    pub pinned: f64,
+}
+
+/// 单个供应商的排名结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRank {
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 综合得分（0~1），不可用的供应商为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// 排名第一的可用供应商
+    pub recommended: bool,
+    pub components: RankComponents,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// 最近 24 小时经本地代理的错误率
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<QuotaSnapshot>,
+    /// 不可用原因（额度耗尽、端点不可达）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unavailable: Option<String>,
+}
+
+/// `switch_to_best_available` 的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BestProviderSwitch {
+    pub provider_id: String,
+    /// 所有供应商的排名（高分在前）
+    pub ranks: Vec<ProviderRank>,
+}
+
+/// 参与评分的原始数据
+#[derive(Debug, Clone, Default)]
+struct RankInput {
+    provider_id: String,
+    provider_name: String,
+    pinned: bool,
+    /// 未测速（无端点或离线模式）为 None；测速失败为 Some(None)
+    latency_ms: Option<Option<u64>>,
+    error_rate: Option<f64>,
+    quota: Option<QuotaSnapshot>,
+    /// 换算为同一货币后的每百万输出 tokens 价格
+    price: Option<f64>,
+}
+
+/// 供应商评分与推荐：综合延迟、错误率、剩余额度、价格与置顶状态
+pub struct RankingService;
+
+impl RankingService {
+    /// 按权重为供应商排名（高分在前，不可用的排在最后）；未指定权重时使用设置中的权重
+    pub async fn rank(
+        state: &AppState,
+        app_type: &AppType,
+        weights: Option<RankingWeights>,
+    ) -> Result<Vec<ProviderRank>, AppError> {
+        let settings = settings::get_settings();
+        let weights = weights.or(settings.ranking_weights).unwrap_or_default();
+        weights.validate()?;
+
+        let providers = ProviderService::list_sorted(state, app_type.clone())?.0;
+        let urls: Vec<Option<String>> = providers
+            .iter()
+            .map(|(_, provider)| ProviderService::provider_base_url(app_type, provider))
+            .collect();
+        // 延迟权重为 0 时无需测速
+        let latencies = if settings.offline_mode || weights.latency == 0.0 {
+            Vec::new()
+        } else {
+            SpeedtestService::test_endpoints(
+                urls.iter().flatten().cloned().collect(),
+                Some(PROBE_TIMEOUT_SECS),
+            )
+            .await?
+        };
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let inputs = providers
+            .into_iter()
+            .zip(urls)
+            .map(|((id, provider), url)| {
+                let latency_ms = url.as_deref().and_then(|url| {
+                    latencies
+                        .iter()
+                        .find(|result| result.url == url)
+                        .map(|result| result.latency.map(|ms| ms as u64))
+                });
+                let error_rate = ProxyMetricsService::provider_metrics(
+                    app_type.as_str(),
+                    &id,
+                    now - ERROR_RATE_WINDOW_MS,
+                    None,
+                )
+                .ok()
+                .filter(|metrics| metrics.requests >= MIN_ERROR_RATE_SAMPLES)
+                .map(|metrics| metrics.error_rate);
+                RankInput {
+                    quota: usage_script::latest_quota(app_type, &id, now),
+                    price: output_price(&provider, &settings),
+                    pinned: provider.pinned,
+                    provider_name: provider.name,
+                    provider_id: id,
+                    latency_ms,
+                    error_rate,
+                }
+            })
+            .collect();
+        Ok(rank(inputs, &weights))
+    }
+}
+
+/// 每百万输出 tokens 价格，按设置中的汇率换算为美元（无法换算时为空）
+fn output_price(provider: &Provider, settings: &AppSettings) -> Option<f64> {
+    let pricing = provider.meta.as_ref()?.pricing.as_ref()?;
+    let price = pricing.output_per_million?;
+    match pricing.currency.as_deref() {
+        Some(currency) => {
+            Some(price * crate::usage_normalize::conversion_rate(currency, "USD", settings)?)
+        }
+        None => Some(price),
+    }
+}
+
+fn rank(inputs: Vec<RankInput>, weights: &RankingWeights) -> Vec<ProviderRank> {
+    let max_latency = inputs
+        .iter()
+        .filter_map(|input| input.latency_ms.flatten())
+        .max();
+    let min_price = inputs
+        .iter()
+        .filter_map(|input| input.price)
+        .filter(|price| *price > 0.0)
+        .min_by(f64::total_cmp);
+    let total_weight =
+        weights.latency + weights.error_rate + weights.quota + weights.price + weights.pinned;
+
+    let mut ranks: Vec<ProviderRank> = inputs
+        .into_iter()
+        .map(|input| {
+            let unavailable = if input.quota.is_some_and(|quota| quota.remaining <= 0.0) {
+                Some("quota exhausted".to_string())
+            } else if input.latency_ms == Some(None) {
+                Some("endpoint unreachable".to_string())
+            } else {
+                None
+            };
+            let components = RankComponents {
+                // 按相对最慢端点的比例线性计分：最快接近 1，最慢为 0.5
+                latency: match (input.latency_ms.flatten(), max_latency) {
+                    (Some(ms), Some(max)) if max > 0 => 1.0 - ms as f64 / max as f64 * 0.5,
+                    (Some(_), _) => 1.0,
+                    (None, _) => UNKNOWN_SCORE,
+                },
+                error_rate: input.error_rate.map_or(UNKNOWN_SCORE, |rate| 1.0 - rate),
+                quota: match input.quota {
+                    Some(QuotaSnapshot {
+                        remaining,
+                        total: Some(total),
+                        ..
+                    }) if total > 0.0 => (remaining / total).clamp(0.0, 1.0),
+                    Some(_) => 1.0,
+                    None => UNKNOWN_SCORE,
+                },
+                // 最便宜者为 1，其余按价格比例
+                price: match (input.price, min_price) {
+                    (Some(price), Some(min)) if price > 0.0 => min / price,
+                    (Some(_), _) => 1.0,
+                    (None, _) => UNKNOWN_SCORE,
+                },
+                pinned: if input.pinned { 1.0 } else { 0.0 },
+            };
+            let weighted = weights.latency * components.latency
+                + weights.error_rate * components.error_rate
+                + weights.quota * components.quota
+                + weights.price * components.price
+                + weights.pinned * components.pinned;
+            ProviderRank {
+                score: unavailable.is_none().then(|| weighted / total_weight),
+                recommended: false,
+                latency_ms: input.latency_ms.flatten(),
+                provider_id: input.provider_id,
+                provider_name: input.provider_name,
+                components,
+                error_rate: input.error_rate,
+                quota: input.quota,
+                unavailable,
+            }
+        })
+        .collect();
+
+    ranks.sort_by(|a, b| b.score.unwrap_or(-1.0).total_cmp(&a.score.unwrap_or(-1.0)));
+    if let Some(best) = ranks.first_mut().filter(|rank| rank.score.is_some()) {
+        best.recommended = true;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(id: &str) -> RankInput {
+        RankInput {
+            provider_id: id.into(),
+            provider_name: id.into(),
+            ..Default::default()
+        }
+    }
+
+    fn quota(remaining: f64, total: f64) -> Option<QuotaSnapshot> {
+        Some(QuotaSnapshot {
+            remaining,
+            total: Some(total),
+            captured_at: 0,
+        })
+    }
+
+    #[test]
+    fn ranks_by_weighted_components() {
+        let inputs = vec![
+            RankInput {
+                latency_ms: Some(Some(400)),
+                quota: quota(100.0, 100.0),
+                price: Some(15.0),
+                ..input("slow-full")
+            },
+            RankInput {
+                latency_ms: Some(Some(100)),
+                quota: quota(10.0, 100.0),
+                price: Some(3.0),
+                error_rate: Some(0.5),
+                ..input("fast-low")
+            },
+            RankInput {
+                quota: quota(0.0, 100.0),
+                ..input("exhausted")
+            },
+            RankInput {
+                latency_ms: Some(None),
+                ..input("unreachable")
+            },
+        ];
+
+        let quota_first = RankingWeights {
+            latency: 0.4,
+            error_rate: 0.0,
+            quota: 0.6,
+            price: 0.0,
+            pinned: 0.0,
+        };
+        let ranks = rank(inputs.clone(), &quota_first);
+        assert_eq!(ranks[0].provider_id, "slow-full");
+        assert!(ranks[0].recommended && !ranks[1].recommended);
+        assert!((ranks[0].score.unwrap() - 0.8).abs() < 1e-9);
+        // 不可用的供应商排在最后且没有得分
+        assert!(ranks[2..].iter().all(|rank| rank.score.is_none()));
+        assert!(ranks[2..].iter().all(|rank| rank.unavailable.is_some()));
+
+        let price_first = RankingWeights {
+            price: 1.0,
+            ..quota_first
+        };
+        assert_eq!(rank(inputs, &price_first)[0].provider_id, "fast-low");
+
+        assert!(RankingWeights {
+            latency: 0.0,
+            error_rate: 0.0,
+            quota: 0.0,
+            price: 0.0,
+            pinned: 0.0,
+        }
+        .validate()
+        .is_err());
+    }
+}
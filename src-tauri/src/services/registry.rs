@@ -0,0 +1,319 @@
+//! 离线注册表：供应商预设、MCP 目录、用量脚本模板
+//!
+//! 三份注册表以 JSON 形式随程序打包（`assets/registries`），离线时直接使用内置版本。
+//! `refresh_all` 在联网时从注册表地址拉取最新版本并缓存到 `~/.cc-switch/registries`；
+//! 读取时取缓存与内置版本中 `version` 较高者，拉取失败不影响已有数据。
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use futures::future::join_all;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::{get_app_config_dir, write_json_file};
+use crate::error::AppError;
+use crate::settings;
+
+/// 默认注册表地址（与仓库中的内置文件一致）
+const DEFAULT_REGISTRY_URL: &str =
+    "https://raw.githubusercontent.com/farion1231/cc-switch/main/src-tauri/assets/registries";
+const FETCH_TIMEOUT_SECS: u64 = 15;
+/// 单个注册表文件大小上限
+const MAX_REGISTRY_BYTES: usize = 2 * 1024 * 1024;
+
+/// 注册表类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RegistryKind {
+    ProviderPresets,
+    McpCatalog,
+    UsageTemplates,
+}
+
+impl RegistryKind {
+    pub const ALL: [RegistryKind; 3] = [
+        RegistryKind::ProviderPresets,
+        RegistryKind::McpCatalog,
+        RegistryKind::UsageTemplates,
+    ];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::ProviderPresets => "provider-presets.json",
+            Self::McpCatalog => "mcp-catalog.json",
+            Self::UsageTemplates => "usage-templates.json",
+        }
+    }
+
+    fn embedded(self) -> &'static str {
+        match self {
+            Self::ProviderPresets => {
+                include_str!("../../assets/registries/provider-presets.json")
+            }
+            Self::McpCatalog => include_str!("../../assets/registries/mcp-catalog.json"),
+            Self::UsageTemplates => include_str!("../../assets/registries/usage-templates.json"),
+        }
+    }
+
+    /// 文档中必须存在的数组字段
+    fn required_lists(self) -> &'static [&'static str] {
+        match self {
+            Self::ProviderPresets => &["claude", "codex", "gemini"],
+            Self::McpCatalog => &["servers"],
+            Self::UsageTemplates => &["templates"],
+        }
+    }
+}
+
+/// 注册表数据来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistrySource {
+    Embedded,
+    Cache,
+}
+
+/// 当前生效的注册表
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Registry {
+    pub kind: RegistryKind,
+    pub source: RegistrySource,
+    pub version: u64,
+    /// 缓存文件的更新时间（毫秒）；内置版本为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    pub data: Value,
+}
+
+/// 单个注册表的刷新结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryRefresh {
+    pub kind: RegistryKind,
+    /// 缓存内容发生了变化
+    pub updated: bool,
+    /// 刷新后生效的版本
+    pub version: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 刷新结果；`offline` 表示处于离线模式或全部注册表都无法拉取
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryRefreshReport {
+    pub offline: bool,
+    pub results: Vec<RegistryRefresh>,
+}
+
+pub struct RegistryService;
+
+impl RegistryService {
+    /// 读取注册表；缓存缺失、损坏或版本低于内置版本时使用内置版本，不会失败
+    pub fn get(kind: RegistryKind) -> Registry {
+        let (version, data) =
+            Self::parse(kind, kind.embedded()).expect("内置注册表必须是有效的 JSON 文档");
+        let embedded = Registry {
+            kind,
+            source: RegistrySource::Embedded,
+            version,
+            updated_at: None,
+            data,
+        };
+
+        let path = Self::cache_path(kind);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return embedded;
+        };
+        match Self::parse(kind, &content) {
+            Ok((version, data)) if version >= embedded.version => Registry {
+                kind,
+                source: RegistrySource::Cache,
+                version,
+                updated_at: std::fs::metadata(&path)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+                    .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp_millis()),
+                data,
+            },
+            Ok(_) => embedded,
+            Err(e) => {
+                log::warn!("注册表缓存无效，使用内置版本: {e}");
+                embedded
+            }
+        }
+    }
+
+    /// 从注册表地址拉取全部注册表并更新缓存；离线模式下不发起请求
+    pub async fn refresh_all() -> Result<RegistryRefreshReport, AppError> {
+        let settings = settings::get_settings();
+        if settings.offline_mode {
+            return Ok(RegistryRefreshReport {
+                offline: true,
+                results: RegistryKind::ALL
+                    .iter()
+                    .map(|&kind| RegistryRefresh {
+                        kind,
+                        updated: false,
+                        version: Self::get(kind).version,
+                        error: Some("离线模式已开启".to_string()),
+                    })
+                    .collect(),
+            });
+        }
+
+        let base = settings
+            .registry_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .unwrap_or(DEFAULT_REGISTRY_URL)
+            .trim_end_matches('/')
+            .to_string();
+        let client = build_client()?;
+        let fetches = RegistryKind::ALL.iter().map(|&kind| {
+            let url = format!("{base}/{}", kind.file_name());
+            let client = &client;
+            async move { (kind, fetch_registry(client, &url).await) }
+        });
+
+        let mut fetched_any = false;
+        let mut results = Vec::with_capacity(RegistryKind::ALL.len());
+        for (kind, text) in join_all(fetches).await {
+            fetched_any |= text.is_ok();
+            let result = text.and_then(|text| Self::store(kind, &text));
+            results.push(match result {
+                Ok(updated) => RegistryRefresh {
+                    kind,
+                    updated,
+                    version: Self::get(kind).version,
+                    error: None,
+                },
+                Err(e) => {
+                    log::warn!("刷新注册表 {} 失败: {e}", kind.file_name());
+                    RegistryRefresh {
+                        kind,
+                        updated: false,
+                        version: Self::get(kind).version,
+                        error: Some(e.to_string()),
+                    }
+                }
+            });
+        }
+
+        Ok(RegistryRefreshReport {
+            offline: !fetched_any,
+            results,
+        })
+    }
+
+    /// 校验并写入缓存；版本低于当前生效版本时忽略。返回缓存内容是否变化
+    fn store(kind: RegistryKind, text: &str) -> Result<bool, AppError> {
+        let (version, data) = Self::parse(kind, text)?;
+        let current = Self::get(kind);
+        if version < current.version || data == current.data {
+            return Ok(false);
+        }
+        write_json_file(&Self::cache_path(kind), &data)?;
+        Ok(true)
+    }
+
+    /// 解析注册表文档，返回 `version` 与完整文档
+    fn parse(kind: RegistryKind, text: &str) -> Result<(u64, Value), AppError> {
+        let invalid = |detail: String| {
+            AppError::localized(
+                "registry.invalid",
+                format!("注册表 {} 无效: {detail}", kind.file_name()),
+                format!("Registry {} is invalid: {detail}", kind.file_name()),
+            )
+        };
+        let data: Value = serde_json::from_str(text).map_err(|e| invalid(e.to_string()))?;
+        let version = data
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| invalid("缺少 version".to_string()))?;
+        if let Some(missing) = kind
+            .required_lists()
+            .iter()
+            .find(|key| !data.get(**key).is_some_and(Value::is_array))
+        {
+            return Err(invalid(format!("缺少 {missing} 列表")));
+        }
+        Ok((version, data))
+    }
+
+    fn cache_path(kind: RegistryKind) -> PathBuf {
+        get_app_config_dir()
+            .join("registries")
+            .join(kind.file_name())
+    }
+}
+
+async fn fetch_registry(client: &Client, url: &str) -> Result<String, AppError> {
+    let failed = |detail: String| {
+        AppError::localized(
+            "registry.fetch_failed",
+            format!("拉取注册表失败: {detail}"),
+            format!("Failed to fetch registry: {detail}"),
+        )
+    };
+    if !url.starts_with("https://") {
+        return Err(failed(format!("仅支持 https 地址: {url}")));
+    }
+    let mut resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| failed(e.to_string()))?;
+    if !resp.status().is_success() {
+        return Err(failed(format!("HTTP {}", resp.status())));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(|e| failed(e.to_string()))? {
+        if body.len() + chunk.len() > MAX_REGISTRY_BYTES {
+            return Err(failed(format!("超过 {MAX_REGISTRY_BYTES} 字节")));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    String::from_utf8(body).map_err(|e| failed(e.to_string()))
+}
+
+fn build_client() -> Result<Client, AppError> {
+    Client::builder()
+        .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .user_agent("cc-switch-registry/1.0")
+        .build()
+        .map_err(|e| {
+            AppError::localized(
+                "registry.client_create_failed",
+                format!("创建 HTTP 客户端失败: {e}"),
+                format!("Failed to create HTTP client: {e}"),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_registries_are_valid() {
+        for kind in RegistryKind::ALL {
+            let (version, data) = RegistryService::parse(kind, kind.embedded()).unwrap();
+            assert!(version >= 1);
+            for key in kind.required_lists() {
+                assert!(!data[*key].as_array().unwrap().is_empty(), "{key}");
+            }
+        }
+
+        let err = RegistryService::parse(RegistryKind::McpCatalog, r#"{"version":2}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("servers"));
+        assert!(RegistryService::parse(RegistryKind::UsageTemplates, "{").is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::config::{get_app_config_dir, write_text_file};
+use crate::error::AppError;
+use crate::placeholder;
+use crate::provider::Provider;
+use crate::remote_host::{self, quote, RemoteHost};
+use crate::services::shell_wrapper::restrict_permissions;
+use crate::services::ProviderService;
+use crate::settings;
+use crate::store::AppState;
+
+/// 远程切换结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSwitchResult {
+    pub host_id: String,
+    pub provider_id: String,
+    /// 已上传的远程文件（相对远程主目录）
+    pub uploaded: Vec<String>,
+    /// 远程原文件的本地备份目录（远程不存在的文件不备份）
+    pub backup_dir: PathBuf,
+}
+
+/// 远程主机：在本机生成 live 配置后经 SFTP 上传，使远程开发机上的 CLI 跟随本机选择
+pub struct RemoteHostService;
+
+impl RemoteHostService {
+    pub fn list() -> Vec<RemoteHost> {
+        settings::get_settings().remote_hosts
+    }
+
+    /// 新增或更新远程主机（`id` 为空时新增）
+    pub fn save(mut host: RemoteHost) -> Result<RemoteHost, AppError> {
+        host.validate()?;
+        host.host = host.host.trim().to_string();
+        host.name = host.name.trim().to_string();
+
+        let mut current = settings::get_settings();
+        if host.id.trim().is_empty() {
+            host.id = format!("host-{}", chrono::Utc::now().timestamp_millis());
+            current.remote_hosts.push(host.clone());
+        } else if let Some(existing) = current.remote_hosts.iter_mut().find(|h| h.id == host.id) {
+            *existing = host.clone();
+        } else {
+            return Err(Self::not_found(&host.id));
+        }
+        settings::update_settings(current)?;
+        Ok(host)
+    }
+
+    pub fn remove(id: &str) -> Result<bool, AppError> {
+        let mut current = settings::get_settings();
+        let before = current.remote_hosts.len();
+        current.remote_hosts.retain(|host| host.id != id);
+        if current.remote_hosts.len() == before {
+            return Ok(false);
+        }
+        settings::update_settings(current)?;
+        Ok(true)
+    }
+
+    /// 测试连接，返回远程主目录
+    pub fn test(id: &str) -> Result<String, AppError> {
+        let output = remote_host::sftp(&Self::get(id)?, "pwd\n")?;
+        Ok(output
+            .lines()
+            .find_map(|line| line.strip_prefix("Remote working directory:"))
+            .map(str::trim)
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// 取出供应商并展开占位符，供 [`Self::switch_provider`] 使用
+    pub fn expanded_provider(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Result<Provider, AppError> {
+        let mut provider = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .get_manager(app_type)
+                .and_then(|manager| manager.providers.get(provider_id))
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?
+        };
+        provider.settings_config = placeholder::expand_value(&provider.settings_config)?;
+        Ok(provider)
+    }
+
+    /// 将供应商写入远程主机：先下载远程原文件作为备份，再上传本机生成的 live 文件；
+    /// 上传失败时尝试用备份恢复远程文件（不改动本机的当前供应商）
+    pub fn switch_provider(
+        host_id: &str,
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<RemoteSwitchResult, AppError> {
+        let host = Self::get(host_id)?;
+        let remote_paths = ProviderService::live_file_paths(app_type);
+        if remote_paths.is_empty() {
+            // 不支持写到本机以外的应用直接返回渲染错误，避免无谓的连接
+            ProviderService::render_live_files(app_type, provider, None)?;
+        }
+        let backup_dir = get_app_config_dir()
+            .join("remote-backups")
+            .join(&host.id)
+            .join(chrono::Local::now().format("%Y%m%d_%H%M%S").to_string());
+        for relative in remote_paths {
+            if let Some(parent) = local_path(&backup_dir, relative).parent() {
+                std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+            }
+        }
+        remote_host::sftp(&host, &download_batch(remote_paths, &backup_dir))?;
+        // 备份中含 API Key 与 OAuth 凭据，仅允许当前用户读写
+        for relative in remote_paths {
+            let backup = local_path(&backup_dir, relative);
+            if backup.exists() {
+                restrict_permissions(&backup, 0o600);
+            }
+        }
+
+        let existing =
+            std::fs::read_to_string(local_path(&backup_dir, ".claude/settings.json")).ok();
+        let files = ProviderService::render_live_files(app_type, provider, existing.as_deref())?;
+
+        let staging = tempfile::Builder::new()
+            .prefix("cc-switch-remote-")
+            .tempdir()
+            .map_err(|e| AppError::Message(format!("创建临时目录失败: {e}")))?;
+        let mut uploads = Vec::with_capacity(files.len());
+        for (relative, content) in &files {
+            let local = local_path(staging.path(), relative);
+            write_text_file(&local, content)?;
+            restrict_permissions(&local, 0o600);
+            uploads.push((local, *relative));
+        }
+
+        if let Err(err) = remote_host::sftp(&host, &upload_batch(&uploads)) {
+            // 只恢复有备份的文件；原本不存在的文件保持上传后的状态
+            let restores: Vec<(PathBuf, &str)> = remote_paths
+                .iter()
+                .map(|relative| (local_path(&backup_dir, relative), *relative))
+                .filter(|(local, _)| local.exists())
+                .collect();
+            if !restores.is_empty() {
+                if let Err(restore_err) = remote_host::sftp(&host, &upload_batch(&restores)) {
+                    log::warn!("恢复远程主机 {} 的配置失败: {restore_err}", host.name);
+                }
+            }
+            return Err(err);
+        }
+
+        log::info!(
+            "已将供应商 {} 写入远程主机 {}（{}）",
+            provider.id,
+            host.name,
+            app_type.as_str()
+        );
+        Ok(RemoteSwitchResult {
+            host_id: host.id,
+            provider_id: provider.id.clone(),
+            uploaded: files.iter().map(|(path, _)| path.to_string()).collect(),
+            backup_dir,
+        })
+    }
+
+    fn get(id: &str) -> Result<RemoteHost, AppError> {
+        Self::list()
+            .into_iter()
+            .find(|host| host.id == id)
+            .ok_or_else(|| Self::not_found(id))
+    }
+
+    fn not_found(id: &str) -> AppError {
+        AppError::localized(
+            "remote.host_not_found",
+            format!("远程主机不存在: {id}"),
+            format!("Remote host not found: {id}"),
+        )
+    }
+}
+
+fn local_path(base: &Path, relative: &str) -> PathBuf {
+    relative
+        .split('/')
+        .fold(base.to_path_buf(), |path, segment| path.join(segment))
+}
+
+/// 下载远程原文件（不存在时忽略）
+fn download_batch(remote_paths: &[&str], backup_dir: &Path) -> String {
+    remote_paths
+        .iter()
+        .map(|relative| {
+            let local = local_path(backup_dir, relative);
+            format!(
+                "-get {} {}\n",
+                quote(relative),
+                quote(&local.to_string_lossy())
+            )
+        })
+        .collect()
+}
+
+/// 上传文件，必要时先创建远程目录；`put` 会沿用本地文件权限，上传后统一设为 600
+fn upload_batch(files: &[(PathBuf, &str)]) -> String {
+    let mut dirs: Vec<&str> = files
+        .iter()
+        .filter_map(|(_, relative)| relative.rsplit_once('/').map(|(dir, _)| dir))
+        .collect();
+    dirs.dedup();
+    let mut batch: String = dirs
+        .iter()
+        .map(|dir| format!("-mkdir {}\n", quote(dir)))
+        .collect();
+    for (local, relative) in files {
+        batch.push_str(&format!(
+            "put {} {}\nchmod 600 {}\n",
+            quote(&local.to_string_lossy()),
+            quote(relative),
+            quote(relative)
+        ));
+    }
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_sftp_batches() {
+        let backup = Path::new("/tmp/backup");
+        let local = local_path(backup, ".codex/auth.json");
+        assert!(local.ends_with("auth.json"));
+        assert_eq!(
+            download_batch(&[".codex/auth.json"], backup),
+            format!(
+                "-get \".codex/auth.json\" {}\n",
+                quote(&local.to_string_lossy())
+            )
+        );
+
+        let uploads = vec![
+            (PathBuf::from("a.json"), ".codex/auth.json"),
+            (PathBuf::from("c.toml"), ".codex/config.toml"),
+        ];
+        assert_eq!(
+            upload_batch(&uploads),
+            "-mkdir \".codex\"\n\
+             put \"a.json\" \".codex/auth.json\"\nchmod 600 \".codex/auth.json\"\n\
+             put \"c.toml\" \".codex/config.toml\"\nchmod 600 \".codex/config.toml\"\n"
+        );
+    }
+}
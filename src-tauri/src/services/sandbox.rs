@@ -0,0 +1,339 @@
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::app_config::AppType;
+use crate::config::{write_json_file, write_text_file};
+use crate::error::AppError;
+use crate::placeholder;
+use crate::provider::Provider;
+use crate::services::shell_wrapper::{env_pairs, restrict_permissions};
+use crate::services::ProviderService;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const MAX_TIMEOUT_SECS: u64 = 300;
+/// 结果中保留的输出长度（字符）
+const MAX_OUTPUT_CHARS: usize = 2000;
+/// 从父进程继承时移除的环境变量前缀，避免终端里的密钥干扰验证结果
+const STRIPPED_ENV_PREFIXES: [&str; 4] = ["ANTHROPIC_", "OPENAI_", "GEMINI_", "GOOGLE_GEMINI_"];
+
+/// 沙箱验证结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxResult {
+    /// CLI 正常退出且有输出
+    pub success: bool,
+    /// 实际执行的命令（不含密钥）
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub timed_out: bool,
+    /// 未找到 CLI 可执行文件
+    pub cli_missing: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 在临时目录中验证供应商配置：写入独立的配置目录，通过环境变量
+/// （`CLAUDE_CONFIG_DIR`、`CODEX_HOME`）让 CLI 使用它，执行一次非交互调用。
+/// 不修改 live 配置，可在设为当前供应商之前确认其可用
+pub struct SandboxService;
+
+impl SandboxService {
+    /// `provider` 为未展开占位符的供应商配置
+    pub fn validate(
+        app_type: &AppType,
+        provider: &Provider,
+        timeout_secs: Option<u64>,
+    ) -> Result<SandboxResult, AppError> {
+        let mut provider = provider.clone();
+        provider.settings_config = placeholder::expand_value(&provider.settings_config)?;
+
+        let dir = tempfile::Builder::new()
+            .prefix("cc-switch-sandbox-")
+            .tempdir()
+            .map_err(|e| AppError::Message(format!("创建临时目录失败: {e}")))?;
+        let (env, args) = Self::prepare(app_type, &provider, dir.path())?;
+        let program = match app_type {
+            AppType::Claude => "claude",
+            AppType::Codex => "codex",
+            AppType::Gemini => "gemini",
+        };
+        let timeout = Duration::from_secs(
+            timeout_secs
+                .unwrap_or(DEFAULT_TIMEOUT_SECS)
+                .clamp(1, MAX_TIMEOUT_SECS),
+        );
+        run(program, &args, &env, dir.path(), timeout)
+    }
+
+    /// 写入临时配置目录，返回需要设置的环境变量与 CLI 参数
+    #[allow(clippy::type_complexity)]
+    fn prepare(
+        app_type: &AppType,
+        provider: &Provider,
+        dir: &Path,
+    ) -> Result<(Vec<(String, String)>, Vec<String>), AppError> {
+        let dir_env = dir.to_string_lossy().to_string();
+        match app_type {
+            AppType::Claude => {
+                let settings_path = dir.join("settings.json");
+                write_json_file(
+                    &settings_path,
+                    &ProviderService::claude_live_settings(provider),
+                )?;
+                restrict_permissions(&settings_path, 0o600);
+                if let Some(snapshot) = ProviderService::oauth_snapshot(provider) {
+                    let credentials_path = dir.join(".credentials.json");
+                    write_json_file(&credentials_path, snapshot)?;
+                    restrict_permissions(&credentials_path, 0o600);
+                }
+                Ok((
+                    vec![("CLAUDE_CONFIG_DIR".to_string(), dir_env)],
+                    vec![
+                        "-p".to_string(),
+                        "ping".to_string(),
+                        "--max-turns".to_string(),
+                        "1".to_string(),
+                    ],
+                ))
+            }
+            AppType::Codex => {
+                let auth_path = dir.join("auth.json");
+                write_json_file(&auth_path, &ProviderService::codex_live_auth(provider))?;
+                restrict_permissions(&auth_path, 0o600);
+                let config_text = ProviderService::codex_live_config_text(provider)?;
+                write_text_file(
+                    &dir.join("config.toml"),
+                    config_text.as_deref().unwrap_or(""),
+                )?;
+
+                let mut env = vec![("CODEX_HOME".to_string(), dir_env)];
+                if let Some(meta) = provider.meta.as_ref() {
+                    let mut extra: Vec<_> = meta
+                        .extra_env
+                        .iter()
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect();
+                    extra.sort();
+                    env.extend(extra);
+                }
+                Ok((
+                    env,
+                    vec![
+                        "exec".to_string(),
+                        "--skip-git-repo-check".to_string(),
+                        "ping".to_string(),
+                    ],
+                ))
+            }
+            AppType::Gemini => {
+                // Gemini CLI 没有配置目录变量：在临时工作目录写入 .gemini/.env，并通过进程环境变量传入
+                let env_value = provider
+                    .settings_config
+                    .get("env")
+                    .cloned()
+                    .unwrap_or_else(|| json!({}));
+                let env = env_pairs(&env_value);
+                let env_path = dir.join(".gemini").join(".env");
+                let content = env
+                    .iter()
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                write_text_file(&env_path, &content)?;
+                restrict_permissions(&env_path, 0o600);
+                Ok((env, vec!["-p".to_string(), "ping".to_string()]))
+            }
+        }
+    }
+}
+
+fn run(
+    program: &str,
+    args: &[String],
+    env: &[(String, String)],
+    cwd: &Path,
+    timeout: Duration,
+) -> Result<SandboxResult, AppError> {
+    let display = std::iter::once(program.to_string())
+        .chain(args.iter().map(|arg| {
+            if arg.contains(' ') {
+                format!("\"{arg}\"")
+            } else {
+                arg.clone()
+            }
+        }))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // Windows 上 npm 安装的 CLI 为 .cmd 脚本，需经 cmd 启动
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(program);
+        command
+    } else {
+        Command::new(program)
+    };
+    command
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for (key, _) in std::env::vars_os() {
+        let inherited = key.to_str().is_some_and(|key| {
+            STRIPPED_ENV_PREFIXES
+                .iter()
+                .any(|prefix| key.starts_with(prefix))
+        });
+        if inherited {
+            command.env_remove(&key);
+        }
+    }
+    command.envs(env.iter().map(|(key, value)| (key, value)));
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let started = Instant::now();
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(SandboxResult {
+                success: false,
+                command: display,
+                exit_code: None,
+                duration_ms: 0,
+                timed_out: false,
+                cli_missing: true,
+                stdout: String::new(),
+                stderr: format!("未找到 {program}，请先安装对应 CLI"),
+            });
+        }
+        Err(e) => return Err(AppError::Message(format!("执行 {program} 失败: {e}"))),
+    };
+
+    // 在线程中读取输出，避免管道写满导致子进程阻塞
+    let read_pipe = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            String::from_utf8_lossy(&buf).to_string()
+        })
+    };
+    let stdout = read_pipe(
+        child
+            .stdout
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    );
+    let stderr = read_pipe(
+        child
+            .stderr
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    );
+
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if started.elapsed() < timeout => {
+                std::thread::sleep(Duration::from_millis(100))
+            }
+            Ok(None) => {
+                timed_out = true;
+                let _ = child.kill();
+                break child.wait().ok();
+            }
+            Err(e) => return Err(AppError::Message(format!("等待 {program} 退出失败: {e}"))),
+        }
+    };
+    let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    let stdout = truncate(stdout.join().unwrap_or_default());
+    let stderr = truncate(stderr.join().unwrap_or_default());
+    let exit_code = status.and_then(|status| status.code());
+
+    Ok(SandboxResult {
+        success: !timed_out && exit_code == Some(0) && !stdout.trim().is_empty(),
+        command: display,
+        exit_code,
+        duration_ms,
+        timed_out,
+        cli_missing: false,
+        stdout,
+        stderr,
+    })
+}
+
+fn truncate(text: String) -> String {
+    let text = text.trim();
+    if text.chars().count() <= MAX_OUTPUT_CHARS {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(MAX_OUTPUT_CHARS).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepare_writes_isolated_config_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let codex = Provider::with_id(
+            "x".into(),
+            "Codex".into(),
+            json!({
+                "auth": { "OPENAI_API_KEY": "sk-test" },
+                "config": "model = \"gpt-5\"\n"
+            }),
+            None,
+        );
+        let (env, args) = SandboxService::prepare(&AppType::Codex, &codex, dir.path()).unwrap();
+        assert_eq!(env[0].0, "CODEX_HOME");
+        assert_eq!(args.first().map(String::as_str), Some("exec"));
+        let auth: Value = crate::config::read_json_file(&dir.path().join("auth.json")).unwrap();
+        assert_eq!(auth["OPENAI_API_KEY"], "sk-test");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("config.toml")).unwrap(),
+            "model = \"gpt-5\"\n"
+        );
+
+        let gemini = Provider::with_id(
+            "g".into(),
+            "Gemini".into(),
+            json!({ "env": { "GEMINI_API_KEY": "g-key" } }),
+            None,
+        );
+        let (env, _) = SandboxService::prepare(&AppType::Gemini, &gemini, dir.path()).unwrap();
+        assert_eq!(env, vec![("GEMINI_API_KEY".into(), "g-key".into())]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(".gemini").join(".env")).unwrap(),
+            "GEMINI_API_KEY=g-key"
+        );
+
+        let missing = run(
+            "cc-switch-missing-cli",
+            &[],
+            &[],
+            dir.path(),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        assert!(missing.cli_missing && !missing.success);
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::HashSet;
+
+use rusqlite::{params, params_from_iter, Connection};
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::database::{self, db_error};
+use crate::error::AppError;
+use crate::services::Skill;
+use crate::store::AppState;
+
+/// 片段截取时命中位置前后保留的字符数
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// 搜索结果类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchHitKind {
+    Provider,
+    Prompt,
+    McpServer,
+    Skill,
+}
+
+/// 全局搜索命中项
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    /// 所属应用（供应商与提示词按应用区分；MCP 与技能为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<AppType>,
+    pub id: String,
+    pub title: String,
+    /// 得分最高的命中字段
+    pub field: &'static str,
+    /// 命中位置附近的文本片段
+    pub snippet: String,
+    pub score: u32,
+}
+
+/// 待索引的条目：类型、应用、ID、标题及可搜索字段（字段名、内容、权重）
+struct Document {
+    kind: SearchHitKind,
+    app: Option<AppType>,
+    id: String,
+    title: String,
+    fields: Vec<(&'static str, String, u32)>,
+}
+
+/// 全局搜索（命令面板）：覆盖供应商备注 / 标签、提示词内容、MCP 描述 / 标签与技能元数据
+///
+/// 关键字按空白拆分，各关键字均需命中（不区分大小写）；候选条目由本地数据库中的
+/// FTS5 trigram 索引（`search_index`）做子串匹配筛出，再按字段权重打分、降序排列
+pub struct SearchService;
+
+impl SearchService {
+    pub fn global_search(
+        state: &AppState,
+        skills: &[Skill],
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>, AppError> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut documents = Self::collect_documents(state)?;
+        documents.extend(skills.iter().map(|skill| Document {
+            kind: SearchHitKind::Skill,
+            app: None,
+            id: skill.key.clone(),
+            title: skill.name.clone(),
+            fields: vec![
+                ("name", skill.name.clone(), 100),
+                ("description", skill.description.clone(), 40),
+                ("directory", skill.directory.clone(), 30),
+            ],
+        }));
+
+        let matched = database::with_connection(|conn| match_documents(conn, &documents, &terms))?;
+        let mut hits: Vec<SearchHit> = documents
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| matched.contains(index))
+            .filter_map(|(_, document)| Self::score(document, &terms))
+            .collect();
+        hits.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.title.cmp(&b.title)));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    fn collect_documents(state: &AppState) -> Result<Vec<Document>, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let mut documents = Vec::new();
+
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            if let Some(manager) = config.get_manager(&app) {
+                for provider in manager.providers.values() {
+                    let mut fields = vec![("name", provider.name.clone(), 100)];
+                    if let Some(meta) = provider.meta.as_ref() {
+                        fields.extend(meta.tags.iter().map(|tag| ("tags", tag.clone(), 60)));
+                    }
+                    if let Some(notes) = provider.notes.as_ref() {
+                        fields.push(("notes", notes.clone(), 40));
+                    }
+                    documents.push(Document {
+                        kind: SearchHitKind::Provider,
+                        app: Some(app.clone()),
+                        id: provider.id.clone(),
+                        title: provider.name.clone(),
+                        fields,
+                    });
+                }
+            }
+
+            let prompts = match app {
+                AppType::Claude => &config.prompts.claude,
+                AppType::Codex => &config.prompts.codex,
+                AppType::Gemini => &config.prompts.gemini,
+            };
+            for prompt in prompts.prompts.values() {
+                let mut fields = vec![("name", prompt.name.clone(), 100)];
+                if let Some(description) = prompt.description.as_ref() {
+                    fields.push(("description", description.clone(), 50));
+                }
+                fields.push(("content", prompt.content.clone(), 20));
+                documents.push(Document {
+                    kind: SearchHitKind::Prompt,
+                    app: Some(app.clone()),
+                    id: prompt.id.clone(),
+                    title: prompt.name.clone(),
+                    fields,
+                });
+            }
+        }
+
+        for server in config
+            .mcp
+            .servers
+            .iter()
+            .flat_map(|servers| servers.values())
+        {
+            let mut fields = vec![("name", server.name.clone(), 100)];
+            fields.extend(server.tags.iter().map(|tag| ("tags", tag.clone(), 60)));
+            if let Some(description) = server.description.as_ref() {
+                fields.push(("description", description.clone(), 40));
+            }
+            documents.push(Document {
+                kind: SearchHitKind::McpServer,
+                app: None,
+                id: server.id.clone(),
+                title: server.name.clone(),
+                fields,
+            });
+        }
+
+        Ok(documents)
+    }
+
+    /// 每个关键字取命中字段中的最高权重累加；任一关键字未命中则不计入结果
+    fn score(document: Document, terms: &[String]) -> Option<SearchHit> {
+        let lowered: Vec<String> = document
+            .fields
+            .iter()
+            .map(|(_, text, _)| text.to_lowercase())
+            .collect();
+
+        let mut score = 0;
+        let mut best: Option<(usize, usize)> = None;
+        for term in terms {
+            let (index, position) = lowered
+                .iter()
+                .enumerate()
+                .filter_map(|(index, text)| text.find(term.as_str()).map(|pos| (index, pos)))
+                .max_by_key(|(index, _)| document.fields[*index].2)?;
+            let weight = document.fields[index].2;
+            score += weight;
+            if best.is_none_or(|(best_index, _)| document.fields[best_index].2 < weight) {
+                best = Some((index, position));
+            }
+        }
+
+        let (index, position) = best?;
+        let (field, text, _) = &document.fields[index];
+        Some(SearchHit {
+            kind: document.kind,
+            app: document.app,
+            id: document.id,
+            title: document.title,
+            field,
+            snippet: snippet(&lowered[index], text, position),
+            score,
+        })
+    }
+}
+
+/// 全局搜索索引：临时表（仅存在于当前连接），每次搜索按最新配置重建
+pub(crate) fn init_schema(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS temp.search_index
+            USING fts5(doc UNINDEXED, body, tokenize = 'trigram');",
+    )
+    .map_err(db_error)
+}
+
+/// 重建索引并返回命中全部关键字的条目序号
+///
+/// 每个字段一行、内容统一小写；trigram 索引使 `LIKE '%词%'` 走索引（少于 3 个字符的词退化为全表扫描）
+fn match_documents(
+    conn: &mut Connection,
+    documents: &[Document],
+    terms: &[String],
+) -> Result<HashSet<usize>, AppError> {
+    let tx = conn.transaction().map_err(db_error)?;
+    tx.execute("DELETE FROM temp.search_index", [])
+        .map_err(db_error)?;
+    {
+        let mut insert = tx
+            .prepare("INSERT INTO temp.search_index (doc, body) VALUES (?1, ?2)")
+            .map_err(db_error)?;
+        for (index, document) in documents.iter().enumerate() {
+            for (_, text, _) in &document.fields {
+                insert
+                    .execute(params![index as i64, text.to_lowercase()])
+                    .map_err(db_error)?;
+            }
+        }
+    }
+
+    let sql = vec!["SELECT doc FROM temp.search_index WHERE body LIKE ? ESCAPE '\\'"; terms.len()]
+        .join(" INTERSECT ");
+    let patterns = terms.iter().map(|term| format!("%{}%", escape_like(term)));
+    let matched = {
+        let mut stmt = tx.prepare(&sql).map_err(db_error)?;
+        let rows = stmt
+            .query_map(params_from_iter(patterns), |row| row.get::<_, i64>(0))
+            .map_err(db_error)?;
+        rows.map(|row| row.map(|doc| doc as usize))
+            .collect::<Result<HashSet<_>, _>>()
+            .map_err(db_error)?
+    };
+    tx.commit().map_err(db_error)?;
+    Ok(matched)
+}
+
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// 截取命中位置附近的片段（按字符计算，避免截断多字节字符）
+fn snippet(lowered: &str, original: &str, byte_position: usize) -> String {
+    // 小写化可能改变字节长度，按字符序号映射回原文
+    let char_position = lowered[..byte_position].chars().count();
+    let chars: Vec<char> = original.chars().collect();
+    let start = char_position.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (char_position + SNIPPET_CONTEXT_CHARS * 2).min(chars.len());
+    let mut text: String = chars[start.min(end)..end].iter().collect();
+    text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        text.insert(0, '…');
+    }
+    if end < chars.len() {
+        text.push('…');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(fields: Vec<(&'static str, &str, u32)>) -> Document {
+        Document {
+            kind: SearchHitKind::Prompt,
+            app: Some(AppType::Claude),
+            id: "p1".into(),
+            title: "Reviewer".into(),
+            fields: fields
+                .into_iter()
+                .map(|(name, text, weight)| (name, text.to_string(), weight))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn all_terms_must_match_and_best_field_wins() {
+        let terms = vec!["review".to_string(), "rust".to_string()];
+        let hit = SearchService::score(
+            document(vec![
+                ("name", "Code Reviewer", 100),
+                ("content", "Always review Rust code for unsafe blocks", 20),
+            ]),
+            &terms,
+        )
+        .expect("both terms match");
+        assert_eq!(hit.field, "name");
+        assert_eq!(hit.score, 120);
+
+        let missing = vec!["review".to_string(), "python".to_string()];
+        assert!(
+            SearchService::score(document(vec![("name", "Code Reviewer", 100)]), &missing)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn fts_index_requires_every_term_in_one_document() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        let documents = vec![
+            document(vec![
+                ("name", "Code Reviewer", 100),
+                ("content", "Always review Rust code", 20),
+            ]),
+            document(vec![("name", "Rust Helper", 100)]),
+            document(vec![("notes", "100% 兼容 OpenAI", 40)]),
+        ];
+        let terms = |query: &str| -> Vec<String> {
+            query.split_whitespace().map(str::to_lowercase).collect()
+        };
+
+        let matched = match_documents(&mut conn, &documents, &terms("REVIEW rust")).unwrap();
+        assert_eq!(matched, HashSet::from([0]));
+        let matched = match_documents(&mut conn, &documents, &terms("ru")).unwrap();
+        assert_eq!(matched, HashSet::from([0, 1]));
+        let matched = match_documents(&mut conn, &documents, &terms("0% 兼容")).unwrap();
+        assert_eq!(matched, HashSet::from([2]));
+        assert!(match_documents(&mut conn, &documents, &terms("c_de"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn snippet_trims_long_text_around_match() {
+        let text = format!("{}needle{}", "a ".repeat(60), " b".repeat(60));
+        let lowered = text.to_lowercase();
+        let position = lowered.find("needle").unwrap();
+        let result = snippet(&lowered, &text, position);
+        assert!(result.starts_with('…') && result.ends_with('…'));
+        assert!(result.contains("needle"));
+    }
+}
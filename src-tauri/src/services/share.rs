@@ -0,0 +1,363 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::Argon2;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::config::fetch_text_capped;
+use crate::services::{PasscodeService, ProviderService};
+use crate::settings;
+use crate::store::AppState;
+
+/// 离线分享码前缀（其后为 base64url 编码的密文）
+const BLOB_PREFIX: &str = "ccsw1.";
+
+/// 密文格式版本（1 为早期的 AES-CTR + HMAC 格式，已不再支持）
+const BLOB_VERSION: u8 = 2;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = 1 + SALT_LEN + NONCE_LEN;
+
+/// 默认有效期（24 小时）
+pub const DEFAULT_SHARE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// 最长有效期（30 天）
+const MAX_SHARE_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// 上传 / 下载分享内容的超时时间
+const SHARE_HTTP_TIMEOUT_SECS: u64 = 15;
+
+/// 下载分享内容的大小上限（单个供应商的密文远小于此值）
+const MAX_SHARE_BYTES: usize = 256 * 1024;
+
+/// 分享结果：分享码与口令需分别发送给对方
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedSnippet {
+    /// 分享码：上传成功时为粘贴服务返回的 key，否则为离线密文（`ccsw1.` 开头）
+    pub code: String,
+    pub passphrase: String,
+    /// 过期时间（毫秒时间戳）
+    pub expires_at: i64,
+    /// 是否已上传到设置中的粘贴服务
+    pub uploaded: bool,
+}
+
+/// 加密前的分享内容
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnippetPayload {
+    app_type: AppType,
+    provider: Provider,
+    expires_at: i64,
+}
+
+/// 供应商配置分享
+///
+/// 配置在本地以口令加密（Argon2id 派生密钥，AES-256-GCM），
+/// 粘贴服务只接触密文。粘贴服务约定：`POST <endpoint>` 上传密文（`X-Expires-In` 为秒数），
+/// 响应为 JSON（`key` / `id` 字段）或纯文本 key；`GET <endpoint>/<key>` 返回密文
+pub struct ShareService;
+
+impl ShareService {
+    /// 加密供应商配置；设置了 `share_endpoint` 时上传并返回短码，否则返回离线密文
+    ///
+    /// 分享内容包含供应商密钥，设置了应用口令时需提供有效的解锁令牌
+    pub async fn share(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        ttl_secs: Option<u64>,
+        passphrase: Option<String>,
+        unlock_token: Option<&str>,
+    ) -> Result<SharedSnippet, AppError> {
+        PasscodeService::require_unlocked(unlock_token)?;
+        let provider = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .get_manager(&app_type)
+                .ok_or_else(|| ProviderService::app_not_found(&app_type))?
+                .providers
+                .get(provider_id)
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?
+        };
+
+        let ttl_secs = ttl_secs
+            .unwrap_or(DEFAULT_SHARE_TTL_SECS)
+            .clamp(60, MAX_SHARE_TTL_SECS);
+        let expires_at = chrono::Utc::now().timestamp_millis()
+            + i64::try_from(ttl_secs * 1000).unwrap_or(i64::MAX);
+        let passphrase = match passphrase.filter(|p| !p.trim().is_empty()) {
+            Some(passphrase) => passphrase,
+            None => generate_passphrase(),
+        };
+
+        let payload = SnippetPayload {
+            app_type,
+            provider: shareable(provider),
+            expires_at,
+        };
+        let plaintext =
+            serde_json::to_vec(&payload).map_err(|source| AppError::JsonSerialize { source })?;
+        let blob = format!(
+            "{BLOB_PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(encrypt(&plaintext, &passphrase)?)
+        );
+
+        let endpoint = settings::get_settings()
+            .share_endpoint
+            .filter(|url| !url.trim().is_empty());
+        let (code, uploaded) = match endpoint {
+            Some(endpoint) => (upload(&endpoint, &blob, ttl_secs).await?, true),
+            None => (blob, false),
+        };
+
+        Ok(SharedSnippet {
+            code,
+            passphrase,
+            expires_at,
+            uploaded,
+        })
+    }
+
+    /// 解密分享码并作为新供应商导入，返回新供应商 ID
+    pub async fn import(
+        state: &AppState,
+        code: &str,
+        passphrase: &str,
+    ) -> Result<String, AppError> {
+        let code = code.trim();
+        let blob = if code.starts_with(BLOB_PREFIX) {
+            code.to_string()
+        } else {
+            let endpoint = settings::get_settings()
+                .share_endpoint
+                .filter(|url| !url.trim().is_empty())
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "share.endpoint_missing",
+                        "未配置分享服务地址，无法获取分享内容",
+                        "No share endpoint configured; cannot fetch the snippet",
+                    )
+                })?;
+            download(&endpoint, code).await?
+        };
+
+        let payload = decode_snippet(&blob, passphrase)?;
+        if payload.expires_at < chrono::Utc::now().timestamp_millis() {
+            return Err(AppError::localized(
+                "share.expired",
+                "分享内容已过期",
+                "The shared snippet has expired",
+            ));
+        }
+
+        let mut provider = payload.provider;
+        provider.id = format!("{}-{}", provider.id, chrono::Utc::now().timestamp_millis());
+        provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+        provider.sort_index = None;
+        let provider_id = provider.id.clone();
+        ProviderService::add(state, payload.app_type, provider)?;
+        Ok(provider_id)
+    }
+}
+
+/// 去掉仅对本机有意义或属于账号登录态的字段
+pub(crate) fn shareable(mut provider: Provider) -> Provider {
+    provider.updated_at = None;
+    if let Some(meta) = provider.meta.as_mut() {
+        meta.live_hash = None;
+        meta.last_used_at = None;
+        meta.rate_limit = None;
+        meta.managed_env_keys.clear();
+        meta.codex_oauth = None;
+        meta.oauth_snapshot = None;
+    }
+    provider
+}
+
+fn generate_passphrase() -> String {
+    let mut bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// 由口令派生加密密钥
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm, AppError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Message(format!("派生分享密钥失败: {e}")))?;
+    Aes256Gcm::new_from_slice(&key).map_err(|e| AppError::Message(format!("创建分享密钥失败: {e}")))
+}
+
+/// 密文布局：版本(1) | salt(16) | nonce(12) | AES-256-GCM 密文与认证标签(16)，头部作为附加认证数据
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+    let cipher = derive_key(passphrase, &salt)?;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + plaintext.len() + TAG_LEN);
+    out.push(BLOB_VERSION);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    let sealed = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &out,
+            },
+        )
+        .map_err(|_| AppError::Message("加密分享内容失败".to_string()))?;
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+fn decrypt(blob: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    if blob.len() < HEADER_LEN + TAG_LEN || blob[0] != BLOB_VERSION {
+        return Err(AppError::localized(
+            "share.invalid_snippet",
+            "分享码无效或已损坏",
+            "Invalid or corrupted share code",
+        ));
+    }
+    let (header, sealed) = blob.split_at(HEADER_LEN);
+    let cipher = derive_key(passphrase, &header[1..1 + SALT_LEN])?;
+    cipher
+        .decrypt(
+            Nonce::from_slice(&header[1 + SALT_LEN..]),
+            Payload {
+                msg: sealed,
+                aad: header,
+            },
+        )
+        .map_err(|_| {
+            AppError::localized(
+                "share.wrong_passphrase",
+                "口令错误或分享内容被篡改",
+                "Wrong passphrase or the snippet was tampered with",
+            )
+        })
+}
+
+fn decode_snippet(blob: &str, passphrase: &str) -> Result<SnippetPayload, AppError> {
+    let encoded = blob.trim().strip_prefix(BLOB_PREFIX).unwrap_or(blob.trim());
+    let bytes = URL_SAFE_NO_PAD.decode(encoded).map_err(|_| {
+        AppError::localized(
+            "share.invalid_snippet",
+            "分享码无效或已损坏",
+            "Invalid or corrupted share code",
+        )
+    })?;
+    let plaintext = decrypt(&bytes, passphrase)?;
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| AppError::Config(format!("分享内容格式错误: {e}")))
+}
+
+fn http_client() -> Result<reqwest::Client, AppError> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(SHARE_HTTP_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| AppError::Message(format!("创建 HTTP 客户端失败: {e}")))
+}
+
+fn share_http_error(e: impl std::fmt::Display) -> AppError {
+    AppError::localized(
+        "share.request_failed",
+        format!("访问分享服务失败: {e}"),
+        format!("Share service request failed: {e}"),
+    )
+}
+
+async fn upload(endpoint: &str, blob: &str, ttl_secs: u64) -> Result<String, AppError> {
+    let resp = http_client()?
+        .post(endpoint.trim_end_matches('/'))
+        .header("Content-Type", "text/plain")
+        .header("X-Expires-In", ttl_secs.to_string())
+        .body(blob.to_string())
+        .send()
+        .await
+        .map_err(share_http_error)?
+        .error_for_status()
+        .map_err(share_http_error)?;
+    let text = resp.text().await.map_err(share_http_error)?;
+
+    let key = match serde_json::from_str::<serde_json::Value>(&text) {
+        Ok(value) => ["key", "id"]
+            .iter()
+            .find_map(|field| value.get(field).and_then(|v| v.as_str()))
+            .map(str::to_string),
+        Err(_) => Some(text.trim().to_string()),
+    };
+    key.filter(|key| !key.is_empty() && !key.contains(char::is_whitespace))
+        .ok_or_else(|| share_http_error("响应中缺少分享 key"))
+}
+
+async fn download(endpoint: &str, key: &str) -> Result<String, AppError> {
+    let url = format!(
+        "{}/{}",
+        endpoint.trim_end_matches('/'),
+        url::form_urlencoded::byte_serialize(key.as_bytes()).collect::<String>()
+    );
+    fetch_text_capped(
+        &url,
+        MAX_SHARE_BYTES,
+        Duration::from_secs(SHARE_HTTP_TIMEOUT_SECS),
+    )
+    .await
+    .map_err(share_http_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn snippet_round_trips_and_rejects_wrong_passphrase() {
+        let provider = Provider::with_id(
+            "relay".into(),
+            "Relay".into(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-secret" } }),
+            None,
+        );
+        let payload = SnippetPayload {
+            app_type: AppType::Claude,
+            provider,
+            expires_at: 42,
+        };
+        let plaintext = serde_json::to_vec(&payload).unwrap();
+        let blob = format!(
+            "{BLOB_PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(encrypt(&plaintext, "correct horse").unwrap())
+        );
+        assert!(!blob.contains("sk-secret"));
+
+        let decoded = decode_snippet(&blob, "correct horse").unwrap();
+        assert_eq!(decoded.provider.id, "relay");
+        assert_eq!(decoded.expires_at, 42);
+
+        match decode_snippet(&blob, "wrong") {
+            Err(AppError::Localized { key, .. }) => assert_eq!(key, "share.wrong_passphrase"),
+            other => panic!("expected wrong passphrase error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::app_config::AppType;
+use crate::config::{write_json_file, write_text_file};
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::ProviderService;
+use crate::settings::TraySort;
+use crate::store::AppState;
+
+/// 单个供应商在包装脚本中的调用参数
+struct WrapperEntry {
+    id: String,
+    name: String,
+    env: Vec<(String, String)>,
+    args: Vec<String>,
+}
+
+/// 生成 `claude-with <供应商>` 等包装脚本：仅为单次调用设置环境变量，
+/// 不修改 live 配置文件，便于在多个终端中并行使用不同供应商
+pub struct ShellWrapperService;
+
+impl ShellWrapperService {
+    /// 在指定目录生成包装脚本，返回生成的脚本路径
+    ///
+    /// 脚本中包含供应商密钥，Unix 下权限设置为仅当前用户可读写
+    pub fn generate(state: &AppState, dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+        let config = state.config.read().map_err(AppError::from)?.clone();
+        fs::create_dir_all(dir).map_err(|e| AppError::io(dir, e))?;
+
+        let mut written = Vec::new();
+        for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            let Some(manager) = config.get_manager(&app_type) else {
+                continue;
+            };
+            if manager.providers.is_empty() {
+                continue;
+            }
+
+            let providers =
+                ProviderService::sort_providers(&app_type, &manager.providers, TraySort::Manual);
+            let mut entries = Vec::with_capacity(providers.len());
+            for (_, provider) in providers {
+                entries.push(Self::build_entry(&app_type, provider, dir)?);
+            }
+
+            let script_path = dir.join(script_file_name(&app_type));
+            write_text_file(&script_path, &render_script(&app_type, &entries)?)?;
+            restrict_permissions(&script_path, 0o700);
+            written.push(script_path);
+        }
+
+        Ok(written)
+    }
+
+    fn build_entry(
+        app_type: &AppType,
+        provider: &Provider,
+        dir: &Path,
+    ) -> Result<WrapperEntry, AppError> {
+        let mut entry = WrapperEntry {
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            env: Vec::new(),
+            args: Vec::new(),
+        };
+
+        match app_type {
+            AppType::Claude => {
+                let settings = ProviderService::claude_live_settings(provider);
+                let env = settings.get("env").cloned().unwrap_or_else(|| json!({}));
+                entry.env = env_pairs(&env);
+
+                // live settings.json 中的 env 优先级高于进程环境变量，
+                // 通过 --settings 传入同样的 env 以覆盖当前 live 配置
+                let settings_path = dir
+                    .join(".cc-switch-wrappers")
+                    .join("claude")
+                    .join(format!("{}.json", sanitize_file_stem(&provider.id)));
+                write_json_file(&settings_path, &json!({ "env": env }))?;
+                restrict_permissions(&settings_path, 0o600);
+                entry.args = vec![
+                    "--settings".to_string(),
+                    settings_path.to_string_lossy().to_string(),
+                ];
+            }
+            AppType::Codex => {
+                // Codex 通过 CODEX_HOME 读取独立的 auth.json 与 config.toml
+                let home = dir
+                    .join(".cc-switch-wrappers")
+                    .join("codex")
+                    .join(sanitize_file_stem(&provider.id));
+                let auth = provider
+                    .settings_config
+                    .get("auth")
+                    .cloned()
+                    .unwrap_or_else(|| json!({}));
+                let auth_path = home.join("auth.json");
+                write_json_file(&auth_path, &auth)?;
+                restrict_permissions(&auth_path, 0o600);
+
+                let config_text = ProviderService::codex_live_config_text(provider)?;
+                write_text_file(
+                    &home.join("config.toml"),
+                    config_text.as_deref().unwrap_or(""),
+                )?;
+
+                entry.env = vec![("CODEX_HOME".to_string(), home.to_string_lossy().to_string())];
+                if let Some(meta) = provider.meta.as_ref() {
+                    let mut extra: Vec<_> = meta
+                        .extra_env
+                        .iter()
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect();
+                    extra.sort();
+                    entry.env.extend(extra);
+                }
+            }
+            AppType::Gemini => {
+                let env = provider
+                    .settings_config
+                    .get("env")
+                    .cloned()
+                    .unwrap_or_else(|| json!({}));
+                entry.env = env_pairs(&env);
+            }
+        }
+
+        Ok(entry)
+    }
+}
+
+fn program_name(app_type: &AppType) -> &'static str {
+    match app_type {
+        AppType::Claude => "claude",
+        AppType::Codex => "codex",
+        AppType::Gemini => "gemini",
+    }
+}
+
+fn script_file_name(app_type: &AppType) -> String {
+    if cfg!(windows) {
+        format!("{}-with.cmd", program_name(app_type))
+    } else {
+        format!("{}-with", program_name(app_type))
+    }
+}
+
+/// 提取可导出的环境变量（跳过非法变量名与非标量值）
+pub(crate) fn env_pairs(env: &Value) -> Vec<(String, String)> {
+    let Some(map) = env.as_object() else {
+        return Vec::new();
+    };
+    let mut pairs: Vec<(String, String)> = map
+        .iter()
+        .filter(|(key, _)| is_valid_env_name(key))
+        .filter_map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            Some((key.clone(), value))
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+pub(crate) fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn sanitize_file_stem(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn restrict_permissions(path: &Path, mode: u32) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(mode)) {
+            log::warn!("设置文件权限失败: {}: {e}", path.display());
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (path, mode);
+    }
+}
+
+fn render_script(app_type: &AppType, entries: &[WrapperEntry]) -> Result<String, AppError> {
+    if cfg!(windows) {
+        render_cmd_script(app_type, entries)
+    } else {
+        Ok(render_sh_script(app_type, entries))
+    }
+}
+
+pub(crate) fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn render_sh_script(app_type: &AppType, entries: &[WrapperEntry]) -> String {
+    let program = program_name(app_type);
+    let mut out = String::new();
+    out.push_str("#!/bin/sh\n");
+    out.push_str("# Generated by CC Switch. Do not edit; regenerate instead.\n");
+    out.push_str(&format!(
+        "# Usage: {program}-with <provider id or name> [args...]\n\n"
+    ));
+
+    out.push_str("if [ $# -lt 1 ]; then\n");
+    out.push_str(&format!(
+        "  echo \"usage: {program}-with <provider> [args...]\" >&2\n"
+    ));
+    out.push_str("  echo \"providers:\" >&2\n");
+    for entry in entries {
+        out.push_str(&format!(
+            "  echo {} >&2\n",
+            sh_quote(&format!("  {}  {}", entry.id, entry.name))
+        ));
+    }
+    out.push_str("  exit 2\nfi\n\n");
+
+    out.push_str("provider=\"$1\"\nshift\n\ncase \"$provider\" in\n");
+    for entry in entries {
+        let mut patterns = vec![sh_quote(&entry.id)];
+        if !entry.name.is_empty() && entry.name != entry.id {
+            patterns.push(sh_quote(&entry.name));
+        }
+        out.push_str(&format!("  {})\n", patterns.join("|")));
+        for (key, value) in &entry.env {
+            out.push_str(&format!("    export {key}={}\n", sh_quote(value)));
+        }
+        let args: Vec<String> = entry.args.iter().map(|arg| sh_quote(arg)).collect();
+        if args.is_empty() {
+            out.push_str(&format!("    exec {program} \"$@\"\n"));
+        } else {
+            out.push_str(&format!("    exec {program} {} \"$@\"\n", args.join(" ")));
+        }
+        out.push_str("    ;;\n");
+    }
+    out.push_str("  *)\n");
+    out.push_str(&format!(
+        "    echo \"{program}-with: unknown provider: $provider\" >&2\n"
+    ));
+    out.push_str("    exit 2\n    ;;\nesac\n");
+    out
+}
+
+/// 用于双引号内的显示名称：`%` 加倍，去掉双引号与换行（换行会结束当前命令，引号会提前闭合）
+fn cmd_escape(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '"' | '\r' | '\n'))
+        .collect::<String>()
+        .replace('%', "%%")
+}
+
+/// 用于双引号内的环境变量值与参数：含双引号或换行的值在 cmd 中无法原样表示，
+/// 返回 `None`，由调用方报错而不是静默改写密钥
+fn cmd_exact(value: &str) -> Option<String> {
+    (!value.contains(['"', '\r', '\n'])).then(|| value.replace('%', "%%"))
+}
+
+fn unsupported_cmd_value(entry: &WrapperEntry, what: &str) -> AppError {
+    AppError::localized(
+        "shell_wrapper.unsupported_value",
+        format!(
+            "供应商 {} 的 {what} 包含双引号或换行，无法写入 cmd 包装脚本",
+            entry.name
+        ),
+        format!(
+            "{what} of provider {} contains a double quote or line break and cannot be written to a cmd wrapper script",
+            entry.name
+        ),
+    )
+}
+
+/// 用于未加引号的 `echo` 文本：在 `cmd_escape` 基础上对 cmd 元字符加 `^` 转义
+fn cmd_echo_escape(value: &str) -> String {
+    let mut out = String::new();
+    for c in cmd_escape(value).chars() {
+        if matches!(c, '^' | '&' | '|' | '<' | '>' | '(' | ')') {
+            out.push('^');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn render_cmd_script(app_type: &AppType, entries: &[WrapperEntry]) -> Result<String, AppError> {
+    let program = program_name(app_type);
+    let mut out = String::new();
+    out.push_str("@echo off\r\n");
+    out.push_str("rem Generated by CC Switch. Do not edit; regenerate instead.\r\n");
+    out.push_str(&format!(
+        "rem Usage: {program}-with ^<provider id or name^> [args...]\r\n"
+    ));
+    out.push_str("setlocal\r\n");
+    out.push_str("set \"CCS_PROVIDER=%~1\"\r\n");
+    out.push_str("if \"%CCS_PROVIDER%\"==\"\" goto usage\r\n");
+    out.push_str("shift\r\nset \"CCS_ARGS=\"\r\n");
+    out.push_str(":collect\r\nif \"%~1\"==\"\" goto dispatch\r\n");
+    out.push_str("set \"CCS_ARGS=%CCS_ARGS% %1\"\r\nshift\r\ngoto collect\r\n");
+    out.push_str(":dispatch\r\n");
+    for (index, entry) in entries.iter().enumerate() {
+        out.push_str(&format!(
+            "if /I \"%CCS_PROVIDER%\"==\"{}\" goto p{index}\r\n",
+            cmd_escape(&entry.id)
+        ));
+        if !entry.name.is_empty() && entry.name != entry.id {
+            out.push_str(&format!(
+                "if /I \"%CCS_PROVIDER%\"==\"{}\" goto p{index}\r\n",
+                cmd_escape(&entry.name)
+            ));
+        }
+    }
+    out.push_str(&format!(
+        "echo {program}-with: unknown provider: \"%CCS_PROVIDER%\" 1>&2\r\nexit /b 2\r\n"
+    ));
+    out.push_str(":usage\r\n");
+    out.push_str(&format!(
+        "echo usage: {program}-with ^<provider^> [args...] 1>&2\r\n"
+    ));
+    for entry in entries {
+        out.push_str(&format!(
+            "echo   {}  {} 1>&2\r\n",
+            cmd_echo_escape(&entry.id),
+            cmd_echo_escape(&entry.name)
+        ));
+    }
+    out.push_str("exit /b 2\r\n");
+
+    for (index, entry) in entries.iter().enumerate() {
+        out.push_str(&format!(":p{index}\r\n"));
+        for (key, value) in &entry.env {
+            let value = cmd_exact(value).ok_or_else(|| unsupported_cmd_value(entry, key))?;
+            out.push_str(&format!("set \"{key}={value}\"\r\n"));
+        }
+        let mut args = Vec::with_capacity(entry.args.len());
+        for arg in &entry.args {
+            let arg = cmd_exact(arg).ok_or_else(|| unsupported_cmd_value(entry, "--settings"))?;
+            args.push(format!("\"{arg}\""));
+        }
+        out.push_str(&format!(
+            "{program} {} %CCS_ARGS%\r\nexit /b %ERRORLEVEL%\r\n",
+            args.join(" ")
+        ));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sh_script_exports_env_and_quotes_values() {
+        let entries = vec![WrapperEntry {
+            id: "relay".into(),
+            name: "My 'Relay'".into(),
+            env: env_pairs(&json!({
+                "ANTHROPIC_BASE_URL": "https://relay.example",
+                "ANTHROPIC_AUTH_TOKEN": "sk-it's",
+                "bad-name": "ignored",
+                "NESTED": { "a": 1 }
+            })),
+            args: Vec::new(),
+        }];
+
+        let script = render_sh_script(&AppType::Claude, &entries);
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("  'relay'|'My '\\''Relay'\\''')\n"));
+        assert!(script.contains("    export ANTHROPIC_AUTH_TOKEN='sk-it'\\''s'\n"));
+        assert!(script.contains("    export ANTHROPIC_BASE_URL='https://relay.example'\n"));
+        assert!(!script.contains("bad-name"));
+        assert!(!script.contains("NESTED"));
+        assert!(script.contains("    exec claude \"$@\"\n"));
+    }
+
+    #[test]
+    fn cmd_script_escapes_metacharacters_in_names() {
+        let entries = vec![WrapperEntry {
+            id: "relay".into(),
+            name: "x & calc\r\ndel *".into(),
+            env: env_pairs(&json!({ "ANTHROPIC_AUTH_TOKEN": "sk-100%&x" })),
+            args: Vec::new(),
+        }];
+
+        let script = render_cmd_script(&AppType::Claude, &entries).unwrap();
+        assert!(script.contains("echo   relay  x ^& calcdel * 1>&2\r\n"));
+        assert!(script.contains("if /I \"%CCS_PROVIDER%\"==\"x & calcdel *\" goto p0\r\n"));
+        assert!(script.contains("set \"ANTHROPIC_AUTH_TOKEN=sk-100%%&x\"\r\n"));
+        assert!(script.contains("set \"CCS_ARGS=%CCS_ARGS% %1\"\r\n"));
+        assert!(!script.contains("calc\r\ndel"));
+    }
+
+    #[test]
+    fn cmd_script_rejects_values_it_cannot_represent() {
+        let entry = |token: &str| WrapperEntry {
+            id: "relay".into(),
+            name: "Relay".into(),
+            env: env_pairs(&json!({ "ANTHROPIC_AUTH_TOKEN": token })),
+            args: Vec::new(),
+        };
+
+        for token in ["sk-\"quoted\"", "sk-a\r\nset X=1"] {
+            let err = render_cmd_script(&AppType::Claude, &[entry(token)]).unwrap_err();
+            let message = err.to_string();
+            assert!(message.contains("ANTHROPIC_AUTH_TOKEN"));
+            assert!(!message.contains("sk-"));
+        }
+    }
+}
@@ -7,6 +7,8 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use tokio::time::timeout;
 
+use crate::app_config::AppType;
+
 /// 技能对象
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
@@ -32,6 +34,9 @@ pub struct Skill {
     /// 分支名称
     #[serde(rename = "repoBranch")]
     pub repo_branch: Option<String>,
+    /// 已安装到的应用（按各应用的安装位置检测）
+    #[serde(rename = "installedApps", default)]
+    pub installed_apps: Vec<AppType>,
 }
 
 /// 仓库配置
@@ -58,6 +63,13 @@ pub struct SkillState {
     /// 安装时间
     #[serde(rename = "installedAt")]
     pub installed_at: DateTime<Utc>,
+    /// 安装目标应用（旧数据未记录时视为仅 Claude）
+    #[serde(default = "default_skill_apps")]
+    pub apps: Vec<AppType>,
+}
+
+fn default_skill_apps() -> Vec<AppType> {
+    vec![AppType::Claude]
 }
 
 /// 持久化存储结构
@@ -141,8 +153,13 @@ impl SkillService {
     pub async fn list_skills(&self, repos: Vec<SkillRepo>) -> Result<Vec<Skill>> {
         let mut skills = Vec::new();
 
-        // 仅使用启用的仓库，并行获取技能列表，避免单个无效仓库拖慢整体刷新
-        let enabled_repos: Vec<SkillRepo> = repos.into_iter().filter(|repo| repo.enabled).collect();
+        // 仅使用启用的仓库，并行获取技能列表，避免单个无效仓库拖慢整体刷新；
+        // 离线模式下只列出本地技能
+        let enabled_repos: Vec<SkillRepo> = if crate::settings::get_settings().offline_mode {
+            Vec::new()
+        } else {
+            repos.into_iter().filter(|repo| repo.enabled).collect()
+        };
 
         let fetch_tasks = enabled_repos
             .iter()
@@ -163,6 +180,7 @@ impl SkillService {
         // 去重并排序
         Self::deduplicate_skills(&mut skills);
         skills.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        self.fill_installed_apps(&mut skills);
 
         Ok(skills)
     }
@@ -234,6 +252,7 @@ impl SkillService {
                         repo_owner: Some(repo.owner.clone()),
                         repo_name: Some(repo.name.clone()),
                         repo_branch: Some(repo.branch.clone()),
+                        installed_apps: Vec::new(),
                     });
                 }
                 Err(e) => log::warn!("解析 {} 元数据失败: {}", skill_md.display(), e),
@@ -246,6 +265,14 @@ impl SkillService {
         Ok(skills)
     }
 
+    /// 列出本地已安装的技能（不访问网络）
+    pub fn list_installed(&self) -> Result<Vec<Skill>> {
+        let mut skills = Vec::new();
+        self.merge_local_skills(&mut skills)?;
+        self.fill_installed_apps(&mut skills);
+        Ok(skills)
+    }
+
     /// 解析技能元数据
     fn parse_skill_metadata(&self, path: &Path) -> Result<SkillMetadata> {
         let content = fs::read_to_string(path)?;
@@ -312,6 +339,7 @@ impl SkillService {
                             repo_owner: None,
                             repo_name: None,
                             repo_branch: None,
+                            installed_apps: Vec::new(),
                         });
                     }
                 }
@@ -336,7 +364,7 @@ impl SkillService {
     }
 
     /// 下载仓库
-    async fn download_repo(&self, repo: &SkillRepo) -> Result<PathBuf> {
+    pub(crate) async fn download_repo(&self, repo: &SkillRepo) -> Result<PathBuf> {
         let temp_dir = tempfile::tempdir()?;
         let temp_path = temp_dir.path().to_path_buf();
         let _ = temp_dir.keep(); // 保持临时目录，稍后手动清理
@@ -425,15 +453,34 @@ impl SkillService {
         Ok(())
     }
 
-    /// 安装技能（仅负责下载和文件操作，状态更新由上层负责）
+    /// 安装技能到 Claude（仅负责下载和文件操作，状态更新由上层负责）
     pub async fn install_skill(&self, directory: String, repo: SkillRepo) -> Result<()> {
-        let dest = self.install_dir.join(&directory);
+        self.install_skill_for(directory, Some(repo), &AppType::Claude)
+            .await
+    }
 
-        // 若目标目录已存在，则视为已安装，避免重复下载
+    /// 安装技能到指定应用（仅负责下载和文件操作，状态更新由上层负责）
+    ///
+    /// 已安装到 Claude 时以本地副本为来源，否则从 `repo` 下载
+    pub async fn install_skill_for(
+        &self,
+        directory: String,
+        repo: Option<SkillRepo>,
+        app: &AppType,
+    ) -> Result<()> {
+        let dest = self.target_path(&directory, app);
+
+        // 若目标已存在，则视为已安装，避免重复下载
         if dest.exists() {
             return Ok(());
         }
 
+        let local = self.install_dir.join(&directory);
+        if local.is_dir() {
+            return Self::write_target(&local, &dest, &directory, app);
+        }
+
+        let repo = repo.ok_or_else(|| anyhow!("缺少仓库信息"))?;
         // 下载仓库时增加总超时，防止无效链接导致长时间卡住安装过程
         let temp_dir = timeout(
             std::time::Duration::from_secs(15),
@@ -442,26 +489,73 @@ impl SkillService {
         .await
         .map_err(|_| anyhow!("下载仓库 {}/{} 超时", repo.owner, repo.name))??;
 
-        // 复制到安装目录
         let source = temp_dir.join(&directory);
+        let result = if source.exists() {
+            Self::write_target(&source, &dest, &directory, app)
+        } else {
+            Err(anyhow!("技能目录不存在"))
+        };
 
-        if !source.exists() {
-            let _ = fs::remove_dir_all(&temp_dir);
-            return Err(anyhow::anyhow!("技能目录不存在"));
-        }
+        // 清理临时目录
+        let _ = fs::remove_dir_all(&temp_dir);
 
-        // 删除旧版本
-        if dest.exists() {
-            fs::remove_dir_all(&dest)?;
-        }
+        result
+    }
 
-        // 递归复制
-        Self::copy_dir_recursive(&source, &dest)?;
+    /// 技能在各应用中的安装位置
+    ///
+    /// - Claude：`~/.claude/skills/<directory>/`
+    /// - Codex：自定义提示词 `~/.codex/prompts/<directory>.md`（仅 SKILL.md）
+    /// - Gemini：扩展目录 `~/.gemini/extensions/<directory>/`
+    fn target_path(&self, directory: &str, app: &AppType) -> PathBuf {
+        match app {
+            AppType::Claude => self.install_dir.join(directory),
+            AppType::Codex => crate::codex_config::get_codex_config_dir()
+                .join("prompts")
+                .join(format!("{directory}.md")),
+            AppType::Gemini => crate::gemini_config::get_gemini_dir()
+                .join("extensions")
+                .join(directory),
+        }
+    }
 
-        // 清理临时目录
-        let _ = fs::remove_dir_all(&temp_dir);
+    fn write_target(source: &Path, dest: &Path, directory: &str, app: &AppType) -> Result<()> {
+        match app {
+            AppType::Claude => Self::copy_dir_recursive(source, dest),
+            AppType::Codex => {
+                let content = fs::read_to_string(source.join("SKILL.md"))
+                    .with_context(|| format!("技能 {directory} 缺少 SKILL.md"))?;
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(dest, content)?;
+                Ok(())
+            }
+            AppType::Gemini => {
+                Self::copy_dir_recursive(source, dest)?;
+                // Gemini CLI 通过 gemini-extension.json 识别扩展，以 SKILL.md 作为上下文文件
+                let manifest = dest.join("gemini-extension.json");
+                if !manifest.exists() {
+                    let content = serde_json::json!({
+                        "name": directory,
+                        "version": "1.0.0",
+                        "contextFileName": "SKILL.md",
+                    });
+                    fs::write(&manifest, serde_json::to_string_pretty(&content)?)?;
+                }
+                Ok(())
+            }
+        }
+    }
 
-        Ok(())
+    /// 按各应用的安装位置填充 `installed_apps`
+    fn fill_installed_apps(&self, skills: &mut [Skill]) {
+        for skill in skills.iter_mut() {
+            skill.installed_apps = [AppType::Claude, AppType::Codex, AppType::Gemini]
+                .into_iter()
+                .filter(|app| self.target_path(&skill.directory, app).exists())
+                .collect();
+        }
     }
 
     /// 递归复制目录
@@ -483,12 +577,19 @@ impl SkillService {
         Ok(())
     }
 
-    /// 卸载技能（仅负责文件操作，状态更新由上层负责）
+    /// 从 Claude 卸载技能（仅负责文件操作，状态更新由上层负责）
     pub fn uninstall_skill(&self, directory: String) -> Result<()> {
-        let dest = self.install_dir.join(&directory);
+        self.uninstall_skill_for(&directory, &AppType::Claude)
+    }
 
-        if dest.exists() {
+    /// 从指定应用卸载技能（仅负责文件操作，状态更新由上层负责）
+    pub fn uninstall_skill_for(&self, directory: &str, app: &AppType) -> Result<()> {
+        let dest = self.target_path(directory, app);
+
+        if dest.is_dir() {
             fs::remove_dir_all(&dest)?;
+        } else if dest.exists() {
+            fs::remove_file(&dest)?;
         }
 
         Ok(())
@@ -524,3 +625,39 @@ impl SkillService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_target_adapts_skill_to_each_app() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        fs::create_dir_all(source.join("scripts")).unwrap();
+        fs::write(source.join("SKILL.md"), "---\nname: Demo\n---\nDo things").unwrap();
+        fs::write(source.join("scripts").join("run.sh"), "echo hi").unwrap();
+
+        let prompt = dir.path().join("prompts").join("demo.md");
+        SkillService::write_target(&source, &prompt, "demo", &AppType::Codex).unwrap();
+        assert_eq!(
+            fs::read_to_string(&prompt).unwrap(),
+            "---\nname: Demo\n---\nDo things"
+        );
+
+        let extension = dir.path().join("extensions").join("demo");
+        SkillService::write_target(&source, &extension, "demo", &AppType::Gemini).unwrap();
+        assert!(extension.join("scripts").join("run.sh").exists());
+        let manifest: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(extension.join("gemini-extension.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest["name"], "demo");
+        assert_eq!(manifest["contextFileName"], "SKILL.md");
+
+        let state: SkillState =
+            serde_json::from_str(r#"{"installed":true,"installedAt":"2025-01-01T00:00:00Z"}"#)
+                .unwrap();
+        assert_eq!(state.apps, vec![AppType::Claude]);
+    }
+}
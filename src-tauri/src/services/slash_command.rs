@@ -0,0 +1,249 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app_config::MultiAppConfig;
+use crate::config::{delete_file, get_claude_config_dir, write_text_file};
+use crate::error::AppError;
+use crate::slash_command::SlashCommand;
+use crate::store::AppState;
+
+/// Claude Code 自定义斜杠命令管理
+///
+/// 命令内容保存在 config.json 中（随配置导出/导入），启用的命令写入 `~/.claude/commands/`
+pub struct SlashCommandService;
+
+impl SlashCommandService {
+    /// 斜杠命令目录：`<Claude 配置目录>/commands`
+    pub fn commands_dir() -> PathBuf {
+        get_claude_config_dir().join("commands")
+    }
+
+    /// 列出所有命令（按名称排序）
+    pub fn list(state: &AppState) -> Result<Vec<SlashCommand>, AppError> {
+        let cfg = state.config.read()?;
+        let mut commands: Vec<SlashCommand> = cfg.slash_commands.values().cloned().collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(commands)
+    }
+
+    /// 新建或更新命令，并按启用状态写入或移除命令文件
+    pub fn upsert(state: &AppState, command: SlashCommand) -> Result<SlashCommand, AppError> {
+        let name = validate_name(&command.name)?;
+        let now = chrono::Utc::now().timestamp();
+
+        let command = {
+            let mut cfg = state.config.write()?;
+            let created_at = cfg
+                .slash_commands
+                .get(&name)
+                .and_then(|existing| existing.created_at)
+                .or(command.created_at)
+                .unwrap_or(now);
+            let command = SlashCommand {
+                name: name.clone(),
+                created_at: Some(created_at),
+                updated_at: Some(now),
+                ..command
+            };
+            cfg.slash_commands.insert(name, command.clone());
+            command
+        };
+        state.save()?;
+
+        write_live(&command)?;
+        Ok(command)
+    }
+
+    /// 删除命令及其命令文件
+    pub fn delete(state: &AppState, name: &str) -> Result<bool, AppError> {
+        let removed = {
+            let mut cfg = state.config.write()?;
+            cfg.slash_commands.remove(name)
+        };
+        let Some(command) = removed else {
+            return Ok(false);
+        };
+        state.save()?;
+
+        delete_file(&command_path(&command.name))?;
+        Ok(true)
+    }
+
+    /// 启用或停用命令（停用时移除命令文件，内容仍保留在配置中）
+    pub fn set_enabled(state: &AppState, name: &str, enabled: bool) -> Result<(), AppError> {
+        let command = {
+            let mut cfg = state.config.write()?;
+            let command = cfg.slash_commands.get_mut(name).ok_or_else(|| {
+                AppError::localized(
+                    "slash_command.not_found",
+                    format!("斜杠命令不存在: {name}"),
+                    format!("Slash command not found: {name}"),
+                )
+            })?;
+            command.enabled = enabled;
+            command.clone()
+        };
+        state.save()?;
+
+        write_live(&command)
+    }
+
+    /// 导入命令目录中尚未受管理的命令文件（视为已启用），返回导入数量
+    pub fn import_from_dir(state: &AppState) -> Result<usize, AppError> {
+        let dir = Self::commands_dir();
+        let mut files = Vec::new();
+        collect_command_files(&dir, "", &mut files)?;
+
+        let now = chrono::Utc::now().timestamp();
+        let mut imported = 0;
+        {
+            let mut cfg = state.config.write()?;
+            for (name, path) in files {
+                if cfg.slash_commands.contains_key(&name) {
+                    continue;
+                }
+                let content = fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+                let command = SlashCommand {
+                    name: name.clone(),
+                    description: parse_description(&content),
+                    content,
+                    enabled: true,
+                    created_at: Some(now),
+                    updated_at: Some(now),
+                };
+                cfg.slash_commands.insert(name, command);
+                imported += 1;
+            }
+        }
+
+        if imported > 0 {
+            state.save()?;
+        }
+        Ok(imported)
+    }
+
+    /// 按供应商的启用集合切换命令（仅集合内的命令保持启用）
+    pub fn apply_enabled_set(state: &AppState, names: &[String]) -> Result<(), AppError> {
+        {
+            let mut cfg = state.config.write()?;
+            for command in cfg.slash_commands.values_mut() {
+                command.enabled = names.contains(&command.name);
+            }
+        }
+        state.save()?;
+
+        let cfg = state.config.read()?;
+        Self::sync_to_live(&cfg)
+    }
+
+    /// 将配置中的命令同步到命令目录：启用的写入，停用的移除
+    pub fn sync_to_live(config: &MultiAppConfig) -> Result<(), AppError> {
+        for command in config.slash_commands.values() {
+            write_live(command)?;
+        }
+        Ok(())
+    }
+}
+
+/// 校验命令名：以 `/` 分隔的各段仅允许字母、数字、`-`、`_`、`.`，且不能以 `.` 开头
+fn validate_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim().trim_matches('/');
+    let valid = !name.is_empty()
+        && name.split('/').all(|segment| {
+            !segment.is_empty()
+                && !segment.starts_with('.')
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+    if !valid {
+        return Err(AppError::localized(
+            "slash_command.invalid_name",
+            format!("无效的斜杠命令名称: {name}"),
+            format!("Invalid slash command name: {name}"),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn command_path(name: &str) -> PathBuf {
+    SlashCommandService::commands_dir().join(format!("{name}.md"))
+}
+
+fn write_live(command: &SlashCommand) -> Result<(), AppError> {
+    let path = command_path(&command.name);
+    if !command.enabled {
+        return delete_file(&path);
+    }
+    // 内容未变化时不重写，避免触发 Claude Code 的文件监听
+    if fs::read_to_string(&path).is_ok_and(|current| current == command.content) {
+        return Ok(());
+    }
+    write_text_file(&path, &command.content)
+}
+
+/// 递归收集命令目录下的 `.md` 文件，返回（命令名, 路径）
+fn collect_command_files(
+    dir: &Path,
+    prefix: &str,
+    out: &mut Vec<(String, PathBuf)>,
+) -> Result<(), AppError> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if file_name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_command_files(&path, &format!("{prefix}{file_name}/"), out)?;
+        } else if let Some(stem) = file_name.strip_suffix(".md") {
+            let name = format!("{prefix}{stem}");
+            if validate_name(&name).is_ok() {
+                out.push((name, path));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 从 YAML front matter 中读取 `description`
+fn parse_description(content: &str) -> Option<String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let rest = content.strip_prefix("---")?;
+    let (front_matter, _) = rest.split_once("\n---")?;
+    let value: serde_yaml::Value = serde_yaml::from_str(front_matter).ok()?;
+    value
+        .get("description")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_namespaced_names_and_reads_front_matter() {
+        assert_eq!(
+            validate_name("/frontend/component").unwrap(),
+            "frontend/component"
+        );
+        assert!(validate_name("../escape").is_err());
+        assert!(validate_name("bad name").is_err());
+        assert!(validate_name("").is_err());
+
+        let content =
+            "---\ndescription: Review the diff\nallowed-tools: Bash\n---\nReview $ARGUMENTS";
+        assert_eq!(
+            parse_description(content).as_deref(),
+            Some("Review the diff")
+        );
+        assert_eq!(parse_description("no front matter"), None);
+    }
+}
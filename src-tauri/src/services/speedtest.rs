@@ -1,13 +1,21 @@
 use futures::future::join_all;
 use reqwest::{Client, Url};
 use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
 use std::time::{Duration, Instant};
 
+use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::provider::{Provider, RateLimitInfo};
+use crate::services::ProviderService;
 
 const DEFAULT_TIMEOUT_SECS: u64 = 8;
 const MAX_TIMEOUT_SECS: u64 = 30;
 const MIN_TIMEOUT_SECS: u64 = 2;
+/// 每个端点保留的历史测速记录条数
+const MAX_HISTORY_PER_URL: usize = 10;
 
 /// 端点测速结果
 #[derive(Debug, Clone, Serialize)]
@@ -16,6 +24,90 @@ pub struct EndpointLatency {
     pub latency: Option<u128>,
     pub status: Option<u16>,
     pub error: Option<String>,
+    /// 响应中携带的限流 / 配额信息
+    #[serde(rename = "rateLimit", skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitInfo>,
+}
+
+/// 真实请求测速的结果分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthProbeStatus {
+    /// 请求成功（2xx）
+    Ok,
+    /// 401：API Key 无效
+    Unauthorized,
+    /// 403：无权限（Key 未开通该模型、IP 限制等）
+    Forbidden,
+    /// 429：限流或额度耗尽
+    RateLimited,
+    /// 其他 HTTP 错误
+    HttpError,
+    /// 超时、连接失败等网络错误
+    NetworkError,
+}
+
+/// 使用供应商 API Key 发送最小请求的测速结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatedProbe {
+    pub url: String,
+    pub model: String,
+    pub status: AuthProbeStatus,
+    /// 完整请求耗时（毫秒），网络错误时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitInfo>,
+}
+
+/// 按供应商配置构造的真实请求
+struct AuthProbeRequest {
+    /// 用于测速历史的端点地址（供应商的 base_url）
+    base_url: String,
+    url: String,
+    /// 模型列表地址（验证供应商时检查）
+    models_url: String,
+    model: String,
+    headers: Vec<(String, String)>,
+    body: Value,
+}
+
+/// 单次测速记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencySample {
+    pub latency: u128,
+    pub tested_at: i64,
+}
+
+/// 最近一次测速结果（含失败）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatestProbe {
+    pub latency: Option<u128>,
+    pub error: Option<String>,
+    pub tested_at: i64,
+}
+
+/// 测速历史（进程内缓存，按规范化 URL 索引）
+fn history_store() -> &'static RwLock<HashMap<String, Vec<LatencySample>>> {
+    static HISTORY: OnceLock<RwLock<HashMap<String, Vec<LatencySample>>>> = OnceLock::new();
+    HISTORY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// 每个端点最近一次测速结果（进程内缓存，按规范化 URL 索引）
+fn latest_probe_store() -> &'static RwLock<HashMap<String, LatestProbe>> {
+    static LATEST: OnceLock<RwLock<HashMap<String, LatestProbe>>> = OnceLock::new();
+    LATEST.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn normalize_history_key(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_lowercase()
 }
 
 /// 网络测速相关业务
@@ -44,6 +136,7 @@ impl SpeedtestService {
                         latency: None,
                         status: None,
                         error: Some("URL 不能为空".to_string()),
+                        rate_limit: None,
                     };
                 }
 
@@ -55,6 +148,7 @@ impl SpeedtestService {
                             latency: None,
                             status: None,
                             error: Some(format!("URL 无效: {err}")),
+                            rate_limit: None,
                         };
                     }
                 };
@@ -70,6 +164,7 @@ impl SpeedtestService {
                         latency: Some(start.elapsed().as_millis()),
                         status: Some(resp.status().as_u16()),
                         error: None,
+                        rate_limit: extract_rate_limit(resp.headers()),
                     },
                     Err(err) => {
                         let status = err.status().map(|s| s.as_u16());
@@ -86,13 +181,176 @@ impl SpeedtestService {
                             latency: None,
                             status,
                             error: Some(error_message),
+                            rate_limit: None,
                         }
                     }
                 }
             }
         });
 
-        Ok(join_all(tasks).await)
+        let results = join_all(tasks).await;
+        Self::record_history(&results);
+        Ok(results)
+    }
+
+    /// 记录成功的测速结果到历史中，并更新各端点最近一次结果（含失败）
+    fn record_history(results: &[EndpointLatency]) {
+        let tested_at = chrono::Utc::now().timestamp_millis();
+        if let Ok(mut latest) = latest_probe_store().write() {
+            for result in results.iter().filter(|r| Url::parse(r.url.trim()).is_ok()) {
+                latest.insert(
+                    normalize_history_key(&result.url),
+                    LatestProbe {
+                        latency: result.latency,
+                        error: result.error.clone(),
+                        tested_at,
+                    },
+                );
+            }
+        }
+
+        let Ok(mut history) = history_store().write() else {
+            return;
+        };
+        for result in results {
+            let Some(latency) = result.latency else {
+                continue;
+            };
+            let samples = history
+                .entry(normalize_history_key(&result.url))
+                .or_default();
+            samples.push(LatencySample { latency, tested_at });
+            if samples.len() > MAX_HISTORY_PER_URL {
+                let overflow = samples.len() - MAX_HISTORY_PER_URL;
+                samples.drain(..overflow);
+            }
+        }
+    }
+
+    /// 使用供应商的 API Key 发送一次最小的真实请求（输出上限 1 token，费用可忽略），
+    /// 测量完整请求延迟，并区分 401/403/429 等配置问题；结果同时计入该端点的测速历史
+    pub async fn probe_authenticated(
+        app_type: &AppType,
+        provider: &Provider,
+        timeout_secs: Option<u64>,
+    ) -> Result<(AuthenticatedProbe, EndpointLatency), AppError> {
+        let request = build_auth_probe(app_type, provider)?;
+        let client = Self::build_client(Self::sanitize_timeout(timeout_secs))?;
+
+        let mut builder = client.post(&request.url).json(&request.body);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let start = Instant::now();
+        let probe = match builder.send().await {
+            Ok(resp) => {
+                let latency = start.elapsed().as_millis();
+                let code = resp.status();
+                let rate_limit = extract_rate_limit(resp.headers());
+                let status = match code.as_u16() {
+                    200..=299 => AuthProbeStatus::Ok,
+                    401 => AuthProbeStatus::Unauthorized,
+                    403 => AuthProbeStatus::Forbidden,
+                    429 => AuthProbeStatus::RateLimited,
+                    _ => AuthProbeStatus::HttpError,
+                };
+                let error = if status == AuthProbeStatus::Ok {
+                    None
+                } else {
+                    let body = resp.text().await.unwrap_or_default();
+                    Some(error_summary(code.as_u16(), &body))
+                };
+                AuthenticatedProbe {
+                    url: request.url,
+                    model: request.model,
+                    status,
+                    latency: Some(latency),
+                    http_status: Some(code.as_u16()),
+                    error,
+                    rate_limit,
+                }
+            }
+            Err(err) => AuthenticatedProbe {
+                url: request.url,
+                model: request.model,
+                status: AuthProbeStatus::NetworkError,
+                latency: None,
+                http_status: err.status().map(|s| s.as_u16()),
+                error: Some(if err.is_timeout() {
+                    "请求超时".to_string()
+                } else if err.is_connect() {
+                    "连接失败".to_string()
+                } else {
+                    err.to_string()
+                }),
+                rate_limit: None,
+            },
+        };
+
+        // 鉴权失败等错误也记为最近一次测速失败，切换预检据此提示
+        let sample = EndpointLatency {
+            url: request.base_url,
+            latency: probe.latency,
+            status: probe.http_status,
+            error: probe.error.clone(),
+            rate_limit: probe.rate_limit.clone(),
+        };
+        Self::record_history(std::slice::from_ref(&sample));
+        Ok((probe, sample))
+    }
+
+    /// 使用供应商的 API Key 请求模型列表，返回是否成功且列表非空
+    pub async fn probe_models(
+        app_type: &AppType,
+        provider: &Provider,
+        timeout_secs: Option<u64>,
+    ) -> Result<bool, AppError> {
+        let request = build_auth_probe(app_type, provider)?;
+        let client = Self::build_client(Self::sanitize_timeout(timeout_secs))?;
+        let mut builder = client.get(&request.models_url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let Ok(resp) = builder.send().await else {
+            return Ok(false);
+        };
+        if !resp.status().is_success() {
+            return Ok(false);
+        }
+        // OpenAI / Anthropic 为 `data`，Gemini 为 `models`
+        let body: Value = resp.json().await.unwrap_or_default();
+        Ok(["data", "models"].iter().any(|key| {
+            body.get(key)
+                .and_then(Value::as_array)
+                .is_some_and(|models| !models.is_empty())
+        }))
+    }
+
+    /// 获取某个端点最近一次成功测速的延迟
+    pub fn latest_latency(url: &str) -> Option<u128> {
+        let history = history_store().read().ok()?;
+        history
+            .get(&normalize_history_key(url))
+            .and_then(|samples| samples.last())
+            .map(|sample| sample.latency)
+    }
+
+    /// 获取某个端点最近一次测速结果（失败时 `latency` 为空）
+    pub fn latest_probe(url: &str) -> Option<LatestProbe> {
+        latest_probe_store()
+            .read()
+            .ok()?
+            .get(&normalize_history_key(url))
+            .cloned()
+    }
+
+    /// 获取某个端点的测速历史（按时间先后排列）
+    pub fn latency_history(url: &str) -> Vec<LatencySample> {
+        history_store()
+            .read()
+            .ok()
+            .and_then(|history| history.get(&normalize_history_key(url)).cloned())
+            .unwrap_or_default()
     }
 
     fn build_client(timeout_secs: u64) -> Result<Client, AppError> {
@@ -116,10 +374,263 @@ impl SpeedtestService {
     }
 }
 
+/// 按应用协议构造最小请求：Claude `/v1/messages`、Codex `/responses` 或 `/chat/completions`、
+/// Gemini `:generateContent`
+fn build_auth_probe(app_type: &AppType, provider: &Provider) -> Result<AuthProbeRequest, AppError> {
+    let settings = crate::placeholder::expand_value(&provider.settings_config)?;
+    let mut expanded = provider.clone();
+    expanded.settings_config = settings.clone();
+    let env = |key: &str| {
+        settings
+            .pointer(&format!("/env/{key}"))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let missing_key = || {
+        AppError::localized(
+            "speedtest.api_key_missing",
+            format!("供应商 {} 未配置 API Key，无法发送真实请求", provider.name),
+            format!(
+                "Provider {} has no API key; cannot send an authenticated request",
+                provider.name
+            ),
+        )
+    };
+    let base_url = ProviderService::provider_base_url(app_type, &expanded);
+    let mut headers: Vec<(String, String)> = provider
+        .meta
+        .as_ref()
+        .map(|meta| {
+            meta.headers
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (base_url, url, models_url, model, body) = match app_type {
+        AppType::Claude => {
+            let base = base_url.unwrap_or_else(|| "https://api.anthropic.com".to_string());
+            if let Some(token) = env("ANTHROPIC_AUTH_TOKEN") {
+                headers.push(("Authorization".to_string(), format!("Bearer {token}")));
+            } else {
+                headers.push((
+                    "x-api-key".to_string(),
+                    env("ANTHROPIC_API_KEY").ok_or_else(missing_key)?,
+                ));
+            }
+            headers.push(("anthropic-version".to_string(), "2023-06-01".to_string()));
+            let model = env("ANTHROPIC_DEFAULT_HAIKU_MODEL")
+                .or_else(|| env("ANTHROPIC_SMALL_FAST_MODEL"))
+                .or_else(|| env("ANTHROPIC_MODEL"))
+                .unwrap_or_else(|| "claude-3-5-haiku-latest".to_string());
+            let url = format!("{}/v1/messages", base.trim_end_matches('/'));
+            let models_url = format!("{}/v1/models", base.trim_end_matches('/'));
+            let body = json!({
+                "model": model,
+                "max_tokens": 1,
+                "messages": [{ "role": "user", "content": "ping" }],
+            });
+            (base, url, models_url, model, body)
+        }
+        AppType::Codex => {
+            let base = base_url.unwrap_or_else(|| "https://api.openai.com/v1".to_string());
+            let key = settings
+                .pointer("/auth/OPENAI_API_KEY")
+                .and_then(Value::as_str)
+                .filter(|key| !key.trim().is_empty())
+                .ok_or_else(missing_key)?;
+            headers.push(("Authorization".to_string(), format!("Bearer {key}")));
+            let config_text = settings
+                .get("config")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let model = crate::codex_config::get_config_value(config_text, "model")
+                .ok()
+                .flatten()
+                .and_then(|value| value.as_str().map(str::to_string))
+                .unwrap_or_else(|| "gpt-4o-mini".to_string());
+            let root = base.trim_end_matches('/');
+            let (url, body) =
+                if crate::codex_config::get_wire_api(config_text).as_deref() == Some("chat") {
+                    (
+                        format!("{root}/chat/completions"),
+                        json!({
+                            "model": model,
+                            "max_tokens": 1,
+                            "messages": [{ "role": "user", "content": "ping" }],
+                        }),
+                    )
+                } else {
+                    // Responses API 要求 max_output_tokens 不小于 16
+                    (
+                        format!("{root}/responses"),
+                        json!({ "model": model, "max_output_tokens": 16, "input": "ping" }),
+                    )
+                };
+            let models_url = format!("{root}/models");
+            (base, url, models_url, model, body)
+        }
+        AppType::Gemini => {
+            let base =
+                base_url.unwrap_or_else(|| "https://generativelanguage.googleapis.com".to_string());
+            headers.push((
+                "x-goog-api-key".to_string(),
+                env("GEMINI_API_KEY").ok_or_else(missing_key)?,
+            ));
+            let model = env("GEMINI_MODEL").unwrap_or_else(|| "gemini-2.5-flash".to_string());
+            let url = format!(
+                "{}/v1beta/models/{model}:generateContent",
+                base.trim_end_matches('/')
+            );
+            let models_url = format!("{}/v1beta/models", base.trim_end_matches('/'));
+            let body = json!({
+                "contents": [{ "role": "user", "parts": [{ "text": "ping" }] }],
+                "generationConfig": { "maxOutputTokens": 1 },
+            });
+            (base, url, models_url, model, body)
+        }
+    };
+
+    Url::parse(&url).map_err(|e| {
+        AppError::localized(
+            "speedtest.invalid_url",
+            format!("URL 无效: {e}"),
+            format!("Invalid URL: {e}"),
+        )
+    })?;
+    Ok(AuthProbeRequest {
+        base_url,
+        url,
+        models_url,
+        model,
+        headers,
+        body,
+    })
+}
+
+/// 提取错误响应中的说明（JSON `error.message` 或正文前 200 个字符）
+fn error_summary(status: u16, body: &str) -> String {
+    let message = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|value| {
+            value
+                .pointer("/error/message")
+                .or_else(|| value.get("message"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| body.trim().chars().take(200).collect());
+    if message.is_empty() {
+        format!("HTTP {status}")
+    } else {
+        format!("HTTP {status}: {message}")
+    }
+}
+
+/// 是否为限流 / 配额相关响应头（含各中转站自定义的 quota 头）
+fn is_rate_limit_header(name: &str) -> bool {
+    name == "retry-after"
+        || name.contains("ratelimit")
+        || name.contains("rate-limit")
+        || name.contains("quota")
+}
+
+/// 从响应头中提取限流信息（OpenAI `x-ratelimit-*`、Anthropic `anthropic-ratelimit-*`、`retry-after`）
+fn extract_rate_limit(headers: &reqwest::header::HeaderMap) -> Option<RateLimitInfo> {
+    let captured: std::collections::BTreeMap<String, String> = headers
+        .iter()
+        .filter(|(name, _)| is_rate_limit_header(name.as_str()))
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?.trim().to_string();
+            Some((name.as_str().to_string(), value))
+        })
+        .collect();
+    if captured.is_empty() {
+        return None;
+    }
+
+    let number = |names: &[&str]| {
+        names.iter().find_map(|name| {
+            let value = captured.get(*name)?;
+            value.split([',', ';']).next()?.trim().parse::<u64>().ok()
+        })
+    };
+
+    Some(RateLimitInfo {
+        requests_limit: number(&[
+            "x-ratelimit-limit-requests",
+            "anthropic-ratelimit-requests-limit",
+            "x-ratelimit-limit",
+        ]),
+        requests_remaining: number(&[
+            "x-ratelimit-remaining-requests",
+            "anthropic-ratelimit-requests-remaining",
+            "x-ratelimit-remaining",
+        ]),
+        tokens_limit: number(&[
+            "x-ratelimit-limit-tokens",
+            "anthropic-ratelimit-tokens-limit",
+        ]),
+        tokens_remaining: number(&[
+            "x-ratelimit-remaining-tokens",
+            "anthropic-ratelimit-tokens-remaining",
+        ]),
+        retry_after: captured.get("retry-after").cloned(),
+        headers: captured,
+        captured_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn auth_probe_requests_follow_each_protocol() {
+        let claude = Provider::with_id(
+            "c".into(),
+            "Claude".into(),
+            json!({ "env": {
+                "ANTHROPIC_BASE_URL": "https://relay.example/",
+                "ANTHROPIC_AUTH_TOKEN": "sk-claude",
+                "ANTHROPIC_MODEL": "claude-sonnet-4"
+            } }),
+            None,
+        );
+        let request = build_auth_probe(&AppType::Claude, &claude).unwrap();
+        assert_eq!(request.url, "https://relay.example/v1/messages");
+        assert_eq!(request.model, "claude-sonnet-4");
+        assert!(request
+            .headers
+            .contains(&("Authorization".into(), "Bearer sk-claude".into())));
+
+        let codex = Provider::with_id(
+            "x".into(),
+            "Codex".into(),
+            json!({
+                "auth": { "OPENAI_API_KEY": "sk-codex" },
+                "config": "model_provider = \"relay\"\nmodel = \"gpt-5\"\n[model_providers.relay]\nbase_url = \"https://relay.example/v1\"\nwire_api = \"chat\"\n"
+            }),
+            None,
+        );
+        let request = build_auth_probe(&AppType::Codex, &codex).unwrap();
+        assert_eq!(request.url, "https://relay.example/v1/chat/completions");
+        assert_eq!(request.base_url, "https://relay.example/v1");
+        assert_eq!(request.body["model"], "gpt-5");
+
+        let gemini = Provider::with_id("g".into(), "Gemini".into(), json!({ "env": {} }), None);
+        assert!(build_auth_probe(&AppType::Gemini, &gemini).is_err());
+
+        assert_eq!(
+            error_summary(401, r#"{"error":{"message":"invalid x-api-key"}}"#),
+            "HTTP 401: invalid x-api-key"
+        );
+        assert_eq!(error_summary(502, ""), "HTTP 502");
+    }
+
     #[test]
     fn sanitize_timeout_clamps_values() {
         assert_eq!(
@@ -140,6 +651,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn record_history_tracks_latest_probe_including_failures() {
+        let url = "https://latest-probe.example.com/";
+        SpeedtestService::record_history(&[EndpointLatency {
+            url: url.into(),
+            latency: Some(120),
+            status: Some(200),
+            error: None,
+            rate_limit: None,
+        }]);
+        assert_eq!(
+            SpeedtestService::latest_probe("https://latest-probe.example.com")
+                .and_then(|p| p.latency),
+            Some(120)
+        );
+
+        SpeedtestService::record_history(&[EndpointLatency {
+            url: url.into(),
+            latency: None,
+            status: None,
+            error: Some("连接失败".into()),
+            rate_limit: None,
+        }]);
+        let probe = SpeedtestService::latest_probe(url).expect("probe recorded");
+        assert!(probe.latency.is_none());
+        assert_eq!(probe.error.as_deref(), Some("连接失败"));
+        // 成功记录仍保留在历史中
+        assert_eq!(SpeedtestService::latest_latency(url), Some(120));
+    }
+
+    #[test]
+    fn extract_rate_limit_reads_known_and_relay_headers() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-ratelimit-limit-requests",
+            HeaderValue::from_static("500"),
+        );
+        headers.insert(
+            "x-ratelimit-remaining-requests",
+            HeaderValue::from_static("499"),
+        );
+        headers.insert(
+            "anthropic-ratelimit-tokens-remaining",
+            HeaderValue::from_static("78000"),
+        );
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+        headers.insert("x-relay-quota-remaining", HeaderValue::from_static("$12.5"));
+        headers.insert("content-type", HeaderValue::from_static("text/html"));
+
+        let info = extract_rate_limit(&headers).expect("rate limit headers");
+        assert_eq!(info.requests_limit, Some(500));
+        assert_eq!(info.requests_remaining, Some(499));
+        assert_eq!(info.tokens_remaining, Some(78000));
+        assert_eq!(info.retry_after.as_deref(), Some("30"));
+        assert_eq!(
+            info.headers
+                .get("x-relay-quota-remaining")
+                .map(String::as_str),
+            Some("$12.5")
+        );
+        assert!(!info.headers.contains_key("content-type"));
+
+        assert!(extract_rate_limit(&HeaderMap::new()).is_none());
+    }
+
     #[test]
     fn test_endpoints_handles_empty_list() {
         let result =
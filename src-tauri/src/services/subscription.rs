@@ -0,0 +1,271 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::provider::{Provider, ProviderMeta, SubscriptionOrigin};
+use crate::services::config::{fetch_text_capped, is_loopback_http_url};
+use crate::services::ProviderService;
+use crate::settings;
+use crate::store::AppState;
+use crate::subscription::{self, SubscribedProvider, SubscriptionSource};
+
+/// 拉取订阅清单的超时时间
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// 订阅清单大小上限
+const MAX_MANIFEST_BYTES: usize = 1024 * 1024;
+
+/// 订阅预设（前端展示为可安装的供应商）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionPreset {
+    /// `<订阅源 ID>/<条目 ID>`，用于安装
+    pub id: String,
+    pub source_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_name: Option<String>,
+    #[serde(flatten)]
+    pub provider: SubscribedProvider,
+    /// 已安装时对应的本地供应商 ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_provider_id: Option<String>,
+    /// 已安装且上游定义已变化
+    pub update_available: bool,
+}
+
+/// 订阅源（团队共享的只读供应商清单）
+pub struct SubscriptionService;
+
+impl SubscriptionService {
+    /// 添加订阅源并立即拉取一次
+    pub async fn add(url: &str) -> Result<SubscriptionSource, AppError> {
+        let url = url.trim();
+        ensure_secure_url(url)?;
+        if settings::get_settings()
+            .subscriptions
+            .iter()
+            .any(|source| source.url == url)
+        {
+            return Err(AppError::localized(
+                "subscription.exists",
+                format!("订阅已存在: {url}"),
+                format!("Subscription already exists: {url}"),
+            ));
+        }
+
+        // 首次添加时拉取失败直接报错，避免保存无效地址
+        let text = fetch_manifest(url).await?;
+        let (name, providers) = subscription::parse_manifest(&text)?;
+        let source = SubscriptionSource {
+            id: format!("sub-{}", chrono::Utc::now().timestamp_millis()),
+            url: url.to_string(),
+            name,
+            last_fetched_at: Some(chrono::Utc::now().timestamp_millis()),
+            last_error: None,
+            providers,
+        };
+
+        let mut current = settings::get_settings();
+        current.subscriptions.push(source.clone());
+        settings::update_settings(current)?;
+        Ok(source)
+    }
+
+    /// 删除订阅源（已安装的供应商保留）
+    pub fn remove(id: &str) -> Result<bool, AppError> {
+        let mut current = settings::get_settings();
+        let before = current.subscriptions.len();
+        current.subscriptions.retain(|source| source.id != id);
+        if current.subscriptions.len() == before {
+            return Ok(false);
+        }
+        settings::update_settings(current)?;
+        Ok(true)
+    }
+
+    /// 重新拉取全部订阅源；单个源失败时保留上次的清单并记录错误
+    pub async fn refresh(state: &AppState) -> Result<Vec<SubscriptionPreset>, AppError> {
+        let sources = settings::get_settings().subscriptions;
+        if sources.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut refreshed = Vec::with_capacity(sources.len());
+        for mut source in sources {
+            let result = match fetch_manifest(&source.url).await {
+                Ok(text) => subscription::parse_manifest(&text),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok((name, providers)) => {
+                    source.name = name.or(source.name);
+                    source.providers = providers;
+                    source.last_fetched_at = Some(chrono::Utc::now().timestamp_millis());
+                    source.last_error = None;
+                }
+                Err(e) => {
+                    log::warn!("拉取订阅 {} 失败: {e}", source.url);
+                    source.last_error = Some(e.to_string());
+                }
+            }
+            refreshed.push((source.id.clone(), source));
+        }
+
+        // 拉取期间订阅列表可能被修改：只更新仍存在的订阅源
+        let mut current = settings::get_settings();
+        for source in current.subscriptions.iter_mut() {
+            if let Some((_, fresh)) = refreshed.iter().find(|(id, _)| *id == source.id) {
+                *source = fresh.clone();
+            }
+        }
+        settings::update_settings(current)?;
+
+        Self::presets(state)
+    }
+
+    /// 列出全部订阅预设，并标注安装与更新状态
+    pub fn presets(state: &AppState) -> Result<Vec<SubscriptionPreset>, AppError> {
+        let mut presets = Vec::new();
+        for source in settings::get_settings().subscriptions {
+            for provider in source.providers {
+                let installed = Self::find_installed(state, &source.id, &provider)?;
+                presets.push(SubscriptionPreset {
+                    id: format!("{}/{}", source.id, provider.id),
+                    source_id: source.id.clone(),
+                    source_name: source.name.clone(),
+                    update_available: installed
+                        .as_ref()
+                        .and_then(|p| p.meta.as_ref()?.subscription.as_ref())
+                        .is_some_and(|origin| origin.revision != provider.revision),
+                    installed_provider_id: installed.map(|p| p.id),
+                    provider,
+                });
+            }
+        }
+        Ok(presets)
+    }
+
+    /// 安装订阅中的供应商；已安装时按上游定义更新（保留本地填写的密钥等字段），返回本地供应商 ID
+    pub fn install(state: &AppState, preset_id: &str) -> Result<String, AppError> {
+        let not_found = || {
+            AppError::localized(
+                "subscription.provider_not_found",
+                format!("订阅中不存在该供应商: {preset_id}"),
+                format!("Provider not found in subscriptions: {preset_id}"),
+            )
+        };
+        let (source_id, entry_id) = preset_id.split_once('/').ok_or_else(not_found)?;
+        let upstream = settings::get_settings()
+            .subscriptions
+            .into_iter()
+            .find(|source| source.id == source_id)
+            .and_then(|source| source.providers.into_iter().find(|p| p.id == entry_id))
+            .ok_or_else(not_found)?;
+        let origin = SubscriptionOrigin {
+            source_id: source_id.to_string(),
+            entry_id: upstream.id.clone(),
+            revision: upstream.revision.clone(),
+        };
+
+        if let Some(mut existing) = Self::find_installed(state, source_id, &upstream)? {
+            subscription::merge_upstream(&mut existing.settings_config, &upstream.settings_config);
+            existing.name = upstream.name;
+            existing.website_url = upstream.website_url;
+            existing.category = upstream.category;
+            existing.notes = upstream.notes.or(existing.notes);
+            existing
+                .meta
+                .get_or_insert_with(ProviderMeta::default)
+                .subscription = Some(origin);
+            // 订阅更新不做并发修改检查
+            existing.updated_at = None;
+            let id = existing.id.clone();
+            ProviderService::update(state, upstream.app, existing)?;
+            return Ok(id);
+        }
+
+        let taken = ProviderService::list(state, upstream.app.clone())?;
+        let id = if taken.contains_key(&upstream.id) {
+            format!("{}-{}", upstream.id, chrono::Utc::now().timestamp_millis())
+        } else {
+            upstream.id.clone()
+        };
+        let provider = Provider {
+            id: id.clone(),
+            name: upstream.name,
+            settings_config: upstream.settings_config,
+            website_url: upstream.website_url,
+            category: upstream.category,
+            created_at: Some(chrono::Utc::now().timestamp_millis()),
+            sort_index: None,
+            notes: upstream.notes,
+            meta: Some(ProviderMeta {
+                subscription: Some(origin),
+                ..ProviderMeta::default()
+            }),
+            auth_kind: None,
+            updated_at: None,
+            pinned: false,
+            icon: None,
+            icon_color: None,
+        };
+        ProviderService::add(state, upstream.app, provider)?;
+        Ok(id)
+    }
+
+    fn find_installed(
+        state: &AppState,
+        source_id: &str,
+        upstream: &SubscribedProvider,
+    ) -> Result<Option<Provider>, AppError> {
+        let providers = ProviderService::list(state, upstream.app.clone())?;
+        Ok(providers.into_values().find(|provider| {
+            provider
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.subscription.as_ref())
+                .is_some_and(|origin| {
+                    origin.source_id == source_id && origin.entry_id == upstream.id
+                })
+        }))
+    }
+}
+
+/// 是否存在订阅源（定时任务据此跳过拉取）
+pub fn has_subscriptions() -> bool {
+    !settings::get_settings().subscriptions.is_empty()
+}
+
+/// 订阅地址必须为 https；仅本机回环地址允许 http（便于本地调试清单）
+fn ensure_secure_url(url: &str) -> Result<(), AppError> {
+    if url.starts_with("https://") || is_loopback_http_url(url) {
+        return Ok(());
+    }
+    Err(AppError::localized(
+        "subscription.invalid_url",
+        format!("订阅地址必须以 https:// 开头（仅本机地址可使用 http://）: {url}"),
+        format!(
+            "Subscription URL must start with https:// (http:// is only allowed for localhost): {url}"
+        ),
+    ))
+}
+
+/// 拉取订阅清单；早期版本可能保存了远程 http 地址，拉取前同样校验
+async fn fetch_manifest(url: &str) -> Result<String, AppError> {
+    ensure_secure_url(url)?;
+    let failed = |detail: String| {
+        AppError::localized(
+            "subscription.fetch_failed",
+            format!("拉取订阅清单失败: {detail}"),
+            format!("Failed to fetch subscription manifest: {detail}"),
+        )
+    };
+    fetch_text_capped(
+        url,
+        MAX_MANIFEST_BYTES,
+        Duration::from_secs(FETCH_TIMEOUT_SECS),
+    )
+    .await
+    .map_err(failed)
+}
@@ -0,0 +1,143 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::AppError;
+use crate::settings;
+use crate::store::AppState;
+use crate::switch_rule::{parse_clock, SwitchRule, SwitchRuleCondition};
+
+/// 自动切换规则相关业务逻辑
+pub struct SwitchRuleService;
+
+impl SwitchRuleService {
+    /// 列出所有规则（保持匹配顺序）
+    pub fn list() -> Vec<SwitchRule> {
+        settings::get_settings().switch_rules
+    }
+
+    /// 新增或更新规则（id 为空时自动生成）
+    pub fn upsert(state: &AppState, rule: SwitchRule) -> Result<SwitchRule, AppError> {
+        let mut rule = rule;
+        rule.id = rule.id.trim().to_string();
+        if rule.id.is_empty() {
+            let millis = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            rule.id = format!("rule-{millis}");
+        }
+        Self::validate(state, &rule)?;
+
+        let mut app_settings = settings::get_settings();
+        match app_settings
+            .switch_rules
+            .iter_mut()
+            .find(|existing| existing.id == rule.id)
+        {
+            Some(existing) => *existing = rule.clone(),
+            None => app_settings.switch_rules.push(rule.clone()),
+        }
+        settings::update_settings(app_settings)?;
+        Ok(rule)
+    }
+
+    /// 删除规则
+    pub fn delete(id: &str) -> Result<bool, AppError> {
+        let mut app_settings = settings::get_settings();
+        let before = app_settings.switch_rules.len();
+        app_settings.switch_rules.retain(|rule| rule.id != id);
+        if app_settings.switch_rules.len() == before {
+            return Ok(false);
+        }
+        settings::update_settings(app_settings)?;
+        Ok(true)
+    }
+
+    /// 启用或停用自动切换
+    pub fn set_enabled(enabled: bool) -> Result<(), AppError> {
+        let mut app_settings = settings::get_settings();
+        app_settings.switch_rules_enabled = enabled;
+        settings::update_settings(app_settings)
+    }
+
+    fn validate(state: &AppState, rule: &SwitchRule) -> Result<(), AppError> {
+        {
+            let config = state.config.read().map_err(AppError::from)?;
+            let exists = config
+                .get_manager(&rule.app)
+                .map(|manager| manager.providers.contains_key(&rule.provider_id))
+                .unwrap_or(false);
+            if !exists {
+                return Err(AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {}", rule.provider_id),
+                    format!("Provider not found: {}", rule.provider_id),
+                ));
+            }
+        }
+
+        match &rule.condition {
+            SwitchRuleCondition::TimeWindow { start, end, days } => {
+                for value in [start, end] {
+                    if parse_clock(value).is_none() {
+                        return Err(AppError::localized(
+                            "switch_rule.invalid_time",
+                            format!("无效的时间格式（应为 HH:MM）: {value}"),
+                            format!("Invalid time format (expected HH:MM): {value}"),
+                        ));
+                    }
+                }
+                if start.trim() == end.trim() {
+                    return Err(AppError::localized(
+                        "switch_rule.empty_window",
+                        "开始时间与结束时间不能相同",
+                        "Start and end time must differ",
+                    ));
+                }
+                if let Some(day) = days.iter().find(|day| !(1..=7).contains(*day)) {
+                    return Err(AppError::localized(
+                        "switch_rule.invalid_day",
+                        format!("无效的星期编号（应为 1-7）: {day}"),
+                        format!("Invalid weekday (expected 1-7): {day}"),
+                    ));
+                }
+            }
+            SwitchRuleCondition::Network {
+                ssid,
+                gateway,
+                interface,
+                vpn,
+            } => {
+                let has_text =
+                    |value: &Option<String>| value.as_deref().is_some_and(|v| !v.trim().is_empty());
+                if !has_text(ssid) && !has_text(gateway) && !has_text(interface) && vpn.is_none() {
+                    return Err(AppError::localized(
+                        "switch_rule.empty_network",
+                        "网络条件至少需要填写 SSID、网关、接口或 VPN 状态中的一项",
+                        "Network condition requires at least one of SSID, gateway, interface or VPN state",
+                    ));
+                }
+            }
+            SwitchRuleCondition::QuotaBelow {
+                threshold,
+                resume_above,
+            } => {
+                if !threshold.is_finite() || *threshold < 0.0 {
+                    return Err(AppError::localized(
+                        "switch_rule.invalid_quota",
+                        format!("无效的额度阈值: {threshold}"),
+                        format!("Invalid quota threshold: {threshold}"),
+                    ));
+                }
+                if resume_above.is_some_and(|resume| !resume.is_finite() || resume < *threshold) {
+                    return Err(AppError::localized(
+                        "switch_rule.invalid_quota_resume",
+                        "恢复阈值不能低于触发阈值",
+                        "Resume threshold must not be lower than the trigger threshold",
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
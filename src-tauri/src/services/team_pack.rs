@@ -0,0 +1,830 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::app_config::{AppType, McpServer, MultiAppConfig};
+use crate::error::AppError;
+use crate::prompt::Prompt;
+use crate::provider::Provider;
+use crate::services::passcode::{clear_secrets, is_secret_key};
+use crate::services::share::shareable;
+use crate::services::skill::SkillRepo;
+use crate::services::{McpService, PromptService, ProviderService};
+use crate::settings;
+use crate::store::AppState;
+
+/// 团队包格式版本
+const TEAM_PACK_VERSION: u32 = 1;
+
+/// 团队包可携带的推荐设置（不含路径、密钥、远程主机等仅对本机有意义的项）
+const RECOMMENDED_SETTINGS: [&str; 13] = [
+    "language",
+    "claudeSettingsMerge",
+    "claudeManagedKeys",
+    "codexMcpCommentDisabled",
+    "geminiEnvReplace",
+    "mcpSwitchSync",
+    "writeEnvFiles",
+    "verifyLiveWrites",
+    "switchLatencyWarningMs",
+    "displayCurrency",
+    "exchangeRates",
+    "registryUrl",
+    "rankingWeights",
+];
+
+/// 指定应用下的某一项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamPackItemRef {
+    pub app: AppType,
+    pub id: String,
+}
+
+/// `create_team_pack` 的打包范围
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamPackSelection {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub providers: Vec<TeamPackItemRef>,
+    #[serde(default)]
+    pub mcp_servers: Vec<String>,
+    #[serde(default)]
+    pub prompts: Vec<TeamPackItemRef>,
+    /// 包含技能仓库与已安装技能的引用
+    #[serde(default)]
+    pub include_skills: bool,
+    /// 包含推荐设置
+    #[serde(default)]
+    pub include_settings: bool,
+}
+
+/// 团队包清单（`manifest.json`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamPackManifest {
+    pub version: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 创建时间（Unix 毫秒）
+    pub created_at: i64,
+    #[serde(default)]
+    pub app_version: String,
+    #[serde(default)]
+    pub providers: usize,
+    #[serde(default)]
+    pub mcp_servers: usize,
+    #[serde(default)]
+    pub prompts: usize,
+    #[serde(default)]
+    pub skills: usize,
+    #[serde(default)]
+    pub settings: usize,
+}
+
+/// 已安装技能的引用（技能文件不打包，由接收方从仓库安装）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamPackSkill {
+    pub directory: String,
+    pub apps: Vec<AppType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackedProvider {
+    app: AppType,
+    provider: Provider,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackedPrompt {
+    app: AppType,
+    prompt: Prompt,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackedSkills {
+    #[serde(default)]
+    repos: Vec<SkillRepo>,
+    #[serde(default)]
+    installed: Vec<TeamPackSkill>,
+}
+
+/// 团队包内容（zip 中每部分一个 JSON 文件）
+struct TeamPack {
+    manifest: TeamPackManifest,
+    providers: Vec<PackedProvider>,
+    mcp_servers: Vec<McpServer>,
+    prompts: Vec<PackedPrompt>,
+    skills: PackedSkills,
+    settings: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TeamPackItemKind {
+    Provider,
+    Mcp,
+    Prompt,
+    Settings,
+}
+
+/// 团队包中的一项及其与本地配置的冲突情况
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamPackItem {
+    /// 冲突处理时引用的键：`provider:<app>:<id>`、`mcp:<id>`、`prompt:<app>:<id>`、`settings`
+    pub key: String,
+    pub kind: TeamPackItemKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<AppType>,
+    pub id: String,
+    pub name: String,
+    /// 本地已有同 ID 的项（推荐设置为与本地取值不同）
+    pub conflict: bool,
+}
+
+/// `inspect_team_pack` 的返回值
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamPackPreview {
+    pub manifest: TeamPackManifest,
+    pub items: Vec<TeamPackItem>,
+    pub skill_repos: Vec<SkillRepo>,
+    pub skills: Vec<TeamPackSkill>,
+}
+
+/// 冲突项的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TeamPackResolution {
+    /// 保留本地
+    Skip,
+    /// 用团队包覆盖（本地已填写的密钥会保留）
+    Overwrite,
+    /// 以新 ID 另存一份（推荐设置不支持，按跳过处理）
+    KeepBoth,
+}
+
+/// `apply_team_pack` 的返回值（各列表为项的键）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamPackApplyResult {
+    pub installed: Vec<String>,
+    pub overwritten: Vec<String>,
+    /// 以新 ID 另存的项（原键 -> 新 ID）
+    pub renamed: HashMap<String, String>,
+    pub skipped: Vec<String>,
+    /// 存在冲突但未指定处理方式的项（已跳过，需提示用户选择后重新应用）
+    pub unresolved: Vec<String>,
+    /// 新增的技能仓库数
+    pub added_skill_repos: usize,
+    /// 本地尚未安装的技能（需逐个安装）
+    pub pending_skills: Vec<TeamPackSkill>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Install,
+    Overwrite,
+    Rename,
+    Skip,
+    Unresolved,
+}
+
+impl TeamPackApplyResult {
+    /// 记录未安装的项，返回是否需要继续安装
+    fn proceed(&mut self, key: &str, action: Action) -> bool {
+        match action {
+            Action::Skip => self.skipped.push(key.to_string()),
+            Action::Unresolved => self.unresolved.push(key.to_string()),
+            _ => return true,
+        }
+        false
+    }
+
+    fn record(&mut self, key: String, action: Action, id: &str) {
+        match action {
+            Action::Overwrite => self.overwritten.push(key),
+            Action::Rename => {
+                self.renamed.insert(key, id.to_string());
+            }
+            _ => self.installed.push(key),
+        }
+    }
+}
+
+/// 团队包：将供应商（不含密钥）、MCP 服务器、提示词、技能引用与推荐设置打包为单个 zip，
+/// 便于新成员一步完成配置
+pub struct TeamPackService;
+
+impl TeamPackService {
+    /// 按选择打包并写入 `path`
+    pub fn create(
+        state: &AppState,
+        selection: &TeamPackSelection,
+        path: &Path,
+    ) -> Result<TeamPackManifest, AppError> {
+        let pack = {
+            let cfg = state.config.read()?;
+            let mut providers = Vec::with_capacity(selection.providers.len());
+            for item in &selection.providers {
+                let provider = cfg
+                    .get_manager(&item.app)
+                    .and_then(|manager| manager.providers.get(&item.id))
+                    .cloned()
+                    .ok_or_else(|| not_found("provider", &item.id))?;
+                providers.push(PackedProvider {
+                    app: item.app.clone(),
+                    provider: without_secrets(shareable(provider))?,
+                });
+            }
+
+            let mut mcp_servers = Vec::with_capacity(selection.mcp_servers.len());
+            for id in &selection.mcp_servers {
+                let mut server = cfg
+                    .mcp
+                    .servers
+                    .as_ref()
+                    .and_then(|servers| servers.get(id))
+                    .cloned()
+                    .ok_or_else(|| not_found("mcp", id))?;
+                server.health = None;
+                clear_secrets(&mut server.server);
+                let overrides = &mut server.overrides;
+                for patch in [
+                    &mut overrides.claude,
+                    &mut overrides.codex,
+                    &mut overrides.gemini,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    clear_secrets(patch);
+                }
+                mcp_servers.push(server);
+            }
+
+            let mut prompts = Vec::with_capacity(selection.prompts.len());
+            for item in &selection.prompts {
+                let mut prompt = prompts_of(&cfg, &item.app)
+                    .get(&item.id)
+                    .cloned()
+                    .ok_or_else(|| not_found("prompt", &item.id))?;
+                prompt.enabled = false;
+                prompts.push(PackedPrompt {
+                    app: item.app.clone(),
+                    prompt,
+                });
+            }
+
+            let skills = if selection.include_skills {
+                let mut installed: Vec<TeamPackSkill> = cfg
+                    .skills
+                    .skills
+                    .iter()
+                    .filter(|(_, skill)| skill.installed)
+                    .map(|(directory, skill)| TeamPackSkill {
+                        directory: directory.clone(),
+                        apps: skill.apps.clone(),
+                    })
+                    .collect();
+                installed.sort_by(|a, b| a.directory.cmp(&b.directory));
+                PackedSkills {
+                    repos: cfg.skills.repos.clone(),
+                    installed,
+                }
+            } else {
+                PackedSkills::default()
+            };
+
+            let settings = if selection.include_settings {
+                recommended_settings(&current_settings()?)
+            } else {
+                Map::new()
+            };
+
+            TeamPack {
+                manifest: TeamPackManifest {
+                    version: TEAM_PACK_VERSION,
+                    name: selection.name.trim().to_string(),
+                    description: selection.description.clone(),
+                    created_at: chrono::Utc::now().timestamp_millis(),
+                    app_version: env!("CARGO_PKG_VERSION").to_string(),
+                    providers: providers.len(),
+                    mcp_servers: mcp_servers.len(),
+                    prompts: prompts.len(),
+                    skills: skills.installed.len(),
+                    settings: settings.len(),
+                },
+                providers,
+                mcp_servers,
+                prompts,
+                skills,
+                settings,
+            }
+        };
+
+        write_pack(path, &pack)?;
+        Ok(pack.manifest)
+    }
+
+    /// 读取团队包并列出各项与本地配置的冲突
+    pub fn inspect(state: &AppState, path: &Path) -> Result<TeamPackPreview, AppError> {
+        let pack = read_pack(path)?;
+        let items = {
+            let cfg = state.config.read()?;
+            pack_items(&cfg, &pack, &current_settings()?)
+        };
+        Ok(TeamPackPreview {
+            manifest: pack.manifest,
+            items,
+            skill_repos: pack.skills.repos,
+            skills: pack.skills.installed,
+        })
+    }
+
+    /// 安装团队包：无冲突的项直接安装，冲突项按 `resolutions`（键见 [`TeamPackItem::key`]）处理
+    pub fn apply(
+        state: &AppState,
+        path: &Path,
+        resolutions: &HashMap<String, TeamPackResolution>,
+    ) -> Result<TeamPackApplyResult, AppError> {
+        let pack = read_pack(path)?;
+        let mut result = TeamPackApplyResult::default();
+
+        for PackedProvider { app, mut provider } in pack.providers {
+            let key = item_key("provider", Some(&app), &provider.id);
+            let (local, taken) = {
+                let cfg = state.config.read()?;
+                let providers = cfg.get_manager(&app).map(|manager| &manager.providers);
+                let local = providers.and_then(|providers| providers.get(&provider.id).cloned());
+                let taken: Vec<String> = providers
+                    .map(|providers| providers.keys().cloned().collect())
+                    .unwrap_or_default();
+                (local, taken)
+            };
+            let action = action_for(&key, local.is_some(), resolutions);
+            if !result.proceed(&key, action) {
+                continue;
+            }
+            match (action, local) {
+                (Action::Overwrite, Some(local)) => {
+                    provider = with_local_secrets(provider, &local)?;
+                    provider.sort_index = local.sort_index;
+                }
+                (Action::Rename, _) => {
+                    provider.id = unique_id(&provider.id, |id| taken.iter().any(|t| t == id));
+                    provider.sort_index = None;
+                }
+                _ => {}
+            }
+            let id = provider.id.clone();
+            ProviderService::add(state, app, provider)?;
+            result.record(key, action, &id);
+        }
+
+        for mut server in pack.mcp_servers {
+            let key = item_key("mcp", None, &server.id);
+            let (local, taken) = {
+                let cfg = state.config.read()?;
+                let servers = cfg.mcp.servers.as_ref();
+                let local = servers.and_then(|servers| servers.get(&server.id).cloned());
+                let taken: Vec<String> = servers
+                    .map(|servers| servers.keys().cloned().collect())
+                    .unwrap_or_default();
+                (local, taken)
+            };
+            let action = action_for(&key, local.is_some(), resolutions);
+            if !result.proceed(&key, action) {
+                continue;
+            }
+            match (action, local) {
+                (Action::Overwrite, Some(local)) => {
+                    restore_secrets(&mut server.server, &local.server);
+                    let (ours, theirs) = (&mut server.overrides, &local.overrides);
+                    for (patch, local_patch) in [
+                        (&mut ours.claude, &theirs.claude),
+                        (&mut ours.codex, &theirs.codex),
+                        (&mut ours.gemini, &theirs.gemini),
+                    ] {
+                        if let (Some(patch), Some(local_patch)) = (patch, local_patch) {
+                            restore_secrets(patch, local_patch);
+                        }
+                    }
+                }
+                (Action::Rename, _) => {
+                    server.id = unique_id(&server.id, |id| taken.iter().any(|t| t == id));
+                }
+                _ => {}
+            }
+            let id = server.id.clone();
+            McpService::upsert_server(state, server)?;
+            result.record(key, action, &id);
+        }
+
+        for PackedPrompt { app, mut prompt } in pack.prompts {
+            let key = item_key("prompt", Some(&app), &prompt.id);
+            let (local, taken) = {
+                let cfg = state.config.read()?;
+                let prompts = prompts_of(&cfg, &app);
+                (
+                    prompts.get(&prompt.id).cloned(),
+                    prompts.keys().cloned().collect::<Vec<_>>(),
+                )
+            };
+            let action = action_for(&key, local.is_some(), resolutions);
+            if !result.proceed(&key, action) {
+                continue;
+            }
+            // 新增的提示词不启用；覆盖时沿用本地的启用状态（启用中的会同步写入提示词文件）
+            prompt.enabled = match (action, &local) {
+                (Action::Overwrite, Some(local)) => local.enabled,
+                _ => false,
+            };
+            if action == Action::Rename {
+                prompt.id = unique_id(&prompt.id, |id| taken.iter().any(|t| t == id));
+            }
+            prompt.updated_at = Some(chrono::Utc::now().timestamp_millis());
+            let id = prompt.id.clone();
+            PromptService::upsert_prompt(state, app, &id, prompt)?;
+            result.record(key, action, &id);
+        }
+
+        if !pack.settings.is_empty() {
+            let key = item_key("settings", None, "");
+            let current = current_settings()?;
+            let action =
+                match action_for(&key, settings_differ(&pack.settings, &current), resolutions) {
+                    Action::Rename => Action::Skip,
+                    action => action,
+                };
+            if result.proceed(&key, action) {
+                apply_settings(current, &pack.settings)?;
+                result.record(key, action, "");
+            }
+        }
+
+        let (added_skill_repos, pending_skills) = {
+            let mut cfg = state.config.write()?;
+            let mut added = 0;
+            for repo in pack.skills.repos {
+                let exists = cfg.skills.repos.iter().any(|r| {
+                    r.owner.eq_ignore_ascii_case(&repo.owner)
+                        && r.name.eq_ignore_ascii_case(&repo.name)
+                });
+                if !exists {
+                    cfg.skills.repos.push(repo);
+                    added += 1;
+                }
+            }
+            let pending: Vec<TeamPackSkill> = pack
+                .skills
+                .installed
+                .into_iter()
+                .filter(|skill| {
+                    !cfg.skills
+                        .skills
+                        .get(&skill.directory)
+                        .is_some_and(|local| {
+                            local.installed && skill.apps.iter().all(|app| local.apps.contains(app))
+                        })
+                })
+                .collect();
+            (added, pending)
+        };
+        if added_skill_repos > 0 {
+            state.save()?;
+        }
+        result.added_skill_repos = added_skill_repos;
+        result.pending_skills = pending_skills;
+        Ok(result)
+    }
+}
+
+fn item_key(kind: &str, app: Option<&AppType>, id: &str) -> String {
+    match (app, id.is_empty()) {
+        (_, true) => kind.to_string(),
+        (Some(app), false) => format!("{kind}:{}:{id}", app.as_str()),
+        (None, false) => format!("{kind}:{id}"),
+    }
+}
+
+fn action_for(
+    key: &str,
+    conflict: bool,
+    resolutions: &HashMap<String, TeamPackResolution>,
+) -> Action {
+    if !conflict {
+        return Action::Install;
+    }
+    match resolutions.get(key) {
+        Some(TeamPackResolution::Skip) => Action::Skip,
+        Some(TeamPackResolution::Overwrite) => Action::Overwrite,
+        Some(TeamPackResolution::KeepBoth) => Action::Rename,
+        None => Action::Unresolved,
+    }
+}
+
+/// 生成未被占用的 ID：`<id>-team`、`<id>-team-2`……
+fn unique_id(base: &str, taken: impl Fn(&str) -> bool) -> String {
+    let mut candidate = format!("{base}-team");
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{base}-team-{n}");
+        n += 1;
+    }
+    candidate
+}
+
+fn prompts_of<'a>(cfg: &'a MultiAppConfig, app: &AppType) -> &'a HashMap<String, Prompt> {
+    match app {
+        AppType::Claude => &cfg.prompts.claude.prompts,
+        AppType::Codex => &cfg.prompts.codex.prompts,
+        AppType::Gemini => &cfg.prompts.gemini.prompts,
+    }
+}
+
+fn pack_items(cfg: &MultiAppConfig, pack: &TeamPack, settings: &Value) -> Vec<TeamPackItem> {
+    let mut items = Vec::new();
+    for PackedProvider { app, provider } in &pack.providers {
+        items.push(TeamPackItem {
+            key: item_key("provider", Some(app), &provider.id),
+            kind: TeamPackItemKind::Provider,
+            app: Some(app.clone()),
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            conflict: cfg
+                .get_manager(app)
+                .is_some_and(|manager| manager.providers.contains_key(&provider.id)),
+        });
+    }
+    for server in &pack.mcp_servers {
+        items.push(TeamPackItem {
+            key: item_key("mcp", None, &server.id),
+            kind: TeamPackItemKind::Mcp,
+            app: None,
+            id: server.id.clone(),
+            name: server.name.clone(),
+            conflict: cfg
+                .mcp
+                .servers
+                .as_ref()
+                .is_some_and(|servers| servers.contains_key(&server.id)),
+        });
+    }
+    for PackedPrompt { app, prompt } in &pack.prompts {
+        items.push(TeamPackItem {
+            key: item_key("prompt", Some(app), &prompt.id),
+            kind: TeamPackItemKind::Prompt,
+            app: Some(app.clone()),
+            id: prompt.id.clone(),
+            name: prompt.name.clone(),
+            conflict: prompts_of(cfg, app).contains_key(&prompt.id),
+        });
+    }
+    if !pack.settings.is_empty() {
+        items.push(TeamPackItem {
+            key: item_key("settings", None, ""),
+            kind: TeamPackItemKind::Settings,
+            app: None,
+            id: String::new(),
+            name: pack.settings.keys().cloned().collect::<Vec<_>>().join(", "),
+            conflict: settings_differ(&pack.settings, settings),
+        });
+    }
+    items
+}
+
+fn current_settings() -> Result<Value, AppError> {
+    serde_json::to_value(settings::get_settings())
+        .map_err(|source| AppError::JsonSerialize { source })
+}
+
+fn recommended_settings(settings: &Value) -> Map<String, Value> {
+    RECOMMENDED_SETTINGS
+        .iter()
+        .filter_map(|key| {
+            settings
+                .get(*key)
+                .filter(|value| !value.is_null())
+                .map(|value| (key.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+fn settings_differ(pack: &Map<String, Value>, settings: &Value) -> bool {
+    pack.iter()
+        .any(|(key, value)| settings.get(key) != Some(value))
+}
+
+/// 只写入白名单内的设置项
+fn apply_settings(mut current: Value, pack: &Map<String, Value>) -> Result<(), AppError> {
+    if let Some(map) = current.as_object_mut() {
+        for (key, value) in pack {
+            if RECOMMENDED_SETTINGS.contains(&key.as_str()) {
+                map.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    let updated = serde_json::from_value(current).map_err(|e| {
+        AppError::localized(
+            "team_pack.invalid_settings",
+            format!("团队包中的推荐设置无效: {e}"),
+            format!("Invalid recommended settings in team pack: {e}"),
+        )
+    })?;
+    settings::update_settings(updated)
+}
+
+/// 清空供应商中的全部密钥（配置、请求头、用量脚本等）
+fn without_secrets(provider: Provider) -> Result<Provider, AppError> {
+    let mut value =
+        serde_json::to_value(provider).map_err(|source| AppError::JsonSerialize { source })?;
+    clear_secrets(&mut value);
+    serde_json::from_value(value).map_err(|source| AppError::JsonSerialize { source })
+}
+
+/// 覆盖本地供应商时沿用本地已填写的密钥
+fn with_local_secrets(provider: Provider, local: &Provider) -> Result<Provider, AppError> {
+    let to_value =
+        |p: &Provider| serde_json::to_value(p).map_err(|source| AppError::JsonSerialize { source });
+    let mut value = to_value(&provider)?;
+    restore_secrets(&mut value, &to_value(local)?);
+    serde_json::from_value(value).map_err(|source| AppError::JsonSerialize { source })
+}
+
+/// 将 `local` 中同路径的密钥填回 `target` 里为空的密钥字段
+fn restore_secrets(target: &mut Value, local: &Value) {
+    let (Some(target), Some(local)) = (target.as_object_mut(), local.as_object()) else {
+        return;
+    };
+    for (key, child) in target.iter_mut() {
+        let Some(local_child) = local.get(key) else {
+            continue;
+        };
+        match child {
+            Value::String(text) if text.is_empty() && is_secret_key(key) => {
+                if let Some(secret) = local_child.as_str() {
+                    *text = secret.to_string();
+                }
+            }
+            _ => restore_secrets(child, local_child),
+        }
+    }
+}
+
+fn write_pack(path: &Path, pack: &TeamPack) -> Result<(), AppError> {
+    fn to_json<T: Serialize>(value: &T) -> Result<String, AppError> {
+        serde_json::to_string_pretty(value).map_err(|source| AppError::JsonSerialize { source })
+    }
+    let entries = [
+        ("manifest.json", to_json(&pack.manifest)?),
+        ("providers.json", to_json(&pack.providers)?),
+        ("mcp.json", to_json(&pack.mcp_servers)?),
+        ("prompts.json", to_json(&pack.prompts)?),
+        ("skills.json", to_json(&pack.skills)?),
+        ("settings.json", to_json(&pack.settings)?),
+    ];
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    let file = File::create(path).map_err(|e| AppError::io(path, e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    for (name, content) in entries {
+        zip.start_file(name, options)
+            .map_err(|e| pack_error(path, e))?;
+        zip.write_all(content.as_bytes())
+            .map_err(|e| AppError::io(path, e))?;
+    }
+    zip.finish().map_err(|e| pack_error(path, e))?;
+    Ok(())
+}
+
+fn read_pack(path: &Path) -> Result<TeamPack, AppError> {
+    let file = File::open(path).map_err(|e| AppError::io(path, e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| pack_error(path, e))?;
+    let manifest: TeamPackManifest = read_entry(&mut archive, path, "manifest.json")?
+        .ok_or_else(|| pack_error(path, "missing manifest.json"))?;
+    if manifest.version > TEAM_PACK_VERSION {
+        return Err(AppError::localized(
+            "team_pack.unsupported_version",
+            format!("团队包版本 {} 过新，请升级应用后再导入", manifest.version),
+            format!(
+                "Team pack version {} is newer than supported; please update the app",
+                manifest.version
+            ),
+        ));
+    }
+    Ok(TeamPack {
+        manifest,
+        providers: read_entry(&mut archive, path, "providers.json")?.unwrap_or_default(),
+        mcp_servers: read_entry(&mut archive, path, "mcp.json")?.unwrap_or_default(),
+        prompts: read_entry(&mut archive, path, "prompts.json")?.unwrap_or_default(),
+        skills: read_entry(&mut archive, path, "skills.json")?.unwrap_or_default(),
+        settings: read_entry(&mut archive, path, "settings.json")?.unwrap_or_default(),
+    })
+}
+
+/// 读取并解析 zip 中的 JSON 文件，不存在时返回 `None`
+fn read_entry<T: DeserializeOwned>(
+    archive: &mut ZipArchive<File>,
+    path: &Path,
+    name: &str,
+) -> Result<Option<T>, AppError> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(pack_error(path, e)),
+    };
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| AppError::io(path, e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|source| AppError::Json {
+            path: format!("{}!{name}", path.display()),
+            source,
+        })
+}
+
+fn pack_error(path: &Path, e: impl std::fmt::Display) -> AppError {
+    AppError::localized(
+        "team_pack.invalid_archive",
+        format!("读写团队包失败: {}: {e}", path.display()),
+        format!("Failed to read or write team pack: {}: {e}", path.display()),
+    )
+}
+
+fn not_found(kind: &str, id: &str) -> AppError {
+    AppError::localized(
+        "team_pack.item_not_found",
+        format!("要打包的项不存在: {kind}:{id}"),
+        format!("Item to pack not found: {kind}:{id}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strips_and_restores_secrets() {
+        let local = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-local",
+                "ANTHROPIC_BASE_URL": "https://old.example"
+            }
+        });
+        let mut packed = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-team",
+                "ANTHROPIC_BASE_URL": "https://new.example"
+            }
+        });
+        clear_secrets(&mut packed);
+        assert_eq!(packed["env"]["ANTHROPIC_AUTH_TOKEN"], json!(""));
+
+        restore_secrets(&mut packed, &local);
+        assert_eq!(
+            packed,
+            json!({
+                "env": {
+                    "ANTHROPIC_AUTH_TOKEN": "sk-local",
+                    "ANTHROPIC_BASE_URL": "https://new.example"
+                }
+            })
+        );
+
+        let taken = ["api-team", "api-team-2"];
+        assert_eq!(unique_id("api", |id| taken.contains(&id)), "api-team-3");
+
+        let resolutions = HashMap::from([("mcp:fs".to_string(), TeamPackResolution::KeepBoth)]);
+        assert_eq!(action_for("mcp:fs", false, &resolutions), Action::Install);
+        assert_eq!(action_for("mcp:fs", true, &resolutions), Action::Rename);
+        assert_eq!(
+            action_for("mcp:git", true, &resolutions),
+            Action::Unresolved
+        );
+    }
+}
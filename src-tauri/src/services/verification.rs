@@ -0,0 +1,201 @@
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::{ProviderMeta, ProviderVerification, VerificationStatus};
+use crate::services::{AuthProbeStatus, AuthenticatedProbe, ProviderService, SpeedtestService};
+use crate::store::AppState;
+
+/// 验证请求超时（秒）
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// 供应商综合验证：真实请求（端点可达、Key 有效）+ 模型列表，结果保存在供应商元数据中
+pub struct VerificationService;
+
+impl VerificationService {
+    /// 验证单个供应商并保存结果；未配置 API Key 等无法发送请求时返回错误
+    pub async fn verify(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Result<ProviderVerification, AppError> {
+        let verification = Self::check(state, app_type, provider_id).await?;
+        Self::record(
+            state,
+            vec![(
+                app_type.clone(),
+                provider_id.to_string(),
+                verification.clone(),
+            )],
+        )?;
+        Ok(verification)
+    }
+
+    /// 验证所有应用的全部供应商（无法发送请求的跳过），一次性保存结果，返回已验证的数量
+    pub async fn verify_all(state: &AppState) -> Result<usize, AppError> {
+        let targets: Vec<(AppType, String)> = {
+            let config = state.config.read().map_err(AppError::from)?;
+            [AppType::Claude, AppType::Codex, AppType::Gemini]
+                .into_iter()
+                .flat_map(|app_type| {
+                    let ids: Vec<String> = config
+                        .get_manager(&app_type)
+                        .map(|manager| manager.providers.keys().cloned().collect())
+                        .unwrap_or_default();
+                    ids.into_iter().map(move |id| (app_type.clone(), id))
+                })
+                .collect()
+        };
+
+        let mut results = Vec::new();
+        for (app_type, id) in targets {
+            match Self::check(state, &app_type, &id).await {
+                Ok(verification) => results.push((app_type, id, verification)),
+                Err(e) => log::debug!("跳过供应商验证 {}/{id}: {e}", app_type.as_str()),
+            }
+        }
+        let count = results.len();
+        Self::record(state, results)?;
+        Ok(count)
+    }
+
+    async fn check(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Result<ProviderVerification, AppError> {
+        let provider = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .get_manager(app_type)
+                .and_then(|manager| manager.providers.get(provider_id))
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?
+        };
+        let previous = provider
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.verification.as_ref())
+            .and_then(|verification| verification.verified_at);
+
+        let (probe, sample) =
+            SpeedtestService::probe_authenticated(app_type, &provider, Some(PROBE_TIMEOUT_SECS))
+                .await?;
+        ProviderService::record_rate_limit(state, app_type, provider_id, &[sample])?;
+        let models_ok = probe.status == AuthProbeStatus::Ok
+            && SpeedtestService::probe_models(app_type, &provider, Some(PROBE_TIMEOUT_SECS))
+                .await
+                .unwrap_or(false);
+        Ok(evaluate(
+            &probe,
+            models_ok,
+            previous,
+            chrono::Utc::now().timestamp_millis(),
+        ))
+    }
+
+    fn record(
+        state: &AppState,
+        results: Vec<(AppType, String, ProviderVerification)>,
+    ) -> Result<(), AppError> {
+        if results.is_empty() {
+            return Ok(());
+        }
+        {
+            let mut config = state.config.write().map_err(AppError::from)?;
+            for (app_type, id, verification) in results {
+                if let Some(provider) = config
+                    .get_manager_mut(&app_type)
+                    .and_then(|manager| manager.providers.get_mut(&id))
+                {
+                    provider
+                        .meta
+                        .get_or_insert_with(ProviderMeta::default)
+                        .verification = Some(verification);
+                }
+            }
+        }
+        state.save()
+    }
+}
+
+/// 根据真实请求与模型列表的结果得出验证结论；未全部通过时保留上次的通过时间
+fn evaluate(
+    probe: &AuthenticatedProbe,
+    models_ok: bool,
+    previous_verified_at: Option<i64>,
+    now: i64,
+) -> ProviderVerification {
+    let endpoint_reachable = probe.status != AuthProbeStatus::NetworkError;
+    let key_valid = endpoint_reachable
+        && !matches!(
+            probe.status,
+            AuthProbeStatus::Unauthorized | AuthProbeStatus::Forbidden
+        );
+    let status = if !key_valid {
+        VerificationStatus::Failed
+    } else if probe.status == AuthProbeStatus::Ok && models_ok {
+        VerificationStatus::Verified
+    } else {
+        VerificationStatus::Degraded
+    };
+    let error = probe.error.clone().or_else(|| {
+        (probe.status == AuthProbeStatus::Ok && !models_ok)
+            .then(|| "model list unavailable".to_string())
+    });
+    ProviderVerification {
+        status,
+        checked_at: now,
+        verified_at: if status == VerificationStatus::Verified {
+            Some(now)
+        } else {
+            previous_verified_at
+        },
+        endpoint_reachable,
+        key_valid,
+        models_ok,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(status: AuthProbeStatus) -> AuthenticatedProbe {
+        AuthenticatedProbe {
+            url: "https://relay.example.com/v1/messages".into(),
+            model: "claude-3-5-haiku-latest".into(),
+            status,
+            latency: Some(120),
+            http_status: None,
+            error: None,
+            rate_limit: None,
+        }
+    }
+
+    #[test]
+    fn evaluates_combined_checks() {
+        let ok = evaluate(&probe(AuthProbeStatus::Ok), true, Some(1), 100);
+        assert_eq!(ok.status, VerificationStatus::Verified);
+        assert_eq!(ok.verified_at, Some(100));
+
+        let no_models = evaluate(&probe(AuthProbeStatus::Ok), false, Some(1), 100);
+        assert_eq!(no_models.status, VerificationStatus::Degraded);
+        assert_eq!(no_models.verified_at, Some(1));
+        assert!(no_models.key_valid && no_models.error.is_some());
+
+        let unauthorized = evaluate(&probe(AuthProbeStatus::Unauthorized), false, None, 100);
+        assert_eq!(unauthorized.status, VerificationStatus::Failed);
+        assert!(unauthorized.endpoint_reachable && !unauthorized.key_valid);
+
+        let offline = evaluate(&probe(AuthProbeStatus::NetworkError), false, None, 100);
+        assert_eq!(offline.status, VerificationStatus::Failed);
+        assert!(!offline.endpoint_reachable);
+        assert_eq!(offline.verified_at, None);
+    }
+}
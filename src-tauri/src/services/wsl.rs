@@ -0,0 +1,194 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::config::{delete_file, write_text_file};
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::ProviderService;
+use crate::wsl;
+
+/// 已安装的 WSL 发行版
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WslDistroStatus {
+    pub name: String,
+    /// 已加入 `wsl_distros`，切换时同步写入
+    pub enabled: bool,
+}
+
+/// 发行版中的一个 live 配置文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WslLiveFile {
+    pub path: PathBuf,
+    /// 文件不存在时为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// 写入前的文件内容，用于回滚
+#[derive(Debug, Clone)]
+pub(crate) struct WslFileBackup {
+    path: PathBuf,
+    content: Option<String>,
+}
+
+/// 在 WSL 发行版中管理 Claude / Codex 的 live 配置
+///
+/// 切换供应商时，设置中 `wsl_distros` 列出的发行版与 Windows 侧文件在同一事务中写入：
+/// 任一发行版写入失败或后续步骤失败时，已写入的文件恢复原状。
+pub struct WslService;
+
+impl WslService {
+    /// 列出已安装的发行版及是否启用同步
+    pub fn distros() -> Result<Vec<WslDistroStatus>, AppError> {
+        let enabled = crate::settings::get_settings().wsl_distros;
+        Ok(wsl::list_distros()?
+            .into_iter()
+            .map(|name| WslDistroStatus {
+                enabled: enabled.contains(&name),
+                name,
+            })
+            .collect())
+    }
+
+    /// 读取发行版中该应用的 live 配置文件
+    pub fn read_live(distro: &str, app_type: &AppType) -> Result<Vec<WslLiveFile>, AppError> {
+        Self::read_live_at(app_type, &wsl::home_dir(distro)?)
+    }
+
+    fn read_live_at(app_type: &AppType, home: &Path) -> Result<Vec<WslLiveFile>, AppError> {
+        live_paths(app_type, home)
+            .into_iter()
+            .map(|path| {
+                Ok(WslLiveFile {
+                    content: read_optional(&path)?,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    /// 将供应商写入所有启用同步的发行版，返回写入前的备份（`provider` 为已展开占位符的副本）
+    pub(crate) fn write_live(
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<Vec<WslFileBackup>, AppError> {
+        let distros = crate::settings::get_settings().wsl_distros;
+        if distros.is_empty() || matches!(app_type, AppType::Gemini) {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for distro in &distros {
+            let result = wsl::home_dir(distro)
+                .and_then(|home| write_distro(app_type, provider, &home, &mut backups));
+            if let Err(e) = result {
+                Self::restore(&backups);
+                return Err(AppError::localized(
+                    "wsl.sync_failed",
+                    format!("写入 WSL 发行版 {distro} 失败，已回滚: {e}"),
+                    format!("Failed to write WSL distro {distro}, rolled back: {e}"),
+                ));
+            }
+        }
+        Ok(backups)
+    }
+
+    /// 恢复写入前的文件（尽力而为，失败只记录日志）
+    pub(crate) fn restore(backups: &[WslFileBackup]) {
+        for backup in backups.iter().rev() {
+            let result = match &backup.content {
+                Some(content) => write_text_file(&backup.path, content),
+                None => delete_file(&backup.path),
+            };
+            if let Err(e) = result {
+                log::warn!("恢复 WSL 文件 {} 失败: {e}", backup.path.display());
+            }
+        }
+    }
+}
+
+fn live_paths(app_type: &AppType, home: &Path) -> Vec<PathBuf> {
+    ProviderService::live_file_paths(app_type)
+        .iter()
+        .map(|relative| resolve(home, relative))
+        .collect()
+}
+
+/// 将相对主目录的路径（以 `/` 分隔）拼接到主目录下
+fn resolve(home: &Path, relative: &str) -> PathBuf {
+    relative
+        .split('/')
+        .fold(home.to_path_buf(), |path, segment| path.join(segment))
+}
+
+/// 生成需要写入的文件（与 Windows 侧写入规则一致），逐个备份后写入
+fn write_distro(
+    app_type: &AppType,
+    provider: &Provider,
+    home: &Path,
+    backups: &mut Vec<WslFileBackup>,
+) -> Result<(), AppError> {
+    let existing = match app_type {
+        AppType::Claude => read_optional(&resolve(home, ".claude/settings.json"))?,
+        _ => None,
+    };
+    let files = ProviderService::render_live_files(app_type, provider, existing.as_deref())?;
+
+    for (relative, content) in files {
+        let path = resolve(home, relative);
+        backups.push(WslFileBackup {
+            content: read_optional(&path)?,
+            path: path.clone(),
+        });
+        write_text_file(&path, &content)?;
+    }
+    Ok(())
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, AppError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AppError::io(path, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn write_distro_backs_up_and_restores_files() {
+        let home = tempfile::tempdir().unwrap();
+        let auth_path = home.path().join(".codex").join("auth.json");
+        write_text_file(&auth_path, "{\"OPENAI_API_KEY\":\"old\"}").unwrap();
+
+        let provider = Provider::with_id(
+            "p".into(),
+            "Codex".into(),
+            json!({
+                "auth": { "OPENAI_API_KEY": "sk-new" },
+                "config": "model = \"gpt-5\"\n"
+            }),
+            None,
+        );
+        let mut backups = Vec::new();
+        write_distro(&AppType::Codex, &provider, home.path(), &mut backups).unwrap();
+        assert_eq!(backups.len(), 2);
+        let live = WslService::read_live_at(&AppType::Codex, home.path()).unwrap();
+        assert!(live[0].content.as_deref().unwrap().contains("sk-new"));
+        assert_eq!(live[1].content.as_deref(), Some("model = \"gpt-5\"\n"));
+
+        WslService::restore(&backups);
+        assert_eq!(
+            std::fs::read_to_string(&auth_path).unwrap(),
+            "{\"OPENAI_API_KEY\":\"old\"}"
+        );
+        assert!(!home.path().join(".codex").join("config.toml").exists());
+    }
+}
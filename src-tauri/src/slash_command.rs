@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Claude Code 自定义斜杠命令（对应 `~/.claude/commands/<name>.md`）
+///
+/// `name` 即命令名，可用 `/` 分隔子目录作为命名空间（如 `frontend/component`）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommand {
+    pub name: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
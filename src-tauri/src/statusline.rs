@@ -0,0 +1,283 @@
+//! Claude Code 状态栏：生成内置脚本，并在写入 Claude live 配置时注入 `statusLine`
+//!
+//! 脚本位于 `~/.cc-switch/statusline/`，仅依赖 POSIX sh（存在 jq 时优先使用 jq 解析输入）。
+//! 每次切换供应商时会更新 `~/.cc-switch/current-<app>.txt`，脚本直接读取该文件显示当前供应商。
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::config::{
+    get_app_config_dir, get_claude_settings_path, read_json_file, write_json_file, write_text_file,
+};
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::ProviderService;
+use crate::settings::{self, StatuslineVariant};
+
+/// 脚本首行标记，用于识别由 cc-switch 生成的状态栏
+const SCRIPT_MARKER: &str = "# cc-switch statusline";
+
+/// 状态栏安装状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatuslineStatus {
+    pub installed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<StatuslineVariant>,
+    pub script_path: String,
+    pub command: String,
+}
+
+/// 状态栏展示用的当前供应商信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveProviderInfo {
+    pub app: String,
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+/// 当前供应商标记文件：`~/.cc-switch/current-<app>.txt`
+pub fn active_provider_file(app_type: &AppType) -> PathBuf {
+    get_app_config_dir().join(format!("current-{}.txt", app_type.as_str()))
+}
+
+/// 读取指定应用的当前供应商（仅读取内存中的配置，不访问 live 文件）
+pub fn active_provider(config: &MultiAppConfig, app_type: &AppType) -> Option<ActiveProviderInfo> {
+    let manager = config.get_manager(app_type)?;
+    let provider = manager.providers.get(&manager.current)?;
+    Some(ActiveProviderInfo {
+        app: app_type.as_str().to_string(),
+        id: provider.id.clone(),
+        name: provider.name.clone(),
+        base_url: ProviderService::provider_base_url(app_type, provider),
+    })
+}
+
+/// 切换供应商后更新标记文件（单行供应商名称，内容未变化时不重写）
+pub fn write_active_provider(app_type: &AppType, provider: &Provider) -> Result<(), AppError> {
+    let path = active_provider_file(app_type);
+    let line = provider.name.lines().next().unwrap_or_default().trim();
+    let content = format!("{line}\n");
+    if fs::read_to_string(&path).is_ok_and(|current| current == content) {
+        return Ok(());
+    }
+    write_text_file(&path, &content)
+}
+
+/// 状态栏脚本路径
+pub fn script_path() -> PathBuf {
+    get_app_config_dir()
+        .join("statusline")
+        .join("statusline.sh")
+}
+
+/// 写入 `statusLine.command` 的命令
+fn script_command() -> String {
+    let path = script_path().to_string_lossy().replace('\\', "/");
+    if cfg!(windows) {
+        // Windows 下 Claude Code 通过 Git Bash 执行命令
+        format!("bash \"{path}\"")
+    } else {
+        path
+    }
+}
+
+pub fn status() -> StatuslineStatus {
+    let variant = settings::get_settings().claude_statusline;
+    StatuslineStatus {
+        installed: variant.is_some() && script_path().exists(),
+        variant,
+        script_path: script_path().to_string_lossy().to_string(),
+        command: script_command(),
+    }
+}
+
+/// 安装指定样式的状态栏：写入脚本、记录设置，并立即更新当前 Claude settings.json
+pub fn install(variant: StatuslineVariant) -> Result<StatuslineStatus, AppError> {
+    let path = script_path();
+    write_text_file(&path, &render_script(variant))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| AppError::io(&path, e))?;
+    }
+
+    let mut app_settings = settings::get_settings();
+    app_settings.claude_statusline = Some(variant);
+    settings::update_settings(app_settings)?;
+
+    patch_live_settings()?;
+    Ok(status())
+}
+
+/// 移除状态栏：清除设置、删除脚本，并从当前 Claude settings.json 中移除 cc-switch 的 statusLine
+pub fn remove() -> Result<StatuslineStatus, AppError> {
+    let mut app_settings = settings::get_settings();
+    app_settings.claude_statusline = None;
+    settings::update_settings(app_settings)?;
+
+    let path = script_path();
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| AppError::io(&path, e))?;
+    }
+
+    patch_live_settings()?;
+    Ok(status())
+}
+
+/// 按当前设置调整 Claude 配置中的 `statusLine`（供写入 live 配置时调用）
+///
+/// 已安装时写入 cc-switch 状态栏；未安装时仅移除由 cc-switch 写入的条目，不影响用户自定义的状态栏
+pub fn patch_settings(content: &mut Value) {
+    let command = (settings::get_settings().claude_statusline.is_some() && script_path().exists())
+        .then(script_command);
+    apply_statusline(content, command.as_deref());
+}
+
+fn apply_statusline(content: &mut Value, command: Option<&str>) {
+    let Some(obj) = content.as_object_mut() else {
+        return;
+    };
+    match command {
+        Some(command) => {
+            obj.insert(
+                "statusLine".to_string(),
+                json!({ "type": "command", "command": command, "padding": 0 }),
+            );
+        }
+        None => {
+            let managed = obj
+                .get("statusLine")
+                .and_then(|line| line.get("command"))
+                .and_then(|c| c.as_str())
+                .is_some_and(is_managed_command);
+            if managed {
+                obj.remove("statusLine");
+            }
+        }
+    }
+}
+
+fn is_managed_command(command: &str) -> bool {
+    command
+        .replace('\\', "/")
+        .contains("/statusline/statusline.sh")
+        && command.contains(".cc-switch")
+}
+
+fn patch_live_settings() -> Result<(), AppError> {
+    let path = get_claude_settings_path();
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut content: Value = read_json_file(&path)?;
+    let before = content.clone();
+    patch_settings(&mut content);
+    if content != before {
+        write_json_file(&path, &content)?;
+    }
+    Ok(())
+}
+
+/// 生成状态栏脚本：从标准输入读取 Claude Code 提供的会话 JSON，输出单行状态
+fn render_script(variant: StatuslineVariant) -> String {
+    let variant_name = match variant {
+        StatuslineVariant::Minimal => "minimal",
+        StatuslineVariant::Provider => "provider",
+        StatuslineVariant::Full => "full",
+    };
+
+    let mut script = format!(
+        r#"#!/bin/sh
+{SCRIPT_MARKER} ({variant_name}) - generated by cc-switch, changes will be overwritten
+input=$(cat)
+
+json_get() {{
+  if command -v jq >/dev/null 2>&1; then
+    printf '%s' "$input" | jq -r "$2 // empty" 2>/dev/null
+  else
+    printf '%s' "$input" | sed -n "s/.*\"$1\"[[:space:]]*:[[:space:]]*\"\{{0,1\}}\([^\",}}]*\).*/\1/p" | head -n 1
+  fi
+}}
+
+model=$(json_get display_name '.model.display_name')
+dir=$(json_get current_dir '.workspace.current_dir')
+out="[${{model:-Claude}}] ${{dir##*/}}"
+"#
+    );
+
+    if matches!(
+        variant,
+        StatuslineVariant::Provider | StatuslineVariant::Full
+    ) {
+        script.push_str(
+            &format!(
+                r#"
+provider=$(head -n 1 "{current_file}" 2>/dev/null)
+[ -z "$provider" ] && provider=$(printf '%s' "${{ANTHROPIC_BASE_URL:-}}" | sed -e 's#^[A-Za-z]*://##' -e 's#/.*$##')
+out="$out | ${{provider:-official}}"
+"#,
+                current_file = active_provider_file(&AppType::Claude)
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+            ),
+        );
+    }
+
+    if variant == StatuslineVariant::Full {
+        script.push_str(
+            r#"
+cost=$(json_get total_cost_usd '.cost.total_cost_usd')
+[ -n "$cost" ] && out="$out | \$$(printf '%.2f' "$cost" 2>/dev/null || printf '%s' "$cost")"
+branch=$(git -C "${dir:-.}" rev-parse --abbrev-ref HEAD 2>/dev/null)
+[ -n "$branch" ] && out="$out | $branch"
+"#,
+        );
+    }
+
+    script.push_str("\nprintf '%s' \"$out\"\n");
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_statusline_only_removes_managed_entries() {
+        let mut content = json!({ "env": {} });
+        apply_statusline(
+            &mut content,
+            Some("/home/u/.cc-switch/statusline/statusline.sh"),
+        );
+        assert_eq!(content["statusLine"]["type"], "command");
+
+        apply_statusline(&mut content, None);
+        assert!(content.get("statusLine").is_none());
+
+        let mut custom = json!({ "statusLine": { "type": "command", "command": "~/my-line.sh" } });
+        apply_statusline(&mut custom, None);
+        assert!(custom.get("statusLine").is_some());
+    }
+
+    #[test]
+    fn render_script_includes_variant_sections() {
+        let minimal = render_script(StatuslineVariant::Minimal);
+        assert!(minimal.starts_with("#!/bin/sh\n# cc-switch statusline (minimal)"));
+        assert!(!minimal.contains("ANTHROPIC_BASE_URL"));
+
+        let full = render_script(StatuslineVariant::Full);
+        assert!(full.contains("current-claude.txt"));
+        assert!(full.contains("ANTHROPIC_BASE_URL"));
+        assert!(full.contains("total_cost_usd"));
+        assert!(full.contains("rev-parse"));
+    }
+}
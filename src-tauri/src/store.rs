@@ -17,10 +17,13 @@ impl AppState {
         })
     }
 
-    /// 保存配置到文件
+    /// 保存配置到文件，成功后递增配置修订号并发送 `config-changed` 事件
     pub fn save(&self) -> Result<(), AppError> {
         let config = self.config.read().map_err(AppError::from)?;
 
-        config.save()
+        config.save()?;
+        drop(config);
+        crate::config_events::notify_changed();
+        Ok(())
     }
 }
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::config::sha256_hex;
+use crate::error::AppError;
+use crate::services::passcode::is_secret_key;
+
+/// 订阅源：团队维护的供应商清单（JSON，不含密钥），定期拉取并作为可安装的预设展示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionSource {
+    pub id: String,
+    pub url: String,
+    /// 清单中的名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 最近一次成功拉取时间（毫秒时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_fetched_at: Option<i64>,
+    /// 最近一次拉取失败的原因（成功后清空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// 最近一次成功拉取的供应商定义
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<SubscribedProvider>,
+}
+
+/// 订阅清单中的供应商定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribedProvider {
+    pub id: String,
+    pub app: AppType,
+    pub name: String,
+    pub settings_config: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// 内容修订号（定义内容的 SHA-256），用于识别上游更新
+    #[serde(default)]
+    pub revision: String,
+}
+
+/// 订阅清单文件格式
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionManifest {
+    #[serde(default)]
+    name: Option<String>,
+    providers: Vec<SubscribedProvider>,
+}
+
+/// 解析订阅清单：移除清单中误带的密钥字段并计算各条目的修订号
+pub fn parse_manifest(text: &str) -> Result<(Option<String>, Vec<SubscribedProvider>), AppError> {
+    let manifest: SubscriptionManifest = serde_json::from_str(text).map_err(|e| {
+        AppError::localized(
+            "subscription.invalid_manifest",
+            format!("订阅清单格式无效: {e}"),
+            format!("Invalid subscription manifest: {e}"),
+        )
+    })?;
+
+    let mut providers: Vec<SubscribedProvider> = Vec::with_capacity(manifest.providers.len());
+    for mut provider in manifest.providers {
+        if provider.id.trim().is_empty() || providers.iter().any(|p| p.id == provider.id) {
+            return Err(AppError::localized(
+                "subscription.invalid_manifest",
+                format!("订阅清单中的供应商 ID 为空或重复: '{}'", provider.id),
+                format!(
+                    "Provider id is empty or duplicated in subscription manifest: '{}'",
+                    provider.id
+                ),
+            ));
+        }
+        strip_secrets(&mut provider.settings_config);
+        provider.revision = revision_of(&provider);
+        providers.push(provider);
+    }
+    Ok((manifest.name, providers))
+}
+
+/// 将上游配置合并到已安装的配置：上游的值覆盖本地，本地独有的字段（如用户填写的密钥）保留
+pub fn merge_upstream(local: &mut Value, upstream: &Value) {
+    match (local, upstream) {
+        (Value::Object(local), Value::Object(upstream)) => {
+            for (key, value) in upstream {
+                match local.get_mut(key) {
+                    Some(existing) => merge_upstream(existing, value),
+                    None => {
+                        local.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (local, upstream) => *local = upstream.clone(),
+    }
+}
+
+/// 递归移除密钥字段（订阅只分发不含密钥的定义，密钥由用户在本地填写）
+fn strip_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, child| !(child.is_string() && is_secret_key(key)));
+            map.values_mut().for_each(strip_secrets);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_secrets),
+        _ => {}
+    }
+}
+
+fn revision_of(provider: &SubscribedProvider) -> String {
+    let content = serde_json::json!({
+        "app": provider.app,
+        "name": provider.name,
+        "settingsConfig": provider.settings_config,
+        "websiteUrl": provider.website_url,
+        "category": provider.category,
+        "notes": provider.notes,
+    });
+    sha256_hex(content.to_string().as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn manifest_strips_secrets_and_merge_keeps_local_keys() {
+        let manifest = json!({
+            "name": "Team",
+            "providers": [{
+                "id": "team-claude",
+                "app": "claude",
+                "name": "Team Claude",
+                "settingsConfig": {
+                    "env": {
+                        "ANTHROPIC_BASE_URL": "https://gw.example.com",
+                        "ANTHROPIC_AUTH_TOKEN": "sk-leaked",
+                        "CLAUDE_CODE_MAX_OUTPUT_TOKENS": "8192"
+                    }
+                }
+            }]
+        });
+        let (name, providers) = parse_manifest(&manifest.to_string()).unwrap();
+        assert_eq!(name.as_deref(), Some("Team"));
+        let upstream = &providers[0];
+        assert_eq!(upstream.revision.len(), 64);
+        assert!(upstream.settings_config["env"]
+            .get("ANTHROPIC_AUTH_TOKEN")
+            .is_none());
+
+        let mut local = json!({
+            "env": {
+                "ANTHROPIC_BASE_URL": "https://old.example.com",
+                "ANTHROPIC_AUTH_TOKEN": "sk-mine"
+            }
+        });
+        merge_upstream(&mut local, &upstream.settings_config);
+        assert_eq!(local["env"]["ANTHROPIC_BASE_URL"], "https://gw.example.com");
+        assert_eq!(local["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-mine");
+        assert_eq!(local["env"]["CLAUDE_CODE_MAX_OUTPUT_TOKENS"], "8192");
+
+        let duplicated = json!({
+            "providers": [upstream, upstream]
+        });
+        assert!(parse_manifest(&duplicated.to_string()).is_err());
+    }
+}
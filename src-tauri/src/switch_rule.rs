@@ -0,0 +1,254 @@
+use chrono::{DateTime, Datelike, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::network::NetworkSnapshot;
+
+/// 自动切换规则：满足条件时将指定应用切换到目标供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchRule {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub app: AppType,
+    pub provider_id: String,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    pub condition: SwitchRuleCondition,
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
+
+/// 规则触发条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SwitchRuleCondition {
+    /// 每日时间窗口（本地时间，`HH:MM`，允许跨越午夜，如 22:00 → 08:00）
+    ///
+    /// `days` 为 ISO 星期编号（1 = 周一 … 7 = 周日），为空表示每天生效
+    TimeWindow {
+        start: String,
+        end: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        days: Vec<u32>,
+    },
+    /// 网络环境：所有已填写的条件均满足时生效
+    ///
+    /// `ssid` 与 `gateway` 为精确匹配；`interface` 为接口名前缀（如 `utun`、`wg`）；
+    /// `vpn` 为 true/false 时要求检测到/未检测到 VPN 隧道接口
+    Network {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ssid: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gateway: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        interface: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        vpn: Option<bool>,
+    },
+    /// 当前供应商剩余额度低于 `threshold`（单位与其用量脚本返回的 remaining 一致）
+    ///
+    /// 触发后，原供应商的剩余额度需回升到 `resume_above`（默认为阈值的 1.5 倍）
+    /// 才会再次触发，避免在阈值附近反复切换
+    QuotaBelow {
+        threshold: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        resume_above: Option<f64>,
+    },
+}
+
+/// 额度规则未指定恢复阈值时，以触发阈值的该倍数作为恢复阈值
+const DEFAULT_QUOTA_RESUME_FACTOR: f64 = 1.5;
+
+/// 解析 `HH:MM` 格式的时间
+pub fn parse_clock(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+impl SwitchRuleCondition {
+    /// 条件是否依赖网络环境检测
+    pub fn uses_network(&self) -> bool {
+        matches!(self, SwitchRuleCondition::Network { .. })
+    }
+
+    /// 条件是否依赖用量查询得到的额度（由调度器单独评估，不参与 [`SwitchRule::find_active`]）
+    pub fn uses_quota(&self) -> bool {
+        matches!(self, SwitchRuleCondition::QuotaBelow { .. })
+    }
+
+    /// 额度条件在给定剩余额度下是否处于触发状态
+    ///
+    /// `triggered` 为上一次的状态：未触发时低于阈值才触发，已触发时回升到恢复阈值才解除
+    pub fn quota_triggered(&self, remaining: f64, triggered: bool) -> bool {
+        let SwitchRuleCondition::QuotaBelow {
+            threshold,
+            resume_above,
+        } = self
+        else {
+            return false;
+        };
+        if triggered {
+            remaining < resume_above.unwrap_or(threshold * DEFAULT_QUOTA_RESUME_FACTOR)
+        } else {
+            remaining < *threshold
+        }
+    }
+
+    /// 判断条件在给定时间点与网络环境下是否满足
+    ///
+    /// 未提供网络快照时，网络条件一律视为不满足
+    pub fn matches<Tz: TimeZone>(
+        &self,
+        now: &DateTime<Tz>,
+        network: Option<&NetworkSnapshot>,
+    ) -> bool {
+        match self {
+            SwitchRuleCondition::TimeWindow { start, end, days } => {
+                let (Some(start), Some(end)) = (parse_clock(start), parse_clock(end)) else {
+                    return false;
+                };
+                let time = now.time();
+
+                // 跨午夜窗口的后半段归属于前一天
+                let (in_window, weekday) = if start <= end {
+                    (time >= start && time < end, now.weekday())
+                } else if time >= start {
+                    (true, now.weekday())
+                } else {
+                    (time < end, now.weekday().pred())
+                };
+
+                in_window && (days.is_empty() || days.contains(&weekday.number_from_monday()))
+            }
+            SwitchRuleCondition::Network {
+                ssid,
+                gateway,
+                interface,
+                vpn,
+            } => {
+                let Some(network) = network else {
+                    return false;
+                };
+                let same = |expected: &Option<String>, actual: &Option<String>| match expected {
+                    Some(expected) => actual.as_deref() == Some(expected.trim()),
+                    None => true,
+                };
+
+                same(ssid, &network.ssid)
+                    && same(gateway, &network.gateway)
+                    && interface
+                        .as_deref()
+                        .is_none_or(|prefix| network.has_interface(prefix))
+                    && vpn.is_none_or(|vpn| vpn == network.vpn_active)
+            }
+            SwitchRuleCondition::QuotaBelow { .. } => false,
+        }
+    }
+}
+
+impl SwitchRule {
+    /// 在规则列表中查找指定应用当前生效的规则（按列表顺序，先匹配者优先）
+    pub fn find_active<'a, Tz: TimeZone>(
+        rules: &'a [SwitchRule],
+        app_type: &AppType,
+        now: &DateTime<Tz>,
+        network: Option<&NetworkSnapshot>,
+    ) -> Option<&'a SwitchRule> {
+        rules
+            .iter()
+            .filter(|rule| rule.enabled && &rule.app == app_type)
+            .find(|rule| rule.condition.matches(now, network))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn window(start: &str, end: &str, days: Vec<u32>) -> SwitchRuleCondition {
+        SwitchRuleCondition::TimeWindow {
+            start: start.into(),
+            end: end.into(),
+            days,
+        }
+    }
+
+    #[test]
+    fn time_window_matches_same_day_range() {
+        // 2025-01-06 是周一
+        let cond = window("09:00", "18:00", vec![1, 2, 3, 4, 5]);
+        let inside = Utc.with_ymd_and_hms(2025, 1, 6, 10, 30, 0).unwrap();
+        let outside = Utc.with_ymd_and_hms(2025, 1, 6, 18, 0, 0).unwrap();
+        let weekend = Utc.with_ymd_and_hms(2025, 1, 5, 10, 30, 0).unwrap();
+        assert!(cond.matches(&inside, None));
+        assert!(!cond.matches(&outside, None));
+        assert!(!cond.matches(&weekend, None));
+    }
+
+    #[test]
+    fn time_window_handles_overnight_range() {
+        // 周五晚 22:00 开始，延续到周六早上
+        let cond = window("22:00", "08:00", vec![5]);
+        let friday_night = Utc.with_ymd_and_hms(2025, 1, 10, 23, 0, 0).unwrap();
+        let saturday_morning = Utc.with_ymd_and_hms(2025, 1, 11, 7, 59, 0).unwrap();
+        let saturday_night = Utc.with_ymd_and_hms(2025, 1, 11, 23, 0, 0).unwrap();
+        assert!(cond.matches(&friday_night, None));
+        assert!(cond.matches(&saturday_morning, None));
+        assert!(!cond.matches(&saturday_night, None));
+    }
+
+    #[test]
+    fn invalid_clock_never_matches() {
+        let cond = window("25:00", "08:00", vec![]);
+        let now = Utc.with_ymd_and_hms(2025, 1, 6, 1, 0, 0).unwrap();
+        assert!(!cond.matches(&now, None));
+    }
+
+    #[test]
+    fn network_condition_requires_all_criteria() {
+        let cond = SwitchRuleCondition::Network {
+            ssid: Some("Corp".into()),
+            gateway: None,
+            interface: Some("utun".into()),
+            vpn: None,
+        };
+        let now = Utc.with_ymd_and_hms(2025, 1, 6, 10, 0, 0).unwrap();
+        let mut network = NetworkSnapshot {
+            ssid: Some("Corp".into()),
+            gateway: Some("10.0.0.1".into()),
+            interfaces: vec!["en0".into(), "utun6".into()],
+            vpn_active: true,
+        };
+        assert!(cond.matches(&now, Some(&network)));
+        assert!(!cond.matches(&now, None));
+
+        network.interfaces.retain(|name| name != "utun6");
+        assert!(!cond.matches(&now, Some(&network)));
+    }
+
+    #[test]
+    fn quota_condition_uses_hysteresis() {
+        let cond = SwitchRuleCondition::QuotaBelow {
+            threshold: 10.0,
+            resume_above: None,
+        };
+        let now = Utc.with_ymd_and_hms(2025, 1, 6, 10, 0, 0).unwrap();
+        assert!(!cond.matches(&now, None));
+
+        assert!(!cond.quota_triggered(12.0, false));
+        assert!(cond.quota_triggered(9.5, false));
+        // 已触发时需回升到 15（阈值的 1.5 倍）才解除
+        assert!(cond.quota_triggered(12.0, true));
+        assert!(!cond.quota_triggered(15.0, true));
+
+        let explicit = SwitchRuleCondition::QuotaBelow {
+            threshold: 10.0,
+            resume_above: Some(11.0),
+        };
+        assert!(!explicit.quota_triggered(11.0, true));
+    }
+}
@@ -0,0 +1,111 @@
+//! 本地 token 计数（不调用任何 API）
+//!
+//! OpenAI 系模型使用 tiktoken 的精确编码；Claude 与 Gemini 的分词器未公开，
+//! 以 cl100k_base 近似估算，结果标记为 `approximate`。
+
+use std::str::FromStr;
+
+use serde::Serialize;
+use tiktoken_rs::CoreBPE;
+
+use crate::error::AppError;
+
+/// 模型家族
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelFamily {
+    Claude,
+    Openai,
+    Gemini,
+}
+
+impl FromStr for ModelFamily {
+    type Err = AppError;
+
+    /// 同时接受家族名、应用名与常见模型名前缀（如 `claude-sonnet-4`、`gpt-4o`、`codex`）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase();
+        if normalized.starts_with("claude") || normalized == "anthropic" {
+            Ok(Self::Claude)
+        } else if normalized.starts_with("gemini") || normalized == "google" {
+            Ok(Self::Gemini)
+        } else if normalized == "openai"
+            || normalized == "codex"
+            || normalized.starts_with("gpt")
+            || normalized
+                .strip_prefix('o')
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            Ok(Self::Openai)
+        } else {
+            Err(AppError::localized(
+                "tokens.unknown_family",
+                format!("未知的模型家族: {s}"),
+                format!("Unknown model family: {s}"),
+            ))
+        }
+    }
+}
+
+/// token 计数结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCount {
+    pub family: ModelFamily,
+    pub tokens: usize,
+    /// 使用的编码名称
+    pub encoding: &'static str,
+    /// 是否为近似值（非该模型的官方分词器）
+    pub approximate: bool,
+}
+
+fn encoding_for(family: ModelFamily) -> (&'static CoreBPE, &'static str) {
+    match family {
+        ModelFamily::Openai => (tiktoken_rs::o200k_base_singleton(), "o200k_base"),
+        ModelFamily::Claude | ModelFamily::Gemini => {
+            (tiktoken_rs::cl100k_base_singleton(), "cl100k_base")
+        }
+    }
+}
+
+/// 统计文本的 token 数（特殊标记按普通文本处理）
+pub fn count_tokens(family: ModelFamily, text: &str) -> TokenCount {
+    let (bpe, encoding) = encoding_for(family);
+    TokenCount {
+        family,
+        tokens: bpe.encode_ordinary(text).len(),
+        encoding,
+        approximate: family != ModelFamily::Openai,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_tokens_per_family() {
+        assert_eq!(
+            "claude-sonnet-4".parse::<ModelFamily>().unwrap(),
+            ModelFamily::Claude
+        );
+        assert_eq!(
+            "gpt-4o".parse::<ModelFamily>().unwrap(),
+            ModelFamily::Openai
+        );
+        assert_eq!("codex".parse::<ModelFamily>().unwrap(), ModelFamily::Openai);
+        assert_eq!(
+            "o3-mini".parse::<ModelFamily>().unwrap(),
+            ModelFamily::Openai
+        );
+        assert!("llama".parse::<ModelFamily>().is_err());
+
+        let openai = count_tokens(ModelFamily::Openai, "hello world");
+        assert_eq!(openai.tokens, 2);
+        assert!(!openai.approximate);
+
+        let claude = count_tokens(ModelFamily::Claude, "");
+        assert_eq!(claude.tokens, 0);
+        assert!(claude.approximate);
+    }
+}
@@ -0,0 +1,227 @@
+//! 按当前供应商定制托盘图标
+//!
+//! 供应商设置了 `icon`（PNG 文件）时直接用作托盘图标；只设置了 `iconColor` 时，
+//! 在默认托盘图标右下角叠加该颜色的圆形角标。生成结果按来源缓存在内存中，切换时无需重复解码。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::error::AppError;
+use crate::provider::Provider;
+
+/// 图标文件的最大边长（像素），避免误选大图
+const MAX_ICON_SIZE: u32 = 1024;
+/// 缓存条目上限，超出后整体清空
+const MAX_CACHE_ENTRIES: usize = 32;
+
+/// RGBA 图标数据（行优先，自上而下）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrayIcon {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn cache() -> &'static Mutex<HashMap<String, Arc<TrayIcon>>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Arc<TrayIcon>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cached(
+    key: String,
+    build: impl FnOnce() -> Result<TrayIcon, AppError>,
+) -> Result<Arc<TrayIcon>, AppError> {
+    let mut cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(icon) = cache.get(&key) {
+        return Ok(icon.clone());
+    }
+    let icon = Arc::new(build()?);
+    if cache.len() >= MAX_CACHE_ENTRIES {
+        cache.clear();
+    }
+    cache.insert(key, icon.clone());
+    Ok(icon)
+}
+
+/// 决定托盘图标的供应商：各应用的当前供应商中最近一次切换到的那个
+pub fn active_provider(config: &MultiAppConfig) -> Option<&Provider> {
+    // max_by_key 在并列时取最后一个，按此顺序使无使用记录时优先 Claude
+    [AppType::Gemini, AppType::Codex, AppType::Claude]
+        .iter()
+        .filter_map(|app| {
+            let manager = config.get_manager(app)?;
+            manager.providers.get(&manager.current)
+        })
+        .max_by_key(|provider| provider.meta.as_ref().and_then(|meta| meta.last_used_at))
+}
+
+/// 生成供应商的托盘图标；未设置图标或颜色时返回 None（使用默认图标）
+///
+/// `base` 为默认托盘图标的 RGBA 数据，用于叠加颜色角标
+pub fn render(provider: &Provider, base: &[u8], width: u32, height: u32) -> Option<Arc<TrayIcon>> {
+    if let Some(path) = provider
+        .icon
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        match cached(format!("file:{path}:{modified:?}"), || {
+            load_png(Path::new(path))
+        }) {
+            Ok(icon) => return Some(icon),
+            Err(e) => log::warn!("加载供应商 {} 的托盘图标失败: {e}", provider.id),
+        }
+    }
+
+    let color = provider.icon_color.as_deref().and_then(parse_color)?;
+    if base.len() != (width as usize) * (height as usize) * 4 {
+        return None;
+    }
+    let key = format!(
+        "badge:{:02x}{:02x}{:02x}:{width}x{height}",
+        color[0], color[1], color[2]
+    );
+    cached(key, || Ok(with_badge(base, width, height, color))).ok()
+}
+
+/// 解析 `#RRGGBB` 或 `#RGB`（`#` 可省略）
+pub fn parse_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        6 => Some([
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ]),
+        3 => {
+            let short = |i: usize| channel(&hex[i..=i]).map(|v| v * 17);
+            Some([short(0)?, short(1)?, short(2)?])
+        }
+        _ => None,
+    }
+}
+
+/// 在右下角绘制带白色描边的圆形角标
+fn with_badge(base: &[u8], width: u32, height: u32, color: [u8; 3]) -> TrayIcon {
+    let mut rgba = base.to_vec();
+    let size = width.min(height) as f32;
+    let radius = size * 0.24;
+    let border = (size * 0.05).max(1.0);
+    let center_x = width as f32 - radius - border;
+    let center_y = height as f32 - radius - border;
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let pixel = if distance <= radius {
+                [color[0], color[1], color[2], 255]
+            } else if distance <= radius + border {
+                [255, 255, 255, 255]
+            } else {
+                continue;
+            };
+            let offset = ((y * width + x) * 4) as usize;
+            rgba[offset..offset + 4].copy_from_slice(&pixel);
+        }
+    }
+
+    TrayIcon {
+        rgba,
+        width,
+        height,
+    }
+}
+
+fn load_png(path: &Path) -> Result<TrayIcon, AppError> {
+    let invalid = |reason: String| {
+        AppError::localized(
+            "tray.icon_invalid",
+            format!("托盘图标不是有效的 PNG 文件: {} ({reason})", path.display()),
+            format!(
+                "Tray icon is not a valid PNG file: {} ({reason})",
+                path.display()
+            ),
+        )
+    };
+
+    let file = fs::File::open(path).map_err(|e| AppError::io(path, e))?;
+    let mut decoder = png::Decoder::new(std::io::BufReader::new(file));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| invalid(e.to_string()))?;
+    let (width, height) = reader.info().size();
+    if width > MAX_ICON_SIZE || height > MAX_ICON_SIZE {
+        return Err(invalid(format!("{width}x{height} > {MAX_ICON_SIZE}px")));
+    }
+
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(|e| invalid(e.to_string()))?;
+    let pixels = &buf[..info.buffer_size()];
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => pixels.to_vec(),
+        png::ColorType::Rgb => pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => pixels
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => pixels.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Err(invalid("indexed color".to_string())),
+    };
+
+    Ok(TrayIcon {
+        rgba,
+        width: info.width,
+        height: info.height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn badge_and_png_icons() {
+        assert_eq!(parse_color("#ff8000"), Some([255, 128, 0]));
+        assert_eq!(parse_color("0f0"), Some([0, 255, 0]));
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("#gg0000"), None);
+
+        let base = vec![10u8; 32 * 32 * 4];
+        let badged = with_badge(&base, 32, 32, [255, 0, 0]);
+        let pixel = |icon: &TrayIcon, x: u32, y: u32| {
+            let offset = ((y * icon.width + x) * 4) as usize;
+            icon.rgba[offset..offset + 4].to_vec()
+        };
+        assert_eq!(pixel(&badged, 0, 0), vec![10, 10, 10, 10]);
+        assert_eq!(pixel(&badged, 23, 23), vec![255, 0, 0, 255]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("icon.png");
+        {
+            let file = fs::File::create(&path).unwrap();
+            let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), 2, 1);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&[1, 2, 3, 4, 5, 6]).unwrap();
+        }
+        let icon = load_png(&path).unwrap();
+        assert_eq!((icon.width, icon.height), (2, 1));
+        assert_eq!(icon.rgba, vec![1, 2, 3, 255, 4, 5, 6, 255]);
+        assert!(load_png(&dir.path().join("missing.png")).is_err());
+    }
+}
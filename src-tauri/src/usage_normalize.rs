@@ -0,0 +1,161 @@
+//! 用量数据归一化与货币换算
+//!
+//! 各中转站面板返回的额度单位不一（USD、CNY、积分、tokens）。这里根据 `unit`
+//! 推断单位类型与货币代码，并按设置中的展示货币换算，便于统一展示。
+
+use crate::provider::{ConvertedUsage, UsageData, UsageUnitKind};
+use crate::settings::AppSettings;
+
+/// 汇率基准货币
+pub const BASE_CURRENCY: &str = "USD";
+
+/// 内置参考汇率（1 USD 可兑换的目标货币数量），可通过设置 `exchangeRates` 覆盖
+const DEFAULT_RATES: &[(&str, f64)] = &[
+    ("USD", 1.0),
+    ("CNY", 7.2),
+    ("HKD", 7.8),
+    ("EUR", 0.92),
+    ("GBP", 0.79),
+    ("JPY", 150.0),
+];
+
+/// 由单位文本推断单位类型与货币代码
+pub fn detect_unit(unit: &str) -> Option<(UsageUnitKind, Option<String>)> {
+    let trimmed = unit.trim();
+    let upper = trimmed.to_uppercase();
+    let currency = match upper.as_str() {
+        "$" | "US$" | "USD" | "美元" | "DOLLAR" | "DOLLARS" => Some("USD"),
+        "¥" | "￥" | "CNY" | "RMB" | "元" | "人民币" => Some("CNY"),
+        "€" | "EUR" => Some("EUR"),
+        "£" | "GBP" => Some("GBP"),
+        "HK$" | "HKD" => Some("HKD"),
+        "JPY" | "円" => Some("JPY"),
+        _ => None,
+    };
+    if let Some(code) = currency {
+        return Some((UsageUnitKind::Currency, Some(code.to_string())));
+    }
+
+    let lower = trimmed.to_lowercase();
+    if ["credit", "积分", "点"]
+        .iter()
+        .any(|keyword| lower.contains(keyword))
+    {
+        return Some((UsageUnitKind::Credits, None));
+    }
+    if lower.contains("token") {
+        return Some((UsageUnitKind::Tokens, None));
+    }
+    None
+}
+
+/// 1 USD 可兑换的 `currency` 数量（设置中的自定义汇率优先）
+fn rate_of(currency: &str, settings: Option<&AppSettings>) -> Option<f64> {
+    let custom = settings.and_then(|settings| {
+        settings
+            .exchange_rates
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(currency))
+            .map(|(_, rate)| *rate)
+    });
+    custom
+        .or_else(|| {
+            DEFAULT_RATES
+                .iter()
+                .find(|(code, _)| code.eq_ignore_ascii_case(currency))
+                .map(|(_, rate)| *rate)
+        })
+        .filter(|rate| rate.is_finite() && *rate > 0.0)
+}
+
+/// `from` 到 `to` 的换算比例；任一货币缺少汇率时返回 None
+pub fn conversion_rate(from: &str, to: &str, settings: &AppSettings) -> Option<f64> {
+    if from.eq_ignore_ascii_case(to) {
+        return Some(1.0);
+    }
+    Some(rate_of(to, Some(settings))? / rate_of(from, Some(settings))?)
+}
+
+/// 补全单位元数据，并在设置了展示货币时换算货币类额度
+pub fn normalize(data: &mut UsageData, settings: &AppSettings) {
+    if let Some((kind, currency)) = data.unit.as_deref().and_then(detect_unit) {
+        data.unit_kind.get_or_insert(kind);
+        if data.currency.is_none() {
+            data.currency = currency;
+        }
+    }
+    if data.currency.is_some() {
+        data.unit_kind.get_or_insert(UsageUnitKind::Currency);
+    }
+    if let Some(currency) = data.currency.as_mut() {
+        *currency = currency.trim().to_uppercase();
+    }
+
+    data.converted = None;
+    let (Some(from), Some(to)) = (
+        data.currency.as_deref(),
+        settings.display_currency.as_deref(),
+    ) else {
+        return;
+    };
+    let to = to.trim().to_uppercase();
+    let Some(rate) = conversion_rate(from, &to, settings) else {
+        log::debug!("缺少 {from} -> {to} 的汇率，跳过用量换算");
+        return;
+    };
+    let convert = |value: Option<f64>| value.map(|v| (v * rate * 100.0).round() / 100.0);
+    data.converted = Some(ConvertedUsage {
+        currency: to,
+        rate,
+        total: convert(data.total),
+        used: convert(data.used),
+        remaining: convert(data.remaining),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(unit: &str, remaining: f64) -> UsageData {
+        serde_json::from_value(serde_json::json!({ "unit": unit, "remaining": remaining }))
+            .expect("usage data")
+    }
+
+    #[test]
+    fn detects_currency_credits_and_tokens() {
+        assert_eq!(
+            detect_unit("￥"),
+            Some((UsageUnitKind::Currency, Some("CNY".to_string())))
+        );
+        assert_eq!(
+            detect_unit("usd"),
+            Some((UsageUnitKind::Currency, Some("USD".to_string())))
+        );
+        assert_eq!(detect_unit("积分"), Some((UsageUnitKind::Credits, None)));
+        assert_eq!(detect_unit("tokens"), Some((UsageUnitKind::Tokens, None)));
+        assert_eq!(detect_unit("GB"), None);
+    }
+
+    #[test]
+    fn converts_to_display_currency_with_custom_rates() {
+        let mut settings = AppSettings {
+            display_currency: Some("usd".to_string()),
+            ..AppSettings::default()
+        };
+        settings.exchange_rates.insert("CNY".to_string(), 8.0);
+
+        let mut data = usage("元", 80.0);
+        normalize(&mut data, &settings);
+        assert_eq!(data.unit_kind, Some(UsageUnitKind::Currency));
+        assert_eq!(data.currency.as_deref(), Some("CNY"));
+        let converted = data.converted.expect("converted");
+        assert_eq!(converted.currency, "USD");
+        assert_eq!(converted.remaining, Some(10.0));
+
+        // 积分类额度不做货币换算
+        let mut credits = usage("credits", 100.0);
+        normalize(&mut credits, &settings);
+        assert!(credits.converted.is_none());
+    }
+}
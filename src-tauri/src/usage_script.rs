@@ -1,17 +1,83 @@
 use reqwest::Client;
 use rquickjs::{Context, Function, Runtime};
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 
+use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::provider::{UsageData, UsageScript};
+
+/// 默认超时（秒）
+pub const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// 默认响应体大小上限（1 MiB）
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// 重试次数上限，防止异常配置导致长时间重试
+const MAX_RETRIES: u32 = 5;
+
+/// 默认首次重试等待时间（毫秒）
+const DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
+
+/// 默认熔断阈值（连续失败次数）
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// 默认熔断冷却时间（分钟）
+const DEFAULT_COOLDOWN_MINUTES: u64 = 30;
+
+/// 额度快照的有效期（毫秒），超过后视为未知，避免按过期数据自动切换
+const QUOTA_STALE_MS: i64 = 2 * 60 * 60 * 1000;
+
+/// 脚本执行策略：超时、重试退避与响应大小限制
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutionPolicy {
+    pub timeout_secs: u64,
+    pub max_retries: u32,
+    pub retry_backoff_ms: u64,
+    pub max_response_bytes: usize,
+}
+
+impl ExecutionPolicy {
+    /// 仅指定超时，不重试（用于编辑器中的脚本测试）
+    pub fn with_timeout(timeout_secs: u64) -> Self {
+        Self {
+            timeout_secs,
+            max_retries: 0,
+            retry_backoff_ms: DEFAULT_RETRY_BACKOFF_MS,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+
+    /// 从已保存的脚本配置读取策略
+    pub fn from_script(script: &UsageScript) -> Self {
+        Self {
+            timeout_secs: script.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS),
+            max_retries: script.max_retries.unwrap_or(0).min(MAX_RETRIES),
+            retry_backoff_ms: script.retry_backoff_ms.unwrap_or(DEFAULT_RETRY_BACKOFF_MS),
+            max_response_bytes: script
+                .max_response_bytes
+                .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES),
+        }
+    }
+
+    /// 第 `attempt` 次重试前的等待时间（指数退避）
+    fn backoff(&self, attempt: u32) -> Duration {
+        Duration::from_millis(
+            self.retry_backoff_ms
+                .saturating_mul(1u64 << attempt.min(MAX_RETRIES)),
+        )
+    }
+}
 
 /// 执行用量查询脚本
 pub async fn execute_usage_script(
     script_code: &str,
     api_key: &str,
     base_url: &str,
-    timeout_secs: u64,
+    policy: &ExecutionPolicy,
     access_token: Option<&str>,
     user_id: Option<&str>,
 ) -> Result<Value, AppError> {
@@ -103,8 +169,8 @@ pub async fn execute_usage_script(
         )
     })?;
 
-    // 4. 发送 HTTP 请求
-    let response_data = send_http_request(&request, timeout_secs).await?;
+    // 4. 发送 HTTP 请求（按策略重试）
+    let response_data = send_with_retry(&request, policy).await?;
 
     // 5. 在独立作用域中执行 extractor（确保 Runtime/Context 在函数结束前释放）
     let result: Value = {
@@ -215,28 +281,77 @@ struct RequestConfig {
     body: Option<String>,
 }
 
+/// 单次请求失败：错误及是否值得重试
+struct RequestFailure {
+    error: AppError,
+    retryable: bool,
+}
+
+impl RequestFailure {
+    fn fatal(error: AppError) -> Self {
+        Self {
+            error,
+            retryable: false,
+        }
+    }
+
+    fn transient(error: AppError) -> Self {
+        Self {
+            error,
+            retryable: true,
+        }
+    }
+}
+
+/// 发送请求，网络错误与 HTTP 429/5xx 按指数退避重试
+async fn send_with_retry(
+    config: &RequestConfig,
+    policy: &ExecutionPolicy,
+) -> Result<String, AppError> {
+    let mut attempt = 0;
+    loop {
+        match send_http_request(config, policy).await {
+            Ok(text) => return Ok(text),
+            Err(failure) if failure.retryable && attempt < policy.max_retries => {
+                let delay = policy.backoff(attempt);
+                attempt += 1;
+                log::debug!(
+                    "用量脚本请求失败，{}ms 后进行第 {attempt} 次重试: {}",
+                    delay.as_millis(),
+                    failure.error
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(failure) => return Err(failure.error),
+        }
+    }
+}
+
 /// 发送 HTTP 请求
-async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<String, AppError> {
+async fn send_http_request(
+    config: &RequestConfig,
+    policy: &ExecutionPolicy,
+) -> Result<String, RequestFailure> {
     // 约束超时范围，防止异常配置导致长时间阻塞
-    let timeout = timeout_secs.clamp(2, 30);
+    let timeout = policy.timeout_secs.clamp(2, 30);
     let client = Client::builder()
         .timeout(Duration::from_secs(timeout))
         .build()
         .map_err(|e| {
-            AppError::localized(
+            RequestFailure::fatal(AppError::localized(
                 "usage_script.client_create_failed",
                 format!("创建客户端失败: {e}"),
                 format!("Failed to create client: {e}"),
-            )
+            ))
         })?;
 
     // 严格校验 HTTP 方法，非法值不回退为 GET
     let method: reqwest::Method = config.method.parse().map_err(|_| {
-        AppError::localized(
+        RequestFailure::fatal(AppError::localized(
             "usage_script.invalid_http_method",
             format!("不支持的 HTTP 方法: {}", config.method),
             format!("Unsupported HTTP method: {}", config.method),
-        )
+        ))
     })?;
 
     let mut req = client.request(method.clone(), &config.url);
@@ -253,21 +368,15 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
 
     // 发送请求
     let resp = req.send().await.map_err(|e| {
-        AppError::localized(
+        RequestFailure::transient(AppError::localized(
             "usage_script.request_failed",
             format!("请求失败: {e}"),
             format!("Request failed: {e}"),
-        )
+        ))
     })?;
 
     let status = resp.status();
-    let text = resp.text().await.map_err(|e| {
-        AppError::localized(
-            "usage_script.read_response_failed",
-            format!("读取响应失败: {e}"),
-            format!("Failed to read response: {e}"),
-        )
-    })?;
+    let text = read_limited_body(resp, policy.max_response_bytes).await?;
 
     if !status.is_success() {
         let preview = if text.len() > 200 {
@@ -275,16 +384,55 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
         } else {
             text.clone()
         };
-        return Err(AppError::localized(
+        let error = AppError::localized(
             "usage_script.http_error",
             format!("HTTP {status} : {preview}"),
             format!("HTTP {status} : {preview}"),
-        ));
+        );
+        return Err(RequestFailure {
+            error,
+            retryable: status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+        });
     }
 
     Ok(text)
 }
 
+/// 分块读取响应体，超过 `max_bytes` 时立即中止
+async fn read_limited_body(
+    mut resp: reqwest::Response,
+    max_bytes: usize,
+) -> Result<String, RequestFailure> {
+    let too_large = || {
+        RequestFailure::fatal(AppError::localized(
+            "usage_script.response_too_large",
+            format!("响应体超过大小上限 {max_bytes} 字节"),
+            format!("Response body exceeds the {max_bytes}-byte limit"),
+        ))
+    };
+    if resp
+        .content_length()
+        .is_some_and(|len| len > max_bytes as u64)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(|e| {
+        RequestFailure::transient(AppError::localized(
+            "usage_script.read_response_failed",
+            format!("读取响应失败: {e}"),
+            format!("Failed to read response: {e}"),
+        ))
+    })? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
 /// 验证脚本返回值（支持单对象或数组）
 fn validate_result(result: &Value) -> Result<(), AppError> {
     // 如果是数组，验证每个元素
@@ -394,3 +542,230 @@ fn validate_single_usage(result: &Value) -> Result<(), AppError> {
 
     Ok(())
 }
+
+/// 用量脚本熔断状态（进程内保存，重启后清零）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageScriptStatus {
+    pub provider_id: String,
+    pub consecutive_failures: u32,
+    /// 是否处于熔断状态（脚本被自动停用）
+    pub tripped: bool,
+    /// 熔断时间（毫秒时间戳）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tripped_at: Option<i64>,
+    /// 冷却结束、允许再次尝试的时间（毫秒时间戳）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success_at: Option<i64>,
+}
+
+/// 熔断配置（阈值为 0 表示不启用）
+#[derive(Debug, Clone, Copy)]
+pub struct BreakerPolicy {
+    pub failure_threshold: u32,
+    pub cooldown_ms: i64,
+}
+
+impl BreakerPolicy {
+    pub fn from_script(script: &UsageScript) -> Self {
+        let cooldown_minutes = script.cooldown_minutes.unwrap_or(DEFAULT_COOLDOWN_MINUTES);
+        Self {
+            failure_threshold: script
+                .failure_threshold
+                .unwrap_or(DEFAULT_FAILURE_THRESHOLD),
+            cooldown_ms: i64::try_from(cooldown_minutes.saturating_mul(60_000)).unwrap_or(i64::MAX),
+        }
+    }
+}
+
+fn breakers() -> &'static Mutex<HashMap<String, UsageScriptStatus>> {
+    static BREAKERS: OnceLock<Mutex<HashMap<String, UsageScriptStatus>>> = OnceLock::new();
+    BREAKERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 熔断期间拒绝执行；冷却结束后放行一次试探请求（成功即恢复，失败则重新熔断）
+pub fn ensure_breaker_closed(provider_id: &str, now: i64) -> Result<(), AppError> {
+    let guard = breakers().lock().map_err(AppError::from)?;
+    match guard.get(provider_id) {
+        Some(status) if status.tripped && status.retry_after.is_some_and(|at| at > now) => {
+            Err(AppError::localized(
+                "usage_script.circuit_open",
+                format!(
+                    "用量脚本连续失败 {} 次，已自动停用，冷却结束后将自动重试",
+                    status.consecutive_failures
+                ),
+                format!(
+                    "Usage script disabled after {} consecutive failures; it will retry after the cooldown",
+                    status.consecutive_failures
+                ),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 记录一次执行结果并更新熔断状态
+pub fn record_outcome(
+    provider_id: &str,
+    outcome: Result<(), String>,
+    policy: BreakerPolicy,
+    now: i64,
+) {
+    let Ok(mut guard) = breakers().lock() else {
+        return;
+    };
+    let status = guard
+        .entry(provider_id.to_string())
+        .or_insert_with(|| UsageScriptStatus {
+            provider_id: provider_id.to_string(),
+            ..Default::default()
+        });
+    match outcome {
+        Ok(()) => {
+            status.consecutive_failures = 0;
+            status.tripped = false;
+            status.tripped_at = None;
+            status.retry_after = None;
+            status.last_error = None;
+            status.last_success_at = Some(now);
+        }
+        Err(error) => {
+            status.consecutive_failures = status.consecutive_failures.saturating_add(1);
+            status.last_error = Some(error);
+            if policy.failure_threshold > 0
+                && status.consecutive_failures >= policy.failure_threshold
+            {
+                if !status.tripped {
+                    log::warn!(
+                        "供应商 {provider_id} 的用量脚本连续失败 {} 次，已自动停用",
+                        status.consecutive_failures
+                    );
+                }
+                status.tripped = true;
+                status.tripped_at = Some(now);
+                status.retry_after = Some(now.saturating_add(policy.cooldown_ms));
+            }
+        }
+    }
+}
+
+/// 查询供应商用量脚本的熔断状态
+pub fn usage_script_status(provider_id: &str) -> UsageScriptStatus {
+    breakers()
+        .lock()
+        .ok()
+        .and_then(|guard| guard.get(provider_id).cloned())
+        .unwrap_or_else(|| UsageScriptStatus {
+            provider_id: provider_id.to_string(),
+            ..Default::default()
+        })
+}
+
+/// 手动解除熔断并清空失败计数
+pub fn reset_usage_script_status(provider_id: &str) {
+    if let Ok(mut guard) = breakers().lock() {
+        guard.remove(provider_id);
+    }
+}
+
+/// 最近一次成功查询到的额度（进程内保存，供额度规则与最佳供应商评分使用）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaSnapshot {
+    /// 各有效套餐中最小的剩余额度（单位与脚本返回值一致）
+    pub remaining: f64,
+    /// 对应套餐的总额度
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    pub captured_at: i64,
+}
+
+impl QuotaSnapshot {
+    /// 从用量结果中取剩余最少的有效套餐；没有套餐返回剩余额度时为空
+    pub fn from_usage(data: &[UsageData], now: i64) -> Option<Self> {
+        data.iter()
+            .filter(|usage| usage.is_valid != Some(false))
+            .filter_map(|usage| usage.remaining.map(|remaining| (remaining, usage.total)))
+            .filter(|(remaining, _)| remaining.is_finite())
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(remaining, total)| Self {
+                remaining,
+                total,
+                captured_at: now,
+            })
+    }
+}
+
+fn quotas() -> &'static Mutex<HashMap<String, QuotaSnapshot>> {
+    static QUOTAS: OnceLock<Mutex<HashMap<String, QuotaSnapshot>>> = OnceLock::new();
+    QUOTAS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn quota_key(app_type: &AppType, provider_id: &str) -> String {
+    format!("{}:{provider_id}", app_type.as_str())
+}
+
+/// 记录一次成功查询到的额度
+pub fn record_quota(app_type: &AppType, provider_id: &str, data: &[UsageData], now: i64) {
+    let Some(snapshot) = QuotaSnapshot::from_usage(data, now) else {
+        return;
+    };
+    if let Ok(mut guard) = quotas().lock() {
+        guard.insert(quota_key(app_type, provider_id), snapshot);
+    }
+}
+
+/// 获取供应商未过期的额度快照
+pub fn latest_quota(app_type: &AppType, provider_id: &str, now: i64) -> Option<QuotaSnapshot> {
+    let guard = quotas().lock().ok()?;
+    guard
+        .get(&quota_key(app_type, provider_id))
+        .filter(|snapshot| now - snapshot.captured_at <= QUOTA_STALE_MS)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaker_trips_after_threshold_and_recovers_after_cooldown() {
+        let id = "breaker-test";
+        reset_usage_script_status(id);
+        let policy = BreakerPolicy {
+            failure_threshold: 2,
+            cooldown_ms: 1_000,
+        };
+
+        record_outcome(id, Err("boom".into()), policy, 0);
+        assert!(ensure_breaker_closed(id, 0).is_ok());
+        record_outcome(id, Err("boom".into()), policy, 10);
+        let status = usage_script_status(id);
+        assert!(status.tripped);
+        assert_eq!(status.retry_after, Some(1_010));
+        assert!(ensure_breaker_closed(id, 500).is_err());
+
+        // 冷却结束后放行试探请求，成功即恢复
+        assert!(ensure_breaker_closed(id, 1_010).is_ok());
+        record_outcome(id, Ok(()), policy, 1_020);
+        let status = usage_script_status(id);
+        assert!(!status.tripped);
+        assert_eq!(status.consecutive_failures, 0);
+
+        reset_usage_script_status(id);
+    }
+
+    #[test]
+    fn backoff_doubles_per_attempt() {
+        let policy = ExecutionPolicy {
+            retry_backoff_ms: 100,
+            ..ExecutionPolicy::with_timeout(DEFAULT_TIMEOUT_SECS)
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+    }
+}
@@ -0,0 +1,129 @@
+//! WSL 互操作：列出已安装的发行版，并通过 `\\wsl$` 路径访问其中的配置文件
+//!
+//! 发行版列表与主目录通过 `wsl.exe` 获取（主目录按发行版缓存，避免每次切换都启动发行版），
+//! 文件读写直接使用 `\\wsl$\<发行版>\home\<用户>\...` 路径。非 Windows 平台上没有发行版。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use crate::error::AppError;
+
+/// 列出已安装的发行版名称
+pub fn list_distros() -> Result<Vec<String>, AppError> {
+    if !cfg!(windows) {
+        return Ok(Vec::new());
+    }
+    let output = wsl(&["--list", "--quiet"])?;
+    if !output.status.success() {
+        // 未安装任何发行版时 wsl.exe 返回非零
+        return Ok(Vec::new());
+    }
+    Ok(parse_distro_list(&decode_output(&output.stdout)))
+}
+
+/// 发行版中当前用户主目录对应的 Windows 路径（`\\wsl$\<发行版>\home\<用户>`）
+pub fn home_dir(distro: &str) -> Result<PathBuf, AppError> {
+    static HOMES: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    let homes = HOMES.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(home) = homes.lock().unwrap_or_else(|e| e.into_inner()).get(distro) {
+        return Ok(unc_path(distro, home));
+    }
+
+    let output = wsl(&[
+        "--distribution",
+        distro,
+        "--exec",
+        "sh",
+        "-c",
+        "printf %s \"$HOME\"",
+    ])?;
+    let home = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || !home.starts_with('/') {
+        let detail = decode_output(&output.stderr).trim().to_string();
+        return Err(AppError::localized(
+            "wsl.home_unavailable",
+            format!("无法获取 WSL 发行版 {distro} 的主目录: {detail}"),
+            format!("Failed to resolve home directory in WSL distro {distro}: {detail}"),
+        ));
+    }
+    homes
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(distro.to_string(), home.clone());
+    Ok(unc_path(distro, &home))
+}
+
+/// 将发行版内的绝对路径转换为 `\\wsl$` 路径
+pub fn unc_path(distro: &str, linux_path: &str) -> PathBuf {
+    let mut path = format!(r"\\wsl$\{distro}");
+    for segment in linux_path.split('/').filter(|s| !s.is_empty()) {
+        path.push('\\');
+        path.push_str(segment);
+    }
+    PathBuf::from(path)
+}
+
+fn wsl(args: &[&str]) -> Result<std::process::Output, AppError> {
+    let mut command = std::process::Command::new("wsl.exe");
+    command.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command.output().map_err(|e| {
+        AppError::localized(
+            "wsl.unavailable",
+            format!("执行 wsl.exe 失败: {e}"),
+            format!("Failed to run wsl.exe: {e}"),
+        )
+    })
+}
+
+/// wsl.exe 自身的输出为 UTF-16LE，发行版内程序的输出为 UTF-8
+fn decode_output(bytes: &[u8]) -> String {
+    let looks_utf16 = bytes.len() >= 2
+        && bytes.chunks_exact(2).remainder().is_empty()
+        && (bytes.starts_with(&[0xff, 0xfe]) || bytes[1] == 0);
+    if looks_utf16 {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn parse_distro_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}'))
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wsl_output_and_builds_unc_paths() {
+        let utf16: Vec<u8> = "\u{feff}Ubuntu\r\ndocker-desktop\r\n\r\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        assert_eq!(
+            parse_distro_list(&decode_output(&utf16)),
+            vec!["Ubuntu".to_string(), "docker-desktop".to_string()]
+        );
+        assert_eq!(decode_output(b"/home/me"), "/home/me");
+
+        assert_eq!(
+            unc_path("Ubuntu", "/home/me/.claude/"),
+            PathBuf::from(r"\\wsl$\Ubuntu\home\me\.claude")
+        );
+    }
+}
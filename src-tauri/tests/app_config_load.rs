@@ -105,3 +105,49 @@ fn load_valid_v2_config_succeeds() {
         .is_some());
     assert!(loaded.get_manager(&cc_switch_lib::AppType::Codex).is_some());
 }
+
+#[test]
+fn load_applies_config_migrations_and_records_them() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    ensure_test_home();
+    let path = cfg_path();
+    fs::create_dir_all(path.parent().unwrap()).expect("create cfg dir");
+
+    let v2_json = r#"{
+        "version": 2,
+        "claude": {
+            "providers": {
+                "p1": {
+                    "id": "p1",
+                    "name": "Legacy",
+                    "settingsConfig": {
+                        "env": {
+                            "ANTHROPIC_MODEL": "main",
+                            "ANTHROPIC_SMALL_FAST_MODEL": "fast"
+                        }
+                    }
+                }
+            },
+            "current": "p1"
+        },
+        "codex": { "providers": {}, "current": "" },
+        "gemini": { "providers": {}, "current": "" }
+    }"#;
+    fs::write(&path, v2_json).expect("seed v2 json");
+
+    let config = MultiAppConfig::load().expect("load should succeed");
+    let env = &config.apps["claude"].providers["p1"].settings_config["env"];
+    assert_eq!(env["ANTHROPIC_DEFAULT_HAIKU_MODEL"], "fast");
+    assert_eq!(env["ANTHROPIC_DEFAULT_SONNET_MODEL"], "main");
+    assert!(env.get("ANTHROPIC_SMALL_FAST_MODEL").is_none());
+    assert_eq!(config.applied_config_migrations.len(), 1);
+
+    // 迁移结果与记录应已写回磁盘
+    let saved: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).expect("read saved")).expect("parse");
+    assert_eq!(
+        saved["applied_config_migrations"][0]["appliedCount"],
+        serde_json::json!(1)
+    );
+}
@@ -4,7 +4,7 @@ use tauri::async_runtime;
 
 use cc_switch_lib::{
     get_claude_settings_path, read_json_file, AppError, AppState, AppType, ConfigService,
-    MultiAppConfig, Provider, ProviderMeta,
+    ImportMergeStrategy, MultiAppConfig, Provider, ProviderMeta,
 };
 
 #[path = "support.rs"]
@@ -381,6 +381,68 @@ fn sync_codex_provider_missing_auth_returns_error() {
     );
 }
 
+#[test]
+fn sync_report_isolates_failing_app() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    let claude_config = json!({
+        "env": { "ANTHROPIC_AUTH_TOKEN": "ok-key" }
+    });
+    let claude = Provider::with_id(
+        "claude-ok".to_string(),
+        "Claude OK".to_string(),
+        claude_config.clone(),
+        None,
+    );
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    manager.providers.insert(claude.id.clone(), claude);
+    manager.current = "claude-ok".to_string();
+
+    let codex = Provider::with_id(
+        "codex-missing-auth".to_string(),
+        "No Auth".to_string(),
+        json!({ "config": "model = \"test\"" }),
+        None,
+    );
+    let manager = config
+        .get_manager_mut(&AppType::Codex)
+        .expect("codex manager");
+    manager.providers.insert(codex.id.clone(), codex);
+    manager.current = "codex-missing-auth".to_string();
+
+    let results = ConfigService::sync_current_providers_to_live_report(&mut config)
+        .expect("report should be produced");
+    let result_for = |app: AppType| {
+        results
+            .iter()
+            .find(|r| r.app == app)
+            .expect("result for app")
+            .clone()
+    };
+
+    let claude_result = result_for(AppType::Claude);
+    assert!(claude_result.success);
+    assert_eq!(claude_result.provider_id.as_deref(), Some("claude-ok"));
+
+    let codex_result = result_for(AppType::Codex);
+    assert!(!codex_result.success);
+    assert!(codex_result.error.is_some());
+
+    let gemini_result = result_for(AppType::Gemini);
+    assert!(gemini_result.success && gemini_result.provider_id.is_none());
+
+    // 失败的 Codex 不影响 Claude 的写入
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read claude live");
+    assert_eq!(live, claude_config);
+    assert!(!cc_switch_lib::get_codex_auth_path().exists());
+}
+
 #[test]
 fn write_codex_live_atomic_persists_auth_and_config() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
@@ -561,6 +623,8 @@ command = "echo"
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            health: None,
+            overrides: Default::default(),
         },
     );
 
@@ -686,6 +750,8 @@ fn import_from_claude_merges_into_config() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            health: None,
+            overrides: Default::default(),
         },
     );
 
@@ -1077,6 +1143,7 @@ fn export_config_to_file_writes_target_path() {
 
     let result = async_runtime::block_on(cc_switch_lib::export_config_to_file(
         export_path.to_string_lossy().to_string(),
+        None,
     ))
     .expect("export should succeed");
     assert_eq!(result.get("success").and_then(|v| v.as_bool()), Some(true));
@@ -1101,10 +1168,173 @@ fn export_config_to_file_returns_error_when_source_missing() {
 
     let err = async_runtime::block_on(cc_switch_lib::export_config_to_file(
         export_path.to_string_lossy().to_string(),
+        None,
     ))
     .expect_err("export should fail when config.json missing");
     assert!(
-        err.contains("IO 错误"),
+        err.message.contains("IO 错误"),
         "expected IO error message, got {err}"
     );
+    assert_eq!(err.code, "file_not_found");
+}
+
+#[test]
+fn live_checkpoint_restores_hand_edited_files() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    fs::create_dir_all(settings_path.parent().unwrap()).expect("create claude dir");
+    fs::write(
+        &settings_path,
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://before"}}"#,
+    )
+    .expect("write claude settings");
+    let gemini_env = home.join(".gemini").join(".env");
+
+    let checkpoint =
+        ConfigService::create_live_checkpoint(" before experiment ").expect("create checkpoint");
+    assert_eq!(checkpoint.label, "before experiment");
+    let listed = ConfigService::list_live_checkpoints();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, checkpoint.id);
+
+    // 手动改动后恢复：已改文件还原，检查点时不存在的文件被移除
+    fs::write(
+        &settings_path,
+        r#"{"env":{"ANTHROPIC_BASE_URL":"https://after"}}"#,
+    )
+    .expect("edit claude settings");
+    fs::create_dir_all(gemini_env.parent().unwrap()).expect("create gemini dir");
+    fs::write(&gemini_env, "GEMINI_API_KEY=experiment\n").expect("write gemini env");
+
+    ConfigService::restore_live_checkpoint(&checkpoint.id).expect("restore checkpoint");
+    let restored: serde_json::Value = read_json_file(&settings_path).expect("read settings");
+    assert_eq!(restored["env"]["ANTHROPIC_BASE_URL"], "https://before");
+    assert!(
+        !gemini_env.exists(),
+        "files absent at checkpoint time are removed"
+    );
+
+    assert!(ConfigService::restore_live_checkpoint("../config").is_err());
+    ConfigService::delete_live_checkpoint(&checkpoint.id).expect("delete checkpoint");
+    assert!(ConfigService::list_live_checkpoints().is_empty());
+}
+
+#[test]
+fn merge_imported_config_respects_strategy() {
+    let provider = |id: &str, token: &str| {
+        Provider::with_id(
+            id.to_string(),
+            id.to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": token } }),
+            None,
+        )
+    };
+    let config_with = |providers: &[(&str, &str)], current: &str| {
+        let mut config = MultiAppConfig::default();
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        for (id, token) in providers {
+            manager
+                .providers
+                .insert(id.to_string(), provider(id, token));
+        }
+        manager.current = current.to_string();
+        config
+    };
+    let token = |config: &MultiAppConfig, id: &str| {
+        config.get_manager(&AppType::Claude).unwrap().providers[id].settings_config["env"]
+            ["ANTHROPIC_AUTH_TOKEN"]
+            .clone()
+    };
+    let local = config_with(&[("shared", "local"), ("mine", "mine")], "mine");
+    let imported = config_with(&[("shared", "remote"), ("team", "team")], "team");
+
+    let mut merged = local.clone();
+    ConfigService::merge_imported_config(&mut merged, imported.clone(), ImportMergeStrategy::Merge);
+    assert_eq!(token(&merged, "shared"), "remote");
+    assert_eq!(token(&merged, "mine"), "mine");
+    assert_eq!(token(&merged, "team"), "team");
+    assert_eq!(
+        merged.get_manager(&AppType::Claude).unwrap().current,
+        "mine"
+    );
+
+    let mut kept = local.clone();
+    ConfigService::merge_imported_config(
+        &mut kept,
+        imported.clone(),
+        ImportMergeStrategy::KeepExisting,
+    );
+    assert_eq!(token(&kept, "shared"), "local");
+    assert_eq!(token(&kept, "team"), "team");
+
+    let mut replaced = local;
+    ConfigService::merge_imported_config(&mut replaced, imported, ImportMergeStrategy::Replace);
+    assert!(!replaced
+        .get_manager(&AppType::Claude)
+        .unwrap()
+        .providers
+        .contains_key("mine"));
+
+    let state = AppState {
+        config: RwLock::new(MultiAppConfig::default()),
+    };
+    let err = async_runtime::block_on(ConfigService::import_config_from_url(
+        &state,
+        "http://example.com/config.json",
+        ImportMergeStrategy::Replace,
+    ))
+    .expect_err("plain http is rejected");
+    assert!(err.to_string().contains("https://"));
+}
+
+#[test]
+fn list_backups_detailed_summarizes_each_backup() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let dir = ConfigService::ensure_backup_dir().expect("create backup dir");
+    let backup = json!({
+        "version": 2,
+        "claude": {
+            "providers": { "a": {}, "b": {} },
+            "current": "a"
+        },
+        "codex": { "providers": { "c": {} }, "current": "" },
+        "mcp": { "servers": { "echo": {} } }
+    });
+    fs::write(
+        dir.join("backup_20250101_000000.json"),
+        serde_json::to_string(&backup).unwrap(),
+    )
+    .expect("write backup");
+    fs::write(dir.join("backup_20250102_000000.json"), "{ broken").expect("write broken");
+
+    let page = ConfigService::list_backups_detailed(0, 1);
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items.len(), 1);
+    assert!(page.has_more);
+
+    let page = ConfigService::list_backups_detailed(0, 10);
+    let find = |id: &str| {
+        page.items
+            .iter()
+            .find(|item| item["id"] == id)
+            .unwrap_or_else(|| panic!("missing backup {id}"))
+            .clone()
+    };
+    let good = find("backup_20250101_000000");
+    assert_eq!(good["configVersion"], 2);
+    assert_eq!(good["providerCounts"], json!({ "claude": 2, "codex": 1 }));
+    assert_eq!(good["currentProviders"], json!({ "claude": "a" }));
+    assert_eq!(good["mcpServerCount"], 1);
+    assert!(good["size"].as_u64().unwrap() > 0);
+
+    let broken = find("backup_20250102_000000");
+    assert!(broken["error"].is_string());
 }
@@ -3,8 +3,9 @@ use std::{collections::HashMap, fs, sync::RwLock};
 use serde_json::json;
 
 use cc_switch_lib::{
-    get_claude_mcp_path, get_claude_settings_path, import_default_config_test_hook, AppError,
-    AppState, AppType, McpApps, McpServer, McpService, MultiAppConfig,
+    get_claude_mcp_path, get_claude_settings_path, get_codex_config_path,
+    import_default_config_test_hook, AppError, AppSettings, AppState, AppType, McpApps, McpServer,
+    McpService, McpSwitchSync, MultiAppConfig, Provider, ProviderService,
 };
 
 #[path = "support.rs"]
@@ -218,6 +219,8 @@ fn set_mcp_enabled_for_codex_writes_live_config() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            health: None,
+            overrides: Default::default(),
         },
     );
 
@@ -254,3 +257,262 @@ fn set_mcp_enabled_for_codex_writes_live_config() {
         "codex config should include the enabled server definition"
     );
 }
+
+#[test]
+fn rename_mcp_server_keeps_metadata_and_updates_live_config() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let codex_dir = home.join(".codex");
+    fs::create_dir_all(&codex_dir).expect("create codex dir");
+    fs::write(codex_dir.join("config.toml"), "").expect("create empty config.toml");
+
+    let mut config = MultiAppConfig::default();
+    config.ensure_app(&AppType::Codex);
+    config.mcp.servers = Some(HashMap::new());
+    config.mcp.servers.as_mut().unwrap().insert(
+        "old-server".into(),
+        McpServer {
+            id: "old-server".to_string(),
+            name: "Old Server".to_string(),
+            server: json!({
+                "type": "stdio",
+                "command": "echo"
+            }),
+            apps: McpApps {
+                claude: false,
+                codex: true,
+                gemini: false,
+            },
+            description: Some("keep me".to_string()),
+            homepage: None,
+            docs: None,
+            tags: vec!["tools".to_string()],
+            health: None,
+            overrides: Default::default(),
+        },
+    );
+
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+    McpService::sync_all_enabled(&state).expect("initial sync");
+
+    McpService::rename_server(&state, "old-server", "new-server").expect("rename succeeds");
+    McpService::duplicate_server(&state, "new-server", "copy-server").expect("duplicate succeeds");
+    assert!(
+        McpService::rename_server(&state, "new-server", "copy-server").is_err(),
+        "renaming onto an existing id should fail"
+    );
+
+    let guard = state.config.read().expect("lock config");
+    let servers = guard.mcp.servers.as_ref().unwrap();
+    assert!(!servers.contains_key("old-server"));
+    for id in ["new-server", "copy-server"] {
+        let entry = servers.get(id).expect("server exists");
+        assert_eq!(entry.id, id);
+        assert!(entry.apps.codex, "apps flags should be preserved");
+        assert_eq!(entry.description.as_deref(), Some("keep me"));
+        assert_eq!(entry.tags, vec!["tools".to_string()]);
+    }
+    drop(guard);
+
+    let toml_text =
+        fs::read_to_string(cc_switch_lib::get_codex_config_path()).expect("read codex config");
+    assert!(!toml_text.contains("old-server"));
+    assert!(toml_text.contains("new-server"));
+    assert!(toml_text.contains("copy-server"));
+}
+
+#[test]
+fn preview_mcp_sync_reports_changes_without_writing() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    ensure_test_home();
+
+    let mcp_path = get_claude_mcp_path();
+    let live = json!({
+        "mcpServers": {
+            "echo": { "type": "stdio", "command": "echo" },
+            "stale": { "type": "stdio", "command": "old" }
+        }
+    });
+    let live_text = serde_json::to_string_pretty(&live).expect("serialize live");
+    fs::write(&mcp_path, &live_text).expect("seed ~/.claude.json");
+
+    let mut config = MultiAppConfig::default();
+    let mut servers = HashMap::new();
+    for (id, command) in [("echo", "echo-v2"), ("fresh", "fresh")] {
+        servers.insert(
+            id.to_string(),
+            McpServer {
+                id: id.to_string(),
+                name: id.to_string(),
+                server: json!({ "type": "stdio", "command": command }),
+                apps: McpApps {
+                    claude: true,
+                    codex: false,
+                    gemini: false,
+                },
+                description: None,
+                homepage: None,
+                docs: None,
+                tags: Vec::new(),
+                health: None,
+                overrides: Default::default(),
+            },
+        );
+    }
+    config.mcp.servers = Some(servers);
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    let preview = McpService::preview_sync(&state, AppType::Claude).expect("preview succeeds");
+    assert_eq!(preview.added, vec!["fresh".to_string()]);
+    assert_eq!(preview.modified, vec!["echo".to_string()]);
+    assert_eq!(preview.removed, vec!["stale".to_string()]);
+    assert!(preview.snippet.contains("echo-v2"));
+    assert_eq!(
+        fs::read_to_string(&mcp_path).expect("read ~/.claude.json"),
+        live_text,
+        "preview must not modify the live file"
+    );
+}
+
+#[test]
+fn normalize_mcp_keys_and_resync_rewrite_live_config() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    ensure_test_home();
+
+    let mcp_path = get_claude_mcp_path();
+    let live = json!({
+        "mcpServers": {
+            "stale": { "type": "stdio", "command": "old" }
+        }
+    });
+    fs::write(
+        &mcp_path,
+        serde_json::to_string_pretty(&live).expect("serialize live"),
+    )
+    .expect("seed ~/.claude.json");
+
+    let mut config = MultiAppConfig::default();
+    let mut servers = HashMap::new();
+    servers.insert(
+        "legacy-key".to_string(),
+        McpServer {
+            id: "echo".to_string(),
+            name: "echo".to_string(),
+            server: json!({ "type": "stdio", "command": "echo" }),
+            apps: McpApps {
+                claude: true,
+                codex: false,
+                gemini: false,
+            },
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+            health: None,
+            overrides: Default::default(),
+        },
+    );
+    config.mcp.servers = Some(servers);
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    assert_eq!(McpService::normalize_keys(&state).expect("normalize"), 1);
+    {
+        let cfg = state.config.read().expect("read config");
+        let servers = cfg.mcp.servers.as_ref().expect("servers");
+        assert!(servers.contains_key("echo"));
+        assert!(!servers.contains_key("legacy-key"));
+    }
+
+    McpService::resync_app(&state, AppType::Claude).expect("resync claude");
+    let text = fs::read_to_string(&mcp_path).expect("read ~/.claude.json");
+    let value: serde_json::Value = serde_json::from_str(&text).expect("parse ~/.claude.json");
+    let live_servers = value["mcpServers"].as_object().expect("mcpServers object");
+    assert!(live_servers.contains_key("echo"));
+    assert!(
+        !live_servers.contains_key("stale"),
+        "unmanaged servers are removed"
+    );
+}
+
+#[test]
+fn switch_syncs_mcp_according_to_per_app_policy() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut settings = AppSettings::default();
+    settings
+        .mcp_switch_sync
+        .insert("claude".to_string(), McpSwitchSync::App);
+    settings
+        .mcp_switch_sync
+        .insert("gemini".to_string(), McpSwitchSync::Off);
+    cc_switch_lib::update_settings(settings).expect("save policy");
+
+    let mut config = MultiAppConfig::default();
+    for (app, settings_config) in [
+        (
+            AppType::Claude,
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "claude-key" } }),
+        ),
+        (
+            AppType::Gemini,
+            json!({ "env": { "GEMINI_API_KEY": "gemini-key" } }),
+        ),
+    ] {
+        config.ensure_app(&app);
+        config
+            .get_manager_mut(&app)
+            .expect("manager")
+            .providers
+            .insert(
+                "p1".to_string(),
+                Provider::with_id("p1".to_string(), "P1".to_string(), settings_config, None),
+            );
+    }
+    config.mcp.servers = Some(HashMap::from([(
+        "echo".to_string(),
+        McpServer {
+            id: "echo".to_string(),
+            name: "Echo".to_string(),
+            server: json!({ "type": "stdio", "command": "echo" }),
+            apps: McpApps {
+                claude: true,
+                codex: true,
+                gemini: false,
+            },
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+            health: None,
+            overrides: Default::default(),
+        },
+    )]));
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    let details = ProviderService::switch(&state, AppType::Gemini, "p1").expect("switch gemini");
+    assert!(!details.mcp_synced, "gemini policy is off");
+    assert!(!get_claude_mcp_path().exists());
+
+    let details = ProviderService::switch(&state, AppType::Claude, "p1").expect("switch claude");
+    assert!(details.mcp_synced);
+    let claude_mcp = fs::read_to_string(get_claude_mcp_path()).expect("read ~/.claude.json");
+    assert!(claude_mcp.contains("echo"));
+    assert!(
+        !get_codex_config_path().exists(),
+        "app policy should only sync the switched app"
+    );
+}
@@ -321,6 +321,185 @@ fn switch_google_official_gemini_sets_oauth_security() {
     );
 }
 
+#[test]
+fn codex_oauth_provider_restores_captured_auth_snapshot() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let login_auth = json!({
+        "OPENAI_API_KEY": null,
+        "tokens": { "access_token": "at", "refresh_token": "rt" },
+        "last_refresh": "2025-01-01T00:00:00Z"
+    });
+    write_codex_live_atomic(&login_auth, None).expect("seed codex login auth");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Codex)
+            .expect("codex manager");
+        manager.current = "official".to_string();
+        for (id, auth) in [
+            ("official", json!({})),
+            ("relay", json!({ "OPENAI_API_KEY": "sk-relay" })),
+        ] {
+            manager.providers.insert(
+                id.to_string(),
+                Provider::with_id(
+                    id.to_string(),
+                    id.to_string(),
+                    json!({ "auth": auth, "config": "" }),
+                    None,
+                ),
+            );
+        }
+    }
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    ProviderService::capture_oauth_snapshot(&state, AppType::Codex, "official")
+        .expect("capture oauth snapshot");
+    ProviderService::switch(&state, AppType::Codex, "relay").expect("switch to relay");
+    let auth: serde_json::Value =
+        read_json_file(&cc_switch_lib::get_codex_auth_path()).expect("read relay auth");
+    assert_eq!(auth, json!({ "OPENAI_API_KEY": "sk-relay" }));
+
+    ProviderService::switch(&state, AppType::Codex, "official").expect("switch back");
+    let auth: serde_json::Value =
+        read_json_file(&cc_switch_lib::get_codex_auth_path()).expect("read official auth");
+    assert_eq!(auth, login_auth);
+}
+
+#[test]
+fn claude_oauth_provider_round_trips_credentials() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let credentials_path = home.join(".claude").join(".credentials.json");
+    std::fs::create_dir_all(credentials_path.parent().unwrap()).expect("create claude dir");
+    let login = json!({ "claudeAiOauth": { "accessToken": "at", "refreshToken": "rt" } });
+    std::fs::write(&credentials_path, login.to_string()).expect("seed credentials");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "official".to_string();
+        for (id, env) in [
+            ("official", json!({})),
+            ("relay", json!({ "ANTHROPIC_AUTH_TOKEN": "relay-token" })),
+        ] {
+            manager.providers.insert(
+                id.to_string(),
+                Provider::with_id(id.to_string(), id.to_string(), json!({ "env": env }), None),
+            );
+        }
+    }
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    ProviderService::capture_oauth_snapshot(&state, AppType::Claude, "official")
+        .expect("capture claude credentials");
+    let details =
+        ProviderService::switch(&state, AppType::Claude, "relay").expect("switch to relay");
+    assert_eq!(details.previous_provider_id.as_deref(), Some("official"));
+    assert_eq!(details.provider_name, "relay");
+    assert!(!details.mcp_synced, "no MCP servers configured");
+    std::fs::remove_file(&credentials_path).expect("simulate logout");
+
+    ProviderService::switch(&state, AppType::Claude, "official").expect("switch back");
+    let restored: serde_json::Value =
+        read_json_file(&credentials_path).expect("read restored credentials");
+    assert_eq!(restored, login);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&credentials_path)
+            .expect("credentials metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn switch_gemini_preserves_unmanaged_env_keys() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let env_path = home.join(".gemini").join(".env");
+    std::fs::create_dir_all(env_path.parent().unwrap()).expect("create gemini dir");
+    std::fs::write(
+        &env_path,
+        "CUSTOM_A=1\nGEMINI_API_KEY=key-a\nGOOGLE_GEMINI_BASE_URL=https://a.example\nNO_BROWSER=1",
+    )
+    .expect("seed gemini .env");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Gemini)
+            .expect("gemini manager");
+        manager.current = "provider-a".to_string();
+        manager.providers.insert(
+            "provider-a".to_string(),
+            Provider::with_id(
+                "provider-a".to_string(),
+                "A".to_string(),
+                json!({
+                    "env": {
+                        "CUSTOM_A": "1",
+                        "GEMINI_API_KEY": "key-a",
+                        "GOOGLE_GEMINI_BASE_URL": "https://a.example"
+                    }
+                }),
+                None,
+            ),
+        );
+        manager.providers.insert(
+            "provider-b".to_string(),
+            Provider::with_id(
+                "provider-b".to_string(),
+                "B".to_string(),
+                json!({ "env": { "GEMINI_API_KEY": "key-b" } }),
+                None,
+            ),
+        );
+    }
+
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    ProviderService::switch(&state, AppType::Gemini, "provider-b")
+        .expect("switching Gemini provider should succeed");
+
+    let env = std::fs::read_to_string(&env_path).expect("read gemini .env");
+    assert_eq!(env, "GEMINI_API_KEY=key-b\nNO_BROWSER=1");
+
+    let guard = state.config.read().expect("read config");
+    let manager = guard.get_manager(&AppType::Gemini).expect("gemini manager");
+    let backfilled = &manager.providers["provider-a"].settings_config["env"];
+    assert!(
+        backfilled.get("NO_BROWSER").is_none(),
+        "unmanaged keys should not be backfilled into the previous provider"
+    );
+    assert_eq!(
+        manager.providers["provider-b"]
+            .meta
+            .as_ref()
+            .map(|meta| meta.managed_env_keys.clone()),
+        Some(vec!["GEMINI_API_KEY".to_string()])
+    );
+}
+
 #[test]
 fn provider_service_switch_claude_updates_live_and_state() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
@@ -413,6 +592,69 @@ fn provider_service_switch_claude_updates_live_and_state() {
     );
 }
 
+#[test]
+fn switch_claude_expands_placeholders_and_backfill_keeps_them() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("CC_SWITCH_TEST_GATEWAY", "https://gw.example.com");
+
+    let template = json!({
+        "env": {
+            "ANTHROPIC_BASE_URL": "${env:CC_SWITCH_TEST_GATEWAY}",
+            "NODE_EXTRA_CA_CERTS": "${HOME}/certs/ca.pem"
+        }
+    });
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.providers.insert(
+            "templated".to_string(),
+            Provider::with_id(
+                "templated".to_string(),
+                "Templated".to_string(),
+                template.clone(),
+                None,
+            ),
+        );
+        manager.providers.insert(
+            "plain".to_string(),
+            Provider::with_id(
+                "plain".to_string(),
+                "Plain".to_string(),
+                json!({ "env": { "ANTHROPIC_API_KEY": "plain-key" } }),
+                None,
+            ),
+        );
+    }
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    ProviderService::switch(&state, AppType::Claude, "templated").expect("switch to templated");
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read claude live settings");
+    let home = std::env::var("HOME").expect("HOME set for tests");
+    assert_eq!(live["env"]["ANTHROPIC_BASE_URL"], "https://gw.example.com");
+    assert_eq!(
+        live["env"]["NODE_EXTRA_CA_CERTS"],
+        format!("{home}/certs/ca.pem")
+    );
+
+    ProviderService::switch(&state, AppType::Claude, "plain").expect("switch to plain");
+    let guard = state.config.read().expect("read config");
+    let templated = guard
+        .get_manager(&AppType::Claude)
+        .and_then(|manager| manager.providers.get("templated"))
+        .expect("templated provider exists");
+    assert_eq!(
+        templated.settings_config, template,
+        "backfill should keep placeholders instead of expanded values"
+    );
+}
+
 #[test]
 fn provider_service_switch_missing_provider_returns_error() {
     let state = AppState {
@@ -630,3 +872,306 @@ fn provider_service_delete_current_provider_returns_error() {
         other => panic!("expected Config error, got {other:?}"),
     }
 }
+
+#[test]
+fn update_provider_rejects_stale_updated_at() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .current = "current".to_string();
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+    let provider = Provider::with_id(
+        "relay".into(),
+        "Relay".into(),
+        json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "token" } }),
+        None,
+    );
+    ProviderService::add(&state, AppType::Claude, provider).expect("add provider");
+
+    let loaded = |state: &AppState| {
+        state.config.read().expect("read config").apps["claude"].providers["relay"].clone()
+    };
+    // 两个窗口读取到同一版本
+    let mut window_a = loaded(&state);
+    let mut window_b = loaded(&state);
+    assert!(window_a.updated_at.is_some());
+
+    window_a.name = "Relay A".into();
+    ProviderService::update(&state, AppType::Claude, window_a).expect("first update succeeds");
+
+    window_b.name = "Relay B".into();
+    let err = ProviderService::update(&state, AppType::Claude, window_b)
+        .expect_err("stale copy should conflict");
+    match err {
+        AppError::ProviderConflict { current } => assert_eq!(current.name, "Relay A"),
+        other => panic!("expected conflict, got {other:?}"),
+    }
+    assert_eq!(loaded(&state).name, "Relay A");
+}
+
+#[test]
+fn switching_to_current_provider_is_noop_until_live_changes() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.providers.insert(
+            "relay".to_string(),
+            Provider::with_id(
+                "relay".into(),
+                "Relay".into(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "relay-token" } }),
+                None,
+            ),
+        );
+    }
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    let first = ProviderService::switch(&state, AppType::Claude, "relay").expect("first switch");
+    assert!(!first.no_change);
+
+    let settings_path = get_claude_settings_path();
+    let modified_before = std::fs::metadata(&settings_path)
+        .and_then(|meta| meta.modified())
+        .expect("settings mtime");
+    let second = ProviderService::switch(&state, AppType::Claude, "relay").expect("second switch");
+    assert!(second.no_change, "identical target should be a no-op");
+    let modified_after = std::fs::metadata(&settings_path)
+        .and_then(|meta| meta.modified())
+        .expect("settings mtime");
+    assert_eq!(modified_before, modified_after);
+
+    // 外部修改 live 文件后再次切换会重写
+    std::fs::write(&settings_path, "{}").expect("tamper live settings");
+    let third = ProviderService::switch(&state, AppType::Claude, "relay").expect("third switch");
+    assert!(!third.no_change);
+    let live: serde_json::Value = read_json_file(&settings_path).expect("read live settings");
+    assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], "relay-token");
+
+    // 显式重写
+    let forced = ProviderService::switch_with_rewrite(&state, AppType::Claude, "relay", true)
+        .expect("forced switch");
+    assert!(!forced.no_change);
+}
+
+#[test]
+fn set_provider_model_rewrites_codex_toml_and_gemini_env() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    config.ensure_app(&AppType::Codex);
+    config.ensure_app(&AppType::Gemini);
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+    let codex = Provider::with_id(
+        "relay".into(),
+        "Relay".into(),
+        json!({
+            "auth": { "OPENAI_API_KEY": "sk" },
+            "config": "# relay\nmodel = \"gpt-5\"\nmodel_provider = \"relay\"\n"
+        }),
+        None,
+    );
+    ProviderService::add(&state, AppType::Codex, codex).expect("add codex provider");
+    let gemini = Provider::with_id(
+        "flash".into(),
+        "Flash".into(),
+        json!({ "env": { "GEMINI_API_KEY": "key" } }),
+        None,
+    );
+    ProviderService::add(&state, AppType::Gemini, gemini).expect("add gemini provider");
+
+    ProviderService::set_provider_model(&state, AppType::Codex, "relay", Some(" o3 ".into()))
+        .expect("set codex model");
+    ProviderService::set_provider_model(
+        &state,
+        AppType::Gemini,
+        "flash",
+        Some("gemini-2.5-flash".into()),
+    )
+    .expect("set gemini model");
+
+    {
+        let cfg = state.config.read().expect("read config");
+        let text = cfg.apps["codex"].providers["relay"].settings_config["config"]
+            .as_str()
+            .expect("codex config text")
+            .to_string();
+        assert!(text.starts_with("# relay\n"), "comments are preserved");
+        assert!(text.contains("model = \"o3\""));
+        assert!(text.contains("model_provider = \"relay\""));
+        assert_eq!(
+            cfg.apps["gemini"].providers["flash"].settings_config["env"]["GEMINI_MODEL"],
+            "gemini-2.5-flash"
+        );
+    }
+
+    ProviderService::set_provider_model(&state, AppType::Codex, "relay", None)
+        .expect("clear codex model");
+    let cfg = state.config.read().expect("read config");
+    let text = cfg.apps["codex"].providers["relay"].settings_config["config"]
+        .as_str()
+        .expect("codex config text");
+    assert!(!text.contains("model = "));
+}
+
+#[test]
+fn switch_gemini_oauth_providers_swaps_google_account_credentials() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Gemini)
+            .expect("gemini manager");
+        manager.current = "work".to_string();
+        for (id, name) in [("work", "Google Work"), ("personal", "Google Personal")] {
+            manager.providers.insert(
+                id.to_string(),
+                Provider::with_id(id.to_string(), name.to_string(), json!({ "env": {} }), None),
+            );
+        }
+    }
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    let creds_path = home.join(".gemini").join("oauth_creds.json");
+    let write_creds = |token: &str| {
+        std::fs::create_dir_all(creds_path.parent().unwrap()).expect("create gemini dir");
+        std::fs::write(&creds_path, json!({ "refresh_token": token }).to_string())
+            .expect("write oauth creds");
+    };
+    let live_token = || {
+        read_json_file::<serde_json::Value>(&creds_path).expect("read oauth creds")["refresh_token"]
+            .as_str()
+            .map(str::to_string)
+    };
+
+    write_creds("work-1");
+    ProviderService::capture_oauth_snapshot(&state, AppType::Gemini, "work")
+        .expect("capture work account");
+
+    // 切到尚未采集凭据的供应商后登录第二个账号
+    ProviderService::switch(&state, AppType::Gemini, "personal").expect("switch to personal");
+    write_creds("personal-1");
+    ProviderService::capture_oauth_snapshot(&state, AppType::Gemini, "personal")
+        .expect("capture personal account");
+
+    // Gemini CLI 自动刷新 token 后切走，快照应随之更新
+    write_creds("personal-2");
+    ProviderService::switch(&state, AppType::Gemini, "work").expect("switch to work");
+    assert_eq!(live_token().as_deref(), Some("work-1"));
+
+    ProviderService::switch(&state, AppType::Gemini, "personal").expect("switch back");
+    assert_eq!(live_token().as_deref(), Some("personal-2"));
+}
+
+#[test]
+fn switch_with_live_verification_reports_checks() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut settings = cc_switch_lib::AppSettings {
+        verify_live_writes: true,
+        ..Default::default()
+    };
+    settings.live_verify_commands.insert(
+        "claude".to_string(),
+        "cc-switch-missing-cli --validate".to_string(),
+    );
+    cc_switch_lib::update_settings(settings).expect("enable verification");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.providers.insert(
+            "next".to_string(),
+            Provider::with_id(
+                "next".to_string(),
+                "Next".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "next-key" } }),
+                None,
+            ),
+        );
+    }
+    let state = AppState {
+        config: RwLock::new(config),
+    };
+
+    let details = ProviderService::switch(&state, AppType::Claude, "next").expect("switch");
+    let verification = details.verification.expect("verification result");
+    assert!(verification.passed);
+    let check = |name: &str| {
+        verification
+            .checks
+            .iter()
+            .find(|check| check.name == name)
+            .unwrap_or_else(|| panic!("missing check {name}"))
+    };
+    assert!(check("claude.settings").passed);
+    assert!(check("cli").skipped, "missing CLI is skipped, not failed");
+
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read live settings");
+    assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], "next-key");
+}
+
+#[test]
+fn save_conflicting_with_external_change_restores_memory_and_keeps_error() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let app_state = AppState {
+        config: RwLock::new(MultiAppConfig::default()),
+    };
+    app_state.save().expect("seed config");
+    let config_path = home.join(".cc-switch").join("config.json");
+    std::fs::write(
+        &config_path,
+        r#"{"version":2,"claude":{"providers":{},"current":""}}"#,
+    )
+    .expect("simulate external edit");
+
+    let err = ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        Provider::with_id(
+            "new".to_string(),
+            "New".to_string(),
+            json!({ "env": { "ANTHROPIC_API_KEY": "k" } }),
+            None,
+        ),
+    )
+    .expect_err("save should be refused");
+    assert_eq!(err.code(), "config.external_change");
+    assert!(!app_state
+        .config
+        .read()
+        .unwrap()
+        .get_manager(&AppType::Claude)
+        .is_some_and(|manager| manager.providers.contains_key("new")));
+}
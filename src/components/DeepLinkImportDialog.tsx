@@ -13,6 +13,7 @@ import { Button } from "@/components/ui/button";
 import { toast } from "sonner";
 import { useTranslation } from "react-i18next";
 import { useQueryClient } from "@tanstack/react-query";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 interface DeeplinkError {
   url: string;
@@ -75,7 +76,7 @@ export function DeepLinkImportDialog() {
     } catch (error) {
       console.error("Failed to import provider from deep link:", error);
       toast.error(t("deeplink.importError"), {
-        description: error instanceof Error ? error.message : String(error),
+        description: extractErrorMessage(error),
       });
     } finally {
       setIsImporting(false);
@@ -14,6 +14,7 @@ import {
   DialogHeader,
   DialogTitle,
 } from "@/components/ui/dialog";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 interface EnvWarningBannerProps {
   conflicts: EnvConflict[];
@@ -87,7 +88,7 @@ export function EnvWarningBanner({
     } catch (error) {
       console.error("删除环境变量失败:", error);
       toast.error(t("env.delete.error"), {
-        description: String(error),
+        description: extractErrorMessage(error),
       });
     } finally {
       setIsDeleting(false);
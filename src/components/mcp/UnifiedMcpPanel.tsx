@@ -20,6 +20,7 @@ import { Edit3, Trash2 } from "lucide-react";
 import { settingsApi } from "@/lib/api";
 import { mcpPresets } from "@/config/mcpPresets";
 import { toast } from "sonner";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 interface UnifiedMcpPanelProps {
   open: boolean;
@@ -75,7 +76,7 @@ const UnifiedMcpPanel: React.FC<UnifiedMcpPanelProps> = ({
       await toggleAppMutation.mutateAsync({ serverId, app, enabled });
     } catch (error) {
       toast.error(t("common.error"), {
-        description: String(error),
+        description: extractErrorMessage(error),
       });
     }
   };
@@ -102,7 +103,7 @@ const UnifiedMcpPanel: React.FC<UnifiedMcpPanelProps> = ({
           toast.success(t("common.success"));
         } catch (error) {
           toast.error(t("common.error"), {
-            description: String(error),
+            description: extractErrorMessage(error),
           });
         }
       },
@@ -13,6 +13,7 @@ import {
   DialogFooter,
 } from "@/components/ui/dialog";
 import type { CustomEndpoint, EndpointCandidate } from "@/types";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 // 端点测速超时配置（秒）
 const ENDPOINT_TIMEOUT_SECS = {
@@ -320,6 +321,8 @@ const EndpointSpeedTest: React.FC<EndpointSpeedTestProps> = ({
     try {
       const results = await vscodeApi.testApiEndpoints(urls, {
         timeoutSecs: ENDPOINT_TIMEOUT_SECS[appId],
+        appId,
+        providerId,
       });
 
       const resultMap = new Map(
@@ -364,12 +367,14 @@ const EndpointSpeedTest: React.FC<EndpointSpeedTestProps> = ({
       const message =
         error instanceof Error
           ? error.message
-          : `${t("endpointTest.testFailed", { error: String(error) })}`;
+          : `${t("endpointTest.testFailed", {
+              error: extractErrorMessage(error),
+            })}`;
       setLastError(message);
     } finally {
       setIsTesting(false);
     }
-  }, [entries, autoSelect, appId, normalizedSelected, onChange, t]);
+  }, [entries, autoSelect, appId, providerId, normalizedSelected, onChange, t]);
 
   const handleSelect = useCallback(
     (url: string) => {
@@ -4,6 +4,7 @@ import {
   hasTomlCommonConfigSnippet,
 } from "@/utils/providerConfigUtils";
 import { configApi } from "@/lib/api";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 const LEGACY_STORAGE_KEY = "cc-switch:codex-common-config-snippet";
 const DEFAULT_CODEX_COMMON_CONFIG_SNIPPET = `# Common Codex config
@@ -140,7 +141,7 @@ export function useCodexCommonConfig({
         // 保存到 config.json（清空）
         configApi.setCommonConfigSnippet("codex", "").catch((error) => {
           console.error("保存 Codex 通用配置失败:", error);
-          setCommonConfigError(`保存失败: ${error}`);
+          setCommonConfigError(`保存失败: ${extractErrorMessage(error)}`);
         });
 
         if (useCommonConfig) {
@@ -160,7 +161,7 @@ export function useCodexCommonConfig({
       // 保存到 config.json
       configApi.setCommonConfigSnippet("codex", value).catch((error) => {
         console.error("保存 Codex 通用配置失败:", error);
-        setCommonConfigError(`保存失败: ${error}`);
+        setCommonConfigError(`保存失败: ${extractErrorMessage(error)}`);
       });
 
       // 若当前启用通用配置，需要替换为最新片段
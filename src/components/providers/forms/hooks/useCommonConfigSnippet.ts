@@ -5,6 +5,7 @@ import {
   validateJsonConfig,
 } from "@/utils/providerConfigUtils";
 import { configApi } from "@/lib/api";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 const LEGACY_STORAGE_KEY = "cc-switch:common-config-snippet";
 const DEFAULT_COMMON_CONFIG_SNIPPET = `{
@@ -141,7 +142,7 @@ export function useCommonConfigSnippet({
         // 保存到 config.json（清空）
         configApi.setCommonConfigSnippet("claude", "").catch((error) => {
           console.error("保存通用配置失败:", error);
-          setCommonConfigError(`保存失败: ${error}`);
+          setCommonConfigError(`保存失败: ${extractErrorMessage(error)}`);
         });
 
         if (useCommonConfig) {
@@ -165,7 +166,7 @@ export function useCommonConfigSnippet({
         // 保存到 config.json
         configApi.setCommonConfigSnippet("claude", value).catch((error) => {
           console.error("保存通用配置失败:", error);
-          setCommonConfigError(`保存失败: ${error}`);
+          setCommonConfigError(`保存失败: ${extractErrorMessage(error)}`);
         });
       }
 
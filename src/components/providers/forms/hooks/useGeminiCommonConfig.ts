@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { configApi } from "@/lib/api";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 const LEGACY_STORAGE_KEY = "cc-switch:gemini-common-config-snippet";
 const DEFAULT_GEMINI_COMMON_CONFIG_SNIPPET = `{
@@ -239,7 +240,7 @@ export function useGeminiCommonConfig({
         // 保存到 config.json（清空）
         configApi.setCommonConfigSnippet("gemini", "").catch((error) => {
           console.error("保存 Gemini 通用配置失败:", error);
-          setCommonConfigError(`保存失败: ${error}`);
+          setCommonConfigError(`保存失败: ${extractErrorMessage(error)}`);
         });
 
         if (useCommonConfig) {
@@ -267,7 +268,7 @@ export function useGeminiCommonConfig({
         // 保存到 config.json
         configApi.setCommonConfigSnippet("gemini", value).catch((error) => {
           console.error("保存 Gemini 通用配置失败:", error);
-          setCommonConfigError(`保存失败: ${error}`);
+          setCommonConfigError(`保存失败: ${extractErrorMessage(error)}`);
         });
       } catch {
         setCommonConfigError("通用配置片段格式错误（必须是有效的 JSON）");
@@ -20,6 +20,10 @@ export function useDragSort(providers: Record<string, Provider>, appId: AppId) {
   const sortedProviders = useMemo(() => {
     const locale = i18n.language === "zh" ? "zh-CN" : "en-US";
     return Object.values(providers).sort((a, b) => {
+      // 置顶的供应商始终排在前面
+      if (!!a.pinned !== !!b.pinned) {
+        return a.pinned ? -1 : 1;
+      }
       if (a.sortIndex !== undefined && b.sortIndex !== undefined) {
         return a.sortIndex - b.sortIndex;
       }
@@ -82,8 +82,12 @@ export function useProviderActions(activeApp: AppId) {
   const switchProvider = useCallback(
     async (provider: Provider) => {
       try {
-        await switchProviderMutation.mutateAsync(provider.id);
-        await syncClaudePlugin(provider);
+        const outcome = await switchProviderMutation.mutateAsync({
+          providerId: provider.id,
+        });
+        if (outcome.switched) {
+          await syncClaudePlugin(provider);
+        }
       } catch {
         // 错误提示由 mutation 与同步函数处理
       }
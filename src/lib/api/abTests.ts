@@ -0,0 +1,59 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+import type { ProviderMetrics } from "./proxy";
+
+// proxy：本地代理按请求交替转发；rotation：按时长轮流切换当前供应商
+export type AbTestMode = "proxy" | "rotation";
+
+export interface AbTest {
+  id: string;
+  name?: string;
+  app: AppId;
+  providerA: string;
+  providerB: string;
+  mode: AbTestMode;
+  // 轮换模式下每个供应商连续使用的小时数（默认 24）
+  rotationHours?: number;
+  // 开始 / 结束时间（Unix 毫秒），由后端填写
+  startedAt?: number;
+  endedAt?: number;
+}
+
+export interface AbTestArm {
+  providerId: string;
+  providerName: string;
+  metrics: ProviderMetrics;
+  // 占两者请求总数的比例（0~1）
+  share: number;
+}
+
+export interface AbTestReport {
+  test: AbTest;
+  until: number;
+  arms: AbTestArm[];
+  // 样本不足或差异不明显时缺省
+  winner?: string;
+}
+
+// 指标来自经过本地代理的请求，测试期间需启用代理
+export const abTestsApi = {
+  async list(): Promise<AbTest[]> {
+    return await invoke("list_ab_tests");
+  },
+
+  async start(test: AbTest): Promise<AbTest> {
+    return await invoke("start_ab_test", { test });
+  },
+
+  async stop(id: string): Promise<AbTest> {
+    return await invoke("stop_ab_test", { id });
+  },
+
+  async delete(id: string): Promise<boolean> {
+    return await invoke("delete_ab_test", { id });
+  },
+
+  async getReport(id: string): Promise<AbTestReport> {
+    return await invoke("get_ab_test_report", { id });
+  },
+};
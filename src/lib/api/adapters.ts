@@ -0,0 +1,63 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Provider } from "@/types";
+
+export type AdapterFileFormat = "env" | "json" | "toml";
+
+export interface AdapterFile {
+  id: string;
+  // 以 ~/ 开头、相对用户主目录的路径
+  path: string;
+  format: AdapterFileFormat;
+}
+
+// 供应商字段到配置文件键的映射（json / toml 用 . 分隔嵌套路径）
+export interface AdapterField {
+  key: string;
+  label?: string;
+  file: string;
+  path: string;
+  required: boolean;
+  secret: boolean;
+  pattern?: string;
+}
+
+// ~/.cc-switch/adapters/<id>.json 中的应用适配器清单
+export interface AppAdapter {
+  id: string;
+  name: string;
+  description?: string;
+  files: AdapterFile[];
+  fields: AdapterField[];
+}
+
+export interface AdapterProviders {
+  current: string;
+  providers: Record<string, Provider>;
+}
+
+// 适配器应用的供应商 settingsConfig 为扁平的 { 字段 key: 值 }
+export const adaptersApi = {
+  async list(): Promise<AppAdapter[]> {
+    return await invoke("list_app_adapters");
+  },
+
+  async getProviders(adapter: string): Promise<AdapterProviders> {
+    return await invoke("get_adapter_providers", { adapter });
+  },
+
+  async readLiveConfig(adapter: string): Promise<Record<string, string>> {
+    return await invoke("read_adapter_live_config", { adapter });
+  },
+
+  async saveProvider(adapter: string, provider: Provider): Promise<boolean> {
+    return await invoke("save_adapter_provider", { adapter, provider });
+  },
+
+  async deleteProvider(adapter: string, id: string): Promise<boolean> {
+    return await invoke("delete_adapter_provider", { adapter, id });
+  },
+
+  async switchProvider(adapter: string, id: string): Promise<boolean> {
+    return await invoke("switch_adapter_provider", { adapter, id });
+  },
+};
@@ -0,0 +1,60 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { SkillRepo } from "./skills";
+
+// Claude Code 子代理（~/.claude/agents/<name>.md，停用时移动到 agents-disabled）
+export interface SubAgent {
+  name: string;
+  description?: string;
+  content: string;
+  enabled: boolean;
+}
+
+// 子代理仓库中可安装的定义
+export interface RepoAgent {
+  name: string;
+  description?: string;
+  path: string; // 仓库内相对路径
+  repoOwner: string;
+  repoName: string;
+  repoBranch: string;
+  installed: boolean;
+}
+
+export const agentsApi = {
+  async getAll(): Promise<SubAgent[]> {
+    return await invoke("get_sub_agents");
+  },
+
+  async save(name: string, content: string): Promise<SubAgent> {
+    return await invoke("save_sub_agent", { name, content });
+  },
+
+  async delete(name: string): Promise<boolean> {
+    return await invoke("delete_sub_agent", { name });
+  },
+
+  async setEnabled(name: string, enabled: boolean): Promise<void> {
+    return await invoke("set_sub_agent_enabled", { name, enabled });
+  },
+
+  async getRepoAgents(): Promise<RepoAgent[]> {
+    return await invoke("get_repo_agents");
+  },
+
+  async install(agent: RepoAgent): Promise<SubAgent> {
+    return await invoke("install_repo_agent", { agent });
+  },
+
+  // 仓库结构与技能仓库一致，skillsPath 表示子代理所在子目录
+  async getRepos(): Promise<SkillRepo[]> {
+    return await invoke("get_agent_repos");
+  },
+
+  async addRepo(repo: SkillRepo): Promise<boolean> {
+    return await invoke("add_agent_repo", { repo });
+  },
+
+  async removeRepo(owner: string, name: string): Promise<boolean> {
+    return await invoke("remove_agent_repo", { owner, name });
+  },
+};
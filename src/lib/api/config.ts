@@ -1,5 +1,6 @@
 // 配置相关 API
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 export type AppType = "claude" | "codex" | "gemini";
 
@@ -47,3 +48,27 @@ export async function setCommonConfigSnippet(
 ): Promise<void> {
   return invoke("set_common_config_snippet", { appType, snippet });
 }
+
+export interface ConfigChangedEvent {
+  // 单调递增的配置修订号（进程内有效）
+  revision: number;
+  changedAt: number;
+}
+
+/**
+ * 获取当前配置修订号，可与 config-changed 事件配合做缓存失效
+ */
+export async function getConfigRevision(): Promise<number> {
+  return invoke<number>("get_config_revision");
+}
+
+/**
+ * 监听配置变更（托盘切换、深链接导入、调度器等任意来源的保存）
+ */
+export async function onConfigChanged(
+  handler: (event: ConfigChangedEvent) => void,
+): Promise<UnlistenFn> {
+  return await listen<ConfigChangedEvent>("config-changed", (event) => {
+    handler(event.payload);
+  });
+}
@@ -10,6 +10,8 @@ export interface DeepLinkImportRequest {
   apiKey: string;
   model?: string;
   notes?: string;
+  // Codex wire API; probed on import when omitted
+  wireApi?: "responses" | "chat";
 }
 
 export const deeplinkApi = {
@@ -32,4 +34,22 @@ export const deeplinkApi = {
   ): Promise<string> => {
     return invoke("import_from_deeplink", { request });
   },
+
+  /**
+   * Generate a ccswitch:// deep link for an existing provider
+   * @param includeApiKey Embed the API key (requires unlockToken when a passcode is set)
+   * @returns The generated deep link URL
+   */
+  generateDeeplink: async (
+    app: "claude" | "codex" | "gemini",
+    providerId: string,
+    options?: { includeApiKey?: boolean; unlockToken?: string },
+  ): Promise<string> => {
+    return invoke("generate_deeplink", {
+      app,
+      providerId,
+      includeApiKey: options?.includeApiKey,
+      unlockToken: options?.unlockToken,
+    });
+  },
 };
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { EnvConflict, BackupInfo } from "@/types/env";
+import type { EnvConflict, BackupInfo, InstalledCli } from "@/types/env";
 
 /**
  * 环境变量管理 API
@@ -35,6 +35,13 @@ export async function restoreEnvBackup(backupPath: string): Promise<void> {
   return invoke<void>("restore_env_backup", { backupPath });
 }
 
+/**
+ * 检测 claude / codex / gemini CLI 是否已安装及其版本
+ */
+export async function detectInstalledClis(): Promise<InstalledCli[]> {
+  return invoke<InstalledCli[]>("detect_installed_clis");
+}
+
 /**
  * 检查所有应用的环境变量冲突
  * @returns 按应用类型分组的环境变量冲突
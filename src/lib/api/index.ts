@@ -1,10 +1,85 @@
-export type { AppId } from "./types";
+export type { AppId, Page, PageRequest } from "./types";
 export { providersApi } from "./providers";
 export { settingsApi } from "./settings";
 export { mcpApi } from "./mcp";
 export { promptsApi } from "./prompts";
+export { slashCommandsApi } from "./slashCommands";
+export { agentsApi } from "./agents";
 export { usageApi } from "./usage";
 export { vscodeApi } from "./vscode";
+export { switchRulesApi } from "./switchRules";
+export { tokensApi } from "./tokens";
+export { searchApi } from "./search";
+export { subscriptionsApi } from "./subscriptions";
+export { registryApi } from "./registry";
+export { remoteHostsApi } from "./remoteHosts";
+export { adaptersApi } from "./adapters";
+export { proxyApi } from "./proxy";
+export { abTestsApi } from "./abTests";
+export { teamPacksApi } from "./teamPacks";
 export * as configApi from "./config";
-export type { ProviderSwitchEvent } from "./providers";
-export type { Prompt } from "./prompts";
+export type {
+  CapabilityReport,
+  CapabilitySuggestion,
+  CsvColumnMapping,
+  CsvImportResult,
+  ProviderRank,
+  ProviderSwitchEvent,
+  QuotaSnapshot,
+  SwitchOutcome,
+  SwitchWarning,
+  TemporarySwitch,
+  TemporarySwitchCountdown,
+  UpcomingRenewal,
+  WireApiFix,
+} from "./providers";
+export type { GlobalInstructions, Prompt } from "./prompts";
+export type { SlashCommand } from "./slashCommands";
+export type { RepoAgent, SubAgent } from "./agents";
+export type { ModelFamily, TokenCount } from "./tokens";
+export type { SearchHit, SearchHitKind } from "./search";
+export type {
+  BestProviderSwitch,
+  NetworkStatus,
+  QuotaSwitchEvent,
+  SwitchRule,
+  SwitchRuleCondition,
+} from "./switchRules";
+export type {
+  SubscribedProvider,
+  SubscriptionPreset,
+  SubscriptionSource,
+} from "./subscriptions";
+export type {
+  Registry,
+  RegistryKind,
+  RegistryRefresh,
+  RegistryRefreshReport,
+} from "./registry";
+export type { RemoteSwitchResult } from "./remoteHosts";
+export type {
+  AdapterField,
+  AdapterFile,
+  AdapterFileFormat,
+  AdapterProviders,
+  AppAdapter,
+} from "./adapters";
+export type {
+  MetricsPeriod,
+  ProviderMetrics,
+  ProxyMetricsReport,
+  ProxyRequestLog,
+  ProxyStatus,
+} from "./proxy";
+export type { AbTest, AbTestArm, AbTestMode, AbTestReport } from "./abTests";
+export type {
+  TeamPackApplyResult,
+  TeamPackItem,
+  TeamPackItemKind,
+  TeamPackItemRef,
+  TeamPackManifest,
+  TeamPackPreview,
+  TeamPackResolution,
+  TeamPackSelection,
+  TeamPackSkill,
+} from "./teamPacks";
@@ -5,8 +5,11 @@ import type {
   McpServerSpec,
   McpServersMap,
   McpStatus,
+  McpSyncPreview,
+  McpUsageStat,
+  ProjectMcpImport,
 } from "@/types";
-import type { AppId } from "./types";
+import type { AppId, Page, PageRequest } from "./types";
 
 export const mcpApi = {
   async getStatus(): Promise<McpStatus> {
@@ -95,6 +98,15 @@ export const mcpApi = {
     return await invoke("get_mcp_servers");
   },
 
+  /**
+   * 分页获取 MCP 服务器（默认不包含 server 定义）
+   */
+  async getServersPage(
+    request: PageRequest = {},
+  ): Promise<Page<Partial<McpServer> & { id: string }>> {
+    return await invoke("get_mcp_servers_page", { ...request });
+  },
+
   /**
    * 添加或更新 MCP 服务器（统一结构）
    */
@@ -109,6 +121,20 @@ export const mcpApi = {
     return await invoke("delete_mcp_server", { id });
   },
 
+  /**
+   * 复制 MCP 服务器为新 ID（保留启用的应用、描述与标签）
+   */
+  async duplicateServer(id: string, newId: string): Promise<McpServer> {
+    return await invoke("duplicate_mcp_server", { id, newId });
+  },
+
+  /**
+   * 重命名 MCP 服务器 ID，并同步更新各应用的 live 配置
+   */
+  async renameServer(id: string, newId: string): Promise<McpServer> {
+    return await invoke("rename_mcp_server", { id, newId });
+  },
+
   /**
    * 切换 MCP 服务器在指定应用的启用状态
    */
@@ -119,4 +145,46 @@ export const mcpApi = {
   ): Promise<void> {
     return await invoke("toggle_mcp_app", { serverId, app, enabled });
   },
+
+  /**
+   * 预览同步到指定应用将产生的变更（新增/移除/修改及渲染后的片段）
+   */
+  async previewSync(app: AppId): Promise<McpSyncPreview> {
+    return await invoke("preview_mcp_sync", { app });
+  },
+
+  /**
+   * 统计 Claude 会话记录中各 MCP 服务器的调用次数（含从未调用的已配置服务器）
+   */
+  async getUsageStats(): Promise<McpUsageStat[]> {
+    return await invoke("get_mcp_usage_stats");
+  },
+
+  /**
+   * 获取最近一次健康检查中不健康的服务器（命令缺失、包版本被撤回、URL 404）
+   */
+  async getUnhealthyServers(): Promise<McpServer[]> {
+    return await invoke("get_unhealthy_mcp_servers");
+  },
+
+  /**
+   * 立即执行健康检查，返回不健康的服务器
+   */
+  async checkHealth(): Promise<McpServer[]> {
+    return await invoke("check_mcp_health");
+  },
+
+  /**
+   * 从项目目录（或 .mcp.json 文件）导入 MCP 服务器，新服务器带项目标签且默认不启用
+   */
+  async importFromProject(path: string): Promise<ProjectMcpImport> {
+    return await invoke("import_mcp_from_project", { path });
+  },
+
+  /**
+   * 将选中的服务器导出为项目的 .mcp.json（密钥类环境变量写为 ${变量名}），返回文件路径
+   */
+  async exportToProject(path: string, ids: string[]): Promise<string> {
+    return await invoke("export_mcp_to_project", { path, ids });
+  },
 };
@@ -11,6 +11,15 @@ export interface Prompt {
   updatedAt?: number;
 }
 
+// 全局指令文件（CLAUDE.md / AGENTS.md / GEMINI.md）
+export interface GlobalInstructions {
+  path: string;
+  exists: boolean;
+  content: string;
+  // cc-switch 管理区段内的内容
+  managed?: string;
+}
+
 export const promptsApi = {
   async getPrompts(app: AppId): Promise<Record<string, Prompt>> {
     return await invoke("get_prompts", { app });
@@ -35,4 +44,16 @@ export const promptsApi = {
   async getCurrentFileContent(app: AppId): Promise<string | null> {
     return await invoke("get_current_prompt_file_content", { app });
   },
+
+  async readGlobalInstructions(app: AppId): Promise<GlobalInstructions> {
+    return await invoke("read_global_instructions", { app });
+  },
+
+  // 仅替换 cc-switch 管理区段，区段外的内容保持不变；写入前自动备份
+  async writeGlobalInstructions(
+    app: AppId,
+    content: string,
+  ): Promise<GlobalInstructions> {
+    return await invoke("write_global_instructions", { app, content });
+  },
 };
@@ -1,16 +1,235 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
-import type { Provider } from "@/types";
-import type { AppId } from "./types";
+import type {
+  BillingCycle,
+  ClaudeSettingsOverrides,
+  Provider,
+  ProviderBilling,
+  ProviderPerformance,
+  ProviderPricing,
+  RankingWeights,
+} from "@/types";
+import type { AppId, Page, PageRequest } from "./types";
+import { isCommandError } from "@/utils/errorUtils";
 
 export interface ProviderSortUpdate {
   id: string;
   sortIndex: number;
 }
 
+// 批量导入默认配置时单个应用的结果（import_all_default_configs）
+export interface DefaultImportResult {
+  appType: AppId;
+  status: "imported" | "already_configured" | "not_installed" | "failed";
+  error?: string;
+}
+
 export interface ProviderSwitchEvent {
   appType: AppId;
   providerId: string;
+  previousProviderId?: string;
+  providerName: string;
+  // 写入 live 配置耗时（毫秒）
+  liveWriteMs: number;
+  // 本次切换是否同步了 MCP 服务器
+  mcpSynced: boolean;
+  // 目标已是当前供应商且 live 配置未变化，未做任何写入
+  noChange: boolean;
+  // 写入校验结果（开启 verifyLiveWrites 时）
+  verification?: LiveVerification;
+}
+
+// 切换后的 live 写入校验
+export interface LiveVerification {
+  passed: boolean;
+  checks: Array<{
+    // 如 claude.settings、codex.config、cli
+    name: string;
+    passed: boolean;
+    skipped?: boolean;
+    detail?: string;
+  }>;
+}
+
+// 供应商搜索筛选条件（search_providers）
+export interface ProviderSearchFilters {
+  categories?: string[];
+  tags?: string[];
+  maxLatencyMs?: number;
+}
+
+export interface ProviderSearchHit {
+  id: string;
+  provider: Provider;
+  score: number;
+  matchedFields: Array<"name" | "tags" | "endpoint" | "notes">;
+  latency?: number;
+}
+
+// 供应商对比中的单个字段（密钥已掩码）
+export interface ProviderFieldDiff {
+  path: string;
+  kind: "added" | "removed" | "changed" | "same";
+  a?: unknown;
+  b?: unknown;
+}
+
+export interface ComparedProvider {
+  id: string;
+  name: string;
+  category?: string;
+  baseUrl?: string;
+  customEndpoints: string[];
+  latency?: number;
+  pricing?: ProviderPricing;
+  billing?: ProviderBilling;
+}
+
+export interface ProviderComparison {
+  appType: AppId;
+  a: ComparedProvider;
+  b: ComparedProvider;
+  settings: ProviderFieldDiff[];
+  models: ProviderFieldDiff[];
+  endpoints: ProviderFieldDiff[];
+  faster?: "a" | "b";
+  pricing: ProviderFieldDiff[];
+  cheaper?: "a" | "b";
+}
+
+// 切换前健康预检警告（最近一次测速失败或延迟超过阈值）
+export interface SwitchWarning {
+  kind: "high_latency" | "probe_failed";
+  providerId: string;
+  baseUrl: string;
+  latency?: number;
+  thresholdMs: number;
+  error?: string;
+  testedAt: number;
+}
+
+export interface SwitchOutcome {
+  switched: boolean;
+  warning?: SwitchWarning;
+  // 切换详情（与 provider-switched 事件载荷一致）；noChange 时 switched 为 false
+  details?: ProviderSwitchEvent;
+}
+
+export type ApiProtocol = "anthropic" | "openai_chat" | "openai_responses";
+
+export interface RouteProbe {
+  protocol: ApiProtocol;
+  url: string;
+  supported: boolean;
+  status?: number;
+  error?: string;
+}
+
+export interface CapabilitySuggestion {
+  app: AppId;
+  wireApi?: "responses" | "chat";
+  settingsConfig: Record<string, any>;
+}
+
+// 端点能力探测报告（suggestions 按推荐程度排序）
+export interface CapabilityReport {
+  baseUrl: string;
+  probes: RouteProbe[];
+  suggestions: CapabilitySuggestion[];
+}
+
+export interface WireApiFix {
+  wireApi: "responses" | "chat";
+  previous?: string;
+  changed: boolean;
+}
+
+export interface TemporarySwitch {
+  appType: AppId;
+  providerId: string;
+  previousProviderId: string;
+  // 回退时间（Unix 毫秒）
+  revertAt: number;
+}
+
+export interface TemporarySwitchCountdown extends TemporarySwitch {
+  remainingSecs: number;
+}
+
+// 最近一次用量查询得到的额度
+export interface QuotaSnapshot {
+  remaining: number;
+  total?: number;
+  capturedAt: number;
+}
+
+export interface ProviderRank {
+  providerId: string;
+  providerName: string;
+  // 0~1，不可用时缺省
+  score?: number;
+  // 排名第一的可用供应商，界面中标记为“推荐”
+  recommended: boolean;
+  // 各维度得分（0~1）
+  components: RankingWeights;
+  latencyMs?: number;
+  // 最近 24 小时经本地代理的错误率
+  errorRate?: number;
+  quota?: QuotaSnapshot;
+  // 不可用原因（quota exhausted / endpoint unreachable）
+  unavailable?: string;
+}
+
+// CSV 导入的列映射（表头列名，不区分大小写）；未指定的列按常见列名自动匹配
+export interface CsvColumnMapping {
+  name?: string;
+  endpoint?: string;
+  apiKey?: string;
+  notes?: string;
+  model?: string;
+  homepage?: string;
+}
+
+export interface CsvImportResult {
+  // 新建的供应商 ID
+  imported: string[];
+  // row 为文件中的行号（表头为第 1 行）
+  errors: { row: number; name?: string; message: string }[];
+}
+
+// 即将到来的供应商续费（get_upcoming_renewals / billing-renewal-reminder 事件）
+export interface UpcomingRenewal {
+  appType: AppId;
+  providerId: string;
+  providerName: string;
+  cycle: BillingCycle;
+  renewalDate: string;
+  // 距续费的天数，当天为 0
+  daysLeft: number;
+  monthlyCost?: number;
+  currency?: string;
+  // 已进入提醒时间
+  remind: boolean;
+  // 按界面语言格式化的提醒文本
+  summary: string;
+}
+
+// update_provider 冲突错误：供应商已被其他窗口或托盘修改
+export interface ProviderConflict {
+  code: "provider_conflict";
+  message: string;
+  current: Provider;
+}
+
+// 解析 update_provider 的错误；冲突时返回当前已保存的版本，否则返回 null
+export function parseProviderConflict(error: unknown): ProviderConflict | null {
+  if (!isCommandError(error) || error.code !== "provider_conflict") {
+    return null;
+  }
+  const current = error.details?.current as Provider | undefined;
+  return current
+    ? { code: "provider_conflict", message: error.message, current }
+    : null;
 }
 
 export const providersApi = {
@@ -18,6 +237,29 @@ export const providersApi = {
     return await invoke("get_providers", { app: appId });
   },
 
+  async getPage(
+    appId: AppId,
+    request: PageRequest = {},
+  ): Promise<Page<Partial<Provider> & { id: string }>> {
+    return await invoke("get_providers_page", { app: appId, ...request });
+  },
+
+  async search(
+    appId: AppId,
+    query: string,
+    filters?: ProviderSearchFilters,
+  ): Promise<ProviderSearchHit[]> {
+    return await invoke("search_providers", { app: appId, query, filters });
+  },
+
+  async compare(
+    appId: AppId,
+    idA: string,
+    idB: string,
+  ): Promise<ProviderComparison> {
+    return await invoke("compare_providers", { app: appId, idA, idB });
+  },
+
   async getCurrent(appId: AppId): Promise<string> {
     return await invoke("get_current_provider", { app: appId });
   },
@@ -34,14 +276,24 @@ export const providersApi = {
     return await invoke("delete_provider", { id, app: appId });
   },
 
-  async switch(id: string, appId: AppId): Promise<boolean> {
-    return await invoke("switch_provider", { id, app: appId });
+  // 预检未通过时不切换并返回 warning，用户确认后以 force=true 重试（同时强制重写 live 配置）
+  async switch(
+    id: string,
+    appId: AppId,
+    force?: boolean,
+  ): Promise<SwitchOutcome> {
+    return await invoke("switch_provider", { id, app: appId, force });
   },
 
   async importDefault(appId: AppId): Promise<boolean> {
     return await invoke("import_default_config", { app: appId });
   },
 
+  // 首次使用：为所有已安装且尚无供应商的 CLI 导入当前配置
+  async importAllDefaults(): Promise<DefaultImportResult[]> {
+    return await invoke("import_all_default_configs");
+  },
+
   async updateTrayMenu(): Promise<boolean> {
     return await invoke("update_tray_menu");
   },
@@ -53,6 +305,231 @@ export const providersApi = {
     return await invoke("update_providers_sort_order", { updates, app: appId });
   },
 
+  // 跨应用拖拽排序：一次提交多个应用的排序并只保存一次
+  async updateSortOrderMulti(
+    updates: Partial<Record<AppId, ProviderSortUpdate[]>>,
+  ): Promise<boolean> {
+    return await invoke("update_sort_order_multi", { updates });
+  },
+
+  /**
+   * 设置供应商默认模型（传 null 移除）；Codex 改写 config.toml 中的 model
+   */
+  async setModel(
+    providerId: string,
+    model: string | null,
+    appId: AppId,
+  ): Promise<boolean> {
+    return await invoke("set_provider_model", {
+      providerId,
+      model,
+      app: appId,
+    });
+  },
+
+  /**
+   * 读取 Codex 供应商 config.toml 中的单个值，键路径如 `@provider.base_url`、`model_reasoning_effort`
+   */
+  async getCodexConfigValue(
+    providerId: string,
+    keyPath: string,
+  ): Promise<unknown | null> {
+    return await invoke("get_codex_config_value", { providerId, keyPath });
+  },
+
+  /**
+   * 设置 Codex 供应商 config.toml 中的单个值（传 null 移除该键）
+   */
+  async setCodexConfigValue(
+    providerId: string,
+    keyPath: string,
+    value: unknown,
+  ): Promise<boolean> {
+    return await invoke("set_codex_config_value", {
+      providerId,
+      keyPath,
+      value,
+    });
+  },
+
+  async setPinned(
+    providerId: string,
+    pinned: boolean,
+    appId: AppId,
+  ): Promise<boolean> {
+    return await invoke("set_provider_pinned", {
+      providerId,
+      pinned,
+      app: appId,
+    });
+  },
+
+  async setHeaders(
+    providerId: string,
+    headers: Record<string, string>,
+    appId: AppId,
+  ): Promise<boolean> {
+    return await invoke("set_provider_headers", {
+      providerId,
+      headers,
+      app: appId,
+    });
+  },
+
+  async setPerformance(
+    providerId: string,
+    performance: ProviderPerformance,
+    appId: AppId,
+  ): Promise<boolean> {
+    return await invoke("set_provider_performance", {
+      providerId,
+      performance,
+      app: appId,
+    });
+  },
+
+  async setClaudeSettings(
+    providerId: string,
+    settings: ClaudeSettingsOverrides,
+  ): Promise<boolean> {
+    return await invoke("set_claude_provider_settings", {
+      providerId,
+      settings,
+    });
+  },
+
+  async setExtraEnv(
+    providerId: string,
+    env: Record<string, string>,
+    appId: AppId,
+  ): Promise<boolean> {
+    return await invoke("set_provider_extra_env", {
+      providerId,
+      env,
+      app: appId,
+    });
+  },
+
+  // 未传权重时使用设置中的评分权重
+  async rank(appId: AppId, weights?: RankingWeights): Promise<ProviderRank[]> {
+    return await invoke("rank_providers", { app: appId, weights });
+  },
+
+  // 从 CSV（电子表格导出）批量创建供应商，返回新建 ID 与各行错误
+  async importFromCsv(
+    appId: AppId,
+    path: string,
+    columnMapping?: CsvColumnMapping,
+  ): Promise<CsvImportResult> {
+    return await invoke("import_providers_from_csv", {
+      app: appId,
+      path,
+      columnMapping,
+    });
+  },
+
+  // 列出 withinDays 天内（默认 30 天）所有应用的续费
+  async getUpcomingRenewals(withinDays?: number): Promise<UpcomingRenewal[]> {
+    return await invoke("get_upcoming_renewals", { withinDays });
+  },
+
+  async onRenewalReminder(
+    handler: (renewals: UpcomingRenewal[]) => void,
+  ): Promise<UnlistenFn> {
+    return await listen("billing-renewal-reminder", (event) => {
+      handler(event.payload as UpcomingRenewal[]);
+    });
+  },
+
+  // 临时切换：到期后自动回退到原供应商
+  async switchTemporarily(
+    providerId: string,
+    durationMinutes: number,
+    appId: AppId,
+  ): Promise<TemporarySwitch> {
+    return await invoke("switch_temporarily", {
+      providerId,
+      durationMinutes,
+      app: appId,
+    });
+  },
+
+  async getTemporarySwitch(appId: AppId): Promise<TemporarySwitch | null> {
+    return await invoke("get_temporary_switch", { app: appId });
+  },
+
+  async cancelTemporarySwitch(appId: AppId): Promise<boolean> {
+    return await invoke("cancel_temporary_switch", { app: appId });
+  },
+
+  async onTemporarySwitchCountdown(
+    handler: (event: TemporarySwitchCountdown) => void,
+  ): Promise<UnlistenFn> {
+    return await listen("temporary-switch-countdown", (event) => {
+      handler(event.payload as TemporarySwitchCountdown);
+    });
+  },
+
+  async onTemporarySwitchReverted(
+    handler: (event: TemporarySwitch) => void,
+  ): Promise<UnlistenFn> {
+    return await listen("temporary-switch-reverted", (event) => {
+      handler(event.payload as TemporarySwitch);
+    });
+  },
+
+  // 探测未知中转站支持的协议，返回推荐的应用类型与配置模板
+  async probeCapabilities(
+    baseUrl: string,
+    apiKey: string,
+    timeoutSecs?: number,
+  ): Promise<CapabilityReport> {
+    return await invoke("probe_provider_capabilities", {
+      baseUrl,
+      apiKey,
+      timeoutSecs,
+    });
+  },
+
+  // 探测 Codex 端点应使用的 wire_api（均不支持时返回 null）
+  async detectCodexWireApi(
+    baseUrl: string,
+    apiKey: string,
+  ): Promise<"responses" | "chat" | null> {
+    return await invoke("detect_codex_wire_api", { baseUrl, apiKey });
+  },
+
+  // 按探测结果改写 Codex 供应商的 wire_api
+  async fixCodexWireApi(providerId: string): Promise<WireApiFix> {
+    return await invoke("fix_codex_wire_api", { providerId });
+  },
+
+  // 从 live 配置采集官方登录凭据快照，并将供应商标记为 OAuth 类型
+  async captureOAuthSnapshot(id: string, appId: AppId): Promise<boolean> {
+    return await invoke("capture_oauth_snapshot", {
+      app: appId,
+      providerId: id,
+    });
+  },
+
+  // 生成 claude-with / codex-with / gemini-with 包装脚本，返回脚本路径
+  async generateShellWrappers(dir: string): Promise<string[]> {
+    return await invoke("generate_shell_wrappers", { dir });
+  },
+
+  // 当前供应商环境变量文件（~/.cc-switch/env/<app>.env）路径
+  async getEnvFilePath(appId: AppId): Promise<string> {
+    return await invoke("get_env_file_path", { app: appId });
+  },
+
+  // 生成在 shell 启动文件中 source 环境变量文件的片段
+  async getEnvShellSnippet(
+    appId: AppId,
+    shell?: "sh" | "bash" | "zsh" | "fish" | "powershell",
+  ): Promise<string> {
+    return await invoke("get_env_shell_snippet", { app: appId, shell });
+  },
+
   async onSwitched(
     handler: (event: ProviderSwitchEvent) => void,
   ): Promise<UnlistenFn> {
@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+
+// 一次代理请求的记录
+export interface ProxyRequestLog {
+  at: number;
+  appType: string;
+  // 最终处理请求的供应商
+  providerId: string;
+  method: string;
+  path: string;
+  status?: number;
+  latencyMs: number;
+  // 尝试的供应商数量（故障转移时大于 1）
+  attempts: number;
+  error?: string;
+}
+
+export interface ProxyStatus {
+  running: boolean;
+  port?: number;
+  // 各应用应配置的基础地址，如 claude -> http://127.0.0.1:15721/claude
+  endpoints: Record<string, string>;
+  failover: boolean;
+  // 最近的请求（新的在前）
+  recent: ProxyRequestLog[];
+}
+
+export type MetricsPeriod = "hour" | "day" | "week" | "month";
+
+// 单个供应商在统计范围内的指标（故障转移时每次尝试各计一次）
+export interface ProviderMetrics {
+  appType: string;
+  providerId: string;
+  requests: number;
+  errors: number;
+  // 0~1
+  errorRate: number;
+  // 成功请求的延迟（毫秒），没有成功请求时缺省
+  p50Ms?: number;
+  p90Ms?: number;
+  p99Ms?: number;
+  avgMs?: number;
+}
+
+export interface ProxyMetricsReport {
+  period: MetricsPeriod;
+  since: number;
+  providers: ProviderMetrics[];
+}
+
+// 请求头 x-cc-switch-provider: <供应商 ID> 可让单个请求改用指定供应商
+export const proxyApi = {
+  async start(port?: number): Promise<number> {
+    return await invoke("start_proxy", { port });
+  },
+
+  async stop(): Promise<boolean> {
+    return await invoke("stop_proxy");
+  },
+
+  async getStatus(): Promise<ProxyStatus> {
+    return await invoke("get_proxy_status");
+  },
+
+  async getMetrics(period: MetricsPeriod): Promise<ProxyMetricsReport> {
+    return await invoke("get_proxy_metrics", { period });
+  },
+};
@@ -0,0 +1,43 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type RegistryKind =
+  | "provider-presets"
+  | "mcp-catalog"
+  | "usage-templates";
+
+// 当前生效的注册表：embedded 为随程序打包的版本，cache 为联网刷新后的缓存
+export interface Registry<T = Record<string, any>> {
+  kind: RegistryKind;
+  source: "embedded" | "cache";
+  version: number;
+  updatedAt?: number;
+  data: T & { version: number };
+}
+
+export interface RegistryRefresh {
+  kind: RegistryKind;
+  updated: boolean;
+  version: number;
+  error?: string;
+}
+
+export interface RegistryRefreshReport {
+  // 离线模式或全部注册表均无法拉取
+  offline: boolean;
+  results: RegistryRefresh[];
+}
+
+export const registryApi = {
+  async get<T = Record<string, any>>(
+    kind: RegistryKind,
+  ): Promise<Registry<T>> {
+    return await invoke("get_registry", { kind });
+  },
+
+  /**
+   * 联网刷新全部注册表；失败时保留现有数据，不会抛出网络错误
+   */
+  async refresh(): Promise<RegistryRefreshReport> {
+    return await invoke("refresh_registries");
+  },
+};
@@ -0,0 +1,48 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { RemoteHost } from "@/types";
+import type { AppId } from "./types";
+
+export interface RemoteSwitchResult {
+  hostId: string;
+  providerId: string;
+  // 已上传的远程文件（相对远程主目录）
+  uploaded: string[];
+  // 远程原文件的本地备份目录
+  backupDir: string;
+}
+
+export const remoteHostsApi = {
+  async list(): Promise<RemoteHost[]> {
+    return await invoke("list_remote_hosts");
+  },
+
+  async save(host: RemoteHost): Promise<RemoteHost> {
+    return await invoke("save_remote_host", { host });
+  },
+
+  async remove(id: string): Promise<boolean> {
+    return await invoke("remove_remote_host", { id });
+  },
+
+  /**
+   * 测试连接，返回远程主目录
+   */
+  async test(id: string): Promise<string> {
+    return await invoke("test_remote_host", { id });
+  },
+
+  /**
+   * 将供应商写入远程主机（不改变本机当前供应商）；远程原文件先备份到本地
+   */
+  async switchProvider(
+    hostId: string,
+    appId: AppId,
+    providerId: string,
+  ): Promise<RemoteSwitchResult> {
+    return await invoke("switch_provider_remote", {
+      host: hostId,
+      app: appId,
+      providerId,
+    });
+  },
+};
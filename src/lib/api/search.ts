@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+
+export type SearchHitKind = "provider" | "prompt" | "mcp_server" | "skill";
+
+export interface SearchHit {
+  kind: SearchHitKind;
+  // 供应商与提示词所属应用；MCP 与技能为空
+  app?: AppId;
+  id: string;
+  title: string;
+  field: string;
+  snippet: string;
+  score: number;
+}
+
+export const searchApi = {
+  // 全局搜索（命令面板）：关键字按空白拆分，需全部命中
+  async global(query: string, limit?: number): Promise<SearchHit[]> {
+    return await invoke("global_search", { query, limit });
+  },
+};
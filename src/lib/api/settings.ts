@@ -1,6 +1,40 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Settings } from "@/types";
-import type { AppId } from "./types";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  ActiveProviderInfo,
+  AppliedConfigMigration,
+  BackupDetail,
+  ClaudeHook,
+  ClaudeHookEvent,
+  ConfigChange,
+  ConfigDirResolution,
+  ConfigIntegrityReport,
+  ConfigVersion,
+  DaemonEvent,
+  DaemonStatus,
+  DoctorReport,
+  EditorDiagnostic,
+  EditorKind,
+  ExternalConfigChange,
+  LiveCheckpoint,
+  ManagedFile,
+  PermissionReport,
+  Settings,
+  StatuslineStatus,
+  StatuslineVariant,
+  TemplateSecret,
+  WslDistro,
+  WslLiveFile,
+} from "@/types";
+import type { AppId, Page } from "./types";
+
+// error_codes 返回的错误码说明
+export interface ErrorCodeInfo {
+  code: string;
+  description: string;
+}
+
+export type ImportMergeStrategy = "replace" | "merge" | "keep-existing";
 
 export interface ConfigTransferResult {
   success: boolean;
@@ -9,6 +43,46 @@ export interface ConfigTransferResult {
   backupId?: string;
 }
 
+export interface SharedSnippet {
+  // 粘贴服务返回的 key，或离线分享码（ccsw1. 开头）
+  code: string;
+  passphrase: string;
+  expiresAt: number;
+  uploaded: boolean;
+}
+
+export interface LiveSyncResult {
+  app: AppId;
+  providerId?: string;
+  success: boolean;
+  error?: string;
+}
+
+export interface UnlockToken {
+  token: string;
+  // 过期时间（Unix 毫秒）
+  expiresAt: number;
+}
+
+export interface LogEntry {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+}
+
+export interface CrashReport {
+  id: string;
+  kind: "panic" | "command";
+  message: string;
+  location?: string;
+  backtrace?: string;
+  appVersion: string;
+  os: string;
+  arch: string;
+  createdAt: number;
+}
+
 export const settingsApi = {
   async get(): Promise<Settings> {
     return await invoke("get_settings");
@@ -18,6 +92,15 @@ export const settingsApi = {
     return await invoke("save_settings", { settings });
   },
 
+  // 仅导出/导入应用设置（语言、托盘、自动切换规则等），不含供应商配置与密钥
+  async exportSettings(path: string): Promise<boolean> {
+    return await invoke("export_settings", { path });
+  },
+
+  async importSettings(path: string): Promise<Settings> {
+    return await invoke("import_settings", { path });
+  },
+
   async restart(): Promise<boolean> {
     return await invoke("restart_app");
   },
@@ -34,6 +117,44 @@ export const settingsApi = {
     return await invoke("get_config_dir", { app: appId });
   },
 
+  /**
+   * 获取配置目录的候选位置与自动检测结果（用于在设置中选择解析方式）
+   */
+  async getConfigDirResolution(appId: AppId): Promise<ConfigDirResolution> {
+    return await invoke("get_config_dir_resolution", { app: appId });
+  },
+
+  async getDaemonStatus(): Promise<DaemonStatus> {
+    return await invoke("daemon_status");
+  },
+
+  // 调试用：模拟供应商故障，durationSecs 为 0 时取消
+  async simulateProviderOutage(
+    app: AppId,
+    providerId: string,
+    durationSecs: number,
+  ): Promise<DaemonEvent[]> {
+    return await invoke("simulate_provider_outage", {
+      app,
+      providerId,
+      durationSecs,
+    });
+  },
+
+  /**
+   * 列出已安装的 WSL 发行版（非 Windows 返回空列表）；在设置 wslDistros 中启用同步
+   */
+  async listWslDistros(): Promise<WslDistro[]> {
+    return await invoke("list_wsl_distros");
+  },
+
+  async readWslLiveConfig(
+    distro: string,
+    appId: AppId,
+  ): Promise<WslLiveFile[]> {
+    return await invoke("read_wsl_live_config", { distro, app: appId });
+  },
+
   async openConfigFolder(appId: AppId): Promise<void> {
     await invoke("open_config_folder", { app: appId });
   },
@@ -54,6 +175,39 @@ export const settingsApi = {
     await invoke("open_app_config_folder");
   },
 
+  // level 为最低严重级别（如 "warn" 返回 warn 与 error）
+  async getRecentLogs(
+    level?: NonNullable<Settings["logLevel"]>,
+    limit?: number,
+  ): Promise<LogEntry[]> {
+    return await invoke("get_recent_logs", { level, limit });
+  },
+
+  async openLogFolder(): Promise<void> {
+    await invoke("open_log_folder");
+  },
+
+  async listCrashReports(): Promise<CrashReport[]> {
+    return await invoke("list_crash_reports");
+  },
+
+  async clearCrashReports(): Promise<number> {
+    return await invoke("clear_crash_reports");
+  },
+
+  // 记录未处理的命令错误（仅写入本地，是否上传取决于设置）
+  async reportCommandError(
+    command: string,
+    message: string,
+  ): Promise<CrashReport> {
+    return await invoke("report_command_error", { command, message });
+  },
+
+  // 命令错误的 code 列表及说明
+  async getErrorCodes(): Promise<ErrorCodeInfo[]> {
+    return await invoke("error_codes");
+  },
+
   async getAppConfigDirOverride(): Promise<string | null> {
     return await invoke("get_app_config_dir_override");
   },
@@ -77,18 +231,236 @@ export const settingsApi = {
     return await invoke("open_file_dialog");
   },
 
-  async exportConfigToFile(filePath: string): Promise<ConfigTransferResult> {
-    return await invoke("export_config_to_file", { filePath });
+  async exportConfigToFile(
+    filePath: string,
+    unlockToken?: string,
+  ): Promise<ConfigTransferResult> {
+    return await invoke("export_config_to_file", { filePath, unlockToken });
+  },
+
+  // 导出去除密钥的配置模板（密钥替换为 {{CLAUDE_KEY_1}} 等占位符），返回所需密钥清单
+  async exportConfigTemplate(path: string): Promise<TemplateSecret[]> {
+    return await invoke("export_config_template", { path });
+  },
+
+  // secrets：占位符名称 → 密钥；返回替换前的备份 ID
+  async applyConfigTemplate(
+    path: string,
+    secrets: Record<string, string>,
+  ): Promise<string> {
+    return await invoke("apply_config_template", { path, secrets });
+  },
+
+  // 加密分享单个供应商配置，分享码与口令需分别发送
+  async shareConfigSnippet(
+    appId: AppId,
+    providerId: string,
+    options?: { ttl?: number; passphrase?: string; unlockToken?: string },
+  ): Promise<SharedSnippet> {
+    return await invoke("share_config_snippet", {
+      app: appId,
+      providerId,
+      ...options,
+    });
+  },
+
+  // 导入分享码，返回新供应商 ID
+  async importSharedSnippet(code: string, passphrase: string): Promise<string> {
+    return await invoke("import_shared_snippet", { code, passphrase });
+  },
+
+  // 生成诊断包 zip（设置已脱敏，不含供应商配置），返回文件路径
+  async generateDiagnosticsBundle(path: string): Promise<string> {
+    return await invoke("generate_diagnostics_bundle", { path });
+  },
+
+  async getPasscodeStatus(): Promise<boolean> {
+    return await invoke("get_passcode_status");
+  },
+
+  // passcode 为 null 表示清除口令；已设置口令时需提供 current
+  async setPasscode(
+    passcode: string | null,
+    current?: string,
+  ): Promise<boolean> {
+    return await invoke("set_passcode", { passcode, current });
+  },
+
+  async unlockSecrets(passcode: string): Promise<UnlockToken> {
+    return await invoke("unlock_secrets", { passcode });
+  },
+
+  async lockSecrets(): Promise<boolean> {
+    return await invoke("lock_secrets");
+  },
+
+  async getStatuslineStatus(): Promise<StatuslineStatus> {
+    return await invoke("get_statusline_status");
+  },
+
+  async installStatusline(
+    variant: StatuslineVariant,
+  ): Promise<StatuslineStatus> {
+    return await invoke("install_statusline", { variant });
+  },
+
+  async removeStatusline(): Promise<StatuslineStatus> {
+    return await invoke("remove_statusline");
+  },
+
+  async listClaudeHooks(): Promise<ClaudeHook[]> {
+    return await invoke("list_claude_hooks");
+  },
+
+  async addClaudeHook(
+    event: ClaudeHookEvent,
+    command: string,
+    matcher?: string,
+    timeout?: number,
+  ): Promise<ClaudeHook> {
+    return await invoke("add_claude_hook", {
+      event,
+      command,
+      matcher,
+      timeout,
+    });
+  },
+
+  async removeClaudeHook(id: string): Promise<boolean> {
+    return await invoke("remove_claude_hook", { id });
+  },
+
+  async getActiveProviderForStatusline(
+    app: AppId,
+  ): Promise<ActiveProviderInfo | null> {
+    return await invoke("get_active_provider_for_statusline", { app });
+  },
+
+  async listAppliedConfigMigrations(): Promise<AppliedConfigMigration[]> {
+    return await invoke("list_applied_config_migrations");
+  },
+
+  async hardenConfigPermissions(dryRun = false): Promise<PermissionReport> {
+    return await invoke("harden_config_permissions", { dryRun });
+  },
+
+  /**
+   * 整体配置诊断（完整性、live 一致性、MCP、备份目录、权限、凭据）
+   */
+  async runDoctor(): Promise<DoctorReport> {
+    return await invoke("run_doctor");
+  },
+
+  /**
+   * 执行某条诊断项的自动修复，返回修复后的最新报告
+   */
+  async doctorFix(findingId: string): Promise<DoctorReport> {
+    return await invoke("doctor_fix", { findingId });
+  },
+
+  /**
+   * 列出 config.json 历史版本（新到旧）
+   */
+  async listConfigVersions(): Promise<ConfigVersion[]> {
+    return await invoke("list_config_versions");
+  },
+
+  async diffConfigVersions(a: number, b: number): Promise<ConfigChange[]> {
+    return await invoke("diff_config_versions", { a, b });
+  },
+
+  /**
+   * 恢复到指定历史版本，返回恢复前自动创建的备份 ID；恢复后需同步 live 配置
+   */
+  async restoreConfigVersion(id: number): Promise<string> {
+    return await invoke("restore_config_version", { id });
+  },
+
+  // 分页列出 config.json 备份（新到旧）
+  async listBackupsDetailed(
+    offset?: number,
+    limit?: number,
+  ): Promise<Page<BackupDetail>> {
+    return await invoke("list_backups_detailed", { offset, limit });
+  },
+
+  /**
+   * 将三个应用当前的 live 配置保存为检查点（手动编辑前留底）
+   */
+  async createLiveCheckpoint(label: string): Promise<LiveCheckpoint> {
+    return await invoke("create_live_checkpoint", { label });
+  },
+
+  async listLiveCheckpoints(): Promise<LiveCheckpoint[]> {
+    return await invoke("list_live_checkpoints");
+  },
+
+  async restoreLiveCheckpoint(id: string): Promise<boolean> {
+    return await invoke("restore_live_checkpoint", { id });
+  },
+
+  async deleteLiveCheckpoint(id: string): Promise<boolean> {
+    return await invoke("delete_live_checkpoint", { id });
+  },
+
+  /**
+   * 检查 config.json 是否被外部修改（如同步盘同步了其他机器的版本）
+   */
+  async checkExternalConfigChange(): Promise<ExternalConfigChange | null> {
+    return await invoke("check_external_config_change");
+  },
+
+  async reloadExternalConfig(): Promise<boolean> {
+    return await invoke("reload_external_config");
+  },
+
+  // 以当前配置覆盖外部修改，返回覆盖前的备份 ID
+  async overwriteExternalConfig(): Promise<string> {
+    return await invoke("overwrite_external_config");
+  },
+
+  // 外部修改与内存中未保存的修改冲突时触发，需由用户选择重新加载或覆盖
+  async onExternalConfigChange(
+    handler: (change: ExternalConfigChange) => void,
+  ): Promise<UnlistenFn> {
+    return await listen("config-external-change", (event) => {
+      handler(event.payload as ExternalConfigChange);
+    });
+  },
+
+  async listManagedFiles(): Promise<ManagedFile[]> {
+    return await invoke("list_managed_files");
+  },
+
+  async validateEditorContent(
+    app: AppId,
+    kind: EditorKind,
+    text: string,
+  ): Promise<EditorDiagnostic[]> {
+    return await invoke("validate_editor_content", { app, kind, text });
+  },
+
+  async getConfigIntegrityReport(): Promise<ConfigIntegrityReport | null> {
+    return await invoke("get_config_integrity_report");
   },
 
   async importConfigFromFile(filePath: string): Promise<ConfigTransferResult> {
     return await invoke("import_config_from_file", { filePath });
   },
 
+  // 从 https 地址下载配置并导入；merge 以导入为准合并，keep-existing 仅添加本地没有的条目
+  async importConfigFromUrl(
+    url: string,
+    mergeStrategy?: ImportMergeStrategy,
+  ): Promise<ConfigTransferResult> {
+    return await invoke("import_config_from_url", { url, mergeStrategy });
+  },
+
   async syncCurrentProvidersLive(): Promise<void> {
     const result = (await invoke("sync_current_providers_live")) as {
       success?: boolean;
       message?: string;
+      results?: LiveSyncResult[];
     };
     if (!result?.success) {
       throw new Error(result?.message || "Sync current providers failed");
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
 
 export interface Skill {
   key: string;
@@ -10,6 +11,8 @@ export interface Skill {
   repoOwner?: string;
   repoName?: string;
   repoBranch?: string;
+  // 已安装到的应用
+  installedApps: AppId[];
 }
 
 export interface SkillRepo {
@@ -25,12 +28,13 @@ export const skillsApi = {
     return await invoke("get_skills");
   },
 
-  async install(directory: string): Promise<boolean> {
-    return await invoke("install_skill", { directory });
+  // app 默认 claude；codex 安装为自定义提示词，gemini 安装为扩展
+  async install(directory: string, app?: AppId): Promise<boolean> {
+    return await invoke("install_skill", { directory, app });
   },
 
-  async uninstall(directory: string): Promise<boolean> {
-    return await invoke("uninstall_skill", { directory });
+  async uninstall(directory: string, app?: AppId): Promise<boolean> {
+    return await invoke("uninstall_skill", { directory, app });
   },
 
   async getRepos(): Promise<SkillRepo[]> {
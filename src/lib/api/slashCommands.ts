@@ -0,0 +1,35 @@
+import { invoke } from "@tauri-apps/api/core";
+
+// Claude Code 自定义斜杠命令（~/.claude/commands/<name>.md）
+export interface SlashCommand {
+  // 命令名，可用 "/" 分隔子目录作为命名空间
+  name: string;
+  content: string;
+  description?: string;
+  enabled: boolean;
+  createdAt?: number;
+  updatedAt?: number;
+}
+
+export const slashCommandsApi = {
+  async list(): Promise<SlashCommand[]> {
+    return await invoke("get_slash_commands");
+  },
+
+  async upsert(command: SlashCommand): Promise<SlashCommand> {
+    return await invoke("upsert_slash_command", { command });
+  },
+
+  async delete(name: string): Promise<boolean> {
+    return await invoke("delete_slash_command", { name });
+  },
+
+  async setEnabled(name: string, enabled: boolean): Promise<void> {
+    return await invoke("set_slash_command_enabled", { name, enabled });
+  },
+
+  // 导入命令目录中尚未受管理的命令文件，返回导入数量
+  async importFromDir(): Promise<number> {
+    return await invoke("import_slash_commands");
+  },
+};
@@ -0,0 +1,61 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+
+// 订阅清单中的供应商定义（不含密钥）
+export interface SubscribedProvider {
+  id: string;
+  app: AppId;
+  name: string;
+  settingsConfig: Record<string, any>;
+  websiteUrl?: string;
+  category?: string;
+  notes?: string;
+  // 定义内容的 SHA-256，用于识别上游更新
+  revision: string;
+}
+
+export interface SubscriptionSource {
+  id: string;
+  url: string;
+  name?: string;
+  lastFetchedAt?: number;
+  lastError?: string;
+  providers?: SubscribedProvider[];
+}
+
+export interface SubscriptionPreset extends SubscribedProvider {
+  // `<订阅源 ID>/<条目 ID>`，用于安装
+  sourceId: string;
+  sourceName?: string;
+  installedProviderId?: string;
+  updateAvailable: boolean;
+}
+
+export const subscriptionsApi = {
+  async list(): Promise<SubscriptionSource[]> {
+    return await invoke("list_subscriptions");
+  },
+
+  async add(url: string): Promise<SubscriptionSource> {
+    return await invoke("add_subscription", { url });
+  },
+
+  async remove(id: string): Promise<boolean> {
+    return await invoke("remove_subscription", { id });
+  },
+
+  async presets(): Promise<SubscriptionPreset[]> {
+    return await invoke("list_subscription_presets");
+  },
+
+  async refresh(): Promise<SubscriptionPreset[]> {
+    return await invoke("refresh_subscriptions");
+  },
+
+  /**
+   * 安装订阅中的供应商（已安装时按上游更新并保留本地密钥），返回本地供应商 ID
+   */
+  async install(id: string): Promise<string> {
+    return await invoke("install_subscribed_provider", { id });
+  },
+};
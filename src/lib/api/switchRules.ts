@@ -0,0 +1,87 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+import type { ProviderRank } from "./providers";
+
+export type SwitchRuleCondition =
+  | {
+      type: "timeWindow";
+      // 本地时间 HH:MM，允许跨越午夜
+      start: string;
+      end: string;
+      // ISO 星期编号（1 = 周一 … 7 = 周日），为空表示每天
+      days?: number[];
+    }
+  | {
+      type: "network";
+      // 以下条件至少填写一项，全部满足时生效
+      ssid?: string;
+      gateway?: string;
+      // 接口名前缀，如 utun、wg
+      interface?: string;
+      vpn?: boolean;
+    }
+  | {
+      type: "quotaBelow";
+      // 当前供应商剩余额度低于该值时切换（单位与其用量脚本返回的 remaining 一致）
+      threshold: number;
+      // 原供应商额度回升到该值后才会再次触发，默认阈值的 1.5 倍
+      resumeAbove?: number;
+    };
+
+// 额度规则触发切换后的 quota-auto-switch 事件负载
+export interface QuotaSwitchEvent {
+  ruleId: string;
+  appType: string;
+  fromProviderId: string;
+  toProviderId: string;
+  remaining: number;
+}
+
+export interface BestProviderSwitch {
+  providerId: string;
+  // 高分在前
+  ranks: ProviderRank[];
+}
+
+export interface NetworkStatus {
+  ssid?: string;
+  gateway?: string;
+  interfaces: string[];
+  vpnActive: boolean;
+}
+
+export interface SwitchRule {
+  id: string;
+  name?: string;
+  app: AppId;
+  providerId: string;
+  enabled: boolean;
+  condition: SwitchRuleCondition;
+}
+
+export const switchRulesApi = {
+  async list(): Promise<SwitchRule[]> {
+    return await invoke("list_switch_rules");
+  },
+
+  async upsert(rule: SwitchRule): Promise<SwitchRule> {
+    return await invoke("upsert_switch_rule", { rule });
+  },
+
+  async delete(id: string): Promise<boolean> {
+    return await invoke("delete_switch_rule", { id });
+  },
+
+  async setEnabled(enabled: boolean): Promise<boolean> {
+    return await invoke("set_switch_rules_enabled", { enabled });
+  },
+
+  async getNetworkStatus(): Promise<NetworkStatus> {
+    return await invoke("get_network_status");
+  },
+
+  // 按供应商排名（设置中的评分权重）切换到推荐的可用供应商
+  async switchToBestAvailable(app: AppId): Promise<BestProviderSwitch> {
+    return await invoke("switch_to_best_available", { app });
+  },
+};
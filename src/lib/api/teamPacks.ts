@@ -0,0 +1,94 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+import type { SkillRepo } from "./skills";
+
+export interface TeamPackItemRef {
+  app: AppId;
+  id: string;
+}
+
+export interface TeamPackSelection {
+  name?: string;
+  description?: string;
+  providers?: TeamPackItemRef[];
+  mcpServers?: string[];
+  prompts?: TeamPackItemRef[];
+  // 技能仓库与已安装技能的引用（不含技能文件）
+  includeSkills?: boolean;
+  includeSettings?: boolean;
+}
+
+export interface TeamPackManifest {
+  version: number;
+  name: string;
+  description?: string;
+  createdAt: number;
+  appVersion: string;
+  providers: number;
+  mcpServers: number;
+  prompts: number;
+  skills: number;
+  settings: number;
+}
+
+export interface TeamPackSkill {
+  directory: string;
+  apps: AppId[];
+}
+
+export type TeamPackItemKind = "provider" | "mcp" | "prompt" | "settings";
+
+export interface TeamPackItem {
+  // provider:<app>:<id>、mcp:<id>、prompt:<app>:<id> 或 settings
+  key: string;
+  kind: TeamPackItemKind;
+  app?: AppId;
+  id: string;
+  name: string;
+  // 本地已有同 ID 的项（推荐设置为与本地取值不同）
+  conflict: boolean;
+}
+
+export interface TeamPackPreview {
+  manifest: TeamPackManifest;
+  items: TeamPackItem[];
+  skillRepos: SkillRepo[];
+  skills: TeamPackSkill[];
+}
+
+// keepBoth：以新 ID 另存（推荐设置按 skip 处理）
+export type TeamPackResolution = "skip" | "overwrite" | "keepBoth";
+
+export interface TeamPackApplyResult {
+  installed: string[];
+  overwritten: string[];
+  // 原键 -> 新 ID
+  renamed: Record<string, string>;
+  skipped: string[];
+  // 冲突但未指定处理方式的项，需提示用户后重新应用
+  unresolved: string[];
+  addedSkillRepos: number;
+  // 尚未安装的技能，需逐个调用 skillsApi.install
+  pendingSkills: TeamPackSkill[];
+}
+
+// 团队包不含密钥，接收方需自行填写 API Key
+export const teamPacksApi = {
+  async create(
+    selection: TeamPackSelection,
+    path: string,
+  ): Promise<TeamPackManifest> {
+    return await invoke("create_team_pack", { selection, path });
+  },
+
+  async inspect(path: string): Promise<TeamPackPreview> {
+    return await invoke("inspect_team_pack", { path });
+  },
+
+  async apply(
+    path: string,
+    resolutions?: Record<string, TeamPackResolution>,
+  ): Promise<TeamPackApplyResult> {
+    return await invoke("apply_team_pack", { path, resolutions });
+  },
+};
@@ -0,0 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type ModelFamily = "claude" | "openai" | "gemini";
+
+export interface TokenCount {
+  family: ModelFamily;
+  tokens: number;
+  encoding: string;
+  // Claude / Gemini 使用 cl100k_base 近似估算
+  approximate: boolean;
+}
+
+export const tokensApi = {
+  // 本地统计 token 数；modelFamily 也可传模型名（如 claude-sonnet-4、gpt-4o）
+  async count(modelFamily: string, text: string): Promise<TokenCount> {
+    return await invoke("count_tokens", { modelFamily, text });
+  },
+};
@@ -1,2 +1,18 @@
 // 前端统一使用 AppId 作为应用标识（与后端命令参数 `app` 一致）
 export type AppId = "claude" | "codex" | "gemini"; // 新增 gemini
+
+// 分页结果（items 按 fields 投影，始终包含 id）
+export interface Page<T> {
+  items: T[];
+  total: number;
+  offset: number;
+  limit: number;
+  hasMore: boolean;
+}
+
+export interface PageRequest {
+  offset?: number;
+  limit?: number;
+  // 未指定时不返回 settingsConfig / server 等大字段
+  fields?: string[];
+}
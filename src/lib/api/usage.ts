@@ -1,7 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { UsageResult } from "@/types";
+import type { UsageResult, UsageScriptStatus } from "@/types";
 import type { AppId } from "./types";
 import i18n from "@/i18n";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 export const usageApi = {
   async query(providerId: string, appId: AppId): Promise<UsageResult> {
@@ -12,12 +13,7 @@ export const usageApi = {
       });
     } catch (error: unknown) {
       // 提取错误消息：优先使用后端返回的错误信息
-      const message =
-        typeof error === "string"
-          ? error
-          : error instanceof Error
-            ? error.message
-            : "";
+      const message = extractErrorMessage(error);
 
       // 如果没有错误消息，使用国际化的默认提示
       return {
@@ -49,12 +45,7 @@ export const usageApi = {
         userId: userId,
       });
     } catch (error: unknown) {
-      const message =
-        typeof error === "string"
-          ? error
-          : error instanceof Error
-            ? error.message
-            : "";
+      const message = extractErrorMessage(error);
 
       return {
         success: false,
@@ -62,4 +53,12 @@ export const usageApi = {
       };
     }
   },
+
+  async getStatus(providerId: string): Promise<UsageScriptStatus> {
+    return await invoke("get_usage_script_status", { providerId });
+  },
+
+  async resetStatus(providerId: string): Promise<UsageScriptStatus> {
+    return await invoke("reset_usage_script_status", { providerId });
+  },
 };
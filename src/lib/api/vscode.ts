@@ -1,5 +1,9 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { CustomEndpoint } from "@/types";
+import type {
+  CustomEndpoint,
+  ProviderVerification,
+  RateLimitInfo,
+} from "@/types";
 import type { AppId } from "./types";
 
 export interface EndpointLatencyResult {
@@ -7,20 +11,95 @@ export interface EndpointLatencyResult {
   latency: number | null;
   status?: number;
   error?: string;
+  rateLimit?: RateLimitInfo;
+}
+
+// 真实请求测速结果（probe_provider_authenticated）
+export interface AuthenticatedProbeResult {
+  url: string;
+  model: string;
+  status:
+    | "ok"
+    | "unauthorized"
+    | "forbidden"
+    | "rate_limited"
+    | "http_error"
+    | "network_error";
+  latency?: number;
+  httpStatus?: number;
+  error?: string;
+  rateLimit?: RateLimitInfo;
+}
+
+// 沙箱验证结果（sandbox_validate）
+export interface SandboxValidateResult {
+  success: boolean;
+  command: string;
+  exitCode?: number;
+  durationMs: number;
+  timedOut: boolean;
+  cliMissing: boolean;
+  stdout: string;
+  stderr: string;
 }
 
 export const vscodeApi = {
-  async getLiveProviderSettings(appId: AppId) {
-    return await invoke("read_live_provider_settings", { app: appId });
+  // 设置了应用口令时，明文读取需传入 unlockToken；revealSecrets=false 返回掩码内容
+  async getLiveProviderSettings(
+    appId: AppId,
+    options?: { revealSecrets?: boolean; unlockToken?: string },
+  ) {
+    return await invoke("read_live_provider_settings", {
+      app: appId,
+      revealSecrets: options?.revealSecrets,
+      unlockToken: options?.unlockToken,
+    });
   },
 
+  // 传入 appId 与 providerId 时，后端会把响应中的限流信息记录到该供应商
   async testApiEndpoints(
     urls: string[],
-    options?: { timeoutSecs?: number },
+    options?: { timeoutSecs?: number; appId?: AppId; providerId?: string },
   ): Promise<EndpointLatencyResult[]> {
     return await invoke("test_api_endpoints", {
       urls,
       timeoutSecs: options?.timeoutSecs,
+      app: options?.appId,
+      providerId: options?.providerId,
+    });
+  },
+
+  // 使用供应商 API Key 发送最小真实请求（1 token 输出），区分 401/403/429 等配置问题
+  async probeProviderAuthenticated(
+    appId: AppId,
+    providerId: string,
+    timeoutSecs?: number,
+  ): Promise<AuthenticatedProbeResult> {
+    return await invoke("probe_provider_authenticated", {
+      app: appId,
+      providerId,
+      timeoutSecs,
+    });
+  },
+
+  // 综合验证供应商（真实请求 + 模型列表），结果同时保存到 meta.verification
+  async verifyProvider(
+    appId: AppId,
+    providerId: string,
+  ): Promise<ProviderVerification> {
+    return await invoke("verify_provider", { app: appId, providerId });
+  },
+
+  // 在临时配置目录中运行一次 CLI（如 claude -p "ping"），验证供应商可用且不修改 live 配置
+  async sandboxValidate(
+    appId: AppId,
+    providerId: string,
+    timeoutSecs?: number,
+  ): Promise<SandboxValidateResult> {
+    return await invoke("sandbox_validate", {
+      app: appId,
+      providerId,
+      timeoutSecs,
     });
   },
 
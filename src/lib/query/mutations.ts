@@ -119,11 +119,43 @@ export const useSwitchProviderMutation = (appId: AppId) => {
   const queryClient = useQueryClient();
   const { t } = useTranslation();
 
-  return useMutation({
-    mutationFn: async (providerId: string) => {
-      return await providersApi.switch(providerId, appId);
+  const mutation = useMutation({
+    mutationFn: async ({
+      providerId,
+      force,
+    }: {
+      providerId: string;
+      force?: boolean;
+    }) => {
+      return await providersApi.switch(providerId, appId, force);
     },
-    onSuccess: async () => {
+    onSuccess: async (outcome, { providerId }) => {
+      // 预检未通过：提示用户确认后强制切换
+      if (!outcome.switched && outcome.warning) {
+        const { warning } = outcome;
+        toast.warning(
+          warning.kind === "probe_failed"
+            ? t("notifications.switchProbeFailed", {
+                defaultValue: "该供应商最近一次测速失败",
+              })
+            : t("notifications.switchHighLatency", {
+                defaultValue: "该供应商最近一次测速延迟 {{latency}}ms",
+                latency: warning.latency,
+              }),
+          {
+            description: warning.error ?? warning.baseUrl,
+            duration: 8000,
+            action: {
+              label: t("notifications.switchAnyway", {
+                defaultValue: "仍然切换",
+              }),
+              onClick: () => mutation.mutate({ providerId, force: true }),
+            },
+          },
+        );
+        return;
+      }
+
       await queryClient.invalidateQueries({ queryKey: ["providers", appId] });
 
       // 更新托盘菜单（失败不影响主操作）
@@ -165,6 +197,8 @@ export const useSwitchProviderMutation = (appId: AppId) => {
       );
     },
   });
+
+  return mutation;
 };
 
 export const useSaveSettingsMutation = () => {
@@ -11,8 +11,10 @@ import { queryClient } from "@/lib/query";
 import { Toaster } from "@/components/ui/sonner";
 import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
-import { message } from "@tauri-apps/plugin-dialog";
-import { exit } from "@tauri-apps/plugin-process";
+import { ask, message } from "@tauri-apps/plugin-dialog";
+import { exit, relaunch } from "@tauri-apps/plugin-process";
+import type { ConfigIntegrityReport } from "@/types";
+import { extractErrorMessage } from "@/utils/errorUtils";
 
 // 根据平台添加 body class，便于平台特定样式
 try {
@@ -30,11 +32,54 @@ try {
 interface ConfigLoadErrorPayload {
   path?: string;
   error?: string;
+  report?: ConfigIntegrityReport;
 }
 
 /**
- * 处理配置加载失败：显示错误消息并强制退出应用
- * 不给用户"取消"选项，因为配置损坏时应用无法正常运行
+ * 若存在可用备份，询问是否从最近的备份恢复；恢复成功后重启应用
+ */
+async function offerBackupRestore(
+  report: ConfigIntegrityReport | undefined,
+  detail: string,
+): Promise<boolean> {
+  const backup = report?.repairOptions.find(
+    (option) => option.kind === "restore_backup" && option.path,
+  );
+  if (!report || !backup?.path) return false;
+
+  const location = report.parseError
+    ? `\n位置：第 ${report.parseError.line} 行，第 ${report.parseError.column} 列`
+    : "";
+  const confirmed = await ask(
+    `无法读取配置文件：\n${report.path}\n\n错误详情：\n${detail}${location}\n\n是否从备份恢复？\n${backup.path}\n\n当前文件将另存为 config.json.broken-*。`,
+    {
+      title: "配置加载失败",
+      kind: "error",
+      okLabel: "从备份恢复",
+      cancelLabel: "退出",
+    },
+  );
+  if (!confirmed) return false;
+
+  try {
+    await invoke("repair_config", {
+      kind: "restore_backup",
+      path: backup.path,
+    });
+    await relaunch();
+    return true;
+  } catch (e) {
+    await message(`从备份恢复失败：\n${extractErrorMessage(e)}`, {
+      title: "配置加载失败",
+      kind: "error",
+    });
+    return false;
+  }
+}
+
+/**
+ * 处理配置加载失败：有可用备份时提供恢复选项，否则显示错误消息并退出应用
+ * 不提供"忽略并继续"的选项，因为配置损坏时应用无法正常运行
  */
 async function handleConfigLoadError(
   payload: ConfigLoadErrorPayload | null,
@@ -42,6 +87,10 @@ async function handleConfigLoadError(
   const path = payload?.path ?? "~/.cc-switch/config.json";
   const detail = payload?.error ?? "Unknown error";
 
+  if (await offerBackupRestore(payload?.report, detail)) {
+    return;
+  }
+
   await message(
     `无法读取配置文件：\n${path}\n\n错误详情：\n${detail}\n\n请手动检查 JSON 是否有效，或从同目录的备份文件（如 config.json.bak）恢复。\n\n应用将退出以便您进行修复。`,
     { title: "配置加载失败", kind: "error" },
@@ -50,7 +99,7 @@ async function handleConfigLoadError(
   await exit(1);
 }
 
-// 监听后端的配置加载错误事件：仅提醒用户并强制退出，不修改任何配置文件
+// 监听后端的配置加载错误事件：提醒用户并在确认后才从备份恢复，否则退出
 try {
   void listen("configLoadError", async (evt) => {
     await handleConfigLoadError(evt.payload as ConfigLoadErrorPayload | null);
@@ -20,6 +20,16 @@ export interface Provider {
   isPartner?: boolean;
   // 可选：供应商元数据（仅存于 ~/.cc-switch/config.json，不写入 live 配置）
   meta?: ProviderMeta;
+  // 认证方式：oauth 供应商切换时恢复保存的官方登录凭据（Claude / Codex）
+  authKind?: "api_key" | "oauth";
+  // 最近一次保存时间（毫秒），更新时原样回传以检测并发修改
+  updatedAt?: number;
+  // 置顶：列表与托盘菜单中优先展示
+  pinned?: boolean;
+  // 托盘图标文件（PNG），该供应商为当前供应商时替换托盘图标
+  icon?: string;
+  // 托盘图标角标颜色（#RRGGBB）
+  iconColor?: string;
 }
 
 export interface AppConfig {
@@ -52,6 +62,22 @@ export interface UsageScript {
   accessToken?: string; // 访问令牌（NewAPI 模板使用）
   userId?: string; // 用户ID（NewAPI 模板使用）
   autoQueryInterval?: number; // 自动查询间隔（单位：分钟，0 表示禁用）
+  maxRetries?: number; // 请求失败时的最大重试次数（默认 0）
+  retryBackoffMs?: number; // 首次重试等待时间（毫秒，之后每次翻倍）
+  maxResponseBytes?: number; // 响应体大小上限（字节，默认 1 MiB）
+  failureThreshold?: number; // 连续失败多少次后自动停用（默认 5，0 表示不熔断）
+  cooldownMinutes?: number; // 熔断后自动恢复前的冷却时间（分钟，默认 30）
+}
+
+// 用量脚本熔断状态
+export interface UsageScriptStatus {
+  providerId: string;
+  consecutiveFailures: number;
+  tripped: boolean; // 是否已自动停用
+  trippedAt?: number;
+  retryAfter?: number; // 冷却结束时间（毫秒时间戳）
+  lastError?: string;
+  lastSuccessAt?: number;
 }
 
 // 单个套餐用量数据
@@ -64,6 +90,18 @@ export interface UsageData {
   used?: number; // 已用额度（可选）
   remaining?: number; // 剩余额度（可选）
   unit?: string; // 单位（可选）
+  unitKind?: "currency" | "credits" | "tokens"; // 单位类型（由 unit 推断）
+  currency?: string; // 货币代码（ISO 4217）
+  converted?: ConvertedUsage; // 按展示货币换算后的额度
+}
+
+// 换算为展示货币后的额度
+export interface ConvertedUsage {
+  currency: string;
+  rate: number;
+  total?: number;
+  used?: number;
+  remaining?: number;
 }
 
 // 用量查询结果（支持多套餐）
@@ -83,6 +121,403 @@ export interface ProviderMeta {
   isPartner?: boolean;
   // 合作伙伴促销 key（用于后端识别 PackyCode 等）
   partnerPromotionKey?: string;
+  // 自定义 HTTP 请求头（Claude 写入 ANTHROPIC_CUSTOM_HEADERS，Codex 写入 http_headers）
+  headers?: Record<string, string>;
+  // Codex 额外环境变量（写入 shell_environment_policy.set，并导出到环境变量文件与包装脚本）
+  extra_env?: Record<string, string>;
+  // 切换到该供应商时启用的 Claude 斜杠命令（未设置时保持现状）
+  slash_commands?: string[];
+  // 最近一次测速采集到的限流信息
+  rate_limit?: RateLimitInfo;
+  // 最近一次写入 live 配置时由 cc-switch 管理的环境变量键（Gemini .env 合并写入）
+  managed_env_keys?: string[];
+  // Codex 登录方式（未设置时按 auth 中是否有 OPENAI_API_KEY 推断）
+  codex_login_type?: "api_key" | "chatgpt";
+  // 切换离开时暂存的 Codex OAuth 字段（tokens、last_refresh）
+  codex_oauth?: Record<string, unknown>;
+  // OAuth 供应商的凭据快照（Codex 为 auth.json，Claude 为 .credentials.json）
+  oauth_snapshot?: Record<string, unknown>;
+  // 用户自定义标签（用于搜索与筛选）
+  tags?: string[];
+  // 价格信息（用于供应商对比）
+  pricing?: ProviderPricing;
+  // 来自订阅源时记录来源与安装时的上游修订号（订阅通过 subscriptionsApi 管理）
+  subscription?: {
+    sourceId: string;
+    entryId: string;
+    revision: string;
+  };
+  // Claude settings.json 的结构化设置（通过 providersApi.setClaudeSettings 修改）
+  claude_settings?: ClaudeSettingsOverrides;
+  // 付费周期与续费日期（驱动续费提醒）
+  billing?: ProviderBilling;
+  // 最近一次综合验证结果（真实请求 + 模型列表）
+  verification?: ProviderVerification;
+  // 并发、超时与重试参数（通过 providersApi.setPerformance 修改）
+  performance?: ProviderPerformance;
+}
+
+// 供应商性能参数：Codex 写入当前 model_providers 表，Claude 仅支持超时（API_TIMEOUT_MS）
+export interface ProviderPerformance {
+  maxConcurrentRequests?: number;
+  requestTimeoutMs?: number;
+  requestMaxRetries?: number;
+  streamIdleTimeoutMs?: number;
+}
+
+// 供应商级 Claude 设置，切换时合并写入 settings.json
+export interface ClaudeSettingsOverrides {
+  // permissions.allow / permissions.deny（permissions 下其他字段保持不变）
+  permissions?: { allow: string[]; deny: string[] };
+  includeCoAuthoredBy?: boolean;
+  // 默认模型，可为 opus / sonnet / haiku 等别名或完整模型名
+  model?: string;
+  // 别名实际指向的模型（ANTHROPIC_DEFAULT_{OPUS,SONNET,HAIKU}_MODEL）
+  modelAliases?: { opus?: string; sonnet?: string; haiku?: string };
+}
+
+// 供应商价格信息
+export interface ProviderPricing {
+  currency?: string; // 计价货币（ISO 4217）
+  inputPerMillion?: number; // 每百万输入 tokens 价格
+  outputPerMillion?: number; // 每百万输出 tokens 价格
+  multiplier?: number; // 中转站倍率
+  note?: string;
+}
+
+export type BillingCycle = "monthly" | "quarterly" | "yearly";
+
+export interface ProviderBilling {
+  cycle?: BillingCycle; // 默认 monthly
+  renewalDate?: string; // YYYY-MM-DD，已过去时按周期顺延
+  monthlyCost?: number; // 折算到每月的费用
+  currency?: string; // 计价货币（ISO 4217）
+  remindDaysBefore?: number; // 提前提醒天数，默认 3
+}
+
+export type VerificationStatus = "verified" | "degraded" | "failed";
+
+export interface ProviderVerification {
+  status: VerificationStatus;
+  checkedAt: number; // 最近一次检查时间（毫秒时间戳）
+  verifiedAt?: number; // 最近一次全部检查通过的时间
+  endpointReachable: boolean;
+  keyValid: boolean;
+  modelsOk: boolean;
+  error?: string;
+}
+
+// 测速响应头中的限流 / 配额信息（x-ratelimit-*、retry-after 及中转站配额头）
+export interface RateLimitInfo {
+  requestsLimit?: number;
+  requestsRemaining?: number;
+  tokensLimit?: number;
+  tokensRemaining?: number;
+  retryAfter?: string;
+  headers: Record<string, string>;
+  capturedAt: number;
+}
+
+// cc-switch 内置的 Claude Code 状态栏样式
+export type StatuslineVariant = "minimal" | "provider" | "full";
+
+export interface StatuslineStatus {
+  installed: boolean;
+  variant?: StatuslineVariant;
+  scriptPath: string;
+  command: string;
+}
+
+// Claude Code 钩子事件（settings.json 的 hooks）
+export type ClaudeHookEvent =
+  | "PreToolUse"
+  | "PostToolUse"
+  | "Notification"
+  | "UserPromptSubmit"
+  | "Stop"
+  | "SubagentStop"
+  | "PreCompact"
+  | "SessionStart"
+  | "SessionEnd";
+
+// cc-switch 管理的钩子，切换供应商时保留
+export interface ClaudeHook {
+  id: string;
+  event: ClaudeHookEvent;
+  matcher?: string; // 工具名匹配（如 Bash、Edit|Write），未设置时匹配全部
+  command: string;
+  timeout?: number; // 秒
+}
+
+// 状态栏使用的当前供应商信息
+export interface ActiveProviderInfo {
+  app: string;
+  id: string;
+  name: string;
+  baseUrl?: string;
+}
+
+// 已生效的供应商配置键迁移（list_applied_config_migrations）
+export interface AppliedConfigMigration {
+  id: string;
+  app: "claude" | "codex" | "gemini";
+  version: string;
+  description: string;
+  firstAppliedAt: number;
+  lastAppliedAt: number;
+  appliedCount: number;
+}
+
+// 配置文件权限审计（harden_config_permissions）
+export interface PermissionEntry {
+  path: string;
+  isDir: boolean;
+  mode: string;
+  expected: string;
+  worldReadable: boolean;
+  tooPermissive: boolean;
+  fixed: boolean;
+  error?: string;
+}
+
+export interface PermissionReport {
+  supported: boolean;
+  entries: PermissionEntry[];
+  worldReadable: string[];
+}
+
+// 整体配置诊断（run_doctor）
+export type DoctorFix =
+  | { kind: "syncLive"; app: string }
+  | { kind: "syncMcp"; app: string }
+  | { kind: "hardenPermissions" }
+  | { kind: "createBackupDir" }
+  | { kind: "acceptConfigChecksum" }
+  | { kind: "trimBackups" }
+  | { kind: "normalizeMcpKeys" };
+
+export interface DoctorFinding {
+  // 稳定 ID（code 或 code:对象），供修复命令引用
+  id: string;
+  code: string;
+  category:
+    | "config"
+    | "live"
+    | "mcp"
+    | "backup"
+    | "permissions"
+    | "credentials";
+  severity: "info" | "warning" | "error";
+  message: string;
+  messageEn: string;
+  detail?: string;
+  fix?: DoctorFix;
+}
+
+// config.json 历史版本摘要
+export interface ConfigVersion {
+  id: number;
+  createdAt: number;
+  // 相对上一版本变化的字段路径（JSON Pointer）
+  paths: string[];
+}
+
+// 单个字段的变化（新增时无 before，删除时无 after）
+export interface ConfigChange {
+  path: string;
+  before?: unknown;
+  after?: unknown;
+}
+
+// config.json 备份摘要（list_backups_detailed）
+export interface BackupDetail {
+  id: string;
+  // 备份时间（毫秒时间戳）
+  createdAt?: number;
+  // 占用字节数（含子代理快照）
+  size: number;
+  // 按界面语言格式化的大小与备份时间
+  sizeText: string;
+  createdAtText?: string;
+  configVersion?: number;
+  providerCounts: Record<string, number>;
+  currentProviders: Record<string, string>;
+  mcpServerCount: number;
+  // 备份文件无法解析时的错误
+  error?: string;
+}
+
+// live 配置检查点（三个应用的 live 文件快照，与供应商无关）
+export interface LiveCheckpoint {
+  id: string;
+  label: string;
+  createdAt: number;
+}
+
+// 配置模板所需的密钥（占位符名称与使用方）
+export interface TemplateSecret {
+  name: string;
+  usedBy: string[];
+}
+
+// 磁盘上的 config.json 已在 cc-switch 之外被修改
+export interface ExternalConfigChange {
+  path: string;
+  modifiedAt?: number;
+}
+
+export type ManagedFileOwner = "ccSwitch" | "claude" | "codex" | "gemini";
+
+export type ManagedFileKind =
+  | "config"
+  | "settings"
+  | "live"
+  | "credentials"
+  | "mcp"
+  | "env"
+  | "backups";
+
+export type LastWriter = "ccSwitch" | "external" | "unknown";
+
+// 原始配置编辑器内容类型：settings（Claude / Gemini JSON）、
+// auth / config（Codex）、env（Gemini）、mcp（单个服务器 JSON）
+export type EditorKind = "settings" | "auth" | "config" | "env" | "mcp";
+
+// 编辑器诊断（行列从 1 开始，列按字符计数）
+export interface EditorDiagnostic {
+  line: number;
+  column: number;
+  endLine?: number;
+  endColumn?: number;
+  severity: "error" | "warning";
+  message: string;
+}
+
+export interface ManagedFile {
+  path: string;
+  owner: ManagedFileOwner;
+  kind: ManagedFileKind;
+  isDir: boolean;
+  exists: boolean;
+  size?: number;
+  modifiedAt?: number;
+  lastWriter?: LastWriter;
+  writtenAt?: number;
+}
+
+export interface DoctorReport {
+  findings: DoctorFinding[];
+  // 没有警告或错误
+  healthy: boolean;
+  checkedAt: number;
+}
+
+// 配置完整性报告（启动检查 / get_config_integrity_report）
+export interface ConfigRepairOption {
+  kind: "restore_backup" | "reset";
+  path?: string;
+  modifiedAt?: number;
+}
+
+export interface ConfigIntegrityReport {
+  path: string;
+  expectedSchemaVersion: number;
+  schemaVersion?: number;
+  checksum: "match" | "mismatch" | "missing";
+  parseError?: {
+    message: string;
+    line: number;
+    column: number;
+  };
+  repairOptions: ConfigRepairOption[];
+}
+
+// 配置目录解析方式：主目录（~/.claude）、%APPDATA%、%LOCALAPPDATA%
+export type PathStrategy = "auto" | "home" | "app-data" | "local-app-data";
+
+// 配置目录的候选位置与检测结果（get_config_dir_resolution）
+export interface PathCandidate {
+  strategy: Exclude<PathStrategy, "auto">;
+  path: string;
+  exists: boolean;
+  // 目录中存在该 CLI 的配置文件
+  inUse: boolean;
+}
+
+export interface ConfigDirResolution {
+  strategy: PathStrategy;
+  detected: Exclude<PathStrategy, "auto">;
+  // 设置了目录覆盖（此时忽略解析方式）
+  overridden: boolean;
+  dir: string;
+  candidates: PathCandidate[];
+}
+
+// 已安装的 WSL 发行版（enabled：切换供应商时同步写入）
+export interface WslDistro {
+  name: string;
+  enabled: boolean;
+}
+
+// WSL 发行版中的 live 配置文件（文件不存在时无 content）
+export interface WslLiveFile {
+  path: string;
+  content?: string;
+}
+
+// 供应商排名各评分维度的权重（按总和归一化，0 表示忽略该维度）
+export interface RankingWeights {
+  latency: number;
+  errorRate: number;
+  quota: number;
+  price: number;
+  pinned: number;
+}
+
+// SSH 远程主机（host 可为 ~/.ssh/config 中的别名；需密钥认证）
+export interface RemoteHost {
+  // 为空时由后端生成
+  id: string;
+  name: string;
+  host: string;
+  port?: number;
+  user?: string;
+  identityFile?: string;
+}
+
+export type DaemonEventKind =
+  | "reconciled"
+  | "reconcile_failed"
+  | "failover"
+  | "failover_failed";
+
+export interface DaemonEvent {
+  at: number;
+  appType: string;
+  kind: DaemonEventKind;
+  providerId: string;
+  detail?: string;
+}
+
+// 进行中的模拟故障（调试故障转移用）
+export interface SimulatedOutage {
+  appType: string;
+  providerId: string;
+  until: number;
+}
+
+// 守护模式运行状态（非守护模式时 daemon 为 false）
+export interface DaemonStatus {
+  daemon: boolean;
+  tray: boolean;
+  startedAt?: number;
+  // HTTP 控制接口监听地址
+  controlApi?: string;
+  // 各应用当前供应商的连续探测失败次数
+  probeFailures: Record<string, number>;
+  // 最近的巡检与故障转移事件（新的在前）
+  events: DaemonEvent[];
+  simulatedOutages: SimulatedOutage[];
 }
 
 // 应用设置类型（用于设置对话框与 Tauri API）
@@ -97,12 +532,78 @@ export interface Settings {
   claudeConfigDir?: string;
   // 覆盖 Codex 配置目录（可选）
   codexConfigDir?: string;
+  // 未覆盖目录时各应用配置目录的解析方式（主要用于 Windows，默认 auto：检测 CLI 实际使用的目录）
+  pathStrategies?: Partial<Record<"claude" | "codex" | "gemini", PathStrategy>>;
+  // 切换 Claude / Codex 供应商时同步写入的 WSL 发行版（仅 Windows）
+  wslDistros?: string[];
+  // SSH 远程主机（仅本机，不随设置导出）
+  remoteHosts?: RemoteHost[];
   // 首选语言（可选，默认中文）
   language?: "en" | "zh";
   // Claude 自定义端点列表
   customEndpointsClaude?: Record<string, CustomEndpoint>;
   // Codex 自定义端点列表
   customEndpointsCodex?: Record<string, CustomEndpoint>;
+  // 托盘菜单与供应商列表的排序方式
+  traySort?: "manual" | "name" | "latency" | "last-used";
+  // 是否启用自动切换规则（规则本身通过 switchRulesApi 管理）
+  switchRulesEnabled?: boolean;
+  // 日志级别（写入 ~/.cc-switch/logs）
+  logLevel?: "error" | "warn" | "info" | "debug" | "trace";
+  // 是否同意上传匿名崩溃报告（默认仅记录到本地）
+  crashReportUpload?: boolean;
+  // 崩溃报告上传地址
+  crashReportEndpoint?: string;
+  // 停用 Codex MCP 服务器时注释保留其配置（重新启用时恢复），而非删除
+  codexMcpCommentDisabled?: boolean;
+  // 已安装的 cc-switch 状态栏样式（通过 settingsApi.installStatusline 管理）
+  claudeStatusline?: StatuslineVariant;
+  // cc-switch 管理的 Claude Code 钩子（通过 settingsApi.addClaudeHook 管理）
+  claudeHooks?: ClaudeHook[];
+  // 切换供应商时同步写入 ~/.cc-switch/env/<app>.env（供终端 source）
+  writeEnvFiles?: boolean;
+  // 切换 Gemini 供应商时整体替换 ~/.gemini/.env（默认合并，保留用户自行添加的变量）
+  geminiEnvReplace?: boolean;
+  // 切换 Claude 供应商时 settings.json 的写入方式：整体替换 / 仅改写 env 与托管键
+  claudeSettingsMerge?: "replace" | "managed-keys";
+  // managed-keys 模式下除 env 外由供应商接管的顶层键
+  claudeManagedKeys?: string[];
+  // 写入配置后自动将受管理文件收紧为 600、目录 700（仅 Unix）
+  strictPermissions?: boolean;
+  // 切换后重新读取 live 配置核对写入结果，不一致时回滚
+  verifyLiveWrites?: boolean;
+  // 开启写入校验时额外执行的 CLI 校验命令（按应用，如 codex: "codex config validate"）
+  liveVerifyCommands?: Partial<Record<"claude" | "codex" | "gemini", string>>;
+  // 切换供应商时的 MCP 同步范围（按应用，默认 all：同步到所有应用；app：仅当前应用；off：不同步）
+  mcpSwitchSync?: Partial<
+    Record<"claude" | "codex" | "gemini", "all" | "app" | "off">
+  >;
+  // 切换前延迟预检阈值（毫秒），0 表示仅在测速失败时提示
+  switchLatencyWarningMs?: number;
+  // 用量展示货币（如 USD、CNY），未设置时保留原始单位
+  displayCurrency?: string;
+  // 自定义汇率：1 USD 可兑换的目标货币数量
+  exchangeRates?: Record<string, number>;
+  // 私有粘贴服务地址（分享供应商配置时上传密文，未设置时生成离线分享码）
+  shareEndpoint?: string;
+  // 离线模式：不拉取注册表、技能仓库与 MCP 包版本，只使用内置与本地数据
+  offlineMode?: boolean;
+  // 注册表（供应商预设、MCP 目录、用量脚本模板）拉取地址，未设置时使用官方仓库
+  registryUrl?: string;
+  // 守护模式（--daemon）HTTP 控制接口端口，仅监听 127.0.0.1；需同时设置令牌
+  daemonApiPort?: number;
+  // 守护模式 HTTP 控制接口令牌（Authorization: Bearer <令牌>）
+  daemonApiToken?: string;
+  // 启动时运行本地反向代理
+  proxyEnabled?: boolean;
+  // 本地代理端口（默认 15721）
+  proxyPort?: number;
+  // 代理请求遇到连接失败、5xx 或 429 时依次尝试其他供应商
+  proxyFailover?: boolean;
+  // 供应商排名的评分权重（推荐与按排名自动切换使用），未设置时使用默认权重
+  rankingWeights?: RankingWeights;
+  // 每天对所有供应商执行一次综合验证（会发送 1 token 输出的真实请求）
+  providerVerification?: boolean;
   // 安全设置（兼容未来扩展）
   security?: {
     auth?: {
@@ -120,6 +621,8 @@ export interface McpServerSpec {
   args?: string[];
   env?: Record<string, string>;
   cwd?: string;
+  // 按平台调整启动命令：Windows 上 npx 等改为 cmd /c 启动，其他系统去掉 cmd /c
+  platformLauncher?: boolean;
   // http 和 sse 字段
   url?: string;
   headers?: Record<string, string>;
@@ -144,15 +647,66 @@ export interface McpServer {
   tags?: string[];
   homepage?: string;
   docs?: string;
+  health?: McpHealth; // 最近一次健康检查结果
+  // 分应用覆盖：同步到对应应用时与 server 合并（对象逐键合并，null 表示删除该字段）
+  overrides?: Partial<
+    Record<"claude" | "codex" | "gemini", Partial<McpServerSpec>>
+  >;
   // 兼容旧字段（v3.6.x 及以前）
   enabled?: boolean; // 已废弃，v3.7.0 使用 apps 字段
   source?: string;
   [key: string]: any;
 }
 
+// MCP 服务器健康检查结果
+export interface McpHealth {
+  status: "healthy" | "unhealthy";
+  issues?: {
+    kind: "missingCommand" | "yankedPackage" | "urlNotFound";
+    detail: string;
+  }[];
+  checkedAt: number;
+}
+
 // MCP 服务器映射（id -> McpServer）
 export type McpServersMap = Record<string, McpServer>;
 
+// MCP 同步预览（dry-run，不写入任何文件）
+export interface McpSyncPreview {
+  app: string;
+  added: string[];
+  removed: string[];
+  modified: string[];
+  unchanged: string[];
+  // Claude/Gemini 为 mcpServers JSON，Codex 为 [mcp_servers] TOML
+  snippet: string;
+}
+
+// MCP 服务器调用统计（来自 Claude 会话记录）
+export interface McpUsageStat {
+  serverId: string;
+  name?: string;
+  // 是否为 cc-switch 管理的服务器
+  configured: boolean;
+  enabledForClaude: boolean;
+  calls: number;
+  lastUsedAt?: number;
+  tools: { tool: string; calls: number }[];
+}
+
+// 从项目 .mcp.json 导入 MCP 服务器的结果
+export interface ProjectMcpImport {
+  file: string;
+  // 添加到导入服务器上的标签（project:<目录名>）
+  tag: string;
+  imported: string[];
+  // 已存在且定义相同，仅追加了项目标签
+  tagged: string[];
+  // 已存在但定义不同，保留本地定义
+  conflicts: string[];
+  invalid: string[];
+}
+
 // MCP 配置状态
 export interface McpStatus {
   userConfigPath: string;
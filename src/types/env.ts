@@ -27,3 +27,13 @@ export interface BackupInfo {
   /** 被备份的环境变量冲突列表 */
   conflicts: EnvConflict[];
 }
+
+/**
+ * CLI 安装检测结果
+ */
+export interface InstalledCli {
+  name: "claude" | "codex" | "gemini";
+  installed: boolean;
+  path?: string;
+  version?: string;
+}
@@ -1,3 +1,25 @@
+/**
+ * 后端命令返回的结构化错误
+ * code 为稳定的错误码（见 error_codes 命令；本地化错误使用其 i18n key，如 provider.not_found）
+ */
+export interface CommandError {
+  code: string;
+  message: string;
+  details?: Record<string, unknown>;
+}
+
+export const isCommandError = (error: unknown): error is CommandError =>
+  !!error &&
+  typeof error === "object" &&
+  typeof (error as CommandError).code === "string" &&
+  typeof (error as CommandError).message === "string";
+
+/**
+ * 获取后端命令错误的错误码（非命令错误时返回 undefined）
+ */
+export const getErrorCode = (error: unknown): string | undefined =>
+  isCommandError(error) ? error.code : undefined;
+
 /**
  * 从各种错误对象中提取错误信息
  * @param error 错误对象
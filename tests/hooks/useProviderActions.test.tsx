@@ -143,7 +143,7 @@ describe("useProviderActions", () => {
   });
 
   it("should not request plugin sync when switching non-Claude provider", async () => {
-    switchProviderMutateAsync.mockResolvedValueOnce(undefined);
+    switchProviderMutateAsync.mockResolvedValueOnce({ switched: true });
     const { wrapper } = createWrapper();
     const provider = createProvider({ category: "custom" });
 
@@ -155,13 +155,15 @@ describe("useProviderActions", () => {
       await result.current.switchProvider(provider);
     });
 
-    expect(switchProviderMutateAsync).toHaveBeenCalledWith(provider.id);
+    expect(switchProviderMutateAsync).toHaveBeenCalledWith({
+      providerId: provider.id,
+    });
     expect(settingsApiGetMock).not.toHaveBeenCalled();
     expect(settingsApiApplyMock).not.toHaveBeenCalled();
   });
 
   it("should sync plugin config when switching Claude provider with integration enabled", async () => {
-    switchProviderMutateAsync.mockResolvedValueOnce(undefined);
+    switchProviderMutateAsync.mockResolvedValueOnce({ switched: true });
     settingsApiGetMock.mockResolvedValueOnce({
       enableClaudePluginIntegration: true,
     });
@@ -177,13 +179,15 @@ describe("useProviderActions", () => {
       await result.current.switchProvider(provider);
     });
 
-    expect(switchProviderMutateAsync).toHaveBeenCalledWith(provider.id);
+    expect(switchProviderMutateAsync).toHaveBeenCalledWith({
+      providerId: provider.id,
+    });
     expect(settingsApiGetMock).toHaveBeenCalledTimes(1);
     expect(settingsApiApplyMock).toHaveBeenCalledWith({ official: true });
   });
 
   it("should not call applyClaudePluginConfig when integration is disabled", async () => {
-    switchProviderMutateAsync.mockResolvedValueOnce(undefined);
+    switchProviderMutateAsync.mockResolvedValueOnce({ switched: true });
     settingsApiGetMock.mockResolvedValueOnce({
       enableClaudePluginIntegration: false,
     });
@@ -203,7 +207,7 @@ describe("useProviderActions", () => {
   });
 
   it("should show error toast when plugin sync fails with error message", async () => {
-    switchProviderMutateAsync.mockResolvedValueOnce(undefined);
+    switchProviderMutateAsync.mockResolvedValueOnce({ switched: true });
     settingsApiGetMock.mockResolvedValueOnce({
       enableClaudePluginIntegration: true,
     });
@@ -240,7 +244,7 @@ describe("useProviderActions", () => {
   });
 
   it("should use default error message when plugin sync fails without error message", async () => {
-    switchProviderMutateAsync.mockResolvedValueOnce(undefined);
+    switchProviderMutateAsync.mockResolvedValueOnce({ switched: true });
     settingsApiGetMock.mockResolvedValueOnce({
       enableClaudePluginIntegration: true,
     });
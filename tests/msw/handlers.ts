@@ -64,7 +64,7 @@ export const handlers = [
       return HttpResponse.json(false, { status: 404 });
     }
     setCurrentProviderId(app, id);
-    return success(true);
+    return success({ switched: true });
   }),
 
   http.post(`${TAURI_ENDPOINT}/add_provider`, async ({ request }) => {